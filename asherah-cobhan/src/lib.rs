@@ -6,17 +6,19 @@
 #![allow(unsafe_code)]
 #![allow(dead_code)] // Some error codes are defined for API completeness
 
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{BTreeMap, HashMap};
 use std::os::raw::c_char;
 use std::ptr;
-use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
-use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicI32, AtomicI64, Ordering};
+use std::sync::{Arc, RwLock};
 
+use asherah::error::ErrorCode;
 use asherah::session::PublicFactory;
 use asherah::types::{DataRowRecord, EnvelopeKeyRecord, KeyMeta};
 use asherah::{aead::AES256GCM, builders::DynKms, builders::DynMetastore};
 use asherah_config::ConfigOptions;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 // ============================================================================
 // Stderr Log Sink (matches Go asherah-cobhan logging behavior)
@@ -179,6 +181,51 @@ const ERR_BAD_CONFIG: i32 = -105;
 const ERR_PANIC: i32 = -106;
 /// Unsupported temp-file buffer (negative length in cobhan protocol)
 const ERR_UNSUPPORTED_TEMP_FILE: i32 = -107;
+/// Factory handle is not open (closed, or never returned by `FactoryCreate`)
+const ERR_INVALID_FACTORY_HANDLE: i32 = -108;
+
+// ============================================================================
+// Stable Error Codes
+// ============================================================================
+//
+// The ERR_* values above are fixed by the Go library and only say which step
+// failed. `GetLastErrorCode` adds the reason, as an `asherah::error::ErrorCode`
+// shared with asherah-ffi and the language bindings.
+
+thread_local! {
+    /// What `GetLastErrorCode` reports for this thread's last call.
+    static LAST_ERROR_CODE: Cell<i32> = const { Cell::new(0) };
+    /// The code of the error behind a failure, recorded while the error is
+    /// still at hand and consumed by `finish`.
+    static FAILURE_CODE: Cell<Option<ErrorCode>> = const { Cell::new(None) };
+}
+
+fn record_failure(err: &anyhow::Error) {
+    FAILURE_CODE.set(Some(ErrorCode::of(err)));
+}
+
+/// Record the code `GetLastErrorCode` reports for `result`, then return it.
+/// A code recorded with [`record_failure`] wins over the generic mapping.
+fn finish(result: i32) -> i32 {
+    let recorded = FAILURE_CODE.take();
+    let code = match result {
+        ERR_NONE => None,
+        ERR_NULL_PTR
+        | ERR_BUFFER_TOO_LARGE
+        | ERR_BUFFER_TOO_SMALL
+        | ERR_COPY_FAILED
+        | ERR_JSON_DECODE_FAILED
+        | ERR_UNSUPPORTED_TEMP_FILE => Some(ErrorCode::InvalidArgument),
+        ERR_NOT_INITIALIZED => Some(ErrorCode::NotInitialized),
+        ERR_ALREADY_INITIALIZED => Some(ErrorCode::AlreadyInitialized),
+        ERR_BAD_CONFIG => Some(ErrorCode::InvalidConfig),
+        ERR_INVALID_FACTORY_HANDLE => Some(ErrorCode::Closed),
+        ERR_JSON_ENCODE_FAILED | ERR_PANIC => Some(ErrorCode::Internal),
+        _ => Some(ErrorCode::Unknown),
+    };
+    LAST_ERROR_CODE.set(code.map_or(0, |code| recorded.unwrap_or(code).as_i32()));
+    result
+}
 
 /// Estimated encryption overhead (matches Go EstimatedEncryptionOverhead)
 const ESTIMATED_ENCRYPTION_OVERHEAD: i32 = 48;
@@ -190,28 +237,70 @@ const ESTIMATED_ENVELOPE_OVERHEAD: i32 = 185;
 // ============================================================================
 
 /// Global factory instance (RwLock allows proper shutdown/re-initialization)
-static FACTORY: RwLock<Option<Factory>> = RwLock::new(None);
+static FACTORY: RwLock<Option<Arc<Factory>>> = RwLock::new(None);
 
 /// Estimated intermediate key overhead, set during SetupJson
 /// (len(ProductID) + len(ServiceName), matching Go behavior)
 static ESTIMATED_INTERMEDIATE_KEY_OVERHEAD: AtomicI32 = AtomicI32::new(0);
 
-/// Acquire a read guard on FACTORY, refusing the operation if the lock is
-/// poisoned. Encrypt/Decrypt paths must reject poisoned state because
-/// continuing past a previous panic could re-enter half-initialized FFI
-/// state. Setup paths may legitimately recover via `FACTORY.write()` +
+/// The handle the `*WithFactory` functions take for the global factory of
+/// `SetupJson`/`Shutdown`.
+const DEFAULT_FACTORY_HANDLE: i64 = 0;
+
+/// A factory created by `FactoryCreate`.
+struct HandleFactory {
+    factory: Arc<Factory>,
+    /// `EstimateBufferWithFactory`'s counterpart of
+    /// `ESTIMATED_INTERMEDIATE_KEY_OVERHEAD`.
+    intermediate_key_overhead: i32,
+}
+
+/// Factories created by `FactoryCreate`, by handle.
+static FACTORIES: RwLock<BTreeMap<i64, HandleFactory>> = RwLock::new(BTreeMap::new());
+
+static NEXT_FACTORY_HANDLE: AtomicI64 = AtomicI64::new(DEFAULT_FACTORY_HANDLE + 1);
+
+/// Whether the stderr sink logs below error level.
+static VERBOSE_LOGGING: AtomicBool = AtomicBool::new(false);
+
+fn set_stderr_sink(verbose: bool) {
+    VERBOSE_LOGGING.store(verbose, Ordering::Relaxed);
+    asherah::logging::set_sink("stderr", Some(Arc::new(StderrLogSink { verbose })));
+}
+
+/// Look up the factory behind `handle` for one call, refusing the operation
+/// if a lock is poisoned. Encrypt/Decrypt paths must reject poisoned state
+/// because continuing past a previous panic could re-enter half-initialized
+/// FFI state. Setup paths may legitimately recover via `FACTORY.write()` +
 /// `into_inner()` because they overwrite state, so they don't go through
 /// this helper. T-finding "Poisoned-lock recovery silently uses corrupted
 /// state via into_inner()" in docs/review-2026-05-05-findings.md.
-fn factory_read_or_panic_err() -> Result<std::sync::RwLockReadGuard<'static, Option<Factory>>, i32>
-{
-    match FACTORY.read() {
-        Ok(g) => Ok(g),
-        Err(_) => {
+///
+/// The call holds its own reference, so a concurrent `Shutdown` or
+/// `FactoryClose` releases the factory once the call is done with it.
+fn factory_for(handle: i64) -> Result<Arc<Factory>, i32> {
+    if handle == DEFAULT_FACTORY_HANDLE {
+        let guard = FACTORY.read().map_err(|_| {
             log::error!("FACTORY lock is poisoned; refusing operation");
-            Err(ERR_PANIC)
-        }
-    }
+            ERR_PANIC
+        })?;
+        return guard.clone().ok_or(ERR_NOT_INITIALIZED);
+    }
+    let factories = FACTORIES.read().map_err(|_| {
+        log::error!("FACTORIES lock is poisoned; refusing operation");
+        ERR_PANIC
+    })?;
+    factories
+        .get(&handle)
+        .map(|entry| Arc::clone(&entry.factory))
+        .ok_or(ERR_INVALID_FACTORY_HANDLE)
+}
+
+/// len(ProductID) + len(ServiceName), matching Go behavior.
+fn intermediate_key_overhead(config: &ConfigOptions) -> i32 {
+    let product_id_len = config.product_id.as_ref().map_or(0, |s| s.len());
+    let service_name_len = config.service_name.as_ref().map_or(0, |s| s.len());
+    (product_id_len + service_name_len) as i32
 }
 
 // ============================================================================
@@ -428,7 +517,7 @@ pub extern "C" fn Shutdown() {
 /// - `ERR_JSON_DECODE_FAILED` if JSON parsing fails
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn SetEnv(env_json: *const c_char) -> i32 {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         if env_json.is_null() {
             return ERR_NULL_PTR;
         }
@@ -448,7 +537,11 @@ pub unsafe extern "C" fn SetEnv(env_json: *const c_char) -> i32 {
             Ok(g) => g.is_some(),
             Err(p) => p.into_inner().is_some(),
         };
-        if factory_initialized {
+        let handles_open = match FACTORIES.read() {
+            Ok(g) => !g.is_empty(),
+            Err(p) => !p.into_inner().is_empty(),
+        };
+        if factory_initialized || handles_open {
             log::error!(
                 "SetEnv refused: Asherah is already initialized; environment \
                  mutation now would race with running threads"
@@ -470,7 +563,8 @@ pub unsafe extern "C" fn SetEnv(env_json: *const c_char) -> i32 {
             log::error!("internal panic in SetEnv");
             ERR_PANIC
         }
-    }
+    };
+    finish(result)
 }
 
 /// Initializes Asherah with the provided JSON configuration.
@@ -488,17 +582,14 @@ pub unsafe extern "C" fn SetEnv(env_json: *const c_char) -> i32 {
 /// - `ERR_NULL_PTR` if buffer is null
 #[unsafe(no_mangle)]
 pub unsafe extern "C" fn SetupJson(config_json: *const c_char) -> i32 {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         if config_json.is_null() {
             return ERR_NULL_PTR;
         }
 
         // Install error-only stderr sink immediately so setup errors are visible
         let _ = asherah::logging::ensure_logger();
-        asherah::logging::set_sink(
-            "stderr",
-            Some(std::sync::Arc::new(StderrLogSink { verbose: false })),
-        );
+        set_stderr_sink(false);
 
         let mut guard = match FACTORY.write() {
             Ok(g) => g,
@@ -523,29 +614,23 @@ pub unsafe extern "C" fn SetupJson(config_json: *const c_char) -> i32 {
         };
 
         // Track intermediate key overhead (matching Go behavior)
-        let product_id_len = config.product_id.as_ref().map_or(0, |s| s.len());
-        let service_name_len = config.service_name.as_ref().map_or(0, |s| s.len());
-        ESTIMATED_INTERMEDIATE_KEY_OVERHEAD.store(
-            (product_id_len + service_name_len) as i32,
-            Ordering::Relaxed,
-        );
+        ESTIMATED_INTERMEDIATE_KEY_OVERHEAD
+            .store(intermediate_key_overhead(&config), Ordering::Relaxed);
 
         // Apply configuration and create factory
         match asherah_config::factory_from_config(&config) {
             Ok((factory, applied)) => {
                 // Upgrade to verbose sink if Verbose=true (debug+error to stderr)
                 if applied.verbose {
-                    asherah::logging::set_sink(
-                        "stderr",
-                        Some(std::sync::Arc::new(StderrLogSink { verbose: true })),
-                    );
+                    set_stderr_sink(true);
                 }
                 set_canaries_enabled(applied.enable_canaries);
-                *guard = Some(factory);
+                *guard = Some(Arc::new(factory));
                 ERR_NONE
             }
             Err(e) => {
                 log::error!("SetupJson failed: {:#}", e);
+                record_failure(&e);
                 ERR_BAD_CONFIG
             }
         }
@@ -555,7 +640,255 @@ pub unsafe extern "C" fn SetupJson(config_json: *const c_char) -> i32 {
             log::error!("internal panic in SetupJson");
             ERR_PANIC
         }
+    };
+    finish(result)
+}
+
+/// Creates a factory alongside the `SetupJson` one, for a process that talks
+/// to several service/product/metastore configurations. Use the handle with
+/// the `*WithFactory` functions and release it with `FactoryClose`.
+///
+/// # Safety
+/// `config_json` must point to a valid Cobhan buffer with a properly initialized header.
+/// `output_handle_ptr` must point to a scalar buffer of at least 8 bytes.
+///
+/// # Parameters
+/// - `config_json`: Cobhan buffer containing JSON configuration matching ConfigOptions
+/// - `output_handle_ptr`: Output buffer for the factory handle (raw i64, no header)
+///
+/// # Returns
+/// - `ERR_NONE` on success
+/// - `ERR_BAD_CONFIG` if configuration is invalid
+/// - `ERR_NULL_PTR` if a buffer is null
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn FactoryCreate(
+    config_json: *const c_char,
+    output_handle_ptr: *mut c_char,
+) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if config_json.is_null() || output_handle_ptr.is_null() {
+            return ERR_NULL_PTR;
+        }
+
+        let _ = asherah::logging::ensure_logger();
+        set_stderr_sink(VERBOSE_LOGGING.load(Ordering::Relaxed));
+
+        let config: ConfigOptions = match cobhan_buffer_to_json(config_json) {
+            Ok(c) => c,
+            Err(code) => {
+                log::error!(
+                    "FactoryCreate: failed to parse config JSON (error code {})",
+                    code
+                );
+                return ERR_BAD_CONFIG;
+            }
+        };
+
+        let (factory, applied) = match asherah_config::factory_from_config(&config) {
+            Ok(created) => created,
+            Err(e) => {
+                log::error!("FactoryCreate failed: {:#}", e);
+                record_failure(&e);
+                return ERR_BAD_CONFIG;
+            }
+        };
+        // Logging and canaries are process-wide: a factory can turn them
+        // on, but not off for the others.
+        if applied.verbose {
+            set_stderr_sink(true);
+        }
+        if applied.enable_canaries {
+            set_canaries_enabled(true);
+        }
+
+        let mut factories = match FACTORIES.write() {
+            Ok(g) => g,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        let handle = NEXT_FACTORY_HANDLE.fetch_add(1, Ordering::Relaxed);
+        let result = cobhan_int64_to_buffer(handle, output_handle_ptr);
+        if result == ERR_NONE {
+            factories.insert(
+                handle,
+                HandleFactory {
+                    factory: Arc::new(factory),
+                    intermediate_key_overhead: intermediate_key_overhead(&config),
+                },
+            );
+        }
+        result
+    })) {
+        Ok(result) => result,
+        Err(_) => {
+            log::error!("internal panic in FactoryCreate");
+            ERR_PANIC
+        }
+    };
+    finish(result)
+}
+
+/// Closes a factory from `FactoryCreate`. Calls already under way finish
+/// first; later ones with the handle fail with `ERR_INVALID_FACTORY_HANDLE`.
+/// `DEFAULT_FACTORY_HANDLE` (0) shuts down the `SetupJson` factory, as
+/// `Shutdown` does.
+///
+/// # Returns
+/// - `ERR_NONE` on success
+/// - `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open
+#[unsafe(no_mangle)]
+pub extern "C" fn FactoryClose(handle: i64) -> i32 {
+    if handle == DEFAULT_FACTORY_HANDLE {
+        Shutdown();
+        return finish(ERR_NONE);
     }
+    let result = match std::panic::catch_unwind(|| {
+        let removed = match FACTORIES.write() {
+            Ok(mut g) => g.remove(&handle),
+            Err(poisoned) => poisoned.into_inner().remove(&handle),
+        };
+        match removed {
+            Some(_) => ERR_NONE,
+            None => ERR_INVALID_FACTORY_HANDLE,
+        }
+    }) {
+        Ok(result) => result,
+        Err(_) => {
+            log::error!("internal panic in FactoryClose");
+            ERR_PANIC
+        }
+    };
+    finish(result)
+}
+
+/// The JSON `FactoryCacheStats` writes, from `asherah::session::CacheStats`.
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct CacheStatsJson {
+    sessions: usize,
+    session_cache_hits: u64,
+    session_cache_misses: u64,
+    intermediate_keys: usize,
+    system_keys: usize,
+}
+
+/// Writes the cache sizes and session cache hit counts of the factory
+/// behind `handle` as JSON, e.g. `{"Sessions":2,"SessionCacheHits":10,
+/// "SessionCacheMisses":2,"IntermediateKeys":2,"SystemKeys":1}`. The
+/// session counts are 0 if session caching is disabled.
+///
+/// # Safety
+/// `output_json_ptr` must point to a valid Cobhan buffer with a properly
+/// initialized header; 256 bytes is enough.
+///
+/// # Returns
+/// - `ERR_NONE` on success
+/// - `ERR_NOT_INITIALIZED` or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open
+/// - `ERR_BUFFER_TOO_SMALL` if the JSON does not fit
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn FactoryCacheStats(handle: i64, output_json_ptr: *mut c_char) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if output_json_ptr.is_null() {
+            return ERR_NULL_PTR;
+        }
+        let factory = match factory_for(handle) {
+            Ok(f) => f,
+            Err(code) => return code,
+        };
+        let stats = factory.cache_stats();
+        cobhan_json_to_buffer(
+            &CacheStatsJson {
+                sessions: stats.sessions,
+                session_cache_hits: stats.session_cache_hits,
+                session_cache_misses: stats.session_cache_misses,
+                intermediate_keys: stats.intermediate_keys,
+                system_keys: stats.system_keys,
+            },
+            output_json_ptr,
+        )
+    })) {
+        Ok(result) => result,
+        Err(_) => {
+            log::error!("internal panic in FactoryCacheStats");
+            ERR_PANIC
+        }
+    };
+    finish(result)
+}
+
+/// Drops the cached session of one partition, so the next call for it
+/// builds a fresh session. Succeeds whether or not a session was cached.
+///
+/// # Safety
+/// `partition_id_ptr` must point to a valid Cobhan buffer with a properly
+/// initialized header.
+///
+/// # Returns
+/// - `ERR_NONE` on success
+/// - `ERR_NOT_INITIALIZED` or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn FactoryEvictSession(handle: i64, partition_id_ptr: *const c_char) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if partition_id_ptr.is_null() {
+            return ERR_NULL_PTR;
+        }
+        let factory = match factory_for(handle) {
+            Ok(f) => f,
+            Err(code) => return code,
+        };
+        match cobhan_buffer_borrow_str(partition_id_ptr) {
+            Ok(partition_id) => {
+                factory.evict_session(partition_id);
+                ERR_NONE
+            }
+            Err(e) => e,
+        }
+    })) {
+        Ok(result) => result,
+        Err(_) => {
+            log::error!("internal panic in FactoryEvictSession");
+            ERR_PANIC
+        }
+    };
+    finish(result)
+}
+
+/// Empties the session cache and the system and intermediate key caches of
+/// the factory behind `handle`, so every key is loaded from the metastore
+/// again: after a key revocation, or to release memory.
+///
+/// # Returns
+/// - `ERR_NONE` on success
+/// - `ERR_NOT_INITIALIZED` or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open
+#[unsafe(no_mangle)]
+pub extern "C" fn FactoryFlushCaches(handle: i64) -> i32 {
+    let result = match std::panic::catch_unwind(|| match factory_for(handle) {
+        Ok(factory) => {
+            factory.flush_caches();
+            ERR_NONE
+        }
+        Err(code) => code,
+    }) {
+        Ok(result) => result,
+        Err(_) => {
+            log::error!("internal panic in FactoryFlushCaches");
+            ERR_PANIC
+        }
+    };
+    finish(result)
+}
+
+/// Returns the stable Asherah error code (`asherah::error::ErrorCode`) for
+/// the last `SetEnv`, `SetupJson`, `FactoryCreate`, `FactoryClose`,
+/// `FactoryCacheStats`, `FactoryEvictSession`, `FactoryFlushCaches`,
+/// `Encrypt`, `Decrypt`, `EncryptToJson` or `DecryptFromJson` call (or
+/// `*WithFactory` variant) on this thread, or 0 if it succeeded.
+///
+/// The `ERR_*` return value says which step failed; this says why, e.g.
+/// 500 (metastore unavailable, worth retrying) versus 700 (decryption
+/// failed). See the README for the full table.
+#[unsafe(no_mangle)]
+pub extern "C" fn GetLastErrorCode() -> i32 {
+    LAST_ERROR_CODE.get()
 }
 
 /// Estimates the buffer size needed for encryption output.
@@ -579,9 +912,37 @@ pub extern "C" fn EstimateBuffer(data_len: i32, partition_len: i32) -> i32 {
     //   estimatedDataLen := ((int(dataLen) + EstimatedEncryptionOverhead + 2) / 3) * 4
     //   result := int32(BUFFER_HEADER_SIZE + EstimatedEnvelopeOverhead +
     //             EstimatedIntermediateKeyOverhead + int(partitionLen) + estimatedDataLen)
+    estimate_buffer(
+        ESTIMATED_INTERMEDIATE_KEY_OVERHEAD.load(Ordering::Relaxed),
+        data_len,
+        partition_len,
+    )
+}
+
+/// [`EstimateBuffer`] for the factory behind `handle`, whose service and
+/// product names may differ from the `SetupJson` factory's.
+///
+/// # Returns
+/// - Estimated buffer size in bytes. An unknown handle is estimated
+///   without the intermediate key overhead; the encrypt call reports it.
+#[unsafe(no_mangle)]
+pub extern "C" fn EstimateBufferWithFactory(handle: i64, data_len: i32, partition_len: i32) -> i32 {
+    let intermediate_key_overhead = if handle == DEFAULT_FACTORY_HANDLE {
+        ESTIMATED_INTERMEDIATE_KEY_OVERHEAD.load(Ordering::Relaxed)
+    } else {
+        match FACTORIES.read() {
+            Ok(factories) => factories
+                .get(&handle)
+                .map_or(0, |entry| entry.intermediate_key_overhead),
+            Err(_) => 0,
+        }
+    };
+    estimate_buffer(intermediate_key_overhead, data_len, partition_len)
+}
+
+fn estimate_buffer(intermediate_key_overhead: i32, data_len: i32, partition_len: i32) -> i32 {
     let estimated_data_len = ((data_len as i64 + ESTIMATED_ENCRYPTION_OVERHEAD as i64 + 2) / 3) * 4;
-    let intermediate_key_overhead =
-        ESTIMATED_INTERMEDIATE_KEY_OVERHEAD.load(Ordering::Relaxed) as i64;
+    let intermediate_key_overhead = intermediate_key_overhead as i64;
 
     let result = BUFFER_HEADER_SIZE as i64
         + ESTIMATED_ENVELOPE_OVERHEAD as i64
@@ -624,7 +985,64 @@ pub unsafe extern "C" fn Encrypt(
     output_parent_key_id_ptr: *mut c_char,
     output_parent_key_created_ptr: *mut c_char,
 ) -> i32 {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    encrypt(
+        "Encrypt",
+        DEFAULT_FACTORY_HANDLE,
+        partition_id_ptr,
+        data_ptr,
+        output_encrypted_data_ptr,
+        output_encrypted_key_ptr,
+        output_created_ptr,
+        output_parent_key_id_ptr,
+        output_parent_key_created_ptr,
+    )
+}
+
+/// [`Encrypt`] with the factory behind `handle`: one from `FactoryCreate`,
+/// or `DEFAULT_FACTORY_HANDLE` (0) for the `SetupJson` factory.
+///
+/// # Safety
+/// As for [`Encrypt`].
+///
+/// # Returns
+/// As for [`Encrypt`], or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn EncryptWithFactory(
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    data_ptr: *const c_char,
+    output_encrypted_data_ptr: *mut c_char,
+    output_encrypted_key_ptr: *mut c_char,
+    output_created_ptr: *mut c_char,
+    output_parent_key_id_ptr: *mut c_char,
+    output_parent_key_created_ptr: *mut c_char,
+) -> i32 {
+    encrypt(
+        "EncryptWithFactory",
+        handle,
+        partition_id_ptr,
+        data_ptr,
+        output_encrypted_data_ptr,
+        output_encrypted_key_ptr,
+        output_created_ptr,
+        output_parent_key_id_ptr,
+        output_parent_key_created_ptr,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe fn encrypt(
+    op: &str,
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    data_ptr: *const c_char,
+    output_encrypted_data_ptr: *mut c_char,
+    output_encrypted_key_ptr: *mut c_char,
+    output_created_ptr: *mut c_char,
+    output_parent_key_id_ptr: *mut c_char,
+    output_parent_key_created_ptr: *mut c_char,
+) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         // Validate inputs
         if partition_id_ptr.is_null()
             || data_ptr.is_null()
@@ -638,14 +1056,10 @@ pub unsafe extern "C" fn Encrypt(
         }
 
         // Get factory
-        let guard = match factory_read_or_panic_err() {
-            Ok(g) => g,
+        let factory = match factory_for(handle) {
+            Ok(f) => f,
             Err(code) => return code,
         };
-        let factory = match guard.as_ref() {
-            Some(f) => f,
-            None => return ERR_NOT_INITIALIZED,
-        };
 
         // Read inputs
         let partition_id = match cobhan_buffer_borrow_str(partition_id_ptr) {
@@ -663,7 +1077,8 @@ pub unsafe extern "C" fn Encrypt(
         let drr = match session.encrypt(data) {
             Ok(d) => d,
             Err(e) => {
-                log::error!("Encrypt failed: {e:#}");
+                log::error!("{op} failed: {e:#}");
+                record_failure(&e);
                 return ERR_ENCRYPT_FAILED;
             }
         };
@@ -717,10 +1132,11 @@ pub unsafe extern "C" fn Encrypt(
     })) {
         Ok(result) => result,
         Err(_) => {
-            log::error!("internal panic in Encrypt");
+            log::error!("internal panic in {op}");
             ERR_PANIC
         }
-    }
+    };
+    finish(result)
 }
 
 /// Decrypts data from components.
@@ -751,7 +1167,64 @@ pub unsafe extern "C" fn Decrypt(
     parent_key_created: i64,
     output_decrypted_data_ptr: *mut c_char,
 ) -> i32 {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    decrypt(
+        "Decrypt",
+        DEFAULT_FACTORY_HANDLE,
+        partition_id_ptr,
+        encrypted_data_ptr,
+        encrypted_key_ptr,
+        created,
+        parent_key_id_ptr,
+        parent_key_created,
+        output_decrypted_data_ptr,
+    )
+}
+
+/// [`Decrypt`] with the factory behind `handle`: one from `FactoryCreate`,
+/// or `DEFAULT_FACTORY_HANDLE` (0) for the `SetupJson` factory.
+///
+/// # Safety
+/// As for [`Decrypt`].
+///
+/// # Returns
+/// As for [`Decrypt`], or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DecryptWithFactory(
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    encrypted_data_ptr: *const c_char,
+    encrypted_key_ptr: *const c_char,
+    created: i64,
+    parent_key_id_ptr: *const c_char,
+    parent_key_created: i64,
+    output_decrypted_data_ptr: *mut c_char,
+) -> i32 {
+    decrypt(
+        "DecryptWithFactory",
+        handle,
+        partition_id_ptr,
+        encrypted_data_ptr,
+        encrypted_key_ptr,
+        created,
+        parent_key_id_ptr,
+        parent_key_created,
+        output_decrypted_data_ptr,
+    )
+}
+
+#[allow(clippy::too_many_arguments)]
+unsafe fn decrypt(
+    op: &str,
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    encrypted_data_ptr: *const c_char,
+    encrypted_key_ptr: *const c_char,
+    created: i64,
+    parent_key_id_ptr: *const c_char,
+    parent_key_created: i64,
+    output_decrypted_data_ptr: *mut c_char,
+) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         // Validate inputs
         if partition_id_ptr.is_null()
             || encrypted_data_ptr.is_null()
@@ -763,14 +1236,10 @@ pub unsafe extern "C" fn Decrypt(
         }
 
         // Get factory
-        let guard = match factory_read_or_panic_err() {
-            Ok(g) => g,
+        let factory = match factory_for(handle) {
+            Ok(f) => f,
             Err(code) => return code,
         };
-        let factory = match guard.as_ref() {
-            Some(f) => f,
-            None => return ERR_NOT_INITIALIZED,
-        };
 
         // Read inputs - raw bytes, no base64 (matching Go cobhan.BufferToBytes)
         let partition_id = match cobhan_buffer_borrow_str(partition_id_ptr) {
@@ -816,7 +1285,8 @@ pub unsafe extern "C" fn Decrypt(
         let mut plaintext = match session.decrypt(drr) {
             Ok(p) => p,
             Err(e) => {
-                log::error!("Decrypt failed: {e:#}");
+                log::error!("{op} failed: {e:#}");
+                record_failure(&e);
                 return ERR_DECRYPT_FAILED;
             }
         };
@@ -831,10 +1301,11 @@ pub unsafe extern "C" fn Decrypt(
     })) {
         Ok(result) => result,
         Err(_) => {
-            log::error!("internal panic in Decrypt");
+            log::error!("internal panic in {op}");
             ERR_PANIC
         }
-    }
+    };
+    finish(result)
 }
 
 /// Encrypts data and returns the result as a JSON DataRowRecord.
@@ -857,21 +1328,57 @@ pub unsafe extern "C" fn EncryptToJson(
     data_ptr: *const c_char,
     json_ptr: *mut c_char,
 ) -> i32 {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    encrypt_to_json(
+        "EncryptToJson",
+        DEFAULT_FACTORY_HANDLE,
+        partition_id_ptr,
+        data_ptr,
+        json_ptr,
+    )
+}
+
+/// [`EncryptToJson`] with the factory behind `handle`: one from `FactoryCreate`,
+/// or `DEFAULT_FACTORY_HANDLE` (0) for the `SetupJson` factory.
+///
+/// # Safety
+/// As for [`EncryptToJson`].
+///
+/// # Returns
+/// As for [`EncryptToJson`], or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn EncryptToJsonWithFactory(
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    data_ptr: *const c_char,
+    json_ptr: *mut c_char,
+) -> i32 {
+    encrypt_to_json(
+        "EncryptToJsonWithFactory",
+        handle,
+        partition_id_ptr,
+        data_ptr,
+        json_ptr,
+    )
+}
+
+unsafe fn encrypt_to_json(
+    op: &str,
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    data_ptr: *const c_char,
+    json_ptr: *mut c_char,
+) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         // Validate inputs
         if partition_id_ptr.is_null() || data_ptr.is_null() || json_ptr.is_null() {
             return ERR_NULL_PTR;
         }
 
         // Get factory
-        let guard = match factory_read_or_panic_err() {
-            Ok(g) => g,
+        let factory = match factory_for(handle) {
+            Ok(f) => f,
             Err(code) => return code,
         };
-        let factory = match guard.as_ref() {
-            Some(f) => f,
-            None => return ERR_NOT_INITIALIZED,
-        };
 
         // Read inputs
         let partition_id = match cobhan_buffer_borrow_str(partition_id_ptr) {
@@ -889,7 +1396,8 @@ pub unsafe extern "C" fn EncryptToJson(
         let drr = match session.encrypt(data) {
             Ok(d) => d,
             Err(e) => {
-                log::error!("EncryptToJson failed: {e:#}");
+                log::error!("{op} failed: {e:#}");
+                record_failure(&e);
                 return ERR_ENCRYPT_FAILED;
             }
         };
@@ -900,10 +1408,11 @@ pub unsafe extern "C" fn EncryptToJson(
     })) {
         Ok(result) => result,
         Err(_) => {
-            log::error!("internal panic in EncryptToJson");
+            log::error!("internal panic in {op}");
             ERR_PANIC
         }
-    }
+    };
+    finish(result)
 }
 
 /// Decrypts data from a JSON DataRowRecord.
@@ -926,21 +1435,57 @@ pub unsafe extern "C" fn DecryptFromJson(
     json_ptr: *const c_char,
     data_ptr: *mut c_char,
 ) -> i32 {
-    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+    decrypt_from_json(
+        "DecryptFromJson",
+        DEFAULT_FACTORY_HANDLE,
+        partition_id_ptr,
+        json_ptr,
+        data_ptr,
+    )
+}
+
+/// [`DecryptFromJson`] with the factory behind `handle`: one from `FactoryCreate`,
+/// or `DEFAULT_FACTORY_HANDLE` (0) for the `SetupJson` factory.
+///
+/// # Safety
+/// As for [`DecryptFromJson`].
+///
+/// # Returns
+/// As for [`DecryptFromJson`], or `ERR_INVALID_FACTORY_HANDLE` if `handle` is not open.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn DecryptFromJsonWithFactory(
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    json_ptr: *const c_char,
+    data_ptr: *mut c_char,
+) -> i32 {
+    decrypt_from_json(
+        "DecryptFromJsonWithFactory",
+        handle,
+        partition_id_ptr,
+        json_ptr,
+        data_ptr,
+    )
+}
+
+unsafe fn decrypt_from_json(
+    op: &str,
+    handle: i64,
+    partition_id_ptr: *const c_char,
+    json_ptr: *const c_char,
+    data_ptr: *mut c_char,
+) -> i32 {
+    let result = match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         // Validate inputs
         if partition_id_ptr.is_null() || json_ptr.is_null() || data_ptr.is_null() {
             return ERR_NULL_PTR;
         }
 
         // Get factory
-        let guard = match factory_read_or_panic_err() {
-            Ok(g) => g,
+        let factory = match factory_for(handle) {
+            Ok(f) => f,
             Err(code) => return code,
         };
-        let factory = match guard.as_ref() {
-            Some(f) => f,
-            None => return ERR_NOT_INITIALIZED,
-        };
 
         // Read inputs
         let partition_id = match cobhan_buffer_borrow_str(partition_id_ptr) {
@@ -954,7 +1499,10 @@ pub unsafe extern "C" fn DecryptFromJson(
         };
         let drr: DataRowRecord = match serde_json::from_slice(json_bytes) {
             Ok(d) => d,
-            Err(_) => return ERR_JSON_DECODE_FAILED,
+            Err(_) => {
+                FAILURE_CODE.set(Some(ErrorCode::InvalidDataRowRecord));
+                return ERR_JSON_DECODE_FAILED;
+            }
         };
 
         // Get session and decrypt
@@ -962,7 +1510,8 @@ pub unsafe extern "C" fn DecryptFromJson(
         let mut plaintext = match session.decrypt(drr) {
             Ok(p) => p,
             Err(e) => {
-                log::error!("DecryptFromJson failed: {e:#}");
+                log::error!("{op} failed: {e:#}");
+                record_failure(&e);
                 return ERR_DECRYPT_FAILED;
             }
         };
@@ -975,10 +1524,11 @@ pub unsafe extern "C" fn DecryptFromJson(
     })) {
         Ok(result) => result,
         Err(_) => {
-            log::error!("internal panic in DecryptFromJson");
+            log::error!("internal panic in {op}");
             ERR_PANIC
         }
-    }
+    };
+    finish(result)
 }
 
 // ============================================================================
@@ -1007,6 +1557,7 @@ pub mod test_helpers {
     pub const ERR_DECRYPT_FAILED: i32 = -104;
     pub const ERR_BAD_CONFIG: i32 = -105;
     pub const ERR_PANIC: i32 = -106;
+    pub const ERR_INVALID_FACTORY_HANDLE: i32 = -108;
 
     /// Creates a cobhan input buffer from bytes.
     /// Layout: [length:i32le][reserved:i32le=0][data...][canary1?][canary2?][padding?]
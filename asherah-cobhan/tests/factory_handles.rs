@@ -0,0 +1,249 @@
+//! Tests for factories created with `FactoryCreate` and used by handle.
+//!
+//! Only `test_default_handle_is_the_setup_factory` touches the global
+//! `SetupJson` factory; the others use their own handles and may run in
+//! parallel with it.
+
+use std::os::raw::c_char;
+
+use asherah_cobhan::test_helpers::{
+    create_input_buffer, create_output_buffer, create_scalar_buffer, create_string_buffer,
+    get_buffer_data, get_buffer_i64, ERR_ALREADY_INITIALIZED, ERR_DECRYPT_FAILED,
+    ERR_INVALID_FACTORY_HANDLE, ERR_NONE, ERR_NOT_INITIALIZED,
+};
+use asherah_cobhan::{
+    DecryptFromJsonWithFactory, DecryptWithFactory, EncryptToJsonWithFactory, EncryptWithFactory,
+    EstimateBuffer, EstimateBufferWithFactory, FactoryCacheStats, FactoryClose, FactoryCreate,
+    FactoryEvictSession, FactoryFlushCaches, GetLastErrorCode, SetEnv, SetupJson,
+};
+
+fn config(service: &str, product: &str) -> String {
+    format!(
+        r#"{{
+            "ServiceName": "{service}",
+            "ProductID": "{product}",
+            "Metastore": "memory",
+            "KMS": "test-debug-static",
+            "EnableSessionCaching": true
+        }}"#
+    )
+}
+
+fn create(service: &str, product: &str) -> i64 {
+    let config = create_string_buffer(&config(service, product));
+    let mut handle = create_scalar_buffer();
+    let result = unsafe {
+        FactoryCreate(
+            config.as_ptr().cast::<c_char>(),
+            handle.as_mut_ptr().cast::<c_char>(),
+        )
+    };
+    assert_eq!(result, ERR_NONE, "FactoryCreate should succeed");
+    get_buffer_i64(&handle)
+}
+
+fn encrypt_to_json(handle: i64, partition: &str, data: &[u8]) -> Result<Vec<u8>, i32> {
+    let partition_buf = create_string_buffer(partition);
+    let data_buf = create_input_buffer(data);
+    let mut json = create_output_buffer(EstimateBufferWithFactory(
+        handle,
+        data.len() as i32,
+        partition.len() as i32,
+    ));
+    let result = unsafe {
+        EncryptToJsonWithFactory(
+            handle,
+            partition_buf.as_ptr().cast::<c_char>(),
+            data_buf.as_ptr().cast::<c_char>(),
+            json.as_mut_ptr().cast::<c_char>(),
+        )
+    };
+    if result == ERR_NONE {
+        Ok(get_buffer_data(&json).to_vec())
+    } else {
+        Err(result)
+    }
+}
+
+fn decrypt_from_json(handle: i64, partition: &str, json: &[u8]) -> Result<Vec<u8>, i32> {
+    let partition_buf = create_string_buffer(partition);
+    let json_buf = create_input_buffer(json);
+    let mut data = create_output_buffer(json.len() as i32);
+    let result = unsafe {
+        DecryptFromJsonWithFactory(
+            handle,
+            partition_buf.as_ptr().cast::<c_char>(),
+            json_buf.as_ptr().cast::<c_char>(),
+            data.as_mut_ptr().cast::<c_char>(),
+        )
+    };
+    if result == ERR_NONE {
+        Ok(get_buffer_data(&data).to_vec())
+    } else {
+        Err(result)
+    }
+}
+
+#[test]
+fn test_factories_are_independent() {
+    let orders = create("orders", "shop");
+    let billing = create("billing-service", "payments");
+    assert_ne!(orders, billing);
+
+    let json = encrypt_to_json(orders, "user-1", b"order 42").expect("encrypt");
+    assert_eq!(
+        decrypt_from_json(orders, "user-1", &json).expect("decrypt"),
+        b"order 42"
+    );
+
+    // The billing factory has its own metastore, without the orders keys.
+    assert_eq!(
+        decrypt_from_json(billing, "user-1", &json),
+        Err(ERR_DECRYPT_FAILED)
+    );
+
+    // Estimates account for each factory's service and product names.
+    assert_eq!(
+        EstimateBufferWithFactory(billing, 100, 6) - EstimateBufferWithFactory(orders, 100, 6),
+        ("billing-service".len() + "payments".len()) as i32
+            - ("orders".len() + "shop".len()) as i32
+    );
+
+    assert_eq!(FactoryClose(orders), ERR_NONE);
+    assert_eq!(FactoryClose(billing), ERR_NONE);
+}
+
+#[test]
+fn test_component_functions_with_factory() {
+    let handle = create("components", "shop");
+    let partition = create_string_buffer("user-2");
+    let data = create_input_buffer(b"component payload");
+    let mut encrypted_data = create_output_buffer(4096);
+    let mut encrypted_key = create_output_buffer(4096);
+    let mut created = create_scalar_buffer();
+    let mut parent_key_id = create_output_buffer(4096);
+    let mut parent_key_created = create_scalar_buffer();
+    let mut decrypted = create_output_buffer(4096);
+
+    unsafe {
+        let result = EncryptWithFactory(
+            handle,
+            partition.as_ptr().cast::<c_char>(),
+            data.as_ptr().cast::<c_char>(),
+            encrypted_data.as_mut_ptr().cast::<c_char>(),
+            encrypted_key.as_mut_ptr().cast::<c_char>(),
+            created.as_mut_ptr().cast::<c_char>(),
+            parent_key_id.as_mut_ptr().cast::<c_char>(),
+            parent_key_created.as_mut_ptr().cast::<c_char>(),
+        );
+        assert_eq!(result, ERR_NONE);
+
+        let result = DecryptWithFactory(
+            handle,
+            partition.as_ptr().cast::<c_char>(),
+            encrypted_data.as_ptr().cast::<c_char>(),
+            encrypted_key.as_ptr().cast::<c_char>(),
+            get_buffer_i64(&created),
+            parent_key_id.as_ptr().cast::<c_char>(),
+            get_buffer_i64(&parent_key_created),
+            decrypted.as_mut_ptr().cast::<c_char>(),
+        );
+        assert_eq!(result, ERR_NONE);
+    }
+    assert_eq!(get_buffer_data(&decrypted), b"component payload");
+    assert_eq!(FactoryClose(handle), ERR_NONE);
+}
+
+#[test]
+fn test_closed_and_unknown_handles() {
+    let handle = create("closing", "shop");
+
+    // Environment mutation would race with the open factory's threads.
+    let env = create_string_buffer(r#"{"ASHERAH_FACTORY_HANDLES_TEST":"1"}"#);
+    assert_eq!(
+        unsafe { SetEnv(env.as_ptr().cast::<c_char>()) },
+        ERR_ALREADY_INITIALIZED
+    );
+
+    assert_eq!(FactoryClose(handle), ERR_NONE);
+    assert_eq!(
+        encrypt_to_json(handle, "user-3", b"late"),
+        Err(ERR_INVALID_FACTORY_HANDLE)
+    );
+    // CLOSED
+    assert_eq!(GetLastErrorCode(), 302);
+    assert_eq!(FactoryClose(handle), ERR_INVALID_FACTORY_HANDLE);
+    assert_eq!(
+        encrypt_to_json(i64::MAX, "user-3", b"never"),
+        Err(ERR_INVALID_FACTORY_HANDLE)
+    );
+}
+
+#[test]
+fn test_default_handle_is_the_setup_factory() {
+    assert_eq!(
+        encrypt_to_json(0, "user-4", b"before setup"),
+        Err(ERR_NOT_INITIALIZED)
+    );
+
+    let setup = create_string_buffer(&config("default-service", "default-product"));
+    assert_eq!(
+        unsafe { SetupJson(setup.as_ptr().cast::<c_char>()) },
+        ERR_NONE
+    );
+    assert_eq!(EstimateBufferWithFactory(0, 64, 6), EstimateBuffer(64, 6));
+
+    let json = encrypt_to_json(0, "user-4", b"default").expect("encrypt");
+    assert_eq!(
+        decrypt_from_json(0, "user-4", &json).expect("decrypt"),
+        b"default"
+    );
+
+    assert_eq!(FactoryClose(0), ERR_NONE);
+    assert_eq!(
+        decrypt_from_json(0, "user-4", &json),
+        Err(ERR_NOT_INITIALIZED)
+    );
+}
+
+#[test]
+fn test_cache_stats_evict_and_flush() {
+    let handle = create("caching", "shop");
+    let stats = || {
+        let mut json = create_output_buffer(256);
+        let result = unsafe { FactoryCacheStats(handle, json.as_mut_ptr().cast::<c_char>()) };
+        assert_eq!(result, ERR_NONE);
+        serde_json::from_slice::<serde_json::Value>(get_buffer_data(&json)).expect("stats JSON")
+    };
+
+    encrypt_to_json(handle, "user-5", b"one").expect("encrypt");
+    encrypt_to_json(handle, "user-5", b"two").expect("encrypt");
+    encrypt_to_json(handle, "user-6", b"three").expect("encrypt");
+    assert_eq!(
+        stats(),
+        serde_json::json!({
+            "Sessions": 2,
+            "SessionCacheHits": 1,
+            "SessionCacheMisses": 2,
+            "IntermediateKeys": 2,
+            "SystemKeys": 1,
+        })
+    );
+
+    let partition = create_string_buffer("user-6");
+    assert_eq!(
+        unsafe { FactoryEvictSession(handle, partition.as_ptr().cast::<c_char>()) },
+        ERR_NONE
+    );
+    assert_eq!(stats()["Sessions"], 1);
+
+    assert_eq!(FactoryFlushCaches(handle), ERR_NONE);
+    let flushed = stats();
+    assert_eq!(flushed["Sessions"], 0);
+    assert_eq!(flushed["IntermediateKeys"], 0);
+    assert_eq!(flushed["SystemKeys"], 0);
+    assert_eq!(flushed["SessionCacheHits"], 1);
+
+    assert_eq!(FactoryClose(handle), ERR_NONE);
+    assert_eq!(FactoryFlushCaches(handle), ERR_INVALID_FACTORY_HANDLE);
+}
@@ -14,7 +14,8 @@ use asherah_cobhan::test_helpers::{
     ERR_JSON_DECODE_FAILED, ERR_NONE, ERR_NULL_PTR,
 };
 use asherah_cobhan::{
-    Decrypt, DecryptFromJson, Encrypt, EncryptToJson, EstimateBuffer, SetEnv, SetupJson, Shutdown,
+    Decrypt, DecryptFromJson, Encrypt, EncryptToJson, EstimateBuffer, GetLastErrorCode, SetEnv,
+    SetupJson, Shutdown,
 };
 use std::ptr;
 
@@ -590,6 +591,7 @@ fn test_decrypt_with_wrong_partition() {
             json_output.as_mut_ptr().cast::<c_char>(),
         );
         assert_eq!(result, ERR_NONE, "Encryption should succeed");
+        assert_eq!(GetLastErrorCode(), 0);
     }
 
     // Try to decrypt with partition 2 - this should fail
@@ -605,6 +607,8 @@ fn test_decrypt_with_wrong_partition() {
             result, ERR_DECRYPT_FAILED,
             "Decryption with wrong partition should fail"
         );
+        // The stable code says why: 103, PARTITION_MISMATCH.
+        assert_eq!(GetLastErrorCode(), 103);
     }
 }
 
@@ -1323,6 +1327,7 @@ fn test_set_env_rejected_after_setup_impl() {
         result, ERR_ALREADY_INITIALIZED,
         "SetEnv after Setup must return ERR_ALREADY_INITIALIZED, got {result}"
     );
+    assert_eq!(GetLastErrorCode(), 301);
     assert!(
         std::env::var(&key).is_err(),
         "SetEnv must not mutate the environ block after Setup; \
@@ -1454,6 +1459,8 @@ fn test_decrypt_invalid_json_impl() {
             result, ERR_JSON_DECODE_FAILED,
             "DecryptFromJson with invalid JSON should return ERR_JSON_DECODE_FAILED"
         );
+        // INVALID_DATA_ROW_RECORD
+        assert_eq!(GetLastErrorCode(), 102);
     }
 }
 
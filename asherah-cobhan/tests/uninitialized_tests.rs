@@ -11,7 +11,7 @@ use asherah_cobhan::test_helpers::{
     create_input_buffer, create_output_buffer, create_scalar_buffer, create_string_buffer,
     ERR_NOT_INITIALIZED,
 };
-use asherah_cobhan::{Decrypt, DecryptFromJson, Encrypt, EncryptToJson};
+use asherah_cobhan::{Decrypt, DecryptFromJson, Encrypt, EncryptToJson, GetLastErrorCode};
 
 #[test]
 fn test_encrypt_to_json_not_initialized() {
@@ -29,6 +29,8 @@ fn test_encrypt_to_json_not_initialized() {
             result, ERR_NOT_INITIALIZED,
             "EncryptToJson should return ERR_NOT_INITIALIZED when factory is not set up"
         );
+        // NOT_INITIALIZED
+        assert_eq!(GetLastErrorCode(), 300);
     }
 }
 
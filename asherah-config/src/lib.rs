@@ -5,7 +5,7 @@
 //! construction from JSON or environment-based configuration.
 
 use anyhow::{anyhow, Context, Result};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 type Factory = asherah::session::PublicFactory<
@@ -14,7 +14,7 @@ type Factory = asherah::session::PublicFactory<
     asherah::builders::DynMetastore,
 >;
 
-#[derive(Clone, Debug, Deserialize, Default)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default)]
 #[serde(default)]
 pub struct ConfigOptions {
     #[serde(rename = "ServiceName")]
@@ -124,6 +124,21 @@ pub struct ConfigOptions {
     #[serde(rename = "KmsKeyId")]
     pub kms_key_id: Option<String>,
 
+    // --- KMS: master-key rotation window ---
+    /// Previous AWS KMS key ID or ARN while rotating the master key. SKs
+    /// wrapped under it still decrypt; new SKs use `KmsKeyId`.
+    /// Env: PREVIOUS_KMS_KEY_ID
+    #[serde(rename = "PreviousKmsKeyId")]
+    pub previous_kms_key_id: Option<String>,
+    /// Previous multi-region key map while rotating the master key.
+    /// Env: PREVIOUS_REGION_MAP
+    #[serde(rename = "PreviousRegionMap")]
+    pub previous_region_map: Option<HashMap<String, String>>,
+    /// Previous hex-encoded static master key while rotating the master key.
+    /// Env: PREVIOUS_STATIC_MASTER_KEY_HEX
+    #[serde(rename = "PreviousStaticMasterKeyHex")]
+    pub previous_static_master_key_hex: Option<String>,
+
     // --- KMS: AWS Secrets Manager ---
     /// Secrets Manager secret ARN or name containing the master key.
     #[serde(rename = "SecretsManagerSecretId")]
@@ -151,6 +166,10 @@ pub struct ConfigOptions {
     /// AppRole secret ID.
     #[serde(rename = "VaultApproleSecretId")]
     pub vault_approle_secret_id: Option<String>,
+    /// File containing the AppRole secret ID; re-read on every login so a
+    /// rotated secret ID is picked up. Overrides `VaultApproleSecretId`.
+    #[serde(rename = "VaultApproleSecretIdFile")]
+    pub vault_approle_secret_id_file: Option<String>,
     /// Path to TLS client certificate PEM (for cert auth).
     #[serde(rename = "VaultClientCert")]
     pub vault_client_cert: Option<String>,
@@ -166,6 +185,19 @@ pub struct ConfigOptions {
     /// Vault Transit mount path (default: "transit").
     #[serde(rename = "VaultTransitMount")]
     pub vault_transit_mount: Option<String>,
+    /// Pin encryption to this Transit key version (default: latest).
+    #[serde(rename = "VaultTransitKeyVersion")]
+    pub vault_transit_key_version: Option<u32>,
+
+    // --- KMS: quorum ---
+    /// Member KMS kinds for KMS=quorum (e.g. `["aws", "vault"]`), each
+    /// configured through its own fields above. Env: QUORUM_KMS_MEMBERS
+    #[serde(rename = "KmsQuorumMembers")]
+    pub kms_quorum_members: Option<Vec<String>>,
+    /// How many members must cooperate to unwrap a system key (default: all
+    /// of them). Env: QUORUM_KMS_THRESHOLD
+    #[serde(rename = "KmsQuorumThreshold")]
+    pub kms_quorum_threshold: Option<usize>,
 }
 
 #[derive(Clone, Debug)]
@@ -182,16 +214,44 @@ pub struct AppliedConfig {
 }
 
 use asherah::builders::{
-    ConfigDriftGuardOptions, KmsConfig, MetastoreConfig, PolicyConfig, PoolConfig, ResolvedConfig,
-    TEST_DEBUG_STATIC_MASTER_KEY_HEX,
+    ConfigDriftGuardOptions, KmsConfig, MetastoreConfig, PolicyConfig, PoolConfig, QuorumMember,
+    ResolvedConfig, VaultOptions, TEST_DEBUG_STATIC_MASTER_KEY_HEX,
 };
+use asherah::error::{ErrorCode, WithErrorCode};
 
 impl ConfigOptions {
     pub fn from_json(json: &str) -> Result<Self> {
-        let cfg = serde_json::from_str(json).context("invalid config JSON")?;
+        let cfg = serde_json::from_str(json)
+            .code(ErrorCode::InvalidConfig)
+            .context("invalid config JSON")?;
         Ok(cfg)
     }
 
+    /// `self` with every key of the JSON object `overrides` (named as in
+    /// [`from_json`](Self::from_json)) taking precedence. Keys `overrides`
+    /// doesn't mention keep their value here; an explicit `null` clears one.
+    pub fn overlay_json(&self, overrides: &str) -> Result<Self> {
+        let overrides: serde_json::Map<String, serde_json::Value> =
+            serde_json::from_str(overrides).context("invalid config JSON")?;
+        let serde_json::Value::Object(mut merged) =
+            serde_json::to_value(self).context("serialize config")?
+        else {
+            return Err(anyhow!("config did not serialize to a JSON object"));
+        };
+        merged.retain(|_, v| !v.is_null());
+        for (key, value) in overrides {
+            // Store aliased keys under their canonical name, which `self`
+            // serialized to, so the two don't collide as duplicates.
+            let key = match key.as_str() {
+                "ForceRunWithConfigDrift" => "ConfigDriftForceRun".to_string(),
+                "ForceUpdateConfigDriftGuard" => "ConfigDriftForceUpdate".to_string(),
+                _ => key,
+            };
+            merged.insert(key, value);
+        }
+        serde_json::from_value(serde_json::Value::Object(merged)).context("invalid config JSON")
+    }
+
     pub fn config_drift_guard_options(&self) -> ConfigDriftGuardOptions {
         ConfigDriftGuardOptions {
             allow_mismatch: self.config_drift_force_run.unwrap_or(false),
@@ -238,6 +298,9 @@ impl ConfigOptions {
 
         let metastore = match metastore_kind.as_str() {
             "memory" => MetastoreConfig::Memory,
+            "host" => MetastoreConfig::Host {
+                name: "host".to_string(),
+            },
             "sqlite" => {
                 let conn = self.connection_string.as_ref().ok_or_else(|| {
                     anyhow!("ConnectionString is required when Metastore is sqlite")
@@ -290,52 +353,14 @@ impl ConfigOptions {
             .as_deref()
             .ok_or_else(|| anyhow!("KMS is required"))?;
         let kms_kind = normalize_alias(kms_raw);
-        let kms = match kms_kind.as_str() {
-            "static" => KmsConfig::Static {
-                key_hex: self
-                    .static_master_key_hex
-                    .clone()
-                    .filter(|key_hex| !key_hex.is_empty())
-                    .ok_or_else(|| anyhow!("StaticMasterKeyHex is required when KMS=static"))?,
-            },
-            "test-debug-static" => KmsConfig::Static {
-                key_hex: self
-                    .static_master_key_hex
-                    .clone()
-                    .filter(|key_hex| !key_hex.is_empty())
-                    .unwrap_or_else(|| TEST_DEBUG_STATIC_MASTER_KEY_HEX.to_string()),
-            },
-            "aws" => KmsConfig::Aws {
-                region_map: self.region_map.clone(),
-                preferred_region: self.preferred_region.clone(),
-                key_id: self.kms_key_id.clone(),
-                region: self
-                    .dynamo_db_signing_region
-                    .as_deref()
-                    .or(self.dynamo_db_region.as_deref())
-                    .map(String::from),
-            },
-            "secrets-manager" => KmsConfig::SecretsManager {
-                secret_id: self.secrets_manager_secret_id.clone().ok_or_else(|| {
-                    anyhow!("SecretsManagerSecretId required for KMS=secrets-manager")
-                })?,
-                region: None,
-            },
-            "vault" | "vault-transit" => KmsConfig::Vault {
-                addr: self
-                    .vault_addr
-                    .clone()
-                    .ok_or_else(|| anyhow!("VaultAddr required for KMS=vault"))?,
-                transit_key: self
-                    .vault_transit_key
-                    .clone()
-                    .ok_or_else(|| anyhow!("VaultTransitKey required for KMS=vault"))?,
-                transit_mount: self.vault_transit_mount.clone(),
-            },
-            other => {
-                anyhow::bail!("Unknown KMS type '{other}'");
-            }
-        };
+        let kms = self.kms_config(&kms_kind)?;
+
+        let previous_kms = asherah::builders::previous_kms_config(
+            &kms,
+            self.previous_kms_key_id.clone(),
+            self.previous_region_map.clone(),
+            self.previous_static_master_key_hex.clone(),
+        )?;
 
         // Clamp `create_date_precision_s` to `expire_after` when the
         // operator picks a short expiry. The default precision is 60s
@@ -389,6 +414,7 @@ impl ConfigOptions {
             aws_profile_name,
             metastore,
             kms,
+            previous_kms,
             policy,
         };
 
@@ -401,6 +427,95 @@ impl ConfigOptions {
 
         Ok((resolved, applied))
     }
+
+    /// Map one KMS kind (already alias-normalized) to its config.
+    fn kms_config(&self, kind: &str) -> Result<KmsConfig> {
+        Ok(match kind {
+            "static" => KmsConfig::Static {
+                key_hex: self
+                    .static_master_key_hex
+                    .clone()
+                    .filter(|key_hex| !key_hex.is_empty())
+                    .ok_or_else(|| anyhow!("StaticMasterKeyHex is required when KMS=static"))?,
+            },
+            "test-debug-static" => KmsConfig::Static {
+                key_hex: self
+                    .static_master_key_hex
+                    .clone()
+                    .filter(|key_hex| !key_hex.is_empty())
+                    .unwrap_or_else(|| TEST_DEBUG_STATIC_MASTER_KEY_HEX.to_string()),
+            },
+            "aws" => KmsConfig::Aws {
+                region_map: self.region_map.clone(),
+                preferred_region: self.preferred_region.clone(),
+                key_id: self.kms_key_id.clone(),
+                region: self
+                    .dynamo_db_signing_region
+                    .as_deref()
+                    .or(self.dynamo_db_region.as_deref())
+                    .map(String::from),
+            },
+            "secrets-manager" => KmsConfig::SecretsManager {
+                secret_id: self.secrets_manager_secret_id.clone().ok_or_else(|| {
+                    anyhow!("SecretsManagerSecretId required for KMS=secrets-manager")
+                })?,
+                region: None,
+            },
+            "vault" | "vault-transit" => KmsConfig::Vault {
+                addr: self
+                    .vault_addr
+                    .clone()
+                    .ok_or_else(|| anyhow!("VaultAddr required for KMS=vault"))?,
+                transit_key: self
+                    .vault_transit_key
+                    .clone()
+                    .ok_or_else(|| anyhow!("VaultTransitKey required for KMS=vault"))?,
+                transit_mount: self.vault_transit_mount.clone(),
+                options: VaultOptions {
+                    token: self.vault_token.clone(),
+                    auth_method: self.vault_auth_method.clone(),
+                    auth_role: self.vault_auth_role.clone(),
+                    auth_mount: self.vault_auth_mount.clone(),
+                    approle_role_id: self.vault_approle_role_id.clone(),
+                    approle_secret_id: self.vault_approle_secret_id.clone(),
+                    approle_secret_id_file: self.vault_approle_secret_id_file.clone(),
+                    client_cert: self.vault_client_cert.clone(),
+                    client_key: self.vault_client_key.clone(),
+                    k8s_token_path: self.vault_k8s_token_path.clone(),
+                    key_version: self.vault_transit_key_version,
+                },
+            },
+            "quorum" => {
+                let members = self
+                    .kms_quorum_members
+                    .as_deref()
+                    .filter(|m| !m.is_empty())
+                    .ok_or_else(|| anyhow!("KmsQuorumMembers required for KMS=quorum"))?
+                    .iter()
+                    .map(|kind| {
+                        let kind = kind.trim().to_lowercase();
+                        if kind == "quorum" {
+                            anyhow::bail!("quorum KMS members cannot themselves be KMS=quorum");
+                        }
+                        Ok(QuorumMember {
+                            kms: self.kms_config(&kind)?,
+                            name: kind,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                KmsConfig::Quorum {
+                    threshold: self.kms_quorum_threshold.unwrap_or(members.len()),
+                    members,
+                }
+            }
+            "host" => KmsConfig::Host {
+                name: "host".to_string(),
+            },
+            other => {
+                anyhow::bail!("Unknown KMS type '{other}'");
+            }
+        })
+    }
 }
 
 fn normalize_sqlite_path(conn: &str) -> String {
@@ -472,7 +587,7 @@ fn resolve_rdbms_connection(
 
 /// Build a factory from structured config — no env var side effects.
 pub fn factory_from_config(config: &ConfigOptions) -> Result<(Factory, AppliedConfig)> {
-    let (mut resolved, applied) = config.resolve()?;
+    let (mut resolved, applied) = config.resolve().code(ErrorCode::InvalidConfig)?;
     let mut config_drift_guard = config.config_drift_guard_options();
     merge_runtime_env_overrides(&mut resolved, &mut config_drift_guard);
     let factory = asherah::builders::factory_from_resolved_with_config_drift_guard(
@@ -484,7 +599,7 @@ pub fn factory_from_config(config: &ConfigOptions) -> Result<(Factory, AppliedCo
 
 /// Async variant — safe for concurrent use since no env vars are written.
 pub async fn factory_from_config_async(config: &ConfigOptions) -> Result<(Factory, AppliedConfig)> {
-    let (mut resolved, applied) = config.resolve()?;
+    let (mut resolved, applied) = config.resolve().code(ErrorCode::InvalidConfig)?;
     let mut config_drift_guard = config.config_drift_guard_options();
     merge_runtime_env_overrides(&mut resolved, &mut config_drift_guard);
     let factory = asherah::builders::factory_from_resolved_with_config_drift_guard_async(
@@ -495,6 +610,87 @@ pub async fn factory_from_config_async(config: &ConfigOptions) -> Result<(Factor
     Ok((factory, applied))
 }
 
+/// Build a factory whose metastore and/or KMS are implemented by the
+/// application. A supplied backend fills in for `Metastore`/`KMS`, which may
+/// be left unset or set to `"host"`; naming a built-in backend there as well
+/// is an error.
+pub fn factory_from_config_with_backends(
+    config: &ConfigOptions,
+    backends: &asherah::builders::HostBackends,
+) -> Result<(Factory, AppliedConfig)> {
+    let config = with_host_kinds(config, backends)?;
+    let (mut resolved, applied) = config.resolve().code(ErrorCode::InvalidConfig)?;
+    let mut config_drift_guard = config.config_drift_guard_options();
+    merge_runtime_env_overrides(&mut resolved, &mut config_drift_guard);
+    let factory = asherah::builders::factory_from_resolved_with_backends(
+        &resolved,
+        config_drift_guard,
+        backends,
+    )?;
+    Ok((factory, applied))
+}
+
+/// Async variant of [`factory_from_config_with_backends`].
+pub async fn factory_from_config_with_backends_async(
+    config: &ConfigOptions,
+    backends: &asherah::builders::HostBackends,
+) -> Result<(Factory, AppliedConfig)> {
+    let config = with_host_kinds(config, backends)?;
+    let (mut resolved, applied) = config.resolve().code(ErrorCode::InvalidConfig)?;
+    let mut config_drift_guard = config.config_drift_guard_options();
+    merge_runtime_env_overrides(&mut resolved, &mut config_drift_guard);
+    let factory = asherah::builders::factory_from_resolved_with_backends_async(
+        &resolved,
+        config_drift_guard,
+        backends,
+    )
+    .await?;
+    Ok((factory, applied))
+}
+
+/// `config` with `Metastore`/`KMS` set to `"host"` for each supplied backend.
+fn with_host_kinds(
+    config: &ConfigOptions,
+    backends: &asherah::builders::HostBackends,
+) -> Result<ConfigOptions> {
+    fn host_kind(field: &mut Option<String>, key: &str, supplied: bool) -> Result<()> {
+        if !supplied {
+            return Ok(());
+        }
+        match field.as_deref() {
+            None => *field = Some("host".to_string()),
+            Some(kind) if kind.eq_ignore_ascii_case("host") => {}
+            Some(kind) => {
+                return Err(ErrorCode::InvalidConfig.error(format!(
+                    "{key} is '{kind}' but the application supplied its own {key}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    let mut config = config.clone();
+    host_kind(
+        &mut config.metastore,
+        "Metastore",
+        backends.metastore().is_some(),
+    )?;
+    host_kind(&mut config.kms, "KMS", backends.kms().is_some())?;
+    Ok(config)
+}
+
+/// Re-wrap every system key under the new master key and commit the config
+/// drift guard. `config` must carry the rotation window (`PreviousKmsKeyId`,
+/// `PreviousRegionMap` or `PreviousStaticMasterKeyHex`).
+pub fn rotate_master_key_from_config(
+    config: &ConfigOptions,
+) -> Result<asherah::master_key_rotation::RewrapReport> {
+    let (mut resolved, _applied) = config.resolve().code(ErrorCode::InvalidConfig)?;
+    let mut config_drift_guard = config.config_drift_guard_options();
+    merge_runtime_env_overrides(&mut resolved, &mut config_drift_guard);
+    asherah::builders::rotate_master_key_from_resolved(&resolved)
+}
+
 /// Union any `RECOVERY_REGION_SUFFIXES` env entries into the resolved config's
 /// recovery list (config-provided entries first, then env extras), deduping
 /// while preserving order. This gives every language binding the env-based
@@ -805,4 +1001,93 @@ mod tests {
             None
         );
     }
+
+    #[test]
+    fn vault_auth_fields_reach_kms_config() {
+        let cfg = ConfigOptions {
+            kms: Some("vault".into()),
+            static_master_key_hex: None,
+            vault_addr: Some("https://vault.example:8200".into()),
+            vault_transit_key: Some("asherah".into()),
+            vault_auth_method: Some("approle".into()),
+            vault_approle_role_id: Some("role".into()),
+            vault_approle_secret_id_file: Some("/run/secrets/secret-id".into()),
+            vault_client_cert: Some("/etc/vault/client.crt".into()),
+            vault_transit_key_version: Some(4),
+            ..base_memory()
+        };
+        let (resolved, _) = cfg.resolve().expect("resolve");
+        match resolved.kms {
+            KmsConfig::Vault { options, .. } => {
+                assert_eq!(options.auth_method.as_deref(), Some("approle"));
+                assert_eq!(options.approle_role_id.as_deref(), Some("role"));
+                assert_eq!(
+                    options.approle_secret_id_file.as_deref(),
+                    Some("/run/secrets/secret-id")
+                );
+                assert_eq!(
+                    options.client_cert.as_deref(),
+                    Some("/etc/vault/client.crt")
+                );
+                assert_eq!(options.key_version, Some(4));
+            }
+            other => panic!("expected vault kms, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn quorum_members_resolve_from_their_own_fields() {
+        let cfg = ConfigOptions {
+            kms: Some("quorum".into()),
+            kms_quorum_members: Some(vec!["aws".into(), "Vault".into()]),
+            kms_quorum_threshold: Some(2),
+            kms_key_id: Some("arn:aws:kms:us-west-2:111122223333:key/abc".into()),
+            vault_addr: Some("https://vault.example:8200".into()),
+            vault_transit_key: Some("asherah".into()),
+            ..base_memory()
+        };
+        let (resolved, _) = cfg.resolve().expect("resolve");
+        match resolved.kms {
+            KmsConfig::Quorum { threshold, members } => {
+                assert_eq!(threshold, 2);
+                let names: Vec<_> = members.iter().map(|m| m.name.as_str()).collect();
+                assert_eq!(names, ["aws", "vault"]);
+                assert!(matches!(members[0].kms, KmsConfig::Aws { .. }));
+                assert!(matches!(members[1].kms, KmsConfig::Vault { .. }));
+            }
+            other => panic!("expected quorum kms, got {other:?}"),
+        }
+
+        let nested = ConfigOptions {
+            kms_quorum_members: Some(vec!["quorum".into()]),
+            ..cfg
+        };
+        assert!(nested.resolve().is_err());
+    }
+
+    #[test]
+    fn overlay_json_overrides_only_named_keys() {
+        let base = ConfigOptions {
+            session_cache_max_size: Some(10),
+            check_interval: Some(60),
+            config_drift_force_run: Some(false),
+            ..base_memory()
+        };
+        let merged = base
+            .overlay_json(
+                r#"{"SessionCacheMaxSize": 500, "CheckInterval": null,
+                    "ForceRunWithConfigDrift": true, "NotAnOption": 1}"#,
+            )
+            .expect("overlay");
+        assert_eq!(merged.session_cache_max_size, Some(500));
+        assert_eq!(merged.check_interval, None);
+        assert_eq!(merged.config_drift_force_run, Some(true));
+        assert_eq!(merged.service_name, base.service_name);
+        assert_eq!(merged.metastore.as_deref(), Some("memory"));
+
+        assert!(base.overlay_json("[]").is_err());
+        assert!(base
+            .overlay_json(r#"{"SessionCacheMaxSize": "lots"}"#)
+            .is_err());
+    }
 }
@@ -127,119 +127,204 @@ public sealed class AsherahSession : IAsherahSession
     /// <summary>
     /// True async encrypt — runs on Rust's tokio runtime, does not block a .NET thread pool thread.
     /// </summary>
-    public unsafe Task<byte[]> EncryptBytesAsync(byte[] plaintext)
+    public Task<byte[]> EncryptBytesAsync(byte[] plaintext) =>
+        EncryptBytesAsync(plaintext, CancellationToken.None);
+
+    /// <summary>
+    /// <see cref="EncryptBytesAsync(byte[])"/> that stops when <paramref name="cancellationToken"/>
+    /// is cancelled. The task is then cancelled, and any metastore or KMS call in flight is abandoned.
+    /// </summary>
+    public Task<byte[]> EncryptBytesAsync(byte[] plaintext, CancellationToken cancellationToken)
     {
         if (plaintext is null)
         {
             throw new ArgumentNullException(nameof(plaintext));
         }
-        EnsureNotDisposed();
-        bool addedRef = false;
-        _handle.DangerousAddRef(ref addedRef);
-        Interlocked.Increment(ref _pendingOps);
-
-        var tcs = new TaskCompletionSource<byte[]>(TaskCreationOptions.RunContinuationsAsynchronously);
-        var gcHandle = GCHandle.Alloc(new AsyncCallbackState(tcs, this));
-        var stateOwnedByCaller = true;
-
-        try
-        {
-            fixed (byte* ptr = plaintext)
-            {
-                var status = NativeMethods.asherah_encrypt_to_json_async(
-                    _handle.DangerousGetHandle(),
-                    ptr,
-                    new UIntPtr((ulong)plaintext.LongLength),
-                    &AsyncCompletionCallback,
-                    GCHandle.ToIntPtr(gcHandle));
+        return RunAsync(plaintext, encrypt: true, 0, cancellationToken);
+    }
 
-                if (status != 0)
-                {
-                    gcHandle.Free();
-                    Interlocked.Decrement(ref _pendingOps);
-                    stateOwnedByCaller = false;
-                    throw NativeError.Create("encrypt_to_json_async");
-                }
-                stateOwnedByCaller = false;
-            }
-        }
-        catch
+    /// <summary>
+    /// <see cref="EncryptBytesAsync(byte[], CancellationToken)"/> that fails with
+    /// <see cref="TimeoutException"/> if it has not finished within <paramref name="timeout"/>.
+    /// </summary>
+    public Task<byte[]> EncryptBytesAsync(
+        byte[] plaintext, TimeSpan timeout, CancellationToken cancellationToken = default)
+    {
+        if (plaintext is null)
         {
-            if (stateOwnedByCaller)
-            {
-                gcHandle.Free();
-                Interlocked.Decrement(ref _pendingOps);
-            }
-            throw;
+            throw new ArgumentNullException(nameof(plaintext));
         }
-        finally
+        return RunAsync(plaintext, encrypt: true, TimeoutMs(timeout), cancellationToken);
+    }
+
+    /// <inheritdoc cref="EncryptString(string)"/>
+    public Task<string> EncryptStringAsync(string plaintext) =>
+        EncryptStringAsync(plaintext, CancellationToken.None);
+
+    /// <inheritdoc cref="EncryptBytesAsync(byte[], CancellationToken)"/>
+    public async Task<string> EncryptStringAsync(string plaintext, CancellationToken cancellationToken)
+    {
+        if (plaintext is null)
         {
-            if (addedRef)
-            {
-                _handle.DangerousRelease();
-            }
+            throw new ArgumentNullException(nameof(plaintext));
         }
-
-        return tcs.Task;
+        var bytes = Encoding.UTF8.GetBytes(plaintext);
+        var result = await EncryptBytesAsync(bytes, cancellationToken).ConfigureAwait(false);
+        return Encoding.UTF8.GetString(result);
     }
 
-    /// <inheritdoc cref="EncryptString(string)"/>
-    public async Task<string> EncryptStringAsync(string plaintext)
+    /// <inheritdoc cref="EncryptBytesAsync(byte[], TimeSpan, CancellationToken)"/>
+    public async Task<string> EncryptStringAsync(
+        string plaintext, TimeSpan timeout, CancellationToken cancellationToken = default)
     {
         if (plaintext is null)
         {
             throw new ArgumentNullException(nameof(plaintext));
         }
         var bytes = Encoding.UTF8.GetBytes(plaintext);
-        var result = await EncryptBytesAsync(bytes).ConfigureAwait(false);
+        var result = await EncryptBytesAsync(bytes, timeout, cancellationToken).ConfigureAwait(false);
         return Encoding.UTF8.GetString(result);
     }
 
     /// <summary>
     /// True async decrypt — runs on Rust's tokio runtime, does not block a .NET thread pool thread.
     /// </summary>
-    public unsafe Task<byte[]> DecryptBytesAsync(byte[] ciphertextJson)
+    public Task<byte[]> DecryptBytesAsync(byte[] ciphertextJson) =>
+        DecryptBytesAsync(ciphertextJson, CancellationToken.None);
+
+    /// <summary>
+    /// <see cref="DecryptBytesAsync(byte[])"/> that stops when <paramref name="cancellationToken"/>
+    /// is cancelled. The task is then cancelled, and any metastore or KMS call in flight is abandoned.
+    /// </summary>
+    public Task<byte[]> DecryptBytesAsync(byte[] ciphertextJson, CancellationToken cancellationToken)
+    {
+        if (ciphertextJson is null)
+        {
+            throw new ArgumentNullException(nameof(ciphertextJson));
+        }
+        return StartDecrypt(ciphertextJson, 0, cancellationToken);
+    }
+
+    /// <summary>
+    /// <see cref="DecryptBytesAsync(byte[], CancellationToken)"/> that fails with
+    /// <see cref="TimeoutException"/> if it has not finished within <paramref name="timeout"/>.
+    /// </summary>
+    public Task<byte[]> DecryptBytesAsync(
+        byte[] ciphertextJson, TimeSpan timeout, CancellationToken cancellationToken = default)
+    {
+        if (ciphertextJson is null)
+        {
+            throw new ArgumentNullException(nameof(ciphertextJson));
+        }
+        return StartDecrypt(ciphertextJson, TimeoutMs(timeout), cancellationToken);
+    }
+
+    /// <inheritdoc cref="DecryptString(string)"/>
+    public Task<string> DecryptStringAsync(string ciphertextJson) =>
+        DecryptStringAsync(ciphertextJson, CancellationToken.None);
+
+    /// <inheritdoc cref="DecryptBytesAsync(byte[], CancellationToken)"/>
+    public async Task<string> DecryptStringAsync(string ciphertextJson, CancellationToken cancellationToken)
+    {
+        var bytes = DecryptStringInput(ciphertextJson);
+        var result = await DecryptBytesAsync(bytes, cancellationToken).ConfigureAwait(false);
+        return Encoding.UTF8.GetString(result);
+    }
+
+    /// <inheritdoc cref="DecryptBytesAsync(byte[], TimeSpan, CancellationToken)"/>
+    public async Task<string> DecryptStringAsync(
+        string ciphertextJson, TimeSpan timeout, CancellationToken cancellationToken = default)
+    {
+        var bytes = DecryptStringInput(ciphertextJson);
+        var result = await DecryptBytesAsync(bytes, timeout, cancellationToken).ConfigureAwait(false);
+        return Encoding.UTF8.GetString(result);
+    }
+
+    private static byte[] DecryptStringInput(string ciphertextJson)
     {
         if (ciphertextJson is null)
         {
             throw new ArgumentNullException(nameof(ciphertextJson));
         }
+        if (ciphertextJson.Length == 0)
+        {
+            throw new AsherahException(
+                "decrypt: ciphertext is empty (expected a DataRowRecord JSON envelope)");
+        }
+        return Encoding.UTF8.GetBytes(ciphertextJson);
+    }
+
+    private Task<byte[]> StartDecrypt(byte[] ciphertextJson, ulong timeoutMs, CancellationToken cancellationToken)
+    {
         if (ciphertextJson.Length == 0)
         {
             // Surface as a faulted Task (consistent with how
             // the C ABI surfaces errors via the async callback path)
             // rather than throwing synchronously. ArgumentNullException
-            // (above) does throw sync — that's the established C# contract
-            // for null inputs across both sync and async APIs.
+            // (in the callers) does throw sync — that's the established C#
+            // contract for null inputs across both sync and async APIs.
             return Task.FromException<byte[]>(new AsherahException(
                 "decrypt: ciphertext is empty (expected a DataRowRecord JSON envelope)"));
         }
+        return RunAsync(ciphertextJson, encrypt: false, timeoutMs, cancellationToken);
+    }
+
+    /// <summary>Milliseconds for the native timeout; 0 means none.</summary>
+    private static ulong TimeoutMs(TimeSpan timeout)
+    {
+        if (timeout == Timeout.InfiniteTimeSpan)
+        {
+            return 0;
+        }
+        if (timeout <= TimeSpan.Zero)
+        {
+            throw new ArgumentOutOfRangeException(nameof(timeout), timeout, "timeout must be positive");
+        }
+        return (ulong)Math.Ceiling(timeout.TotalMilliseconds);
+    }
+
+    /// <summary>
+    /// Starts a native async encrypt or decrypt. A cancellable token gets a native operation
+    /// handle that <see cref="CancellationToken.Register(Action{object?}, object?)"/> cancels; the
+    /// handle is released once the task completes and the registration is disposed, so a cancel
+    /// racing the completion never sees a freed handle.
+    /// </summary>
+    private unsafe Task<byte[]> RunAsync(byte[] input, bool encrypt, ulong timeoutMs, CancellationToken cancellationToken)
+    {
+        if (cancellationToken.IsCancellationRequested)
+        {
+            return Task.FromCanceled<byte[]>(cancellationToken);
+        }
         EnsureNotDisposed();
         bool addedRef = false;
         _handle.DangerousAddRef(ref addedRef);
         Interlocked.Increment(ref _pendingOps);
 
         var tcs = new TaskCompletionSource<byte[]>(TaskCreationOptions.RunContinuationsAsynchronously);
-        var gcHandle = GCHandle.Alloc(new AsyncCallbackState(tcs, this));
+        var gcHandle = GCHandle.Alloc(new AsyncCallbackState(tcs, this, cancellationToken));
         var stateOwnedByCaller = true;
+        var operation = IntPtr.Zero;
+        var operationOut = cancellationToken.CanBeCanceled ? &operation : null;
 
         try
         {
-            fixed (byte* ptr = ciphertextJson)
+            fixed (byte* ptr = input)
             {
-                var status = NativeMethods.asherah_decrypt_from_json_async(
-                    _handle.DangerousGetHandle(),
-                    ptr,
-                    new UIntPtr((ulong)ciphertextJson.LongLength),
-                    &AsyncCompletionCallback,
-                    GCHandle.ToIntPtr(gcHandle));
+                var length = new UIntPtr((ulong)input.LongLength);
+                var status = encrypt
+                    ? NativeMethods.asherah_encrypt_to_json_async_cancellable(
+                        _handle.DangerousGetHandle(), ptr, length, timeoutMs,
+                        &AsyncCompletionCallback, GCHandle.ToIntPtr(gcHandle), operationOut)
+                    : NativeMethods.asherah_decrypt_from_json_async_cancellable(
+                        _handle.DangerousGetHandle(), ptr, length, timeoutMs,
+                        &AsyncCompletionCallback, GCHandle.ToIntPtr(gcHandle), operationOut);
 
                 if (status != 0)
                 {
                     gcHandle.Free();
                     Interlocked.Decrement(ref _pendingOps);
                     stateOwnedByCaller = false;
-                    throw NativeError.Create("decrypt_from_json_async");
+                    throw NativeError.Create(encrypt ? "encrypt_to_json_async" : "decrypt_from_json_async");
                 }
                 stateOwnedByCaller = false;
             }
@@ -261,24 +346,24 @@ public sealed class AsherahSession : IAsherahSession
             }
         }
 
-        return tcs.Task;
-    }
-
-    /// <inheritdoc cref="DecryptString(string)"/>
-    public async Task<string> DecryptStringAsync(string ciphertextJson)
-    {
-        if (ciphertextJson is null)
-        {
-            throw new ArgumentNullException(nameof(ciphertextJson));
-        }
-        if (ciphertextJson.Length == 0)
+        if (operation != IntPtr.Zero)
         {
-            throw new AsherahException(
-                "decrypt: ciphertext is empty (expected a DataRowRecord JSON envelope)");
+            var registration = cancellationToken.Register(
+                static state => NativeMethods.asherah_cancel((IntPtr)state!), operation);
+            _ = tcs.Task.ContinueWith(
+                static (_, state) =>
+                {
+                    var (reg, op) = ((CancellationTokenRegistration, IntPtr))state!;
+                    reg.Dispose();
+                    NativeMethods.asherah_operation_free(op);
+                },
+                (registration, operation),
+                CancellationToken.None,
+                TaskContinuationOptions.ExecuteSynchronously,
+                TaskScheduler.Default);
         }
-        var bytes = Encoding.UTF8.GetBytes(ciphertextJson);
-        var result = await DecryptBytesAsync(bytes).ConfigureAwait(false);
-        return Encoding.UTF8.GetString(result);
+
+        return tcs.Task;
     }
 
     /// <summary>
@@ -380,7 +465,8 @@ public sealed class AsherahSession : IAsherahSession
         System.Security.Cryptography.CryptographicOperations.ZeroMemory(plaintext);
     }
 
-    private sealed record AsyncCallbackState(TaskCompletionSource<byte[]> Tcs, AsherahSession Session);
+    private sealed record AsyncCallbackState(
+        TaskCompletionSource<byte[]> Tcs, AsherahSession Session, CancellationToken Token);
 
     /// <summary>
     /// Callback invoked by Rust on a tokio worker thread when an async operation completes.
@@ -417,8 +503,22 @@ public sealed class AsherahSession : IAsherahSession
             {
                 if (errorMessage != IntPtr.Zero)
                 {
+                    // Read on this (callback) thread: the native side records
+                    // the code here just before invoking us.
+                    var code = NativeMethods.asherah_last_error_code();
                     var error = Marshal.PtrToStringUTF8(errorMessage) ?? "unknown async error";
-                    state.Tcs.TrySetException(new AsherahException(error));
+                    if (code == NativeMethods.ErrorCancelled)
+                    {
+                        state.Tcs.TrySetCanceled(state.Token);
+                    }
+                    else if (code == NativeMethods.ErrorDeadlineExceeded)
+                    {
+                        state.Tcs.TrySetException(new TimeoutException(error));
+                    }
+                    else
+                    {
+                        state.Tcs.TrySetException(new AsherahException(error));
+                    }
                 }
                 else if (resultData == IntPtr.Zero || resultLen == UIntPtr.Zero)
                 {
@@ -1,4 +1,5 @@
 using System;
+using System.Threading;
 using System.Threading.Tasks;
 
 namespace GoDaddy.Asherah.Encryption;
@@ -31,4 +32,28 @@ public interface IAsherahSession : IDisposable
 
     /// <inheritdoc cref="AsherahSession.DecryptStringAsync(string)"/>
     Task<string> DecryptStringAsync(string ciphertextJson);
+
+    /// <inheritdoc cref="AsherahSession.EncryptBytesAsync(byte[], CancellationToken)"/>
+    Task<byte[]> EncryptBytesAsync(byte[] plaintext, CancellationToken cancellationToken);
+
+    /// <inheritdoc cref="AsherahSession.EncryptBytesAsync(byte[], TimeSpan, CancellationToken)"/>
+    Task<byte[]> EncryptBytesAsync(byte[] plaintext, TimeSpan timeout, CancellationToken cancellationToken = default);
+
+    /// <inheritdoc cref="AsherahSession.EncryptStringAsync(string, CancellationToken)"/>
+    Task<string> EncryptStringAsync(string plaintext, CancellationToken cancellationToken);
+
+    /// <inheritdoc cref="AsherahSession.EncryptStringAsync(string, TimeSpan, CancellationToken)"/>
+    Task<string> EncryptStringAsync(string plaintext, TimeSpan timeout, CancellationToken cancellationToken = default);
+
+    /// <inheritdoc cref="AsherahSession.DecryptBytesAsync(byte[], CancellationToken)"/>
+    Task<byte[]> DecryptBytesAsync(byte[] ciphertextJson, CancellationToken cancellationToken);
+
+    /// <inheritdoc cref="AsherahSession.DecryptBytesAsync(byte[], TimeSpan, CancellationToken)"/>
+    Task<byte[]> DecryptBytesAsync(byte[] ciphertextJson, TimeSpan timeout, CancellationToken cancellationToken = default);
+
+    /// <inheritdoc cref="AsherahSession.DecryptStringAsync(string, CancellationToken)"/>
+    Task<string> DecryptStringAsync(string ciphertextJson, CancellationToken cancellationToken);
+
+    /// <inheritdoc cref="AsherahSession.DecryptStringAsync(string, TimeSpan, CancellationToken)"/>
+    Task<string> DecryptStringAsync(string ciphertextJson, TimeSpan timeout, CancellationToken cancellationToken = default);
 }
@@ -51,18 +51,32 @@ internal static class NativeMethods
     [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
     internal static extern IntPtr asherah_last_error_message();
 
-    // Async FFI — callback-based
     [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
-    internal static extern unsafe int asherah_encrypt_to_json_async(
-        IntPtr session, byte* data, UIntPtr length,
+    internal static extern int asherah_last_error_code();
+
+    // asherah_last_error_code values for an async operation ended early.
+    internal const int ErrorCancelled = 303;
+    internal const int ErrorDeadlineExceeded = 304;
+
+    // Async FFI — callback-based. timeoutMs 0 means no timeout; a null
+    // operation pointer means no cancel handle is wanted.
+    [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+    internal static extern unsafe int asherah_encrypt_to_json_async_cancellable(
+        IntPtr session, byte* data, UIntPtr length, ulong timeoutMs,
         delegate* unmanaged[Cdecl]<IntPtr, IntPtr, UIntPtr, IntPtr, void> callback,
-        IntPtr userData);
+        IntPtr userData, IntPtr* operation);
 
     [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
-    internal static extern unsafe int asherah_decrypt_from_json_async(
-        IntPtr session, byte* json, UIntPtr length,
+    internal static extern unsafe int asherah_decrypt_from_json_async_cancellable(
+        IntPtr session, byte* json, UIntPtr length, ulong timeoutMs,
         delegate* unmanaged[Cdecl]<IntPtr, IntPtr, UIntPtr, IntPtr, void> callback,
-        IntPtr userData);
+        IntPtr userData, IntPtr* operation);
+
+    [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+    internal static extern int asherah_cancel(IntPtr operation);
+
+    [DllImport(LibraryName, CallingConvention = CallingConvention.Cdecl)]
+    internal static extern void asherah_operation_free(IntPtr operation);
 
     // Log / metrics hooks (C ABI exposed by asherah-ffi/src/hooks.rs).
     // Callback signatures:
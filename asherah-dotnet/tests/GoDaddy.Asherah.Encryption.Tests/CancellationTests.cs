@@ -0,0 +1,107 @@
+using System;
+using System.IO;
+using System.Threading;
+using System.Threading.Tasks;
+using GoDaddy.Asherah.Encryption;
+using Xunit;
+
+namespace GoDaddy.Asherah.Encryption.Tests;
+
+/// <summary>
+/// <see cref="CancellationToken"/> and timeout overloads of the async session
+/// methods. A hung backend can't be staged with the in-memory metastore; the
+/// native side's cancel/deadline behavior is covered by
+/// <c>asherah-ffi/tests/cancellation.rs</c>.
+/// </summary>
+public class CancellationTests : IDisposable
+{
+    static CancellationTests()
+    {
+        Environment.SetEnvironmentVariable("STATIC_MASTER_KEY_HEX",
+            Environment.GetEnvironmentVariable("STATIC_MASTER_KEY_HEX")
+                ?? new string('2', 64));
+        if (string.IsNullOrWhiteSpace(Environment.GetEnvironmentVariable("ASHERAH_DOTNET_NATIVE")))
+        {
+            var root = LocateRepoRoot();
+            Environment.SetEnvironmentVariable(
+                "ASHERAH_DOTNET_NATIVE", Path.Join(root, "target", "debug"));
+        }
+    }
+
+    private readonly AsherahFactory _factory;
+    private readonly AsherahSession _session;
+
+    public CancellationTests()
+    {
+        var config = AsherahConfig.CreateBuilder()
+            .WithServiceName("cancellation-test-svc")
+            .WithProductId("cancellation-test-prod")
+            .WithMetastore(MetastoreKind.Memory)
+            .WithKms(KmsKind.TestDebugStatic)
+            .Build();
+        _factory = AsherahFactory.FromConfig(config);
+        _session = _factory.GetSession("cancellation-test-partition");
+    }
+
+    public void Dispose()
+    {
+        _session.Dispose();
+        _factory.Dispose();
+    }
+
+    [Fact]
+    public async Task AlreadyCancelledToken_CancelsWithoutCallingNative()
+    {
+        using var cts = new CancellationTokenSource();
+        cts.Cancel();
+        var ex = await Assert.ThrowsAnyAsync<OperationCanceledException>(
+            () => _session.EncryptBytesAsync(new byte[] { 1, 2, 3 }, cts.Token));
+        Assert.Equal(cts.Token, ex.CancellationToken);
+        await Assert.ThrowsAnyAsync<OperationCanceledException>(
+            () => _session.DecryptStringAsync("{}", cts.Token));
+    }
+
+    [Fact]
+    public async Task LiveTokenAndTimeout_RoundTrip()
+    {
+        using var cts = new CancellationTokenSource();
+        var ciphertext = await _session.EncryptStringAsync("cancellable payload", cts.Token);
+        Assert.Equal(
+            "cancellable payload",
+            await _session.DecryptStringAsync(ciphertext, TimeSpan.FromSeconds(30), cts.Token));
+
+        // Cancelling after completion is harmless.
+        cts.Cancel();
+
+        var bytes = await _session.EncryptBytesAsync(new byte[] { 4, 5 }, Timeout.InfiniteTimeSpan);
+        Assert.Equal(new byte[] { 4, 5 }, await _session.DecryptBytesAsync(bytes, CancellationToken.None));
+    }
+
+    [Fact]
+    public void NonPositiveTimeout_ThrowsSync()
+    {
+        Assert.Throws<ArgumentOutOfRangeException>(() =>
+        {
+            _ = _session.EncryptBytesAsync(new byte[] { 1 }, TimeSpan.Zero);
+        });
+    }
+
+    [Fact]
+    public async Task NativeErrors_StillFaultWithAsherahException()
+    {
+        using var cts = new CancellationTokenSource();
+        await Assert.ThrowsAsync<AsherahException>(
+            () => _session.DecryptStringAsync("{not json", TimeSpan.FromSeconds(30), cts.Token));
+    }
+
+    private static string LocateRepoRoot()
+    {
+        var dir = AppContext.BaseDirectory;
+        for (int i = 0; i < 8 && dir is not null; i++)
+        {
+            if (File.Exists(Path.Join(dir, "Cargo.toml"))) return dir;
+            dir = Path.GetDirectoryName(dir);
+        }
+        throw new InvalidOperationException("Could not locate repo root from " + AppContext.BaseDirectory);
+    }
+}
@@ -0,0 +1,489 @@
+//! C ABI for host-implemented metastores and KMSes.
+//!
+//! A binding fills in an [`AsherahMetastoreVTable`] or [`AsherahKmsVTable`]
+//! with its own functions, registers it with `asherah_metastore_new` /
+//! `asherah_kms_new`, and passes the handles to
+//! `asherah_factory_new_with_backends`. Everything else (service, product,
+//! policy, caching) still comes from the config JSON, whose `Metastore` /
+//! `KMS` may be left out or set to `"host"` for a backend supplied here.
+//!
+//! ## Threading
+//!
+//! Callbacks may fire from any thread, concurrently — every session of every
+//! factory built with the handle shares it, and the async entry points run
+//! them on tokio worker threads. Each call blocks the calling thread until
+//! the callback returns.
+//!
+//! ## Lifetime
+//!
+//! The callbacks and `user_data` must stay valid until `release` is called.
+//! A factory keeps the backend alive, so the handle may be freed as soon as
+//! the factory is built; `release` (if non-null) runs exactly once, after
+//! the handle and every factory using it have been freed.
+//!
+//! ## Panic safety
+//!
+//! As for the hooks in `hooks.rs`: bindings MUST catch their own exceptions
+//! inside a callback, because unwinding across `extern "C"` aborts the
+//! process. A Rust-side panic while marshalling a call is contained and
+//! reported as an error from that operation.
+//!
+//! ## Results
+//!
+//! Callbacks return a status code and hand back bytes by calling
+//! `asherah_backend_output_set` on the `out` argument, which copies them;
+//! calling it again replaces what was set. A negative status is an error,
+//! and any bytes set alongside it are read as a UTF-8 error message.
+//!
+//! Key records cross the boundary as the JSON Asherah stores in its SQL and
+//! DynamoDB metastores: `{"Created":…,"Key":"<base64>","ParentKeyMeta":
+//! {"KeyId":…,"Created":…},"Revoked":…}`. The host stores it as an opaque
+//! value under `(id, created)`.
+//!
+//! ## Strings
+//!
+//! `id` arguments are NUL-terminated UTF-8 and, like every pointer passed
+//! to a callback, valid ONLY for the duration of the call.
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_void};
+use std::ptr::null_mut;
+use std::sync::Arc;
+
+use asherah as ael;
+use asherah::error::ErrorCode;
+use asherah::types::EnvelopeKeyRecord;
+
+use crate::{cstr_to_str, set_error, set_error_sanitized};
+
+/// `load` / `load_latest` status: the record was found and set on `out`.
+pub const ASHERAH_BACKEND_FOUND: i32 = 1;
+/// `load` / `load_latest` status: no such record.
+pub const ASHERAH_BACKEND_NOT_FOUND: i32 = 0;
+/// `store` status: the record was written.
+pub const ASHERAH_BACKEND_STORED: i32 = 1;
+/// `store` status: a record already exists under `(id, created)`; it must
+/// not be overwritten.
+pub const ASHERAH_BACKEND_DUPLICATE: i32 = 0;
+/// Success status for KMS callbacks.
+pub const ASHERAH_BACKEND_OK: i32 = 0;
+/// Generic error status. Any negative value is treated as an error.
+pub const ASHERAH_BACKEND_ERROR: i32 = -1;
+
+/// Where a callback writes its result. Only valid during the callback.
+pub struct AsherahBackendOutput {
+    bytes: zeroize::Zeroizing<Vec<u8>>,
+}
+
+impl std::fmt::Debug for AsherahBackendOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AsherahBackendOutput { .. }")
+    }
+}
+
+/// Load the record stored under `(id, created)`.
+pub type AsherahMetastoreLoadFn = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    id: *const c_char,
+    created: i64,
+    out: *mut AsherahBackendOutput,
+) -> i32;
+
+/// Load the record under `id` with the largest `created`.
+pub type AsherahMetastoreLoadLatestFn = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    id: *const c_char,
+    out: *mut AsherahBackendOutput,
+) -> i32;
+
+/// Store `record` under `(id, created)` unless one already exists.
+pub type AsherahMetastoreStoreFn = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    id: *const c_char,
+    created: i64,
+    record: *const u8,
+    record_len: usize,
+    out: *mut AsherahBackendOutput,
+) -> i32;
+
+/// Wrap (`encrypt_key`) or unwrap (`decrypt_key`) a system key.
+pub type AsherahKmsFn = unsafe extern "C" fn(
+    user_data: *mut c_void,
+    data: *const u8,
+    len: usize,
+    out: *mut AsherahBackendOutput,
+) -> i32;
+
+/// Called once when nothing uses the backend any more.
+pub type AsherahReleaseFn = unsafe extern "C" fn(user_data: *mut c_void);
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct AsherahMetastoreVTable {
+    pub user_data: *mut c_void,
+    pub load: Option<AsherahMetastoreLoadFn>,
+    pub load_latest: Option<AsherahMetastoreLoadLatestFn>,
+    pub store: Option<AsherahMetastoreStoreFn>,
+    /// May be null.
+    pub release: Option<AsherahReleaseFn>,
+}
+
+#[repr(C)]
+#[derive(Debug)]
+pub struct AsherahKmsVTable {
+    pub user_data: *mut c_void,
+    pub encrypt_key: Option<AsherahKmsFn>,
+    pub decrypt_key: Option<AsherahKmsFn>,
+    /// May be null.
+    pub release: Option<AsherahReleaseFn>,
+}
+
+/// Opaque handle to a registered metastore vtable.
+pub struct AsherahMetastore {
+    inner: Arc<HostMetastore>,
+}
+
+/// Opaque handle to a registered KMS vtable.
+pub struct AsherahKms {
+    inner: Arc<HostKms>,
+}
+
+impl std::fmt::Debug for AsherahMetastore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsherahMetastore")
+            .field("name", &self.inner.name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl std::fmt::Debug for AsherahKms {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsherahKms")
+            .field("name", &self.inner.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Runs the host's `release` when the last reference goes away. `user_data`
+/// is stored as `usize` so the backends are `Send + Sync`; the binding
+/// guarantees it stays valid until then.
+struct UserData {
+    ptr: usize,
+    release: Option<AsherahReleaseFn>,
+}
+
+impl UserData {
+    fn ptr(&self) -> *mut c_void {
+        self.ptr as *mut c_void
+    }
+}
+
+impl Drop for UserData {
+    fn drop(&mut self) {
+        if let Some(release) = self.release {
+            unsafe { release(self.ptr()) };
+        }
+    }
+}
+
+struct HostMetastore {
+    name: String,
+    load: AsherahMetastoreLoadFn,
+    load_latest: AsherahMetastoreLoadLatestFn,
+    store: AsherahMetastoreStoreFn,
+    user_data: UserData,
+}
+
+struct HostKms {
+    name: String,
+    encrypt_key: AsherahKmsFn,
+    decrypt_key: AsherahKmsFn,
+    user_data: UserData,
+}
+
+/// Run one callback. `call` gets the output slot and returns the host's
+/// status; a panic while marshalling becomes an error.
+fn invoke(
+    what: &str,
+    name: &str,
+    call: impl FnOnce(*mut AsherahBackendOutput) -> i32,
+) -> anyhow::Result<(i32, zeroize::Zeroizing<Vec<u8>>)> {
+    let mut out = AsherahBackendOutput {
+        bytes: zeroize::Zeroizing::new(Vec::new()),
+    };
+    let status = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        call(std::ptr::addr_of_mut!(out))
+    }))
+    .map_err(|_| anyhow::anyhow!("{what} '{name}': internal panic"))?;
+    if status < 0 {
+        let message = String::from_utf8_lossy(&out.bytes);
+        if message.is_empty() {
+            anyhow::bail!("{what} '{name}' failed with status {status}");
+        }
+        anyhow::bail!("{what} '{name}' failed: {message}");
+    }
+    Ok((status, out.bytes))
+}
+
+impl HostMetastore {
+    fn found(
+        &self,
+        id: &str,
+        status: i32,
+        bytes: &[u8],
+    ) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        if status == ASHERAH_BACKEND_NOT_FOUND {
+            return Ok(None);
+        }
+        let json = std::str::from_utf8(bytes).map_err(|_| {
+            anyhow::anyhow!("metastore '{}' returned a non-UTF-8 record", self.name)
+        })?;
+        let mut ekr = EnvelopeKeyRecord::from_json_fast(json).map_err(|e| {
+            anyhow::anyhow!("metastore '{}' returned an invalid record: {e}", self.name)
+        })?;
+        ekr.id = id.to_string();
+        Ok(Some(ekr))
+    }
+}
+
+impl ael::traits::Metastore for HostMetastore {
+    fn load(&self, id: &str, created: i64) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let c_id = CString::new(id)?;
+        let (status, bytes) = invoke("metastore load", &self.name, |out| unsafe {
+            (self.load)(self.user_data.ptr(), c_id.as_ptr(), created, out)
+        })?;
+        self.found(id, status, &bytes)
+    }
+
+    fn load_latest(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let c_id = CString::new(id)?;
+        let (status, bytes) = invoke("metastore load_latest", &self.name, |out| unsafe {
+            (self.load_latest)(self.user_data.ptr(), c_id.as_ptr(), out)
+        })?;
+        self.found(id, status, &bytes)
+    }
+
+    fn store(&self, id: &str, created: i64, ekr: &EnvelopeKeyRecord) -> anyhow::Result<bool> {
+        let c_id = CString::new(id)?;
+        let record = serde_json::to_vec(ekr)?;
+        let (status, _) = invoke("metastore store", &self.name, |out| unsafe {
+            (self.store)(
+                self.user_data.ptr(),
+                c_id.as_ptr(),
+                created,
+                record.as_ptr(),
+                record.len(),
+                out,
+            )
+        })?;
+        Ok(status != ASHERAH_BACKEND_DUPLICATE)
+    }
+}
+
+impl ael::traits::KeyManagementService for HostKms {
+    fn encrypt_key(&self, _ctx: &(), key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let (_, mut bytes) = invoke("KMS encrypt_key", &self.name, |out| unsafe {
+            (self.encrypt_key)(
+                self.user_data.ptr(),
+                key_bytes.as_ptr(),
+                key_bytes.len(),
+                out,
+            )
+        })?;
+        Ok(std::mem::take(&mut *bytes))
+    }
+
+    fn decrypt_key(&self, _ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let (_, mut bytes) = invoke("KMS decrypt_key", &self.name, |out| unsafe {
+            (self.decrypt_key)(self.user_data.ptr(), blob.as_ptr(), blob.len(), out)
+        })?;
+        Ok(std::mem::take(&mut *bytes))
+    }
+}
+
+/// Copy `len` bytes from `data` into a callback's result.
+///
+/// # Safety
+/// `out` must be the output pointer passed to the running callback, and
+/// `data` must point to `len` readable bytes (it may be null when `len` is 0).
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_backend_output_set(
+    out: *mut AsherahBackendOutput,
+    data: *const u8,
+    len: usize,
+) -> i32 {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if out.is_null() || (data.is_null() && len > 0) {
+            return ASHERAH_BACKEND_ERROR;
+        }
+        let out = &mut *out;
+        // Wipe what was set before: KMS results are plaintext system keys.
+        zeroize::Zeroize::zeroize(&mut *out.bytes);
+        if len > 0 {
+            out.bytes.reserve_exact(len);
+            out.bytes
+                .extend_from_slice(std::slice::from_raw_parts(data, len));
+        }
+        0
+    })) {
+        Ok(result) => result,
+        Err(_) => ASHERAH_BACKEND_ERROR,
+    }
+}
+
+/// Register a host metastore. `name` identifies it to the config drift
+/// guard, so it must stay the same across restarts. Returns null on error
+/// (see `asherah_last_error_message`).
+///
+/// # Safety
+/// `name` must be a valid C string and `vtable` a valid vtable pointer whose
+/// callbacks and `user_data` follow the contract in the module docs.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_metastore_new(
+    name: *const c_char,
+    vtable: *const AsherahMetastoreVTable,
+) -> *mut AsherahMetastore {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let name = match backend_name(name, vtable.is_null()) {
+            Ok(name) => name,
+            Err(e) => {
+                set_error_sanitized("metastore_new", &e);
+                return null_mut();
+            }
+        };
+        let vtable = &*vtable;
+        let (Some(load), Some(load_latest), Some(store)) =
+            (vtable.load, vtable.load_latest, vtable.store)
+        else {
+            set_error(
+                ErrorCode::InvalidArgument,
+                "metastore_new failed: load, load_latest and store are required",
+            );
+            return null_mut();
+        };
+        let inner = Arc::new(HostMetastore {
+            name,
+            load,
+            load_latest,
+            store,
+            user_data: UserData {
+                ptr: vtable.user_data as usize,
+                release: vtable.release,
+            },
+        });
+        Box::into_raw(Box::new(AsherahMetastore { inner }))
+    })) {
+        Ok(result) => result,
+        Err(_) => {
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_metastore_new",
+            );
+            null_mut()
+        }
+    }
+}
+
+/// Register a host KMS. `name` identifies it to the config drift guard, so
+/// it must stay the same across restarts. Returns null on error.
+///
+/// # Safety
+/// `name` must be a valid C string and `vtable` a valid vtable pointer whose
+/// callbacks and `user_data` follow the contract in the module docs.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_kms_new(
+    name: *const c_char,
+    vtable: *const AsherahKmsVTable,
+) -> *mut AsherahKms {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let name = match backend_name(name, vtable.is_null()) {
+            Ok(name) => name,
+            Err(e) => {
+                set_error_sanitized("kms_new", &e);
+                return null_mut();
+            }
+        };
+        let vtable = &*vtable;
+        let (Some(encrypt_key), Some(decrypt_key)) = (vtable.encrypt_key, vtable.decrypt_key)
+        else {
+            set_error(
+                ErrorCode::InvalidArgument,
+                "kms_new failed: encrypt_key and decrypt_key are required",
+            );
+            return null_mut();
+        };
+        let inner = Arc::new(HostKms {
+            name,
+            encrypt_key,
+            decrypt_key,
+            user_data: UserData {
+                ptr: vtable.user_data as usize,
+                release: vtable.release,
+            },
+        });
+        Box::into_raw(Box::new(AsherahKms { inner }))
+    })) {
+        Ok(result) => result,
+        Err(_) => {
+            set_error(ErrorCode::Internal, "internal panic in asherah_kms_new");
+            null_mut()
+        }
+    }
+}
+
+/// # Safety
+/// `ptr` must be a handle from `asherah_metastore_new`, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_metastore_free(ptr: *mut AsherahMetastore) {
+    drop(std::panic::catch_unwind(std::panic::AssertUnwindSafe(
+        || {
+            if !ptr.is_null() {
+                drop(Box::from_raw(ptr));
+            }
+        },
+    )));
+}
+
+/// # Safety
+/// `ptr` must be a handle from `asherah_kms_new`, or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_kms_free(ptr: *mut AsherahKms) {
+    drop(std::panic::catch_unwind(std::panic::AssertUnwindSafe(
+        || {
+            if !ptr.is_null() {
+                drop(Box::from_raw(ptr));
+            }
+        },
+    )));
+}
+
+unsafe fn backend_name(name: *const c_char, null_vtable: bool) -> anyhow::Result<String> {
+    if null_vtable {
+        return Err(ErrorCode::InvalidArgument.error("null vtable"));
+    }
+    let name = cstr_to_str(name)?.trim();
+    if name.is_empty() {
+        return Err(ErrorCode::InvalidArgument.error("backend name must not be empty"));
+    }
+    Ok(name.to_string())
+}
+
+/// The [`ael::builders::HostBackends`] for a pair of optional handles.
+///
+/// # Safety
+/// Each pointer must be a live handle from this module, or null.
+pub(crate) unsafe fn host_backends(
+    metastore: *const AsherahMetastore,
+    kms: *const AsherahKms,
+) -> ael::builders::HostBackends {
+    let mut backends = ael::builders::HostBackends::default();
+    if let Some(metastore) = metastore.as_ref() {
+        let store: Arc<dyn ael::traits::Metastore> = metastore.inner.clone();
+        backends = backends.with_metastore(metastore.inner.name.clone(), store);
+    }
+    if let Some(kms) = kms.as_ref() {
+        let inner: Arc<dyn ael::traits::KeyManagementService> = kms.inner.clone();
+        backends = backends.with_kms(kms.inner.name.clone(), inner);
+    }
+    backends
+}
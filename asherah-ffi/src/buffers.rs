@@ -0,0 +1,454 @@
+//! Caller-provided output buffers and the struct form of a DataRowRecord.
+//!
+//! The `*_into` functions write their output into memory the caller owns
+//! rather than allocating an `AsherahBuffer`, which saves an allocation, a
+//! copy into the binding's own buffer and the `asherah_buffer_free` call.
+//! They take `out`, `out_capacity` and `out_len`:
+//!
+//! - With `out` null nothing is encrypted or decrypted: `*out_len` is set to
+//!   an upper bound on the output size and 0 is returned, like cobhan's
+//!   `EstimateBuffer`.
+//! - If the output is larger than `out_capacity`, `*out_len` is set to its
+//!   exact size, `out` is left untouched and `ASHERAH_BUFFER_TOO_SMALL` is
+//!   returned. The output of an encrypt is discarded; encrypt again.
+//! - Otherwise the output is written to `out`, `*out_len` is set to its
+//!   size and 0 is returned.
+//!
+//! `AsherahDataRowRecord` holds a record's parts separately, so a binding
+//! can store them in native columns with no JSON or base64 step.
+
+use std::os::raw::c_int;
+
+use asherah as ael;
+use asherah::error::ErrorCode;
+use asherah::types::{DataRowRecord, EnvelopeKeyRecord, KeyMeta};
+use zeroize::Zeroizing;
+
+use crate::{
+    asherah_buffer_free, set_error, set_error_sanitized, take_vec_into_buffer, AsherahBuffer,
+    SharedSession,
+};
+
+/// Returned by a `*_into` function when the output does not fit; `*out_len`
+/// (or each field's `len`) holds the size needed.
+pub const ASHERAH_BUFFER_TOO_SMALL: c_int = 1;
+
+/// AES-256-GCM nonce and tag around every ciphertext.
+const AEAD_OVERHEAD: usize = ael::aead::AES256GCM::NONCE_SIZE + ael::aead::AES256GCM::TAG_SIZE;
+/// A data key is an AES-256 key, encrypted under the intermediate key.
+const ENCRYPTED_KEY_LEN: usize = 32 + AEAD_OVERHEAD;
+
+/// A DataRowRecord split into its parts.
+///
+/// Output from `asherah_encrypt_to_record` is owned by the library and
+/// released with `asherah_record_free`. For `asherah_encrypt_to_record_into`
+/// the caller points each buffer's `data` at memory it owns and sets
+/// `capacity`; the library sets `len`. Input to the decrypt functions is
+/// read through each buffer's `data` and `len`.
+#[repr(C)]
+#[derive(Debug)]
+pub struct AsherahDataRowRecord {
+    /// Encrypted data: nonce, ciphertext and tag.
+    pub data: AsherahBuffer,
+    /// The data key, encrypted under the intermediate key.
+    pub key: AsherahBuffer,
+    /// When the data key was created, in Unix seconds.
+    pub created: i64,
+    /// Id of the intermediate key; UTF-8, not NUL-terminated.
+    pub parent_key_id: AsherahBuffer,
+    /// When the intermediate key was created, in Unix seconds.
+    pub parent_key_created: i64,
+}
+
+/// Run an entry point, turning a panic into an `ASHERAH_ERR_INTERNAL` error.
+pub(crate) fn ffi_call(name: &str, body: impl FnOnce() -> c_int) -> c_int {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or_else(|_| {
+        set_error(ErrorCode::Internal, format!("internal panic in {name}"));
+        -1
+    })
+}
+
+/// The `len` bytes at `ptr`, or an error naming `what` if `ptr` is null.
+unsafe fn input<'data>(ptr: *const u8, len: usize, what: &str) -> Result<&'data [u8], c_int> {
+    if ptr.is_null() {
+        if len > 0 {
+            set_error(ErrorCode::InvalidArgument, format!("null {what}"));
+            return Err(-1);
+        }
+        return Ok(&[]);
+    }
+    Ok(std::slice::from_raw_parts(ptr, len))
+}
+
+unsafe fn session_ref<'session>(
+    session: *mut SharedSession,
+) -> Result<&'session SharedSession, c_int> {
+    session.as_ref().ok_or_else(|| {
+        set_error(ErrorCode::InvalidArgument, "null session");
+        -1
+    })
+}
+
+/// Copy `bytes` to the caller's `out`, or report the size it needs.
+unsafe fn write_out(bytes: &[u8], out: *mut u8, out_capacity: usize, out_len: *mut usize) -> c_int {
+    *out_len = bytes.len();
+    if bytes.len() > out_capacity {
+        return ASHERAH_BUFFER_TOO_SMALL;
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len());
+    0
+}
+
+/// Upper bound on the JSON record for `len` bytes of plaintext.
+fn json_len_bound(session: &SharedSession, len: usize) -> usize {
+    DataRowRecord::json_len_bound(session.session.inner.intermediate_key_id(), len)
+}
+
+fn encrypt(session: &SharedSession, data: &[u8], op: &str) -> Result<DataRowRecord, c_int> {
+    if let Err(e) = asherah::limits::check_plaintext_len(data.len()) {
+        set_error(ErrorCode::of(&e), e.to_string());
+        return Err(-1);
+    }
+    session.session.inner.encrypt(data).map_err(|e| {
+        set_error_sanitized(op, &e);
+        -1
+    })
+}
+
+fn decrypt(
+    session: &SharedSession,
+    drr: DataRowRecord,
+    op: &str,
+) -> Result<Zeroizing<Vec<u8>>, c_int> {
+    match session.session.inner.decrypt(drr) {
+        Ok(plaintext) => Ok(Zeroizing::new(plaintext)),
+        Err(e) => {
+            set_error_sanitized(op, &e);
+            Err(-1)
+        }
+    }
+}
+
+/// Like `asherah_encrypt_to_json`, writing the JSON record to `out`. See
+/// the module docs for `out`, `out_capacity` and `out_len`.
+///
+/// # Safety
+/// `session` must be valid, `data` must reference `len` bytes, `out` must be
+/// null or reference `out_capacity` writable bytes, and `out_len` must be
+/// non-null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_encrypt_to_json_into(
+    session: *mut SharedSession,
+    data: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_capacity: usize,
+    out_len: *mut usize,
+) -> c_int {
+    ffi_call("asherah_encrypt_to_json_into", || {
+        let run = || -> Result<c_int, c_int> {
+            let session = session_ref(session)?;
+            if out_len.is_null() {
+                set_error(ErrorCode::InvalidArgument, "null out_len");
+                return Err(-1);
+            }
+            let data = input(data, len, "data")?;
+            if out.is_null() {
+                *out_len = json_len_bound(session, len);
+                return Ok(0);
+            }
+            let drr = encrypt(session, data, "encrypt_to_json_into")?;
+            Ok(write_out(
+                drr.to_json_fast().as_bytes(),
+                out,
+                out_capacity,
+                out_len,
+            ))
+        };
+        run().unwrap_or_else(|status| status)
+    })
+}
+
+/// Like `asherah_decrypt_from_json`, writing the plaintext to `out`. See
+/// the module docs for `out`, `out_capacity` and `out_len`. The size query
+/// returns `len`, which the plaintext never exceeds.
+///
+/// # Safety
+/// `session` must be valid, `json` must reference `len` bytes, `out` must be
+/// null or reference `out_capacity` writable bytes, and `out_len` must be
+/// non-null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_decrypt_from_json_into(
+    session: *mut SharedSession,
+    json: *const u8,
+    len: usize,
+    out: *mut u8,
+    out_capacity: usize,
+    out_len: *mut usize,
+) -> c_int {
+    ffi_call("asherah_decrypt_from_json_into", || {
+        let run = || -> Result<c_int, c_int> {
+            let session = session_ref(session)?;
+            if out_len.is_null() {
+                set_error(ErrorCode::InvalidArgument, "null out_len");
+                return Err(-1);
+            }
+            let json = input(json, len, "json")?;
+            if let Err(e) = asherah::limits::check_ciphertext_len(len) {
+                set_error(ErrorCode::of(&e), e.to_string());
+                return Err(-1);
+            }
+            if out.is_null() {
+                *out_len = len;
+                return Ok(0);
+            }
+            let drr = serde_json::from_slice::<DataRowRecord>(json).map_err(|e| {
+                // As in `asherah_decrypt_from_json`: keep serde's input
+                // snippet out of the user-facing chain.
+                set_error(
+                    ErrorCode::InvalidDataRowRecord,
+                    format!("decrypt_from_json_into: invalid JSON: {e}"),
+                );
+                log::warn!("decrypt_from_json_into: invalid JSON: {e:#}");
+                -1
+            })?;
+            if drr.plaintext_len() > out_capacity {
+                *out_len = drr.plaintext_len();
+                return Ok(ASHERAH_BUFFER_TOO_SMALL);
+            }
+            let plaintext = decrypt(session, drr, "decrypt_from_json_into")?;
+            Ok(write_out(&plaintext, out, out_capacity, out_len))
+        };
+        run().unwrap_or_else(|status| status)
+    })
+}
+
+/// The parts of an encrypted record, or an internal error if it lacks them.
+fn parts(drr: DataRowRecord) -> Result<(Vec<u8>, EnvelopeKeyRecord, KeyMeta), c_int> {
+    let Some(mut key) = drr.key else {
+        set_error(ErrorCode::Internal, "encrypted record has no key");
+        return Err(-1);
+    };
+    let Some(parent) = key.parent_key_meta.take() else {
+        set_error(ErrorCode::Internal, "encrypted record has no parent key");
+        return Err(-1);
+    };
+    Ok((drr.data, key, parent))
+}
+
+/// Encrypt `len` bytes of `data` into a record whose buffers the library
+/// allocates. Release it with `asherah_record_free`.
+///
+/// # Safety
+/// `session` must be valid, `data` must reference `len` bytes, and `out`
+/// must be non-null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_encrypt_to_record(
+    session: *mut SharedSession,
+    data: *const u8,
+    len: usize,
+    out: *mut AsherahDataRowRecord,
+) -> c_int {
+    ffi_call("asherah_encrypt_to_record", || {
+        let run = || -> Result<c_int, c_int> {
+            let session = session_ref(session)?;
+            if out.is_null() {
+                set_error(ErrorCode::InvalidArgument, "null output record");
+                return Err(-1);
+            }
+            let data = input(data, len, "data")?;
+            let drr = encrypt(session, data, "encrypt_to_record")?;
+            let (data, key, parent) = parts(drr)?;
+            let out = &mut *out;
+            take_vec_into_buffer(data, &mut out.data);
+            take_vec_into_buffer(key.encrypted_key, &mut out.key);
+            take_vec_into_buffer(parent.id.into_bytes(), &mut out.parent_key_id);
+            out.created = key.created;
+            out.parent_key_created = parent.created;
+            Ok(0)
+        };
+        run().unwrap_or_else(|status| status)
+    })
+}
+
+/// Encrypt `len` bytes of `data` into the caller's buffers in `record`.
+///
+/// The caller sets `data`, `key` and `parent_key_id` to buffers it owns,
+/// with their `capacity`; the library sets each `len` and the timestamps.
+/// With all three `data` pointers null, nothing is encrypted and each `len`
+/// is set to the size needed. If any part does not fit, each `len` is set
+/// to its exact size and `ASHERAH_BUFFER_TOO_SMALL` is returned.
+///
+/// # Safety
+/// `session` must be valid, `data` must reference `len` bytes, and `record`
+/// must be non-null with each buffer null or referencing `capacity`
+/// writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_encrypt_to_record_into(
+    session: *mut SharedSession,
+    data: *const u8,
+    len: usize,
+    record: *mut AsherahDataRowRecord,
+) -> c_int {
+    ffi_call("asherah_encrypt_to_record_into", || {
+        let run = || -> Result<c_int, c_int> {
+            let session = session_ref(session)?;
+            let Some(record) = record.as_mut() else {
+                set_error(ErrorCode::InvalidArgument, "null record");
+                return Err(-1);
+            };
+            let data = input(data, len, "data")?;
+            if record.data.data.is_null()
+                && record.key.data.is_null()
+                && record.parent_key_id.data.is_null()
+            {
+                record.data.len = len + AEAD_OVERHEAD;
+                record.key.len = ENCRYPTED_KEY_LEN;
+                record.parent_key_id.len = session.session.inner.intermediate_key_id().len();
+                return Ok(0);
+            }
+            let drr = encrypt(session, data, "encrypt_to_record_into")?;
+            let (data, key, parent) = parts(drr)?;
+            let fields = [
+                (&mut record.data, data.as_slice()),
+                (&mut record.key, key.encrypted_key.as_slice()),
+                (&mut record.parent_key_id, parent.id.as_bytes()),
+            ];
+            let fits = fields
+                .iter()
+                .all(|(buf, bytes)| !buf.data.is_null() && bytes.len() <= buf.capacity);
+            for (buf, bytes) in fields {
+                buf.len = bytes.len();
+                if fits {
+                    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf.data, bytes.len());
+                }
+            }
+            if !fits {
+                return Ok(ASHERAH_BUFFER_TOO_SMALL);
+            }
+            record.created = key.created;
+            record.parent_key_created = parent.created;
+            Ok(0)
+        };
+        run().unwrap_or_else(|status| status)
+    })
+}
+
+/// Rebuild a DataRowRecord from the caller's parts.
+unsafe fn record_to_drr(record: &AsherahDataRowRecord) -> Result<DataRowRecord, c_int> {
+    let data = input(record.data.data, record.data.len, "record data")?;
+    let key = input(record.key.data, record.key.len, "record key")?;
+    let id = input(
+        record.parent_key_id.data,
+        record.parent_key_id.len,
+        "record parent_key_id",
+    )?;
+    let Ok(id) = std::str::from_utf8(id) else {
+        set_error(
+            ErrorCode::InvalidDataRowRecord,
+            "record parent_key_id is not UTF-8",
+        );
+        return Err(-1);
+    };
+    if let Err(e) = asherah::limits::check_ciphertext_len(data.len()) {
+        set_error(ErrorCode::of(&e), e.to_string());
+        return Err(-1);
+    }
+    Ok(DataRowRecord {
+        key: Some(EnvelopeKeyRecord {
+            revoked: None,
+            id: String::new(),
+            created: record.created,
+            encrypted_key: key.to_vec(),
+            parent_key_meta: Some(KeyMeta {
+                id: id.to_string(),
+                created: record.parent_key_created,
+            }),
+        }),
+        data: data.to_vec(),
+    })
+}
+
+/// Decrypt a record given as parts into a library-allocated buffer. Release
+/// it with `asherah_buffer_free`.
+///
+/// # Safety
+/// `session` must be valid, `record` must be non-null with each buffer
+/// referencing `len` bytes, and `out` must be non-null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_decrypt_from_record(
+    session: *mut SharedSession,
+    record: *const AsherahDataRowRecord,
+    out: *mut AsherahBuffer,
+) -> c_int {
+    ffi_call("asherah_decrypt_from_record", || {
+        let run = || -> Result<c_int, c_int> {
+            let session = session_ref(session)?;
+            let (Some(record), false) = (record.as_ref(), out.is_null()) else {
+                set_error(ErrorCode::InvalidArgument, "null record or output buffer");
+                return Err(-1);
+            };
+            let drr = record_to_drr(record)?;
+            let mut plaintext = decrypt(session, drr, "decrypt_from_record")?;
+            Ok(take_vec_into_buffer(std::mem::take(&mut *plaintext), out))
+        };
+        run().unwrap_or_else(|status| status)
+    })
+}
+
+/// Decrypt a record given as parts into the caller's `out`. See the module
+/// docs for `out`, `out_capacity` and `out_len`; the size query is exact.
+///
+/// # Safety
+/// `session` must be valid, `record` must be non-null with each buffer
+/// referencing `len` bytes, `out` must be null or reference `out_capacity`
+/// writable bytes, and `out_len` must be non-null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_decrypt_from_record_into(
+    session: *mut SharedSession,
+    record: *const AsherahDataRowRecord,
+    out: *mut u8,
+    out_capacity: usize,
+    out_len: *mut usize,
+) -> c_int {
+    ffi_call("asherah_decrypt_from_record_into", || {
+        let run = || -> Result<c_int, c_int> {
+            let session = session_ref(session)?;
+            let (Some(record), false) = (record.as_ref(), out_len.is_null()) else {
+                set_error(ErrorCode::InvalidArgument, "null record or out_len");
+                return Err(-1);
+            };
+            let needed = record.data.len.saturating_sub(AEAD_OVERHEAD);
+            if out.is_null() {
+                *out_len = needed;
+                return Ok(0);
+            }
+            if needed > out_capacity {
+                *out_len = needed;
+                return Ok(ASHERAH_BUFFER_TOO_SMALL);
+            }
+            let drr = record_to_drr(record)?;
+            let plaintext = decrypt(session, drr, "decrypt_from_record_into")?;
+            Ok(write_out(&plaintext, out, out_capacity, out_len))
+        };
+        run().unwrap_or_else(|status| status)
+    })
+}
+
+/// Release the buffers of a record from `asherah_encrypt_to_record` and
+/// reset it. Do not pass a record whose buffers the caller owns.
+///
+/// # Safety
+/// `record` must be null or point to a record filled by
+/// `asherah_encrypt_to_record`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_record_free(record: *mut AsherahDataRowRecord) {
+    let Some(record) = record.as_mut() else {
+        return;
+    };
+    asherah_buffer_free(&mut record.data);
+    asherah_buffer_free(&mut record.key);
+    asherah_buffer_free(&mut record.parent_key_id);
+    record.created = 0;
+    record.parent_key_created = 0;
+}
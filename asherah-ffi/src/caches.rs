@@ -0,0 +1,114 @@
+//! Session and key cache introspection and control for a factory.
+//!
+//! Statistics are racy under concurrent use: fit for monitoring, not for
+//! decisions.
+
+use std::os::raw::{c_char, c_int};
+
+use asherah::error::ErrorCode;
+
+use crate::buffers::ffi_call;
+use crate::{cstr_to_str, set_error, set_error_sanitized, AsherahFactory};
+
+/// A snapshot of a factory's caches, from `asherah_factory_cache_stats`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsherahCacheStats {
+    /// Sessions in the session cache; 0 with session caching disabled.
+    pub sessions: u64,
+    /// `asherah_factory_get_session` calls served from the session cache.
+    pub session_cache_hits: u64,
+    /// `asherah_factory_get_session` calls that built a session despite
+    /// session caching.
+    pub session_cache_misses: u64,
+    /// Entries in the shared intermediate key cache.
+    pub intermediate_keys: u64,
+    /// Entries in the system key cache.
+    pub system_keys: u64,
+}
+
+unsafe fn factory_ref<'factory>(
+    factory: *mut AsherahFactory,
+) -> Result<&'factory AsherahFactory, c_int> {
+    factory.as_ref().ok_or_else(|| {
+        set_error(ErrorCode::InvalidArgument, "null factory");
+        -1
+    })
+}
+
+/// Fill `*out` with the sizes and hit counts of `factory`'s caches.
+///
+/// # Safety
+/// `factory` must be a valid factory pointer and `out` non-null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_factory_cache_stats(
+    factory: *mut AsherahFactory,
+    out: *mut AsherahCacheStats,
+) -> c_int {
+    ffi_call("asherah_factory_cache_stats", || {
+        let factory = match factory_ref(factory) {
+            Ok(f) => f,
+            Err(status) => return status,
+        };
+        let Some(out) = out.as_mut() else {
+            set_error(ErrorCode::InvalidArgument, "null output stats");
+            return -1;
+        };
+        let stats = factory.inner.cache_stats();
+        *out = AsherahCacheStats {
+            sessions: stats.sessions as u64,
+            session_cache_hits: stats.session_cache_hits,
+            session_cache_misses: stats.session_cache_misses,
+            intermediate_keys: stats.intermediate_keys as u64,
+            system_keys: stats.system_keys as u64,
+        };
+        0
+    })
+}
+
+/// Drop `partition_id`'s cached session, so the next
+/// `asherah_factory_get_session` for it builds a fresh one. Sessions already
+/// handed out are unaffected. Returns 1 if a session was cached, 0 if not,
+/// and -1 on error.
+///
+/// # Safety
+/// `factory` must be a valid factory pointer and `partition_id` a valid C
+/// string.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_factory_evict_session(
+    factory: *mut AsherahFactory,
+    partition_id: *const c_char,
+) -> c_int {
+    ffi_call("asherah_factory_evict_session", || {
+        let factory = match factory_ref(factory) {
+            Ok(f) => f,
+            Err(status) => return status,
+        };
+        match cstr_to_str(partition_id) {
+            Ok(pid) => c_int::from(factory.inner.evict_session(pid)),
+            Err(e) => {
+                set_error_sanitized("factory_evict_session", &e);
+                -1
+            }
+        }
+    })
+}
+
+/// Empty `factory`'s session cache and its shared system and intermediate
+/// key caches, so every key is loaded from the metastore again: after a key
+/// revocation, or to release memory.
+///
+/// # Safety
+/// `factory` must be a valid factory pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_factory_flush_caches(factory: *mut AsherahFactory) -> c_int {
+    ffi_call("asherah_factory_flush_caches", || {
+        match factory_ref(factory) {
+            Ok(f) => {
+                f.inner.flush_caches();
+                0
+            }
+            Err(status) => status,
+        }
+    })
+}
@@ -0,0 +1,86 @@
+//! C ABI for Asherah's stable error codes.
+//!
+//! Every entry point that reports an error through
+//! `asherah_last_error_message` also records an `ASHERAH_ERR_*` code, read
+//! with `asherah_last_error_code`. Branch on the code rather than matching
+//! message text: the values are stable across releases and shared with
+//! cobhan and the language bindings (see `asherah::error::ErrorCode`).
+//!
+//! Async completion callbacks receive only a message, so before invoking a
+//! callback with an error the worker thread records the code as well;
+//! calling `asherah_last_error_code` inside the callback returns it.
+
+use std::ffi::CString;
+use std::os::raw::{c_char, c_int};
+use std::sync::OnceLock;
+
+use asherah::error::ErrorCode;
+
+pub const ASHERAH_ERR_NONE: c_int = 0;
+pub const ASHERAH_ERR_UNKNOWN: c_int = ErrorCode::Unknown.as_i32();
+pub const ASHERAH_ERR_INTERNAL: c_int = ErrorCode::Internal.as_i32();
+pub const ASHERAH_ERR_INVALID_ARGUMENT: c_int = ErrorCode::InvalidArgument.as_i32();
+pub const ASHERAH_ERR_PAYLOAD_TOO_LARGE: c_int = ErrorCode::PayloadTooLarge.as_i32();
+pub const ASHERAH_ERR_INVALID_DATA_ROW_RECORD: c_int = ErrorCode::InvalidDataRowRecord.as_i32();
+pub const ASHERAH_ERR_PARTITION_MISMATCH: c_int = ErrorCode::PartitionMismatch.as_i32();
+pub const ASHERAH_ERR_INVALID_CONFIG: c_int = ErrorCode::InvalidConfig.as_i32();
+pub const ASHERAH_ERR_NOT_INITIALIZED: c_int = ErrorCode::NotInitialized.as_i32();
+pub const ASHERAH_ERR_ALREADY_INITIALIZED: c_int = ErrorCode::AlreadyInitialized.as_i32();
+pub const ASHERAH_ERR_CLOSED: c_int = ErrorCode::Closed.as_i32();
+pub const ASHERAH_ERR_CANCELLED: c_int = ErrorCode::Cancelled.as_i32();
+pub const ASHERAH_ERR_DEADLINE_EXCEEDED: c_int = ErrorCode::DeadlineExceeded.as_i32();
+pub const ASHERAH_ERR_KEY_NOT_FOUND: c_int = ErrorCode::KeyNotFound.as_i32();
+pub const ASHERAH_ERR_METASTORE_UNAVAILABLE: c_int = ErrorCode::MetastoreUnavailable.as_i32();
+pub const ASHERAH_ERR_KMS_UNAVAILABLE: c_int = ErrorCode::KmsUnavailable.as_i32();
+pub const ASHERAH_ERR_KMS_ACCESS_DENIED: c_int = ErrorCode::KmsAccessDenied.as_i32();
+pub const ASHERAH_ERR_DECRYPTION_FAILED: c_int = ErrorCode::DecryptionFailed.as_i32();
+
+/// NUL-terminated code and category names, built once so the pointers
+/// handed out stay valid for the life of the process.
+struct Names {
+    code: CString,
+    category: CString,
+}
+
+fn names(code: c_int) -> Option<&'static Names> {
+    static NAMES: OnceLock<Vec<(c_int, Names)>> = OnceLock::new();
+    let table = NAMES.get_or_init(|| {
+        ErrorCode::ALL
+            .iter()
+            .map(|code| {
+                let names = Names {
+                    code: CString::new(code.name()).expect("code names have no NUL"),
+                    category: CString::new(code.category().name())
+                        .expect("category names have no NUL"),
+                };
+                (code.as_i32(), names)
+            })
+            .collect()
+    });
+    table
+        .iter()
+        .find(|(value, _)| *value == code)
+        .map(|(_, names)| names)
+}
+
+/// The name of an `ASHERAH_ERR_*` code, e.g. `"KMS_ACCESS_DENIED"`, or null
+/// for a value that is not a code. The string is static; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn asherah_error_code_name(code: c_int) -> *const c_char {
+    names(code).map_or(std::ptr::null(), |names| names.code.as_ptr())
+}
+
+/// The category of an `ASHERAH_ERR_*` code (`"INPUT"`, `"CONFIG"`, `"STATE"`,
+/// `"KEY"`, `"METASTORE"`, `"KMS"`, `"CRYPTO"` or `"INTERNAL"`), or null for
+/// a value that is not a code. The string is static; do not free it.
+#[unsafe(no_mangle)]
+pub extern "C" fn asherah_error_code_category(code: c_int) -> *const c_char {
+    names(code).map_or(std::ptr::null(), |names| names.category.as_ptr())
+}
+
+/// 1 if an operation that failed with `code` may succeed when retried later
+/// (the metastore or KMS was unavailable), otherwise 0.
+#[unsafe(no_mangle)]
+pub extern "C" fn asherah_error_code_is_retryable(code: c_int) -> c_int {
+    ErrorCode::from_i32(code).is_some_and(ErrorCode::is_retryable) as c_int
+}
@@ -12,6 +12,40 @@
 #[allow(unused_extern_crates)]
 extern crate asherah_cobhan;
 
+mod backends;
+pub use backends::{
+    asherah_backend_output_set, asherah_kms_free, asherah_kms_new, asherah_metastore_free,
+    asherah_metastore_new, AsherahBackendOutput, AsherahKms, AsherahKmsFn, AsherahKmsVTable,
+    AsherahMetastore, AsherahMetastoreLoadFn, AsherahMetastoreLoadLatestFn,
+    AsherahMetastoreStoreFn, AsherahMetastoreVTable, AsherahReleaseFn, ASHERAH_BACKEND_DUPLICATE,
+    ASHERAH_BACKEND_ERROR, ASHERAH_BACKEND_FOUND, ASHERAH_BACKEND_NOT_FOUND, ASHERAH_BACKEND_OK,
+    ASHERAH_BACKEND_STORED,
+};
+mod buffers;
+pub use buffers::{
+    asherah_decrypt_from_json_into, asherah_decrypt_from_record, asherah_decrypt_from_record_into,
+    asherah_encrypt_to_json_into, asherah_encrypt_to_record, asherah_encrypt_to_record_into,
+    asherah_record_free, AsherahDataRowRecord, ASHERAH_BUFFER_TOO_SMALL,
+};
+mod caches;
+pub use caches::{
+    asherah_factory_cache_stats, asherah_factory_evict_session, asherah_factory_flush_caches,
+    AsherahCacheStats,
+};
+mod errors;
+pub use errors::{
+    asherah_error_code_category, asherah_error_code_is_retryable, asherah_error_code_name,
+    ASHERAH_ERR_ALREADY_INITIALIZED, ASHERAH_ERR_CANCELLED, ASHERAH_ERR_CLOSED,
+    ASHERAH_ERR_DEADLINE_EXCEEDED, ASHERAH_ERR_DECRYPTION_FAILED, ASHERAH_ERR_INTERNAL,
+    ASHERAH_ERR_INVALID_ARGUMENT, ASHERAH_ERR_INVALID_CONFIG, ASHERAH_ERR_INVALID_DATA_ROW_RECORD,
+    ASHERAH_ERR_KEY_NOT_FOUND, ASHERAH_ERR_KMS_ACCESS_DENIED, ASHERAH_ERR_KMS_UNAVAILABLE,
+    ASHERAH_ERR_METASTORE_UNAVAILABLE, ASHERAH_ERR_NONE, ASHERAH_ERR_NOT_INITIALIZED,
+    ASHERAH_ERR_PARTITION_MISMATCH, ASHERAH_ERR_PAYLOAD_TOO_LARGE, ASHERAH_ERR_UNKNOWN,
+};
+mod operations;
+pub use operations::{
+    asherah_cancel, asherah_operation_free, AsherahOperation, ASHERAH_OPERATION_FINISHED,
+};
 mod hooks;
 pub use hooks::{
     asherah_clear_log_hook, asherah_clear_metrics_hook, asherah_log_dropped_count,
@@ -31,8 +65,10 @@ use std::os::raw::{c_char, c_int, c_void};
 use std::ptr::null_mut;
 use std::sync::Arc;
 
+use ael::error::{ErrorCode, WithErrorCode};
 use asherah as ael;
 use asherah_config as config;
+use operations::Control;
 
 type Factory = ael::session::PublicFactory<
     ael::aead::AES256GCM,
@@ -87,9 +123,11 @@ impl fmt::Debug for SharedSession {
 
 thread_local! {
     static LAST_ERROR: std::cell::RefCell<Option<CString>> = const { std::cell::RefCell::new(None) };
+    static LAST_ERROR_CODE: std::cell::Cell<c_int> = const { std::cell::Cell::new(0) };
 }
 
-fn set_error(msg: impl Into<String>) {
+fn set_error(code: ErrorCode, msg: impl Into<String>) {
+    LAST_ERROR_CODE.with(|c| c.set(code.as_i32()));
     LAST_ERROR.with(|c| {
         let message = msg.into();
         let cstring =
@@ -116,7 +154,7 @@ fn set_error(msg: impl Into<String>) {
 /// unify the two paths without re-checking that.
 fn set_error_sanitized(op: &str, err: &anyhow::Error) {
     log::warn!("{op} failed: {err:#}");
-    set_error(format!("{op} failed: {err}"));
+    set_error(ErrorCode::of(err), format!("{op} failed: {err}"));
 }
 
 #[unsafe(no_mangle)]
@@ -134,6 +172,13 @@ pub extern "C" fn asherah_last_error_message() -> *const c_char {
     }
 }
 
+/// The `ASHERAH_ERR_*` code of the error `asherah_last_error_message`
+/// describes, or `ASHERAH_ERR_NONE` if no call on this thread has failed.
+#[unsafe(no_mangle)]
+pub extern "C" fn asherah_last_error_code() -> c_int {
+    LAST_ERROR_CODE.with(std::cell::Cell::get)
+}
+
 #[unsafe(no_mangle)]
 pub extern "C" fn asherah_factory_new_from_env() -> *mut AsherahFactory {
     match std::panic::catch_unwind(|| match ael::builders::factory_from_env() {
@@ -152,7 +197,10 @@ pub extern "C" fn asherah_factory_new_from_env() -> *mut AsherahFactory {
     }) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_factory_new_from_env");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_factory_new_from_env",
+            );
             null_mut()
         }
     }
@@ -181,7 +229,10 @@ pub unsafe extern "C" fn asherah_apply_config_json(config_json: *const c_char) -
     )) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_apply_config_json");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_apply_config_json",
+            );
             -1
         }
     }
@@ -208,7 +259,54 @@ pub unsafe extern "C" fn asherah_factory_new_with_config(
     )) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_factory_new_with_config");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_factory_new_with_config",
+            );
+            null_mut()
+        }
+    }
+}
+
+/// Build a factory whose metastore and/or KMS are implemented by the host
+/// (see `backends.rs`). Either handle may be null to use the backend named
+/// in `config_json` instead. The factory keeps the backends alive, so the
+/// handles may be freed once this returns.
+///
+/// # Safety
+/// `config_json` must point to a valid, null-terminated UTF-8 string, and
+/// `metastore` / `kms` must each be a live handle from this module or null.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_factory_new_with_backends(
+    config_json: *const c_char,
+    metastore: *const AsherahMetastore,
+    kms: *const AsherahKms,
+) -> *mut AsherahFactory {
+    match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let factory = cstr_to_str(config_json)
+            .and_then(config::ConfigOptions::from_json)
+            .and_then(|cfg| {
+                let backends = backends::host_backends(metastore, kms);
+                config::factory_from_config_with_backends(&cfg, &backends)
+            });
+        match factory {
+            // Always enable per-factory metrics — see comment in
+            // asherah_factory_new_from_env.
+            Ok((factory, _applied)) => Box::into_raw(Box::new(AsherahFactory {
+                inner: factory.with_metrics(true),
+            })),
+            Err(e) => {
+                set_error_sanitized("factory_new_with_backends", &e);
+                null_mut()
+            }
+        }
+    })) {
+        Ok(result) => result,
+        Err(_) => {
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_factory_new_with_backends",
+            );
             null_mut()
         }
     }
@@ -233,9 +331,9 @@ pub unsafe extern "C" fn asherah_factory_free(ptr: *mut AsherahFactory) {
 /// returned reference's lifetime.
 unsafe fn cstr_to_str<'ptr>(s: *const c_char) -> Result<&'ptr str, anyhow::Error> {
     if s.is_null() {
-        return Err(anyhow::anyhow!("null string"));
+        return Err(ErrorCode::InvalidArgument.error("null string"));
     }
-    Ok(CStr::from_ptr(s).to_str()?)
+    CStr::from_ptr(s).to_str().code(ErrorCode::InvalidArgument)
 }
 
 /// # Safety
@@ -247,7 +345,7 @@ pub unsafe extern "C" fn asherah_factory_get_session(
 ) -> *mut SharedSession {
     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         if factory.is_null() {
-            set_error("null factory");
+            set_error(ErrorCode::InvalidArgument, "null factory");
             return null_mut();
         }
         let f = &*factory;
@@ -266,7 +364,10 @@ pub unsafe extern "C" fn asherah_factory_get_session(
     })) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_factory_get_session");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_factory_get_session",
+            );
             null_mut()
         }
     }
@@ -290,7 +391,7 @@ pub unsafe extern "C" fn asherah_session_free(ptr: *mut SharedSession) {
 
 fn take_vec_into_buffer(v: Vec<u8>, out: *mut AsherahBuffer) -> c_int {
     if out.is_null() {
-        set_error("null output buffer");
+        set_error(ErrorCode::InvalidArgument, "null output buffer");
         return -1;
     }
     let mut v = ManuallyDrop::new(v);
@@ -341,15 +442,15 @@ pub unsafe extern "C" fn asherah_encrypt_to_json(
 ) -> c_int {
     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         if session.is_null() {
-            set_error("null session");
+            set_error(ErrorCode::InvalidArgument, "null session");
             return -1;
         }
         if data.is_null() && len > 0 {
-            set_error("null data");
+            set_error(ErrorCode::InvalidArgument, "null data");
             return -1;
         }
         if let Err(e) = asherah::limits::check_plaintext_len(len) {
-            set_error(e.to_string());
+            set_error(ErrorCode::of(&e), e.to_string());
             return -1;
         }
         let s = &(*session).session;
@@ -371,7 +472,10 @@ pub unsafe extern "C" fn asherah_encrypt_to_json(
     })) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_encrypt_to_json");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_encrypt_to_json",
+            );
             -1
         }
     }
@@ -388,15 +492,15 @@ pub unsafe extern "C" fn asherah_decrypt_from_json(
 ) -> c_int {
     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
         if session.is_null() {
-            set_error("null session");
+            set_error(ErrorCode::InvalidArgument, "null session");
             return -1;
         }
         if json.is_null() && len > 0 {
-            set_error("null json");
+            set_error(ErrorCode::InvalidArgument, "null json");
             return -1;
         }
         if let Err(e) = asherah::limits::check_ciphertext_len(len) {
-            set_error(e.to_string());
+            set_error(ErrorCode::of(&e), e.to_string());
             return -1;
         }
         let s = &(*session).session;
@@ -417,7 +521,10 @@ pub unsafe extern "C" fn asherah_decrypt_from_json(
                 // serde_json's Display already includes the offending
                 // input snippet ("at line N column M ..."); strip the
                 // chain so the user-facing message stays minimal.
-                set_error(format!("decrypt_from_json: invalid JSON: {e}"));
+                set_error(
+                    ErrorCode::InvalidDataRowRecord,
+                    format!("decrypt_from_json: invalid JSON: {e}"),
+                );
                 log::warn!("decrypt_from_json: invalid JSON: {e:#}");
                 -1
             }
@@ -425,7 +532,10 @@ pub unsafe extern "C" fn asherah_decrypt_from_json(
     })) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_decrypt_from_json");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_decrypt_from_json",
+            );
             -1
         }
     }
@@ -506,6 +616,7 @@ fn try_async_rt() -> Result<&'static tokio::runtime::Runtime, std::io::Error> {
 /// - `user_data`: opaque pointer passed through from the async call.
 /// - `result_data`/`result_len`: output bytes on success (NULL/0 on error).
 /// - `error_message`: null-terminated UTF-8 error string on failure (NULL on success).
+///   `asherah_last_error_code` called inside the callback returns its code.
 ///
 /// The callback runs on a tokio worker thread. Do not block in the callback.
 /// The result buffer is freed after the callback returns — copy it if needed.
@@ -529,18 +640,49 @@ pub unsafe extern "C" fn asherah_encrypt_to_json_async(
     len: usize,
     callback: AsherahCompletionFn,
     user_data: *mut c_void,
+) -> c_int {
+    asherah_encrypt_to_json_async_cancellable(
+        session,
+        data,
+        len,
+        0,
+        callback,
+        user_data,
+        null_mut(),
+    )
+}
+
+/// Like `asherah_encrypt_to_json_async`, but the operation can be ended
+/// early (see `src/operations.rs`). `timeout_ms` bounds it (0 for no
+/// timeout). If `out_op` is non-null it receives a handle for
+/// `asherah_cancel`, to be released with `asherah_operation_free`; on
+/// failure it is set to null and the callback never fires.
+///
+/// # Safety
+/// As for `asherah_encrypt_to_json_async`; `out_op` must be null or valid
+/// for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_encrypt_to_json_async_cancellable(
+    session: *mut SharedSession,
+    data: *const u8,
+    len: usize,
+    timeout_ms: u64,
+    callback: AsherahCompletionFn,
+    user_data: *mut c_void,
+    out_op: *mut *mut AsherahOperation,
 ) -> c_int {
     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        operations::clear_handle(out_op);
         if session.is_null() {
-            set_error("null session");
+            set_error(ErrorCode::InvalidArgument, "null session");
             return -1;
         }
         if data.is_null() && len > 0 {
-            set_error("null data");
+            set_error(ErrorCode::InvalidArgument, "null data");
             return -1;
         }
         if let Err(e) = asherah::limits::check_plaintext_len(len) {
-            set_error(e.to_string());
+            set_error(ErrorCode::of(&e), e.to_string());
             return -1;
         }
         // Clone the Arc so the session outlives a premature free.
@@ -551,34 +693,69 @@ pub unsafe extern "C" fn asherah_encrypt_to_json_async(
         } else {
             std::slice::from_raw_parts(data, len).to_vec()
         };
-        spawn_encrypt_async(AsyncContext::new(arc, callback, user_data), input);
+        let control = Control::new(timeout_ms, out_op);
+        spawn_encrypt_async(AsyncContext::new(arc, callback, user_data), input, control);
         0
     })) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_encrypt_to_json_async");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_encrypt_to_json_async",
+            );
             -1
         }
     }
 }
 
-fn spawn_encrypt_async(ctx: AsyncContext, input: Vec<u8>) {
-    let rt = match try_async_rt() {
-        Ok(rt) => rt,
+/// Report a failed async operation: record `code` on this thread for
+/// `asherah_last_error_code`, then invoke the callback with `message`.
+fn fail_async(
+    callback: AsherahCompletionFn,
+    user_data: usize,
+    code: ErrorCode,
+    message: String,
+    fallback: &CStr,
+) {
+    set_error(code, message.clone());
+    let msg = CString::new(message).unwrap_or_else(|_| fallback.into());
+    unsafe { callback(user_data as *mut c_void, std::ptr::null(), 0, msg.as_ptr()) };
+}
+
+/// The async runtime, or `None` after failing `ctx` with the reason.
+fn async_rt_or_fail(
+    ctx: &AsyncContext,
+    control: &Control,
+) -> Option<&'static tokio::runtime::Runtime> {
+    match try_async_rt() {
+        Ok(rt) => Some(rt),
         Err(e) => {
             // Synthesize a callback failure: the user expected exactly one
             // callback per async call, so signal the runtime-init error
             // through the same channel rather than dropping the request.
+            control.finish();
             let (cb, ud) = unsafe { ctx.restore_callback() };
-            let msg = CString::new(format!("async runtime init failed: {e}"))
-                .unwrap_or_else(|_| c"async runtime init failed".into());
-            unsafe { cb(ud as *mut c_void, std::ptr::null(), 0, msg.as_ptr()) };
-            return;
+            fail_async(
+                cb,
+                ud,
+                ErrorCode::Internal,
+                format!("async runtime init failed: {e}"),
+                c"async runtime init failed",
+            );
+            None
         }
+    }
+}
+
+fn spawn_encrypt_async(ctx: AsyncContext, input: Vec<u8>, control: Control) {
+    let Some(rt) = async_rt_or_fail(&ctx, &control) else {
+        return;
     };
     rt.spawn(async move {
         let (cb, ud) = unsafe { ctx.restore_callback() };
-        match ctx.session.inner.encrypt_async(&input).await {
+        let session = Arc::clone(&ctx.session);
+        let work = async move { session.inner.encrypt_async(&input).await };
+        match control.run(work).await {
             Ok(drr) => {
                 let json = drr.to_json_fast();
                 let bytes = json.as_bytes();
@@ -591,55 +768,55 @@ fn spawn_encrypt_async(ctx: AsyncContext, input: Vec<u8>) {
                     )
                 };
             }
-            Err(e) => {
-                let msg =
-                    CString::new(e.to_string()).unwrap_or_else(|_| c"async encrypt error".into());
-                unsafe { cb(ud as *mut c_void, std::ptr::null(), 0, msg.as_ptr()) };
-            }
+            Err(e) => fail_async(
+                cb,
+                ud,
+                ErrorCode::of(&e),
+                e.to_string(),
+                c"async encrypt error",
+            ),
         }
     });
 }
 
-fn spawn_decrypt_async(ctx: AsyncContext, input: Vec<u8>) {
-    let rt = match try_async_rt() {
-        Ok(rt) => rt,
-        Err(e) => {
-            let (cb, ud) = unsafe { ctx.restore_callback() };
-            let msg = CString::new(format!("async runtime init failed: {e}"))
-                .unwrap_or_else(|_| c"async runtime init failed".into());
-            unsafe { cb(ud as *mut c_void, std::ptr::null(), 0, msg.as_ptr()) };
-            return;
-        }
+fn spawn_decrypt_async(ctx: AsyncContext, input: Vec<u8>, control: Control) {
+    let Some(rt) = async_rt_or_fail(&ctx, &control) else {
+        return;
     };
     rt.spawn(async move {
         let (cb, ud) = unsafe { ctx.restore_callback() };
-        let drr = match serde_json::from_slice::<ael::types::DataRowRecord>(&input) {
-            Ok(d) => d,
-            Err(e) => {
-                let msg = CString::new(format!("invalid DataRowRecord JSON: {e}"))
-                    .unwrap_or_else(|_| c"json parse error".into());
-                unsafe { cb(ud as *mut c_void, std::ptr::null(), 0, msg.as_ptr()) };
-                return;
-            }
+        let session = Arc::clone(&ctx.session);
+        let work = async move {
+            let drr = serde_json::from_slice::<ael::types::DataRowRecord>(&input).map_err(|e| {
+                ErrorCode::InvalidDataRowRecord
+                    .error(format_args!("invalid DataRowRecord JSON: {e}"))
+            })?;
+            // Wrapped so a result dropped by a late cancel is wiped too.
+            session
+                .inner
+                .decrypt_async(drr)
+                .await
+                .map(zeroize::Zeroizing::new)
         };
-        match ctx.session.inner.decrypt_async(drr).await {
-            Ok(mut pt) => {
-                // Hand the plaintext to the language binding, then wipe our
-                // copy. The callback runs synchronously here so the binding
-                // has already copied the bytes by the time `cb(...)` returns.
-                // Without the wipe, the freed Vec leaves plaintext in the
-                // heap allocator until the slot is reused (the sync path was
-                // fixed in PR #216; this is the async-path variant noted in
+        match control.run(work).await {
+            Ok(pt) => {
+                // Hand the plaintext to the language binding; `Zeroizing`
+                // wipes our copy when it drops. The callback runs
+                // synchronously here so the binding has already copied the
+                // bytes by the time `cb(...)` returns. Without the wipe, the
+                // freed Vec leaves plaintext in the heap allocator until the
+                // slot is reused (the sync path was fixed in PR #216; this is
+                // the async-path variant noted in
                 // docs/review-2026-05-05-findings.md).
                 unsafe { cb(ud as *mut c_void, pt.as_ptr(), pt.len(), std::ptr::null()) };
-                use zeroize::Zeroize;
-                pt.zeroize();
-            }
-            Err(e) => {
-                let msg =
-                    CString::new(e.to_string()).unwrap_or_else(|_| c"async decrypt error".into());
-                unsafe { cb(ud as *mut c_void, std::ptr::null(), 0, msg.as_ptr()) };
             }
+            Err(e) => fail_async(
+                cb,
+                ud,
+                ErrorCode::of(&e),
+                e.to_string(),
+                c"async decrypt error",
+            ),
         }
     });
 }
@@ -655,18 +832,46 @@ pub unsafe extern "C" fn asherah_decrypt_from_json_async(
     len: usize,
     callback: AsherahCompletionFn,
     user_data: *mut c_void,
+) -> c_int {
+    asherah_decrypt_from_json_async_cancellable(
+        session,
+        json,
+        len,
+        0,
+        callback,
+        user_data,
+        null_mut(),
+    )
+}
+
+/// Like `asherah_decrypt_from_json_async`, with a timeout and an optional
+/// cancel handle as for `asherah_encrypt_to_json_async_cancellable`.
+///
+/// # Safety
+/// As for `asherah_decrypt_from_json_async`; `out_op` must be null or valid
+/// for a write.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_decrypt_from_json_async_cancellable(
+    session: *mut SharedSession,
+    json: *const u8,
+    len: usize,
+    timeout_ms: u64,
+    callback: AsherahCompletionFn,
+    user_data: *mut c_void,
+    out_op: *mut *mut AsherahOperation,
 ) -> c_int {
     match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        operations::clear_handle(out_op);
         if session.is_null() {
-            set_error("null session");
+            set_error(ErrorCode::InvalidArgument, "null session");
             return -1;
         }
         if json.is_null() && len > 0 {
-            set_error("null json");
+            set_error(ErrorCode::InvalidArgument, "null json");
             return -1;
         }
         if let Err(e) = asherah::limits::check_ciphertext_len(len) {
-            set_error(e.to_string());
+            set_error(ErrorCode::of(&e), e.to_string());
             return -1;
         }
         let arc = Arc::clone(&(*session).session);
@@ -675,12 +880,16 @@ pub unsafe extern "C" fn asherah_decrypt_from_json_async(
         } else {
             std::slice::from_raw_parts(json, len).to_vec()
         };
-        spawn_decrypt_async(AsyncContext::new(arc, callback, user_data), input);
+        let control = Control::new(timeout_ms, out_op);
+        spawn_decrypt_async(AsyncContext::new(arc, callback, user_data), input, control);
         0
     })) {
         Ok(result) => result,
         Err(_) => {
-            set_error("internal panic in asherah_decrypt_from_json_async");
+            set_error(
+                ErrorCode::Internal,
+                "internal panic in asherah_decrypt_from_json_async",
+            );
             -1
         }
     }
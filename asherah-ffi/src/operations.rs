@@ -0,0 +1,189 @@
+//! Cancellation and deadlines for the async encrypt/decrypt calls.
+//!
+//! The `*_async_cancellable` variants take a timeout and hand back an
+//! `AsherahOperation` handle. Whatever ends the operation first — the work
+//! finishing, `asherah_cancel`, or the timeout — decides what the callback
+//! reports, and the callback still fires exactly once:
+//!
+//! - cancelled: an error with code `ASHERAH_ERR_CANCELLED`
+//! - timed out: an error with code `ASHERAH_ERR_DEADLINE_EXCEEDED`
+//!
+//! The callback fires as soon as the operation is cancelled or times out,
+//! even if a backend is hung. The work itself runs on tokio's blocking pool,
+//! so a synchronous backend (the SQL metastores, host callbacks) stuck in a
+//! call cannot hold up the worker threads that watch for either. That call
+//! still runs to completion in the background and its result is discarded;
+//! a key the metastore already stored is simply used next time.
+
+use std::future::Future;
+use std::os::raw::c_int;
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use asherah::error::ErrorCode;
+use tokio::sync::Notify;
+
+use crate::set_error;
+
+/// Returned by `asherah_cancel` when the operation had already finished,
+/// timed out, or been cancelled; its callback is unaffected.
+pub const ASHERAH_OPERATION_FINISHED: c_int = 1;
+
+const RUNNING: u8 = 0;
+const FINISHED: u8 = 1;
+const CANCELLED: u8 = 2;
+
+#[derive(Debug, Default)]
+struct OperationState {
+    status: AtomicU8,
+    cancel: Notify,
+}
+
+/// Handle to an operation started by a `*_async_cancellable` call. Release
+/// it with `asherah_operation_free`, before or after the callback fires.
+#[derive(Debug)]
+pub struct AsherahOperation {
+    state: Arc<OperationState>,
+}
+
+/// How an async task may be ended early.
+#[derive(Debug, Default)]
+pub(crate) struct Control {
+    state: Option<Arc<OperationState>>,
+    timeout: Option<Duration>,
+}
+
+impl Control {
+    /// A control with the given timeout (0 for none). If `out_op` is
+    /// non-null, it receives a handle the caller can cancel through.
+    ///
+    /// # Safety
+    /// `out_op` must be null or valid for a write.
+    pub(crate) unsafe fn new(timeout_ms: u64, out_op: *mut *mut AsherahOperation) -> Self {
+        let state = (!out_op.is_null()).then(|| {
+            let state = Arc::new(OperationState::default());
+            *out_op = Box::into_raw(Box::new(AsherahOperation {
+                state: Arc::clone(&state),
+            }));
+            state
+        });
+        Self {
+            state,
+            timeout: (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)),
+        }
+    }
+
+    /// Run `work`, unless the operation is cancelled or times out first.
+    pub(crate) async fn run<T: Send + 'static>(
+        &self,
+        work: impl Future<Output = anyhow::Result<T>> + Send + 'static,
+    ) -> anyhow::Result<T> {
+        if self.state.is_none() && self.timeout.is_none() {
+            return work.await;
+        }
+        let cancelled = async {
+            match &self.state {
+                Some(state) => state.cancel.notified().await,
+                None => std::future::pending().await,
+            }
+        };
+        let deadline = async {
+            match self.timeout {
+                Some(timeout) => tokio::time::sleep(timeout).await,
+                None => std::future::pending().await,
+            }
+        };
+        let rt = tokio::runtime::Handle::current();
+        let task = tokio::task::spawn_blocking(move || rt.block_on(work));
+        let result = tokio::select! {
+            joined = task => joined.unwrap_or_else(|e| {
+                Err(ErrorCode::Internal.error(format_args!("async task failed: {e}")))
+            }),
+            () = cancelled => return Err(cancelled_error()),
+            () = deadline => Err(ErrorCode::DeadlineExceeded.error(format_args!(
+                "operation timed out after {} ms",
+                self.timeout.map_or(0, |t| t.as_millis())
+            ))),
+        };
+        if self.finish() {
+            result
+        } else {
+            Err(cancelled_error())
+        }
+    }
+
+    /// Mark the operation finished. False if a cancel got there first, in
+    /// which case the callback must report the cancellation instead.
+    pub(crate) fn finish(&self) -> bool {
+        self.state.as_ref().is_none_or(|state| {
+            state
+                .status
+                .compare_exchange(RUNNING, FINISHED, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok()
+        })
+    }
+}
+
+fn cancelled_error() -> anyhow::Error {
+    ErrorCode::Cancelled.error("operation cancelled")
+}
+
+/// Cancel an operation. Returns 0 if its callback will report
+/// `ASHERAH_ERR_CANCELLED`, or `ASHERAH_OPERATION_FINISHED` if it had
+/// already finished. Does not wait for the callback.
+///
+/// # Safety
+/// `op` must be a handle from a `*_async_cancellable` call that has not
+/// been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_cancel(op: *mut AsherahOperation) -> c_int {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let Some(op) = op.as_ref() else {
+            set_error(ErrorCode::InvalidArgument, "null operation");
+            return -1;
+        };
+        let state = &op.state;
+        if state
+            .status
+            .compare_exchange(RUNNING, CANCELLED, Ordering::AcqRel, Ordering::Acquire)
+            .is_err()
+        {
+            return ASHERAH_OPERATION_FINISHED;
+        }
+        // Stores a permit if the task has not started waiting yet.
+        state.cancel.notify_one();
+        0
+    }))
+    .unwrap_or_else(|_| {
+        set_error(ErrorCode::Internal, "internal panic in asherah_cancel");
+        -1
+    })
+}
+
+/// Release an operation handle. The operation itself carries on, and its
+/// callback still fires.
+///
+/// # Safety
+/// `op` must be null or a handle from a `*_async_cancellable` call that has
+/// not been freed.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn asherah_operation_free(op: *mut AsherahOperation) {
+    if op.is_null() {
+        return;
+    }
+    drop(std::panic::catch_unwind(std::panic::AssertUnwindSafe(
+        || drop(Box::from_raw(op)),
+    )));
+}
+
+/// Clear `out_op` before validation so a failed call leaves no handle.
+///
+/// # Safety
+/// `out_op` must be null or valid for a write.
+pub(crate) unsafe fn clear_handle(out_op: *mut *mut AsherahOperation) {
+    if !out_op.is_null() {
+        *out_op = null_mut();
+    }
+}
@@ -0,0 +1,344 @@
+//! Integration tests for host-implemented metastore and KMS vtables.
+//!
+//! The "host" here is plain Rust behind `extern "C"` callbacks: a
+//! `Mutex<BTreeMap>` metastore and an XOR "KMS", each reached only through
+//! `user_data`, the way a binding would wire its own objects.
+
+#![allow(unsafe_code, clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_void};
+use std::ptr::null;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+use asherah_ffi::{
+    asherah_backend_output_set, asherah_buffer_free, asherah_decrypt_from_json,
+    asherah_encrypt_to_json, asherah_factory_free, asherah_factory_get_session,
+    asherah_factory_new_with_backends, asherah_kms_free, asherah_kms_new, asherah_last_error_code,
+    asherah_last_error_message, asherah_metastore_free, asherah_metastore_new,
+    asherah_session_free, AsherahBackendOutput, AsherahBuffer, AsherahKmsVTable,
+    AsherahMetastoreVTable, ASHERAH_BACKEND_DUPLICATE, ASHERAH_BACKEND_ERROR,
+    ASHERAH_BACKEND_FOUND, ASHERAH_BACKEND_NOT_FOUND, ASHERAH_BACKEND_OK, ASHERAH_BACKEND_STORED,
+    ASHERAH_ERR_INVALID_ARGUMENT, ASHERAH_ERR_INVALID_CONFIG, ASHERAH_ERR_KMS_UNAVAILABLE,
+};
+
+#[derive(Default)]
+struct HostStore {
+    records: Mutex<BTreeMap<(String, i64), Vec<u8>>>,
+    stores: AtomicUsize,
+    released: AtomicBool,
+}
+
+struct HostKms {
+    key: u8,
+    fail: AtomicBool,
+    released: AtomicBool,
+}
+
+unsafe fn set(out: *mut AsherahBackendOutput, bytes: &[u8]) {
+    assert_eq!(
+        asherah_backend_output_set(out, bytes.as_ptr(), bytes.len()),
+        0
+    );
+}
+
+unsafe fn store_of<'host>(user_data: *mut c_void) -> &'host HostStore {
+    &*(user_data as *const HostStore)
+}
+
+unsafe extern "C" fn load(
+    user_data: *mut c_void,
+    id: *const c_char,
+    created: i64,
+    out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = store_of(user_data);
+    let id = CStr::from_ptr(id).to_str().unwrap().to_string();
+    match host.records.lock().unwrap().get(&(id, created)) {
+        Some(record) => {
+            set(out, record);
+            ASHERAH_BACKEND_FOUND
+        }
+        None => ASHERAH_BACKEND_NOT_FOUND,
+    }
+}
+
+unsafe extern "C" fn load_latest(
+    user_data: *mut c_void,
+    id: *const c_char,
+    out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = store_of(user_data);
+    let id = CStr::from_ptr(id).to_str().unwrap();
+    let records = host.records.lock().unwrap();
+    match records.iter().rev().find(|((key, _), _)| key == id) {
+        Some((_, record)) => {
+            set(out, record);
+            ASHERAH_BACKEND_FOUND
+        }
+        None => ASHERAH_BACKEND_NOT_FOUND,
+    }
+}
+
+unsafe extern "C" fn store(
+    user_data: *mut c_void,
+    id: *const c_char,
+    created: i64,
+    record: *const u8,
+    record_len: usize,
+    _out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = store_of(user_data);
+    host.stores.fetch_add(1, Ordering::Relaxed);
+    let id = CStr::from_ptr(id).to_str().unwrap().to_string();
+    let record = std::slice::from_raw_parts(record, record_len).to_vec();
+    let mut records = host.records.lock().unwrap();
+    if records.contains_key(&(id.clone(), created)) {
+        return ASHERAH_BACKEND_DUPLICATE;
+    }
+    records.insert((id, created), record);
+    ASHERAH_BACKEND_STORED
+}
+
+unsafe extern "C" fn release_store(user_data: *mut c_void) {
+    store_of(user_data).released.store(true, Ordering::SeqCst);
+}
+
+unsafe extern "C" fn xor(
+    user_data: *mut c_void,
+    data: *const u8,
+    len: usize,
+    out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = &*(user_data as *const HostKms);
+    if host.fail.load(Ordering::Relaxed) {
+        set(out, b"kms unavailable");
+        return ASHERAH_BACKEND_ERROR;
+    }
+    let bytes: Vec<u8> = std::slice::from_raw_parts(data, len)
+        .iter()
+        .map(|b| b ^ host.key)
+        .collect();
+    set(out, &bytes);
+    ASHERAH_BACKEND_OK
+}
+
+unsafe extern "C" fn release_kms(user_data: *mut c_void) {
+    (*(user_data as *const HostKms))
+        .released
+        .store(true, Ordering::SeqCst);
+}
+
+fn metastore_vtable(host: &HostStore) -> AsherahMetastoreVTable {
+    AsherahMetastoreVTable {
+        user_data: std::ptr::from_ref(host).cast_mut().cast(),
+        load: Some(load),
+        load_latest: Some(load_latest),
+        store: Some(store),
+        release: Some(release_store),
+    }
+}
+
+fn kms_vtable(host: &HostKms) -> AsherahKmsVTable {
+    AsherahKmsVTable {
+        user_data: std::ptr::from_ref(host).cast_mut().cast(),
+        encrypt_key: Some(xor),
+        decrypt_key: Some(xor),
+        release: Some(release_kms),
+    }
+}
+
+fn host_kms() -> HostKms {
+    HostKms {
+        key: 0x5a,
+        fail: AtomicBool::new(false),
+        released: AtomicBool::new(false),
+    }
+}
+
+fn config(extra: &str) -> CString {
+    CString::new(format!(
+        r#"{{"ServiceName":"svc","ProductID":"prod","EnableSessionCaching":false{extra}}}"#
+    ))
+    .unwrap()
+}
+
+fn last_error() -> String {
+    let ptr = asherah_last_error_message();
+    assert!(!ptr.is_null());
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string()
+}
+
+fn empty_buffer() -> AsherahBuffer {
+    AsherahBuffer {
+        data: std::ptr::null_mut(),
+        len: 0,
+        capacity: 0,
+    }
+}
+
+unsafe fn take(buf: &mut AsherahBuffer) -> Vec<u8> {
+    let bytes = std::slice::from_raw_parts(buf.data, buf.len).to_vec();
+    asherah_buffer_free(buf);
+    bytes
+}
+
+#[test]
+fn host_backends_round_trip_and_are_released() {
+    let host_store = HostStore::default();
+    let host_kms = host_kms();
+    let name = CString::new("orm-table").unwrap();
+    let kms_name = CString::new("corp-hsm").unwrap();
+    unsafe {
+        let metastore = asherah_metastore_new(name.as_ptr(), &metastore_vtable(&host_store));
+        let kms = asherah_kms_new(kms_name.as_ptr(), &kms_vtable(&host_kms));
+        assert!(!metastore.is_null() && !kms.is_null());
+
+        let factory = asherah_factory_new_with_backends(config("").as_ptr(), metastore, kms);
+        assert!(!factory.is_null(), "{}", last_error());
+        // The factory keeps the backends; the handles can go.
+        asherah_metastore_free(metastore);
+        asherah_kms_free(kms);
+        assert!(!host_store.released.load(Ordering::SeqCst));
+
+        let partition = CString::new("partition-1").unwrap();
+        let session = asherah_factory_get_session(factory, partition.as_ptr());
+        assert!(!session.is_null(), "{}", last_error());
+        let mut drr = empty_buffer();
+        let plaintext = b"hello host";
+        assert_eq!(
+            asherah_encrypt_to_json(session, plaintext.as_ptr(), plaintext.len(), &mut drr),
+            0,
+            "{}",
+            last_error()
+        );
+        let drr = take(&mut drr);
+        let mut decrypted = empty_buffer();
+        assert_eq!(
+            asherah_decrypt_from_json(session, drr.as_ptr(), drr.len(), &mut decrypted),
+            0,
+            "{}",
+            last_error()
+        );
+        assert_eq!(take(&mut decrypted), plaintext);
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+    }
+
+    // Drift guard, system key and intermediate key all went to the host.
+    let records = host_store.records.lock().unwrap();
+    let ids: Vec<&str> = records.keys().map(|(id, _)| id.as_str()).collect();
+    assert!(
+        ids.iter().any(|id| id.starts_with("_SK_svc_prod")),
+        "{ids:?}"
+    );
+    assert!(
+        ids.iter().any(|id| id.starts_with("_IK_partition-1")),
+        "{ids:?}"
+    );
+    let json: serde_json::Value = serde_json::from_slice(records.values().next().unwrap()).unwrap();
+    assert!(
+        json["Created"].is_i64() && json["Key"].is_string(),
+        "{json}"
+    );
+    drop(records);
+    assert!(host_store.stores.load(Ordering::Relaxed) >= 3);
+    assert!(host_store.released.load(Ordering::SeqCst));
+    assert!(host_kms.released.load(Ordering::SeqCst));
+}
+
+#[test]
+fn host_kms_errors_reach_the_caller() {
+    let host_store = HostStore::default();
+    let host_kms = host_kms();
+    let name = CString::new("kms").unwrap();
+    unsafe {
+        let kms = asherah_kms_new(name.as_ptr(), &kms_vtable(&host_kms));
+        host_kms.fail.store(true, Ordering::Relaxed);
+        // Metastore from config, KMS from the host.
+        let factory = asherah_factory_new_with_backends(
+            config(r#","Metastore":"memory""#).as_ptr(),
+            null(),
+            kms,
+        );
+        assert!(!factory.is_null(), "{}", last_error());
+        let partition = CString::new("p").unwrap();
+        let session = asherah_factory_get_session(factory, partition.as_ptr());
+        let mut out = empty_buffer();
+        assert_eq!(
+            asherah_encrypt_to_json(session, b"x".as_ptr(), 1, &mut out),
+            -1
+        );
+        assert!(last_error().starts_with("encrypt_to_json failed"));
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_KMS_UNAVAILABLE);
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+        asherah_kms_free(kms);
+    }
+    assert!(host_store.records.lock().unwrap().is_empty());
+}
+
+#[test]
+fn config_must_not_name_another_backend() {
+    let host_store = HostStore::default();
+    let name = CString::new("orm-table").unwrap();
+    unsafe {
+        let metastore = asherah_metastore_new(name.as_ptr(), &metastore_vtable(&host_store));
+        let factory = asherah_factory_new_with_backends(
+            config(r#","Metastore":"memory","KMS":"test-debug-static""#).as_ptr(),
+            metastore,
+            null(),
+        );
+        assert!(factory.is_null());
+        assert!(
+            last_error().contains("Metastore is 'memory'"),
+            "{}",
+            last_error()
+        );
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_CONFIG);
+
+        // "host" without a handle has nothing to call.
+        let factory = asherah_factory_new_with_backends(
+            config(r#","Metastore":"host","KMS":"test-debug-static""#).as_ptr(),
+            null(),
+            null(),
+        );
+        assert!(factory.is_null());
+        assert!(
+            last_error().contains("implemented by the application"),
+            "{}",
+            last_error()
+        );
+        asherah_metastore_free(metastore);
+    }
+    assert!(host_store.released.load(Ordering::SeqCst));
+}
+
+#[test]
+fn incomplete_vtables_are_refused() {
+    let host_store = HostStore::default();
+    let name = CString::new("orm-table").unwrap();
+    let mut vtable = metastore_vtable(&host_store);
+    vtable.store = None;
+    vtable.release = None;
+    unsafe {
+        assert!(asherah_metastore_new(name.as_ptr(), &vtable).is_null());
+        assert!(
+            last_error().contains("store are required"),
+            "{}",
+            last_error()
+        );
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_ARGUMENT);
+        let blank = CString::new(" ").unwrap();
+        assert!(asherah_metastore_new(blank.as_ptr(), &metastore_vtable(&host_store)).is_null());
+        assert!(
+            last_error().contains("must not be empty"),
+            "{}",
+            last_error()
+        );
+    }
+    // Never registered, so never released.
+    assert!(!host_store.released.load(Ordering::SeqCst));
+}
@@ -0,0 +1,97 @@
+//! Factory cache statistics, session eviction and cache flushing.
+
+#![allow(unsafe_code, clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use std::ffi::CString;
+use std::ptr::{null, null_mut};
+
+use asherah_ffi::{
+    asherah_buffer_free, asherah_encrypt_to_json, asherah_factory_cache_stats,
+    asherah_factory_evict_session, asherah_factory_flush_caches, asherah_factory_free,
+    asherah_factory_get_session, asherah_factory_new_with_config, asherah_last_error_code,
+    asherah_session_free, AsherahBuffer, AsherahCacheStats, AsherahFactory,
+    ASHERAH_ERR_INVALID_ARGUMENT,
+};
+
+unsafe fn stats(factory: *mut AsherahFactory) -> AsherahCacheStats {
+    let mut stats = AsherahCacheStats::default();
+    assert_eq!(asherah_factory_cache_stats(factory, &mut stats), 0);
+    stats
+}
+
+unsafe fn encrypt(factory: *mut AsherahFactory, partition: &str) {
+    let partition = CString::new(partition).unwrap();
+    let session = asherah_factory_get_session(factory, partition.as_ptr());
+    assert!(!session.is_null());
+    let mut out = AsherahBuffer {
+        data: null_mut(),
+        len: 0,
+        capacity: 0,
+    };
+    assert_eq!(
+        asherah_encrypt_to_json(session, b"secret".as_ptr(), 6, &mut out),
+        0
+    );
+    asherah_buffer_free(&mut out);
+    asherah_session_free(session);
+}
+
+#[test]
+fn stats_evict_and_flush() {
+    unsafe {
+        let config = CString::new(
+            r#"{"ServiceName":"svc","ProductID":"prod","Metastore":"memory","KMS":"test-debug-static","EnableSessionCaching":true}"#,
+        )
+        .unwrap();
+        let factory = asherah_factory_new_with_config(config.as_ptr());
+        assert!(!factory.is_null());
+
+        encrypt(factory, "p1");
+        encrypt(factory, "p1");
+        encrypt(factory, "p2");
+        let before = stats(factory);
+        assert_eq!(before.sessions, 2);
+        assert_eq!(before.session_cache_hits, 1);
+        assert_eq!(before.session_cache_misses, 2);
+        assert_eq!(before.intermediate_keys, 2);
+        assert_eq!(before.system_keys, 1);
+
+        let p2 = CString::new("p2").unwrap();
+        assert_eq!(asherah_factory_evict_session(factory, p2.as_ptr()), 1);
+        assert_eq!(asherah_factory_evict_session(factory, p2.as_ptr()), 0);
+        assert_eq!(stats(factory).sessions, 1);
+
+        assert_eq!(asherah_factory_flush_caches(factory), 0);
+        let flushed = stats(factory);
+        assert_eq!(flushed.sessions, 0);
+        assert_eq!(flushed.intermediate_keys, 0);
+        assert_eq!(flushed.system_keys, 0);
+        // Counters are cumulative and survive a flush.
+        assert_eq!(flushed.session_cache_hits, 1);
+
+        // Keys reload from the metastore after a flush.
+        encrypt(factory, "p1");
+        assert_eq!(stats(factory).intermediate_keys, 1);
+
+        asherah_factory_free(factory);
+    }
+}
+
+#[test]
+fn null_arguments_are_rejected() {
+    unsafe {
+        let mut stats = AsherahCacheStats::default();
+        assert_eq!(asherah_factory_cache_stats(null_mut(), &mut stats), -1);
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_ARGUMENT);
+        assert_eq!(asherah_factory_flush_caches(null_mut()), -1);
+
+        let config = CString::new(
+            r#"{"ServiceName":"svc","ProductID":"prod","Metastore":"memory","KMS":"test-debug-static"}"#,
+        )
+        .unwrap();
+        let factory = asherah_factory_new_with_config(config.as_ptr());
+        assert_eq!(asherah_factory_cache_stats(factory, null_mut()), -1);
+        assert_eq!(asherah_factory_evict_session(factory, null()), -1);
+        asherah_factory_free(factory);
+    }
+}
@@ -0,0 +1,383 @@
+//! The `*_into` caller-buffer variants and the `AsherahDataRowRecord` API.
+
+#![allow(unsafe_code, clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use std::ffi::CString;
+use std::ptr::{null, null_mut};
+
+use asherah::types::{DataRowRecord, EnvelopeKeyRecord, KeyMeta};
+use asherah_ffi::{
+    asherah_buffer_free, asherah_decrypt_from_json, asherah_decrypt_from_json_into,
+    asherah_decrypt_from_record, asherah_decrypt_from_record_into, asherah_encrypt_to_json,
+    asherah_encrypt_to_json_into, asherah_encrypt_to_record, asherah_encrypt_to_record_into,
+    asherah_factory_free, asherah_factory_get_session, asherah_factory_new_with_config,
+    asherah_last_error_code, asherah_record_free, asherah_session_free, AsherahBuffer,
+    AsherahDataRowRecord, AsherahFactory, SharedSession, ASHERAH_BUFFER_TOO_SMALL,
+    ASHERAH_ERR_DECRYPTION_FAILED, ASHERAH_ERR_INVALID_ARGUMENT,
+    ASHERAH_ERR_INVALID_DATA_ROW_RECORD,
+};
+
+fn empty_buffer() -> AsherahBuffer {
+    AsherahBuffer {
+        data: null_mut(),
+        len: 0,
+        capacity: 0,
+    }
+}
+
+fn empty_record() -> AsherahDataRowRecord {
+    AsherahDataRowRecord {
+        data: empty_buffer(),
+        key: empty_buffer(),
+        created: 0,
+        parent_key_id: empty_buffer(),
+        parent_key_created: 0,
+    }
+}
+
+/// A buffer over `v`'s spare capacity, as a caller would hand in.
+fn caller_buffer(v: &mut Vec<u8>) -> AsherahBuffer {
+    AsherahBuffer {
+        data: v.as_mut_ptr(),
+        len: 0,
+        capacity: v.capacity(),
+    }
+}
+
+unsafe fn bytes(buf: &AsherahBuffer) -> Vec<u8> {
+    std::slice::from_raw_parts(buf.data, buf.len).to_vec()
+}
+
+/// A factory whose service name needs escaping in JSON, so the size query
+/// has to account for it.
+unsafe fn open() -> (*mut AsherahFactory, *mut SharedSession) {
+    let config = CString::new(
+        r#"{"ServiceName":"svc\"\\","ProductID":"prod","Metastore":"memory","KMS":"test-debug-static"}"#,
+    )
+    .unwrap();
+    let partition = CString::new("p1").unwrap();
+    let factory = asherah_factory_new_with_config(config.as_ptr());
+    assert!(!factory.is_null());
+    let session = asherah_factory_get_session(factory, partition.as_ptr());
+    assert!(!session.is_null());
+    (factory, session)
+}
+
+#[test]
+fn json_into_caller_buffers() {
+    unsafe {
+        let (factory, session) = open();
+        let plaintext = vec![7_u8; 1000];
+
+        let mut bound = 0;
+        assert_eq!(
+            asherah_encrypt_to_json_into(
+                session,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                null_mut(),
+                0,
+                &mut bound
+            ),
+            0
+        );
+
+        let mut small = vec![0_u8; 16];
+        let mut needed = 0;
+        assert_eq!(
+            asherah_encrypt_to_json_into(
+                session,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                small.as_mut_ptr(),
+                small.len(),
+                &mut needed
+            ),
+            ASHERAH_BUFFER_TOO_SMALL
+        );
+        assert!(needed > small.len() && needed <= bound);
+        assert_eq!(small, vec![0_u8; 16]);
+
+        let mut json = vec![0_u8; bound];
+        let mut json_len = 0;
+        assert_eq!(
+            asherah_encrypt_to_json_into(
+                session,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                json.as_mut_ptr(),
+                json.len(),
+                &mut json_len
+            ),
+            0
+        );
+        json.truncate(json_len);
+        serde_json::from_slice::<serde_json::Value>(&json).unwrap();
+
+        // The output is an ordinary record.
+        let mut out = empty_buffer();
+        assert_eq!(
+            asherah_decrypt_from_json(session, json.as_ptr(), json.len(), &mut out),
+            0
+        );
+        assert_eq!(bytes(&out), plaintext);
+        asherah_buffer_free(&mut out);
+
+        let mut bound = 0;
+        assert_eq!(
+            asherah_decrypt_from_json_into(
+                session,
+                json.as_ptr(),
+                json.len(),
+                null_mut(),
+                0,
+                &mut bound
+            ),
+            0
+        );
+        assert!(bound >= plaintext.len());
+
+        let mut needed = 0;
+        assert_eq!(
+            asherah_decrypt_from_json_into(
+                session,
+                json.as_ptr(),
+                json.len(),
+                small.as_mut_ptr(),
+                small.len(),
+                &mut needed
+            ),
+            ASHERAH_BUFFER_TOO_SMALL
+        );
+        assert_eq!(needed, plaintext.len());
+
+        let mut decrypted = vec![0_u8; needed];
+        let mut decrypted_len = 0;
+        assert_eq!(
+            asherah_decrypt_from_json_into(
+                session,
+                json.as_ptr(),
+                json.len(),
+                decrypted.as_mut_ptr(),
+                decrypted.len(),
+                &mut decrypted_len
+            ),
+            0
+        );
+        assert_eq!(decrypted_len, plaintext.len());
+        assert_eq!(decrypted, plaintext);
+
+        let bad = b"{not json";
+        assert_eq!(
+            asherah_decrypt_from_json_into(
+                session,
+                bad.as_ptr(),
+                bad.len(),
+                decrypted.as_mut_ptr(),
+                decrypted.len(),
+                &mut decrypted_len
+            ),
+            -1
+        );
+        assert_eq!(
+            asherah_last_error_code(),
+            ASHERAH_ERR_INVALID_DATA_ROW_RECORD
+        );
+
+        assert_eq!(
+            asherah_encrypt_to_json_into(session, b"x".as_ptr(), 1, null_mut(), 0, null_mut()),
+            -1
+        );
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_ARGUMENT);
+
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+    }
+}
+
+#[test]
+fn records_match_their_json_form() {
+    unsafe {
+        let (factory, session) = open();
+
+        let mut record = empty_record();
+        assert_eq!(
+            asherah_encrypt_to_record(session, b"secret".as_ptr(), 6, &mut record),
+            0
+        );
+
+        // Rebuild the JSON form from the parts and decrypt it.
+        let json = DataRowRecord {
+            key: Some(EnvelopeKeyRecord {
+                revoked: None,
+                id: String::new(),
+                created: record.created,
+                encrypted_key: bytes(&record.key),
+                parent_key_meta: Some(KeyMeta {
+                    id: String::from_utf8(bytes(&record.parent_key_id)).unwrap(),
+                    created: record.parent_key_created,
+                }),
+            }),
+            data: bytes(&record.data),
+        }
+        .to_json_fast()
+        .into_bytes();
+        let mut out = empty_buffer();
+        assert_eq!(
+            asherah_decrypt_from_json(session, json.as_ptr(), json.len(), &mut out),
+            0
+        );
+        assert_eq!(bytes(&out), b"secret");
+        asherah_buffer_free(&mut out);
+
+        assert_eq!(asherah_decrypt_from_record(session, &record, &mut out), 0);
+        assert_eq!(bytes(&out), b"secret");
+        asherah_buffer_free(&mut out);
+
+        let mut needed = 0;
+        assert_eq!(
+            asherah_decrypt_from_record_into(session, &record, null_mut(), 0, &mut needed),
+            0
+        );
+        assert_eq!(needed, 6);
+        let mut plaintext = [0_u8; 6];
+        let mut plaintext_len = 0;
+        assert_eq!(
+            asherah_decrypt_from_record_into(
+                session,
+                &record,
+                plaintext.as_mut_ptr(),
+                5,
+                &mut plaintext_len
+            ),
+            ASHERAH_BUFFER_TOO_SMALL
+        );
+        assert_eq!(plaintext_len, 6);
+        assert_eq!(
+            asherah_decrypt_from_record_into(
+                session,
+                &record,
+                plaintext.as_mut_ptr(),
+                plaintext.len(),
+                &mut plaintext_len
+            ),
+            0
+        );
+        assert_eq!(&plaintext, b"secret");
+
+        // And the JSON form converts to the same parts.
+        let mut json_out = empty_buffer();
+        assert_eq!(
+            asherah_encrypt_to_json(session, b"secret".as_ptr(), 6, &mut json_out),
+            0
+        );
+        let parsed: serde_json::Value = serde_json::from_slice(&bytes(&json_out)).unwrap();
+        asherah_buffer_free(&mut json_out);
+        assert_eq!(
+            parsed["Key"]["ParentKeyMeta"]["KeyId"]
+                .as_str()
+                .unwrap()
+                .as_bytes(),
+            bytes(&record.parent_key_id)
+        );
+
+        asherah_record_free(&mut record);
+        assert!(record.data.data.is_null() && record.key.data.is_null());
+        assert!(record.parent_key_id.data.is_null());
+        assert_eq!((record.created, record.parent_key_created), (0, 0));
+        asherah_record_free(null_mut());
+
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+    }
+}
+
+#[test]
+fn records_into_caller_buffers() {
+    unsafe {
+        let (factory, session) = open();
+        let plaintext = b"column-stored secret";
+
+        let mut sizes = empty_record();
+        assert_eq!(
+            asherah_encrypt_to_record_into(
+                session,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                &mut sizes
+            ),
+            0
+        );
+
+        let mut data = Vec::with_capacity(sizes.data.len);
+        let mut key = Vec::with_capacity(sizes.key.len);
+        // No room for the key id yet.
+        let mut id = Vec::new();
+        let mut record = AsherahDataRowRecord {
+            data: caller_buffer(&mut data),
+            key: caller_buffer(&mut key),
+            created: 0,
+            parent_key_id: caller_buffer(&mut id),
+            parent_key_created: 0,
+        };
+        assert_eq!(
+            asherah_encrypt_to_record_into(
+                session,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                &mut record
+            ),
+            ASHERAH_BUFFER_TOO_SMALL
+        );
+        assert_eq!(record.parent_key_id.len, sizes.parent_key_id.len);
+        assert_eq!(record.created, 0);
+        id.reserve_exact(record.parent_key_id.len);
+        record.parent_key_id = caller_buffer(&mut id);
+
+        assert_eq!(
+            asherah_encrypt_to_record_into(
+                session,
+                plaintext.as_ptr(),
+                plaintext.len(),
+                &mut record
+            ),
+            0
+        );
+        assert_eq!(record.data.len, sizes.data.len);
+        assert_eq!(record.key.len, sizes.key.len);
+        assert_eq!(record.parent_key_id.len, sizes.parent_key_id.len);
+        assert!(record.created > 0 && record.parent_key_created > 0);
+
+        let mut out = empty_buffer();
+        assert_eq!(asherah_decrypt_from_record(session, &record, &mut out), 0);
+        assert_eq!(bytes(&out), plaintext);
+        asherah_buffer_free(&mut out);
+
+        // Tampered data fails authentication.
+        *record.data.data.add(record.data.len - 1) ^= 1;
+        assert_eq!(asherah_decrypt_from_record(session, &record, &mut out), -1);
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_DECRYPTION_FAILED);
+
+        let bad_id = [0xff_u8];
+        let mut bad = AsherahDataRowRecord {
+            parent_key_id: AsherahBuffer {
+                data: bad_id.as_ptr().cast_mut(),
+                len: 1,
+                capacity: 1,
+            },
+            ..record
+        };
+        assert_eq!(asherah_decrypt_from_record(session, &bad, &mut out), -1);
+        assert_eq!(
+            asherah_last_error_code(),
+            ASHERAH_ERR_INVALID_DATA_ROW_RECORD
+        );
+        bad.data.data = null_mut();
+        assert_eq!(asherah_decrypt_from_record(session, &bad, &mut out), -1);
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_ARGUMENT);
+        assert_eq!(asherah_decrypt_from_record(session, null(), &mut out), -1);
+
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+    }
+}
@@ -0,0 +1,293 @@
+//! Timeouts and cancellation for the `*_async_cancellable` calls.
+//!
+//! A host metastore that can be made to hang stands in for an unreachable
+//! database; every callback is counted to check it fires exactly once.
+
+#![allow(
+    unsafe_code,
+    clippy::unwrap_used,
+    clippy::expect_used,
+    clippy::panic,
+    clippy::unwrap_in_result
+)]
+
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::null_mut;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant};
+
+use asherah_ffi::{
+    asherah_backend_output_set, asherah_cancel, asherah_decrypt_from_json_async_cancellable,
+    asherah_encrypt_to_json_async_cancellable, asherah_factory_free, asherah_factory_get_session,
+    asherah_factory_new_with_backends, asherah_last_error_code, asherah_metastore_free,
+    asherah_metastore_new, asherah_operation_free, asherah_session_free, AsherahBackendOutput,
+    AsherahMetastoreVTable, AsherahOperation, SharedSession, ASHERAH_BACKEND_DUPLICATE,
+    ASHERAH_BACKEND_FOUND, ASHERAH_BACKEND_NOT_FOUND, ASHERAH_BACKEND_STORED,
+    ASHERAH_ERR_CANCELLED, ASHERAH_ERR_DEADLINE_EXCEEDED, ASHERAH_OPERATION_FINISHED,
+};
+
+#[derive(Default)]
+struct HangingStore {
+    records: Mutex<BTreeMap<(String, i64), Vec<u8>>>,
+    hang: AtomicBool,
+}
+
+impl HangingStore {
+    fn wait(&self) {
+        while self.hang.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+unsafe fn host<'host>(user_data: *mut c_void) -> &'host HangingStore {
+    &*(user_data as *const HangingStore)
+}
+
+unsafe fn found(out: *mut AsherahBackendOutput, record: &[u8]) -> i32 {
+    assert_eq!(
+        asherah_backend_output_set(out, record.as_ptr(), record.len()),
+        0
+    );
+    ASHERAH_BACKEND_FOUND
+}
+
+unsafe extern "C" fn load(
+    user_data: *mut c_void,
+    id: *const c_char,
+    created: i64,
+    out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = host(user_data);
+    host.wait();
+    let id = CStr::from_ptr(id).to_str().unwrap().to_string();
+    match host.records.lock().unwrap().get(&(id, created)) {
+        Some(record) => found(out, record),
+        None => ASHERAH_BACKEND_NOT_FOUND,
+    }
+}
+
+unsafe extern "C" fn load_latest(
+    user_data: *mut c_void,
+    id: *const c_char,
+    out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = host(user_data);
+    host.wait();
+    let id = CStr::from_ptr(id).to_str().unwrap();
+    let records = host.records.lock().unwrap();
+    match records.iter().rev().find(|((key, _), _)| key == id) {
+        Some((_, record)) => found(out, record),
+        None => ASHERAH_BACKEND_NOT_FOUND,
+    }
+}
+
+unsafe extern "C" fn store(
+    user_data: *mut c_void,
+    id: *const c_char,
+    created: i64,
+    record: *const u8,
+    record_len: usize,
+    _out: *mut AsherahBackendOutput,
+) -> i32 {
+    let host = host(user_data);
+    host.wait();
+    let id = CStr::from_ptr(id).to_str().unwrap().to_string();
+    let record = std::slice::from_raw_parts(record, record_len).to_vec();
+    let mut records = host.records.lock().unwrap();
+    if records.contains_key(&(id.clone(), created)) {
+        return ASHERAH_BACKEND_DUPLICATE;
+    }
+    records.insert((id, created), record);
+    ASHERAH_BACKEND_STORED
+}
+
+/// What a callback reported: the result bytes, or the error code.
+type Outcome = Result<Vec<u8>, c_int>;
+
+unsafe extern "C" fn report(
+    user_data: *mut c_void,
+    result: *const u8,
+    len: usize,
+    error: *const c_char,
+) {
+    let sender = &*(user_data as *const mpsc::Sender<Outcome>);
+    let outcome = if error.is_null() {
+        Ok(std::slice::from_raw_parts(result, len).to_vec())
+    } else {
+        Err(asherah_last_error_code())
+    };
+    sender.send(outcome).unwrap();
+}
+
+struct Fixture {
+    host: &'static HangingStore,
+    factory: *mut asherah_ffi::AsherahFactory,
+    session: *mut SharedSession,
+    sender: &'static mpsc::Sender<Outcome>,
+    receiver: mpsc::Receiver<Outcome>,
+}
+
+impl Fixture {
+    fn new() -> Self {
+        // Leaked: a cancelled backend call may still be running in the
+        // background when the test ends.
+        let host: &'static HangingStore = Box::leak(Box::default());
+        let (sender, receiver) = mpsc::channel();
+        let sender = Box::leak(Box::new(sender));
+        let config = CString::new(
+            r#"{"ServiceName":"svc","ProductID":"prod","KMS":"test-debug-static","EnableSessionCaching":false}"#,
+        )
+        .unwrap();
+        let name = CString::new("hanging-db").unwrap();
+        let partition = CString::new("partition-1").unwrap();
+        unsafe {
+            let vtable = AsherahMetastoreVTable {
+                user_data: std::ptr::from_ref(host).cast_mut().cast(),
+                load: Some(load),
+                load_latest: Some(load_latest),
+                store: Some(store),
+                release: None,
+            };
+            let metastore = asherah_metastore_new(name.as_ptr(), &vtable);
+            let factory = asherah_factory_new_with_backends(config.as_ptr(), metastore, null_mut());
+            asherah_metastore_free(metastore);
+            assert!(!factory.is_null());
+            let session = asherah_factory_get_session(factory, partition.as_ptr());
+            assert!(!session.is_null());
+            Self {
+                host,
+                factory,
+                session,
+                sender,
+                receiver,
+            }
+        }
+    }
+
+    fn user_data(&self) -> *mut c_void {
+        std::ptr::from_ref(self.sender).cast_mut().cast()
+    }
+
+    fn encrypt(&self, timeout_ms: u64, op: *mut *mut AsherahOperation) {
+        let data = b"secret";
+        let status = unsafe {
+            asherah_encrypt_to_json_async_cancellable(
+                self.session,
+                data.as_ptr(),
+                data.len(),
+                timeout_ms,
+                report,
+                self.user_data(),
+                op,
+            )
+        };
+        assert_eq!(status, 0);
+    }
+
+    fn outcome(&self) -> Outcome {
+        self.receiver.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    /// No second callback arrives.
+    fn assert_quiet(&self) {
+        assert!(self
+            .receiver
+            .recv_timeout(Duration::from_millis(200))
+            .is_err());
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        self.host.hang.store(false, Ordering::SeqCst);
+        unsafe {
+            asherah_session_free(self.session);
+            asherah_factory_free(self.factory);
+        }
+    }
+}
+
+#[test]
+fn a_hung_backend_times_out() {
+    let fixture = Fixture::new();
+    fixture.host.hang.store(true, Ordering::SeqCst);
+
+    let started = Instant::now();
+    fixture.encrypt(100, null_mut());
+    assert_eq!(fixture.outcome(), Err(ASHERAH_ERR_DEADLINE_EXCEEDED));
+    assert!(started.elapsed() < Duration::from_secs(2));
+
+    // The abandoned call finishes in the background without a second
+    // callback.
+    fixture.host.hang.store(false, Ordering::SeqCst);
+    fixture.assert_quiet();
+}
+
+#[test]
+fn cancel_reports_cancelled_once() {
+    let fixture = Fixture::new();
+    fixture.host.hang.store(true, Ordering::SeqCst);
+
+    let mut op = null_mut();
+    fixture.encrypt(0, &mut op);
+    assert!(!op.is_null());
+    unsafe {
+        assert_eq!(asherah_cancel(op), 0);
+        assert_eq!(fixture.outcome(), Err(ASHERAH_ERR_CANCELLED));
+        assert_eq!(asherah_cancel(op), ASHERAH_OPERATION_FINISHED);
+        asherah_operation_free(op);
+    }
+    fixture.host.hang.store(false, Ordering::SeqCst);
+    fixture.assert_quiet();
+}
+
+#[test]
+fn completed_operations_ignore_cancel() {
+    let fixture = Fixture::new();
+
+    let mut op = null_mut();
+    fixture.encrypt(5_000, &mut op);
+    let drr = fixture.outcome().unwrap();
+    unsafe {
+        assert_eq!(asherah_cancel(op), ASHERAH_OPERATION_FINISHED);
+        asherah_operation_free(op);
+
+        let status = asherah_decrypt_from_json_async_cancellable(
+            fixture.session,
+            drr.as_ptr(),
+            drr.len(),
+            5_000,
+            report,
+            fixture.user_data(),
+            &mut op,
+        );
+        assert_eq!(status, 0);
+        assert_eq!(fixture.outcome().unwrap(), b"secret");
+        // Freeing the handle before or after the callback is fine.
+        asherah_operation_free(op);
+    }
+    fixture.assert_quiet();
+}
+
+#[test]
+fn handle_is_cleared_on_failure() {
+    let mut op: *mut AsherahOperation = std::ptr::NonNull::dangling().as_ptr();
+    let status = unsafe {
+        asherah_encrypt_to_json_async_cancellable(
+            null_mut(),
+            null_mut(),
+            0,
+            0,
+            report,
+            null_mut(),
+            &mut op,
+        )
+    };
+    assert_eq!(status, -1);
+    assert!(op.is_null());
+    assert_eq!(unsafe { asherah_cancel(null_mut()) }, -1);
+}
@@ -0,0 +1,172 @@
+//! `asherah_last_error_code` and the `ASHERAH_ERR_*` lookups.
+
+#![allow(unsafe_code, clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int, c_void};
+use std::ptr::null_mut;
+use std::sync::mpsc;
+
+use asherah_ffi::{
+    asherah_apply_config_json, asherah_buffer_free, asherah_decrypt_from_json,
+    asherah_decrypt_from_json_async, asherah_encrypt_to_json, asherah_error_code_category,
+    asherah_error_code_is_retryable, asherah_error_code_name, asherah_factory_free,
+    asherah_factory_get_session, asherah_factory_new_with_config, asherah_last_error_code,
+    asherah_session_free, AsherahBuffer, ASHERAH_ERR_DECRYPTION_FAILED,
+    ASHERAH_ERR_INVALID_ARGUMENT, ASHERAH_ERR_INVALID_CONFIG, ASHERAH_ERR_INVALID_DATA_ROW_RECORD,
+    ASHERAH_ERR_KMS_UNAVAILABLE, ASHERAH_ERR_METASTORE_UNAVAILABLE, ASHERAH_ERR_NONE,
+    ASHERAH_ERR_PARTITION_MISMATCH,
+};
+
+fn name(code: c_int) -> Option<String> {
+    let ptr = asherah_error_code_name(code);
+    (!ptr.is_null()).then(|| unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string())
+}
+
+fn category(code: c_int) -> String {
+    let ptr = asherah_error_code_category(code);
+    assert!(!ptr.is_null());
+    unsafe { CStr::from_ptr(ptr) }.to_str().unwrap().to_string()
+}
+
+fn empty_buffer() -> AsherahBuffer {
+    AsherahBuffer {
+        data: null_mut(),
+        len: 0,
+        capacity: 0,
+    }
+}
+
+#[test]
+fn codes_describe_themselves() {
+    assert_eq!(
+        name(ASHERAH_ERR_DECRYPTION_FAILED).as_deref(),
+        Some("DECRYPTION_FAILED")
+    );
+    assert_eq!(category(ASHERAH_ERR_DECRYPTION_FAILED), "CRYPTO");
+    assert_eq!(category(ASHERAH_ERR_INVALID_DATA_ROW_RECORD), "INPUT");
+    assert_eq!(
+        asherah_error_code_is_retryable(ASHERAH_ERR_KMS_UNAVAILABLE),
+        1
+    );
+    assert_eq!(
+        asherah_error_code_is_retryable(ASHERAH_ERR_METASTORE_UNAVAILABLE),
+        1
+    );
+    assert_eq!(
+        asherah_error_code_is_retryable(ASHERAH_ERR_INVALID_CONFIG),
+        0
+    );
+
+    assert_eq!(name(ASHERAH_ERR_NONE), None);
+    assert_eq!(name(-42), None);
+    assert!(asherah_error_code_category(-42).is_null());
+    assert_eq!(asherah_error_code_is_retryable(-42), 0);
+}
+
+#[test]
+fn sync_failures_record_a_code() {
+    let bad =
+        CString::new(r#"{"ServiceName":"svc","ProductID":"prod","Metastore":"nope"}"#).unwrap();
+    assert_eq!(unsafe { asherah_apply_config_json(bad.as_ptr()) }, -1);
+    assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_CONFIG);
+
+    let config = CString::new(
+        r#"{"ServiceName":"svc","ProductID":"prod","Metastore":"memory","KMS":"test-debug-static"}"#,
+    )
+    .unwrap();
+    let partition = CString::new("p1").unwrap();
+    let other = CString::new("p2").unwrap();
+    unsafe {
+        let factory = asherah_factory_new_with_config(config.as_ptr());
+        assert!(!factory.is_null());
+        let session = asherah_factory_get_session(factory, partition.as_ptr());
+        let other = asherah_factory_get_session(factory, other.as_ptr());
+
+        let mut out = empty_buffer();
+        assert_eq!(
+            asherah_encrypt_to_json(null_mut(), b"x".as_ptr(), 1, &mut out),
+            -1
+        );
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_INVALID_ARGUMENT);
+
+        let json = b"{not json";
+        assert_eq!(
+            asherah_decrypt_from_json(session, json.as_ptr(), json.len(), &mut out),
+            -1
+        );
+        assert_eq!(
+            asherah_last_error_code(),
+            ASHERAH_ERR_INVALID_DATA_ROW_RECORD
+        );
+
+        assert_eq!(
+            asherah_encrypt_to_json(session, b"secret".as_ptr(), 6, &mut out),
+            0
+        );
+        let drr = std::slice::from_raw_parts(out.data, out.len).to_vec();
+        asherah_buffer_free(&mut out);
+
+        let mut plain = empty_buffer();
+        assert_eq!(
+            asherah_decrypt_from_json(other, drr.as_ptr(), drr.len(), &mut plain),
+            -1
+        );
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_PARTITION_MISMATCH);
+
+        let mut tampered: serde_json::Value = serde_json::from_slice(&drr).unwrap();
+        tampered["Data"] = serde_json::Value::from("AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA");
+        let tampered = serde_json::to_vec(&tampered).unwrap();
+        assert_eq!(
+            asherah_decrypt_from_json(session, tampered.as_ptr(), tampered.len(), &mut plain),
+            -1
+        );
+        assert_eq!(asherah_last_error_code(), ASHERAH_ERR_DECRYPTION_FAILED);
+
+        asherah_session_free(other);
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+    }
+}
+
+unsafe extern "C" fn report_code(
+    user_data: *mut c_void,
+    _result: *const u8,
+    _len: usize,
+    error: *const c_char,
+) {
+    let sender = &*(user_data as *const mpsc::Sender<(bool, c_int)>);
+    sender
+        .send((!error.is_null(), asherah_last_error_code()))
+        .unwrap();
+}
+
+#[test]
+fn async_callbacks_can_read_the_code() {
+    let config = CString::new(
+        r#"{"ServiceName":"svc","ProductID":"prod","Metastore":"memory","KMS":"test-debug-static"}"#,
+    )
+    .unwrap();
+    let partition = CString::new("p1").unwrap();
+    let (sender, receiver) = mpsc::channel::<(bool, c_int)>();
+    unsafe {
+        let factory = asherah_factory_new_with_config(config.as_ptr());
+        let session = asherah_factory_get_session(factory, partition.as_ptr());
+        let json = br#"{"Data":"AAAA"}"#;
+        assert_eq!(
+            asherah_decrypt_from_json_async(
+                session,
+                json.as_ptr(),
+                json.len(),
+                report_code,
+                std::ptr::from_ref(&sender).cast_mut().cast(),
+            ),
+            0
+        );
+        let (failed, code) = receiver.recv().unwrap();
+        assert!(failed);
+        assert_eq!(code, ASHERAH_ERR_INVALID_DATA_ROW_RECORD);
+        asherah_session_free(session);
+        asherah_factory_free(factory);
+    }
+}
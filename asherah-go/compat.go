@@ -256,11 +256,10 @@ type CompatSession struct {
 	inner *Session
 }
 
-// Encrypt encrypts data and returns a DataRowRecord.
-// The context parameter is accepted for API compatibility but not used
-// (the native layer does not support cancellation).
-func (s *CompatSession) Encrypt(_ context.Context, data []byte) (*DataRowRecord, error) {
-	jsonBytes, err := s.inner.Encrypt(data)
+// Encrypt encrypts data and returns a DataRowRecord. Cancelling ctx, or
+// passing its deadline, ends the call early (see Session.EncryptContext).
+func (s *CompatSession) Encrypt(ctx context.Context, data []byte) (*DataRowRecord, error) {
+	jsonBytes, err := s.inner.EncryptContext(ctx, data)
 	if err != nil {
 		return nil, err
 	}
@@ -272,12 +271,12 @@ func (s *CompatSession) Encrypt(_ context.Context, data []byte) (*DataRowRecord,
 }
 
 // Decrypt decrypts a DataRowRecord and returns the original plaintext.
-func (s *CompatSession) Decrypt(_ context.Context, d DataRowRecord) ([]byte, error) {
+func (s *CompatSession) Decrypt(ctx context.Context, d DataRowRecord) ([]byte, error) {
 	jsonBytes, err := json.Marshal(&d)
 	if err != nil {
 		return nil, fmt.Errorf("asherah: failed to serialize DataRowRecord: %w", err)
 	}
-	return s.inner.Decrypt(jsonBytes)
+	return s.inner.DecryptContext(ctx, jsonBytes)
 }
 
 // Load loads a DataRowRecord from the store and decrypts it.
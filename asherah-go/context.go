@@ -0,0 +1,198 @@
+package asherah
+
+import (
+	"context"
+	"errors"
+	"fmt"
+	"runtime"
+	"sync"
+	"time"
+	"unsafe"
+
+	"github.com/ebitengine/purego"
+)
+
+// Error codes the cancellable FFI calls report through
+// asherah_last_error_code.
+const (
+	errCodeCancelled        = 303
+	errCodeDeadlineExceeded = 304
+)
+
+// FFI entry points populated by loadSymbols.
+var (
+	fnEncryptToJSONAsyncCancellable   func(session uintptr, data uintptr, dataLen uintptr, timeoutMs uint64, callback uintptr, userData uintptr, outOp uintptr) int
+	fnDecryptFromJSONAsyncCancellable func(session uintptr, json uintptr, jsonLen uintptr, timeoutMs uint64, callback uintptr, userData uintptr, outOp uintptr) int
+	fnCancel                          func(op uintptr) int
+	fnOperationFree                   func(op uintptr)
+	fnLastErrorCode                   func() int32
+)
+
+// asyncResult is what a completion callback reported.
+type asyncResult struct {
+	data []byte
+	code int32
+	err  string
+}
+
+// asyncState routes completion callbacks back to the waiting goroutine. A
+// single trampoline serves every operation (purego never releases callback
+// slots); userData is the key into pending.
+type asyncState struct {
+	once       sync.Once
+	trampoline uintptr
+
+	mu      sync.Mutex
+	next    uintptr
+	pending map[uintptr]chan asyncResult
+}
+
+var async asyncState
+
+func (a *asyncState) register() (uintptr, chan asyncResult) {
+	a.once.Do(func() {
+		a.trampoline = purego.NewCallback(completionTrampoline)
+		a.pending = make(map[uintptr]chan asyncResult)
+	})
+	ch := make(chan asyncResult, 1)
+	a.mu.Lock()
+	a.next++
+	id := a.next
+	a.pending[id] = ch
+	a.mu.Unlock()
+	return id, ch
+}
+
+func (a *asyncState) take(id uintptr) chan asyncResult {
+	a.mu.Lock()
+	defer a.mu.Unlock()
+	ch := a.pending[id]
+	delete(a.pending, id)
+	return ch
+}
+
+// completionTrampoline is the C-callable completion callback. It runs on a
+// native worker thread, so the error code is read here, on the thread that
+// set it.
+func completionTrampoline(userData uintptr, result uintptr, resultLen uintptr, errMsg uintptr) uintptr {
+	defer func() { _ = recover() }()
+	var r asyncResult
+	if errMsg != 0 {
+		r.code = fnLastErrorCode()
+		r.err = cstr(errMsg)
+	} else if resultLen > 0 && result != 0 {
+		r.data = make([]byte, int(resultLen))
+		copy(r.data, unsafe.Slice((*byte)(unsafe.Pointer(result)), int(resultLen)))
+	}
+	if ch := async.take(userData); ch != nil {
+		ch <- r
+	}
+	return 0
+}
+
+// EncryptContext is Encrypt, ending early when ctx is cancelled or its
+// deadline passes. The context error is returned in that case, even if a
+// metastore or KMS call is still hung; that call finishes in the background
+// and its result is discarded.
+func (s *Session) EncryptContext(ctx context.Context, plaintext []byte) ([]byte, error) {
+	return s.runContext(ctx, "encrypt", fnEncryptToJSONAsyncCancellable, s.Encrypt, plaintext)
+}
+
+// DecryptContext is Decrypt, ending early when ctx is cancelled or its
+// deadline passes. See EncryptContext.
+func (s *Session) DecryptContext(ctx context.Context, dataRowRecord []byte) ([]byte, error) {
+	return s.runContext(ctx, "decrypt", fnDecryptFromJSONAsyncCancellable, s.Decrypt, dataRowRecord)
+}
+
+func (s *Session) runContext(
+	ctx context.Context,
+	name string,
+	start func(uintptr, uintptr, uintptr, uint64, uintptr, uintptr, uintptr) int,
+	blocking func([]byte) ([]byte, error),
+	input []byte,
+) ([]byte, error) {
+	if ctx.Done() == nil {
+		// Never cancelled and no deadline: the plain call is cheaper.
+		return blocking(input)
+	}
+	if err := ctx.Err(); err != nil {
+		return nil, err
+	}
+	// The native side takes the deadline as a timeout, rounded up so it
+	// never fires before ctx does. 0 means none.
+	var timeoutMs uint64
+	if deadline, ok := ctx.Deadline(); ok {
+		remaining := time.Until(deadline)
+		if remaining <= 0 {
+			return nil, context.DeadlineExceeded
+		}
+		timeoutMs = uint64((remaining + time.Millisecond - 1) / time.Millisecond)
+	}
+
+	op, ch, err := s.startContext(name, start, input, timeoutMs)
+	if err != nil {
+		return nil, err
+	}
+	defer fnOperationFree(op)
+
+	// The callback fires exactly once, cancelled or not, so always wait
+	// for it.
+	var r asyncResult
+	select {
+	case r = <-ch:
+	case <-ctx.Done():
+		fnCancel(op)
+		r = <-ch
+	}
+	switch {
+	case r.err == "":
+		return r.data, nil
+	case r.code == errCodeCancelled && ctx.Err() != nil:
+		return nil, ctx.Err()
+	case r.code == errCodeDeadlineExceeded:
+		return nil, fmt.Errorf("asherah-go: %s failed: %s: %w", name, r.err, context.DeadlineExceeded)
+	default:
+		return nil, fmt.Errorf("asherah-go: %s failed: %s", name, r.err)
+	}
+}
+
+// startContext starts the native operation and returns its handle and the
+// channel its result arrives on.
+func (s *Session) startContext(
+	name string,
+	start func(uintptr, uintptr, uintptr, uint64, uintptr, uintptr, uintptr) int,
+	input []byte,
+	timeoutMs uint64,
+) (uintptr, chan asyncResult, error) {
+	s.mu.RLock()
+	defer s.mu.RUnlock()
+	if s.ptr == 0 {
+		return 0, nil, errors.New("asherah-go: session is closed")
+	}
+
+	// LockOSThread for the FFI-call + lastErrorMessage pair — see Encrypt.
+	runtime.LockOSThread()
+	defer runtime.UnlockOSThread()
+
+	// The native side copies the input before returning, so it only needs
+	// pinning for the call itself.
+	var pinner runtime.Pinner
+	op := new(uintptr)
+	pinner.Pin(op)
+	defer pinner.Unpin()
+
+	var dataPtr uintptr
+	if len(input) > 0 {
+		pinner.Pin(&input[0])
+		dataPtr = uintptr(unsafe.Pointer(&input[0]))
+	}
+	id, ch := async.register()
+	rc := start(s.ptr, dataPtr, uintptr(len(input)), timeoutMs, async.trampoline, id, uintptr(unsafe.Pointer(op)))
+	runtime.KeepAlive(input)
+	runtime.KeepAlive(op)
+	if rc != 0 {
+		async.take(id)
+		return 0, nil, fmt.Errorf("asherah-go: %s failed: %s", name, lastErrorMessage())
+	}
+	return *op, ch, nil
+}
@@ -0,0 +1,71 @@
+package asherah_test
+
+import (
+	"context"
+	"errors"
+	"testing"
+	"time"
+)
+
+// A hung backend can't be staged with the in-memory metastore; the native
+// side's cancel and deadline behavior is covered by
+// asherah-ffi/tests/cancellation.rs.
+
+func TestSessionContextRoundTrip(t *testing.T) {
+	factory := newTestFactory(t)
+	defer factory.Close()
+
+	session, err := factory.GetSession("context")
+	if err != nil {
+		t.Fatalf("GetSession failed: %v", err)
+	}
+	defer session.Close()
+
+	ctx, cancel := context.WithTimeout(context.Background(), 30*time.Second)
+	defer cancel()
+
+	ct, err := session.EncryptContext(ctx, []byte("cancellable payload"))
+	if err != nil {
+		t.Fatalf("EncryptContext failed: %v", err)
+	}
+	recovered, err := session.DecryptContext(ctx, ct)
+	if err != nil {
+		t.Fatalf("DecryptContext failed: %v", err)
+	}
+	if string(recovered) != "cancellable payload" {
+		t.Fatalf("expected %q, got %q", "cancellable payload", recovered)
+	}
+
+	// Background contexts take the plain path.
+	recovered, err = session.DecryptContext(context.Background(), ct)
+	if err != nil || string(recovered) != "cancellable payload" {
+		t.Fatalf("DecryptContext(Background) = %q, %v", recovered, err)
+	}
+
+	if _, err := session.DecryptContext(ctx, []byte("{not json")); err == nil {
+		t.Fatal("expected error for invalid DataRowRecord")
+	}
+}
+
+func TestSessionContextAlreadyDone(t *testing.T) {
+	factory := newTestFactory(t)
+	defer factory.Close()
+
+	session, err := factory.GetSession("context-done")
+	if err != nil {
+		t.Fatalf("GetSession failed: %v", err)
+	}
+	defer session.Close()
+
+	ctx, cancel := context.WithCancel(context.Background())
+	cancel()
+	if _, err := session.EncryptContext(ctx, []byte("x")); !errors.Is(err, context.Canceled) {
+		t.Fatalf("expected context.Canceled, got %v", err)
+	}
+
+	expired, cancelExpired := context.WithDeadline(context.Background(), time.Now().Add(-time.Second))
+	defer cancelExpired()
+	if _, err := session.DecryptContext(expired, []byte("{}")); !errors.Is(err, context.DeadlineExceeded) {
+		t.Fatalf("expected context.DeadlineExceeded, got %v", err)
+	}
+}
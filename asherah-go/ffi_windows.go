@@ -35,6 +35,11 @@ func loadSymbols(lib uintptr) error {
 	reg(&fnDecryptFromJSON, "asherah_decrypt_from_json")
 	reg(&fnBufferFree, "asherah_buffer_free")
 	reg(&fnLastErrorMessage, "asherah_last_error_message")
+	reg(&fnLastErrorCode, "asherah_last_error_code")
+	reg(&fnEncryptToJSONAsyncCancellable, "asherah_encrypt_to_json_async_cancellable")
+	reg(&fnDecryptFromJSONAsyncCancellable, "asherah_decrypt_from_json_async_cancellable")
+	reg(&fnCancel, "asherah_cancel")
+	reg(&fnOperationFree, "asherah_operation_free")
 	reg(&fnSetLogHook, "asherah_set_log_hook")
 	reg(&fnClearLogHook, "asherah_clear_log_hook")
 	reg(&fnSetMetricsHook, "asherah_set_metrics_hook")
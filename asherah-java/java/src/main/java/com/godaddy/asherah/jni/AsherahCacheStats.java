@@ -0,0 +1,57 @@
+package com.godaddy.asherah.jni;
+
+/**
+ * Snapshot of a factory's caches, from {@link AsherahFactory#cacheStats()}.
+ *
+ * <p>The session counts are {@code 0} when session caching is disabled. Hits and misses count
+ * {@link AsherahFactory#getSession} calls since the factory was created, and survive
+ * {@link AsherahFactory#flushCaches()}.
+ */
+public final class AsherahCacheStats {
+    private final long sessions;
+    private final long sessionCacheHits;
+    private final long sessionCacheMisses;
+    private final long intermediateKeys;
+    private final long systemKeys;
+
+    AsherahCacheStats(long sessions, long sessionCacheHits, long sessionCacheMisses,
+        long intermediateKeys, long systemKeys) {
+        this.sessions = sessions;
+        this.sessionCacheHits = sessionCacheHits;
+        this.sessionCacheMisses = sessionCacheMisses;
+        this.intermediateKeys = intermediateKeys;
+        this.systemKeys = systemKeys;
+    }
+
+    /** Sessions in the session cache. */
+    public long getSessions() {
+        return sessions;
+    }
+
+    /** Sessions served from the session cache. */
+    public long getSessionCacheHits() {
+        return sessionCacheHits;
+    }
+
+    /** Sessions built despite session caching. */
+    public long getSessionCacheMisses() {
+        return sessionCacheMisses;
+    }
+
+    /** Entries in the shared intermediate key cache. */
+    public long getIntermediateKeys() {
+        return intermediateKeys;
+    }
+
+    /** Entries in the system key cache. */
+    public long getSystemKeys() {
+        return systemKeys;
+    }
+
+    @Override
+    public String toString() {
+        return "AsherahCacheStats{sessions=" + sessions + ", sessionCacheHits=" + sessionCacheHits
+            + ", sessionCacheMisses=" + sessionCacheMisses + ", intermediateKeys="
+            + intermediateKeys + ", systemKeys=" + systemKeys + "}";
+    }
+}
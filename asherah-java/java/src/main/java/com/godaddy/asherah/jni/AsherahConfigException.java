@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** The configuration is invalid. */
+public class AsherahConfigException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahConfigException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** The data or a wrapped key failed authenticated decryption. */
+public class AsherahDecryptionException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahDecryptionException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
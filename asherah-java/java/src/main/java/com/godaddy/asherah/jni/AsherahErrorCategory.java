@@ -0,0 +1,21 @@
+package com.godaddy.asherah.jni;
+
+/** Category of an {@link AsherahErrorCode}, which picks its exception class. */
+public enum AsherahErrorCategory {
+  /** An argument or DataRowRecord is invalid ({@link AsherahInputException}). */
+  INPUT,
+  /** The configuration is invalid ({@link AsherahConfigException}). */
+  CONFIG,
+  /** Called in the wrong state ({@link AsherahStateException}). */
+  STATE,
+  /** A key is missing from the metastore ({@link AsherahKeyNotFoundException}). */
+  KEY,
+  /** The metastore failed ({@link AsherahMetastoreException}). */
+  METASTORE,
+  /** The KMS failed or refused ({@link AsherahKmsException}). */
+  KMS,
+  /** Authenticated decryption failed ({@link AsherahDecryptionException}). */
+  CRYPTO,
+  /** A bug or unexpected failure (plain {@link AsherahException}). */
+  INTERNAL
+}
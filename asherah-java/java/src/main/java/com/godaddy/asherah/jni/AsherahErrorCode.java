@@ -0,0 +1,59 @@
+package com.godaddy.asherah.jni;
+
+/**
+ * Asherah's stable error codes. The numbers never change between releases
+ * and are shared with the C ABI, Cobhan and the other language bindings.
+ */
+public enum AsherahErrorCode {
+  UNKNOWN(1, AsherahErrorCategory.INTERNAL, false),
+  INTERNAL(2, AsherahErrorCategory.INTERNAL, false),
+  INVALID_ARGUMENT(100, AsherahErrorCategory.INPUT, false),
+  PAYLOAD_TOO_LARGE(101, AsherahErrorCategory.INPUT, false),
+  INVALID_DATA_ROW_RECORD(102, AsherahErrorCategory.INPUT, false),
+  PARTITION_MISMATCH(103, AsherahErrorCategory.INPUT, false),
+  INVALID_CONFIG(200, AsherahErrorCategory.CONFIG, false),
+  NOT_INITIALIZED(300, AsherahErrorCategory.STATE, false),
+  ALREADY_INITIALIZED(301, AsherahErrorCategory.STATE, false),
+  CLOSED(302, AsherahErrorCategory.STATE, false),
+  CANCELLED(303, AsherahErrorCategory.STATE, false),
+  DEADLINE_EXCEEDED(304, AsherahErrorCategory.STATE, true),
+  KEY_NOT_FOUND(400, AsherahErrorCategory.KEY, false),
+  METASTORE_UNAVAILABLE(500, AsherahErrorCategory.METASTORE, true),
+  KMS_UNAVAILABLE(600, AsherahErrorCategory.KMS, true),
+  KMS_ACCESS_DENIED(601, AsherahErrorCategory.KMS, false),
+  DECRYPTION_FAILED(700, AsherahErrorCategory.CRYPTO, false);
+
+  private final int code;
+  private final AsherahErrorCategory category;
+  private final boolean retryable;
+
+  AsherahErrorCode(int code, AsherahErrorCategory category, boolean retryable) {
+    this.code = code;
+    this.category = category;
+    this.retryable = retryable;
+  }
+
+  /** The stable numeric code. */
+  public int getCode() {
+    return code;
+  }
+
+  public AsherahErrorCategory getCategory() {
+    return category;
+  }
+
+  /** Whether the call may succeed if retried later (a metastore or KMS outage). */
+  public boolean isRetryable() {
+    return retryable;
+  }
+
+  /** The code with the given number, or {@link #UNKNOWN} for one this version doesn't know. */
+  public static AsherahErrorCode fromCode(int code) {
+    for (AsherahErrorCode value : values()) {
+      if (value.code == code) {
+        return value;
+      }
+    }
+    return UNKNOWN;
+  }
+}
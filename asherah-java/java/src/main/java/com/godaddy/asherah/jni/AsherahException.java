@@ -0,0 +1,58 @@
+package com.godaddy.asherah.jni;
+
+/**
+ * Thrown for every failure reported by the native library. The subclass is
+ * picked by the error's {@link AsherahErrorCategory}; branch on it or on
+ * {@link #getErrorCode()} rather than on the message, which may change.
+ *
+ * <p>Extends {@link RuntimeException}, which is what the binding threw before
+ * error codes existed. Precondition checks made in Java (a closed session, a
+ * null argument) still throw {@link IllegalStateException} and
+ * {@link NullPointerException}.
+ */
+public class AsherahException extends RuntimeException {
+  private static final long serialVersionUID = 1L;
+
+  private final AsherahErrorCode errorCode;
+
+  public AsherahException(AsherahErrorCode errorCode, String message) {
+    super(message);
+    this.errorCode = errorCode;
+  }
+
+  public AsherahErrorCode getErrorCode() {
+    return errorCode;
+  }
+
+  public AsherahErrorCategory getCategory() {
+    return errorCode.getCategory();
+  }
+
+  /** Whether the call may succeed if retried later (a metastore or KMS outage). */
+  public boolean isRetryable() {
+    return errorCode.isRetryable();
+  }
+
+  /** Called by the JNI bridge to build the exception for a native error code. */
+  static AsherahException create(int code, String message) {
+    final AsherahErrorCode errorCode = AsherahErrorCode.fromCode(code);
+    switch (errorCode.getCategory()) {
+      case INPUT:
+        return new AsherahInputException(errorCode, message);
+      case CONFIG:
+        return new AsherahConfigException(errorCode, message);
+      case STATE:
+        return new AsherahStateException(errorCode, message);
+      case KEY:
+        return new AsherahKeyNotFoundException(errorCode, message);
+      case METASTORE:
+        return new AsherahMetastoreException(errorCode, message);
+      case KMS:
+        return new AsherahKmsException(errorCode, message);
+      case CRYPTO:
+        return new AsherahDecryptionException(errorCode, message);
+      default:
+        return new AsherahException(errorCode, message);
+    }
+  }
+}
@@ -29,6 +29,34 @@ public final class AsherahFactory implements AutoCloseable {
     return new AsherahSession(sessionHandle);
   }
 
+  /** Sizes and hit counts of this factory's caches. */
+  public AsherahCacheStats cacheStats() {
+    ensureOpen();
+    final long[] values = AsherahNative.cacheStats(cleanup.peek());
+    return new AsherahCacheStats(values[0], values[1], values[2], values[3], values[4]);
+  }
+
+  /**
+   * Drops {@code partitionId}'s cached session, so the next {@link #getSession} for it builds a
+   * fresh one. Sessions already handed out keep working.
+   *
+   * @return whether a session was cached
+   */
+  public boolean evictSession(String partitionId) {
+    ensureOpen();
+    Objects.requireNonNull(partitionId, "partitionId");
+    return AsherahNative.evictSession(cleanup.peek(), partitionId);
+  }
+
+  /**
+   * Empties the session cache and the system and intermediate key caches, so keys are loaded
+   * from the metastore again: after a key revocation, or to release memory.
+   */
+  public void flushCaches() {
+    ensureOpen();
+    AsherahNative.flushCaches(cleanup.peek());
+  }
+
   @Override
   public synchronized void close() {
     if (closed.getAndSet(true)) {
@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** An argument or DataRowRecord is invalid. */
+public class AsherahInputException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahInputException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
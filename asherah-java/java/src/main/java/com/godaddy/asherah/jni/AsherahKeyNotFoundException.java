@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** A key the record refers to is not in the metastore. */
+public class AsherahKeyNotFoundException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahKeyNotFoundException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** The KMS failed or refused the request. */
+public class AsherahKmsException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahKmsException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
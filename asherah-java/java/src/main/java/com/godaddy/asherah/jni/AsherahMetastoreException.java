@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** The metastore failed the request. */
+public class AsherahMetastoreException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahMetastoreException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
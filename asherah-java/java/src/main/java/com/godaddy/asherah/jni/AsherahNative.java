@@ -19,6 +19,13 @@ final class AsherahNative {
 
   static native long getSession(long factoryHandle, String partitionId);
 
+  /** Returns the constructor arguments of {@link AsherahCacheStats}, in order. */
+  static native long[] cacheStats(long factoryHandle);
+
+  static native boolean evictSession(long factoryHandle, String partitionId);
+
+  static native void flushCaches(long factoryHandle);
+
   static native void closeSession(long sessionHandle);
 
   static native void freeSession(long sessionHandle);
@@ -27,6 +34,34 @@ final class AsherahNative {
 
   static native byte[] decrypt(long sessionHandle, byte[] ciphertextJson);
 
+  static native int encryptedLengthBound(long sessionHandle, int plaintextLength);
+
+  /** Returns the bytes written, or minus the bytes needed if {@code out} is too small. */
+  static native int encryptDirect(long sessionHandle, java.nio.ByteBuffer plaintext,
+      int plaintextPosition, int plaintextLength, java.nio.ByteBuffer out, int outPosition,
+      int outLength);
+
+  /** Returns the bytes written, or minus the bytes needed if {@code out} is too small. */
+  static native int decryptDirect(long sessionHandle, java.nio.ByteBuffer ciphertextJson,
+      int ciphertextPosition, int ciphertextLength, java.nio.ByteBuffer out, int outPosition,
+      int outLength);
+
+  static native long streamEncryptorNew(long sessionHandle, int chunkSize);
+
+  static native byte[] streamEncryptorUpdate(long handle, byte[] data, int offset, int length);
+
+  static native byte[] streamEncryptorFinish(long handle);
+
+  static native void streamEncryptorFree(long handle);
+
+  static native long streamDecryptorNew(long sessionHandle);
+
+  static native byte[] streamDecryptorUpdate(long handle, byte[] data, int offset, int length);
+
+  static native void streamDecryptorFinish(long handle);
+
+  static native void streamDecryptorFree(long handle);
+
   static native void encryptAsync(long sessionHandle, byte[] plaintext,
       java.util.concurrent.CompletableFuture<byte[]> future);
 
@@ -1,12 +1,21 @@
 package com.godaddy.asherah.jni;
 
+import java.io.InputStream;
+import java.io.OutputStream;
 import java.lang.ref.Cleaner;
+import java.nio.BufferOverflowException;
+import java.nio.ByteBuffer;
+import java.nio.ReadOnlyBufferException;
 import java.nio.charset.StandardCharsets;
+import java.util.Arrays;
 import java.util.Objects;
 import java.util.concurrent.CompletableFuture;
 import java.util.concurrent.atomic.AtomicBoolean;
 
 public final class AsherahSession implements AutoCloseable {
+  /** Plaintext bytes per frame of an {@link #encryptingStream(OutputStream)}. */
+  public static final int DEFAULT_STREAM_CHUNK_SIZE = 64 * 1024;
+
   private static final Cleaner CLEANER = Cleaner.create();
 
   private final AtomicBoolean closed = new AtomicBoolean(false);
@@ -62,6 +71,103 @@ public final class AsherahSession implements AutoCloseable {
     return new String(plaintext, StandardCharsets.UTF_8);
   }
 
+  /**
+   * Encrypts the remaining bytes of {@code plaintext} into {@code out} as DataRowRecord JSON and
+   * returns its length. Both positions advance. Direct buffers are read and written in place,
+   * without a copy through a Java array.
+   *
+   * @throws BufferOverflowException if the record doesn't fit in {@code out}; neither position
+   *     moves. Size {@code out} with {@link #encryptedLengthBound}.
+   */
+  public int encrypt(ByteBuffer plaintext, ByteBuffer out) {
+    Objects.requireNonNull(plaintext, "plaintext");
+    return transform(plaintext, out, true);
+  }
+
+  /** An upper bound on the record {@link #encrypt(ByteBuffer, ByteBuffer)} writes. */
+  public int encryptedLengthBound(int plaintextLength) {
+    ensureOpen();
+    return AsherahNative.encryptedLengthBound(cleanup.peek(), plaintextLength);
+  }
+
+  /**
+   * Decrypts the DataRowRecord JSON remaining in {@code ciphertextJson} into {@code out} and
+   * returns the plaintext length. Both positions advance. The plaintext is never longer than the
+   * record.
+   *
+   * @throws BufferOverflowException if the plaintext doesn't fit in {@code out}; neither position
+   *     moves, and nothing is decrypted.
+   */
+  public int decrypt(ByteBuffer ciphertextJson, ByteBuffer out) {
+    Objects.requireNonNull(ciphertextJson, "ciphertextJson");
+    return transform(ciphertextJson, out, false);
+  }
+
+  /**
+   * Returns a stream that encrypts what is written to it and writes the result to {@code out},
+   * in chunks of {@link #DEFAULT_STREAM_CHUNK_SIZE} bytes. Closing it writes the last frame and
+   * closes {@code out}; a stream that is never closed can't be decrypted.
+   */
+  public OutputStream encryptingStream(OutputStream out) {
+    return encryptingStream(out, DEFAULT_STREAM_CHUNK_SIZE);
+  }
+
+  /** As {@link #encryptingStream(OutputStream)}, with {@code chunkSize} bytes per frame. */
+  public OutputStream encryptingStream(OutputStream out, int chunkSize) {
+    ensureOpen();
+    Objects.requireNonNull(out, "out");
+    return new EncryptingOutputStream(cleanup.peek(), out, chunkSize);
+  }
+
+  /**
+   * Returns a stream that decrypts what {@link #encryptingStream(OutputStream)} wrote, read from
+   * {@code in}. Reads throw {@link AsherahException} if the data was tampered with or ends early.
+   */
+  public InputStream decryptingStream(InputStream in) {
+    ensureOpen();
+    Objects.requireNonNull(in, "in");
+    return new DecryptingInputStream(cleanup.peek(), in);
+  }
+
+  private int transform(ByteBuffer input, ByteBuffer out, boolean encrypt) {
+    ensureOpen();
+    Objects.requireNonNull(out, "out");
+    if (out.isReadOnly()) {
+      throw new ReadOnlyBufferException();
+    }
+    final int written;
+    if (input.isDirect() && out.isDirect()) {
+      final long handle = cleanup.peek();
+      written =
+          encrypt
+              ? AsherahNative.encryptDirect(
+                  handle, input, input.position(), input.remaining(),
+                  out, out.position(), out.remaining())
+              : AsherahNative.decryptDirect(
+                  handle, input, input.position(), input.remaining(),
+                  out, out.position(), out.remaining());
+      if (written < 0) {
+        throw new BufferOverflowException();
+      }
+      out.position(out.position() + written);
+    } else {
+      final byte[] in = new byte[input.remaining()];
+      input.duplicate().get(in);
+      final byte[] result = encrypt ? encryptBytes(in) : decryptBytes(in);
+      try {
+        if (result.length > out.remaining()) {
+          throw new BufferOverflowException();
+        }
+        out.put(result);
+      } finally {
+        Arrays.fill(encrypt ? in : result, (byte) 0);
+      }
+      written = result.length;
+    }
+    input.position(input.limit());
+    return written;
+  }
+
   /** True async encrypt — runs on Rust's tokio runtime, does not block a Java thread. */
   public CompletableFuture<byte[]> encryptBytesAsync(byte[] plaintext) {
     ensureOpen();
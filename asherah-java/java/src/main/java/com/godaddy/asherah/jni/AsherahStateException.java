@@ -0,0 +1,10 @@
+package com.godaddy.asherah.jni;
+
+/** The native library was called before setup, after shutdown, or twice. */
+public class AsherahStateException extends AsherahException {
+  private static final long serialVersionUID = 1L;
+
+  public AsherahStateException(AsherahErrorCode errorCode, String message) {
+    super(errorCode, message);
+  }
+}
@@ -0,0 +1,102 @@
+package com.godaddy.asherah.jni;
+
+import java.io.IOException;
+import java.io.InputStream;
+import java.util.Arrays;
+import java.util.Objects;
+
+/**
+ * Decrypts an Asherah stream read from another stream, one read at a time. Created by {@link
+ * AsherahSession#decryptingStream}.
+ */
+final class DecryptingInputStream extends InputStream {
+  private static final int READ_SIZE = 64 * 1024;
+
+  private final InputStream in;
+  private final StreamHandle handle;
+  private final byte[] readBuffer = new byte[READ_SIZE];
+  private byte[] plaintext = new byte[0];
+  private int position;
+  private boolean eof;
+  private boolean closed;
+
+  DecryptingInputStream(long sessionHandle, InputStream in) {
+    this.in = in;
+    this.handle =
+        StreamHandle.register(
+            this,
+            AsherahNative.streamDecryptorNew(sessionHandle),
+            AsherahNative::streamDecryptorFree);
+  }
+
+  @Override
+  public int read() throws IOException {
+    final byte[] one = new byte[1];
+    return read(one, 0, 1) < 0 ? -1 : one[0] & 0xff;
+  }
+
+  /**
+   * Reads decrypted bytes. Throws {@link AsherahException} if the stream has been tampered
+   * with, or ends before its last frame.
+   */
+  @Override
+  public int read(byte[] b, int off, int len) throws IOException {
+    Objects.checkFromIndexSize(off, len, b.length);
+    ensureOpen();
+    if (len == 0) {
+      return 0;
+    }
+    while (position == plaintext.length) {
+      if (!fill()) {
+        return -1;
+      }
+    }
+    final int n = Math.min(len, plaintext.length - position);
+    System.arraycopy(plaintext, position, b, off, n);
+    position += n;
+    return n;
+  }
+
+  @Override
+  public int available() throws IOException {
+    ensureOpen();
+    return plaintext.length - position;
+  }
+
+  @Override
+  public void close() throws IOException {
+    if (closed) {
+      return;
+    }
+    closed = true;
+    Arrays.fill(plaintext, (byte) 0);
+    try {
+      in.close();
+    } finally {
+      handle.close();
+    }
+  }
+
+  /** Decrypts the next read from the underlying stream. False at the end of the stream. */
+  private boolean fill() throws IOException {
+    if (eof) {
+      return false;
+    }
+    final int n = in.read(readBuffer);
+    if (n < 0) {
+      eof = true;
+      AsherahNative.streamDecryptorFinish(handle.peek());
+      return false;
+    }
+    Arrays.fill(plaintext, (byte) 0);
+    plaintext = AsherahNative.streamDecryptorUpdate(handle.peek(), readBuffer, 0, n);
+    position = 0;
+    return true;
+  }
+
+  private void ensureOpen() throws IOException {
+    if (closed) {
+      throw new IOException("Stream closed");
+    }
+  }
+}
@@ -0,0 +1,74 @@
+package com.godaddy.asherah.jni;
+
+import java.io.IOException;
+import java.io.OutputStream;
+import java.util.Objects;
+
+/**
+ * Encrypts everything written to it as an Asherah stream, writing the result to another stream
+ * one chunk at a time. Created by {@link AsherahSession#encryptingStream}.
+ */
+final class EncryptingOutputStream extends OutputStream {
+  private final OutputStream out;
+  private final StreamHandle handle;
+  private boolean closed;
+
+  EncryptingOutputStream(long sessionHandle, OutputStream out, int chunkSize) {
+    this.out = out;
+    this.handle =
+        StreamHandle.register(
+            this,
+            AsherahNative.streamEncryptorNew(sessionHandle, chunkSize),
+            AsherahNative::streamEncryptorFree);
+  }
+
+  @Override
+  public void write(int b) throws IOException {
+    write(new byte[] {(byte) b}, 0, 1);
+  }
+
+  @Override
+  public void write(byte[] b, int off, int len) throws IOException {
+    Objects.checkFromIndexSize(off, len, b.length);
+    ensureOpen();
+    if (len > 0) {
+      writeFrames(AsherahNative.streamEncryptorUpdate(handle.peek(), b, off, len));
+    }
+  }
+
+  /**
+   * Flushes the underlying stream. Plaintext short of a full chunk stays buffered until more
+   * arrives or the stream is closed.
+   */
+  @Override
+  public void flush() throws IOException {
+    ensureOpen();
+    out.flush();
+  }
+
+  /** Writes the last frame and closes the underlying stream. An unclosed stream won't decrypt. */
+  @Override
+  public void close() throws IOException {
+    if (closed) {
+      return;
+    }
+    closed = true;
+    try (OutputStream target = out) {
+      writeFrames(AsherahNative.streamEncryptorFinish(handle.peek()));
+    } finally {
+      handle.close();
+    }
+  }
+
+  private void writeFrames(byte[] frames) throws IOException {
+    if (frames.length > 0) {
+      out.write(frames);
+    }
+  }
+
+  private void ensureOpen() throws IOException {
+    if (closed) {
+      throw new IOException("Stream closed");
+    }
+  }
+}
@@ -0,0 +1,56 @@
+package com.godaddy.asherah.jni;
+
+import java.lang.ref.Cleaner;
+import java.util.function.LongConsumer;
+
+/**
+ * A native stream handle. It is freed once: by {@link #close}, or by the cleaner if its stream
+ * is dropped without being closed.
+ */
+final class StreamHandle implements Runnable {
+  private static final Cleaner CLEANER = Cleaner.create();
+
+  private final Object lock = new Object();
+  private final LongConsumer free;
+  private long handle;
+  private Cleaner.Cleanable cleanable;
+
+  private StreamHandle(long handle, LongConsumer free) {
+    this.handle = handle;
+    this.free = free;
+  }
+
+  static StreamHandle register(Object owner, long handle, LongConsumer free) {
+    if (handle == 0) {
+      throw new IllegalStateException("Native stream handle is null");
+    }
+    final StreamHandle streamHandle = new StreamHandle(handle, free);
+    streamHandle.cleanable = CLEANER.register(owner, streamHandle);
+    return streamHandle;
+  }
+
+  long peek() {
+    synchronized (lock) {
+      if (handle == 0) {
+        throw new IllegalStateException("Stream has been closed");
+      }
+      return handle;
+    }
+  }
+
+  void close() {
+    cleanable.clean();
+  }
+
+  @Override
+  public void run() {
+    final long value;
+    synchronized (lock) {
+      value = handle;
+      handle = 0;
+    }
+    if (value != 0) {
+      free.accept(value);
+    }
+  }
+}
@@ -0,0 +1,94 @@
+package com.godaddy.asherah.jni;
+
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertFalse;
+import static org.junit.jupiter.api.Assertions.assertInstanceOf;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.concurrent.CompletionException;
+
+import org.junit.jupiter.api.BeforeAll;
+import org.junit.jupiter.api.Test;
+
+class AsherahErrorsTest {
+
+  @BeforeAll
+  static void configureLibraryPath() {
+    if (System.getProperty("asherah.java.nativeLibraryPath") == null) {
+      final Path defaultDir = Paths.get("..", "..", "target", "debug").toAbsolutePath().normalize();
+      System.setProperty("asherah.java.nativeLibraryPath", defaultDir.toString());
+    }
+  }
+
+  private static AsherahConfig.Builder config() {
+    return AsherahConfig.builder()
+        .serviceName("errors-svc")
+        .productId("errors-prod")
+        .metastore("memory")
+        .kms("test-debug-static");
+  }
+
+  @Test
+  void codesAreStable() {
+    assertEquals(700, AsherahErrorCode.DECRYPTION_FAILED.getCode());
+    assertEquals(AsherahErrorCode.KMS_ACCESS_DENIED, AsherahErrorCode.fromCode(601));
+    assertEquals(AsherahErrorCode.UNKNOWN, AsherahErrorCode.fromCode(-42));
+    assertTrue(AsherahErrorCode.METASTORE_UNAVAILABLE.isRetryable());
+    assertTrue(AsherahErrorCode.KMS_UNAVAILABLE.isRetryable());
+    assertFalse(AsherahErrorCode.KMS_ACCESS_DENIED.isRetryable());
+    assertInstanceOf(AsherahKmsException.class, AsherahException.create(601, "denied"));
+  }
+
+  @Test
+  void sessionFailuresAreTyped() {
+    try (AsherahFactory factory = Asherah.factoryFromConfig(config().build());
+        AsherahSession session = factory.getSession("partition-1");
+        AsherahSession other = factory.getSession("partition-2")) {
+      final String drr = session.encryptString("secret");
+
+      AsherahInputException invalid =
+          assertThrows(AsherahInputException.class, () -> session.decryptString("{not json"));
+      assertEquals(AsherahErrorCode.INVALID_DATA_ROW_RECORD, invalid.getErrorCode());
+      assertEquals(AsherahErrorCategory.INPUT, invalid.getCategory());
+      assertFalse(invalid.isRetryable());
+
+      AsherahInputException mismatch =
+          assertThrows(AsherahInputException.class, () -> other.decryptString(drr));
+      assertEquals(AsherahErrorCode.PARTITION_MISMATCH, mismatch.getErrorCode());
+
+      final String tampered =
+          drr.replaceFirst("\"Data\":\"[^\"]+\"", "\"Data\":\"" + "A".repeat(32) + "\"");
+      AsherahDecryptionException decryption =
+          assertThrows(AsherahDecryptionException.class, () -> session.decryptString(tampered));
+      assertEquals(AsherahErrorCode.DECRYPTION_FAILED, decryption.getErrorCode());
+    }
+  }
+
+  @Test
+  void asyncFailuresAreTyped() {
+    try (AsherahFactory factory = Asherah.factoryFromConfig(config().build());
+        AsherahSession session = factory.getSession("partition-1")) {
+      CompletionException err =
+          assertThrows(
+              CompletionException.class,
+              () -> session.decryptBytesAsync("{not json".getBytes(StandardCharsets.UTF_8)).join());
+      AsherahInputException cause = assertInstanceOf(AsherahInputException.class, err.getCause());
+      assertEquals(AsherahErrorCode.INVALID_DATA_ROW_RECORD, cause.getErrorCode());
+    }
+  }
+
+  @Test
+  void invalidConfigIsTyped() {
+    AsherahConfigException err =
+        assertThrows(
+            AsherahConfigException.class,
+            () -> Asherah.factoryFromConfig(config().metastore("nope").build()));
+    assertEquals(AsherahErrorCode.INVALID_CONFIG, err.getErrorCode());
+    // Still a RuntimeException, as before typed exceptions existed.
+    assertInstanceOf(RuntimeException.class, err);
+  }
+}
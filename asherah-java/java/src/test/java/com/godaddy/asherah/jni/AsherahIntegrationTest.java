@@ -2,8 +2,10 @@ package com.godaddy.asherah.jni;
 
 import static org.junit.jupiter.api.Assertions.assertArrayEquals;
 import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertFalse;
 import static org.junit.jupiter.api.Assertions.assertNotNull;
 import static org.junit.jupiter.api.Assertions.assertThrows;
+import static org.junit.jupiter.api.Assertions.assertTrue;
 
 import java.nio.charset.StandardCharsets;
 import java.nio.file.Files;
@@ -431,6 +433,42 @@ class AsherahIntegrationTest {
     }
   }
 
+  @Test
+  void factoryCacheStatsEvictAndFlush() {
+    final AsherahConfig config =
+        AsherahConfig.builder()
+            .serviceName("cache-test")
+            .productId("prod")
+            .metastore("memory")
+            .kms("test-debug-static")
+            .enableSessionCaching(Boolean.TRUE)
+            .build();
+    try (AsherahFactory factory = Asherah.factoryFromConfig(config)) {
+      for (String partition : new String[] {"cache-a", "cache-a", "cache-b"}) {
+        try (AsherahSession session = factory.getSession(partition)) {
+          session.encryptToJson("x".getBytes(StandardCharsets.UTF_8));
+        }
+      }
+      AsherahCacheStats stats = factory.cacheStats();
+      assertEquals(2, stats.getSessions());
+      assertEquals(1, stats.getSessionCacheHits());
+      assertEquals(2, stats.getSessionCacheMisses());
+      assertEquals(2, stats.getIntermediateKeys());
+      assertEquals(1, stats.getSystemKeys());
+
+      assertTrue(factory.evictSession("cache-b"));
+      assertFalse(factory.evictSession("cache-b"));
+      assertEquals(1, factory.cacheStats().getSessions());
+
+      factory.flushCaches();
+      stats = factory.cacheStats();
+      assertEquals(0, stats.getSessions());
+      assertEquals(0, stats.getIntermediateKeys());
+      assertEquals(0, stats.getSystemKeys());
+      assertEquals(1, stats.getSessionCacheHits());
+    }
+  }
+
   @Test
   void staticEncryptNullPartitionThrows() {
     withSetup(() -> {
@@ -0,0 +1,141 @@
+package com.godaddy.asherah.jni;
+
+import static org.junit.jupiter.api.Assertions.assertArrayEquals;
+import static org.junit.jupiter.api.Assertions.assertEquals;
+import static org.junit.jupiter.api.Assertions.assertThrows;
+import static org.junit.jupiter.api.Assertions.assertTrue;
+
+import java.io.ByteArrayInputStream;
+import java.io.ByteArrayOutputStream;
+import java.io.IOException;
+import java.io.InputStream;
+import java.io.OutputStream;
+import java.nio.BufferOverflowException;
+import java.nio.ByteBuffer;
+import java.nio.charset.StandardCharsets;
+import java.nio.file.Path;
+import java.nio.file.Paths;
+import java.util.Arrays;
+
+import org.junit.jupiter.api.AfterEach;
+import org.junit.jupiter.api.BeforeAll;
+import org.junit.jupiter.api.BeforeEach;
+import org.junit.jupiter.api.Test;
+
+class AsherahStreamingTest {
+  private AsherahFactory factory;
+  private AsherahSession session;
+
+  @BeforeAll
+  static void configureLibraryPath() {
+    if (System.getProperty("asherah.java.nativeLibraryPath") == null) {
+      final Path defaultDir = Paths.get("..", "..", "target", "debug").toAbsolutePath().normalize();
+      System.setProperty("asherah.java.nativeLibraryPath", defaultDir.toString());
+    }
+  }
+
+  @BeforeEach
+  void open() {
+    factory =
+        Asherah.factoryFromConfig(
+            AsherahConfig.builder()
+                .serviceName("stream-svc")
+                .productId("prod")
+                .metastore("memory")
+                .kms("test-debug-static")
+                .build());
+    session = factory.getSession("stream-partition");
+  }
+
+  @AfterEach
+  void close() {
+    session.close();
+    factory.close();
+  }
+
+  @Test
+  void directBuffersRoundTrip() {
+    final byte[] payload = "direct buffer payload".getBytes(StandardCharsets.UTF_8);
+    final ByteBuffer plaintext = ByteBuffer.allocateDirect(payload.length).put(payload).flip();
+    final ByteBuffer ciphertext =
+        ByteBuffer.allocateDirect(session.encryptedLengthBound(payload.length));
+
+    final int written = session.encrypt(plaintext, ciphertext);
+    assertEquals(written, ciphertext.position());
+    assertEquals(0, plaintext.remaining());
+    ciphertext.flip();
+
+    // The JSON is an ordinary record.
+    final byte[] json = new byte[ciphertext.remaining()];
+    ciphertext.duplicate().get(json);
+    assertArrayEquals(payload, session.decryptBytes(json));
+
+    final ByteBuffer decrypted = ByteBuffer.allocateDirect(payload.length);
+    assertEquals(payload.length, session.decrypt(ciphertext, decrypted));
+    assertEquals(0, ciphertext.remaining());
+    decrypted.flip();
+    final byte[] roundTripped = new byte[decrypted.remaining()];
+    decrypted.get(roundTripped);
+    assertArrayEquals(payload, roundTripped);
+  }
+
+  @Test
+  void shortOutputBufferOverflowsWithoutMovingPositions() {
+    final ByteBuffer plaintext = ByteBuffer.allocateDirect(8).put(new byte[8]).flip();
+    final ByteBuffer small = ByteBuffer.allocateDirect(16);
+    assertThrows(BufferOverflowException.class, () -> session.encrypt(plaintext, small));
+    assertEquals(0, plaintext.position());
+    assertEquals(0, small.position());
+
+    final ByteBuffer json = ByteBuffer.wrap(session.encryptBytes(new byte[64]));
+    final ByteBuffer tooSmall = ByteBuffer.allocate(63);
+    assertThrows(BufferOverflowException.class, () -> session.decrypt(json, tooSmall));
+    assertEquals(0, json.position());
+  }
+
+  @Test
+  void heapBuffersUseTheArrayPath() {
+    final byte[] payload = "heap buffer payload".getBytes(StandardCharsets.UTF_8);
+    final ByteBuffer ciphertext = ByteBuffer.allocate(session.encryptedLengthBound(payload.length));
+    session.encrypt(ByteBuffer.wrap(payload), ciphertext);
+    ciphertext.flip();
+    final ByteBuffer decrypted = ByteBuffer.allocateDirect(payload.length);
+    session.decrypt(ciphertext, decrypted);
+    assertEquals(payload.length, decrypted.position());
+  }
+
+  @Test
+  void streamsRoundTripInChunks() throws IOException {
+    final byte[] payload = new byte[300_000];
+    for (int i = 0; i < payload.length; i++) {
+      payload[i] = (byte) (i % 251);
+    }
+    final ByteArrayOutputStream sink = new ByteArrayOutputStream();
+    try (OutputStream out = session.encryptingStream(sink, 4096)) {
+      out.write(payload, 0, 1000);
+      out.write(payload[1000]);
+      out.write(payload, 1001, payload.length - 1001);
+    }
+    final byte[] stream = sink.toByteArray();
+    assertTrue(stream.length > payload.length);
+
+    try (InputStream in = session.decryptingStream(new ByteArrayInputStream(stream))) {
+      assertArrayEquals(payload, in.readAllBytes());
+    }
+  }
+
+  @Test
+  void truncatedStreamsFail() throws IOException {
+    final ByteArrayOutputStream sink = new ByteArrayOutputStream();
+    try (OutputStream out = session.encryptingStream(sink, 1024)) {
+      out.write(new byte[10_000]);
+    }
+    final byte[] stream = sink.toByteArray();
+    final byte[] truncated = Arrays.copyOf(stream, stream.length - 100);
+    try (InputStream in = session.decryptingStream(new ByteArrayInputStream(truncated))) {
+      assertThrows(AsherahException.class, in::readAllBytes);
+    }
+    assertThrows(
+        AsherahException.class, () -> session.encryptingStream(new ByteArrayOutputStream(), 0));
+  }
+}
@@ -1,13 +1,14 @@
 #![allow(non_snake_case)]
 #![allow(unsafe_code)]
 
+use ael::error::ErrorCode;
 use anyhow::Context;
 use asherah as ael;
 use asherah_config as config;
 use jni::errors::ThrowRuntimeExAndDefault;
-use jni::objects::{JByteArray, JClass, JObject, JString};
+use jni::objects::{JByteArray, JByteBuffer, JClass, JLongArray, JObject, JString, JThrowable};
 use jni::strings::JNIString;
-use jni::sys::jlong;
+use jni::sys::{jboolean, jint, jlong};
 use jni::{EnvUnowned, JavaVM};
 use once_cell::sync::Lazy;
 use serde_json::{self, Value};
@@ -38,12 +39,52 @@ unsafe fn from_handle<'handle, T>(handle: jlong) -> Option<&'handle T> {
     (handle as *const T).as_ref()
 }
 
-/// Throw a RuntimeException with the given message and return `Error::JavaException`.
-fn throw_err(env: &mut jni::Env<'_>, msg: impl std::fmt::Display) -> jni::errors::Error {
-    drop(env.throw_new(
-        JNIString::from("java/lang/RuntimeException"),
-        JNIString::from(msg.to_string()),
-    ));
+/// Build the `AsherahException` subclass for `code` through
+/// `AsherahException.create`, which picks the class by category.
+fn new_asherah_exception<'local>(
+    env: &mut jni::Env<'local>,
+    code: ErrorCode,
+    msg: &str,
+) -> jni::errors::Result<JObject<'local>> {
+    let class = env.find_class(JNIString::from("com/godaddy/asherah/jni/AsherahException"))?;
+    let jmsg = env.new_string(msg)?;
+    let sig = jni::signature::RuntimeMethodSignature::from_str(
+        "(ILjava/lang/String;)Lcom/godaddy/asherah/jni/AsherahException;",
+    )?;
+    env.call_static_method(
+        class,
+        JNIString::from("create"),
+        sig.method_signature(),
+        &[
+            jni::objects::JValue::Int(code.as_i32()),
+            jni::objects::JValue::Object(&jmsg.into()),
+        ],
+    )?
+    .l()
+}
+
+/// Throw an `AsherahException` for `code` with the given message and return
+/// `Error::JavaException`. Falls back to a plain RuntimeException if the
+/// exception class can't be loaded.
+fn throw_err(
+    env: &mut jni::Env<'_>,
+    code: ErrorCode,
+    msg: impl std::fmt::Display,
+) -> jni::errors::Error {
+    let msg = msg.to_string();
+    let exception = new_asherah_exception(env, code, &msg)
+        .and_then(|exception| env.cast_local::<JThrowable<'_>>(exception));
+    match exception {
+        // `throw` reports success as `Error::JavaException` too.
+        Ok(throwable) => drop(env.throw(throwable)),
+        Err(_) => {
+            env.exception_clear();
+            drop(env.throw_new(
+                JNIString::from("java/lang/RuntimeException"),
+                JNIString::from(msg),
+            ));
+        }
+    }
     jni::errors::Error::JavaException
 }
 
@@ -57,22 +98,27 @@ fn throw_anyhow(
     err: anyhow::Error,
 ) -> jni::errors::Error {
     log::warn!("{op} failed: {err:#}");
-    throw_err(env, sanitized_anyhow_message(op, &err))
+    throw_err(env, ErrorCode::of(&err), sanitized_anyhow_message(op, &err))
 }
 
 #[allow(deprecated)]
 fn jbyte_array_len(env: &mut jni::Env<'_>, array: &JByteArray<'_>) -> jni::errors::Result<usize> {
     let len = env.get_array_length(array)?;
-    usize::try_from(len)
-        .map_err(|_| throw_err(env, format_args!("Java byte array length {len} is invalid")))
+    usize::try_from(len).map_err(|_| {
+        throw_err(
+            env,
+            ErrorCode::InvalidArgument,
+            format_args!("Java byte array length {len} is invalid"),
+        )
+    })
 }
 
 fn check_plaintext_len_jni(env: &mut jni::Env<'_>, len: usize) -> jni::errors::Result<()> {
-    ael::limits::check_plaintext_len(len).map_err(|err| throw_err(env, err))
+    ael::limits::check_plaintext_len(len).map_err(|err| throw_err(env, ErrorCode::of(&err), err))
 }
 
 fn check_ciphertext_len_jni(env: &mut jni::Env<'_>, len: usize) -> jni::errors::Result<()> {
-    ael::limits::check_ciphertext_len(len).map_err(|err| throw_err(env, err))
+    ael::limits::check_ciphertext_len(len).map_err(|err| throw_err(env, ErrorCode::of(&err), err))
 }
 
 #[allow(deprecated)]
@@ -125,7 +171,7 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_closeFactory(
 ) {
     env.with_env(|env| -> jni::errors::Result<()> {
         let factory = unsafe { from_handle::<Factory>(factory_handle) }
-            .ok_or_else(|| throw_err(env, "factory handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "factory handle is null"))?;
         factory
             .close()
             .map_err(|e| throw_anyhow(env, "factory close", e))?;
@@ -190,7 +236,7 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_getSession(
 ) -> jlong {
     env.with_env(|env| -> jni::errors::Result<jlong> {
         let factory = unsafe { from_handle::<Factory>(factory_handle) }
-            .ok_or_else(|| throw_err(env, "factory handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "factory handle is null"))?;
         let partition = get_jstring(env, &partition_id)?;
         let session = factory.get_session(&partition);
         let shared = SharedJniSession {
@@ -201,6 +247,64 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_getSession(
     .resolve::<ThrowRuntimeExAndDefault>()
 }
 
+/// Cache sizes and hit counts as `[sessions, sessionCacheHits,
+/// sessionCacheMisses, intermediateKeys, systemKeys]`, the order of the
+/// `AsherahCacheStats` constructor.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_cacheStats<'caller>(
+    mut env: EnvUnowned<'caller>,
+    _class: JClass<'caller>,
+    factory_handle: jlong,
+) -> JLongArray<'caller> {
+    env.with_env(|env| -> jni::errors::Result<JLongArray<'caller>> {
+        let factory = unsafe { from_handle::<Factory>(factory_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "factory handle is null"))?;
+        let stats = factory.cache_stats();
+        let values = [
+            stats.sessions as jlong,
+            stats.session_cache_hits as jlong,
+            stats.session_cache_misses as jlong,
+            stats.intermediate_keys as jlong,
+            stats.system_keys as jlong,
+        ];
+        let arr = env.new_long_array(values.len())?;
+        arr.set_region(env, 0, &values)?;
+        Ok(arr)
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_evictSession(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    factory_handle: jlong,
+    partition_id: JString<'_>,
+) -> jboolean {
+    env.with_env(|env| -> jni::errors::Result<jboolean> {
+        let factory = unsafe { from_handle::<Factory>(factory_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "factory handle is null"))?;
+        let partition = get_jstring(env, &partition_id)?;
+        Ok(factory.evict_session(&partition))
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_flushCaches(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    factory_handle: jlong,
+) {
+    env.with_env(|env| -> jni::errors::Result<()> {
+        let factory = unsafe { from_handle::<Factory>(factory_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "factory handle is null"))?;
+        factory.flush_caches();
+        Ok(())
+    })
+    .resolve::<ThrowRuntimeExAndDefault>();
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_closeSession(
     mut env: EnvUnowned<'_>,
@@ -209,7 +313,7 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_closeSession(
 ) {
     env.with_env(|env| -> jni::errors::Result<()> {
         let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
-            .ok_or_else(|| throw_err(env, "session handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
         shared
             .session
             .close()
@@ -259,7 +363,7 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_encrypt<'calle
 ) -> JByteArray<'caller> {
     env.with_env(|env| -> jni::errors::Result<JByteArray<'caller>> {
         let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
-            .ok_or_else(|| throw_err(env, "session handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
         let plaintext_len = jbyte_array_len(env, &plaintext)?;
         check_plaintext_len_jni(env, plaintext_len)?;
         let data = Zeroizing::new(env.convert_byte_array(&plaintext)?);
@@ -267,8 +371,13 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_encrypt<'calle
             .session
             .encrypt(&data)
             .map_err(|e| throw_anyhow(env, "encrypt", e))?;
-        let ciphertext = serde_json::to_vec(&drr)
-            .map_err(|e| throw_err(env, format_args!("encrypt serialization error: {e}")))?;
+        let ciphertext = serde_json::to_vec(&drr).map_err(|e| {
+            throw_err(
+                env,
+                ErrorCode::Internal,
+                format_args!("encrypt serialization error: {e}"),
+            )
+        })?;
         let arr = env.byte_array_from_slice(&ciphertext)?;
         Ok(arr)
     })
@@ -284,12 +393,17 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_decrypt<'calle
 ) -> JByteArray<'caller> {
     env.with_env(|env| -> jni::errors::Result<JByteArray<'caller>> {
         let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
-            .ok_or_else(|| throw_err(env, "session handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
         let ciphertext_len = jbyte_array_len(env, &ciphertext)?;
         check_ciphertext_len_jni(env, ciphertext_len)?;
         let data = env.convert_byte_array(&ciphertext)?;
-        let drr: ael::types::DataRowRecord = serde_json::from_slice(&data)
-            .map_err(|e| throw_err(env, format_args!("invalid DataRowRecord JSON: {e}")))?;
+        let drr: ael::types::DataRowRecord = serde_json::from_slice(&data).map_err(|e| {
+            throw_err(
+                env,
+                ErrorCode::InvalidDataRowRecord,
+                format_args!("invalid DataRowRecord JSON: {e}"),
+            )
+        })?;
         let plaintext = Zeroizing::new(
             shared
                 .session
@@ -302,6 +416,333 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_decrypt<'calle
     .resolve::<ThrowRuntimeExAndDefault>()
 }
 
+// ── Direct ByteBuffers ───────────────────────────────────────────────
+
+/// Address and length of `len` bytes at `pos` in a direct buffer. Java
+/// checks the bounds against the buffer's limit; this re-checks them against
+/// its capacity.
+fn direct_range(
+    env: &mut jni::Env<'_>,
+    buf: &JByteBuffer<'_>,
+    pos: jint,
+    len: jint,
+) -> jni::errors::Result<(*mut u8, usize)> {
+    let addr = env.get_direct_buffer_address(buf)?;
+    let capacity = env.get_direct_buffer_capacity(buf)?;
+    match (usize::try_from(pos), usize::try_from(len)) {
+        (Ok(pos), Ok(len)) if pos.checked_add(len).is_some_and(|end| end <= capacity) => {
+            Ok((addr.wrapping_add(pos), len))
+        }
+        _ => Err(throw_err(
+            env,
+            ErrorCode::InvalidArgument,
+            format_args!("range {pos}+{len} is outside a buffer of {capacity} bytes"),
+        )),
+    }
+}
+
+/// Copy `bytes` to `out`, returning their length, or minus that length if
+/// they don't fit. The caller's buffers may overlap, so output goes through
+/// a raw pointer once the input is no longer borrowed.
+fn put_direct(bytes: &[u8], (out, capacity): (*mut u8, usize)) -> jint {
+    // Output is bounded by the envelope limit, far below `jint::MAX`.
+    let len = bytes.len() as jint;
+    if bytes.len() > capacity {
+        return -len;
+    }
+    // SAFETY: `out` has room for `capacity` bytes (`direct_range`), and
+    // `bytes` is native memory.
+    unsafe { std::ptr::copy_nonoverlapping(bytes.as_ptr(), out, bytes.len()) };
+    len
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_encryptedLengthBound(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    session_handle: jlong,
+    plaintext_len: jint,
+) -> jint {
+    env.with_env(|env| -> jni::errors::Result<jint> {
+        let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
+        let len = usize::try_from(plaintext_len).map_err(|_| {
+            throw_err(
+                env,
+                ErrorCode::InvalidArgument,
+                "plaintext length is negative",
+            )
+        })?;
+        check_plaintext_len_jni(env, len)?;
+        let bound =
+            ael::types::DataRowRecord::json_len_bound(shared.session.intermediate_key_id(), len);
+        jint::try_from(bound).map_err(|_| {
+            throw_err(
+                env,
+                ErrorCode::PayloadTooLarge,
+                "encrypted length exceeds a Java buffer",
+            )
+        })
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_encryptDirect(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    session_handle: jlong,
+    plaintext: JByteBuffer<'_>,
+    plaintext_pos: jint,
+    plaintext_len: jint,
+    out: JByteBuffer<'_>,
+    out_pos: jint,
+    out_len: jint,
+) -> jint {
+    env.with_env(|env| -> jni::errors::Result<jint> {
+        let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
+        let (addr, len) = direct_range(env, &plaintext, plaintext_pos, plaintext_len)?;
+        // SAFETY: in bounds of the buffer, which the Java caller keeps
+        // reachable for the duration of the call.
+        let data = unsafe { std::slice::from_raw_parts(addr.cast_const(), len) };
+        check_plaintext_len_jni(env, data.len())?;
+        let out = direct_range(env, &out, out_pos, out_len)?;
+        let drr = shared
+            .session
+            .encrypt(data)
+            .map_err(|e| throw_anyhow(env, "encrypt", e))?;
+        Ok(put_direct(drr.to_json_fast().as_bytes(), out))
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_decryptDirect(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    session_handle: jlong,
+    ciphertext: JByteBuffer<'_>,
+    ciphertext_pos: jint,
+    ciphertext_len: jint,
+    out: JByteBuffer<'_>,
+    out_pos: jint,
+    out_len: jint,
+) -> jint {
+    env.with_env(|env| -> jni::errors::Result<jint> {
+        let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
+        let (addr, len) = direct_range(env, &ciphertext, ciphertext_pos, ciphertext_len)?;
+        // SAFETY: in bounds of the buffer, which the Java caller keeps
+        // reachable for the duration of the call.
+        let data = unsafe { std::slice::from_raw_parts(addr.cast_const(), len) };
+        check_ciphertext_len_jni(env, data.len())?;
+        let out = direct_range(env, &out, out_pos, out_len)?;
+        let drr: ael::types::DataRowRecord = serde_json::from_slice(data).map_err(|e| {
+            throw_err(
+                env,
+                ErrorCode::InvalidDataRowRecord,
+                format_args!("invalid DataRowRecord JSON: {e}"),
+            )
+        })?;
+        // Known before decrypting, so a short buffer costs no decrypt.
+        let needed = drr.plaintext_len();
+        if needed > out.1 {
+            return Ok(-(needed as jint));
+        }
+        let plaintext = Zeroizing::new(
+            shared
+                .session
+                .decrypt(drr)
+                .map_err(|e| throw_anyhow(env, "decrypt", e))?,
+        );
+        Ok(put_direct(&plaintext, out))
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+// ── Streams ──────────────────────────────────────────────────────────
+
+/// A `StreamEncryptor` behind a handle. The Java streams are not
+/// thread-safe, but a lock keeps misuse from racing in native code.
+struct JniStreamEncryptor {
+    inner: PMutex<ael::stream::StreamEncryptor>,
+}
+
+/// A `StreamDecryptor` with the session that opens its key. Holding the
+/// session keeps it alive if the Java session is closed mid-stream.
+struct JniStreamDecryptor {
+    session: Arc<Session>,
+    inner: PMutex<ael::stream::StreamDecryptor>,
+}
+
+/// `len` bytes at `off` in a Java byte array.
+fn byte_array_region(
+    env: &mut jni::Env<'_>,
+    array: &JByteArray<'_>,
+    off: jint,
+    len: jint,
+) -> jni::errors::Result<Zeroizing<Vec<u8>>> {
+    let len = usize::try_from(len)
+        .map_err(|_| throw_err(env, ErrorCode::InvalidArgument, "length is negative"))?;
+    let mut region = Zeroizing::new(vec![0_i8; len]);
+    array.get_region(env, off, &mut region)?;
+    Ok(Zeroizing::new(region.iter().map(|&b| b as u8).collect()))
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamEncryptorNew(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    session_handle: jlong,
+    chunk_size: jint,
+) -> jlong {
+    env.with_env(|env| -> jni::errors::Result<jlong> {
+        let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
+        let chunk_size = usize::try_from(chunk_size).unwrap_or(0);
+        let encryptor = ael::stream::StreamEncryptor::new(&shared.session, chunk_size)
+            .map_err(|e| throw_anyhow(env, "stream encrypt", e))?;
+        let handle = JniStreamEncryptor {
+            inner: PMutex::new(encryptor),
+        };
+        Ok(Box::into_raw(Box::new(handle)) as jlong)
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamEncryptorUpdate<'caller>(
+    mut env: EnvUnowned<'caller>,
+    _class: JClass<'caller>,
+    handle: jlong,
+    data: JByteArray<'caller>,
+    off: jint,
+    len: jint,
+) -> JByteArray<'caller> {
+    env.with_env(|env| -> jni::errors::Result<JByteArray<'caller>> {
+        let encryptor = unsafe { from_handle::<JniStreamEncryptor>(handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "stream handle is null"))?;
+        let data = byte_array_region(env, &data, off, len)?;
+        let mut out = Vec::new();
+        encryptor
+            .inner
+            .lock()
+            .update(&data, &mut out)
+            .map_err(|e| throw_anyhow(env, "stream encrypt", e))?;
+        env.byte_array_from_slice(&out)
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamEncryptorFinish<'caller>(
+    mut env: EnvUnowned<'caller>,
+    _class: JClass<'caller>,
+    handle: jlong,
+) -> JByteArray<'caller> {
+    env.with_env(|env| -> jni::errors::Result<JByteArray<'caller>> {
+        let encryptor = unsafe { from_handle::<JniStreamEncryptor>(handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "stream handle is null"))?;
+        let mut out = Vec::new();
+        encryptor
+            .inner
+            .lock()
+            .finish(&mut out)
+            .map_err(|e| throw_anyhow(env, "stream encrypt", e))?;
+        env.byte_array_from_slice(&out)
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+/// Free a handle from `streamEncryptorNew`. Single-free is the Java side's
+/// job, as for `freeSession`.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamEncryptorFree(
+    _env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        unsafe { drop(Box::from_raw(handle as *mut JniStreamEncryptor)) }
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamDecryptorNew(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    session_handle: jlong,
+) -> jlong {
+    env.with_env(|env| -> jni::errors::Result<jlong> {
+        let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
+        let handle = JniStreamDecryptor {
+            session: Arc::clone(&shared.session),
+            inner: PMutex::new(ael::stream::StreamDecryptor::new()),
+        };
+        Ok(Box::into_raw(Box::new(handle)) as jlong)
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamDecryptorUpdate<'caller>(
+    mut env: EnvUnowned<'caller>,
+    _class: JClass<'caller>,
+    handle: jlong,
+    data: JByteArray<'caller>,
+    off: jint,
+    len: jint,
+) -> JByteArray<'caller> {
+    env.with_env(|env| -> jni::errors::Result<JByteArray<'caller>> {
+        let decryptor = unsafe { from_handle::<JniStreamDecryptor>(handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "stream handle is null"))?;
+        let data = byte_array_region(env, &data, off, len)?;
+        let mut out = Zeroizing::new(Vec::new());
+        decryptor
+            .inner
+            .lock()
+            .update(&decryptor.session, &data, &mut out)
+            .map_err(|e| throw_anyhow(env, "stream decrypt", e))?;
+        env.byte_array_from_slice(&out)
+    })
+    .resolve::<ThrowRuntimeExAndDefault>()
+}
+
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamDecryptorFinish(
+    mut env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    handle: jlong,
+) {
+    env.with_env(|env| -> jni::errors::Result<()> {
+        let decryptor = unsafe { from_handle::<JniStreamDecryptor>(handle) }
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "stream handle is null"))?;
+        decryptor
+            .inner
+            .lock()
+            .finish()
+            .map_err(|e| throw_anyhow(env, "stream decrypt", e))
+    })
+    .resolve::<ThrowRuntimeExAndDefault>();
+}
+
+/// Free a handle from `streamDecryptorNew`. Single-free is the Java side's
+/// job, as for `freeSession`.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_streamDecryptorFree(
+    _env: EnvUnowned<'_>,
+    _class: JClass<'_>,
+    handle: jlong,
+) {
+    if handle != 0 {
+        unsafe { drop(Box::from_raw(handle as *mut JniStreamDecryptor)) }
+    }
+}
+
 // ── Async JNI ────────────────────────────────────────────────────────
 
 static ASYNC_RT: OnceLock<Result<tokio::runtime::Runtime, String>> = OnceLock::new();
@@ -317,9 +758,9 @@ fn build_async_runtime() -> std::io::Result<tokio::runtime::Runtime> {
 fn async_rt() -> anyhow::Result<&'static tokio::runtime::Runtime> {
     match ASYNC_RT.get_or_init(|| build_async_runtime().map_err(|e| e.to_string())) {
         Ok(rt) => Ok(rt),
-        Err(e) => Err(anyhow::anyhow!(
+        Err(e) => Err(ErrorCode::Internal.error(format_args!(
             "failed to create async JNI tokio runtime: {e}"
-        )),
+        ))),
     }
 }
 
@@ -335,7 +776,7 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_encryptAsync<'
 ) {
     env.with_env(|env| -> jni::errors::Result<()> {
         let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
-            .ok_or_else(|| throw_err(env, "session handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
         let plaintext_len = jbyte_array_len(env, &plaintext)?;
         check_plaintext_len_jni(env, plaintext_len)?;
         let data = Zeroizing::new(env.convert_byte_array(&plaintext)?);
@@ -353,8 +794,9 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_encryptAsync<'
 
         rt.spawn(async move {
             let result = match session_arc.encrypt_async(&data).await {
-                Ok(drr) => serde_json::to_vec(&drr)
-                    .map_err(|e| anyhow::anyhow!("encrypt serialization error: {e}")),
+                Ok(drr) => serde_json::to_vec(&drr).map_err(|e| {
+                    ErrorCode::Internal.error(format_args!("encrypt serialization error: {e}"))
+                }),
                 Err(e) => Err(e),
             };
             complete_java_future(&jvm, &future_ref, result);
@@ -376,7 +818,7 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_decryptAsync<'
 ) {
     env.with_env(|env| -> jni::errors::Result<()> {
         let shared = unsafe { from_handle::<SharedJniSession>(session_handle) }
-            .ok_or_else(|| throw_err(env, "session handle is null"))?;
+            .ok_or_else(|| throw_err(env, ErrorCode::InvalidArgument, "session handle is null"))?;
         let ciphertext_len = jbyte_array_len(env, &ciphertext)?;
         check_ciphertext_len_jni(env, ciphertext_len)?;
         let data = env.convert_byte_array(&ciphertext)?;
@@ -399,7 +841,8 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_decryptAsync<'
                     complete_java_future(
                         &jvm,
                         &future_ref,
-                        Err(anyhow::anyhow!("invalid DataRowRecord JSON: {e}")),
+                        Err(ErrorCode::InvalidDataRowRecord
+                            .error(format_args!("invalid DataRowRecord JSON: {e}"))),
                     );
                     return;
                 }
@@ -474,12 +917,18 @@ fn complete_java_future(
                 Err(ref e) => {
                     log::warn!("java async operation failed: {e:#}");
                     let msg = e.to_string();
-                    let jmsg = env.new_string(&msg)?;
-                    let exception = env.new_object(
-                        JNIString::from("java/lang/RuntimeException"),
-                        sigs.rt_ctor.method_signature(),
-                        &[jni::objects::JValue::Object(&jmsg.into())],
-                    )?;
+                    let exception = match new_asherah_exception(env, ErrorCode::of(e), &msg) {
+                        Ok(exception) => exception,
+                        Err(_) => {
+                            env.exception_clear();
+                            let jmsg = env.new_string(&msg)?;
+                            env.new_object(
+                                JNIString::from("java/lang/RuntimeException"),
+                                sigs.rt_ctor.method_signature(),
+                                &[jni::objects::JValue::Object(&jmsg.into())],
+                            )?
+                        }
+                    };
                     env.call_method(
                         future_ref.as_obj(),
                         JNIString::from("completeExceptionally"),
@@ -707,7 +1156,13 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_setLogHook(
         }
         let jvm = env.get_java_vm()?;
         let global = env.new_global_ref(&callback)?;
-        ensure_logger().map_err(|e| throw_err(env, format_args!("log init error: {e}")))?;
+        ensure_logger().map_err(|e| {
+            throw_err(
+                env,
+                ErrorCode::Internal,
+                format_args!("log init error: {e}"),
+            )
+        })?;
         let sink = AsyncLogSink::new(
             JavaLogSink,
             AsyncLogConfig {
@@ -715,7 +1170,13 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_setLogHook(
                 min_level: DEFAULT_LOG_HOOK_MIN_LEVEL,
             },
         )
-        .map_err(|e| throw_err(env, format_args!("log hook dispatcher error: {e}")))?;
+        .map_err(|e| {
+            throw_err(
+                env,
+                ErrorCode::Internal,
+                format_args!("log hook dispatcher error: {e}"),
+            )
+        })?;
         *JAVA_LOG_HOOK.lock() = Some(Arc::new(JavaHook {
             jvm,
             callback: global,
@@ -756,7 +1217,13 @@ pub extern "system" fn Java_com_godaddy_asherah_jni_AsherahNative_setMetricsHook
                 queue_capacity: NATIVE_HOOK_QUEUE_CAPACITY,
             },
         )
-        .map_err(|e| throw_err(env, format_args!("metrics hook dispatcher error: {e}")))?;
+        .map_err(|e| {
+            throw_err(
+                env,
+                ErrorCode::Internal,
+                format_args!("metrics hook dispatcher error: {e}"),
+            )
+        })?;
         *JAVA_METRICS_HOOK.lock() = Some(Arc::new(JavaHook {
             jvm,
             callback: global,
@@ -0,0 +1,248 @@
+//! Injectable failures: throttling, access denied, and internal errors,
+//! optionally scoped to one operation or one key, for a number of calls or
+//! until cleared.
+
+use std::fmt;
+use std::str::FromStr;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+/// The KMS operations the emulator implements.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Operation {
+    Encrypt,
+    Decrypt,
+    GenerateDataKey,
+}
+
+impl Operation {
+    pub const ALL: [Self; 3] = [Self::Encrypt, Self::Decrypt, Self::GenerateDataKey];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Encrypt => "Encrypt",
+            Self::Decrypt => "Decrypt",
+            Self::GenerateDataKey => "GenerateDataKey",
+        }
+    }
+
+    /// Parse an `X-Amz-Target` header value such as `TrentService.Encrypt`.
+    pub(crate) fn from_target(target: &str) -> Option<Self> {
+        target.strip_prefix("TrentService.")?.parse().ok()
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Operation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|op| op.as_str() == s)
+            .ok_or_else(|| anyhow::anyhow!("unknown operation {s:?}"))
+    }
+}
+
+/// The error a matching request receives.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum FaultKind {
+    /// `ThrottlingException`; the AWS SDK retries it.
+    Throttling,
+    /// `AccessDeniedException`; not retried.
+    AccessDenied,
+    /// `KMSInternalException` with HTTP 500; retried as a transient error.
+    Internal,
+}
+
+impl FaultKind {
+    pub(crate) fn error_type(self) -> &'static str {
+        match self {
+            Self::Throttling => "ThrottlingException",
+            Self::AccessDenied => "AccessDeniedException",
+            Self::Internal => "KMSInternalException",
+        }
+    }
+
+    pub(crate) fn status(self) -> u16 {
+        match self {
+            Self::Throttling | Self::AccessDenied => 400,
+            Self::Internal => 500,
+        }
+    }
+}
+
+impl FromStr for FaultKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "throttling" => Ok(Self::Throttling),
+            "access-denied" => Ok(Self::AccessDenied),
+            "internal" => Ok(Self::Internal),
+            _ => anyhow::bail!(
+                "unknown fault {s:?} (expected throttling, access-denied or internal)"
+            ),
+        }
+    }
+}
+
+/// One injected failure.
+///
+/// `operation` and `key` narrow which requests match; `key` accepts any
+/// form KMS does (id, ARN, alias). `count` limits how many requests fail
+/// before the rule retires itself; without it the rule stays until cleared.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaultRule {
+    pub error: FaultKind,
+    #[serde(default)]
+    pub operation: Option<Operation>,
+    #[serde(default)]
+    pub key: Option<String>,
+    #[serde(default)]
+    pub count: Option<u32>,
+}
+
+impl FaultRule {
+    pub fn new(error: FaultKind) -> Self {
+        Self {
+            error,
+            operation: None,
+            key: None,
+            count: None,
+        }
+    }
+
+    pub fn operation(mut self, operation: Operation) -> Self {
+        self.operation = Some(operation);
+        self
+    }
+
+    pub fn key(mut self, key: impl Into<String>) -> Self {
+        self.key = Some(key.into());
+        self
+    }
+
+    pub fn count(mut self, count: u32) -> Self {
+        self.count = Some(count);
+        self
+    }
+}
+
+/// Command-line form: `error=throttling,operation=Decrypt,key=alias/x,count=3`.
+/// Only `error` is required.
+impl FromStr for FaultRule {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (mut error, mut operation, mut key, mut count) = (None, None, None, None);
+        for part in s.split(',').filter(|p| !p.is_empty()) {
+            let (name, value) = part
+                .split_once('=')
+                .ok_or_else(|| anyhow::anyhow!("expected name=value, got {part:?}"))?;
+            match name.trim() {
+                "error" => error = Some(value.trim().parse()?),
+                "operation" => operation = Some(value.trim().parse()?),
+                "key" => key = Some(value.trim().to_string()),
+                "count" => count = Some(value.trim().parse()?),
+                other => anyhow::bail!("unknown fault field {other:?}"),
+            }
+        }
+        Ok(Self {
+            error: error.ok_or_else(|| anyhow::anyhow!("fault needs error=..."))?,
+            operation,
+            key,
+            count,
+        })
+    }
+}
+
+#[derive(Default)]
+pub(crate) struct Faults {
+    rules: Mutex<Vec<FaultRule>>,
+}
+
+impl Faults {
+    pub(crate) fn inject(&self, rule: FaultRule) {
+        log::info!("injecting fault {rule:?}");
+        self.rules.lock().push(rule);
+    }
+
+    pub(crate) fn clear(&self) {
+        self.rules.lock().clear();
+    }
+
+    /// First rule matching `op` on a key known by any of `key_names`;
+    /// consumes one use of a counted rule.
+    pub(crate) fn check(&self, op: Operation, key_names: &[&str]) -> Option<FaultKind> {
+        let mut rules = self.rules.lock();
+        let idx = rules.iter().position(|r| {
+            r.operation.is_none_or(|o| o == op)
+                && r.key.as_deref().is_none_or(|k| key_names.contains(&k))
+        })?;
+        let rule = &mut rules[idx];
+        let kind = rule.error;
+        if let Some(n) = rule.count.as_mut() {
+            *n = n.saturating_sub(1);
+            if *n == 0 {
+                rules.remove(idx);
+            }
+        }
+        Some(kind)
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rule_parses_from_command_line_form() {
+        let rule: FaultRule = "error=access-denied,operation=Decrypt,key=alias/a,count=2"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            rule,
+            FaultRule::new(FaultKind::AccessDenied)
+                .operation(Operation::Decrypt)
+                .key("alias/a")
+                .count(2)
+        );
+        assert!("operation=Decrypt".parse::<FaultRule>().is_err());
+        assert!("error=slow".parse::<FaultRule>().is_err());
+    }
+
+    #[test]
+    fn counted_rules_retire_and_scoped_rules_only_match_their_scope() {
+        let faults = Faults::default();
+        faults.inject(FaultRule::new(FaultKind::Throttling).count(2));
+        faults.inject(
+            FaultRule::new(FaultKind::AccessDenied)
+                .operation(Operation::Decrypt)
+                .key("alias/locked"),
+        );
+
+        assert_eq!(
+            faults.check(Operation::Encrypt, &[]),
+            Some(FaultKind::Throttling)
+        );
+        assert_eq!(
+            faults.check(Operation::Encrypt, &[]),
+            Some(FaultKind::Throttling)
+        );
+        assert_eq!(faults.check(Operation::Encrypt, &["alias/locked"]), None);
+        assert_eq!(faults.check(Operation::Decrypt, &["alias/open"]), None);
+        assert_eq!(
+            faults.check(Operation::Decrypt, &["key-id", "alias/locked"]),
+            Some(FaultKind::AccessDenied)
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! Symmetric keys, addressed the way AWS KMS addresses them: key id, key
+//! ARN, alias name (`alias/...`) or alias ARN.
+//!
+//! With a key file, keys survive restarts, so blobs written by one test run
+//! still decrypt in the next. Without one they live only in memory.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+/// Key material length; every emulated key is AES-256.
+const KEY_LEN: usize = 32;
+
+/// Public view of a key, as returned by the admin endpoint.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct KeyInfo {
+    pub key_id: String,
+    pub arn: String,
+    pub aliases: Vec<String>,
+}
+
+/// A resolved key: its id plus the material that seals blobs for it.
+pub(crate) struct ResolvedKey {
+    pub(crate) key_id: String,
+    pub(crate) arn: String,
+    pub(crate) aliases: Vec<String>,
+    pub(crate) material: Vec<u8>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct StoredKey {
+    key_id: String,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(with = "crate::b64")]
+    material: Vec<u8>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct KeyFile {
+    keys: Vec<StoredKey>,
+}
+
+/// Reference forms accepted wherever KMS takes a `KeyId`.
+enum KeyRef<'name> {
+    Id(&'name str),
+    Alias(&'name str),
+}
+
+fn parse_key_ref(key_ref: &str) -> KeyRef<'_> {
+    // arn:aws:kms:<region>:<account>:key/<id> or ...:alias/<name>. The
+    // region and account are not checked; the emulator serves one keyspace.
+    let resource = if key_ref.starts_with("arn:") {
+        key_ref.splitn(6, ':').nth(5).unwrap_or("")
+    } else {
+        key_ref
+    };
+    match resource.strip_prefix("key/") {
+        Some(id) => KeyRef::Id(id),
+        None if resource.starts_with("alias/") => KeyRef::Alias(resource),
+        None => KeyRef::Id(resource),
+    }
+}
+
+/// Accept `name` as shorthand for `alias/name`.
+pub(crate) fn normalize_alias(alias: &str) -> String {
+    if alias.starts_with("alias/") {
+        alias.to_string()
+    } else {
+        format!("alias/{alias}")
+    }
+}
+
+fn new_key_id() -> anyhow::Result<String> {
+    let mut b = [0_u8; 16];
+    asherah::aead::fast_random_bytes(&mut b)?;
+    // RFC 4122 version 4 layout, matching the shape of real KMS key ids.
+    b[6] = (b[6] & 0x0f) | 0x40;
+    b[8] = (b[8] & 0x3f) | 0x80;
+    let hex: String = b.iter().map(|x| format!("{x:02x}")).collect();
+    Ok(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    ))
+}
+
+pub(crate) struct KeyStore {
+    path: Option<PathBuf>,
+    region: String,
+    account_id: String,
+    keys: RwLock<Vec<StoredKey>>,
+}
+
+impl KeyStore {
+    pub(crate) fn open(
+        path: Option<&Path>,
+        region: &str,
+        account_id: &str,
+    ) -> anyhow::Result<Self> {
+        let keys = match path {
+            Some(p) if p.exists() => {
+                let raw = fs::read(p).with_context(|| format!("reading {}", p.display()))?;
+                let file: KeyFile = serde_json::from_slice(&raw)
+                    .with_context(|| format!("parsing key file {}", p.display()))?;
+                if let Some(bad) = file.keys.iter().find(|k| k.material.len() != KEY_LEN) {
+                    anyhow::bail!(
+                        "key file {}: key {} has {} bytes of material, expected {KEY_LEN}",
+                        p.display(),
+                        bad.key_id,
+                        bad.material.len()
+                    );
+                }
+                file.keys
+            }
+            _ => Vec::new(),
+        };
+        Ok(Self {
+            path: path.map(Path::to_path_buf),
+            region: region.to_string(),
+            account_id: account_id.to_string(),
+            keys: RwLock::new(keys),
+        })
+    }
+
+    fn arn(&self, key_id: &str) -> String {
+        format!(
+            "arn:aws:kms:{}:{}:key/{key_id}",
+            self.region, self.account_id
+        )
+    }
+
+    fn info(&self, key: &StoredKey) -> KeyInfo {
+        KeyInfo {
+            key_id: key.key_id.clone(),
+            arn: self.arn(&key.key_id),
+            aliases: key.aliases.clone(),
+        }
+    }
+
+    pub(crate) fn list(&self) -> Vec<KeyInfo> {
+        self.keys.read().iter().map(|k| self.info(k)).collect()
+    }
+
+    pub(crate) fn resolve(&self, key_ref: &str) -> Option<ResolvedKey> {
+        let keys = self.keys.read();
+        let key = match parse_key_ref(key_ref) {
+            KeyRef::Id(id) => keys.iter().find(|k| k.key_id == id),
+            KeyRef::Alias(alias) => keys.iter().find(|k| k.aliases.iter().any(|a| a == alias)),
+        }?;
+        Some(ResolvedKey {
+            key_id: key.key_id.clone(),
+            arn: self.arn(&key.key_id),
+            aliases: key.aliases.clone(),
+            material: key.material.clone(),
+        })
+    }
+
+    /// Return the key behind `alias`, creating (and persisting) it first if
+    /// no key carries that alias yet.
+    pub(crate) fn ensure_alias(&self, alias: &str) -> anyhow::Result<KeyInfo> {
+        let alias = normalize_alias(alias);
+        let mut keys = self.keys.write();
+        if let Some(k) = keys.iter().find(|k| k.aliases.contains(&alias)) {
+            return Ok(self.info(k));
+        }
+        let mut material = vec![0_u8; KEY_LEN];
+        asherah::aead::fast_random_bytes(&mut material)?;
+        let key = StoredKey {
+            key_id: new_key_id()?,
+            aliases: vec![alias],
+            material,
+        };
+        let info = self.info(&key);
+        keys.push(key);
+        self.persist(&keys)?;
+        log::info!("created key {} ({})", info.arn, info.aliases.join(", "));
+        Ok(info)
+    }
+
+    /// Rewrite the key file via a temp file and rename so a crash never
+    /// leaves a truncated file behind.
+    fn persist(&self, keys: &[StoredKey]) -> anyhow::Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let body = serde_json::to_vec_pretty(&KeyFile {
+            keys: keys.to_vec(),
+        })?;
+        let tmp = path.with_extension("tmp");
+        let mut opts = fs::OpenOptions::new();
+        opts.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            opts.mode(0o600);
+        }
+        let mut f = opts
+            .open(&tmp)
+            .with_context(|| format!("writing {}", tmp.display()))?;
+        f.write_all(&body)?;
+        f.sync_all()?;
+        fs::rename(&tmp, path).with_context(|| format!("replacing {}", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_reference_form_resolves_to_the_same_key() {
+        let store = KeyStore::open(None, "us-west-2", "111122223333").unwrap();
+        let info = store.ensure_alias("orders").unwrap();
+        assert_eq!(info.aliases, vec!["alias/orders"]);
+
+        for key_ref in [
+            info.key_id.clone(),
+            info.arn.clone(),
+            "alias/orders".to_string(),
+            "arn:aws:kms:eu-west-1:999999999999:alias/orders".to_string(),
+        ] {
+            let key = store.resolve(&key_ref).expect(&key_ref);
+            assert_eq!(key.key_id, info.key_id);
+        }
+        assert!(store.resolve("alias/other").is_none());
+        assert_eq!(store.ensure_alias("alias/orders").unwrap(), info);
+    }
+
+    #[test]
+    fn keys_persist_across_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keys.json");
+        let first = KeyStore::open(Some(&path), "us-east-1", "1")
+            .unwrap()
+            .ensure_alias("persisted")
+            .unwrap();
+
+        let reopened = KeyStore::open(Some(&path), "us-east-1", "1").unwrap();
+        assert_eq!(reopened.list(), vec![first]);
+    }
+}
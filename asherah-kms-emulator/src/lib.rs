@@ -0,0 +1,538 @@
+//! A local stand-in for the AWS KMS subset Asherah calls: `Encrypt`,
+//! `Decrypt` and `GenerateDataKey`, over the AWS JSON 1.1 protocol the SDK
+//! speaks. Point `AWS_ENDPOINT_URL` at it and `AwsKms` / `AwsKmsEnvelope`
+//! run end to end without an AWS account or Docker.
+//!
+//! Request signatures are not checked, so any static credentials work.
+//! Ciphertext blobs are AES-256-GCM under the key's local material, tagged
+//! with the key id, and are only meaningful to this emulator.
+//!
+//! Besides the KMS target, a small admin API under `/_emulator/` lists and
+//! creates keys and injects faults (see [`FaultRule`]) at runtime.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use asherah::aead::AES256GCM;
+use asherah::traits::AEAD;
+use http_body_util::{BodyExt, Full};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::{Deserialize, Serialize};
+use tokio::net::TcpListener;
+
+pub mod faults;
+pub mod keys;
+
+pub use faults::{FaultKind, FaultRule, Operation};
+pub use keys::KeyInfo;
+
+use faults::Faults;
+use keys::{KeyStore, ResolvedKey};
+
+/// Prefix of every ciphertext blob the emulator issues.
+const BLOB_MAGIC: &[u8; 4] = b"AKE1";
+/// KMS accepts at most 4 KiB of plaintext per `Encrypt`.
+const MAX_PLAINTEXT: usize = 4096;
+/// KMS limits `GenerateDataKey` `NumberOfBytes` to 1..=1024.
+const MAX_DATA_KEY: usize = 1024;
+const JSON_CONTENT_TYPE: &str = "application/x-amz-json-1.1";
+
+/// Startup settings; the binary fills this from flags and environment.
+#[derive(Clone, Debug)]
+pub struct EmulatorConfig {
+    /// Where keys persist. `None` keeps them in memory for this process only.
+    pub key_file: Option<PathBuf>,
+    /// Region reported in key ARNs.
+    pub region: String,
+    /// Account id reported in key ARNs.
+    pub account_id: String,
+    /// Aliases to create at startup unless a key already carries them.
+    pub aliases: Vec<String>,
+    /// Faults active from the first request.
+    pub faults: Vec<FaultRule>,
+}
+
+impl Default for EmulatorConfig {
+    fn default() -> Self {
+        Self {
+            key_file: None,
+            region: "us-east-1".to_string(),
+            account_id: "111122223333".to_string(),
+            aliases: Vec::new(),
+            faults: Vec::new(),
+        }
+    }
+}
+
+/// A running key store plus its fault table. Cheap to clone; clones share
+/// state, so a test can keep one to inject faults while another serves.
+#[derive(Clone)]
+#[allow(missing_debug_implementations)]
+pub struct Emulator {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    keys: KeyStore,
+    faults: Faults,
+    aead: AES256GCM,
+    requests: [AtomicU64; 3],
+}
+
+impl Emulator {
+    pub fn new(config: EmulatorConfig) -> anyhow::Result<Self> {
+        let keys = KeyStore::open(
+            config.key_file.as_deref(),
+            &config.region,
+            &config.account_id,
+        )?;
+        for alias in &config.aliases {
+            keys.ensure_alias(alias)?;
+        }
+        let faults = Faults::default();
+        for rule in config.faults {
+            faults.inject(rule);
+        }
+        Ok(Self {
+            inner: Arc::new(Inner {
+                keys,
+                faults,
+                aead: AES256GCM::new(),
+                requests: Default::default(),
+            }),
+        })
+    }
+
+    /// ARN of the key behind `alias`, creating the key if needed.
+    pub fn ensure_key(&self, alias: &str) -> anyhow::Result<String> {
+        Ok(self.inner.keys.ensure_alias(alias)?.arn)
+    }
+
+    pub fn keys(&self) -> Vec<KeyInfo> {
+        self.inner.keys.list()
+    }
+
+    pub fn inject_fault(&self, rule: FaultRule) {
+        self.inner.faults.inject(rule);
+    }
+
+    pub fn clear_faults(&self) {
+        self.inner.faults.clear();
+    }
+
+    /// Requests received for `op`, including ones answered with a fault.
+    /// SDK retries show up here as extra requests.
+    pub fn request_count(&self, op: Operation) -> u64 {
+        self.inner.requests[op as usize].load(Ordering::Relaxed)
+    }
+
+    /// Serve HTTP/1.1 on `listener` until `shutdown` resolves.
+    pub async fn serve_with_shutdown(
+        self,
+        listener: TcpListener,
+        shutdown: impl Future<Output = ()>,
+    ) -> std::io::Result<()> {
+        tokio::pin!(shutdown);
+        loop {
+            let (stream, peer) = tokio::select! {
+                accepted = listener.accept() => accepted?,
+                () = &mut shutdown => return Ok(()),
+            };
+            let emulator = self.clone();
+            tokio::spawn(async move {
+                let svc = hyper::service::service_fn(move |req| {
+                    let emulator = emulator.clone();
+                    async move { emulator.handle(req).await }
+                });
+                if let Err(e) = hyper::server::conn::http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), svc)
+                    .await
+                {
+                    log::debug!("connection from {peer} ended: {e}");
+                }
+            });
+        }
+    }
+
+    /// Serve until the listener fails.
+    pub async fn serve(self, listener: TcpListener) -> std::io::Result<()> {
+        self.serve_with_shutdown(listener, std::future::pending())
+            .await
+    }
+
+    async fn handle(&self, req: Request<Incoming>) -> Result<Response<Full<Bytes>>, Infallible> {
+        let (parts, body) = req.into_parts();
+        let body = match body.collect().await {
+            Ok(b) => b.to_bytes(),
+            Err(e) => {
+                return Ok(KmsError::new(
+                    StatusCode::BAD_REQUEST,
+                    "SerializationException",
+                    format!("reading request body: {e}"),
+                )
+                .into_response())
+            }
+        };
+        let resp = if let Some(path) = parts.uri.path().strip_prefix("/_emulator/") {
+            self.admin(&parts.method, path, &body)
+        } else {
+            self.kms(&parts.headers, &body)
+        };
+        Ok(resp.unwrap_or_else(KmsError::into_response))
+    }
+
+    fn admin(
+        &self,
+        method: &Method,
+        path: &str,
+        body: &[u8],
+    ) -> Result<Response<Full<Bytes>>, KmsError> {
+        match (method, path) {
+            (&Method::GET, "keys") => json_response(StatusCode::OK, &self.keys()),
+            (&Method::POST, "keys") => {
+                #[derive(Deserialize)]
+                struct CreateKey {
+                    alias: String,
+                }
+                let req: CreateKey = parse_json(body)?;
+                let info = self
+                    .inner
+                    .keys
+                    .ensure_alias(&req.alias)
+                    .map_err(KmsError::internal)?;
+                json_response(StatusCode::OK, &info)
+            }
+            (&Method::POST, "faults") => {
+                self.inject_fault(parse_json(body)?);
+                Ok(empty_response(StatusCode::NO_CONTENT))
+            }
+            (&Method::DELETE, "faults") => {
+                self.clear_faults();
+                Ok(empty_response(StatusCode::NO_CONTENT))
+            }
+            _ => Err(KmsError::new(
+                StatusCode::NOT_FOUND,
+                "UnknownOperationException",
+                format!("no admin route {method} /_emulator/{path}"),
+            )),
+        }
+    }
+
+    fn kms(&self, headers: &HeaderMap, body: &[u8]) -> Result<Response<Full<Bytes>>, KmsError> {
+        let target = headers
+            .get("x-amz-target")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or("");
+        let op = Operation::from_target(target).ok_or_else(|| {
+            KmsError::new(
+                StatusCode::BAD_REQUEST,
+                "UnknownOperationException",
+                format!("unsupported target {target:?}"),
+            )
+        })?;
+        self.inner.requests[op as usize].fetch_add(1, Ordering::Relaxed);
+        match op {
+            Operation::Encrypt => self.encrypt(parse_json(body)?),
+            Operation::Decrypt => self.decrypt(parse_json(body)?),
+            Operation::GenerateDataKey => self.generate_data_key(parse_json(body)?),
+        }
+    }
+
+    /// Resolve `key_ref` and apply any matching fault. Faults are checked
+    /// before the not-found error so they can target keys that don't exist.
+    fn key_for(&self, op: Operation, key_ref: &str) -> Result<ResolvedKey, KmsError> {
+        let key = self.inner.keys.resolve(key_ref);
+        let mut names = vec![key_ref];
+        if let Some(k) = &key {
+            names.extend([k.key_id.as_str(), k.arn.as_str()]);
+            names.extend(k.aliases.iter().map(String::as_str));
+        }
+        if let Some(fault) = self.inner.faults.check(op, &names) {
+            return Err(KmsError::fault(fault, op));
+        }
+        key.ok_or_else(|| {
+            KmsError::new(
+                StatusCode::BAD_REQUEST,
+                "NotFoundException",
+                format!("key {key_ref} does not exist"),
+            )
+        })
+    }
+
+    fn seal(&self, key: &ResolvedKey, plaintext: &[u8]) -> Result<Vec<u8>, KmsError> {
+        let sealed = self
+            .inner
+            .aead
+            .encrypt(plaintext, &key.material)
+            .map_err(KmsError::internal)?;
+        let id = key.key_id.as_bytes();
+        let mut blob = Vec::with_capacity(BLOB_MAGIC.len() + 1 + id.len() + sealed.len());
+        blob.extend_from_slice(BLOB_MAGIC);
+        // Key ids are 36-byte UUIDs; the length prefix always fits.
+        blob.push(u8::try_from(id.len()).map_err(KmsError::internal)?);
+        blob.extend_from_slice(id);
+        blob.extend_from_slice(&sealed);
+        Ok(blob)
+    }
+
+    fn encrypt(&self, req: EncryptRequest) -> Result<Response<Full<Bytes>>, KmsError> {
+        if req.plaintext.is_empty() || req.plaintext.len() > MAX_PLAINTEXT {
+            return Err(KmsError::validation(format!(
+                "Plaintext must be 1 to {MAX_PLAINTEXT} bytes"
+            )));
+        }
+        let key = self.key_for(Operation::Encrypt, &req.key_id)?;
+        let blob = self.seal(&key, &req.plaintext)?;
+        json_response(
+            StatusCode::OK,
+            &CiphertextResponse {
+                ciphertext_blob: blob,
+                key_id: key.arn,
+                encryption_algorithm: "SYMMETRIC_DEFAULT",
+            },
+        )
+    }
+
+    fn decrypt(&self, req: DecryptRequest) -> Result<Response<Full<Bytes>>, KmsError> {
+        let (blob_key_id, sealed) = split_blob(&req.ciphertext_blob).ok_or_else(|| {
+            KmsError::new(
+                StatusCode::BAD_REQUEST,
+                "InvalidCiphertextException",
+                "ciphertext was not issued by this emulator",
+            )
+        })?;
+        // Like KMS, the blob names its key; a KeyId in the request must
+        // agree with it.
+        let key = self.key_for(
+            Operation::Decrypt,
+            req.key_id.as_deref().unwrap_or(blob_key_id),
+        )?;
+        if key.key_id != blob_key_id {
+            return Err(KmsError::new(
+                StatusCode::BAD_REQUEST,
+                "IncorrectKeyException",
+                "the ciphertext was encrypted under a different key",
+            ));
+        }
+        let plaintext = self
+            .inner
+            .aead
+            .decrypt(sealed, &key.material)
+            .map_err(|_| {
+                KmsError::new(
+                    StatusCode::BAD_REQUEST,
+                    "InvalidCiphertextException",
+                    "ciphertext failed authentication",
+                )
+            })?;
+        json_response(
+            StatusCode::OK,
+            &PlaintextResponse {
+                plaintext,
+                key_id: key.arn,
+                encryption_algorithm: "SYMMETRIC_DEFAULT",
+            },
+        )
+    }
+
+    fn generate_data_key(
+        &self,
+        req: GenerateDataKeyRequest,
+    ) -> Result<Response<Full<Bytes>>, KmsError> {
+        let len =
+            match (req.key_spec.as_deref(), req.number_of_bytes) {
+                (Some("AES_256"), None) => 32,
+                (Some("AES_128"), None) => 16,
+                (None, Some(n)) if (1..=MAX_DATA_KEY).contains(&n) => n,
+                _ => return Err(KmsError::validation(
+                    "specify exactly one of KeySpec (AES_256, AES_128) or NumberOfBytes (1-1024)",
+                )),
+            };
+        let key = self.key_for(Operation::GenerateDataKey, &req.key_id)?;
+        let mut plaintext = vec![0_u8; len];
+        asherah::aead::fast_random_bytes(&mut plaintext).map_err(KmsError::internal)?;
+        let blob = self.seal(&key, &plaintext)?;
+        json_response(
+            StatusCode::OK,
+            &DataKeyResponse {
+                ciphertext_blob: blob,
+                plaintext,
+                key_id: key.arn,
+            },
+        )
+    }
+}
+
+fn split_blob(blob: &[u8]) -> Option<(&str, &[u8])> {
+    let rest = blob.strip_prefix(BLOB_MAGIC)?;
+    let (&len, rest) = rest.split_first()?;
+    let len = usize::from(len);
+    if rest.len() < len {
+        return None;
+    }
+    let (id, sealed) = rest.split_at(len);
+    Some((std::str::from_utf8(id).ok()?, sealed))
+}
+
+/// Base64 (de)serialization for blob fields, as the JSON 1.1 protocol
+/// encodes them.
+pub(crate) mod b64 {
+    use base64::Engine;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(crate) fn serialize<S: Serializer>(bytes: &[u8], s: S) -> Result<S::Ok, S::Error> {
+        s.serialize_str(&base64::engine::general_purpose::STANDARD.encode(bytes))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(d)?;
+        base64::engine::general_purpose::STANDARD
+            .decode(s)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct EncryptRequest {
+    key_id: String,
+    #[serde(with = "b64")]
+    plaintext: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DecryptRequest {
+    #[serde(default)]
+    key_id: Option<String>,
+    #[serde(with = "b64")]
+    ciphertext_blob: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct GenerateDataKeyRequest {
+    key_id: String,
+    #[serde(default)]
+    key_spec: Option<String>,
+    #[serde(default)]
+    number_of_bytes: Option<usize>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct CiphertextResponse {
+    #[serde(with = "b64")]
+    ciphertext_blob: Vec<u8>,
+    key_id: String,
+    encryption_algorithm: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct PlaintextResponse {
+    #[serde(with = "b64")]
+    plaintext: Vec<u8>,
+    key_id: String,
+    encryption_algorithm: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "PascalCase")]
+struct DataKeyResponse {
+    #[serde(with = "b64")]
+    ciphertext_blob: Vec<u8>,
+    #[serde(with = "b64")]
+    plaintext: Vec<u8>,
+    key_id: String,
+}
+
+/// An error in the shape the AWS SDK's JSON 1.1 deserializer expects:
+/// the type in both `x-amzn-ErrorType` and the body's `__type`.
+#[derive(Debug)]
+struct KmsError {
+    status: StatusCode,
+    error_type: &'static str,
+    message: String,
+}
+
+impl KmsError {
+    fn new(status: StatusCode, error_type: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            error_type,
+            message: message.into(),
+        }
+    }
+
+    fn validation(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, "ValidationException", message)
+    }
+
+    fn internal(e: impl std::fmt::Display) -> Self {
+        log::error!("internal error: {e}");
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "KMSInternalException",
+            e.to_string(),
+        )
+    }
+
+    fn fault(kind: FaultKind, op: Operation) -> Self {
+        Self::new(
+            StatusCode::from_u16(kind.status()).unwrap_or(StatusCode::BAD_REQUEST),
+            kind.error_type(),
+            format!("injected {} on {op}", kind.error_type()),
+        )
+    }
+
+    fn into_response(self) -> Response<Full<Bytes>> {
+        let body = serde_json::json!({
+            "__type": self.error_type,
+            "message": self.message,
+        });
+        let mut resp = Response::new(Full::new(Bytes::from(body.to_string())));
+        *resp.status_mut() = self.status;
+        resp.headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE));
+        resp.headers_mut().insert(
+            "x-amzn-errortype",
+            HeaderValue::from_static(self.error_type),
+        );
+        resp
+    }
+}
+
+fn parse_json<T: serde::de::DeserializeOwned>(body: &[u8]) -> Result<T, KmsError> {
+    serde_json::from_slice(body).map_err(|e| {
+        KmsError::new(
+            StatusCode::BAD_REQUEST,
+            "SerializationException",
+            e.to_string(),
+        )
+    })
+}
+
+fn json_response<T: Serialize>(
+    status: StatusCode,
+    value: &T,
+) -> Result<Response<Full<Bytes>>, KmsError> {
+    let body = serde_json::to_vec(value).map_err(KmsError::internal)?;
+    let mut resp = Response::new(Full::new(Bytes::from(body)));
+    *resp.status_mut() = status;
+    resp.headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(JSON_CONTENT_TYPE));
+    Ok(resp)
+}
+
+fn empty_response(status: StatusCode) -> Response<Full<Bytes>> {
+    let mut resp = Response::new(Full::new(Bytes::new()));
+    *resp.status_mut() = status;
+    resp
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use asherah_kms_emulator::{Emulator, EmulatorConfig, FaultRule};
+use clap::Parser;
+
+#[derive(Parser, Debug)]
+#[command(
+    name = "asherah-kms-emulator",
+    about = "Local AWS KMS emulator (Encrypt, Decrypt, GenerateDataKey) for hermetic tests"
+)]
+struct Args {
+    /// Address to listen on; point AWS_ENDPOINT_URL at it.
+    #[arg(long, env = "KMS_EMULATOR_LISTEN", default_value = "127.0.0.1:4599")]
+    listen: String,
+
+    /// JSON file holding key material. Created on first use. Without it,
+    /// keys are lost when the process exits.
+    #[arg(long, env = "KMS_EMULATOR_KEY_FILE")]
+    key_file: Option<PathBuf>,
+
+    /// Region reported in key ARNs.
+    #[arg(long, env = "KMS_EMULATOR_REGION", default_value = "us-east-1")]
+    region: String,
+
+    /// Account id reported in key ARNs.
+    #[arg(long, env = "KMS_EMULATOR_ACCOUNT_ID", default_value = "111122223333")]
+    account_id: String,
+
+    /// Alias to create at startup if no key has it yet (`name` or
+    /// `alias/name`). Repeatable.
+    #[arg(long = "key", env = "KMS_EMULATOR_KEYS", value_delimiter = ',')]
+    keys: Vec<String>,
+
+    /// Fault active from startup, e.g.
+    /// `error=throttling,operation=Decrypt,count=2`. Repeatable.
+    #[arg(long = "fault")]
+    faults: Vec<FaultRule>,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+
+    let emulator = Emulator::new(EmulatorConfig {
+        key_file: args.key_file,
+        region: args.region,
+        account_id: args.account_id,
+        aliases: args.keys,
+        faults: args.faults,
+    })?;
+    for key in emulator.keys() {
+        log::info!("key {} {}", key.arn, key.aliases.join(", "));
+    }
+
+    let listener = tokio::net::TcpListener::bind(&args.listen)
+        .await
+        .with_context(|| format!("binding {}", args.listen))?;
+    log::info!(
+        "KMS emulator listening on http://{}",
+        listener.local_addr()?
+    );
+
+    emulator
+        .serve_with_shutdown(listener, async {
+            if let Err(e) = tokio::signal::ctrl_c().await {
+                log::error!("waiting for ctrl-c: {e}");
+            }
+        })
+        .await?;
+    log::info!("shut down");
+    Ok(())
+}
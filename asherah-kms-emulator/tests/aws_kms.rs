@@ -0,0 +1,208 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! `AwsKms` and `AwsKmsEnvelope` against the emulator: round trips, key
+//! persistence across restarts, and injected throttling / access-denied
+//! responses as the AWS SDK sees them.
+
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use asherah::aead::AES256GCM;
+use asherah::kms_aws::AwsKms;
+use asherah::kms_aws_envelope::AwsKmsEnvelope;
+use asherah::traits::KeyManagementService;
+use asherah_kms_emulator::{Emulator, EmulatorConfig, FaultKind, FaultRule, Operation};
+use serial_test::serial;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::oneshot;
+
+/// Start an emulator on an ephemeral port and point the AWS SDK at it.
+/// Dropping the returned sender stops the server.
+async fn start(
+    key_file: Option<PathBuf>,
+    aliases: &[&str],
+) -> (Emulator, SocketAddr, oneshot::Sender<()>) {
+    let emulator = Emulator::new(EmulatorConfig {
+        key_file,
+        aliases: aliases.iter().map(|a| (*a).to_string()).collect(),
+        ..EmulatorConfig::default()
+    })
+    .unwrap();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (stop, stopped) = oneshot::channel::<()>();
+    tokio::spawn(emulator.clone().serve_with_shutdown(listener, async {
+        let _ = stopped.await;
+    }));
+
+    std::env::set_var("AWS_ENDPOINT_URL", format!("http://{addr}"));
+    std::env::set_var("AWS_ACCESS_KEY_ID", "emulator");
+    std::env::set_var("AWS_SECRET_ACCESS_KEY", "emulator");
+    std::env::set_var("AWS_EC2_METADATA_DISABLED", "true");
+    (emulator, addr, stop)
+}
+
+async fn aws_kms(key_id: &str) -> AwsKms<AES256GCM> {
+    AwsKms::new_async(
+        Arc::new(AES256GCM::new()),
+        key_id,
+        Some("us-east-1".to_string()),
+        None,
+    )
+    .await
+    .unwrap()
+}
+
+/// Minimal HTTP/1.1 client for the admin API; returns the status code.
+async fn admin(addr: SocketAddr, method: &str, path: &str, body: &str) -> u16 {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let req = format!(
+        "{method} {path} HTTP/1.1\r\nHost: {addr}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    stream.write_all(req.as_bytes()).await.unwrap();
+    let mut resp = String::new();
+    stream.read_to_string(&mut resp).await.unwrap();
+    resp.split(' ').nth(1).unwrap().parse().unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn aws_kms_round_trips_by_alias_and_arn() {
+    let (emulator, _addr, _stop) = start(None, &["asherah"]).await;
+    let by_alias = aws_kms("alias/asherah").await;
+    let blob = by_alias
+        .encrypt_key_async(&(), b"system key")
+        .await
+        .unwrap();
+    assert_eq!(
+        by_alias.decrypt_key_async(&(), &blob).await.unwrap(),
+        b"system key"
+    );
+
+    // The sync path, and a client configured with the key ARN instead.
+    let arn = emulator.ensure_key("asherah").unwrap();
+    let by_arn = aws_kms(&arn).await;
+    assert_eq!(by_arn.decrypt_key(&(), &blob).unwrap(), b"system key");
+
+    // A blob is bound to its key.
+    emulator.ensure_key("other").unwrap();
+    assert!(aws_kms("alias/other")
+        .await
+        .decrypt_key_async(&(), &blob)
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn keys_persist_across_restarts() {
+    let dir = tempfile::tempdir().unwrap();
+    let key_file = dir.path().join("keys.json");
+
+    let (_, _, stop) = start(Some(key_file.clone()), &["durable"]).await;
+    let blob = aws_kms("alias/durable")
+        .await
+        .encrypt_key_async(&(), b"written before restart")
+        .await
+        .unwrap();
+    drop(stop);
+
+    let (_, _, _stop) = start(Some(key_file), &["durable"]).await;
+    assert_eq!(
+        aws_kms("alias/durable")
+            .await
+            .decrypt_key_async(&(), &blob)
+            .await
+            .unwrap(),
+        b"written before restart"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn throttling_is_retried_by_the_sdk() {
+    let (emulator, _addr, _stop) = start(None, &["busy"]).await;
+    emulator.inject_fault(
+        FaultRule::new(FaultKind::Throttling)
+            .operation(Operation::Encrypt)
+            .count(1),
+    );
+
+    let kms = aws_kms("alias/busy").await;
+    let blob = kms.encrypt_key_async(&(), b"eventually").await.unwrap();
+    assert_eq!(emulator.request_count(Operation::Encrypt), 2);
+    assert_eq!(
+        kms.decrypt_key_async(&(), &blob).await.unwrap(),
+        b"eventually"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn access_denied_fails_without_retry_until_cleared() {
+    let (emulator, _addr, _stop) = start(None, &["locked"]).await;
+    let kms = aws_kms("alias/locked").await;
+    let blob = kms.encrypt_key_async(&(), b"guarded").await.unwrap();
+
+    emulator.inject_fault(
+        FaultRule::new(FaultKind::AccessDenied)
+            .operation(Operation::Decrypt)
+            .key("alias/locked"),
+    );
+    assert!(kms.decrypt_key_async(&(), &blob).await.is_err());
+    assert_eq!(emulator.request_count(Operation::Decrypt), 1);
+
+    emulator.clear_faults();
+    assert_eq!(kms.decrypt_key_async(&(), &blob).await.unwrap(), b"guarded");
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn faults_can_be_injected_over_http() {
+    let (_emulator, addr, _stop) = start(None, &["remote"]).await;
+    let kms = aws_kms("alias/remote").await;
+
+    let rule = r#"{"error":"access-denied","operation":"Encrypt"}"#;
+    assert_eq!(admin(addr, "POST", "/_emulator/faults", rule).await, 204);
+    assert!(kms.encrypt_key_async(&(), b"denied").await.is_err());
+
+    assert_eq!(admin(addr, "DELETE", "/_emulator/faults", "").await, 204);
+    assert!(kms.encrypt_key_async(&(), b"allowed").await.is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[serial]
+async fn envelope_falls_back_to_the_next_region_when_denied() {
+    let (emulator, _addr, _stop) = start(None, &["east", "west"]).await;
+    let kms = AwsKmsEnvelope::new_multi_async(
+        Arc::new(AES256GCM::new()),
+        0,
+        vec![
+            ("us-east-1".to_string(), "alias/east".to_string()),
+            ("us-west-2".to_string(), "alias/west".to_string()),
+        ],
+        None,
+    )
+    .await
+    .unwrap();
+
+    let blob = kms.encrypt_key_async(&(), b"multi-region").await.unwrap();
+    assert_eq!(emulator.request_count(Operation::GenerateDataKey), 1);
+    assert_eq!(emulator.request_count(Operation::Encrypt), 1);
+
+    emulator.inject_fault(
+        FaultRule::new(FaultKind::AccessDenied)
+            .operation(Operation::Decrypt)
+            .key("alias/east"),
+    );
+    assert_eq!(
+        kms.decrypt_key_async(&(), &blob).await.unwrap(),
+        b"multi-region"
+    );
+    assert_eq!(emulator.request_count(Operation::Decrypt), 2);
+
+    emulator.inject_fault(FaultRule::new(FaultKind::AccessDenied).key("alias/west"));
+    assert!(kms.decrypt_key_async(&(), &blob).await.is_err());
+}
@@ -1,5 +1,7 @@
 /// <reference types="node" />
 
+import type { Transform, TransformOptions } from 'stream';
+
 // ============================================================================
 //
 // Asherah for Node.js
@@ -54,8 +56,9 @@ export type AsherahConfig = {
   checkInterval?: number | null;
 
   /** Metastore backend. `'memory'` is testing-only and will not persist
-   *  across processes. */
-  metastore: 'memory' | 'rdbms' | 'dynamodb';
+   *  across processes. `'host'` means one supplied to
+   *  {@link SessionFactory.withBackends}. */
+  metastore: 'memory' | 'rdbms' | 'dynamodb' | 'host';
 
   /** SQL connection string when `metastore` is `'rdbms'`. Format depends
    *  on `sqlMetastoreDbType`. */
@@ -336,6 +339,22 @@ export declare class SessionFactory {
    *  canonical Go-based asherah module). */
   static fromEnv(): SessionFactory;
 
+  /**
+   * Construct a factory around a JavaScript-implemented metastore and/or
+   * KMS. `metastore` / `kms` may be left out of `config` (or set to
+   * `'host'`) for a backend supplied here; naming another one is an error.
+   * Resolves once the config drift guard has been checked through the
+   * backends.
+   *
+   * Backend methods are called on the JS thread and may return a Promise.
+   * Sessions of this factory must use the `*Async` methods: a sync call
+   * that needs the backend fails rather than deadlock the event loop.
+   */
+  static withBackends(
+    config: Omit<AsherahConfig, 'metastore'> & { metastore?: AsherahConfig['metastore'] },
+    backends: Backends,
+  ): Promise<SessionFactory>;
+
   /**
    * Get a session for the given partition. Sessions returned for the same
    * partition share the underlying intermediate key; different partitions
@@ -347,10 +366,61 @@ export declare class SessionFactory {
    */
   getSession(partitionId: string): AsherahSession;
 
-  /** Release native resources. After `close()`, `getSession()` will throw. */
+  /**
+   * Share this factory with `worker_threads` workers. Post the returned
+   * handle to a worker and open it there with {@link SessionFactory.fromHandle}:
+   * every worker then uses this factory's key caches, session cache and
+   * metastore connections instead of building its own. Returns the same
+   * handle on every call.
+   */
+  share(): number;
+
+  /**
+   * Open the factory shared as `handle`, from any thread of this process.
+   * The returned instance is closed independently; the factory itself closes
+   * when the last instance over it does.
+   *
+   * @throws StateError (`CLOSED`) if every instance over it has been closed.
+   */
+  static fromHandle(handle: number): SessionFactory;
+
+  /** Sizes and hit counts of this factory's caches. */
+  cacheStats(): CacheStats;
+
+  /**
+   * Drop `partitionId`'s cached session, so the next `getSession()` for it
+   * builds a fresh one. Sessions already handed out keep working.
+   *
+   * @returns whether a session was cached.
+   */
+  evictSession(partitionId: string): boolean;
+
+  /**
+   * Empty the session cache and the system and intermediate key caches, so
+   * keys are loaded from the metastore again: after a key revocation, or to
+   * release memory.
+   */
+  flushCaches(): void;
+
+  /** Release native resources. After `close()`, `getSession()` will throw.
+   *  A shared factory stays open until every instance over it is closed. */
   close(): void;
 }
 
+/** Returned by {@link SessionFactory.cacheStats}. */
+export interface CacheStats {
+  /** Sessions in the session cache; 0 with session caching disabled. */
+  sessions: number;
+  /** `getSession()` calls served from the session cache. */
+  sessionCacheHits: number;
+  /** `getSession()` calls that built a session despite session caching. */
+  sessionCacheMisses: number;
+  /** Entries in the shared intermediate key cache. */
+  intermediateKeys: number;
+  /** Entries in the system key cache. */
+  systemKeys: number;
+}
+
 /**
  * Per-partition encrypt/decrypt session. Created via
  * {@link SessionFactory.getSession}. Always pair with `close()` to release
@@ -365,10 +435,162 @@ export declare class AsherahSession {
   decrypt(dataRowRecordJson: string): Buffer;
   /** Decrypt a DRR JSON string and return the plaintext as a UTF-8 string. */
   decryptString(dataRowRecordJson: string): string;
+  /** Async {@link encrypt}; required for a factory built with
+   *  {@link SessionFactory.withBackends}. */
+  encryptAsync(data: Buffer): Promise<string>;
+  /** Async {@link encryptString}. */
+  encryptStringAsync(data: string): Promise<string>;
+  /** Async {@link decrypt}. */
+  decryptAsync(dataRowRecordJson: string): Promise<Buffer>;
+  /** Async {@link decryptString}. */
+  decryptStringAsync(dataRowRecordJson: string): Promise<string>;
+  /** A `Transform` encrypting everything written to it as an Asherah
+   *  stream. Not available to a {@link SessionFactory.withBackends} factory. */
+  encryptStream(options?: EncryptStreamOptions): EncryptStream;
+  /** A `Transform` decrypting a stream written by {@link encryptStream}. */
+  decryptStream(options?: TransformOptions): DecryptStream;
   /** Release native resources. */
   close(): void;
 }
 
+// ─── Streams ────────────────────────────────────────────────────────────────
+
+/** Options for {@link AsherahSession.encryptStream}. */
+export type EncryptStreamOptions = TransformOptions & {
+  /** Plaintext bytes per encrypted frame, 1 to 16 MiB. Default 64 KiB. */
+  chunkSize?: number;
+};
+
+/**
+ * Encrypts a stream in frames of `chunkSize` plaintext bytes, under a
+ * random key sealed by the session into the stream's header. Frames are
+ * authenticated in order, and the stream only decrypts once it has been
+ * ended.
+ */
+export declare class EncryptStream extends Transform {
+  constructor(session: AsherahSession, options?: EncryptStreamOptions);
+}
+
+/**
+ * Decrypts a stream written by {@link EncryptStream}. Errors with a
+ * `DecryptionError` if the stream was tampered with, and an `InputError` if
+ * it is malformed or ends before its last frame. Plaintext is emitted as
+ * each frame authenticates, so discard it if the stream errors.
+ */
+export declare class DecryptStream extends Transform {
+  constructor(session: AsherahSession, options?: TransformOptions);
+}
+
+/** The native encryptor under {@link EncryptStream}. */
+export declare class StreamEncryptor {
+  constructor(session: AsherahSession, chunkSize?: number);
+  /** Encrypt `data`, returning the frames it completed (possibly none). */
+  update(data: Buffer): Buffer;
+  /** Return the last frame. */
+  finish(): Buffer;
+}
+
+/** The native decryptor under {@link DecryptStream}. */
+export declare class StreamDecryptor {
+  constructor(session: AsherahSession);
+  /** Decrypt the next piece, returning the plaintext of the frames it
+   *  completed (possibly none). */
+  update(data: Buffer): Buffer;
+  /** Throw unless the whole stream has been read. */
+  finish(): void;
+}
+
+// ─── Errors ─────────────────────────────────────────────────────────────────
+
+/** Category of an {@link AsherahError}. */
+export type ErrorCategory =
+  | 'INPUT'
+  | 'CONFIG'
+  | 'STATE'
+  | 'KEY'
+  | 'METASTORE'
+  | 'KMS'
+  | 'CRYPTO'
+  | 'INTERNAL';
+
+/**
+ * Base class of every error thrown or rejected by this module, apart from
+ * argument marshalling `TypeError`s. The code is stable across releases and
+ * shared with the other Asherah bindings; branch on it, not on the message.
+ */
+export declare class AsherahError extends Error {
+  /** Code name, e.g. `'KMS_ACCESS_DENIED'`. */
+  readonly code: string;
+  /** Numeric code, e.g. `601`. */
+  readonly errorCode: number;
+  readonly category: ErrorCategory;
+  /** Whether the call may succeed if retried later (a metastore or KMS
+   *  outage). */
+  readonly retryable: boolean;
+}
+/** An argument or DataRowRecord is invalid. */
+export declare class InputError extends AsherahError {}
+/** The configuration is invalid. */
+export declare class ConfigError extends AsherahError {}
+/** Called before setup, after shutdown or close, or twice. */
+export declare class StateError extends AsherahError {}
+/** A key the record refers to is not in the metastore. */
+export declare class KeyNotFoundError extends AsherahError {}
+/** The metastore failed the request. */
+export declare class MetastoreError extends AsherahError {}
+/** The KMS failed or refused the request. */
+export declare class KmsError extends AsherahError {}
+/** The data or a wrapped key failed authenticated decryption. */
+export declare class DecryptionError extends AsherahError {}
+
+/** One of Asherah's stable error codes. */
+export type ErrorCodeInfo = {
+  code: number;
+  name: string;
+  category: ErrorCategory;
+  retryable: boolean;
+};
+
+/** Every stable error code. */
+export declare function errorCodes(): ErrorCodeInfo[];
+
+/** A value or a Promise of it. */
+export type MaybePromise<T> = T | Promise<T>;
+
+/**
+ * A metastore implemented in JavaScript, e.g. over an existing key table.
+ * Records are the JSON Asherah writes to its SQL and DynamoDB metastores;
+ * store them as opaque values under `(id, created)`.
+ */
+export interface Metastore {
+  /** Identifies the backend to the config drift guard. Default `'node'`;
+   *  keep it stable across deploys. */
+  name?: string;
+  /** The record stored under `(id, created)`, or `null`/`undefined`. */
+  load(id: string, created: number): MaybePromise<string | Buffer | null | undefined>;
+  /** The record for `id` with the greatest `created`, or `null`/`undefined`. */
+  loadLatest(id: string): MaybePromise<string | Buffer | null | undefined>;
+  /** Insert the record; `false` if `(id, created)` already exists. Never
+   *  overwrite an existing record. */
+  store(id: string, created: number, record: string): MaybePromise<boolean>;
+}
+
+/** A KMS implemented in JavaScript, wrapping and unwrapping system keys. */
+export interface KeyManagementService {
+  /** Identifies the backend to the config drift guard. Default `'node'`. */
+  name?: string;
+  /** Wrap a plaintext system key. */
+  encryptKey(key: Buffer): MaybePromise<Buffer | Uint8Array>;
+  /** Unwrap what {@link encryptKey} returned. */
+  decryptKey(blob: Buffer): MaybePromise<Buffer | Uint8Array>;
+}
+
+/** Backends for {@link SessionFactory.withBackends}; either may be left out. */
+export type Backends = {
+  metastore?: Metastore;
+  kms?: KeyManagementService;
+};
+
 // ─── Observability hooks ────────────────────────────────────────────────────
 
 /** Log severity strings carried in {@link LogEvent.level}. */
@@ -1,5 +1,6 @@
 const path = require('path');
 const os = require('os');
+const { Transform } = require('stream');
 
 // Detect musl libc (Alpine Linux, etc.)
 function isMusl() {
@@ -128,6 +129,10 @@ const CONFIG_MAP = {
   EnableCanaries: 'enableCanaries',
   // KMS: AWS
   KmsKeyId: 'kmsKeyId',
+  // KMS: master-key rotation window
+  PreviousKmsKeyId: 'previousKmsKeyId',
+  PreviousRegionMap: 'previousRegionMap',
+  PreviousStaticMasterKeyHex: 'previousStaticMasterKeyHex',
   // KMS: Secrets Manager
   SecretsManagerSecretId: 'secretsManagerSecretId',
   // KMS: Vault Transit
@@ -138,11 +143,16 @@ const CONFIG_MAP = {
   VaultAuthMount: 'vaultAuthMount',
   VaultApproleRoleId: 'vaultApproleRoleId',
   VaultApproleSecretId: 'vaultApproleSecretId',
+  VaultApproleSecretIdFile: 'vaultApproleSecretIdFile',
   VaultClientCert: 'vaultClientCert',
   VaultClientKey: 'vaultClientKey',
   VaultK8sTokenPath: 'vaultK8sTokenPath',
   VaultTransitKey: 'vaultTransitKey',
   VaultTransitMount: 'vaultTransitMount',
+  VaultTransitKeyVersion: 'vaultTransitKeyVersion',
+  // KMS: quorum
+  KmsQuorumMembers: 'kmsQuorumMembers',
+  KmsQuorumThreshold: 'kmsQuorumThreshold',
 };
 
 // Legacy/debug metastore aliases (match Go behavior)
@@ -284,6 +294,101 @@ function clearPlaintext(plaintext) {
   plaintext.fill(0);
 }
 
+// --- Typed errors ---
+//
+// Native errors carry one of Asherah's stable codes as a `[NAME] ` prefix on
+// the message, because napi-rs cannot give a rejected promise its own `code`.
+// Everything exported from this module rethrows them as an AsherahError
+// subclass picked by the code's category, with the prefix removed.
+
+const ERROR_CODES = new Map(native.errorCodes().map((info) => [info.name, info]));
+const CODE_PREFIX = /^\[([A-Z_]+)\] /;
+
+class AsherahError extends Error {
+  constructor(message, info) {
+    super(message);
+    this.name = new.target.name;
+    this.code = info.name;
+    this.errorCode = info.code;
+    this.category = info.category;
+    this.retryable = info.retryable;
+  }
+}
+class InputError extends AsherahError {}
+class ConfigError extends AsherahError {}
+class StateError extends AsherahError {}
+class KeyNotFoundError extends AsherahError {}
+class MetastoreError extends AsherahError {}
+class KmsError extends AsherahError {}
+class DecryptionError extends AsherahError {}
+
+const ERROR_CLASSES = {
+  INPUT: InputError,
+  CONFIG: ConfigError,
+  STATE: StateError,
+  KEY: KeyNotFoundError,
+  METASTORE: MetastoreError,
+  KMS: KmsError,
+  CRYPTO: DecryptionError,
+};
+
+function toAsherahError(err) {
+  if (!(err instanceof Error) || err instanceof AsherahError) return err;
+  const match = CODE_PREFIX.exec(err.message);
+  const info = match && ERROR_CODES.get(match[1]);
+  if (!info) return err;
+  const ErrorClass = ERROR_CLASSES[info.category] || AsherahError;
+  return new ErrorClass(err.message.slice(match[0].length), info);
+}
+
+function withTypedErrors(fn) {
+  return function (...args) {
+    let result;
+    try {
+      result = fn.apply(this, args);
+    } catch (err) {
+      throw toAsherahError(err);
+    }
+    if (result && typeof result.then === 'function') {
+      return result.catch((err) => {
+        throw toAsherahError(err);
+      });
+    }
+    return result;
+  };
+}
+
+// Instance methods are wrapped in place on the prototype, so instances the
+// addon hands out (e.g. from getSession()) throw typed errors too. The
+// native statics are not configurable, so the constructor and statics are
+// wrapped on a stand-in class sharing the native prototype.
+function withTypedClass(NativeClass) {
+  const proto = NativeClass.prototype;
+  for (const key of Object.getOwnPropertyNames(proto)) {
+    const desc = Object.getOwnPropertyDescriptor(proto, key);
+    if (key === 'constructor' || typeof desc.value !== 'function') continue;
+    Object.defineProperty(proto, key, { ...desc, value: withTypedErrors(desc.value) });
+  }
+  function TypedClass(...args) {
+    if (!new.target) {
+      throw new TypeError(`Class constructor ${NativeClass.name} cannot be invoked without 'new'`);
+    }
+    try {
+      return Reflect.construct(NativeClass, args);
+    } catch (err) {
+      throw toAsherahError(err);
+    }
+  }
+  TypedClass.prototype = proto;
+  Object.defineProperty(TypedClass, 'name', { value: NativeClass.name });
+  for (const key of Object.getOwnPropertyNames(NativeClass)) {
+    const value = NativeClass[key];
+    if (['length', 'name', 'prototype'].includes(key) || typeof value !== 'function') continue;
+    TypedClass[key] = withTypedErrors(value);
+  }
+  return TypedClass;
+}
+
 // Export everything from native addon
 Object.assign(module.exports, native);
 
@@ -308,3 +413,85 @@ module.exports.set_safety_padding_overhead = native.setSafetyPaddingOverhead;
 module.exports.set_log_hook = set_log_hook;
 module.exports.set_metrics_hook = native.setMetricsHook;
 module.exports.get_setup_status = native.getSetupStatus;
+
+// Typed errors: wrap every exported function and native class.
+const NATIVE_CLASSES = ['SessionFactory', 'AsherahSession', 'StreamEncryptor', 'StreamDecryptor'];
+for (const [name, value] of Object.entries(module.exports)) {
+  if (typeof value !== 'function' || NATIVE_CLASSES.includes(name)) continue;
+  module.exports[name] = withTypedErrors(value);
+}
+for (const name of NATIVE_CLASSES) {
+  module.exports[name] = withTypedClass(native[name]);
+}
+
+// --- Streams ---
+//
+// Transforms over the native StreamEncryptor/StreamDecryptor. Each chunk is
+// encrypted or decrypted on the JS thread as it arrives; a frame holds at
+// most `chunkSize` bytes of plaintext (64 KiB by default).
+
+function toChunk(chunk, encoding) {
+  return typeof chunk === 'string' ? Buffer.from(chunk, encoding) : chunk;
+}
+
+function streamStep(callback, step) {
+  let out;
+  try {
+    out = step();
+  } catch (err) {
+    callback(toAsherahError(err));
+    return;
+  }
+  callback(null, out && out.length > 0 ? out : undefined);
+}
+
+class EncryptStream extends Transform {
+  constructor(session, options = {}) {
+    const { chunkSize, ...transformOptions } = options;
+    super(transformOptions);
+    this._encryptor = new module.exports.StreamEncryptor(session, chunkSize);
+  }
+
+  _transform(chunk, encoding, callback) {
+    streamStep(callback, () => this._encryptor.update(toChunk(chunk, encoding)));
+  }
+
+  _flush(callback) {
+    streamStep(callback, () => this._encryptor.finish());
+  }
+}
+
+class DecryptStream extends Transform {
+  constructor(session, options = {}) {
+    super(options);
+    this._decryptor = new module.exports.StreamDecryptor(session);
+  }
+
+  _transform(chunk, encoding, callback) {
+    streamStep(callback, () => this._decryptor.update(toChunk(chunk, encoding)));
+  }
+
+  _flush(callback) {
+    streamStep(callback, () => this._decryptor.finish());
+  }
+}
+
+native.AsherahSession.prototype.encryptStream = function (options) {
+  return new EncryptStream(this, options);
+};
+native.AsherahSession.prototype.decryptStream = function (options) {
+  return new DecryptStream(this, options);
+};
+
+Object.assign(module.exports, {
+  EncryptStream,
+  DecryptStream,
+  AsherahError,
+  InputError,
+  ConfigError,
+  StateError,
+  KeyNotFoundError,
+  MetastoreError,
+  KmsError,
+  DecryptionError,
+});
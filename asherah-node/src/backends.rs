@@ -0,0 +1,494 @@
+//! JavaScript-implemented metastores and KMSes for `SessionFactory.withBackends`.
+//!
+//! A metastore is any object with `load(id, created)`, `loadLatest(id)` and
+//! `store(id, created, record)`; a KMS is any object with `encryptKey(key)`
+//! and `decryptKey(blob)`. Each method may return its result directly or as
+//! a Promise. An optional string `name` identifies the backend to the config
+//! drift guard (default `"node"`).
+//!
+//! Every method is bound to its object and wrapped in a threadsafe function.
+//! The return value — or, for a Promise, its settled value — is converted on
+//! the JavaScript thread and handed back through a one-shot completion, so no
+//! JS handle ever leaves that thread.
+//!
+//! The async trait methods await that completion. The sync ones block their
+//! thread on it, which is what the async session path does on tokio's
+//! blocking pool, but would deadlock on the JavaScript thread itself: there
+//! they fail and point at the async session methods instead.
+//!
+//! Key records cross as the JSON Asherah writes to its SQL and DynamoDB
+//! metastores; `load` may return it as a string or a Buffer, or
+//! `null`/`undefined` when there is no such record. `store` returns `true`
+//! when it wrote the record and `false` when `(id, created)` already exists,
+//! which it must never overwrite.
+
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::thread::ThreadId;
+
+use asherah::builders::HostBackends;
+use asherah::error::ErrorCode;
+use asherah::traits::{KeyManagementService, Metastore};
+use asherah::types::EnvelopeKeyRecord;
+use async_trait::async_trait;
+use napi::bindgen_prelude::*;
+use napi::threadsafe_function::{
+    ThreadsafeCallContext, ThreadsafeFunction, ThreadsafeFunctionCallMode,
+};
+use napi::{JsValue, Status, ValueType};
+use parking_lot::Mutex;
+use zeroize::Zeroizing;
+
+use crate::{coded_error, JsArgList};
+
+const DEFAULT_BACKEND_NAME: &str = "node";
+
+/// Arguments of one call into a backend method.
+enum Call {
+    Load {
+        id: String,
+        created: i64,
+    },
+    LoadLatest {
+        id: String,
+    },
+    Store {
+        id: String,
+        created: i64,
+        record: String,
+    },
+    Key(Zeroizing<Vec<u8>>),
+}
+
+type MethodFn = ThreadsafeFunction<Call, Unknown<'static>, JsArgList, Status, false, true>;
+
+/// Turns what a method returned into its Rust result. Runs on the JS thread.
+type Convert<T> = fn(Unknown<'_>) -> anyhow::Result<T>;
+
+type Done<T> = Box<dyn FnOnce(anyhow::Result<T>) + Send>;
+
+/// Settles a call exactly once: from its return value, from its Promise,
+/// or from a failure to enqueue it.
+struct Completion<T>(Arc<Mutex<Option<Done<T>>>>);
+
+impl<T> Clone for Completion<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Completion<T> {
+    fn new(done: Done<T>) -> Self {
+        Self(Arc::new(Mutex::new(Some(done))))
+    }
+
+    fn finish(&self, result: anyhow::Result<T>) {
+        let done = self.0.lock().take();
+        if let Some(done) = done {
+            done(result);
+        }
+    }
+}
+
+/// One backend method, bound to its object.
+struct Method {
+    /// e.g. `JavaScript metastore 'mongo' load`, for error messages.
+    what: Arc<str>,
+    js_thread: ThreadId,
+    tsfn: MethodFn,
+}
+
+impl Method {
+    fn bind(obj: &Object<'_>, key: &str, what: String) -> Result<Self> {
+        let value: Unknown<'_> = obj.get(key)?.ok_or_else(|| {
+            coded_error(
+                ErrorCode::InvalidArgument,
+                format!("{what}: {key}() is missing"),
+            )
+        })?;
+        if value.get_type()? != ValueType::Function {
+            return Err(coded_error(
+                ErrorCode::InvalidArgument,
+                format!("{what}: {key} must be a function"),
+            ));
+        }
+        // SAFETY: the type was checked just above.
+        let method: Function<'_, JsArgList, Unknown<'static>> = unsafe { value.cast()? };
+        let tsfn = method
+            .bind(obj)?
+            .build_threadsafe_function::<Call>()
+            .weak::<true>()
+            .callee_handled::<false>()
+            .build_callback(|ctx: ThreadsafeCallContext<Call>| {
+                let env = ctx.env;
+                let args = match ctx.value {
+                    Call::Load { id, created } => {
+                        vec![
+                            env.create_string(&id)?.raw(),
+                            env.create_int64(created)?.raw(),
+                        ]
+                    }
+                    Call::LoadLatest { id } => vec![env.create_string(&id)?.raw()],
+                    Call::Store {
+                        id,
+                        created,
+                        record,
+                    } => vec![
+                        env.create_string(&id)?.raw(),
+                        env.create_int64(created)?.raw(),
+                        env.create_string(&record)?.raw(),
+                    ],
+                    // Copied into a V8-owned buffer so ours is wiped on drop.
+                    Call::Key(bytes) => vec![BufferSlice::copy_from(&env, &*bytes)?.raw()],
+                };
+                Ok(JsArgList(args))
+            })?;
+        Ok(Self {
+            what: what.into(),
+            js_thread: std::thread::current().id(),
+            tsfn,
+        })
+    }
+
+    fn invoke<T: Send + 'static>(&self, call: Call, convert: Convert<T>, done: Done<T>) {
+        let completion = Completion::new(done);
+        let settle = completion.clone();
+        let what = Arc::clone(&self.what);
+        let status = self.tsfn.call_with_return_value(
+            call,
+            ThreadsafeFunctionCallMode::NonBlocking,
+            move |ret, env| {
+                settle_on_js_thread(&env, &what, ret, convert, settle);
+                Ok(())
+            },
+        );
+        if status != Status::Ok {
+            completion.finish(Err(anyhow::anyhow!(
+                "{} could not be called: {status:?}",
+                self.what
+            )));
+        }
+    }
+
+    fn call<T: Send + 'static>(&self, call: Call, convert: Convert<T>) -> anyhow::Result<T> {
+        if std::thread::current().id() == self.js_thread {
+            anyhow::bail!(
+                "{} cannot run synchronously on the JavaScript thread; use the async session methods",
+                self.what
+            );
+        }
+        let (tx, rx) = mpsc::sync_channel(1);
+        self.invoke(call, convert, Box::new(move |result| drop(tx.send(result))));
+        rx.recv()
+            .map_err(|_| anyhow::anyhow!("{} was abandoned", self.what))?
+    }
+
+    async fn call_async<T: Send + 'static>(
+        &self,
+        call: Call,
+        convert: Convert<T>,
+    ) -> anyhow::Result<T> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        self.invoke(call, convert, Box::new(move |result| drop(tx.send(result))));
+        rx.await
+            .map_err(|_| anyhow::anyhow!("{} was abandoned", self.what))?
+    }
+}
+
+fn settle_on_js_thread<T: Send + 'static>(
+    env: &Env,
+    what: &Arc<str>,
+    ret: Result<Unknown<'static>>,
+    convert: Convert<T>,
+    completion: Completion<T>,
+) {
+    let value = match ret {
+        Ok(value) => value,
+        // A synchronous throw; the reason is "<String(error)>\n<stack>".
+        Err(e) => {
+            let message = e.reason.lines().next().unwrap_or_default();
+            return completion.finish(Err(anyhow::anyhow!("{what} failed: {message}")));
+        }
+    };
+    match value.is_promise() {
+        Ok(true) => {
+            if let Err(e) = settle_promise(env, what, value, convert, completion.clone()) {
+                completion.finish(Err(anyhow::anyhow!(
+                    "{what} returned a Promise that could not be awaited: {e}"
+                )));
+            }
+        }
+        Ok(false) => {
+            completion.finish(convert(value).map_err(|e| anyhow::anyhow!("{what} returned {e}")))
+        }
+        Err(e) => completion.finish(Err(anyhow::anyhow!("{what}: {e}"))),
+    }
+}
+
+fn settle_promise<T: Send + 'static>(
+    env: &Env,
+    what: &Arc<str>,
+    value: Unknown<'static>,
+    convert: Convert<T>,
+    completion: Completion<T>,
+) -> Result<()> {
+    // SAFETY: `value` was just checked to be a Promise of this env.
+    let promise =
+        unsafe { PromiseRaw::<Unknown<'static>>::from_napi_value(env.raw(), value.raw())? };
+    let resolved = completion.clone();
+    let resolved_what = Arc::clone(what);
+    let rejected_what = Arc::clone(what);
+    promise
+        .then(move |ctx: CallbackContext<Unknown<'static>>| {
+            resolved.finish(
+                convert(ctx.value).map_err(|e| anyhow::anyhow!("{resolved_what} resolved to {e}")),
+            );
+            Ok(())
+        })?
+        .catch(move |ctx: CallbackContext<Unknown<'static>>| {
+            let message = js_string(ctx.value).unwrap_or_else(|_| "(unprintable)".to_string());
+            let message = message.lines().next().unwrap_or_default();
+            completion.finish(Err(anyhow::anyhow!("{rejected_what} failed: {message}")));
+            Ok(())
+        })?;
+    Ok(())
+}
+
+fn js_string(value: Unknown<'_>) -> Result<String> {
+    value.coerce_to_string()?.into_utf8()?.into_owned()
+}
+
+fn js_bytes(value: Unknown<'_>) -> anyhow::Result<Vec<u8>> {
+    if value.get_type()? == ValueType::Object {
+        // SAFETY: validated as a Buffer/Uint8Array by BufferSlice itself.
+        if let Ok(buf) = unsafe { value.cast::<BufferSlice<'_>>() } {
+            return Ok(buf.to_vec());
+        }
+    }
+    anyhow::bail!("something other than a Buffer")
+}
+
+fn record(value: Unknown<'_>) -> anyhow::Result<Option<String>> {
+    match value.get_type()? {
+        ValueType::Null | ValueType::Undefined => Ok(None),
+        ValueType::String => Ok(Some(js_string(value)?)),
+        _ => {
+            let bytes = js_bytes(value)
+                .map_err(|_| anyhow::anyhow!("something other than a string, Buffer or null"))?;
+            String::from_utf8(bytes)
+                .map(Some)
+                .map_err(|_| anyhow::anyhow!("a non-UTF-8 record"))
+        }
+    }
+}
+
+fn stored(value: Unknown<'_>) -> anyhow::Result<bool> {
+    if value.get_type()? != ValueType::Boolean {
+        anyhow::bail!("something other than a boolean");
+    }
+    // SAFETY: the type was checked just above.
+    Ok(unsafe { value.cast::<bool>()? })
+}
+
+fn key_bytes(value: Unknown<'_>) -> anyhow::Result<Vec<u8>> {
+    js_bytes(value)
+}
+
+fn backend_name(obj: &Object<'_>, kind: &str) -> Result<String> {
+    let name: Option<Unknown<'_>> = obj.get("name")?;
+    let Some(name) =
+        name.filter(|v| !matches!(v.get_type(), Ok(ValueType::Undefined | ValueType::Null)))
+    else {
+        return Ok(DEFAULT_BACKEND_NAME.to_string());
+    };
+    if name.get_type()? != ValueType::String {
+        return Err(coded_error(
+            ErrorCode::InvalidArgument,
+            format!("{kind} name must be a string"),
+        ));
+    }
+    let name = js_string(name)?;
+    if name.trim().is_empty() {
+        return Err(coded_error(
+            ErrorCode::InvalidArgument,
+            format!("{kind} name must not be empty"),
+        ));
+    }
+    Ok(name)
+}
+
+struct JsMetastore {
+    what: String,
+    load: Method,
+    load_latest: Method,
+    store: Method,
+}
+
+impl JsMetastore {
+    fn new(obj: &Object<'_>) -> Result<Self> {
+        let name = backend_name(obj, "metastore")?;
+        let what = format!("JavaScript metastore '{name}'");
+        Ok(Self {
+            load: Method::bind(obj, "load", format!("{what} load"))?,
+            load_latest: Method::bind(obj, "loadLatest", format!("{what} loadLatest"))?,
+            store: Method::bind(obj, "store", format!("{what} store"))?,
+            what,
+        })
+    }
+
+    fn parse(&self, id: &str, json: Option<String>) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let Some(json) = json else {
+            return Ok(None);
+        };
+        let mut ekr = EnvelopeKeyRecord::from_json_fast(&json)
+            .map_err(|e| anyhow::anyhow!("{} returned an invalid record: {e}", self.what))?;
+        ekr.id = id.to_string();
+        Ok(Some(ekr))
+    }
+}
+
+#[async_trait]
+impl Metastore for JsMetastore {
+    fn load(&self, id: &str, created: i64) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let call = Call::Load {
+            id: id.to_string(),
+            created,
+        };
+        let json = self.load.call(call, record)?;
+        self.parse(id, json)
+    }
+
+    fn load_latest(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let call = Call::LoadLatest { id: id.to_string() };
+        let json = self.load_latest.call(call, record)?;
+        self.parse(id, json)
+    }
+
+    fn store(&self, id: &str, created: i64, ekr: &EnvelopeKeyRecord) -> anyhow::Result<bool> {
+        let call = Call::Store {
+            id: id.to_string(),
+            created,
+            record: serde_json::to_string(ekr)?,
+        };
+        self.store.call(call, stored)
+    }
+
+    async fn load_async(
+        &self,
+        id: &str,
+        created: i64,
+    ) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let call = Call::Load {
+            id: id.to_string(),
+            created,
+        };
+        let json = self.load.call_async(call, record).await?;
+        self.parse(id, json)
+    }
+
+    async fn load_latest_async(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let call = Call::LoadLatest { id: id.to_string() };
+        let json = self.load_latest.call_async(call, record).await?;
+        self.parse(id, json)
+    }
+
+    async fn store_async(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> anyhow::Result<bool> {
+        let call = Call::Store {
+            id: id.to_string(),
+            created,
+            record: serde_json::to_string(ekr)?,
+        };
+        self.store.call_async(call, stored).await
+    }
+
+    async fn upsert_config_drift_guard_async(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> anyhow::Result<()> {
+        if self.store_async(id, created, ekr).await? {
+            Ok(())
+        } else {
+            anyhow::bail!("metastore does not support replacing config drift guard records")
+        }
+    }
+}
+
+struct JsKms {
+    encrypt_key: Method,
+    decrypt_key: Method,
+}
+
+impl JsKms {
+    fn new(obj: &Object<'_>) -> Result<Self> {
+        let name = backend_name(obj, "KMS")?;
+        let what = format!("JavaScript KMS '{name}'");
+        Ok(Self {
+            encrypt_key: Method::bind(obj, "encryptKey", format!("{what} encryptKey"))?,
+            decrypt_key: Method::bind(obj, "decryptKey", format!("{what} decryptKey"))?,
+        })
+    }
+}
+
+#[async_trait]
+impl KeyManagementService for JsKms {
+    fn encrypt_key(&self, _ctx: &(), key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let call = Call::Key(Zeroizing::new(key_bytes.to_vec()));
+        self.encrypt_key.call(call, self::key_bytes)
+    }
+
+    fn decrypt_key(&self, _ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let call = Call::Key(Zeroizing::new(blob.to_vec()));
+        self.decrypt_key.call(call, key_bytes)
+    }
+
+    async fn encrypt_key_async(&self, _ctx: &(), key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let call = Call::Key(Zeroizing::new(key_bytes.to_vec()));
+        self.encrypt_key.call_async(call, self::key_bytes).await
+    }
+
+    async fn decrypt_key_async(&self, _ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let call = Call::Key(Zeroizing::new(blob.to_vec()));
+        self.decrypt_key.call_async(call, key_bytes).await
+    }
+}
+
+/// Reads `{ metastore?, kms? }` into [`HostBackends`]. Must run on the
+/// JavaScript thread that owns the objects.
+pub(crate) fn host_backends(backends: &Object<'_>) -> Result<HostBackends> {
+    let mut host = HostBackends::default();
+    let metastore: Option<Object<'_>> = backends.get("metastore")?;
+    if let Some(obj) = metastore {
+        host = host.with_metastore(
+            backend_name(&obj, "metastore")?,
+            Arc::new(JsMetastore::new(&obj)?),
+        );
+    }
+    let kms: Option<Object<'_>> = backends.get("kms")?;
+    if let Some(obj) = kms {
+        host = host.with_kms(backend_name(&obj, "KMS")?, Arc::new(JsKms::new(&obj)?));
+    }
+    Ok(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completion_settles_once() {
+        let (tx, rx) = mpsc::channel();
+        let completion: Completion<u8> = Completion::new(Box::new(move |r| {
+            drop(tx.send(r.map_err(|e| e.to_string())));
+        }));
+        completion.clone().finish(Ok(1));
+        completion.finish(Err(anyhow::anyhow!("late")));
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![Ok(1)]);
+    }
+}
@@ -4,9 +4,9 @@ use lru::LruCache;
 use parking_lot::Mutex;
 use std::collections::HashMap;
 use std::num::NonZeroUsize;
-use std::sync::atomic::{AtomicBool, Ordering};
-use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
 use std::sync::OnceLock;
+use std::sync::{Arc, Weak};
 use std::time::Instant;
 
 use napi::bindgen_prelude::*;
@@ -20,10 +20,13 @@ use napi::threadsafe_function::{
 use napi_derive::napi;
 use once_cell::sync::Lazy;
 
+use asherah::error::ErrorCode;
 use asherah::logging::{ensure_logger as ensure_core_logger, set_sink as set_log_sink, LogSink};
 use asherah::metrics;
 use asherah::metrics::MetricsSink;
 
+mod backends;
+
 type Factory = asherah::session::PublicFactory<
     asherah::aead::AES256GCM,
     asherah::builders::DynKms,
@@ -62,17 +65,77 @@ fn sanitized_anyhow_message(op: &str, err: &anyhow::Error) -> String {
     format!("{op} failed: {err}")
 }
 
+/// An error carrying one of Asherah's stable codes.
+///
+/// napi-rs only lets a rejected promise carry its own `Status` as `code`, so
+/// the stable code travels as a `[NAME] ` prefix on the message instead.
+/// `npm/index.js` turns these into typed `AsherahError`s and strips it.
+fn coded_error(code: ErrorCode, message: impl AsRef<str>) -> Error {
+    Error::from_reason(format!("[{}] {}", code.name(), message.as_ref()))
+}
+
+/// One of Asherah's stable error codes, as listed by `errorCodes()`.
+#[napi(object)]
+#[derive(Debug)]
+pub struct ErrorCodeInfo {
+    pub code: i32,
+    pub name: String,
+    pub category: String,
+    pub retryable: bool,
+}
+
+/// Every stable error code; `npm/index.js` builds its typed errors from these.
+#[napi]
+pub fn error_codes() -> Vec<ErrorCodeInfo> {
+    ErrorCode::ALL
+        .iter()
+        .map(|code| ErrorCodeInfo {
+            code: code.as_i32(),
+            name: code.name().to_string(),
+            category: code.category().name().to_string(),
+            retryable: code.is_retryable(),
+        })
+        .collect()
+}
+
 fn anyhow_to_napi(op: &'static str, err: anyhow::Error) -> Error {
     log::warn!("{op} failed: {err:#}");
-    Error::from_reason(sanitized_anyhow_message(op, &err))
+    coded_error(ErrorCode::of(&err), sanitized_anyhow_message(op, &err))
 }
 
 fn check_plaintext_len_napi(len: usize) -> Result<()> {
-    asherah::limits::check_plaintext_len(len).map_err(|err| Error::from_reason(err.to_string()))
+    asherah::limits::check_plaintext_len(len)
+        .map_err(|err| coded_error(ErrorCode::of(&err), err.to_string()))
 }
 
 fn check_ciphertext_len_napi(len: usize) -> Result<()> {
-    asherah::limits::check_ciphertext_len(len).map_err(|err| Error::from_reason(err.to_string()))
+    asherah::limits::check_ciphertext_len(len)
+        .map_err(|err| coded_error(ErrorCode::of(&err), err.to_string()))
+}
+
+fn invalid_drr_json(err: serde_json::Error) -> Error {
+    coded_error(
+        ErrorCode::InvalidDataRowRecord,
+        format!("invalid DataRowRecord JSON: {err}"),
+    )
+}
+
+fn utf8_error(err: std::string::FromUtf8Error) -> Error {
+    coded_error(ErrorCode::InvalidArgument, format!("utf8 error: {err}"))
+}
+
+fn not_configured() -> Error {
+    coded_error(
+        ErrorCode::NotInitialized,
+        "asherah not configured; call setup() first",
+    )
+}
+
+fn already_configured() -> Error {
+    coded_error(
+        ErrorCode::AlreadyInitialized,
+        "asherah already configured; call shutdown() first",
+    )
 }
 
 #[derive(Debug)]
@@ -82,7 +145,7 @@ pub struct AsherahConfig {
     pub product_id: String,
     pub expire_after: Option<i64>,
     pub check_interval: Option<i64>,
-    pub metastore: String, // "memory" | "rdbms" | "dynamodb"
+    pub metastore: Option<String>, // "memory" | "rdbms" | "dynamodb" | "host"
     pub connection_string: Option<String>,
     pub dynamo_db_endpoint: Option<String>,
     pub dynamo_db_region: Option<String>,
@@ -111,6 +174,10 @@ pub struct AsherahConfig {
     pub pool_max_idle_time: Option<u32>,
     // KMS: AWS
     pub kms_key_id: Option<String>,
+    // KMS: master-key rotation window
+    pub previous_kms_key_id: Option<String>,
+    pub previous_region_map: Option<HashMap<String, String>>,
+    pub previous_static_master_key_hex: Option<String>,
     // KMS: Secrets Manager
     pub secrets_manager_secret_id: Option<String>,
     // KMS: Vault Transit
@@ -121,11 +188,16 @@ pub struct AsherahConfig {
     pub vault_auth_mount: Option<String>,
     pub vault_approle_role_id: Option<String>,
     pub vault_approle_secret_id: Option<String>,
+    pub vault_approle_secret_id_file: Option<String>,
     pub vault_client_cert: Option<String>,
     pub vault_client_key: Option<String>,
     pub vault_k8s_token_path: Option<String>,
     pub vault_transit_key: Option<String>,
     pub vault_transit_mount: Option<String>,
+    pub vault_transit_key_version: Option<u32>,
+    // KMS: quorum
+    pub kms_quorum_members: Option<Vec<String>>,
+    pub kms_quorum_threshold: Option<u32>,
     // KMS: Static
     pub static_master_key_hex: Option<String>,
 }
@@ -136,7 +208,7 @@ fn to_config_options(cfg: &AsherahConfig) -> asherah_config::ConfigOptions {
         product_id: Some(cfg.product_id.clone()),
         expire_after: cfg.expire_after,
         check_interval: cfg.check_interval,
-        metastore: Some(cfg.metastore.clone()),
+        metastore: cfg.metastore.clone(),
         connection_string: cfg.connection_string.clone(),
         replica_read_consistency: cfg.replica_read_consistency.clone(),
         dynamo_db_endpoint: cfg.dynamo_db_endpoint.clone(),
@@ -169,6 +241,9 @@ fn to_config_options(cfg: &AsherahConfig) -> asherah_config::ConfigOptions {
         pool_max_lifetime: cfg.pool_max_lifetime.map(|v| v as u64),
         pool_max_idle_time: cfg.pool_max_idle_time.map(|v| v as u64),
         kms_key_id: cfg.kms_key_id.clone(),
+        previous_kms_key_id: cfg.previous_kms_key_id.clone(),
+        previous_region_map: cfg.previous_region_map.clone(),
+        previous_static_master_key_hex: cfg.previous_static_master_key_hex.clone(),
         secrets_manager_secret_id: cfg.secrets_manager_secret_id.clone(),
         vault_addr: cfg.vault_addr.clone(),
         vault_token: cfg.vault_token.clone(),
@@ -177,11 +252,15 @@ fn to_config_options(cfg: &AsherahConfig) -> asherah_config::ConfigOptions {
         vault_auth_mount: cfg.vault_auth_mount.clone(),
         vault_approle_role_id: cfg.vault_approle_role_id.clone(),
         vault_approle_secret_id: cfg.vault_approle_secret_id.clone(),
+        vault_approle_secret_id_file: cfg.vault_approle_secret_id_file.clone(),
         vault_client_cert: cfg.vault_client_cert.clone(),
         vault_client_key: cfg.vault_client_key.clone(),
         vault_k8s_token_path: cfg.vault_k8s_token_path.clone(),
         vault_transit_key: cfg.vault_transit_key.clone(),
         vault_transit_mount: cfg.vault_transit_mount.clone(),
+        vault_transit_key_version: cfg.vault_transit_key_version,
+        kms_quorum_members: cfg.kms_quorum_members.clone(),
+        kms_quorum_threshold: cfg.kms_quorum_threshold.map(|v| v as usize),
     }
 }
 
@@ -207,9 +286,7 @@ pub fn setup(config: AsherahConfig) -> Result<()> {
 
     let mut guard = STATE.lock();
     if guard.is_some() {
-        return Err(Error::from_reason(
-            "asherah already configured; call shutdown() first",
-        ));
+        return Err(already_configured());
     }
 
     let cap = NonZeroUsize::new(applied.session_cache_max_size)
@@ -244,9 +321,7 @@ pub async fn setup_async(config: AsherahConfig) -> Result<()> {
 
     let mut guard = STATE.lock();
     if guard.is_some() {
-        return Err(Error::from_reason(
-            "asherah already configured; call shutdown() first",
-        ));
+        return Err(already_configured());
     }
 
     let cap = NonZeroUsize::new(applied.session_cache_max_size)
@@ -284,7 +359,7 @@ pub async fn shutdown_async() -> Result<()> {
         drop(tx.send(result));
     });
     rx.await
-        .map_err(|_| Error::from_reason("shutdown thread panicked"))?
+        .map_err(|_| coded_error(ErrorCode::Internal, "shutdown thread panicked"))?
 }
 
 fn with_session<R>(partition_id: &str, fcall: impl FnOnce(&Session) -> Result<R>) -> Result<R> {
@@ -292,9 +367,7 @@ fn with_session<R>(partition_id: &str, fcall: impl FnOnce(&Session) -> Result<R>
 
     {
         let mut guard = STATE.lock();
-        let state = guard
-            .as_mut()
-            .ok_or_else(|| Error::from_reason("asherah not configured; call setup() first"))?;
+        let state = guard.as_mut().ok_or_else(not_configured)?;
 
         if state.session_caching {
             // LruCache::get reorders to MRU on hit; put inserts and may
@@ -333,9 +406,7 @@ fn with_session<R>(partition_id: &str, fcall: impl FnOnce(&Session) -> Result<R>
 /// Get a session for async operations. Returns an owned Arc so the lock is dropped before await.
 fn get_session_arc(partition_id: &str) -> Result<(Arc<Session>, bool)> {
     let mut guard = STATE.lock();
-    let state = guard
-        .as_mut()
-        .ok_or_else(|| Error::from_reason("asherah not configured; call setup() first"))?;
+    let state = guard.as_mut().ok_or_else(not_configured)?;
 
     if state.session_caching {
         let session = if let Some(existing) = state.sessions.get(partition_id) {
@@ -414,8 +485,8 @@ pub fn decrypt(partition_id: String, data_row_record: Buffer) -> Result<Buffer>
     check_ciphertext_len_napi(data_row_record.len())?;
     let t0 = Instant::now();
     let t_parse0 = Instant::now();
-    let drr: asherah::types::DataRowRecord = serde_json::from_slice(&data_row_record)
-        .map_err(|e| Error::from_reason(format!("invalid DataRowRecord JSON: {e}")))?;
+    let drr: asherah::types::DataRowRecord =
+        serde_json::from_slice(&data_row_record).map_err(invalid_drr_json)?;
     debug_log(&format!(
         "decrypt json parse {} us",
         t_parse0.elapsed().as_micros()
@@ -436,8 +507,8 @@ pub fn decrypt(partition_id: String, data_row_record: Buffer) -> Result<Buffer>
 #[napi]
 pub async fn decrypt_async(partition_id: String, data_row_record: Buffer) -> Result<Buffer> {
     check_ciphertext_len_napi(data_row_record.len())?;
-    let drr: asherah::types::DataRowRecord = serde_json::from_slice(&data_row_record)
-        .map_err(|e| Error::from_reason(format!("invalid DataRowRecord JSON: {e}")))?;
+    let drr: asherah::types::DataRowRecord =
+        serde_json::from_slice(&data_row_record).map_err(invalid_drr_json)?;
     let (session, cached) = get_session_arc(&partition_id)?;
     let pt = session
         .decrypt_async(drr)
@@ -463,21 +534,38 @@ pub async fn encrypt_string_async(partition_id: String, data: String) -> Result<
 pub fn decrypt_string(partition_id: String, drr: String) -> Result<String> {
     check_ciphertext_len_napi(drr.len())?;
     let buf = decrypt(partition_id, Buffer::from(drr.into_bytes()))?;
-    String::from_utf8(buf.to_vec()).map_err(|e| Error::from_reason(format!("utf8 error: {e}")))
+    String::from_utf8(buf.to_vec()).map_err(utf8_error)
 }
 
 #[napi]
 pub async fn decrypt_string_async(partition_id: String, drr: String) -> Result<String> {
     check_ciphertext_len_napi(drr.len())?;
     let buf = decrypt_async(partition_id, Buffer::from(drr.into_bytes())).await?;
-    String::from_utf8(buf.to_vec()).map_err(|e| Error::from_reason(format!("utf8 error: {e}")))
+    String::from_utf8(buf.to_vec()).map_err(utf8_error)
 }
 
 // ── Factory/Session API ─────────────────────────────────────────────
 
+/// Factories shared with `SessionFactory.share()`, by handle. Entries are
+/// weak: a handle resolves while any `SessionFactory` over it is open.
+static SHARED_FACTORIES: Lazy<Mutex<HashMap<u32, Weak<Factory>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+static NEXT_SHARE_HANDLE: AtomicU32 = AtomicU32::new(1);
+
+/// `SessionFactory.cacheStats()`: cache sizes and session cache hit counts.
+#[napi(object)]
+#[derive(Debug)]
+pub struct CacheStats {
+    pub sessions: i64,
+    pub session_cache_hits: i64,
+    pub session_cache_misses: i64,
+    pub intermediate_keys: i64,
+    pub system_keys: i64,
+}
+
 #[napi]
 pub struct SessionFactory {
-    factory: Mutex<Option<Factory>>,
+    factory: Mutex<Option<Arc<Factory>>>,
 }
 
 impl std::fmt::Debug for SessionFactory {
@@ -490,44 +578,143 @@ impl std::fmt::Debug for SessionFactory {
 
 #[napi]
 impl SessionFactory {
+    fn wrap(factory: Factory) -> Self {
+        Self {
+            factory: Mutex::new(Some(Arc::new(factory))),
+        }
+    }
+
+    fn open_factory(&self) -> Result<Arc<Factory>> {
+        self.factory
+            .lock()
+            .clone()
+            .ok_or_else(|| coded_error(ErrorCode::Closed, "factory is closed"))
+    }
+
     #[napi(constructor)]
     pub fn new(config: AsherahConfig) -> Result<Self> {
         let opts = to_config_options(&config);
         let (factory, _applied) = asherah_config::factory_from_config(&opts)
             .map_err(|e| anyhow_to_napi("factory creation", e))?;
         // Always enable per-factory metrics — see comment in setup().
-        let factory = factory.with_metrics(true);
-        Ok(Self {
-            factory: Mutex::new(Some(factory)),
-        })
+        Ok(Self::wrap(factory.with_metrics(true)))
     }
 
     #[napi(factory)]
     pub fn from_env() -> Result<Self> {
         let factory = asherah::builders::factory_from_env()
             .map_err(|e| anyhow_to_napi("factory_from_env", e))?;
-        let factory = factory.with_metrics(true);
+        Ok(Self::wrap(factory.with_metrics(true)))
+    }
+
+    /// Build a factory around a JavaScript-implemented metastore and/or KMS
+    /// (see `backends.rs`). Resolves once the config drift guard has been
+    /// checked through them. Sessions of such a factory must use the async
+    /// encrypt/decrypt methods.
+    #[napi(ts_return_type = "Promise<SessionFactory>")]
+    pub fn with_backends<'env>(
+        env: &'env Env,
+        config: AsherahConfig,
+        backends: Object<'_>,
+    ) -> Result<PromiseRaw<'env, SessionFactory>> {
+        let backends = backends::host_backends(&backends)?;
+        let opts = to_config_options(&config);
+        env.spawn_future(async move {
+            let (factory, _applied) =
+                asherah_config::factory_from_config_with_backends_async(&opts, &backends)
+                    .await
+                    .map_err(|e| anyhow_to_napi("factory creation", e))?;
+            Ok(Self::wrap(factory.with_metrics(true)))
+        })
+    }
+
+    /// Open another `SessionFactory` over the factory shared as `handle`.
+    /// The addon is loaded once per process, so this works from any
+    /// `worker_threads` worker: every worker then uses the same key caches,
+    /// session cache and metastore connections.
+    #[napi(factory)]
+    pub fn from_handle(handle: u32) -> Result<Self> {
+        let factory = SHARED_FACTORIES
+            .lock()
+            .get(&handle)
+            .and_then(Weak::upgrade)
+            .ok_or_else(|| {
+                coded_error(
+                    ErrorCode::Closed,
+                    format!("no open factory is shared as handle {handle}"),
+                )
+            })?;
         Ok(Self {
             factory: Mutex::new(Some(factory)),
         })
     }
 
+    /// Share this factory across worker threads. Returns a handle for
+    /// `SessionFactory.fromHandle`; the same handle every time.
+    #[napi]
+    pub fn share(&self) -> Result<u32> {
+        let factory = self.open_factory()?;
+        let mut shared = SHARED_FACTORIES.lock();
+        shared.retain(|_, weak| weak.strong_count() > 0);
+        if let Some((&handle, _)) = shared
+            .iter()
+            .find(|(_, weak)| std::ptr::eq(weak.as_ptr(), Arc::as_ptr(&factory)))
+        {
+            return Ok(handle);
+        }
+        let handle = NEXT_SHARE_HANDLE.fetch_add(1, Ordering::Relaxed);
+        shared.insert(handle, Arc::downgrade(&factory));
+        Ok(handle)
+    }
+
     #[napi]
     pub fn get_session(&self, partition_id: String) -> Result<AsherahSession> {
         let guard = self.factory.lock();
         let factory = guard
             .as_ref()
-            .ok_or_else(|| Error::from_reason("factory is closed"))?;
+            .ok_or_else(|| coded_error(ErrorCode::Closed, "factory is closed"))?;
         let session = factory.get_session(&partition_id);
         Ok(AsherahSession {
-            session: Mutex::new(Some(session)),
+            session: Mutex::new(Some(Arc::new(session))),
         })
     }
 
+    /// Sizes and hit counts of this factory's caches. The session counts
+    /// are 0 with session caching disabled.
+    #[napi]
+    pub fn cache_stats(&self) -> Result<CacheStats> {
+        let stats = self.open_factory()?.cache_stats();
+        Ok(CacheStats {
+            sessions: stats.sessions as i64,
+            session_cache_hits: stats.session_cache_hits as i64,
+            session_cache_misses: stats.session_cache_misses as i64,
+            intermediate_keys: stats.intermediate_keys as i64,
+            system_keys: stats.system_keys as i64,
+        })
+    }
+
+    /// Drop `partitionId`'s cached session; returns whether one was cached.
+    /// Sessions already handed out keep working.
+    #[napi]
+    pub fn evict_session(&self, partition_id: String) -> Result<bool> {
+        Ok(self.open_factory()?.evict_session(&partition_id))
+    }
+
+    /// Empty the session cache and the system and intermediate key caches,
+    /// so keys are loaded from the metastore again: after a key
+    /// revocation, or to release memory.
+    #[napi]
+    pub fn flush_caches(&self) -> Result<()> {
+        self.open_factory()?.flush_caches();
+        Ok(())
+    }
+
+    /// Close this instance. A shared factory is closed by whichever
+    /// instance over it closes last.
     #[napi]
     pub fn close(&self) -> Result<()> {
-        let mut guard = self.factory.lock();
-        if let Some(factory) = guard.take() {
+        let taken = self.factory.lock().take();
+        if let Some(factory) = taken.and_then(Arc::into_inner) {
             factory
                 .close()
                 .map_err(|e| anyhow_to_napi("factory close", e))?;
@@ -538,7 +725,7 @@ impl SessionFactory {
 
 #[napi]
 pub struct AsherahSession {
-    session: Mutex<Option<Session>>,
+    session: Mutex<Option<Arc<Session>>>,
 }
 
 impl std::fmt::Debug for AsherahSession {
@@ -551,13 +738,21 @@ impl std::fmt::Debug for AsherahSession {
 
 #[napi]
 impl AsherahSession {
+    /// Clone the session out so the lock is not held across an await.
+    fn open_session(&self) -> Result<Arc<Session>> {
+        self.session
+            .lock()
+            .clone()
+            .ok_or_else(|| coded_error(ErrorCode::Closed, "session is closed"))
+    }
+
     #[napi]
     pub fn encrypt(&self, data: Buffer) -> Result<String> {
         check_plaintext_len_napi(data.len())?;
         let guard = self.session.lock();
         let session = guard
             .as_ref()
-            .ok_or_else(|| Error::from_reason("session is closed"))?;
+            .ok_or_else(|| coded_error(ErrorCode::Closed, "session is closed"))?;
         let drr = session
             .encrypt(&data)
             .map_err(|e| anyhow_to_napi("encrypt", e))?;
@@ -572,12 +767,12 @@ impl AsherahSession {
     #[napi]
     pub fn decrypt(&self, data_row_record: String) -> Result<Buffer> {
         check_ciphertext_len_napi(data_row_record.len())?;
-        let drr: asherah::types::DataRowRecord = serde_json::from_str(&data_row_record)
-            .map_err(|e| Error::from_reason(format!("invalid DataRowRecord JSON: {e}")))?;
+        let drr: asherah::types::DataRowRecord =
+            serde_json::from_str(&data_row_record).map_err(invalid_drr_json)?;
         let guard = self.session.lock();
         let session = guard
             .as_ref()
-            .ok_or_else(|| Error::from_reason("session is closed"))?;
+            .ok_or_else(|| coded_error(ErrorCode::Closed, "session is closed"))?;
         let pt = session
             .decrypt(drr)
             .map_err(|e| anyhow_to_napi("decrypt", e))?;
@@ -587,7 +782,42 @@ impl AsherahSession {
     #[napi]
     pub fn decrypt_string(&self, data_row_record: String) -> Result<String> {
         let buf = self.decrypt(data_row_record)?;
-        String::from_utf8(buf.to_vec()).map_err(|e| Error::from_reason(format!("utf8 error: {e}")))
+        String::from_utf8(buf.to_vec()).map_err(utf8_error)
+    }
+
+    #[napi]
+    pub async fn encrypt_async(&self, data: Buffer) -> Result<String> {
+        check_plaintext_len_napi(data.len())?;
+        let session = self.open_session()?;
+        let drr = session
+            .encrypt_async(&data)
+            .await
+            .map_err(|e| anyhow_to_napi("encrypt", e))?;
+        Ok(drr.to_json_fast())
+    }
+
+    #[napi]
+    pub async fn encrypt_string_async(&self, data: String) -> Result<String> {
+        self.encrypt_async(Buffer::from(data.into_bytes())).await
+    }
+
+    #[napi]
+    pub async fn decrypt_async(&self, data_row_record: String) -> Result<Buffer> {
+        check_ciphertext_len_napi(data_row_record.len())?;
+        let drr: asherah::types::DataRowRecord =
+            serde_json::from_str(&data_row_record).map_err(invalid_drr_json)?;
+        let session = self.open_session()?;
+        let pt = session
+            .decrypt_async(drr)
+            .await
+            .map_err(|e| anyhow_to_napi("decrypt", e))?;
+        Ok(Buffer::from(pt))
+    }
+
+    #[napi]
+    pub async fn decrypt_string_async(&self, data_row_record: String) -> Result<String> {
+        let buf = self.decrypt_async(data_row_record).await?;
+        String::from_utf8(buf.to_vec()).map_err(utf8_error)
     }
 
     #[napi]
@@ -602,6 +832,96 @@ impl AsherahSession {
     }
 }
 
+// ── Streams ─────────────────────────────────────────────────────────
+
+/// Encrypts a stream chunk by chunk (see `asherah::stream`). The JS side
+/// wraps this in a `stream.Transform`.
+#[napi(js_name = "StreamEncryptor")]
+pub struct NodeStreamEncryptor {
+    inner: asherah::stream::StreamEncryptor,
+}
+
+impl std::fmt::Debug for NodeStreamEncryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[napi]
+impl NodeStreamEncryptor {
+    #[napi(constructor)]
+    pub fn new(session: &AsherahSession, chunk_size: Option<u32>) -> Result<Self> {
+        let session = session.open_session()?;
+        let chunk_size = chunk_size.map_or(asherah::stream::DEFAULT_CHUNK_SIZE, |n| n as usize);
+        let inner = asherah::stream::StreamEncryptor::new(&session, chunk_size)
+            .map_err(|e| anyhow_to_napi("stream encrypt", e))?;
+        Ok(Self { inner })
+    }
+
+    /// Encrypt `data`, returning whatever frames it completed.
+    #[napi]
+    pub fn update(&mut self, data: Buffer) -> Result<Buffer> {
+        let mut out = Vec::new();
+        self.inner
+            .update(&data, &mut out)
+            .map_err(|e| anyhow_to_napi("stream encrypt", e))?;
+        Ok(Buffer::from(out))
+    }
+
+    /// Return the last frame. The stream does not decrypt without it.
+    #[napi]
+    pub fn finish(&mut self) -> Result<Buffer> {
+        let mut out = Vec::new();
+        self.inner
+            .finish(&mut out)
+            .map_err(|e| anyhow_to_napi("stream encrypt", e))?;
+        Ok(Buffer::from(out))
+    }
+}
+
+/// Decrypts a stream written by `StreamEncryptor`, in pieces of any size.
+#[napi(js_name = "StreamDecryptor")]
+pub struct NodeStreamDecryptor {
+    session: Arc<Session>,
+    inner: asherah::stream::StreamDecryptor,
+}
+
+impl std::fmt::Debug for NodeStreamDecryptor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+#[napi]
+impl NodeStreamDecryptor {
+    #[napi(constructor)]
+    pub fn new(session: &AsherahSession) -> Result<Self> {
+        Ok(Self {
+            session: session.open_session()?,
+            inner: asherah::stream::StreamDecryptor::new(),
+        })
+    }
+
+    /// Decrypt the next piece of the stream, returning the plaintext of
+    /// every frame it completed.
+    #[napi]
+    pub fn update(&mut self, data: Buffer) -> Result<Buffer> {
+        let mut out = Vec::new();
+        self.inner
+            .update(&self.session, &data, &mut out)
+            .map_err(|e| anyhow_to_napi("stream decrypt", e))?;
+        Ok(Buffer::from(out))
+    }
+
+    /// Fail unless the whole stream, up to its last frame, has been read.
+    #[napi]
+    pub fn finish(&self) -> Result<()> {
+        self.inner
+            .finish()
+            .map_err(|e| anyhow_to_napi("stream decrypt", e))
+    }
+}
+
 #[napi]
 pub fn set_max_stack_alloc_item_size(_n: u32) {}
 
@@ -813,7 +1133,8 @@ static LOGGER_READY: OnceLock<()> = OnceLock::new();
 
 fn ensure_logger_initialized() -> Result<()> {
     if LOGGER_READY.get().is_none() {
-        ensure_core_logger().map_err(|e| Error::from_reason(format!("log init error: {e}")))?;
+        ensure_core_logger()
+            .map_err(|e| coded_error(ErrorCode::Internal, format!("log init error: {e}")))?;
         let _ = LOGGER_READY.set(());
     }
     Ok(())
@@ -0,0 +1,190 @@
+// JavaScript-implemented metastore and KMS for SessionFactory.withBackends.
+// Run with `node test/backends.js` after `npm run build`.
+//
+// What this exercises:
+//   - sync and Promise-returning backend methods round-trip through the
+//     async session API, with the drift guard, SK and IK stored in JS
+//   - `this` inside a backend method is the backend object
+//   - a thrown error / rejected Promise surfaces as an encrypt failure
+//   - sync session calls that need the backend fail instead of deadlocking
+//   - `metastore` may be left out of the config when one is supplied
+'use strict';
+
+const assert = require('assert');
+const path = require('path');
+
+const asherah = require(path.resolve(__dirname, '..', 'npm', 'index.js'));
+
+class MapMetastore {
+  constructor(name) {
+    this.name = name;
+    this.records = new Map();
+  }
+
+  load(id, created) {
+    return this.records.get(`${id}/${created}`) ?? null;
+  }
+
+  loadLatest(id) {
+    let latest = null;
+    for (const [key, record] of this.records) {
+      const [recordId, created] = key.split('/');
+      if (recordId === id && (latest === null || Number(created) > latest.created)) {
+        latest = { created: Number(created), record };
+      }
+    }
+    return latest && latest.record;
+  }
+
+  store(id, created, record) {
+    const key = `${id}/${created}`;
+    if (this.records.has(key)) return false;
+    this.records.set(key, record);
+    return true;
+  }
+}
+
+// Same contract, every method async.
+class AsyncMapMetastore extends MapMetastore {
+  async load(id, created) {
+    const record = super.load(id, created);
+    return record === null ? undefined : Buffer.from(record);
+  }
+
+  async loadLatest(id) {
+    return super.loadLatest(id);
+  }
+
+  async store(id, created, record) {
+    return super.store(id, created, record);
+  }
+}
+
+function xorKms(name) {
+  const xor = (buf) => Buffer.from(buf.map((b) => b ^ 0x5a));
+  return {
+    name,
+    calls: 0,
+    fail: false,
+    async encryptKey(key) {
+      this.calls += 1;
+      if (this.fail) throw new Error('kms unavailable');
+      return xor(key);
+    },
+    decryptKey(blob) {
+      this.calls += 1;
+      return xor(blob);
+    },
+  };
+}
+
+const config = {
+  serviceName: 'backends-svc',
+  productId: 'backends-prod',
+  enableSessionCaching: false,
+};
+
+async function roundTrip(metastore) {
+  const kms = xorKms('test-kms');
+  const factory = await asherah.SessionFactory.withBackends(config, { metastore, kms });
+  try {
+    const session = factory.getSession('partition-1');
+    const drr = await session.encryptStringAsync('hello from js');
+    assert.strictEqual(await session.decryptStringAsync(drr), 'hello from js');
+    session.close();
+  } finally {
+    factory.close();
+  }
+  const ids = [...metastore.records.keys()];
+  assert.ok(ids.some((id) => id.startsWith('_SK_backends-svc_backends-prod/')), ids.join());
+  assert.ok(ids.some((id) => id.startsWith('_IK_partition-1_backends-svc_backends-prod/')), ids.join());
+  const record = JSON.parse(metastore.records.values().next().value);
+  assert.ok(Number.isInteger(record.Created) && typeof record.Key === 'string');
+  // Counted through `this`, so this also checks the methods stay bound.
+  assert.ok(kms.calls >= 1);
+}
+
+async function testSyncMethods() {
+  await roundTrip(new MapMetastore('orders-db'));
+  console.log('asherah-node sync backends OK');
+}
+
+async function testPromiseMethods() {
+  await roundTrip(new AsyncMapMetastore('orders-db'));
+  console.log('asherah-node Promise backends OK');
+}
+
+async function testErrorsSurface() {
+  const kms = xorKms('test-kms');
+  const factory = await asherah.SessionFactory.withBackends(
+    { ...config, metastore: 'memory' },
+    { kms },
+  );
+  try {
+    const session = factory.getSession('partition-err');
+    kms.fail = true;
+    await assert.rejects(session.encryptStringAsync('x'), /encrypt failed/);
+    kms.fail = false;
+    const drr = await session.encryptStringAsync('x');
+    assert.strictEqual(await session.decryptStringAsync(drr), 'x');
+    session.close();
+  } finally {
+    factory.close();
+  }
+  console.log('asherah-node backend errors OK');
+}
+
+async function testSyncSessionCallsDoNotDeadlock() {
+  const factory = await asherah.SessionFactory.withBackends(config, {
+    metastore: new MapMetastore('orders-db'),
+    kms: xorKms('test-kms'),
+  });
+  try {
+    const session = factory.getSession('partition-sync');
+    assert.throws(() => session.encryptString('x'), /encrypt failed/);
+    session.close();
+  } finally {
+    factory.close();
+  }
+  console.log('asherah-node sync session calls fail fast OK');
+}
+
+async function testValidation() {
+  await assert.rejects(
+    async () => asherah.SessionFactory.withBackends(config, { metastore: { load() {} } }),
+    /loadLatest\(\) is missing/,
+  );
+  await assert.rejects(
+    async () =>
+      asherah.SessionFactory.withBackends(
+        { ...config, metastore: 'memory' },
+        { metastore: new MapMetastore('orders-db') },
+      ),
+    /Metastore is 'memory'/,
+  );
+  // A method returning the wrong type is reported, not trusted.
+  const metastore = new MapMetastore('orders-db');
+  metastore.store = () => 'yes';
+  await assert.rejects(
+    asherah.SessionFactory.withBackends(
+      { ...config, kms: 'test-debug-static' },
+      { metastore },
+    ),
+    /factory creation failed/,
+  );
+  console.log('asherah-node backend validation OK');
+}
+
+async function main() {
+  await testSyncMethods();
+  await testPromiseMethods();
+  await testErrorsSurface();
+  await testSyncSessionCallsDoNotDeadlock();
+  await testValidation();
+  console.log('asherah-node backends tests OK');
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
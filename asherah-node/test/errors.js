@@ -0,0 +1,150 @@
+// Typed errors carrying Asherah's stable error codes.
+// Run with `node test/errors.js` after `npm run build`.
+//
+// What this exercises:
+//   - failures from module functions, constructors, factory and session
+//     methods (sync and async) are AsherahError subclasses by category
+//   - each carries the code name, number, category and retryable flag, and
+//     the message no longer has the native `[CODE] ` prefix
+//   - a backend outage is retryable
+'use strict';
+
+const assert = require('assert');
+const path = require('path');
+
+const asherah = require(path.resolve(__dirname, '..', 'npm', 'index.js'));
+
+const config = {
+  serviceName: 'errors-svc',
+  productId: 'errors-prod',
+  metastore: 'memory',
+  kms: 'test-debug-static',
+};
+
+function assertCode(err, ErrorClass, code) {
+  assert.ok(err instanceof ErrorClass, `expected ${ErrorClass.name}, got ${err}`);
+  assert.ok(err instanceof asherah.AsherahError);
+  assert.ok(err instanceof Error);
+  assert.strictEqual(err.code, code);
+  assert.strictEqual(err.name, ErrorClass.name);
+  assert.ok(!err.message.startsWith('['), err.message);
+  return true;
+}
+
+function testSessionErrors() {
+  const factory = new asherah.SessionFactory(config);
+  try {
+    const session = factory.getSession('partition-1');
+    const drr = session.encryptString('secret');
+
+    assert.throws(
+      () => session.decryptString('{not json'),
+      (err) => assertCode(err, asherah.InputError, 'INVALID_DATA_ROW_RECORD'),
+    );
+
+    const other = factory.getSession('partition-2');
+    assert.throws(
+      () => other.decryptString(drr),
+      (err) => assertCode(err, asherah.InputError, 'PARTITION_MISMATCH'),
+    );
+    other.close();
+
+    const tampered = JSON.parse(drr);
+    tampered.Data = 'A'.repeat(tampered.Data.length);
+    assert.throws(
+      () => session.decryptString(JSON.stringify(tampered)),
+      (err) => {
+        assertCode(err, asherah.DecryptionError, 'DECRYPTION_FAILED');
+        assert.strictEqual(err.errorCode, 700);
+        assert.strictEqual(err.category, 'CRYPTO');
+        assert.strictEqual(err.retryable, false);
+        return true;
+      },
+    );
+
+    session.close();
+    assert.throws(
+      () => session.encryptString('x'),
+      (err) => assertCode(err, asherah.StateError, 'CLOSED'),
+    );
+  } finally {
+    factory.close();
+  }
+  console.log('asherah-node session errors OK');
+}
+
+async function testModuleErrors() {
+  assert.throws(
+    () => new asherah.SessionFactory({ ...config, metastore: 'nope' }),
+    (err) => assertCode(err, asherah.ConfigError, 'INVALID_CONFIG'),
+  );
+  assert.ok(new asherah.SessionFactory(config) instanceof asherah.SessionFactory);
+
+  asherah.shutdown();
+  assert.throws(
+    () => asherah.encryptString('partition-1', 'x'),
+    (err) => assertCode(err, asherah.StateError, 'NOT_INITIALIZED'),
+  );
+  await assert.rejects(
+    asherah.encryptStringAsync('partition-1', 'x'),
+    (err) => assertCode(err, asherah.StateError, 'NOT_INITIALIZED'),
+  );
+
+  asherah.setup(config);
+  try {
+    assert.throws(
+      () => asherah.setup(config),
+      (err) => assertCode(err, asherah.StateError, 'ALREADY_INITIALIZED'),
+    );
+    await assert.rejects(
+      asherah.decryptAsync('partition-1', '{not json'),
+      (err) => assertCode(err, asherah.InputError, 'INVALID_DATA_ROW_RECORD'),
+    );
+  } finally {
+    asherah.shutdown();
+  }
+  console.log('asherah-node module errors OK');
+}
+
+async function testBackendOutageIsRetryable() {
+  const xor = (buf) => Buffer.from(buf.map((b) => b ^ 0x5a));
+  const kms = {
+    name: 'flaky-kms',
+    down: false,
+    encryptKey(key) {
+      if (this.down) throw new Error('kms unreachable');
+      return xor(key);
+    },
+    decryptKey: xor,
+  };
+  const factory = await asherah.SessionFactory.withBackends(
+    { ...config, kms: undefined, enableSessionCaching: false },
+    { kms },
+  );
+  try {
+    kms.down = true;
+    await assert.rejects(
+      factory.getSession('partition-1').encryptStringAsync('x'),
+      (err) => {
+        assertCode(err, asherah.KmsError, 'KMS_UNAVAILABLE');
+        assert.strictEqual(err.retryable, true);
+        return true;
+      },
+    );
+  } finally {
+    factory.close();
+  }
+  console.log('asherah-node retryable backend errors OK');
+}
+
+async function main() {
+  testSessionErrors();
+  await testModuleErrors();
+  await testBackendOutageIsRetryable();
+  console.log('asherah-node error tests OK');
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
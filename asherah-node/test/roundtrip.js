@@ -519,6 +519,38 @@ function testFactorySessionApi() {
 
     console.log('asherah-node Factory close prevents new sessions OK');
   }
+
+  // --- Cache stats, eviction and flush ---
+  {
+    const factory = new addon.SessionFactory({ ...factoryCfg, enableSessionCaching: true });
+    for (const partition of ['fs-c1', 'fs-c1', 'fs-c2']) {
+      const session = factory.getSession(partition);
+      session.encryptString('cached');
+      session.close();
+    }
+    assert.deepStrictEqual({ ...factory.cacheStats() }, {
+      sessions: 2,
+      sessionCacheHits: 1,
+      sessionCacheMisses: 2,
+      intermediateKeys: 2,
+      systemKeys: 1,
+    });
+
+    assert.strictEqual(factory.evictSession('fs-c2'), true);
+    assert.strictEqual(factory.evictSession('fs-c2'), false);
+    assert.strictEqual(factory.cacheStats().sessions, 1);
+
+    factory.flushCaches();
+    const flushed = factory.cacheStats();
+    assert.strictEqual(flushed.sessions, 0);
+    assert.strictEqual(flushed.intermediateKeys, 0);
+    assert.strictEqual(flushed.systemKeys, 0);
+    assert.strictEqual(flushed.sessionCacheHits, 1);
+
+    factory.close();
+    assert.throws(() => factory.cacheStats(), /closed/);
+    console.log('asherah-node Factory cache stats/evict/flush OK');
+  }
 }
 
 function testNullAndEmptyInputs() {
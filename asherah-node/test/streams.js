@@ -0,0 +1,147 @@
+// Streaming transforms and factories shared across worker threads.
+// Run with `node test/streams.js` after `npm run build`.
+//
+// What this exercises:
+//   - encryptStream/decryptStream round-trip a payload written in uneven
+//     pieces, with a small and the default chunk size
+//   - tampered and truncated streams fail with typed errors
+//   - a factory shared by handle serves sessions in a worker, and stays
+//     open until every instance over it is closed
+'use strict';
+
+const assert = require('assert');
+const path = require('path');
+const { Readable, Writable } = require('stream');
+const { pipeline } = require('stream/promises');
+const { Worker } = require('worker_threads');
+
+const modulePath = path.resolve(__dirname, '..', 'npm', 'index.js');
+const asherah = require(modulePath);
+
+const config = {
+  serviceName: 'streams-svc',
+  productId: 'streams-prod',
+  metastore: 'memory',
+  kms: 'test-debug-static',
+};
+
+async function collect(source, transform, pieceSize) {
+  const pieces = [];
+  for (let i = 0; i < source.length; i += pieceSize) {
+    pieces.push(source.subarray(i, i + pieceSize));
+  }
+  const chunks = [];
+  await pipeline(
+    Readable.from(pieces),
+    transform,
+    new Writable({
+      write(chunk, _encoding, callback) {
+        chunks.push(chunk);
+        callback();
+      },
+    }),
+  );
+  return Buffer.concat(chunks);
+}
+
+async function testRoundTrip() {
+  const factory = new asherah.SessionFactory(config);
+  const session = factory.getSession('partition-1');
+  try {
+    const payload = Buffer.alloc(300000);
+    for (let i = 0; i < payload.length; i++) payload[i] = i % 251;
+
+    for (const options of [{ chunkSize: 4096 }, undefined]) {
+      const stream = await collect(payload, session.encryptStream(options), 7777);
+      assert.ok(stream.length > payload.length);
+      assert.ok(session.encryptStream(options) instanceof asherah.EncryptStream);
+      const plaintext = await collect(stream, session.decryptStream(), 1000);
+      assert.deepStrictEqual(plaintext, payload);
+    }
+
+    const empty = await collect(Buffer.alloc(0), session.encryptStream(), 1);
+    assert.strictEqual((await collect(empty, session.decryptStream(), 3)).length, 0);
+  } finally {
+    session.close();
+    factory.close();
+  }
+  console.log('asherah-node stream round trip OK');
+}
+
+async function testBadStreams() {
+  const factory = new asherah.SessionFactory(config);
+  const session = factory.getSession('partition-1');
+  try {
+    const payload = Buffer.alloc(10000, 9);
+    const stream = await collect(payload, session.encryptStream({ chunkSize: 1000 }), 10000);
+
+    const flipped = Buffer.from(stream);
+    flipped[flipped.length - 40] ^= 1;
+    await assert.rejects(collect(flipped, session.decryptStream(), 512), asherah.DecryptionError);
+
+    const truncated = stream.subarray(0, stream.length - 100);
+    await assert.rejects(collect(truncated, session.decryptStream(), 512), asherah.InputError);
+
+    assert.throws(() => session.encryptStream({ chunkSize: 0 }), asherah.InputError);
+  } finally {
+    session.close();
+    factory.close();
+  }
+  console.log('asherah-node bad streams OK');
+}
+
+function runWorker(handle) {
+  const worker = new Worker(
+    `
+    const { parentPort, workerData } = require('worker_threads');
+    const asherah = require(workerData.modulePath);
+    const factory = asherah.SessionFactory.fromHandle(workerData.handle);
+    const session = factory.getSession('partition-1');
+    const drr = session.encryptString('from a worker');
+    session.close();
+    factory.close();
+    parentPort.postMessage(drr);
+    `,
+    { eval: true, workerData: { modulePath, handle } },
+  );
+  return new Promise((resolve, reject) => {
+    worker.once('message', resolve);
+    worker.once('error', reject);
+  });
+}
+
+async function testSharedFactory() {
+  const factory = new asherah.SessionFactory(config);
+  const handle = factory.share();
+  assert.strictEqual(typeof handle, 'number');
+  assert.strictEqual(factory.share(), handle);
+
+  // The worker closing its instance leaves the factory open here.
+  const drr = await runWorker(handle);
+  const session = factory.getSession('partition-1');
+  assert.strictEqual(session.decryptString(drr), 'from a worker');
+  session.close();
+
+  const other = asherah.SessionFactory.fromHandle(handle);
+  factory.close();
+  assert.strictEqual(other.getSession('partition-1').decryptString(drr), 'from a worker');
+  other.close();
+
+  assert.throws(
+    () => asherah.SessionFactory.fromHandle(handle),
+    (err) => err instanceof asherah.StateError && err.code === 'CLOSED',
+  );
+  console.log('asherah-node shared factory OK');
+}
+
+async function main() {
+  await testRoundTrip();
+  await testBadStreams();
+  await testSharedFactory();
+  console.log('asherah-node stream tests OK');
+}
+
+main().catch((err) => {
+  console.error(err);
+  process.exit(1);
+});
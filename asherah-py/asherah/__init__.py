@@ -42,6 +42,14 @@ from asherah._asherah import (  # noqa: F401
     set_metrics_hook,
     set_log_hook,
     version,
+    AsherahError,
+    InputError,
+    ConfigError,
+    StateError,
+    KeyNotFoundError,
+    MetastoreError,
+    KmsError,
+    DecryptionError,
 )
 
 import asyncio as _asyncio
@@ -105,4 +113,13 @@ __all__ = [
     "encrypt_string_async",
     "decrypt_bytes_async",
     "decrypt_string_async",
+    # Exceptions
+    "AsherahError",
+    "InputError",
+    "ConfigError",
+    "StateError",
+    "KeyNotFoundError",
+    "MetastoreError",
+    "KmsError",
+    "DecryptionError",
 ]
@@ -4,7 +4,8 @@ These stubs document the API surface for IDE tooling (mypy, pyright,
 pylance). The actual implementation lives in the Rust extension module
 ``_asherah.abi3.so``.
 """
-from typing import Any, Awaitable, Callable, Optional, TypedDict, Union
+import asyncio
+from typing import Any, Awaitable, Callable, Optional, Protocol, TypedDict, Union
 
 # ─── Configuration types ────────────────────────────────────────────────────
 
@@ -41,6 +42,86 @@ class _MetricsCacheEvent(TypedDict):
     type: str
     name: str
 
+class _CacheStats(TypedDict):
+    """Returned by :meth:`SessionFactory.cache_stats`.
+
+    ``session_cache_hits`` / ``session_cache_misses`` count
+    :meth:`SessionFactory.get_session` calls since the factory was built.
+    """
+
+    sessions: int
+    session_cache_hits: int
+    session_cache_misses: int
+    intermediate_keys: int
+    system_keys: int
+
+# ─── Application-supplied backends ─────────────────────────────────────────
+
+_Record = Union[str, bytes]
+
+class _Metastore(Protocol):
+    """Metastore passed to :meth:`SessionFactory.with_backends`.
+
+    Records are the JSON Asherah writes to its SQL and DynamoDB metastores;
+    store them verbatim. Any method may be ``async def``; its coroutine is
+    awaited on the factory's event loop. An optional ``name`` attribute
+    identifies the store to the config drift guard (default ``"python"``).
+    """
+
+    def load(self, id: str, created: int) -> Union[Optional[_Record], Awaitable[Optional[_Record]]]:
+        """The record stored under ``(id, created)``, or ``None``."""
+    def load_latest(self, id: str) -> Union[Optional[_Record], Awaitable[Optional[_Record]]]:
+        """The record for ``id`` with the greatest ``created``, or ``None``."""
+    def store(self, id: str, created: int, record: str) -> Union[bool, Awaitable[bool]]:
+        """Insert a record. Return ``False`` without overwriting if
+        ``(id, created)`` already exists."""
+
+class _KeyManagementService(Protocol):
+    """KMS passed to :meth:`SessionFactory.with_backends`. Methods may be
+    ``async def``, as for :class:`_Metastore`."""
+
+    def encrypt_key(self, key: bytes) -> Union[bytes, Awaitable[bytes]]:
+        """Wrap a system key with the master key."""
+    def decrypt_key(self, blob: bytes) -> Union[bytes, Awaitable[bytes]]:
+        """Unwrap what :meth:`encrypt_key` returned."""
+
+# ─── Exceptions ─────────────────────────────────────────────────────────────
+
+class AsherahError(RuntimeError):
+    """Base class for every Asherah failure. Branch on the subclass or on
+    ``code`` rather than on the message, which may change."""
+
+    code: int
+    """Stable numeric code, e.g. ``700``."""
+    code_name: str
+    """Name of the code, e.g. ``"DECRYPTION_FAILED"``."""
+    category: str
+    """``"INPUT"``, ``"CONFIG"``, ``"STATE"``, ``"KEY"``, ``"METASTORE"``,
+    ``"KMS"``, ``"CRYPTO"`` or ``"INTERNAL"``."""
+    retryable: bool
+    """Whether the same call may succeed later (metastore or KMS outage)."""
+
+class InputError(AsherahError):
+    """An argument or DataRowRecord is invalid, or written for another partition."""
+
+class ConfigError(AsherahError):
+    """The configuration is invalid."""
+
+class StateError(AsherahError):
+    """Called before :func:`setup`, or :func:`setup` called twice."""
+
+class KeyNotFoundError(AsherahError):
+    """A key the DataRowRecord refers to is not in the metastore."""
+
+class MetastoreError(AsherahError):
+    """The metastore failed the request. Retryable."""
+
+class KmsError(AsherahError):
+    """The KMS failed the request (retryable) or refused it (not)."""
+
+class DecryptionError(AsherahError):
+    """The data or a wrapped key failed authenticated decryption."""
+
 # ─── Module-level API (legacy / canonical compatibility) ────────────────────
 
 def setup(config: Any) -> None:
@@ -164,12 +245,47 @@ class SessionFactory:
     def from_config(config: Any) -> "SessionFactory":
         """Construct from a config dict using PascalCase keys such as
         ``ServiceName``, ``ProductID``, ``Metastore``, and ``KMS``."""
+    @staticmethod
+    def with_backends(
+        config: Any,
+        metastore: Optional[_Metastore] = ...,
+        kms: Optional[_KeyManagementService] = ...,
+    ) -> "SessionFactory":
+        """Construct with a Python metastore and/or KMS in place of the
+        built-in ones. ``Metastore`` / ``KMS`` may be left out of the
+        config (or set to ``"host"``) for whichever backend is supplied.
+
+        Sync backend methods run on whichever thread needs a key. Coroutine
+        methods are awaited on the event loop running when the factory was
+        built, and on the loop of each later :meth:`Session.encrypt_async` /
+        :meth:`Session.decrypt_async` call. Sync session methods cannot
+        wait for a coroutine from that loop's own thread and raise."""
+    @staticmethod
+    def with_backends_async(
+        config: Any,
+        metastore: Optional[_Metastore] = ...,
+        kms: Optional[_KeyManagementService] = ...,
+    ) -> Awaitable["SessionFactory"]:
+        """:meth:`with_backends` for coroutine backends: the config drift
+        guard's metastore writes are awaited instead of run synchronously.
+        Must be called with an event loop running."""
     def get_session(self, partition_id: str) -> "Session":
         """Get a session for the given partition. Sessions returned for
         the same partition share the underlying intermediate key;
         different partitions are cryptographically isolated.
 
         ``partition_id`` must be non-empty."""
+    def cache_stats(self) -> _CacheStats:
+        """Sizes and hit counts of this factory's caches. The session
+        counts are 0 with session caching disabled."""
+    def evict_session(self, partition_id: str) -> bool:
+        """Drop ``partition_id``'s cached session, so the next
+        :meth:`get_session` for it builds a fresh one. Sessions already
+        handed out keep working. Returns whether a session was cached."""
+    def flush_caches(self) -> None:
+        """Empty the session cache and the system and intermediate key
+        caches, so keys are loaded from the metastore again: after a key
+        revocation, or to release memory."""
     def close(self) -> None:
         """Release native resources. After ``close()``, calls to
         :meth:`get_session` raise."""
@@ -206,6 +322,13 @@ class Session:
     ) -> Awaitable[bytes]:
         """Async decrypt — returns an awaitable. Runs on the Rust tokio
         runtime."""
+    def encrypt_async(self, data: bytes) -> "asyncio.Future[str]":
+        """Encrypt and return an :class:`asyncio.Future` on the running
+        loop. Coroutine backend methods are awaited on that loop; the
+        crypto itself still runs on the binding's own threads."""
+    def decrypt_async(self, data_row_record: str) -> "asyncio.Future[bytes]":
+        """Decrypt, returning an :class:`asyncio.Future` on the running
+        loop, as for :meth:`encrypt_async`."""
     def close(self) -> None:
         """Release native resources."""
     def __enter__(self) -> "Session": ...
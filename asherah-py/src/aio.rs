@@ -0,0 +1,92 @@
+//! asyncio plumbing: futures bound to the caller's running loop, and the
+//! loop Python backends schedule their coroutines on.
+
+use std::sync::Arc;
+use std::thread::ThreadId;
+
+use parking_lot::Mutex;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+use pyo3::types::PyCFunction;
+
+/// A running asyncio loop and the thread it runs on.
+pub(crate) struct BoundLoop {
+    pub(crate) event_loop: Py<PyAny>,
+    pub(crate) thread: ThreadId,
+}
+
+/// The loop a factory's Python backends run coroutines on: the one it was
+/// built on, rebound by every async session call so it follows the caller.
+#[derive(Default)]
+pub(crate) struct EventLoop(Mutex<Option<Arc<BoundLoop>>>);
+
+impl EventLoop {
+    pub(crate) fn bind(&self, event_loop: &Bound<'_, PyAny>) {
+        let mut current = self.0.lock();
+        if current
+            .as_ref()
+            .is_some_and(|bound| bound.event_loop.is(event_loop))
+        {
+            return;
+        }
+        *current = Some(Arc::new(BoundLoop {
+            event_loop: event_loop.clone().unbind(),
+            thread: std::thread::current().id(),
+        }));
+    }
+
+    pub(crate) fn get(&self) -> Option<Arc<BoundLoop>> {
+        self.0.lock().clone()
+    }
+}
+
+/// The loop running on this thread, if any.
+pub(crate) fn running_loop<'py>(py: Python<'py>) -> PyResult<Option<Bound<'py, PyAny>>> {
+    match py.import("asyncio")?.call_method0("get_running_loop") {
+        Ok(event_loop) => Ok(Some(event_loop)),
+        Err(err) if err.is_instance_of::<PyRuntimeError>(py) => Ok(None),
+        Err(err) => Err(err),
+    }
+}
+
+/// The running loop and a new future on it. Raises `RuntimeError` outside
+/// a coroutine, like `asyncio.get_running_loop()`.
+pub(crate) fn loop_future<'py>(
+    py: Python<'py>,
+) -> PyResult<(Bound<'py, PyAny>, Bound<'py, PyAny>)> {
+    let event_loop = py.import("asyncio")?.call_method0("get_running_loop")?;
+    let future = event_loop.call_method0("create_future")?;
+    Ok((event_loop, future))
+}
+
+/// Resolve `future` from any thread. A future the caller already cancelled
+/// is left alone, and so is one whose loop has since closed.
+pub(crate) fn settle_future(
+    event_loop: &Py<PyAny>,
+    future: Py<PyAny>,
+    result: PyResult<Py<PyAny>>,
+) {
+    Python::attach(|py| {
+        let result = Mutex::new(Some(result));
+        let settle = PyCFunction::new_closure(py, None, None, move |args, _kwargs| {
+            let py = args.py();
+            let Some(result) = result.lock().take() else {
+                return PyResult::Ok(());
+            };
+            let future = future.bind(py);
+            if future.call_method0("done")?.is_truthy()? {
+                return Ok(());
+            }
+            match result {
+                Ok(value) => future.call_method1("set_result", (value,))?,
+                Err(err) => future.call_method1("set_exception", (err.into_value(py),))?,
+            };
+            Ok(())
+        });
+        let scheduled = settle
+            .and_then(|settle| event_loop.call_method1(py, "call_soon_threadsafe", (settle,)));
+        if let Err(err) = scheduled {
+            log::warn!("could not resolve an asyncio future: {err}");
+        }
+    });
+}
@@ -0,0 +1,376 @@
+//! Python-implemented metastores and KMSes for `SessionFactory.with_backends`.
+//!
+//! A metastore is any object with `load(id, created)`, `load_latest(id)` and
+//! `store(id, created, record)`; a KMS is any object with `encrypt_key(key)`
+//! and `decrypt_key(blob)`. An optional `name` attribute identifies the
+//! backend to the config drift guard (default `"python"`).
+//!
+//! Methods are called on whichever thread needs a key, with the GIL held. A
+//! method may instead be a coroutine function: its coroutine is scheduled
+//! on the factory's [`EventLoop`] with `asyncio.run_coroutine_threadsafe`.
+//! The async trait methods wait for that future through a done-callback;
+//! the sync ones wait on it directly, which would deadlock on the loop's own
+//! thread, so there they fail and point at the async session methods.
+//!
+//! Key records cross as the JSON Asherah writes to its SQL and DynamoDB
+//! metastores; `load` may return it as `str` or `bytes`, or `None` when
+//! there is no such record. `store` returns `True` when it wrote the record
+//! and `False` when `(id, created)` already exists, which it must never
+//! overwrite.
+
+use std::sync::Arc;
+
+use asherah::builders::HostBackends;
+use asherah::traits::{KeyManagementService, Metastore};
+use asherah::types::EnvelopeKeyRecord;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use pyo3::prelude::*;
+use pyo3::pybacked::PyBackedBytes;
+use pyo3::types::{PyBytes, PyCFunction, PyTuple};
+
+use crate::aio::EventLoop;
+
+const DEFAULT_BACKEND_NAME: &str = "python";
+
+/// Arguments of one call into a backend method.
+enum Call<'args> {
+    Load(&'args str, i64),
+    LoadLatest(&'args str),
+    Store(&'args str, i64, &'args str),
+    Key(&'args [u8]),
+}
+
+impl Call<'_> {
+    fn args<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyTuple>> {
+        match *self {
+            Call::Load(id, created) => (id, created).into_pyobject(py),
+            Call::LoadLatest(id) => (id,).into_pyobject(py),
+            Call::Store(id, created, record) => (id, created, record).into_pyobject(py),
+            Call::Key(bytes) => (PyBytes::new(py, bytes),).into_pyobject(py),
+        }
+    }
+}
+
+/// Turns what a method returned into its Rust result. Runs with the GIL.
+type Convert<T> = fn(&Bound<'_, PyAny>) -> anyhow::Result<T>;
+
+/// What a method call produced: its value, or the `concurrent.futures`
+/// future of its coroutine.
+enum Outcome {
+    Ready(Py<PyAny>),
+    Scheduled(Py<PyAny>),
+}
+
+/// A converted result, or where it will arrive once the coroutine finishes.
+enum Pending<T> {
+    Ready(T),
+    Waiting(tokio::sync::oneshot::Receiver<anyhow::Result<T>>),
+}
+
+struct Backend {
+    obj: Py<PyAny>,
+    /// e.g. `Python metastore 'orders'`, for error messages.
+    what: String,
+    event_loop: Arc<EventLoop>,
+}
+
+impl Backend {
+    fn new(obj: &Bound<'_, PyAny>, kind: &str, event_loop: Arc<EventLoop>) -> PyResult<Self> {
+        let name = backend_name(obj, kind)?;
+        Ok(Self {
+            obj: obj.clone().unbind(),
+            what: format!("Python {kind} '{name}'"),
+            event_loop,
+        })
+    }
+
+    fn start(
+        &self,
+        py: Python<'_>,
+        method: &str,
+        call: &Call<'_>,
+        blocking: bool,
+    ) -> anyhow::Result<Outcome> {
+        let what = &self.what;
+        let ret = self
+            .obj
+            .bind(py)
+            .call_method1(method, call.args(py)?)
+            .map_err(|e| anyhow::anyhow!("{what}.{method} failed: {e}"))?;
+        let asyncio = py.import("asyncio")?;
+        if !asyncio.call_method1("iscoroutine", (&ret,))?.is_truthy()? {
+            return Ok(Outcome::Ready(ret.unbind()));
+        }
+        let refuse = |reason: &str| -> anyhow::Result<Outcome> {
+            drop(ret.call_method0("close"));
+            anyhow::bail!("{what}.{method} returned a coroutine, but {reason}")
+        };
+        let Some(bound) = self.event_loop.get() else {
+            return refuse(
+                "no event loop is bound; build the factory with with_backends_async or \
+                 use the session's async methods",
+            );
+        };
+        if blocking && bound.thread == std::thread::current().id() {
+            return refuse(
+                "it cannot be awaited synchronously on its event loop's thread; \
+                 use the session's async methods",
+            );
+        }
+        let future = asyncio
+            .call_method1(
+                "run_coroutine_threadsafe",
+                (&ret, bound.event_loop.bind(py)),
+            )
+            .map_err(|e| anyhow::anyhow!("{what}.{method} could not be scheduled: {e}"))?;
+        Ok(Outcome::Scheduled(future.unbind()))
+    }
+
+    fn call<T>(&self, method: &str, call: Call<'_>, convert: Convert<T>) -> anyhow::Result<T> {
+        let what = &self.what;
+        Python::attach(|py| {
+            let value = match self.start(py, method, &call, true)? {
+                Outcome::Ready(value) => value.into_bound(py),
+                // `result()` releases the GIL while it waits.
+                Outcome::Scheduled(future) => future
+                    .bind(py)
+                    .call_method0("result")
+                    .map_err(|e| anyhow::anyhow!("{what}.{method} failed: {e}"))?,
+            };
+            convert(&value).map_err(|e| anyhow::anyhow!("{what}.{method} returned {e}"))
+        })
+    }
+
+    async fn call_async<T: Send + 'static>(
+        &self,
+        method: &'static str,
+        call: Call<'_>,
+        convert: Convert<T>,
+    ) -> anyhow::Result<T> {
+        let what = self.what.clone();
+        let rx = Python::attach(|py| -> anyhow::Result<_> {
+            let future = match self.start(py, method, &call, false)? {
+                Outcome::Ready(value) => {
+                    let value = convert(value.bind(py))
+                        .map_err(|e| anyhow::anyhow!("{what}.{method} returned {e}"))?;
+                    return Ok(Pending::Ready(value));
+                }
+                Outcome::Scheduled(future) => future,
+            };
+            let (tx, rx) = tokio::sync::oneshot::channel();
+            let tx = Mutex::new(Some(tx));
+            let done = PyCFunction::new_closure(py, None, None, move |args, _kwargs| {
+                let future = args.get_item(0)?;
+                let result = future
+                    .call_method0("result")
+                    .map_err(|e| anyhow::anyhow!("{what}.{method} failed: {e}"))
+                    .and_then(|value| {
+                        convert(&value).map_err(|e| anyhow::anyhow!("{what}.{method} returned {e}"))
+                    });
+                if let Some(tx) = tx.lock().take() {
+                    drop(tx.send(result));
+                }
+                PyResult::Ok(())
+            })?;
+            future.call_method1(py, "add_done_callback", (done,))?;
+            Ok(Pending::Waiting(rx))
+        })?;
+        match rx {
+            Pending::Ready(value) => Ok(value),
+            Pending::Waiting(rx) => rx
+                .await
+                .map_err(|_| anyhow::anyhow!("{}.{method} was abandoned", self.what))?,
+        }
+    }
+}
+
+fn backend_name(obj: &Bound<'_, PyAny>, kind: &str) -> PyResult<String> {
+    let name = match obj.getattr_opt("name")? {
+        Some(name) if !name.is_none() => name,
+        _ => return Ok(DEFAULT_BACKEND_NAME.to_string()),
+    };
+    let name: String = name.extract().map_err(|_| {
+        pyo3::exceptions::PyTypeError::new_err(format!("{kind} name must be a str"))
+    })?;
+    if name.trim().is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err(format!(
+            "{kind} name must not be empty"
+        )));
+    }
+    Ok(name)
+}
+
+fn record(value: &Bound<'_, PyAny>) -> anyhow::Result<Option<String>> {
+    if value.is_none() {
+        return Ok(None);
+    }
+    if let Ok(json) = value.extract::<String>() {
+        return Ok(Some(json));
+    }
+    let bytes = value
+        .extract::<PyBackedBytes>()
+        .map_err(|_| anyhow::anyhow!("something other than str, bytes or None"))?;
+    String::from_utf8(bytes.to_vec())
+        .map(Some)
+        .map_err(|_| anyhow::anyhow!("a non-UTF-8 record"))
+}
+
+fn stored(value: &Bound<'_, PyAny>) -> anyhow::Result<bool> {
+    value
+        .extract::<bool>()
+        .map_err(|_| anyhow::anyhow!("something other than a bool"))
+}
+
+fn key_bytes(value: &Bound<'_, PyAny>) -> anyhow::Result<Vec<u8>> {
+    value
+        .extract::<PyBackedBytes>()
+        .map(|bytes| bytes.to_vec())
+        .map_err(|_| anyhow::anyhow!("something other than bytes"))
+}
+
+struct PyMetastore(Backend);
+
+impl PyMetastore {
+    fn parse(&self, id: &str, json: Option<String>) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let Some(json) = json else {
+            return Ok(None);
+        };
+        let mut ekr = EnvelopeKeyRecord::from_json_fast(&json)
+            .map_err(|e| anyhow::anyhow!("{} returned an invalid record: {e}", self.0.what))?;
+        ekr.id = id.to_string();
+        Ok(Some(ekr))
+    }
+}
+
+#[async_trait]
+impl Metastore for PyMetastore {
+    fn load(&self, id: &str, created: i64) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let json = self.0.call("load", Call::Load(id, created), record)?;
+        self.parse(id, json)
+    }
+
+    fn load_latest(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let json = self.0.call("load_latest", Call::LoadLatest(id), record)?;
+        self.parse(id, json)
+    }
+
+    fn store(&self, id: &str, created: i64, ekr: &EnvelopeKeyRecord) -> anyhow::Result<bool> {
+        let json = serde_json::to_string(ekr)?;
+        self.0
+            .call("store", Call::Store(id, created, &json), stored)
+    }
+
+    async fn load_async(
+        &self,
+        id: &str,
+        created: i64,
+    ) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let json = self
+            .0
+            .call_async("load", Call::Load(id, created), record)
+            .await?;
+        self.parse(id, json)
+    }
+
+    async fn load_latest_async(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        let json = self
+            .0
+            .call_async("load_latest", Call::LoadLatest(id), record)
+            .await?;
+        self.parse(id, json)
+    }
+
+    async fn store_async(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> anyhow::Result<bool> {
+        let json = serde_json::to_string(ekr)?;
+        self.0
+            .call_async("store", Call::Store(id, created, &json), stored)
+            .await
+    }
+
+    async fn upsert_config_drift_guard_async(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> anyhow::Result<()> {
+        if self.store_async(id, created, ekr).await? {
+            Ok(())
+        } else {
+            anyhow::bail!("metastore does not support replacing config drift guard records")
+        }
+    }
+}
+
+struct PyKms(Backend);
+
+#[async_trait]
+impl KeyManagementService for PyKms {
+    fn encrypt_key(&self, _ctx: &(), key: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.0.call("encrypt_key", Call::Key(key), key_bytes)
+    }
+
+    fn decrypt_key(&self, _ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.0.call("decrypt_key", Call::Key(blob), key_bytes)
+    }
+
+    async fn encrypt_key_async(&self, _ctx: &(), key: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.0
+            .call_async("encrypt_key", Call::Key(key), key_bytes)
+            .await
+    }
+
+    async fn decrypt_key_async(&self, _ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.0
+            .call_async("decrypt_key", Call::Key(blob), key_bytes)
+            .await
+    }
+}
+
+/// Wrap the given objects as [`HostBackends`] whose coroutines run on
+/// `event_loop`.
+pub(crate) fn host_backends(
+    metastore: Option<&Bound<'_, PyAny>>,
+    kms: Option<&Bound<'_, PyAny>>,
+    event_loop: &Arc<EventLoop>,
+) -> PyResult<HostBackends> {
+    let mut host = HostBackends::default();
+    if let Some(obj) = metastore.filter(|obj| !obj.is_none()) {
+        require_methods(obj, "metastore", &["load", "load_latest", "store"])?;
+        host = host.with_metastore(
+            backend_name(obj, "metastore")?,
+            Arc::new(PyMetastore(Backend::new(
+                obj,
+                "metastore",
+                Arc::clone(event_loop),
+            )?)),
+        );
+    }
+    if let Some(obj) = kms.filter(|obj| !obj.is_none()) {
+        require_methods(obj, "KMS", &["encrypt_key", "decrypt_key"])?;
+        host = host.with_kms(
+            backend_name(obj, "KMS")?,
+            Arc::new(PyKms(Backend::new(obj, "KMS", Arc::clone(event_loop))?)),
+        );
+    }
+    Ok(host)
+}
+
+fn require_methods(obj: &Bound<'_, PyAny>, kind: &str, methods: &[&str]) -> PyResult<()> {
+    for method in methods {
+        let callable = obj
+            .getattr_opt(*method)?
+            .is_some_and(|attr| attr.is_callable());
+        if !callable {
+            return Err(pyo3::exceptions::PyTypeError::new_err(format!(
+                "{kind} must implement {method}()"
+            )));
+        }
+    }
+    Ok(())
+}
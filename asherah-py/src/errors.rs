@@ -0,0 +1,101 @@
+//! Exception classes for Asherah's stable error codes.
+//!
+//! Every failure from the core raises a subclass of `AsherahError` picked by
+//! the code's category, carrying the code itself as attributes. The base
+//! class derives from `RuntimeError`, which is what the binding raised
+//! before, so existing `except RuntimeError` handlers keep working.
+
+use asherah::error::{ErrorCategory, ErrorCode};
+use pyo3::create_exception;
+use pyo3::exceptions::PyRuntimeError;
+use pyo3::prelude::*;
+
+create_exception!(
+    asherah,
+    AsherahError,
+    PyRuntimeError,
+    "Base class for Asherah errors. `code` is the stable numeric code, \
+     `code_name` its name (e.g. \"KMS_ACCESS_DENIED\"), `category` its \
+     category, and `retryable` whether the call may succeed later."
+);
+create_exception!(
+    asherah,
+    InputError,
+    AsherahError,
+    "An argument or DataRowRecord is invalid."
+);
+create_exception!(
+    asherah,
+    ConfigError,
+    AsherahError,
+    "The configuration is invalid."
+);
+create_exception!(
+    asherah,
+    StateError,
+    AsherahError,
+    "Called before setup, after shutdown, or twice."
+);
+create_exception!(
+    asherah,
+    KeyNotFoundError,
+    AsherahError,
+    "A key the record refers to is not in the metastore."
+);
+create_exception!(
+    asherah,
+    MetastoreError,
+    AsherahError,
+    "The metastore failed the request."
+);
+create_exception!(
+    asherah,
+    KmsError,
+    AsherahError,
+    "The KMS failed or refused the request."
+);
+create_exception!(
+    asherah,
+    DecryptionError,
+    AsherahError,
+    "The data or a wrapped key failed authenticated decryption."
+);
+
+/// An `AsherahError` subclass for `code`, with `message`.
+pub(crate) fn new_err(code: ErrorCode, message: impl Into<String>) -> PyErr {
+    let message = message.into();
+    let err = match code.category() {
+        ErrorCategory::Input => InputError::new_err(message),
+        ErrorCategory::Config => ConfigError::new_err(message),
+        ErrorCategory::State => StateError::new_err(message),
+        ErrorCategory::Key => KeyNotFoundError::new_err(message),
+        ErrorCategory::Metastore => MetastoreError::new_err(message),
+        ErrorCategory::Kms => KmsError::new_err(message),
+        ErrorCategory::Crypto => DecryptionError::new_err(message),
+        _ => AsherahError::new_err(message),
+    };
+    Python::attach(|py| match describe(py, &err, code) {
+        Ok(()) => err,
+        Err(failed) => failed,
+    })
+}
+
+fn describe(py: Python<'_>, err: &PyErr, code: ErrorCode) -> PyResult<()> {
+    let value = err.value(py);
+    value.setattr("code", code.as_i32())?;
+    value.setattr("code_name", code.name())?;
+    value.setattr("category", code.category().name())?;
+    value.setattr("retryable", code.is_retryable())
+}
+
+pub(crate) fn register(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = m.py();
+    m.add("AsherahError", py.get_type::<AsherahError>())?;
+    m.add("InputError", py.get_type::<InputError>())?;
+    m.add("ConfigError", py.get_type::<ConfigError>())?;
+    m.add("StateError", py.get_type::<StateError>())?;
+    m.add("KeyNotFoundError", py.get_type::<KeyNotFoundError>())?;
+    m.add("MetastoreError", py.get_type::<MetastoreError>())?;
+    m.add("KmsError", py.get_type::<KmsError>())?;
+    m.add("DecryptionError", py.get_type::<DecryptionError>())
+}
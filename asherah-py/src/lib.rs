@@ -2,6 +2,7 @@
 #![allow(unused_qualifications)]
 
 use asherah as ael;
+use asherah::error::ErrorCode;
 use asherah::logging::{
     ensure_logger, set_sink as set_log_sink, AsyncLogConfig, AsyncLogSink, LogSink,
 };
@@ -21,6 +22,10 @@ use std::num::NonZeroUsize;
 use std::sync::{Arc, OnceLock};
 use zeroize::Zeroizing;
 
+mod aio;
+mod backends;
+mod errors;
+
 type Factory = ael::session::PublicFactory<
     ael::aead::AES256GCM,
     ael::builders::DynKms,
@@ -41,19 +46,27 @@ fn sanitized_anyhow_message(op: &str, err: &anyhow::Error) -> String {
 
 fn anyhow_to_py(err: anyhow::Error) -> PyErr {
     log::warn!("python binding operation failed: {err:#}");
-    PyRuntimeError::new_err(sanitized_anyhow_message("asherah operation", &err))
+    errors::new_err(
+        ErrorCode::of(&err),
+        sanitized_anyhow_message("asherah operation", &err),
+    )
 }
 
 fn json_parse_err(err: impl std::fmt::Display) -> PyErr {
-    PyRuntimeError::new_err(format!("invalid DataRowRecord JSON: {err}"))
+    errors::new_err(
+        ErrorCode::InvalidDataRowRecord,
+        format!("invalid DataRowRecord JSON: {err}"),
+    )
 }
 
 fn check_plaintext_len_py(len: usize) -> PyResult<()> {
-    ael::limits::check_plaintext_len(len).map_err(|err| PyRuntimeError::new_err(err.to_string()))
+    ael::limits::check_plaintext_len(len)
+        .map_err(|err| errors::new_err(ErrorCode::of(&err), err.to_string()))
 }
 
 fn check_ciphertext_len_py(len: usize) -> PyResult<()> {
-    ael::limits::check_ciphertext_len(len).map_err(|err| PyRuntimeError::new_err(err.to_string()))
+    ael::limits::check_ciphertext_len(len)
+        .map_err(|err| errors::new_err(ErrorCode::of(&err), err.to_string()))
 }
 
 fn config_options_from_py(config_obj: &Bound<'_, PyAny>) -> PyResult<config::ConfigOptions> {
@@ -83,7 +96,8 @@ fn setup(config_obj: &Bound<'_, PyAny>) -> PyResult<()> {
     let factory = factory.with_metrics(true);
     let mut guard = MANAGER.lock();
     if guard.is_some() {
-        return Err(PyRuntimeError::new_err(
+        return Err(errors::new_err(
+            ErrorCode::AlreadyInitialized,
             "Asherah already configured; call shutdown() first",
         ));
     }
@@ -178,8 +192,8 @@ fn decrypt_bytes<'py>(
 ) -> PyResult<Bound<'py, PyBytes>> {
     check_ciphertext_len_py(data_row_record.len())?;
     let session = with_manager(|mgr| Ok(mgr.get_or_create_session(partition_id)))?;
-    let drr: ael::types::DataRowRecord = serde_json::from_str(data_row_record)
-        .map_err(|e| PyRuntimeError::new_err(format!("invalid DataRowRecord JSON: {e}")))?;
+    let drr: ael::types::DataRowRecord =
+        serde_json::from_str(data_row_record).map_err(json_parse_err)?;
     // `PyBytes::new` copies into a Python-managed buffer that we can no
     // longer wipe — but we *can* wipe our own intermediate Vec the
     // moment the copy completes. Wrap in `Zeroizing` so any early
@@ -192,8 +206,8 @@ fn decrypt_bytes<'py>(
 fn decrypt_string(partition_id: &str, data_row_record: &str) -> PyResult<String> {
     check_ciphertext_len_py(data_row_record.len())?;
     let session = with_manager(|mgr| Ok(mgr.get_or_create_session(partition_id)))?;
-    let drr: ael::types::DataRowRecord = serde_json::from_str(data_row_record)
-        .map_err(|e| PyRuntimeError::new_err(format!("invalid DataRowRecord JSON: {e}")))?;
+    let drr: ael::types::DataRowRecord =
+        serde_json::from_str(data_row_record).map_err(json_parse_err)?;
     // Hold the GIL across the decrypt — see `encrypt_bytes` for why the sync
     // hot path must not release it.
     let bytes: Zeroizing<Vec<u8>> = Zeroizing::new(session.decrypt(drr).map_err(anyhow_to_py)?);
@@ -313,9 +327,12 @@ where
     F: FnOnce(&FactoryManager) -> PyResult<R>,
 {
     let guard = MANAGER.lock();
-    let manager = guard
-        .as_ref()
-        .ok_or_else(|| PyRuntimeError::new_err("Asherah not configured; call setup()"))?;
+    let manager = guard.as_ref().ok_or_else(|| {
+        errors::new_err(
+            ErrorCode::NotInitialized,
+            "Asherah not configured; call setup()",
+        )
+    })?;
     f(manager)
 }
 
@@ -343,6 +360,8 @@ fn async_rt() -> PyResult<&'static tokio::runtime::Runtime> {
 #[allow(missing_debug_implementations)]
 pub struct PySessionFactory {
     inner: Factory,
+    /// Where coroutines returned by Python backends are awaited.
+    event_loop: Arc<aio::EventLoop>,
 }
 
 #[pymethods]
@@ -356,7 +375,10 @@ impl PySessionFactory {
                 .map(|factory| factory.with_metrics(true))
                 .map_err(anyhow_to_py)?,
         };
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            event_loop: Arc::default(),
+        })
     }
 
     #[staticmethod]
@@ -367,16 +389,98 @@ impl PySessionFactory {
     #[staticmethod]
     pub fn from_config(config_obj: &Bound<'_, PyAny>) -> PyResult<Self> {
         let inner = factory_from_py_config(config_obj)?;
-        Ok(Self { inner })
+        Ok(Self {
+            inner,
+            event_loop: Arc::default(),
+        })
+    }
+
+    /// Build a factory whose metastore and/or KMS are Python objects. Any
+    /// running asyncio loop is remembered so coroutine methods can be
+    /// awaited on it.
+    #[staticmethod]
+    #[pyo3(signature = (config_obj, metastore=None, kms=None))]
+    pub fn with_backends(
+        py: Python<'_>,
+        config_obj: &Bound<'_, PyAny>,
+        metastore: Option<&Bound<'_, PyAny>>,
+        kms: Option<&Bound<'_, PyAny>>,
+    ) -> PyResult<Self> {
+        let cfg = config_options_from_py(config_obj)?;
+        let event_loop = Arc::new(aio::EventLoop::default());
+        if let Some(running) = aio::running_loop(py)? {
+            event_loop.bind(&running);
+        }
+        let host = backends::host_backends(metastore, kms, &event_loop)?;
+        let (factory, _applied) =
+            config::factory_from_config_with_backends(&cfg, &host).map_err(anyhow_to_py)?;
+        Ok(Self {
+            inner: factory.with_metrics(true),
+            event_loop,
+        })
+    }
+
+    /// Like `with_backends`, but returns an awaitable and runs the config
+    /// drift guard through the backends' coroutine methods.
+    #[staticmethod]
+    #[pyo3(signature = (config_obj, metastore=None, kms=None))]
+    pub fn with_backends_async<'py>(
+        py: Python<'py>,
+        config_obj: &Bound<'py, PyAny>,
+        metastore: Option<&Bound<'py, PyAny>>,
+        kms: Option<&Bound<'py, PyAny>>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let cfg = config_options_from_py(config_obj)?;
+        let (running, future) = aio::loop_future(py)?;
+        let event_loop = Arc::new(aio::EventLoop::default());
+        event_loop.bind(&running);
+        let host = backends::host_backends(metastore, kms, &event_loop)?;
+        let (loop_ref, future_ref) = (running.unbind(), future.clone().unbind());
+        async_rt()?.spawn(async move {
+            let built = config::factory_from_config_with_backends_async(&cfg, &host).await;
+            let result = Python::attach(|py| {
+                let (factory, _applied) = built.map_err(anyhow_to_py)?;
+                let factory = Self {
+                    inner: factory.with_metrics(true),
+                    event_loop,
+                };
+                Ok(Py::new(py, factory)?.into_any())
+            });
+            aio::settle_future(&loop_ref, future_ref, result);
+        });
+        Ok(future)
     }
 
     pub fn get_session(&self, partition_id: &str) -> PyResult<PySession> {
         let session = self.inner.get_session(partition_id);
         Ok(PySession {
             inner: Arc::new(session),
+            event_loop: Arc::clone(&self.event_loop),
         })
     }
 
+    /// Sizes and hit counts of this factory's caches, as a dict.
+    pub fn cache_stats<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let stats = self.inner.cache_stats();
+        let dict = PyDict::new(py);
+        dict.set_item("sessions", stats.sessions)?;
+        dict.set_item("session_cache_hits", stats.session_cache_hits)?;
+        dict.set_item("session_cache_misses", stats.session_cache_misses)?;
+        dict.set_item("intermediate_keys", stats.intermediate_keys)?;
+        dict.set_item("system_keys", stats.system_keys)?;
+        Ok(dict)
+    }
+
+    /// Drop `partition_id`'s cached session; returns whether one was cached.
+    pub fn evict_session(&self, partition_id: &str) -> bool {
+        self.inner.evict_session(partition_id)
+    }
+
+    /// Empty the session cache and the system and intermediate key caches.
+    pub fn flush_caches(&self) {
+        self.inner.flush_caches();
+    }
+
     pub fn close(&self) -> PyResult<()> {
         self.inner.close().map_err(anyhow_to_py)?;
         Ok(())
@@ -400,6 +504,7 @@ impl PySessionFactory {
 #[allow(missing_debug_implementations)]
 pub struct PySession {
     inner: Arc<SessionHandle>,
+    event_loop: Arc<aio::EventLoop>,
 }
 
 #[pymethods]
@@ -475,6 +580,54 @@ impl PySession {
         Ok(pt.to_vec())
     }
 
+    /// Encrypt on the binding's runtime and return an `asyncio.Future`
+    /// bound to the running loop. Coroutine backend methods are awaited on
+    /// that same loop.
+    pub fn encrypt_async<'py>(&self, py: Python<'py>, data: &[u8]) -> PyResult<Bound<'py, PyAny>> {
+        check_plaintext_len_py(data.len())?;
+        let (running, future) = aio::loop_future(py)?;
+        self.event_loop.bind(&running);
+        let session = Arc::clone(&self.inner);
+        let input = Zeroizing::new(data.to_vec());
+        let (loop_ref, future_ref) = (running.unbind(), future.clone().unbind());
+        async_rt()?.spawn(async move {
+            let result = session.encrypt_async(&input).await.and_then(|drr| {
+                serde_json::to_string(&drr).map_err(|e| anyhow::anyhow!("json error: {e}"))
+            });
+            let result = Python::attach(|py| {
+                let drr = result.map_err(anyhow_to_py)?;
+                Ok(drr.into_pyobject(py)?.into_any().unbind())
+            });
+            aio::settle_future(&loop_ref, future_ref, result);
+        });
+        Ok(future)
+    }
+
+    /// Decrypt on the binding's runtime and return an `asyncio.Future`
+    /// bound to the running loop, resolving to `bytes`.
+    pub fn decrypt_async<'py>(
+        &self,
+        py: Python<'py>,
+        data_row_record: &str,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        check_ciphertext_len_py(data_row_record.len())?;
+        let drr: ael::types::DataRowRecord =
+            serde_json::from_str(data_row_record).map_err(json_parse_err)?;
+        let (running, future) = aio::loop_future(py)?;
+        self.event_loop.bind(&running);
+        let session = Arc::clone(&self.inner);
+        let (loop_ref, future_ref) = (running.unbind(), future.clone().unbind());
+        async_rt()?.spawn(async move {
+            let result = session.decrypt_async(drr).await.map(Zeroizing::new);
+            let result = Python::attach(|py| {
+                let pt = result.map_err(anyhow_to_py)?;
+                Ok(PyBytes::new(py, &pt).into_any().unbind())
+            });
+            aio::settle_future(&loop_ref, future_ref, result);
+        });
+        Ok(future)
+    }
+
     pub fn close(&self) -> PyResult<()> {
         self.inner.close().map_err(anyhow_to_py)?;
         Ok(())
@@ -707,6 +860,7 @@ fn _asherah(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(set_metrics_hook, m)?)?;
     m.add_function(wrap_pyfunction!(set_log_hook, m)?)?;
     m.add_function(wrap_pyfunction!(version, m)?)?;
+    errors::register(m)?;
     Ok(())
 }
 
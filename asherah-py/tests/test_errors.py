@@ -0,0 +1,90 @@
+"""Typed exceptions carrying Asherah's stable error codes."""
+
+import json
+
+import pytest
+
+CONFIG = {
+    "ServiceName": "errors-svc",
+    "ProductID": "errors-prod",
+    "Metastore": "memory",
+    "KMS": "test-debug-static",
+}
+
+
+def test_input_and_crypto_errors():
+    asherah = pytest.importorskip("asherah")
+
+    with asherah.SessionFactory(CONFIG) as factory:
+        session = factory.get_session("partition-1")
+        drr = session.encrypt_bytes(b"secret")
+
+        with pytest.raises(asherah.InputError) as err:
+            session.decrypt_bytes("{not json")
+        assert err.value.code == 102
+        assert err.value.code_name == "INVALID_DATA_ROW_RECORD"
+        assert err.value.category == "INPUT"
+        assert err.value.retryable is False
+
+        with pytest.raises(asherah.InputError) as err:
+            factory.get_session("partition-2").decrypt_bytes(drr)
+        assert err.value.code_name == "PARTITION_MISMATCH"
+
+        tampered = json.loads(drr)
+        tampered["Data"] = "A" * len(tampered["Data"])
+        with pytest.raises(asherah.DecryptionError) as err:
+            session.decrypt_bytes(json.dumps(tampered))
+        assert err.value.code == 700
+        assert err.value.category == "CRYPTO"
+        # Still a RuntimeError, as before typed exceptions existed.
+        assert isinstance(err.value, RuntimeError)
+
+
+def test_config_and_state_errors():
+    asherah = pytest.importorskip("asherah")
+
+    with pytest.raises(asherah.ConfigError) as err:
+        asherah.SessionFactory(dict(CONFIG, Metastore="nope"))
+    assert err.value.code_name == "INVALID_CONFIG"
+
+    asherah.shutdown()
+    with pytest.raises(asherah.StateError) as err:
+        asherah.encrypt_bytes("partition-1", b"x")
+    assert err.value.code_name == "NOT_INITIALIZED"
+
+    asherah.setup(CONFIG)
+    try:
+        with pytest.raises(asherah.StateError) as err:
+            asherah.setup(CONFIG)
+        assert err.value.code_name == "ALREADY_INITIALIZED"
+    finally:
+        asherah.shutdown()
+
+
+def test_backend_outages_are_retryable():
+    asherah = pytest.importorskip("asherah")
+
+    class FlakyKms:
+        name = "flaky-kms"
+        down = False
+
+        def encrypt_key(self, key):
+            if self.down:
+                raise ConnectionError("kms unreachable")
+            return bytes(b ^ 0x5A for b in key)
+
+        def decrypt_key(self, blob):
+            return bytes(b ^ 0x5A for b in blob)
+
+    kms = FlakyKms()
+    factory = asherah.SessionFactory.with_backends(
+        dict(CONFIG, KMS=None, EnableSessionCaching=False), kms=kms
+    )
+    try:
+        kms.down = True
+        with pytest.raises(asherah.KmsError) as err:
+            factory.get_session("partition-1").encrypt_bytes(b"x")
+        assert err.value.code_name == "KMS_UNAVAILABLE"
+        assert err.value.retryable is True
+    finally:
+        factory.close()
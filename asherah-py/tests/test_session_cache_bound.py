@@ -90,3 +90,32 @@ def test_session_caching_disabled_round_trips():
             assert asherah.decrypt_bytes(f"nocache-{i}", ct) == b"x"
     finally:
         asherah.shutdown()
+
+
+def test_factory_cache_stats_evict_and_flush():
+    pytest.importorskip("asherah")
+    import asherah
+
+    _configure_env()
+    with asherah.SessionFactory(_base_config()) as factory:
+        for partition in ("stats-a", "stats-a", "stats-b"):
+            with factory.get_session(partition) as session:
+                session.encrypt_bytes(b"x")
+        assert factory.cache_stats() == {
+            "sessions": 2,
+            "session_cache_hits": 1,
+            "session_cache_misses": 2,
+            "intermediate_keys": 2,
+            "system_keys": 1,
+        }
+
+        assert factory.evict_session("stats-b") is True
+        assert factory.evict_session("stats-b") is False
+        assert factory.cache_stats()["sessions"] == 1
+
+        factory.flush_caches()
+        stats = factory.cache_stats()
+        assert stats["sessions"] == 0
+        assert stats["intermediate_keys"] == 0
+        assert stats["system_keys"] == 0
+        assert stats["session_cache_hits"] == 1
@@ -1,4 +1,8 @@
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    tonic_prost_build::compile_protos("proto/appencryption.proto")?;
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR")?);
+    // The encoded descriptor set backs the gRPC server reflection service.
+    tonic_prost_build::configure()
+        .file_descriptor_set_path(out_dir.join("appencryption_descriptor.bin"))
+        .compile_protos(&["proto/appencryption.proto"], &["proto"])?;
     Ok(())
 }
@@ -0,0 +1,387 @@
+//! Structured audit log behind `--audit-log`: one JSON line per encrypt,
+//! decrypt and `GetSession`, naming the caller, the partition, the
+//! intermediate key used, the plaintext size, the latency and the gRPC
+//! result code. Plaintext and ciphertext are never written.
+//!
+//! ```json
+//! {"ts":"2025-10-19T09:14:03.512Z","rpc":"Encrypt","operation":"encrypt",
+//!  "caller":{"uid":1001,"gid":1001},"partition_sha256":"9f86d0...",
+//!  "key":{"id_sha256":"60303a...","created":1760865243},"bytes":14,
+//!  "latency_us":812,"code":"OK"}
+//! ```
+//!
+//! Partition IDs are usually tenant identifiers, so by default records
+//! carry their SHA-256 instead (and the same for intermediate key IDs,
+//! which embed the partition ID). Lines are written by a dedicated thread so
+//! request handlers never wait on the file.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::net::SocketAddr;
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+use serde_json::{json, Map, Value};
+
+use crate::authz::{Caller, Operation};
+use crate::proto;
+
+/// How records name partitions and intermediate keys.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PartitionIds {
+    /// Hex SHA-256 of the ID, in `partition_sha256` and `key.id_sha256`.
+    #[default]
+    Hashed,
+    /// The ID itself, in `partition` and `key.id`.
+    Raw,
+}
+
+/// Where the audit log goes.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Target {
+    Stdout,
+    /// Appended to, created with mode 0600 if missing. Reopened by
+    /// [`AuditLog::reopen`] so external rotation can move it aside.
+    File(PathBuf),
+}
+
+impl Target {
+    /// `-` for stdout, anything else a file path.
+    pub fn parse(raw: &str) -> Self {
+        match raw {
+            "-" => Self::Stdout,
+            path => Self::File(PathBuf::from(path)),
+        }
+    }
+}
+
+/// One audited operation, minus who asked (see [`AuditLog::record`]).
+#[derive(Clone, Debug)]
+pub struct Event<'req> {
+    pub operation: Operation,
+    pub partition_id: &'req str,
+    /// The intermediate key that wrapped (encrypt) or was asked to unwrap
+    /// (decrypt) the data row key.
+    pub key: Option<&'req proto::KeyMeta>,
+    /// Plaintext size: the input of an encrypt, the output of a decrypt.
+    pub bytes: Option<usize>,
+    pub latency: Duration,
+    pub code: tonic::Code,
+}
+
+enum Message {
+    Line(String),
+    Reopen,
+}
+
+/// The audit stream, shared by every request.
+pub struct AuditLog {
+    partition_ids: PartitionIds,
+    sender: Mutex<Option<mpsc::Sender<Message>>>,
+    writer: Mutex<Option<thread::JoinHandle<()>>>,
+}
+
+impl std::fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("partition_ids", &self.partition_ids)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditLog {
+    /// Open `target` (creating a file target if needed) and start the
+    /// writer thread.
+    pub fn open(target: &Target, partition_ids: PartitionIds) -> Result<Self> {
+        let sink = Sink::open(target)?;
+        let (sender, receiver) = mpsc::channel();
+        let writer = thread::Builder::new()
+            .name("asherah-audit".to_string())
+            .spawn(move || write_lines(&receiver, sink))
+            .context("failed to start the audit log writer")?;
+        Ok(Self {
+            partition_ids,
+            sender: Mutex::new(Some(sender)),
+            writer: Mutex::new(Some(writer)),
+        })
+    }
+
+    /// Queue the record of `event`, made on behalf of `caller` through
+    /// `rpc`.
+    pub fn record(
+        &self,
+        rpc: &str,
+        caller: &Caller,
+        remote_addr: Option<SocketAddr>,
+        event: &Event<'_>,
+    ) {
+        let line = self.format(SystemTime::now(), rpc, caller, remote_addr, event);
+        self.send(Message::Line(line));
+    }
+
+    /// Reopen a file target, so the next record goes to a fresh file after
+    /// the old one was moved aside.
+    pub fn reopen(&self) {
+        self.send(Message::Reopen);
+    }
+
+    /// Write out everything queued and stop the writer. Later records are
+    /// dropped.
+    pub fn close(&self) {
+        drop(self.sender.lock().unwrap_or_else(|e| e.into_inner()).take());
+        let writer = self.writer.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(writer) = writer {
+            if writer.join().is_err() {
+                log::error!("audit log writer panicked");
+            }
+        }
+    }
+
+    fn send(&self, message: Message) {
+        if let Some(sender) = &*self.sender.lock().unwrap_or_else(|e| e.into_inner()) {
+            // Only fails once the writer is gone, which it reports itself.
+            drop(sender.send(message));
+        }
+    }
+
+    fn format(
+        &self,
+        now: SystemTime,
+        rpc: &str,
+        caller: &Caller,
+        remote_addr: Option<SocketAddr>,
+        event: &Event<'_>,
+    ) -> String {
+        let mut record = Map::new();
+        record.insert(
+            "ts".into(),
+            humantime::format_rfc3339_millis(now).to_string().into(),
+        );
+        record.insert("rpc".into(), rpc.into());
+        record.insert("operation".into(), event.operation.as_str().into());
+        record.insert("caller".into(), caller_json(caller, remote_addr));
+        match self.partition_ids {
+            PartitionIds::Hashed => record.insert(
+                "partition_sha256".into(),
+                sha256_hex(event.partition_id).into(),
+            ),
+            PartitionIds::Raw => record.insert("partition".into(), event.partition_id.into()),
+        };
+        if let Some(key) = event.key {
+            let id = match self.partition_ids {
+                PartitionIds::Hashed => ("id_sha256", sha256_hex(&key.key_id)),
+                PartitionIds::Raw => ("id", key.key_id.clone()),
+            };
+            record.insert("key".into(), json!({ id.0: id.1, "created": key.created }));
+        }
+        if let Some(bytes) = event.bytes {
+            record.insert("bytes".into(), bytes.into());
+        }
+        let latency_us = u64::try_from(event.latency.as_micros()).unwrap_or(u64::MAX);
+        record.insert("latency_us".into(), latency_us.into());
+        record.insert("code".into(), crate::metrics::code_name(event.code).into());
+        Value::Object(record).to_string()
+    }
+}
+
+fn caller_json(caller: &Caller, remote_addr: Option<SocketAddr>) -> Value {
+    let mut out = Map::new();
+    if let Some(uid) = caller.uid {
+        out.insert("uid".into(), uid.into());
+    }
+    if let Some(gid) = caller.gid {
+        out.insert("gid".into(), gid.into());
+    }
+    if !caller.sans.is_empty() {
+        out.insert("sans".into(), caller.sans.clone().into());
+    }
+    if let Some(addr) = remote_addr {
+        out.insert("remote_addr".into(), addr.to_string().into());
+    }
+    Value::Object(out)
+}
+
+fn sha256_hex(value: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, value.as_bytes());
+    digest.as_ref().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+enum Sink {
+    Stdout(io::Stdout),
+    File {
+        path: PathBuf,
+        file: BufWriter<File>,
+    },
+}
+
+impl Sink {
+    fn open(target: &Target) -> Result<Self> {
+        match target {
+            Target::Stdout => Ok(Self::Stdout(io::stdout())),
+            Target::File(path) => Ok(Self::File {
+                file: BufWriter::new(open_file(path)?),
+                path: path.clone(),
+            }),
+        }
+    }
+
+    fn write_line(&mut self, line: &str) -> io::Result<()> {
+        match self {
+            Self::Stdout(out) => writeln!(out.lock(), "{line}"),
+            Self::File { file, .. } => writeln!(file, "{line}"),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Stdout(out) => out.flush(),
+            Self::File { file, .. } => file.flush(),
+        }
+    }
+
+    fn reopen(&mut self) -> Result<()> {
+        if let Self::File { path, file } = self {
+            file.flush()?;
+            *file = BufWriter::new(open_file(path)?);
+        }
+        Ok(())
+    }
+}
+
+fn open_file(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .mode(0o600)
+        .open(path)
+        .with_context(|| format!("failed to open audit log {}", path.display()))
+}
+
+/// Drain `receiver` into `sink`, flushing whenever the queue runs dry, until
+/// every sender is gone.
+fn write_lines(receiver: &mpsc::Receiver<Message>, mut sink: Sink) {
+    let mut failing = false;
+    let mut report = |result: Result<()>| match result {
+        Ok(()) => failing = false,
+        // Once per run of failures, not once per record.
+        Err(e) if !failing => {
+            failing = true;
+            log::error!("audit log write failed: {e:#}");
+        }
+        Err(_) => {}
+    };
+    while let Ok(first) = receiver.recv() {
+        let mut next = Some(first);
+        while let Some(message) = next {
+            report(match message {
+                Message::Line(line) => sink.write_line(&line).map_err(Into::into),
+                Message::Reopen => sink.reopen(),
+            });
+            next = receiver.try_recv().ok();
+        }
+        report(sink.flush().map_err(Into::into));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn event(key: Option<&proto::KeyMeta>) -> Event<'_> {
+        Event {
+            operation: Operation::Encrypt,
+            partition_id: "tenant-42",
+            key,
+            bytes: Some(14),
+            latency: Duration::from_micros(812),
+            code: tonic::Code::Ok,
+        }
+    }
+
+    fn format(log: &AuditLog, caller: &Caller, event: &Event<'_>) -> Value {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_millis(1_760_865_243_512);
+        serde_json::from_str(&log.format(now, "Encrypt", caller, None, event)).unwrap()
+    }
+
+    fn log(partition_ids: PartitionIds) -> (AuditLog, tempfile::TempDir) {
+        let dir = tempfile::tempdir().unwrap();
+        let target = Target::File(dir.path().join("audit.jsonl"));
+        (AuditLog::open(&target, partition_ids).unwrap(), dir)
+    }
+
+    #[test]
+    fn records_hash_partition_and_key_ids_by_default() {
+        let (log, _dir) = log(PartitionIds::default());
+        let key = proto::KeyMeta {
+            created: 1_760_865_000,
+            key_id: "_IK_tenant-42_svc_prod".to_string(),
+        };
+        let caller = Caller {
+            uid: Some(1001),
+            gid: Some(1001),
+            sans: vec![],
+        };
+        let record = format(&log, &caller, &event(Some(&key)));
+        assert_eq!(record["ts"], "2025-10-19T09:14:03.512Z");
+        assert_eq!(record["operation"], "encrypt");
+        assert_eq!(record["caller"], json!({"uid": 1001, "gid": 1001}));
+        assert_eq!(record["partition_sha256"], sha256_hex("tenant-42"));
+        assert!(record.get("partition").is_none());
+        assert_eq!(
+            record["key"],
+            json!({"id_sha256": sha256_hex("_IK_tenant-42_svc_prod"), "created": 1_760_865_000})
+        );
+        assert_eq!(record["bytes"], 14);
+        assert_eq!(record["latency_us"], 812);
+        assert_eq!(record["code"], "OK");
+        assert!(!record.to_string().contains("tenant-42"));
+    }
+
+    #[test]
+    fn raw_mode_names_partitions() {
+        let (log, _dir) = log(PartitionIds::Raw);
+        let caller = Caller {
+            sans: vec!["spiffe://example.org/billing".to_string()],
+            ..Caller::default()
+        };
+        let record = format(&log, &caller, &event(None));
+        assert_eq!(record["partition"], "tenant-42");
+        assert_eq!(
+            record["caller"]["sans"],
+            json!(["spiffe://example.org/billing"])
+        );
+        assert!(record.get("key").is_none());
+        assert_eq!(
+            sha256_hex("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn lines_reach_the_file_and_survive_rotation() {
+        let (log, dir) = log(PartitionIds::Hashed);
+        let path = dir.path().join("audit.jsonl");
+        let rotated = dir.path().join("audit.jsonl.1");
+        log.record("Encrypt", &Caller::default(), None, &event(None));
+        std::fs::rename(&path, &rotated).unwrap();
+        log.reopen();
+        log.record("Decrypt", &Caller::default(), None, &event(None));
+        log.close();
+        log.record("Encrypt", &Caller::default(), None, &event(None));
+
+        let old = std::fs::read_to_string(&rotated).unwrap();
+        let new = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(old.lines().count(), 1);
+        assert_eq!(new.lines().count(), 1);
+        let record: Value = serde_json::from_str(new.trim()).unwrap();
+        assert_eq!(record["rpc"], "Decrypt");
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
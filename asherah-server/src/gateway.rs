@@ -0,0 +1,365 @@
+//! Optional HTTP/1.1 gateway for clients without a gRPC stack.
+//!
+//! `POST /v1/partitions/{id}/encrypt` takes the plaintext as the request
+//! body and answers with the Go-compatible `DataRowRecord` JSON (the
+//! `to_json_fast` encoding the bindings' `encrypt_to_json` produces);
+//! `POST /v1/partitions/{id}/decrypt` takes that JSON and answers with the
+//! plaintext. Both run through the gRPC service's own unary handlers, so
+//! the factory, authorization policy, rate limits and validation are shared
+//! with the gRPC listener, and every failure carries the status the gRPC
+//! call would have returned, mapped by [`http_status`].
+
+use std::convert::Infallible;
+use std::io;
+use std::sync::Arc;
+use std::time::Duration;
+
+use bytes::Bytes;
+use http::header::{HeaderValue, ALLOW, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::body::Incoming;
+use tokio::sync::watch;
+use tokio::task::JoinSet;
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::server::Connected;
+
+use crate::authz::Operation;
+use crate::convert::{drr_to_proto, proto_to_drr};
+use crate::health::shutdown_started;
+use crate::limits::Reason;
+use crate::listener::{ConnInfo, ServerConn};
+use crate::proto;
+use crate::proto::app_encryption_server::AppEncryption;
+use crate::service::AppEncryptionService;
+
+const JSON: &str = "application/json";
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// Serve the gateway on `incoming` until shutdown starts, then let open
+/// connections finish their current request and return once they have.
+pub async fn serve<S>(
+    incoming: S,
+    service: Arc<AppEncryptionService>,
+    mut shutdown_rx: watch::Receiver<bool>,
+) where
+    S: Stream<Item = io::Result<ServerConn>> + Send,
+{
+    tokio::pin!(incoming);
+    let mut connections = JoinSet::new();
+    loop {
+        let conn = tokio::select! {
+            biased;
+            () = shutdown_started(&mut shutdown_rx) => break,
+            next = incoming.next() => match next {
+                Some(Ok(conn)) => conn,
+                Some(Err(e)) => {
+                    log::warn!("HTTP gateway accept failed: {e}");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+                None => break,
+            },
+        };
+        while connections.try_join_next().is_some() {}
+        let info = conn.connect_info();
+        let service = Arc::clone(&service);
+        let mut shutdown_rx = shutdown_rx.clone();
+        connections.spawn(async move {
+            let id = info.id;
+            let svc = hyper::service::service_fn(move |req| {
+                let service = Arc::clone(&service);
+                let info = info.clone();
+                async move { Ok::<_, Infallible>(handle(&service, info, req).await) }
+            });
+            let conn = hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(conn), svc);
+            tokio::pin!(conn);
+            let result = tokio::select! {
+                result = conn.as_mut() => result,
+                () = shutdown_started(&mut shutdown_rx) => {
+                    conn.as_mut().graceful_shutdown();
+                    conn.await
+                }
+            };
+            if let Err(e) = result {
+                log::debug!("HTTP gateway connection {id} failed: {e}");
+            }
+        });
+    }
+    while connections.join_next().await.is_some() {}
+}
+
+/// A failed request: the gRPC code it maps to and the HTTP status sent.
+#[derive(Debug)]
+struct HttpError {
+    status: StatusCode,
+    code: tonic::Code,
+    message: String,
+}
+
+impl HttpError {
+    fn new(code: tonic::Code, message: impl Into<String>) -> Self {
+        Self {
+            status: http_status(code),
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// `{"code": "PERMISSION_DENIED", "message": "..."}`.
+    fn into_response(self) -> http::Response<Full<Bytes>> {
+        let body = serde_json::json!({
+            "code": code_name(self.code),
+            "message": self.message,
+        });
+        let mut response = http::Response::new(Full::new(Bytes::from(body.to_string())));
+        *response.status_mut() = self.status;
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static(JSON));
+        response
+    }
+}
+
+impl From<tonic::Status> for HttpError {
+    fn from(status: tonic::Status) -> Self {
+        Self::new(status.code(), status.message())
+    }
+}
+
+/// The HTTP status for a gRPC code, following the mapping grpc-gateway and
+/// Google's HTTP APIs use.
+pub fn http_status(code: tonic::Code) -> StatusCode {
+    use tonic::Code;
+    match code {
+        Code::Ok => StatusCode::OK,
+        // nginx's "client closed request"; there is no standard status.
+        Code::Cancelled => StatusCode::from_u16(499).unwrap_or(StatusCode::BAD_REQUEST),
+        Code::InvalidArgument | Code::FailedPrecondition | Code::OutOfRange => {
+            StatusCode::BAD_REQUEST
+        }
+        Code::Unauthenticated => StatusCode::UNAUTHORIZED,
+        Code::PermissionDenied => StatusCode::FORBIDDEN,
+        Code::NotFound => StatusCode::NOT_FOUND,
+        Code::AlreadyExists | Code::Aborted => StatusCode::CONFLICT,
+        Code::ResourceExhausted => StatusCode::TOO_MANY_REQUESTS,
+        Code::Unimplemented => StatusCode::NOT_IMPLEMENTED,
+        Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+        Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+        Code::Unknown | Code::Internal | Code::DataLoss => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+/// The canonical gRPC name of `code`, as in `grpc-status` documentation.
+fn code_name(code: tonic::Code) -> &'static str {
+    use tonic::Code;
+    match code {
+        Code::Ok => "OK",
+        Code::Cancelled => "CANCELLED",
+        Code::Unknown => "UNKNOWN",
+        Code::InvalidArgument => "INVALID_ARGUMENT",
+        Code::DeadlineExceeded => "DEADLINE_EXCEEDED",
+        Code::NotFound => "NOT_FOUND",
+        Code::AlreadyExists => "ALREADY_EXISTS",
+        Code::PermissionDenied => "PERMISSION_DENIED",
+        Code::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        Code::FailedPrecondition => "FAILED_PRECONDITION",
+        Code::Aborted => "ABORTED",
+        Code::OutOfRange => "OUT_OF_RANGE",
+        Code::Unimplemented => "UNIMPLEMENTED",
+        Code::Internal => "INTERNAL",
+        Code::Unavailable => "UNAVAILABLE",
+        Code::DataLoss => "DATA_LOSS",
+        Code::Unauthenticated => "UNAUTHENTICATED",
+    }
+}
+
+async fn handle(
+    service: &AppEncryptionService,
+    conn: ConnInfo,
+    req: http::Request<Incoming>,
+) -> http::Response<Full<Bytes>> {
+    respond(service, conn, req)
+        .await
+        .unwrap_or_else(HttpError::into_response)
+}
+
+async fn respond(
+    service: &AppEncryptionService,
+    conn: ConnInfo,
+    req: http::Request<Incoming>,
+) -> Result<http::Response<Full<Bytes>>, HttpError> {
+    let (partition_id, op) = route(req.uri().path())?;
+    if req.method() != Method::POST {
+        let mut response =
+            HttpError::new(tonic::Code::Unimplemented, "only POST is supported").into_response();
+        *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+        response
+            .headers_mut()
+            .insert(ALLOW, HeaderValue::from_static("POST"));
+        return Ok(response);
+    }
+    let body = read_body(service, req.into_body()).await?;
+    match op {
+        Operation::Decrypt => decrypt(service, conn, partition_id, &body).await,
+        _ => encrypt(service, conn, partition_id, body).await,
+    }
+}
+
+/// The partition ID (percent-decoded) and operation a path names.
+fn route(path: &str) -> Result<(String, Operation), HttpError> {
+    let not_found = || HttpError::new(tonic::Code::NotFound, format!("no route for {path}"));
+    let (id, action) = path
+        .strip_prefix("/v1/partitions/")
+        .and_then(|rest| rest.split_once('/'))
+        .ok_or_else(not_found)?;
+    let op = match action {
+        "encrypt" => Operation::Encrypt,
+        "decrypt" => Operation::Decrypt,
+        _ => return Err(not_found()),
+    };
+    if id.is_empty() {
+        return Err(not_found());
+    }
+    let id = percent_encoding::percent_decode_str(id)
+        .decode_utf8()
+        .map_err(|_| {
+            HttpError::new(
+                tonic::Code::InvalidArgument,
+                "partition_id is not valid UTF-8",
+            )
+        })?;
+    Ok((id.into_owned(), op))
+}
+
+/// The whole request body, refused with 413 past the request size cap.
+async fn read_body(service: &AppEncryptionService, body: Incoming) -> Result<Bytes, HttpError> {
+    let limiter = service.limiter();
+    match Limited::new(body, limiter.max_request_bytes())
+        .collect()
+        .await
+    {
+        Ok(collected) => Ok(collected.to_bytes()),
+        Err(e) if e.is::<LengthLimitError>() => {
+            let exhausted = limiter.reject(Reason::RequestSize);
+            Err(HttpError {
+                status: StatusCode::PAYLOAD_TOO_LARGE,
+                ..HttpError::new(tonic::Code::ResourceExhausted, exhausted.to_string())
+            })
+        }
+        Err(e) => Err(HttpError::new(
+            tonic::Code::InvalidArgument,
+            format!("failed to read request body: {e}"),
+        )),
+    }
+}
+
+fn request<T>(message: T, conn: ConnInfo) -> tonic::Request<T> {
+    let mut request = tonic::Request::new(message);
+    request.extensions_mut().insert(conn);
+    request
+}
+
+fn ok(content_type: &'static str, body: impl Into<Bytes>) -> http::Response<Full<Bytes>> {
+    let mut response = http::Response::new(Full::new(body.into()));
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    response
+}
+
+async fn encrypt(
+    service: &AppEncryptionService,
+    conn: ConnInfo,
+    partition_id: String,
+    plaintext: Bytes,
+) -> Result<http::Response<Full<Bytes>>, HttpError> {
+    let message = proto::EncryptRequest {
+        partition_id,
+        data: plaintext.into(),
+    };
+    let drr = service
+        .encrypt(request(message, conn))
+        .await?
+        .into_inner()
+        .data_row_record
+        .ok_or_else(|| HttpError::new(tonic::Code::Internal, "encrypt returned no record"))?;
+    Ok(ok(JSON, proto_to_drr(drr).to_json_fast()))
+}
+
+async fn decrypt(
+    service: &AppEncryptionService,
+    conn: ConnInfo,
+    partition_id: String,
+    json: &[u8],
+) -> Result<http::Response<Full<Bytes>>, HttpError> {
+    let drr: asherah::DataRowRecord = serde_json::from_slice(json).map_err(|e| {
+        HttpError::new(
+            tonic::Code::InvalidArgument,
+            format!("invalid DataRowRecord JSON: {e}"),
+        )
+    })?;
+    let message = proto::DecryptRequest {
+        partition_id,
+        data_row_record: Some(drr_to_proto(drr)),
+    };
+    let plaintext = service
+        .decrypt(request(message, conn))
+        .await?
+        .into_inner()
+        .data;
+    Ok(ok(OCTET_STREAM, plaintext))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn routes_decode_partition_ids() {
+        let (id, op) = route("/v1/partitions/team%2Fa%20b/encrypt").unwrap();
+        assert_eq!(id, "team/a b");
+        assert_eq!(op, Operation::Encrypt);
+        let (id, op) = route("/v1/partitions/p1/decrypt").unwrap();
+        assert_eq!(id, "p1");
+        assert_eq!(op, Operation::Decrypt);
+    }
+
+    #[test]
+    fn unknown_routes_are_not_found() {
+        for path in [
+            "/",
+            "/v1/partitions/p1",
+            "/v1/partitions//encrypt",
+            "/v1/partitions/p1/rotate",
+            "/v1/partitions/a/b/encrypt",
+            "/v2/partitions/p1/encrypt",
+        ] {
+            let err = route(path).unwrap_err();
+            assert_eq!(err.status, StatusCode::NOT_FOUND, "{path}");
+        }
+        let err = route("/v1/partitions/%ff/encrypt").unwrap_err();
+        assert_eq!(err.status, StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn grpc_codes_map_to_http_statuses() {
+        use tonic::Code;
+        for (code, status) in [
+            (Code::InvalidArgument, 400),
+            (Code::Unauthenticated, 401),
+            (Code::PermissionDenied, 403),
+            (Code::NotFound, 404),
+            (Code::ResourceExhausted, 429),
+            (Code::Internal, 500),
+            (Code::Unimplemented, 501),
+            (Code::Unavailable, 503),
+            (Code::DeadlineExceeded, 504),
+        ] {
+            assert_eq!(http_status(code).as_u16(), status, "{code:?}");
+        }
+        assert_eq!(code_name(Code::PermissionDenied), "PERMISSION_DENIED");
+    }
+}
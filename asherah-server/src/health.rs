@@ -0,0 +1,286 @@
+//! `grpc.health.v1.Health` backed by a periodic metastore + KMS probe.
+//!
+//! Health RPCs only read the cached status; they never reach the
+//! metastore or KMS, so an orchestrator probing every second can't turn
+//! into a KMS request storm. A background task refreshes the status every
+//! probe interval and flips every service to NOT_SERVING once shutdown
+//! starts, so load balancers stop routing new work during the drain.
+
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context as _;
+use asherah::traits::{KeyManagementService, Metastore};
+use tokio::sync::{mpsc, watch};
+use tokio::task::JoinHandle;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+use tonic_health::pb::health_check_response::ServingStatus as WireStatus;
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tonic_health::pb::{HealthCheckRequest, HealthCheckResponse};
+use tonic_health::server::HealthReporter;
+use tonic_health::ServingStatus;
+
+use crate::proto::app_encryption_server::SERVICE_NAME;
+use crate::service::Factory;
+
+/// Service names whose status the checker maintains: the whole server
+/// (`""`, per the health checking spec) and `AppEncryption`.
+pub const SERVICES: [&str; 2] = ["", SERVICE_NAME];
+
+/// Key bytes wrapped and unwrapped by the KMS half of the probe. Not a
+/// secret; it never leaves this process unencrypted.
+const PROBE_KEY: &[u8; 32] = b"asherah-server-health-probe-key!";
+
+/// Probe the factory's metastore and KMS the way a first encrypt would:
+/// `load_latest` of the system key, then a KMS encrypt/decrypt round trip.
+/// An absent system key is healthy (nothing has been encrypted yet); an
+/// unreachable store or KMS is not.
+pub fn probe_factory(factory: &Factory) -> anyhow::Result<()> {
+    let sk_id = factory.system_key_id();
+    factory
+        .metastore()
+        .load_latest(&sk_id)
+        .with_context(|| format!("metastore load_latest({sk_id}) failed"))?;
+    let kms = factory.kms();
+    let blob = kms
+        .encrypt_key(&(), PROBE_KEY)
+        .context("KMS encrypt failed")?;
+    let unwrapped = kms.decrypt_key(&(), &blob).context("KMS decrypt failed")?;
+    if unwrapped.as_slice() != PROBE_KEY.as_slice() {
+        anyhow::bail!("KMS round trip returned different key bytes");
+    }
+    Ok(())
+}
+
+/// How often, and with what deadline, the checker runs its probe.
+#[derive(Clone, Copy, Debug)]
+pub struct ProbeSchedule {
+    pub interval: Duration,
+    pub timeout: Duration,
+}
+
+/// Start the probe loop and return the Health service to register.
+///
+/// Every service starts NOT_SERVING and flips to SERVING once the first
+/// probe passes. `probe` runs on the blocking pool; one that outlives its
+/// timeout counts as a failure, and no second probe starts until it
+/// returns. When `shutdown_rx` flips, statuses go to NOT_SERVING, the loop
+/// exits, and open `Watch` streams end after delivering that status so
+/// they don't hold the graceful drain open.
+pub fn spawn_checker<F>(
+    probe: F,
+    schedule: ProbeSchedule,
+    shutdown_rx: watch::Receiver<bool>,
+) -> (HealthServer<DrainingHealthService>, JoinHandle<()>)
+where
+    F: Fn() -> anyhow::Result<()> + Send + Sync + 'static,
+{
+    let (reporter, _) = tonic_health::server::health_reporter();
+    let service = DrainingHealthService {
+        inner: tonic_health::server::HealthService::from_health_reporter(reporter.clone()),
+        shutdown_rx: shutdown_rx.clone(),
+    };
+    let task = tokio::spawn(run_checker(
+        reporter,
+        Arc::new(probe),
+        schedule,
+        shutdown_rx,
+    ));
+    (HealthServer::new(service), task)
+}
+
+/// Resolves once shutdown starts. A dropped sender counts too: that only
+/// happens during runtime teardown.
+pub(crate) async fn shutdown_started(shutdown_rx: &mut watch::Receiver<bool>) {
+    drop(shutdown_rx.wait_for(|stop| *stop).await);
+}
+
+async fn set_all(reporter: &HealthReporter, status: ServingStatus) {
+    for name in SERVICES {
+        reporter.set_service_status(name, status).await;
+    }
+}
+
+async fn run_checker<F>(
+    reporter: HealthReporter,
+    probe: Arc<F>,
+    schedule: ProbeSchedule,
+    mut shutdown_rx: watch::Receiver<bool>,
+) where
+    F: Fn() -> anyhow::Result<()> + Send + Sync + 'static,
+{
+    set_all(&reporter, ServingStatus::NotServing).await;
+    let mut ticker = tokio::time::interval(schedule.interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    let mut in_flight: Option<JoinHandle<anyhow::Result<()>>> = None;
+    let mut serving: Option<bool> = None;
+
+    loop {
+        let outcome = tokio::select! {
+            biased;
+            () = shutdown_started(&mut shutdown_rx) => break,
+            outcome = async {
+                ticker.tick().await;
+                probe_once(&probe, &mut in_flight, schedule.timeout).await
+            } => outcome,
+        };
+        let healthy = outcome.is_ok();
+        match (&outcome, serving) {
+            (Ok(()), Some(true)) => {}
+            (Ok(()), _) => log::info!("health probe passed; serving"),
+            (Err(e), Some(false)) => log::debug!("health probe still failing: {e:#}"),
+            (Err(e), _) => log::warn!("health probe failed; not serving: {e:#}"),
+        }
+        if serving != Some(healthy) {
+            let status = if healthy {
+                ServingStatus::Serving
+            } else {
+                ServingStatus::NotServing
+            };
+            set_all(&reporter, status).await;
+            serving = Some(healthy);
+        }
+    }
+
+    set_all(&reporter, ServingStatus::NotServing).await;
+    log::debug!("health checker stopped; reporting not serving");
+}
+
+/// Run `probe` unless the previous run is still stuck, bounded by `timeout`.
+async fn probe_once<F>(
+    probe: &Arc<F>,
+    in_flight: &mut Option<JoinHandle<anyhow::Result<()>>>,
+    timeout: Duration,
+) -> anyhow::Result<()>
+where
+    F: Fn() -> anyhow::Result<()> + Send + Sync + 'static,
+{
+    let handle = match in_flight.take() {
+        Some(stuck) if !stuck.is_finished() => {
+            *in_flight = Some(stuck);
+            anyhow::bail!("previous health probe still running");
+        }
+        _ => {
+            let probe = Arc::clone(probe);
+            in_flight.insert(tokio::task::spawn_blocking(move || probe()))
+        }
+    };
+    match tokio::time::timeout(timeout, handle).await {
+        Ok(joined) => {
+            *in_flight = None;
+            joined.context("health probe panicked")?
+        }
+        Err(_) => anyhow::bail!("health probe timed out after {timeout:?}"),
+    }
+}
+
+type WatchStream = Pin<Box<dyn Stream<Item = Result<HealthCheckResponse, Status>> + Send>>;
+
+/// The stock health service, except that `Watch` streams end once shutdown
+/// starts. Upstream streams never end on their own, so each one would
+/// otherwise keep the server draining until the drain timeout.
+#[derive(Debug)]
+pub struct DrainingHealthService {
+    inner: tonic_health::server::HealthService,
+    shutdown_rx: watch::Receiver<bool>,
+}
+
+#[tonic::async_trait]
+impl Health for DrainingHealthService {
+    async fn check(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<HealthCheckResponse>, Status> {
+        self.inner.check(request).await
+    }
+
+    type WatchStream = WatchStream;
+
+    async fn watch(
+        &self,
+        request: Request<HealthCheckRequest>,
+    ) -> Result<Response<Self::WatchStream>, Status> {
+        let mut updates = self.inner.watch(request).await?.into_inner();
+        let mut shutdown_rx = self.shutdown_rx.clone();
+        let (tx, rx) = mpsc::channel(4);
+        tokio::spawn(async move {
+            let not_serving = WireStatus::NotServing as i32;
+            let mut last = None;
+            loop {
+                tokio::select! {
+                    biased;
+                    () = tx.closed() => return,
+                    () = shutdown_started(&mut shutdown_rx) => break,
+                    update = updates.next() => match update {
+                        Some(update) => {
+                            last = update.as_ref().ok().map(|r| r.status);
+                            if tx.send(update).await.is_err() {
+                                return;
+                            }
+                        }
+                        None => return,
+                    },
+                }
+            }
+            if last != Some(not_serving) {
+                let last_update = HealthCheckResponse {
+                    status: not_serving,
+                };
+                if tx.send(Ok(last_update)).await.is_err() {
+                    log::debug!("health watcher went away before the drain update");
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn factory() -> Factory {
+        let config = asherah_config::ConfigOptions {
+            service_name: Some("health-service".to_string()),
+            product_id: Some("health-product".to_string()),
+            metastore: Some("memory".to_string()),
+            kms: Some("test-debug-static".to_string()),
+            ..Default::default()
+        };
+        asherah_config::factory_from_config(&config).unwrap().0
+    }
+
+    #[test]
+    fn probe_passes_against_memory_metastore_and_static_kms() {
+        probe_factory(&factory()).unwrap();
+    }
+
+    #[test]
+    fn system_key_id_follows_partition_naming() {
+        let f = factory();
+        assert_eq!(f.system_key_id(), "_SK_health-service_health-product");
+    }
+
+    #[tokio::test]
+    async fn stuck_probe_times_out_without_stacking_runs() {
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counted = Arc::clone(&runs);
+        let probe = Arc::new(move || {
+            counted.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(300));
+            Ok(())
+        });
+        let mut in_flight = None;
+        let timeout = Duration::from_millis(20);
+        assert!(probe_once(&probe, &mut in_flight, timeout).await.is_err());
+        let err = probe_once(&probe, &mut in_flight, timeout)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("still running"), "{err}");
+        assert_eq!(runs.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+}
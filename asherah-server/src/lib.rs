@@ -1,8 +1,17 @@
 #[cfg(not(unix))]
 compile_error!("asherah-server requires Unix (Linux/macOS) for Unix domain socket support");
 
+pub mod audit;
+pub mod authz;
 pub mod convert;
+pub mod gateway;
+pub mod health;
+pub mod limits;
+pub mod listener;
+pub mod metrics;
+pub mod reload;
 pub mod service;
+pub mod tls;
 
 #[allow(
     clippy::all,
@@ -12,6 +21,32 @@ pub mod service;
 )]
 pub mod proto {
     tonic::include_proto!("asherah.apps.server");
+
+    /// Encoded descriptors for `appencryption.proto`, served by reflection.
+    pub const FILE_DESCRIPTOR_SET: &[u8] =
+        tonic::include_file_descriptor_set!("appencryption_descriptor");
+}
+
+/// gRPC server reflection (v1, plus v1alpha for older `grpcurl` releases)
+/// describing `AppEncryption` and `grpc.health.v1.Health`, so tools can
+/// list and call the services without a local copy of the `.proto` files.
+pub fn reflection_services() -> Result<
+    (
+        tonic_reflection::server::v1::ServerReflectionServer<
+            impl tonic_reflection::server::v1::ServerReflection,
+        >,
+        tonic_reflection::server::v1alpha::ServerReflectionServer<
+            impl tonic_reflection::server::v1alpha::ServerReflection,
+        >,
+    ),
+    tonic_reflection::server::Error,
+> {
+    let builder = || {
+        tonic_reflection::server::Builder::configure()
+            .register_encoded_file_descriptor_set(proto::FILE_DESCRIPTOR_SET)
+            .register_encoded_file_descriptor_set(tonic_health::pb::FILE_DESCRIPTOR_SET)
+    };
+    Ok((builder().build_v1()?, builder().build_v1alpha()?))
 }
 
 /// Parse a Go-style duration string into seconds.
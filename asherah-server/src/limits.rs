@@ -0,0 +1,557 @@
+//! Admission control: token-bucket rate limits per partition ID and per
+//! connection, a server-wide cap on concurrent `Session` streams, and a
+//! maximum request message size. Every rejection is `RESOURCE_EXHAUSTED`
+//! (or an `ErrorResponse` saying so on a stream) and is counted in
+//! `asherah_requests_rejected_total`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::Hash;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex, RwLock, RwLockReadGuard};
+use std::task::{Context, Poll};
+use std::time::Instant;
+
+use bytes::{Buf, Bytes};
+use http_body::Frame;
+
+use crate::metrics::Metrics;
+
+/// Buckets are forgotten once full again, but only swept when a map grows
+/// past this many keys, so the common case never walks the map.
+const PRUNE_THRESHOLD: usize = 10_000;
+
+/// A sustained rate and the burst allowed above it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RateLimit {
+    /// Operations per second refilled into the bucket.
+    pub per_second: f64,
+    /// Bucket size: operations allowed back to back after an idle period.
+    pub burst: u32,
+}
+
+/// Admission limits as given on the command line. `None` disables a limit.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LimitOptions {
+    /// Encrypt/decrypt operations per partition ID.
+    pub partition: Option<RateLimit>,
+    /// Encrypt/decrypt operations per client connection.
+    pub connection: Option<RateLimit>,
+    /// `Session` streams open at once across all clients.
+    pub max_streams: Option<usize>,
+    /// Largest gRPC request message accepted.
+    pub max_request_bytes: usize,
+}
+
+impl Default for LimitOptions {
+    fn default() -> Self {
+        Self {
+            partition: None,
+            connection: None,
+            max_streams: None,
+            max_request_bytes: asherah::limits::MAX_ENVELOPE_BYTES,
+        }
+    }
+}
+
+/// Which limit turned a request away; the `reason` metric label.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reason {
+    PartitionRate,
+    ConnectionRate,
+    Streams,
+    RequestSize,
+}
+
+impl Reason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::PartitionRate => "partition_rate",
+            Self::ConnectionRate => "connection_rate",
+            Self::Streams => "streams",
+            Self::RequestSize => "request_size",
+        }
+    }
+}
+
+/// A limit refused the request.
+#[derive(Debug)]
+pub struct Exhausted(Reason);
+
+impl Exhausted {
+    pub fn reason(&self) -> Reason {
+        self.0
+    }
+}
+
+impl fmt::Display for Exhausted {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self.0 {
+            Reason::PartitionRate => "resource exhausted: partition rate limit exceeded",
+            Reason::ConnectionRate => "resource exhausted: connection rate limit exceeded",
+            Reason::Streams => "resource exhausted: too many concurrent streams",
+            Reason::RequestSize => "resource exhausted: request message too large",
+        })
+    }
+}
+
+impl std::error::Error for Exhausted {}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+impl Bucket {
+    fn full(limit: RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: f64::from(limit.burst),
+            refilled: now,
+        }
+    }
+
+    fn refill(&mut self, limit: RateLimit, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.per_second).min(f64::from(limit.burst));
+        self.refilled = now;
+    }
+
+    fn try_take(&mut self, limit: RateLimit, now: Instant) -> bool {
+        self.refill(limit, now);
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// One token bucket per key, created full on first use.
+#[derive(Debug)]
+struct Buckets<K> {
+    limit: RateLimit,
+    buckets: Mutex<HashMap<K, Bucket>>,
+}
+
+impl<K: Hash + Eq + Clone> Buckets<K> {
+    fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn try_take(&self, key: &K, now: Instant) -> bool {
+        let limit = self.limit;
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if buckets.len() >= PRUNE_THRESHOLD && !buckets.contains_key(key) {
+            // A bucket that has refilled completely is indistinguishable
+            // from a new one, so dropping it loses nothing.
+            buckets.retain(|_, b| {
+                b.refill(limit, now);
+                b.tokens < f64::from(limit.burst)
+            });
+        }
+        buckets
+            .entry(key.clone())
+            .or_insert_with(|| Bucket::full(limit, now))
+            .try_take(limit, now)
+    }
+
+    /// Give back a token taken for an operation another limit then refused.
+    fn refund(&self, key: &K) {
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(bucket) = buckets.get_mut(key) {
+            bucket.tokens = (bucket.tokens + 1.0).min(f64::from(self.limit.burst));
+        }
+    }
+}
+
+/// The configured limits, shared by every request.
+#[derive(Debug)]
+pub struct Limiter {
+    limits: RwLock<Limits>,
+    metrics: Option<Arc<Metrics>>,
+}
+
+/// The limits in force; replaced as a whole by [`Limiter::reconfigure`].
+#[derive(Debug)]
+struct Limits {
+    partition: Option<Buckets<String>>,
+    connection: Option<Buckets<u64>>,
+    max_streams: Option<usize>,
+    max_request_bytes: usize,
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new(&LimitOptions::default(), None)
+    }
+}
+
+impl Limiter {
+    /// Limits from `opts`, counting rejections in `metrics` when given.
+    pub fn new(opts: &LimitOptions, metrics: Option<Arc<Metrics>>) -> Self {
+        Self {
+            limits: RwLock::new(Limits {
+                partition: opts.partition.map(Buckets::new),
+                connection: opts.connection.map(Buckets::new),
+                max_streams: opts.max_streams,
+                max_request_bytes: opts.max_request_bytes,
+            }),
+            metrics,
+        }
+    }
+
+    /// Switch to the limits in `opts` for every request from now on. A rate
+    /// limit that didn't change keeps its buckets, so a reload doesn't hand
+    /// every partition and connection a fresh burst.
+    pub fn reconfigure(&self, opts: &LimitOptions) {
+        let mut limits = self.limits.write().unwrap_or_else(|e| e.into_inner());
+        limits.partition = match limits.partition.take() {
+            Some(buckets) if Some(buckets.limit) == opts.partition => Some(buckets),
+            _ => opts.partition.map(Buckets::new),
+        };
+        limits.connection = match limits.connection.take() {
+            Some(buckets) if Some(buckets.limit) == opts.connection => Some(buckets),
+            _ => opts.connection.map(Buckets::new),
+        };
+        limits.max_streams = opts.max_streams;
+        limits.max_request_bytes = opts.max_request_bytes;
+    }
+
+    fn limits(&self) -> RwLockReadGuard<'_, Limits> {
+        self.limits.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn max_streams(&self) -> Option<usize> {
+        self.limits().max_streams
+    }
+
+    pub fn max_request_bytes(&self) -> usize {
+        self.limits().max_request_bytes
+    }
+
+    /// Take a token for one encrypt or decrypt under `partition_id` from
+    /// the partition's bucket and, when the caller's connection is known,
+    /// from the connection's.
+    pub fn admit(&self, connection: Option<u64>, partition_id: &str) -> Result<(), Exhausted> {
+        self.admit_at(connection, partition_id, Instant::now())
+    }
+
+    fn admit_at(
+        &self,
+        connection: Option<u64>,
+        partition_id: &str,
+        now: Instant,
+    ) -> Result<(), Exhausted> {
+        let limits = self.limits();
+        let connection = limits.connection.as_ref().zip(connection);
+        if let Some((buckets, id)) = &connection {
+            if !buckets.try_take(id, now) {
+                return Err(self.reject(Reason::ConnectionRate));
+            }
+        }
+        if let Some(buckets) = &limits.partition {
+            if !buckets.try_take(&partition_id.to_string(), now) {
+                if let Some((buckets, id)) = &connection {
+                    buckets.refund(id);
+                }
+                return Err(self.reject(Reason::PartitionRate));
+            }
+        }
+        Ok(())
+    }
+
+    /// Count and log a rejection, returning the error to report.
+    pub fn reject(&self, reason: Reason) -> Exhausted {
+        log::debug!("rejected request: {} limit", reason.as_str());
+        if let Some(metrics) = &self.metrics {
+            metrics.record_rejection(reason.as_str());
+        }
+        Exhausted(reason)
+    }
+}
+
+/// Tower layer that refuses request messages over
+/// [`Limiter::max_request_bytes`] with `RESOURCE_EXHAUSTED`.
+///
+/// The check reads the length prefix of each gRPC frame as it arrives, so an
+/// oversized message is refused before any of it is buffered. Tonic's own
+/// decoding limit would answer `OUT_OF_RANGE` instead; the Go server (and
+/// grpc-go generally) answers `RESOURCE_EXHAUSTED`.
+#[derive(Clone, Debug)]
+pub struct RequestSizeLayer {
+    limiter: Arc<Limiter>,
+}
+
+impl RequestSizeLayer {
+    pub fn new(limiter: Arc<Limiter>) -> Self {
+        Self { limiter }
+    }
+}
+
+impl<S> tower::Layer<S> for RequestSizeLayer {
+    type Service = RequestSizeService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RequestSizeService {
+            inner,
+            limiter: Arc::clone(&self.limiter),
+        }
+    }
+}
+
+/// See [`RequestSizeLayer`].
+#[derive(Clone, Debug)]
+pub struct RequestSizeService<S> {
+    inner: S,
+    limiter: Arc<Limiter>,
+}
+
+impl<S> tower::Service<http::Request<tonic::body::Body>> for RequestSizeService<S>
+where
+    S: tower::Service<http::Request<tonic::body::Body>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<tonic::body::Body>) -> Self::Future {
+        let limiter = Arc::clone(&self.limiter);
+        self.inner.call(req.map(|inner| {
+            tonic::body::Body::new(SizeCheckedBody {
+                inner,
+                frames: FrameLengths::default(),
+                limiter: Some(limiter),
+            })
+        }))
+    }
+}
+
+/// Tracks gRPC message boundaries (a 1-byte flag and 4-byte big-endian
+/// length before each message) across arbitrarily split data frames.
+#[derive(Debug, Default)]
+struct FrameLengths {
+    header: [u8; 5],
+    header_len: usize,
+    remaining: usize,
+}
+
+impl FrameLengths {
+    /// Feed the next chunk; `Err` carries the first declared message
+    /// length over `max`.
+    fn feed(&mut self, mut chunk: &[u8], max: usize) -> Result<(), usize> {
+        while !chunk.is_empty() {
+            if self.remaining > 0 {
+                let n = self.remaining.min(chunk.len());
+                self.remaining -= n;
+                chunk.advance(n);
+                continue;
+            }
+            let n = (self.header.len() - self.header_len).min(chunk.len());
+            self.header[self.header_len..self.header_len + n].copy_from_slice(&chunk[..n]);
+            self.header_len += n;
+            chunk.advance(n);
+            if self.header_len == self.header.len() {
+                self.header_len = 0;
+                let [_, a, b, c, d] = self.header;
+                let len = u32::from_be_bytes([a, b, c, d]) as usize;
+                if len > max {
+                    return Err(len);
+                }
+                self.remaining = len;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Request body that fails with `RESOURCE_EXHAUSTED` at the first message
+/// header announcing more than the limit. Tonic surfaces a `Status` body
+/// error as the call's status.
+struct SizeCheckedBody {
+    inner: tonic::body::Body,
+    frames: FrameLengths,
+    /// Dropped once the limit trips, after which the body reports its end.
+    limiter: Option<Arc<Limiter>>,
+}
+
+impl http_body::Body for SizeCheckedBody {
+    type Data = Bytes;
+    type Error = tonic::Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, tonic::Status>>> {
+        let Some(limiter) = self.limiter.clone() else {
+            return Poll::Ready(None);
+        };
+        let polled = Pin::new(&mut self.inner).poll_frame(cx);
+        if let Poll::Ready(Some(Ok(frame))) = &polled {
+            if let Some(data) = frame.data_ref() {
+                let max = limiter.max_request_bytes();
+                if let Err(len) = self.frames.feed(data, max) {
+                    self.limiter = None;
+                    let err = limiter.reject(Reason::RequestSize);
+                    return Poll::Ready(Some(Err(tonic::Status::resource_exhausted(format!(
+                        "{err}: {len} bytes exceeds the limit of {max} bytes"
+                    )))));
+                }
+            }
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.limiter.is_none() || self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn limiter(partition: Option<RateLimit>, connection: Option<RateLimit>) -> Limiter {
+        Limiter::new(
+            &LimitOptions {
+                partition,
+                connection,
+                ..LimitOptions::default()
+            },
+            None,
+        )
+    }
+
+    const TWO_PER_SECOND: RateLimit = RateLimit {
+        per_second: 2.0,
+        burst: 2,
+    };
+
+    #[test]
+    fn partition_buckets_allow_a_burst_then_refill() {
+        let limiter = limiter(Some(TWO_PER_SECOND), None);
+        let t0 = Instant::now();
+        assert!(limiter.admit_at(None, "a", t0).is_ok());
+        assert!(limiter.admit_at(None, "a", t0).is_ok());
+        let err = limiter.admit_at(None, "a", t0).unwrap_err();
+        assert_eq!(err.reason(), Reason::PartitionRate);
+        // Other partitions have their own bucket.
+        assert!(limiter.admit_at(None, "b", t0).is_ok());
+        // Half a second refills one token at 2/s.
+        let t1 = t0 + Duration::from_millis(500);
+        assert!(limiter.admit_at(None, "a", t1).is_ok());
+        assert!(limiter.admit_at(None, "a", t1).is_err());
+    }
+
+    #[test]
+    fn connection_tokens_are_refunded_when_the_partition_refuses() {
+        let limiter = limiter(
+            Some(RateLimit {
+                per_second: 1.0,
+                burst: 1,
+            }),
+            Some(TWO_PER_SECOND),
+        );
+        let t0 = Instant::now();
+        assert!(limiter.admit_at(Some(1), "a", t0).is_ok());
+        assert_eq!(
+            limiter.admit_at(Some(1), "a", t0).unwrap_err().reason(),
+            Reason::PartitionRate
+        );
+        // The refused call didn't spend connection 1's second token.
+        assert!(limiter.admit_at(Some(1), "b", t0).is_ok());
+        assert_eq!(
+            limiter.admit_at(Some(1), "c", t0).unwrap_err().reason(),
+            Reason::ConnectionRate
+        );
+        // Unknown connections only hit the partition limit.
+        assert!(limiter.admit_at(None, "d", t0).is_ok());
+    }
+
+    #[test]
+    fn reconfigure_keeps_unchanged_buckets() {
+        let limiter = limiter(Some(TWO_PER_SECOND), Some(TWO_PER_SECOND));
+        let t0 = Instant::now();
+        assert!(limiter.admit_at(Some(1), "a", t0).is_ok());
+        assert!(limiter.admit_at(Some(1), "a", t0).is_ok());
+
+        // Same partition limit: "a" stays drained. A new connection limit
+        // starts connection 1 over.
+        limiter.reconfigure(&LimitOptions {
+            partition: Some(TWO_PER_SECOND),
+            connection: Some(RateLimit {
+                per_second: 1.0,
+                burst: 1,
+            }),
+            max_streams: Some(3),
+            max_request_bytes: 1024,
+        });
+        assert_eq!(
+            limiter.admit_at(Some(1), "a", t0).unwrap_err().reason(),
+            Reason::PartitionRate
+        );
+        assert!(limiter.admit_at(Some(1), "b", t0).is_ok());
+        assert_eq!(limiter.max_streams(), Some(3));
+        assert_eq!(limiter.max_request_bytes(), 1024);
+
+        limiter.reconfigure(&LimitOptions::default());
+        for _ in 0..10 {
+            assert!(limiter.admit_at(Some(1), "a", t0).is_ok());
+        }
+    }
+
+    #[test]
+    fn no_limits_admit_everything() {
+        let limiter = Limiter::default();
+        for _ in 0..100 {
+            assert!(limiter.admit(Some(1), "a").is_ok());
+        }
+    }
+
+    #[test]
+    fn full_buckets_are_pruned() {
+        let buckets = Buckets::new(TWO_PER_SECOND);
+        let t0 = Instant::now();
+        for key in 0..PRUNE_THRESHOLD as u64 {
+            assert!(buckets.try_take(&key, t0));
+        }
+        let later = t0 + Duration::from_secs(1);
+        assert!(buckets.try_take(&u64::MAX, later));
+        assert_eq!(buckets.buckets.lock().unwrap().len(), 1);
+    }
+
+    fn frame(len: u32) -> Vec<u8> {
+        let mut out = vec![0];
+        out.extend_from_slice(&len.to_be_bytes());
+        out.extend(std::iter::repeat_n(7, len as usize));
+        out
+    }
+
+    #[test]
+    fn frame_lengths_are_tracked_across_chunks() {
+        let mut frames = FrameLengths::default();
+        let mut stream = frame(3);
+        stream.extend(frame(10));
+        stream.extend(frame(4));
+        // Byte-at-a-time splits headers and payloads everywhere.
+        for byte in &stream[..stream.len() - 1] {
+            assert_eq!(frames.feed(std::slice::from_ref(byte), 10), Ok(()));
+        }
+        let mut frames = FrameLengths::default();
+        assert_eq!(frames.feed(&stream, 9), Err(10));
+    }
+}
@@ -0,0 +1,195 @@
+//! Connections from the Unix socket and the optional TLS listener, merged
+//! into one incoming stream so a single tonic server (and a single drain
+//! and shutdown path) serves both.
+
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use rustls::pki_types::CertificateDer;
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::unix::UCred;
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::{ReceiverStream, UnixListenerStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::transport::server::Connected;
+
+/// A TCP client that hasn't finished its TLS handshake by then is dropped.
+pub const TLS_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// An accepted client connection on either listener.
+#[allow(missing_debug_implementations)]
+pub enum ServerConn {
+    Unix(UnixStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+/// Source of [`ConnInfo::id`].
+static NEXT_CONN_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Per-connection details, available to handlers as a request extension.
+#[derive(Clone, Debug, Default)]
+pub struct ConnInfo {
+    /// Process-unique id of the connection, keying per-connection limits.
+    pub id: u64,
+    /// Peer address for TCP connections; `None` on the Unix socket.
+    pub remote_addr: Option<SocketAddr>,
+    /// Kernel-reported credentials (`SO_PEERCRED`) of a Unix socket peer.
+    pub peer_cred: Option<UCred>,
+    /// Certificate chain the client presented during an mTLS handshake,
+    /// leaf first.
+    pub client_certs: Option<Arc<Vec<CertificateDer<'static>>>>,
+}
+
+impl Connected for ServerConn {
+    type ConnectInfo = ConnInfo;
+
+    fn connect_info(&self) -> ConnInfo {
+        let id = NEXT_CONN_ID.fetch_add(1, Ordering::Relaxed);
+        match self {
+            Self::Unix(stream) => ConnInfo {
+                id,
+                peer_cred: stream.peer_cred().ok(),
+                ..ConnInfo::default()
+            },
+            Self::Tls(stream) => {
+                let (tcp, session) = stream.get_ref();
+                ConnInfo {
+                    id,
+                    remote_addr: tcp.peer_addr().ok(),
+                    peer_cred: None,
+                    client_certs: session.peer_certificates().map(|certs| {
+                        Arc::new(certs.iter().map(|c| c.clone().into_owned()).collect())
+                    }),
+                }
+            }
+        }
+    }
+}
+
+impl AsyncRead for ServerConn {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Unix(s) => Pin::new(s).poll_read(cx, buf),
+            Self::Tls(s) => Pin::new(s.as_mut()).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for ServerConn {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Unix(s) => Pin::new(s).poll_write(cx, buf),
+            Self::Tls(s) => Pin::new(s.as_mut()).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_write_vectored(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[io::IoSlice<'_>],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Self::Unix(s) => Pin::new(s).poll_write_vectored(cx, bufs),
+            Self::Tls(s) => Pin::new(s.as_mut()).poll_write_vectored(cx, bufs),
+        }
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        match self {
+            Self::Unix(s) => s.is_write_vectored(),
+            Self::Tls(s) => s.is_write_vectored(),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Unix(s) => Pin::new(s).poll_flush(cx),
+            Self::Tls(s) => Pin::new(s.as_mut()).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Self::Unix(s) => Pin::new(s).poll_shutdown(cx),
+            Self::Tls(s) => Pin::new(s.as_mut()).poll_shutdown(cx),
+        }
+    }
+}
+
+/// Connections accepted on the Unix socket.
+pub fn unix_incoming(listener: UnixListener) -> impl Stream<Item = io::Result<ServerConn>> {
+    UnixListenerStream::new(listener).map(|r| r.map(ServerConn::Unix))
+}
+
+/// Connections accepted on `listener` that completed a TLS handshake.
+///
+/// Handshakes run on their own tasks so a slow or hostile client can't
+/// stall the accept loop; failed handshakes are logged and never reach
+/// tonic. The accept loop stops once the returned stream is dropped.
+pub fn tls_incoming(
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+) -> impl Stream<Item = io::Result<ServerConn>> {
+    let (tx, rx) = tokio::sync::mpsc::channel(64);
+    tokio::spawn(async move {
+        loop {
+            let (tcp, peer) = tokio::select! {
+                () = tx.closed() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok(v) => v,
+                    Err(e) => {
+                        // EMFILE and friends: back off instead of spinning.
+                        log::warn!("TCP accept failed: {e}");
+                        tokio::time::sleep(Duration::from_millis(100)).await;
+                        continue;
+                    }
+                },
+            };
+            if let Err(e) = tcp.set_nodelay(true) {
+                log::debug!("set_nodelay failed for {peer}: {e}");
+            }
+            let acceptor = acceptor.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                match tokio::time::timeout(TLS_HANDSHAKE_TIMEOUT, acceptor.accept(tcp)).await {
+                    Ok(Ok(stream)) => {
+                        let conn = ServerConn::Tls(Box::new(stream));
+                        if tx.send(Ok(conn)).await.is_err() {
+                            log::debug!("server stopped; dropping TLS connection from {peer}");
+                        }
+                    }
+                    Ok(Err(e)) => log::warn!("TLS handshake with {peer} failed: {e}"),
+                    Err(_) => log::warn!("TLS handshake with {peer} timed out"),
+                }
+            });
+        }
+    });
+    ReceiverStream::new(rx)
+}
+
+/// The Unix socket plus, when configured, the TLS listener.
+pub fn incoming(
+    unix: UnixListener,
+    tls: Option<(TcpListener, TlsAcceptor)>,
+) -> impl Stream<Item = io::Result<ServerConn>> {
+    let tcp: Pin<Box<dyn Stream<Item = io::Result<ServerConn>> + Send>> = match tls {
+        Some((listener, acceptor)) => Box::pin(tls_incoming(listener, acceptor)),
+        None => Box::pin(tokio_stream::empty()),
+    };
+    unix_incoming(unix).merge(tcp)
+}
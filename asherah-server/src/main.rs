@@ -1,7 +1,13 @@
 use anyhow::{Context, Result};
+use asherah_server::health::ProbeSchedule;
+use asherah_server::limits::{LimitOptions, RateLimit};
+use asherah_server::reload::{Reloader, Settings};
+use asherah_server::tls::TlsOptions;
 use asherah_server::{parse_go_duration, proto};
 use clap::{Parser, ValueEnum};
-use std::sync::Arc;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
 use std::time::Duration;
 use tokio::sync::Mutex;
 use tokio::task::JoinSet;
@@ -48,6 +54,23 @@ impl KmsMode {
     }
 }
 
+/// How `--audit-log` records name partitions.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
+#[clap(rename_all = "kebab-case")]
+enum AuditPartitionIds {
+    Hash,
+    Raw,
+}
+
+impl From<AuditPartitionIds> for asherah_server::audit::PartitionIds {
+    fn from(mode: AuditPartitionIds) -> Self {
+        match mode {
+            AuditPartitionIds::Hash => Self::Hashed,
+            AuditPartitionIds::Raw => Self::Raw,
+        }
+    }
+}
+
 #[derive(Copy, Clone, Debug, PartialEq, Eq, ValueEnum)]
 #[clap(rename_all = "kebab-case")]
 enum ReplicaReadConsistency {
@@ -66,7 +89,7 @@ impl ReplicaReadConsistency {
     }
 }
 
-#[derive(Parser, Debug)]
+#[derive(Parser, Clone, Debug)]
 #[command(
     name = "asherah-server",
     about = "gRPC sidecar server for Asherah envelope encryption"
@@ -109,6 +132,124 @@ struct Cli {
     )]
     socket_mode: Option<u32>,
 
+    /// Optional TCP address (e.g. `0.0.0.0:8443`) to serve gRPC over TLS
+    /// in addition to the Unix socket, so one server can act as a shared
+    /// encryption tier for several hosts. Requires `--tls-cert-file` and
+    /// `--tls-key-file`; plaintext TCP is not offered. asherah-ffi
+    /// extension — the Go reference server listens on the socket only.
+    #[arg(long, env = "ASHERAH_LISTEN_ADDR")]
+    listen_addr: Option<String>,
+
+    /// PEM certificate chain (leaf first) for the TCP listener.
+    #[arg(long, env = "ASHERAH_TLS_CERT_FILE")]
+    tls_cert_file: Option<PathBuf>,
+
+    /// PEM private key for `--tls-cert-file`.
+    #[arg(long, env = "ASHERAH_TLS_KEY_FILE")]
+    tls_key_file: Option<PathBuf>,
+
+    /// PEM CA bundle for client certificates. When set, TCP clients must
+    /// present a certificate issued by one of these CAs (mutual TLS).
+    #[arg(long, env = "ASHERAH_TLS_CLIENT_CA_FILE")]
+    tls_client_ca_file: Option<PathBuf>,
+
+    /// Comma-separated subject alternative names; a client certificate must
+    /// carry at least one. DNS names and IPs match like server names
+    /// (certificate wildcards apply); `scheme://` entries such as SPIFFE
+    /// IDs match URI SANs exactly. Requires `--tls-client-ca-file`.
+    #[arg(long, env = "ASHERAH_TLS_ALLOWED_CLIENT_SANS", value_delimiter = ',')]
+    tls_allowed_client_sans: Vec<String>,
+
+    /// Optional TCP address (e.g. `127.0.0.1:9464`) serving `GET /metrics`
+    /// as OpenMetrics text: encrypt/decrypt and metastore latency
+    /// histograms, key cache counters, decrypt recovery counts and per-RPC
+    /// status codes. Plain HTTP; bind it to a private interface.
+    /// asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_METRICS_LISTEN")]
+    metrics_listen: Option<String>,
+
+    /// JSON policy mapping callers (Unix peer uid/gid, mTLS client SANs)
+    /// to the partition ID patterns and operations they may use. When set,
+    /// anything the policy doesn't grant is refused. Re-read on SIGHUP; a
+    /// policy that fails to parse leaves the previous one in force.
+    /// asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_AUTHZ_POLICY_FILE")]
+    authz_policy_file: Option<PathBuf>,
+
+    /// Write a JSON line per encrypt, decrypt and GetSession to this file
+    /// (`-` for stdout): caller, partition, intermediate key, plaintext
+    /// size, latency and result code, never the data. The file is appended
+    /// to and reopened on SIGHUP for rotation. Off by default.
+    /// asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_AUDIT_LOG")]
+    audit_log: Option<String>,
+
+    /// How audit records name partitions: `hash` (SHA-256 of the partition
+    /// ID and of the intermediate key ID, which embeds it) or `raw`.
+    #[arg(
+        long,
+        env = "ASHERAH_AUDIT_PARTITION_IDS",
+        value_enum,
+        default_value = "hash"
+    )]
+    audit_partition_ids: AuditPartitionIds,
+
+    /// Encrypt/decrypt operations per second allowed under any one
+    /// partition ID, summed over all clients. Off by default. Refused
+    /// operations get RESOURCE_EXHAUSTED. asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_PARTITION_RATE_LIMIT")]
+    partition_rate_limit: Option<f64>,
+
+    /// Operations a partition may run back to back above its rate limit.
+    /// Defaults to one second's worth.
+    #[arg(long, env = "ASHERAH_PARTITION_RATE_BURST")]
+    partition_rate_burst: Option<u32>,
+
+    /// Encrypt/decrypt operations per second allowed on any one client
+    /// connection, over all its partitions and streams. Off by default.
+    /// asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_CONNECTION_RATE_LIMIT")]
+    connection_rate_limit: Option<f64>,
+
+    /// Operations a connection may run back to back above its rate limit.
+    /// Defaults to one second's worth.
+    #[arg(long, env = "ASHERAH_CONNECTION_RATE_BURST")]
+    connection_rate_burst: Option<u32>,
+
+    /// Maximum `Session` streams open at once across all clients; further
+    /// streams are refused with RESOURCE_EXHAUSTED. Unlimited by default.
+    /// asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_MAX_CONCURRENT_STREAMS")]
+    max_concurrent_streams: Option<usize>,
+
+    /// Largest gRPC request message accepted, in bytes. Larger messages are
+    /// refused with RESOURCE_EXHAUSTED before they are read. Defaults to
+    /// (and may not exceed) the envelope size limit, 65 MiB.
+    #[arg(
+        long,
+        env = "ASHERAH_MAX_REQUEST_BYTES",
+        default_value_t = asherah::limits::MAX_ENVELOPE_BYTES
+    )]
+    max_request_bytes: usize,
+
+    /// Optional HTTP/1.1 listener for `POST /v1/partitions/{id}/encrypt`
+    /// and `/decrypt`, exchanging Go-compatible DataRowRecord JSON. Either
+    /// `unix:/path/to/socket` or a TCP address (e.g. `0.0.0.0:8080`); TCP is
+    /// served over TLS with the `--tls-*` flags. Shares the authorization
+    /// policy and limits with gRPC. asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_HTTP_LISTEN")]
+    http_listen: Option<String>,
+
+    /// JSON file of settings that override the flags and env vars and are
+    /// re-read on SIGHUP: any `ConfigOptions` key the language bindings
+    /// accept (`SessionCacheMaxSize`, `CheckInterval`, `Verbose`, ...) plus
+    /// the limits (`PartitionRateLimit`, `PartitionRateBurst`,
+    /// `ConnectionRateLimit`, `ConnectionRateBurst`, `MaxConcurrentStreams`,
+    /// `MaxRequestBytes`). A reload that would change the service, product,
+    /// region suffix, metastore or KMS is refused. asherah-ffi extension.
+    #[arg(long, env = "ASHERAH_CONFIG_FILE")]
+    config_file: Option<PathBuf>,
+
     /// The name of this service
     #[arg(long, env = "ASHERAH_SERVICE_NAME")]
     service: String,
@@ -188,6 +329,25 @@ struct Cli {
     #[arg(long, env = "ASHERAH_ENABLE_REGION_SUFFIX")]
     enable_region_suffix: bool,
 
+    /// Comma-separated region suffixes to try when a decrypt can't find the
+    /// row's intermediate key under its recorded id (data written in another
+    /// region, or before region suffixing was enabled). The unsuffixed id is
+    /// always tried. Entries from the `RECOVERY_REGION_SUFFIXES` env var the
+    /// bindings read are appended.
+    #[arg(long, env = "ASHERAH_RECOVERY_REGION_SUFFIXES", value_delimiter = ',')]
+    recovery_region_suffixes: Vec<String>,
+
+    /// After a recovered decrypt, copy the key to the id the row names so
+    /// later reads take the fast path (best-effort, insert-if-absent).
+    /// `SELF_HEAL_RECOVERED_KEYS`, as honored by the bindings, overrides it.
+    #[arg(
+        long,
+        action = clap::ArgAction::Set,
+        default_value_t = true,
+        env = "ASHERAH_SELF_HEAL_RECOVERED_KEYS"
+    )]
+    self_heal_recovered_keys: bool,
+
     /// Continue startup after a conflicting persisted configuration drift guard is detected.
     /// The guard is not rewritten.
     #[arg(long, env = "ASHERAH_CONFIG_DRIFT_FORCE_RUN")]
@@ -230,6 +390,26 @@ struct Cli {
         env = "ASHERAH_SHUTDOWN_DRAIN_TIMEOUT"
     )]
     shutdown_drain_timeout: i64,
+
+    /// How often to probe the metastore (`load_latest` of the system key)
+    /// and KMS (encrypt/decrypt round trip) for `grpc.health.v1.Health`.
+    /// Health RPCs answer from the result of the last probe.
+    #[arg(
+        long,
+        value_parser = parse_go_duration,
+        default_value = "10s",
+        env = "ASHERAH_HEALTH_CHECK_INTERVAL"
+    )]
+    health_check_interval: i64,
+
+    /// Deadline for one health probe; a slower probe reports NOT_SERVING.
+    #[arg(
+        long,
+        value_parser = parse_go_duration,
+        default_value = "5s",
+        env = "ASHERAH_HEALTH_CHECK_TIMEOUT"
+    )]
+    health_check_timeout: i64,
 }
 
 /// Default socket path, matching the Go reference server's
@@ -352,6 +532,186 @@ fn bind_unix_listener(
     tokio::net::UnixListener::bind(socket_path).context("failed to bind Unix socket")
 }
 
+/// Remove a socket file left by a previous run. Anything other than a
+/// socket at `path` is an error rather than something to delete.
+fn remove_stale_socket(path: &str) -> Result<()> {
+    if let Ok(meta) = std::fs::symlink_metadata(path) {
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::FileTypeExt;
+            if meta.file_type().is_socket() {
+                if let Err(err) = std::fs::remove_file(path) {
+                    log::warn!("failed to remove stale socket file '{path}': {err}");
+                }
+            } else {
+                anyhow::bail!("socket path '{path}' exists but is not a Unix socket");
+            }
+        }
+        #[cfg(not(unix))]
+        drop(std::fs::remove_file(path));
+    }
+    Ok(())
+}
+
+/// Remove our socket file on shutdown, but only if it's still a socket
+/// (it could have been replaced during runtime).
+fn remove_socket(path: &str) {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::FileTypeExt;
+        if std::fs::symlink_metadata(path)
+            .map(|m| m.file_type().is_socket())
+            .unwrap_or(false)
+        {
+            if let Err(err) = std::fs::remove_file(path) {
+                log::warn!("failed to remove socket file '{path}' during shutdown: {err}");
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    drop(std::fs::remove_file(path));
+}
+
+/// How TCP clients authenticate, for the listening log line.
+fn client_auth(opts: &TlsOptions) -> String {
+    match (&opts.client_ca_file, opts.allowed_client_sans.len()) {
+        (None, _) => "no client certificates".to_string(),
+        (Some(_), 0) => "client certificates required".to_string(),
+        (Some(_), n) => format!("client certificates required, {n} allowed SAN(s)"),
+    }
+}
+
+type GatewayIncoming = std::pin::Pin<
+    Box<
+        dyn tokio_stream::Stream<Item = std::io::Result<asherah_server::listener::ServerConn>>
+            + Send,
+    >,
+>;
+
+/// Where `--http-listen` serves the HTTP gateway.
+#[derive(Debug, PartialEq, Eq)]
+enum HttpListen {
+    Unix(String),
+    Tcp(String),
+}
+
+fn http_listen(cli: &Cli) -> Option<HttpListen> {
+    let addr = cli
+        .http_listen
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())?;
+    Some(match addr.strip_prefix("unix:") {
+        Some(path) => HttpListen::Unix(path.to_string()),
+        None => HttpListen::Tcp(addr.to_string()),
+    })
+}
+
+/// Validate the TLS flags, which `--listen-addr` and a TCP `--http-listen`
+/// share. `None` when neither TCP listener is configured.
+fn tls_options(cli: &Cli) -> Result<Option<TlsOptions>> {
+    let grpc_tcp = listen_addr(cli).is_some();
+    let http_tcp = matches!(http_listen(cli), Some(HttpListen::Tcp(_)));
+    let allowed_client_sans: Vec<String> = cli
+        .tls_allowed_client_sans
+        .iter()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect();
+    if !grpc_tcp && !http_tcp {
+        if cli.tls_cert_file.is_some()
+            || cli.tls_key_file.is_some()
+            || cli.tls_client_ca_file.is_some()
+            || !allowed_client_sans.is_empty()
+        {
+            anyhow::bail!(
+                "TLS options are set but neither --listen-addr nor a TCP --http-listen is"
+            );
+        }
+        return Ok(None);
+    }
+    let (Some(cert_file), Some(key_file)) = (&cli.tls_cert_file, &cli.tls_key_file) else {
+        let flag = if grpc_tcp {
+            "--listen-addr"
+        } else {
+            "a TCP --http-listen"
+        };
+        anyhow::bail!("{flag} requires --tls-cert-file and --tls-key-file");
+    };
+    Ok(Some(TlsOptions {
+        cert_file: cert_file.clone(),
+        key_file: key_file.clone(),
+        client_ca_file: cli.tls_client_ca_file.clone(),
+        allowed_client_sans,
+    }))
+}
+
+/// The gRPC TCP listener address, if `--listen-addr` is set.
+fn listen_addr(cli: &Cli) -> Option<&str> {
+    cli.listen_addr
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+}
+
+/// Health probe cadence from the CLI; both durations must be positive.
+fn probe_schedule(cli: &Cli) -> Result<ProbeSchedule> {
+    let positive = |flag: &str, seconds: i64| match u64::try_from(seconds) {
+        Ok(s) if s > 0 => Ok(Duration::from_secs(s)),
+        _ => Err(anyhow::anyhow!("{flag} must be positive, got {seconds}s")),
+    };
+    Ok(ProbeSchedule {
+        interval: positive("--health-check-interval", cli.health_check_interval)?,
+        timeout: positive("--health-check-timeout", cli.health_check_timeout)?,
+    })
+}
+
+/// Admission limits from the CLI. A burst without its rate is an error, as
+/// is a non-positive rate or a zero cap.
+fn limit_options(cli: &Cli) -> Result<LimitOptions> {
+    fn rate(flag: &str, per_second: Option<f64>, burst: Option<u32>) -> Result<Option<RateLimit>> {
+        let Some(per_second) = per_second else {
+            if burst.is_some() {
+                anyhow::bail!("{flag}-burst requires {flag}");
+            }
+            return Ok(None);
+        };
+        if !per_second.is_finite() || per_second <= 0.0 {
+            anyhow::bail!("{flag} must be positive, got {per_second}");
+        }
+        // One second's worth of operations, and at least one.
+        let burst = burst.unwrap_or_else(|| per_second.ceil().min(f64::from(u32::MAX)) as u32);
+        if burst == 0 {
+            anyhow::bail!("{flag}-burst must be positive");
+        }
+        Ok(Some(RateLimit { per_second, burst }))
+    }
+    if cli.max_concurrent_streams == Some(0) {
+        anyhow::bail!("--max-concurrent-streams must be positive");
+    }
+    let max = asherah::limits::MAX_ENVELOPE_BYTES;
+    if cli.max_request_bytes == 0 || cli.max_request_bytes > max {
+        anyhow::bail!(
+            "--max-request-bytes must be between 1 and {max}, got {}",
+            cli.max_request_bytes
+        );
+    }
+    Ok(LimitOptions {
+        partition: rate(
+            "--partition-rate",
+            cli.partition_rate_limit,
+            cli.partition_rate_burst,
+        )?,
+        connection: rate(
+            "--connection-rate",
+            cli.connection_rate_limit,
+            cli.connection_rate_burst,
+        )?,
+        max_streams: cli.max_concurrent_streams,
+        max_request_bytes: cli.max_request_bytes,
+    })
+}
+
 /// Parse region map from Go-style `REGION1=ARN1[,REGION2=ARN2]` or JSON format.
 fn parse_region_map(s: &str) -> Option<std::collections::HashMap<String, String>> {
     let trimmed = s.trim();
@@ -410,6 +770,8 @@ fn cli_to_config(cli: &Cli) -> asherah_config::ConfigOptions {
         dynamo_db_table_name: cli.dynamodb_table_name.clone(),
         replica_read_consistency: cli.replica_read_consistency.map(|m| m.as_str().to_string()),
         enable_region_suffix: Some(cli.enable_region_suffix),
+        recovery_region_suffixes: Some(cli.recovery_region_suffixes.clone()),
+        self_heal_recovered_keys: Some(cli.self_heal_recovered_keys),
         config_drift_force_run: Some(cli.config_drift_force_run),
         config_drift_force_update: Some(cli.config_drift_force_update),
         verbose: Some(cli.verbose),
@@ -417,6 +779,131 @@ fn cli_to_config(cli: &Cli) -> asherah_config::ConfigOptions {
     }
 }
 
+/// The limit keys `--config-file` accepts next to `ConfigOptions`, each
+/// overriding its flag.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+struct FileLimits {
+    #[serde(rename = "PartitionRateLimit")]
+    partition_rate_limit: Option<f64>,
+    #[serde(rename = "PartitionRateBurst")]
+    partition_rate_burst: Option<u32>,
+    #[serde(rename = "ConnectionRateLimit")]
+    connection_rate_limit: Option<f64>,
+    #[serde(rename = "ConnectionRateBurst")]
+    connection_rate_burst: Option<u32>,
+    #[serde(rename = "MaxConcurrentStreams")]
+    max_concurrent_streams: Option<usize>,
+    #[serde(rename = "MaxRequestBytes")]
+    max_request_bytes: Option<usize>,
+}
+
+const FILE_LIMIT_KEYS: [&str; 6] = [
+    "PartitionRateLimit",
+    "PartitionRateBurst",
+    "ConnectionRateLimit",
+    "ConnectionRateBurst",
+    "MaxConcurrentStreams",
+    "MaxRequestBytes",
+];
+
+/// The reloadable settings: the flags and env vars, overridden by
+/// `--config-file` when set. Also returns the file's unrecognized keys,
+/// which are ignored.
+fn settings(cli: &Cli) -> Result<(Settings, Vec<String>)> {
+    let Some(path) = &cli.config_file else {
+        let settings = Settings {
+            options: cli_to_config(cli),
+            limits: limit_options(cli)?,
+        };
+        return Ok((settings, Vec::new()));
+    };
+    let json = std::fs::read_to_string(path)
+        .with_context(|| format!("failed to read config file {}", path.display()))?;
+    settings_with_file(cli, &json)
+        .with_context(|| format!("invalid config file {}", path.display()))
+}
+
+fn settings_with_file(cli: &Cli, json: &str) -> Result<(Settings, Vec<String>)> {
+    let file: FileLimits = serde_json::from_str(json)?;
+    let mut cli = cli.clone();
+    cli.partition_rate_limit = file.partition_rate_limit.or(cli.partition_rate_limit);
+    cli.partition_rate_burst = file.partition_rate_burst.or(cli.partition_rate_burst);
+    cli.connection_rate_limit = file.connection_rate_limit.or(cli.connection_rate_limit);
+    cli.connection_rate_burst = file.connection_rate_burst.or(cli.connection_rate_burst);
+    cli.max_concurrent_streams = file.max_concurrent_streams.or(cli.max_concurrent_streams);
+    cli.max_request_bytes = file.max_request_bytes.unwrap_or(cli.max_request_bytes);
+    let limits = limit_options(&cli)?;
+
+    let mut options = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(json)?;
+    options.retain(|key, _| !FILE_LIMIT_KEYS.contains(&key.as_str()));
+    let options = cli_to_config(&cli).overlay_json(&serde_json::to_string(&options)?)?;
+    let known = serde_json::to_value(&options)?;
+    let ignored = serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(json)?
+        .into_iter()
+        .map(|(key, _)| key)
+        .filter(|key| !FILE_LIMIT_KEYS.contains(&key.as_str()) && known.get(key).is_none())
+        .collect();
+    Ok((Settings { options, limits }, ignored))
+}
+
+/// Logs through one of two `env_logger` configurations, so a reload can
+/// switch `Verbose` without a restart.
+struct SwitchLogger {
+    verbose: AtomicBool,
+    quiet: env_logger::Logger,
+    loud: env_logger::Logger,
+}
+
+impl SwitchLogger {
+    fn current(&self) -> &env_logger::Logger {
+        if self.verbose.load(Ordering::Relaxed) {
+            &self.loud
+        } else {
+            &self.quiet
+        }
+    }
+}
+
+impl log::Log for SwitchLogger {
+    fn enabled(&self, metadata: &log::Metadata<'_>) -> bool {
+        self.current().enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        self.current().log(record);
+    }
+
+    fn flush(&self) {
+        self.current().flush();
+    }
+}
+
+static LOGGER: OnceLock<SwitchLogger> = OnceLock::new();
+
+/// Install the logger; the returned switch selects verbose output.
+fn init_logging(verbose: bool) -> &'static AtomicBool {
+    let logger = LOGGER.get_or_init(|| SwitchLogger {
+        verbose: AtomicBool::new(verbose),
+        // Drop-in compatibility with the Go reference: ASHERAH_VERBOSE is
+        // the primary logging knob and the Go server has no RUST_LOG
+        // analog. When verbose is set we override RUST_LOG entirely so a
+        // consumer-supplied restrictive RUST_LOG can't silence the
+        // asherah-crate debug stream they explicitly asked for. When unset
+        // we keep RUST_LOG honored (a power-user knob beyond Go parity)
+        // with `info` as the default.
+        quiet: env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
+            .build(),
+        loud: env_logger::Builder::new()
+            .parse_filters("info,asherah=debug,asherah_server=debug")
+            .build(),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.quiet.filter().max(logger.loud.filter()));
+    }
+    &logger.verbose
+}
+
 fn warn_cpu_vulnerability_status() {
     match asherah::microarchitecture::host_cpu_vulnerabilities_requiring_attention() {
         Ok(statuses) => {
@@ -441,30 +928,79 @@ async fn main() -> Result<()> {
 
     let cli = Cli::parse();
 
-    // Drop-in compatibility with the Go reference: ASHERAH_VERBOSE is the
-    // primary logging knob and the Go server has no RUST_LOG analog. When
-    // verbose is set we override RUST_LOG entirely so a consumer-supplied
-    // restrictive RUST_LOG can't silence the asherah-crate debug stream
-    // they explicitly asked for. When unset we keep RUST_LOG honored (a
-    // power-user knob beyond Go parity) with `info` as the default.
-    if cli.verbose {
-        env_logger::Builder::new()
-            .parse_filters("info,asherah=debug,asherah_server=debug")
-            .init();
-    } else {
-        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    // The config file can set `Verbose`, so it's read before logging
+    // starts.
+    let (initial, ignored) = settings(&cli)?;
+    let verbose = init_logging(initial.options.verbose.unwrap_or(false));
+    for key in ignored {
+        log::warn!("config file: ignoring unknown key {key:?}");
     }
     warn_cpu_vulnerability_status();
 
     let socket_path = resolve_socket_path(cli.socket_file.as_deref(), cli.socket.as_deref());
+    let health_schedule = probe_schedule(&cli)?;
+
+    // Load certificates before the (slow) factory init so a bad TLS setup
+    // fails fast.
+    let tls = match tls_options(&cli)? {
+        Some(opts) => {
+            let config = asherah_server::tls::server_config(&opts)
+                .context("failed to configure TLS listener")?;
+            Some((opts, config))
+        }
+        None => None,
+    };
+
+    let metrics = match cli.metrics_listen.as_deref() {
+        Some(addr) => {
+            let listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("failed to bind metrics listener on {addr}"))?;
+            log::info!(
+                "serving metrics on http://{}/metrics",
+                listener.local_addr()?
+            );
+            let metrics = Arc::new(asherah_server::metrics::Metrics::new());
+            metrics.install_sink();
+            Some((listener, metrics))
+        }
+        None => None,
+    };
+
+    let authorizer = match &cli.authz_policy_file {
+        Some(path) => {
+            let denials = metrics.as_ref().map(|(_, m)| Arc::clone(m));
+            let authorizer = asherah_server::authz::Authorizer::load(path, denials)
+                .context("failed to load authorization policy")?;
+            Some(Arc::new(authorizer))
+        }
+        None => None,
+    };
+
+    let audit = match cli.audit_log.as_deref() {
+        Some(target) => {
+            let audit = asherah_server::audit::AuditLog::open(
+                &asherah_server::audit::Target::parse(target),
+                cli.audit_partition_ids.into(),
+            )
+            .context("failed to open audit log")?;
+            Some(Arc::new(audit))
+        }
+        None => None,
+    };
+
+    let limiter = Arc::new(asherah_server::limits::Limiter::new(
+        &initial.limits,
+        metrics.as_ref().map(|(_, m)| Arc::clone(m)),
+    ));
 
     // Build the factory on the blocking pool. Construction may do DNS,
     // TLS handshakes, and KMS warm-up (synchronous AWS SDK init), all of
     // which would otherwise hold the Tokio main thread (T8 in
     // docs/review-2026-05-05-findings.md).
-    let config = cli_to_config(&cli);
+    let config = initial.options.clone();
     let (factory, _applied) =
-        tokio::task::spawn_blocking(move || asherah_config::factory_from_config(&config))
+        tokio::task::spawn_blocking(move || asherah_server::service::factory_from_config(&config))
             .await
             .context("factory init task panicked")?
             .context("failed to initialize Asherah")?;
@@ -476,41 +1012,94 @@ async fn main() -> Result<()> {
     // memguard-locked pages and skipping IK-cache cleanup.
     let session_tasks: Arc<Mutex<JoinSet<()>>> = Arc::new(Mutex::new(JoinSet::new()));
     let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
-    let svc = asherah_server::service::AppEncryptionService::with_lifecycle(
+    let mut svc = asherah_server::service::AppEncryptionService::with_lifecycle(
         factory,
         shutdown_rx.clone(),
         session_tasks.clone(),
     );
-    let grpc_svc = proto::app_encryption_server::AppEncryptionServer::new(svc)
+    if let Some(authorizer) = &authorizer {
+        svc = svc.with_authorizer(Arc::clone(authorizer));
+    }
+    if let Some(audit) = &audit {
+        svc = svc.with_audit_log(Arc::clone(audit));
+    }
+    let svc = Arc::new(svc.with_limiter(Arc::clone(&limiter)));
+    // Health answers from a cached probe result refreshed in the
+    // background; it flips to NOT_SERVING as soon as shutdown starts.
+    let probe_svc = Arc::clone(&svc);
+    let (health_svc, _health_task) = asherah_server::health::spawn_checker(
+        move || asherah_server::health::probe_factory(&probe_svc.factory()),
+        health_schedule,
+        shutdown_rx.clone(),
+    );
+    let (reflection_v1, reflection_v1alpha) =
+        asherah_server::reflection_services().context("failed to build gRPC reflection service")?;
+    let grpc_svc = proto::app_encryption_server::AppEncryptionServer::from_arc(Arc::clone(&svc))
         .max_decoding_message_size(asherah::limits::MAX_ENVELOPE_BYTES)
         .max_encoding_message_size(asherah::limits::MAX_ENVELOPE_BYTES);
 
-    // Remove stale socket file from previous run (only if it's a socket)
-    if let Ok(meta) = std::fs::symlink_metadata(&socket_path) {
-        #[cfg(unix)]
-        {
-            use std::os::unix::fs::FileTypeExt;
-            if meta.file_type().is_socket() {
-                if let Err(err) = std::fs::remove_file(&socket_path) {
-                    log::warn!("failed to remove stale socket file '{socket_path}': {err}");
-                }
-            } else {
-                anyhow::bail!("socket path '{socket_path}' exists but is not a Unix socket");
-            }
-        }
-        #[cfg(not(unix))]
-        drop(std::fs::remove_file(&socket_path));
-    }
-
+    remove_stale_socket(&socket_path)?;
     let listener = bind_unix_listener(&socket_path, cli.socket_mode)?;
 
-    let incoming = tokio_stream::wrappers::UnixListenerStream::new(listener);
-
     match cli.socket_mode {
         Some(mode) => log::info!("listening on {socket_path} (mode {mode:#o})"),
         None => log::info!("listening on {socket_path} (mode inherited from umask)"),
     }
 
+    let tcp = match (listen_addr(&cli), &tls) {
+        (Some(addr), Some((opts, config))) => {
+            let tcp_listener = tokio::net::TcpListener::bind(addr)
+                .await
+                .with_context(|| format!("failed to bind TCP listener on {addr}"))?;
+            log::info!(
+                "listening on {} (TLS, {})",
+                tcp_listener.local_addr()?,
+                client_auth(opts)
+            );
+            Some((
+                tcp_listener,
+                tokio_rustls::TlsAcceptor::from(Arc::clone(config)),
+            ))
+        }
+        _ => None,
+    };
+
+    let http_socket = match http_listen(&cli) {
+        Some(HttpListen::Unix(path)) => Some(path),
+        _ => None,
+    };
+    let gateway_incoming: Option<GatewayIncoming> = match (http_listen(&cli), tls) {
+        (Some(HttpListen::Unix(path)), _) => {
+            remove_stale_socket(&path)?;
+            let listener = bind_unix_listener(&path, cli.socket_mode)
+                .context("failed to bind HTTP gateway socket")?;
+            log::info!("serving HTTP gateway on unix:{path}");
+            Some(Box::pin(asherah_server::listener::unix_incoming(listener)))
+        }
+        (Some(HttpListen::Tcp(addr)), Some((opts, config))) => {
+            let tcp_listener = tokio::net::TcpListener::bind(&addr)
+                .await
+                .with_context(|| format!("failed to bind HTTP gateway listener on {addr}"))?;
+            log::info!(
+                "serving HTTP gateway on https://{} ({})",
+                tcp_listener.local_addr()?,
+                client_auth(&opts)
+            );
+            // Same certificates and client verification as gRPC, but
+            // negotiating HTTP/1.1 instead of h2.
+            let mut config = (*config).clone();
+            config.alpn_protocols = vec![b"http/1.1".to_vec()];
+            let acceptor = tokio_rustls::TlsAcceptor::from(Arc::new(config));
+            Some(Box::pin(asherah_server::listener::tls_incoming(
+                tcp_listener,
+                acceptor,
+            )))
+        }
+        _ => None,
+    };
+
+    let incoming = asherah_server::listener::incoming(listener, tcp);
+
     // Spawn a task that listens for SIGTERM/SIGINT and broadcasts shutdown.
     // shutdown_signal returns a Result so signal-registration failures (a
     // seccomp-restricted host or a runtime that already owns the signal
@@ -526,9 +1115,51 @@ async fn main() -> Result<()> {
         let _ = shutdown_tx.send(true);
     });
 
+    let reloader = cli.config_file.is_some().then(|| {
+        let reloader = Reloader::new(initial, Arc::clone(&svc), Arc::clone(&limiter))
+            .on_verbose(|on| verbose.store(on, Ordering::Relaxed));
+        (cli.clone(), reloader)
+    });
+    if authorizer.is_some() || reloader.is_some() || audit.is_some() {
+        let hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+            .context("failed to register SIGHUP handler")?;
+        tokio::spawn(reload_on_sighup(
+            hangup,
+            authorizer,
+            reloader,
+            audit.clone(),
+            shutdown_rx.clone(),
+        ));
+    }
+
+    let rpc_metrics = match metrics {
+        Some((listener, metrics)) => {
+            tokio::spawn(asherah_server::metrics::serve(
+                listener,
+                Arc::clone(&metrics),
+                shutdown_rx.clone(),
+            ));
+            Some(metrics)
+        }
+        None => None,
+    };
+
+    let gateway = gateway_incoming.map(|incoming| {
+        tokio::spawn(asherah_server::gateway::serve(
+            incoming,
+            Arc::clone(&svc),
+            shutdown_rx.clone(),
+        ))
+    });
+
     let mut server_shutdown_rx = shutdown_rx.clone();
     let server = Server::builder()
+        .layer(asherah_server::metrics::RpcMetricsLayer::new(rpc_metrics))
+        .layer(asherah_server::limits::RequestSizeLayer::new(limiter))
         .add_service(grpc_svc)
+        .add_service(health_svc)
+        .add_service(reflection_v1)
+        .add_service(reflection_v1alpha)
         .serve_with_incoming_shutdown(incoming, async move {
             // changed() returns Err(RecvError) only when shutdown_tx is
             // dropped without sending — i.e. the spawned signal task
@@ -601,6 +1232,16 @@ async fn main() -> Result<()> {
         }
     }
 
+    if let Some(gateway) = gateway {
+        // Open HTTP connections finish their current request and close.
+        if tokio::time::timeout_at(drain_deadline, gateway)
+            .await
+            .is_err()
+        {
+            log::warn!("HTTP gateway didn't finish draining within {drain_timeout:?}");
+        }
+    }
+
     {
         let mut set = session_tasks.lock().await;
         while !set.is_empty() {
@@ -625,23 +1266,15 @@ async fn main() -> Result<()> {
         }
     }
     drop(shutdown_rx);
+    if let Some(audit) = audit {
+        audit.close();
+    }
 
     log::info!("shutting down");
-    // Only remove if it's still a socket (could have been replaced during runtime)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::FileTypeExt;
-        if std::fs::symlink_metadata(&socket_path)
-            .map(|m| m.file_type().is_socket())
-            .unwrap_or(false)
-        {
-            if let Err(err) = std::fs::remove_file(&socket_path) {
-                log::warn!("failed to remove socket file '{socket_path}' during shutdown: {err}");
-            }
-        }
+    remove_socket(&socket_path);
+    if let Some(path) = http_socket {
+        remove_socket(&path);
     }
-    #[cfg(not(unix))]
-    drop(std::fs::remove_file(&socket_path));
 
     Ok(())
 }
@@ -659,6 +1292,58 @@ async fn shutdown_signal() -> std::io::Result<()> {
     Ok(())
 }
 
+/// Re-read the authorization policy and the config file and reopen the
+/// audit log on every SIGHUP until shutdown. A policy or configuration that
+/// fails to load or apply is logged and the previous one stays in force.
+async fn reload_on_sighup(
+    mut hangup: tokio::signal::unix::Signal,
+    authorizer: Option<Arc<asherah_server::authz::Authorizer>>,
+    mut reloader: Option<(Cli, Reloader)>,
+    audit: Option<Arc<asherah_server::audit::AuditLog>>,
+    mut shutdown_rx: tokio::sync::watch::Receiver<bool>,
+) {
+    loop {
+        tokio::select! {
+            _ = shutdown_rx.changed() => break,
+            received = hangup.recv() => {
+                if received.is_none() {
+                    break;
+                }
+                if let Some(authorizer) = &authorizer {
+                    if let Err(e) = authorizer.reload() {
+                        log::error!(
+                            "keeping the previous authorization policy; reloading {} failed: {e:#}",
+                            authorizer.path().display()
+                        );
+                    }
+                }
+                if let Some((cli, reloader)) = &mut reloader {
+                    reload_config(cli, reloader).await;
+                }
+                if let Some(audit) = &audit {
+                    audit.reopen();
+                }
+            }
+        }
+    }
+}
+
+async fn reload_config(cli: &Cli, reloader: &mut Reloader) {
+    let applied = match settings(cli) {
+        Ok((next, ignored)) => {
+            for key in ignored {
+                log::warn!("config file: ignoring unknown key {key:?}");
+            }
+            reloader.apply(next).await
+        }
+        Err(e) => Err(e),
+    };
+    match applied {
+        Ok(reloaded) => log::info!("configuration reloaded: {reloaded}"),
+        Err(e) => log::error!("keeping the running configuration; reload failed: {e:#}"),
+    }
+}
+
 #[cfg(test)]
 #[allow(clippy::panic, clippy::unwrap_used)]
 mod tests {
@@ -737,6 +1422,242 @@ mod tests {
         );
     }
 
+    fn parse_cli(extra: &[&str]) -> Cli {
+        let base = [
+            "asherah-server",
+            "--service",
+            "svc",
+            "--product",
+            "prod",
+            "--metastore",
+            "memory",
+        ];
+        Cli::try_parse_from(base.iter().chain(extra)).unwrap()
+    }
+
+    #[test]
+    fn tls_listener_is_off_by_default() {
+        let cli = parse_cli(&[]);
+        assert!(listen_addr(&cli).is_none());
+        assert!(tls_options(&cli).unwrap().is_none());
+    }
+
+    #[test]
+    fn tls_listener_collects_options() {
+        let cli = parse_cli(&[
+            "--listen-addr",
+            "0.0.0.0:8443",
+            "--tls-cert-file",
+            "/etc/asherah/tls.pem",
+            "--tls-key-file",
+            "/etc/asherah/tls.key",
+            "--tls-client-ca-file",
+            "/etc/asherah/clients.pem",
+            "--tls-allowed-client-sans",
+            "billing.internal, spiffe://example.test/billing",
+        ]);
+        assert_eq!(listen_addr(&cli), Some("0.0.0.0:8443"));
+        let opts = tls_options(&cli).unwrap().unwrap();
+        assert_eq!(opts.key_file, PathBuf::from("/etc/asherah/tls.key"));
+        assert_eq!(
+            opts.allowed_client_sans,
+            vec!["billing.internal", "spiffe://example.test/billing"]
+        );
+    }
+
+    #[test]
+    fn tls_listener_rejects_incomplete_flags() {
+        let no_key = parse_cli(&[
+            "--listen-addr",
+            "0.0.0.0:8443",
+            "--tls-cert-file",
+            "/etc/asherah/tls.pem",
+        ]);
+        assert!(tls_options(&no_key).is_err());
+
+        let no_addr = parse_cli(&["--tls-cert-file", "/etc/asherah/tls.pem"]);
+        assert!(tls_options(&no_addr).is_err());
+    }
+
+    #[test]
+    fn http_listen_takes_unix_sockets_or_tls_addresses() {
+        assert!(http_listen(&parse_cli(&[])).is_none());
+        let unix = parse_cli(&["--http-listen", "unix:/run/asherah/http.sock"]);
+        assert_eq!(
+            http_listen(&unix),
+            Some(HttpListen::Unix("/run/asherah/http.sock".to_string()))
+        );
+        // A Unix gateway needs no certificates.
+        assert!(tls_options(&unix).unwrap().is_none());
+
+        let tcp = parse_cli(&["--http-listen", "0.0.0.0:8080"]);
+        assert_eq!(
+            http_listen(&tcp),
+            Some(HttpListen::Tcp("0.0.0.0:8080".to_string()))
+        );
+        assert!(tls_options(&tcp).is_err());
+        let tcp_tls = parse_cli(&[
+            "--http-listen",
+            "0.0.0.0:8080",
+            "--tls-cert-file",
+            "/etc/asherah/tls.pem",
+            "--tls-key-file",
+            "/etc/asherah/tls.key",
+        ]);
+        assert!(tls_options(&tcp_tls).unwrap().is_some());
+        // Certificates for the gateway alone don't open a gRPC TCP listener.
+        assert!(listen_addr(&tcp_tls).is_none());
+    }
+
+    #[test]
+    fn probe_schedule_defaults_and_overrides() {
+        let defaults = probe_schedule(&parse_cli(&[])).unwrap();
+        assert_eq!(defaults.interval, Duration::from_secs(10));
+        assert_eq!(defaults.timeout, Duration::from_secs(5));
+
+        let custom = parse_cli(&[
+            "--health-check-interval",
+            "1m",
+            "--health-check-timeout",
+            "2s",
+        ]);
+        let custom = probe_schedule(&custom).unwrap();
+        assert_eq!(custom.interval, Duration::from_secs(60));
+        assert_eq!(custom.timeout, Duration::from_secs(2));
+
+        assert!(probe_schedule(&parse_cli(&["--health-check-interval", "0"])).is_err());
+    }
+
+    #[test]
+    fn limit_options_default_to_unlimited() {
+        assert_eq!(
+            limit_options(&parse_cli(&[])).unwrap(),
+            LimitOptions::default()
+        );
+    }
+
+    #[test]
+    fn limit_options_collect_rates_and_caps() {
+        let cli = parse_cli(&[
+            "--partition-rate-limit",
+            "2.5",
+            "--connection-rate-limit",
+            "100",
+            "--connection-rate-burst",
+            "500",
+            "--max-concurrent-streams",
+            "64",
+            "--max-request-bytes",
+            "1048576",
+        ]);
+        let opts = limit_options(&cli).unwrap();
+        assert_eq!(
+            opts.partition,
+            Some(RateLimit {
+                per_second: 2.5,
+                burst: 3
+            })
+        );
+        assert_eq!(
+            opts.connection,
+            Some(RateLimit {
+                per_second: 100.0,
+                burst: 500
+            })
+        );
+        assert_eq!(opts.max_streams, Some(64));
+        assert_eq!(opts.max_request_bytes, 1 << 20);
+    }
+
+    #[test]
+    fn limit_options_reject_nonsense() {
+        for args in [
+            &["--partition-rate-burst", "5"][..],
+            &["--partition-rate-limit", "0"],
+            &["--connection-rate-limit=-1"],
+            &[
+                "--connection-rate-limit",
+                "1",
+                "--connection-rate-burst",
+                "0",
+            ],
+            &["--max-concurrent-streams", "0"],
+            &["--max-request-bytes", "0"],
+            &["--max-request-bytes", "999999999999"],
+        ] {
+            assert!(limit_options(&parse_cli(args)).is_err(), "{args:?}");
+        }
+    }
+
+    #[test]
+    fn config_file_overrides_flags_it_names() {
+        let cli = parse_cli(&[
+            "--session-cache-max-size",
+            "100",
+            "--partition-rate-limit",
+            "5",
+            "--max-concurrent-streams",
+            "8",
+        ]);
+        let (settings, ignored) = settings_with_file(
+            &cli,
+            r#"{"SessionCacheMaxSize": 7, "Verbose": true,
+                "PartitionRateBurst": 20, "MaxRequestBytes": 4096,
+                "SessionCacheSize": 3}"#,
+        )
+        .unwrap();
+        assert_eq!(settings.options.session_cache_max_size, Some(7));
+        assert_eq!(settings.options.verbose, Some(true));
+        assert_eq!(settings.options.service_name.as_deref(), Some("svc"));
+        assert_eq!(settings.options.metastore.as_deref(), Some("memory"));
+        assert_eq!(
+            settings.limits.partition,
+            Some(RateLimit {
+                per_second: 5.0,
+                burst: 20
+            })
+        );
+        assert_eq!(settings.limits.max_streams, Some(8));
+        assert_eq!(settings.limits.max_request_bytes, 4096);
+        assert_eq!(ignored, ["SessionCacheSize"]);
+    }
+
+    #[test]
+    fn config_file_values_are_validated() {
+        let cli = parse_cli(&[]);
+        assert!(settings_with_file(&cli, "[]").is_err());
+        assert!(settings_with_file(&cli, r#"{"MaxConcurrentStreams": 0}"#).is_err());
+        assert!(settings_with_file(&cli, r#"{"PartitionRateBurst": 5}"#).is_err());
+        assert!(settings_with_file(&cli, r#"{"SessionCacheMaxSize": "many"}"#).is_err());
+
+        let (settings, ignored) = settings_with_file(&cli, "{}").unwrap();
+        assert_eq!(
+            serde_json::to_value(&settings.options).unwrap(),
+            serde_json::to_value(cli_to_config(&cli)).unwrap()
+        );
+        assert_eq!(settings.limits, limit_options(&cli).unwrap());
+        assert!(ignored.is_empty());
+    }
+
+    #[test]
+    fn recovery_options_reach_the_factory_config() {
+        let defaults = cli_to_config(&parse_cli(&[]));
+        assert_eq!(defaults.recovery_region_suffixes, Some(vec![]));
+        assert_eq!(defaults.self_heal_recovered_keys, Some(true));
+
+        let cli = parse_cli(&[
+            "--recovery-region-suffixes",
+            "us-east-1,eu-west-1",
+            "--self-heal-recovered-keys=false",
+        ]);
+        let (resolved, _) = cli_to_config(&cli).resolve().unwrap();
+        assert_eq!(
+            resolved.recovery_region_suffixes,
+            vec!["us-east-1", "eu-west-1"]
+        );
+        assert!(!resolved.self_heal_recovered_keys);
+    }
+
     #[cfg(unix)]
     #[tokio::test]
     async fn bind_unix_listener_applies_requested_socket_mode() {
@@ -0,0 +1,539 @@
+//! OpenMetrics exporter behind `--metrics-listen`.
+//!
+//! A [`MetricsSink`] installed into the core turns encrypt/decrypt and
+//! metastore timings into histograms and cache events into counters, via the
+//! same [`AsyncMetricsSink`] dispatcher the bindings use so the encrypt path
+//! never waits on a scrape. A tower layer on the gRPC server counts every
+//! call by method and final status code. `GET /metrics` on the listen
+//! address serves the lot as OpenMetrics text.
+
+use std::convert::Infallible;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use asherah::metrics::{AsyncMetricsConfig, AsyncMetricsSink, MetricsSink};
+use bytes::Bytes;
+use http::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use http::{Method, StatusCode};
+use http_body::Frame;
+use http_body_util::Full;
+use prometheus_client::collector::Collector;
+use prometheus_client::encoding::{DescriptorEncoder, EncodeLabelSet, EncodeMetric};
+use prometheus_client::metrics::counter::{ConstCounter, Counter};
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+use prometheus_client::metrics::MetricType;
+use prometheus_client::registry::{Registry, Unit};
+use tokio::net::TcpListener;
+use tokio::sync::watch;
+
+/// `Content-Type` of the scrape response.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// gRPC methods counted under their own names. Anything else a client
+/// dials is counted as `unknown` so label cardinality stays fixed.
+const KNOWN_METHODS: [&str; 9] = [
+    "/asherah.apps.server.AppEncryption/Session",
+    "/asherah.apps.server.AppEncryption/Encrypt",
+    "/asherah.apps.server.AppEncryption/Decrypt",
+    "/asherah.apps.server.AppEncryption/EncryptBatch",
+    "/asherah.apps.server.AppEncryption/DecryptBatch",
+    "/grpc.health.v1.Health/Check",
+    "/grpc.health.v1.Health/Watch",
+    "/grpc.reflection.v1.ServerReflection/ServerReflectionInfo",
+    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo",
+];
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct CacheLabels {
+    cache: String,
+    event: &'static str,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RpcLabels {
+    grpc_service: &'static str,
+    grpc_method: &'static str,
+    grpc_code: &'static str,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct DenialLabels {
+    operation: &'static str,
+}
+
+#[derive(Clone, Debug, Hash, PartialEq, Eq, EncodeLabelSet)]
+struct RejectionLabels {
+    reason: &'static str,
+}
+
+/// Every metric the endpoint serves.
+#[derive(Debug)]
+pub struct Metrics {
+    registry: Registry,
+    encrypt: Histogram,
+    decrypt: Histogram,
+    store: Histogram,
+    load: Histogram,
+    cache: Family<CacheLabels, Counter>,
+    handled: Family<RpcLabels, Counter>,
+    denied: Family<DenialLabels, Counter>,
+    rejected: Family<RejectionLabels, Counter>,
+}
+
+fn latency_histogram() -> Histogram {
+    // 100µs to ~3.3s: in-memory cache hits through slow KMS round trips.
+    Histogram::new(exponential_buckets(0.0001, 2.0, 16))
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        let mut registry = Registry::default();
+        let encrypt = latency_histogram();
+        let decrypt = latency_histogram();
+        let store = latency_histogram();
+        let load = latency_histogram();
+        let cache = Family::<CacheLabels, Counter>::default();
+        let handled = Family::<RpcLabels, Counter>::default();
+        let denied = Family::<DenialLabels, Counter>::default();
+        let rejected = Family::<RejectionLabels, Counter>::default();
+        registry.register_with_unit(
+            "asherah_encrypt_duration",
+            "Time spent in successful encrypts",
+            Unit::Seconds,
+            encrypt.clone(),
+        );
+        registry.register_with_unit(
+            "asherah_decrypt_duration",
+            "Time spent in successful decrypts",
+            Unit::Seconds,
+            decrypt.clone(),
+        );
+        registry.register_with_unit(
+            "asherah_metastore_store_duration",
+            "Time spent storing key records in the metastore",
+            Unit::Seconds,
+            store.clone(),
+        );
+        registry.register_with_unit(
+            "asherah_metastore_load_duration",
+            "Time spent loading key records from the metastore",
+            Unit::Seconds,
+            load.clone(),
+        );
+        registry.register(
+            "asherah_cache_events",
+            "Key cache lookups by cache and outcome (hit, miss, stale)",
+            cache.clone(),
+        );
+        registry.register(
+            "grpc_server_handled",
+            "gRPC calls completed, by method and status code",
+            handled.clone(),
+        );
+        registry.register(
+            "asherah_authz_denied",
+            "Operations refused by the authorization policy, by operation",
+            denied.clone(),
+        );
+        registry.register(
+            "asherah_requests_rejected",
+            "Requests refused by a rate, stream or size limit, by limit",
+            rejected.clone(),
+        );
+        registry.register_collector(Box::new(CoreCounters));
+        Self {
+            registry,
+            encrypt,
+            decrypt,
+            store,
+            load,
+            cache,
+            handled,
+            denied,
+            rejected,
+        }
+    }
+
+    /// Route the core's metrics events here and turn metrics collection on.
+    ///
+    /// Events go through an [`AsyncMetricsSink`]; if its worker thread
+    /// can't be spawned the sink is installed synchronously instead, which
+    /// is safe here because recording is only atomic updates.
+    pub fn install_sink(self: &Arc<Self>) {
+        let sink = CoreSink(Arc::clone(self));
+        match AsyncMetricsSink::new(sink.clone(), AsyncMetricsConfig::default()) {
+            Ok(dispatcher) => asherah::metrics::set_sink(dispatcher),
+            Err(e) => {
+                log::warn!("metrics dispatcher thread failed to start ({e}); recording inline");
+                asherah::metrics::set_sink(sink);
+            }
+        }
+        asherah::metrics::set_enabled(true);
+    }
+
+    /// The current values as OpenMetrics text, ending in `# EOF`.
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        if let Err(e) = prometheus_client::encoding::text::encode(&mut out, &self.registry) {
+            log::warn!("encoding metrics failed: {e}");
+        }
+        out
+    }
+
+    fn record_rpc(&self, path: &str, code: tonic::Code) {
+        let (grpc_service, grpc_method) = split_method(path);
+        self.handled
+            .get_or_create(&RpcLabels {
+                grpc_service,
+                grpc_method,
+                grpc_code: code_name(code),
+            })
+            .inc();
+    }
+
+    /// Count one operation refused by the authorization policy.
+    pub(crate) fn record_denial(&self, operation: &'static str) {
+        self.denied.get_or_create(&DenialLabels { operation }).inc();
+    }
+
+    /// Count one request refused by an admission limit.
+    pub(crate) fn record_rejection(&self, reason: &'static str) {
+        self.rejected
+            .get_or_create(&RejectionLabels { reason })
+            .inc();
+    }
+
+    fn record_cache(&self, cache: &str, event: &'static str) {
+        self.cache
+            .get_or_create(&CacheLabels {
+                cache: cache.to_string(),
+                event,
+            })
+            .inc();
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Counters the core keeps itself, read at scrape time.
+#[derive(Debug)]
+struct CoreCounters;
+
+impl Collector for CoreCounters {
+    fn encode(&self, mut encoder: DescriptorEncoder<'_>) -> Result<(), std::fmt::Error> {
+        let dropped = ConstCounter::new(asherah::metrics::metrics_dropped_count());
+        dropped.encode(encoder.encode_descriptor(
+            "asherah_metrics_dropped",
+            "Metrics events dropped because the dispatcher queue was full",
+            None,
+            dropped.metric_type(),
+        )?)?;
+
+        let (recovered, unrecoverable) = asherah::metrics::decrypt_recovery_counts();
+        let mut family = encoder.encode_descriptor(
+            "asherah_decrypt_recovery",
+            "Decrypts that fell back to cross-region key recovery, by outcome",
+            None,
+            MetricType::Counter,
+        )?;
+        ConstCounter::new(recovered).encode(family.encode_family(&[("outcome", "recovered")])?)?;
+        ConstCounter::new(unrecoverable)
+            .encode(family.encode_family(&[("outcome", "unrecoverable")])?)?;
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct CoreSink(Arc<Metrics>);
+
+impl MetricsSink for CoreSink {
+    fn encrypt(&self, dur: Duration) {
+        self.0.encrypt.observe(dur.as_secs_f64());
+    }
+    fn decrypt(&self, dur: Duration) {
+        self.0.decrypt.observe(dur.as_secs_f64());
+    }
+    fn store(&self, dur: Duration) {
+        self.0.store.observe(dur.as_secs_f64());
+    }
+    fn load(&self, dur: Duration) {
+        self.0.load.observe(dur.as_secs_f64());
+    }
+    fn cache_hit(&self, name: &str) {
+        self.0.record_cache(name, "hit");
+    }
+    fn cache_miss(&self, name: &str) {
+        self.0.record_cache(name, "miss");
+    }
+    fn cache_stale(&self, name: &str) {
+        self.0.record_cache(name, "stale");
+    }
+}
+
+fn split_method(path: &str) -> (&'static str, &'static str) {
+    KNOWN_METHODS
+        .iter()
+        .find(|known| **known == path)
+        .and_then(|known| known.trim_start_matches('/').split_once('/'))
+        .unwrap_or(("unknown", "unknown"))
+}
+
+pub(crate) fn code_name(code: tonic::Code) -> &'static str {
+    use tonic::Code;
+    match code {
+        Code::Ok => "OK",
+        Code::Cancelled => "CANCELLED",
+        Code::Unknown => "UNKNOWN",
+        Code::InvalidArgument => "INVALID_ARGUMENT",
+        Code::DeadlineExceeded => "DEADLINE_EXCEEDED",
+        Code::NotFound => "NOT_FOUND",
+        Code::AlreadyExists => "ALREADY_EXISTS",
+        Code::PermissionDenied => "PERMISSION_DENIED",
+        Code::ResourceExhausted => "RESOURCE_EXHAUSTED",
+        Code::FailedPrecondition => "FAILED_PRECONDITION",
+        Code::Aborted => "ABORTED",
+        Code::OutOfRange => "OUT_OF_RANGE",
+        Code::Unimplemented => "UNIMPLEMENTED",
+        Code::Internal => "INTERNAL",
+        Code::Unavailable => "UNAVAILABLE",
+        Code::DataLoss => "DATA_LOSS",
+        Code::Unauthenticated => "UNAUTHENTICATED",
+    }
+}
+
+fn grpc_status(headers: &HeaderMap) -> Option<tonic::Code> {
+    let raw = headers.get("grpc-status")?.to_str().ok()?;
+    Some(tonic::Code::from_i32(raw.parse().ok()?))
+}
+
+/// Tower layer counting gRPC calls into [`Metrics`]; a no-op without one.
+#[derive(Clone, Debug, Default)]
+pub struct RpcMetricsLayer {
+    metrics: Option<Arc<Metrics>>,
+}
+
+impl RpcMetricsLayer {
+    pub fn new(metrics: Option<Arc<Metrics>>) -> Self {
+        Self { metrics }
+    }
+}
+
+impl<S> tower::Layer<S> for RpcMetricsLayer {
+    type Service = RpcMetricsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RpcMetricsService {
+            inner,
+            metrics: self.metrics.clone(),
+        }
+    }
+}
+
+/// See [`RpcMetricsLayer`].
+#[derive(Clone, Debug)]
+pub struct RpcMetricsService<S> {
+    inner: S,
+    metrics: Option<Arc<Metrics>>,
+}
+
+type BoxFuture<T, E> = Pin<Box<dyn Future<Output = Result<T, E>> + Send>>;
+
+impl<S, B> tower::Service<http::Request<B>> for RpcMetricsService<S>
+where
+    S: tower::Service<http::Request<B>, Response = http::Response<tonic::body::Body>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<tonic::body::Body>;
+    type Error = S::Error;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let Some(metrics) = self.metrics.clone() else {
+            return Box::pin(self.inner.call(req));
+        };
+        let path = req.uri().path().to_string();
+        let response = self.inner.call(req);
+        Box::pin(async move {
+            let response = response.await?;
+            // Errors raised before any message is sent arrive as a
+            // trailers-only response; everything else ends with trailers.
+            if let Some(code) = grpc_status(response.headers()) {
+                metrics.record_rpc(&path, code);
+                return Ok(response);
+            }
+            Ok(response.map(|body| {
+                tonic::body::Body::new(ObservedBody {
+                    inner: body,
+                    pending: Some((metrics, path)),
+                })
+            }))
+        })
+    }
+}
+
+/// Response body that records the call once its trailers go out, or as
+/// CANCELLED if the client leaves first.
+struct ObservedBody {
+    inner: tonic::body::Body,
+    pending: Option<(Arc<Metrics>, String)>,
+}
+
+impl ObservedBody {
+    fn finish(&mut self, code: tonic::Code) {
+        if let Some((metrics, path)) = self.pending.take() {
+            metrics.record_rpc(&path, code);
+        }
+    }
+}
+
+impl http_body::Body for ObservedBody {
+    type Data = Bytes;
+    type Error = tonic::Status;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Bytes>, tonic::Status>>> {
+        let polled = Pin::new(&mut self.inner).poll_frame(cx);
+        match &polled {
+            Poll::Ready(Some(Ok(frame))) => {
+                if let Some(trailers) = frame.trailers_ref() {
+                    let code = grpc_status(trailers).unwrap_or(tonic::Code::Unknown);
+                    self.finish(code);
+                }
+            }
+            Poll::Ready(Some(Err(status))) => self.finish(status.code()),
+            Poll::Ready(None) => self.finish(tonic::Code::Unknown),
+            Poll::Pending => {}
+        }
+        polled
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> http_body::SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl Drop for ObservedBody {
+    fn drop(&mut self) {
+        self.finish(tonic::Code::Cancelled);
+    }
+}
+
+fn respond(
+    metrics: &Metrics,
+    req: &http::Request<hyper::body::Incoming>,
+) -> http::Response<Full<Bytes>> {
+    let mut response = http::Response::new(Full::default());
+    if req.uri().path() != "/metrics" {
+        *response.status_mut() = StatusCode::NOT_FOUND;
+    } else if req.method() != Method::GET {
+        *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+    } else {
+        *response.body_mut() = Full::new(Bytes::from(metrics.encode()));
+        response.headers_mut().insert(
+            CONTENT_TYPE,
+            HeaderValue::from_static(OPENMETRICS_CONTENT_TYPE),
+        );
+    }
+    response
+}
+
+/// Serve `GET /metrics` on `listener` until shutdown starts.
+pub async fn serve(
+    listener: TcpListener,
+    metrics: Arc<Metrics>,
+    mut shutdown_rx: watch::Receiver<bool>,
+) {
+    loop {
+        let (stream, peer) = tokio::select! {
+            biased;
+            () = crate::health::shutdown_started(&mut shutdown_rx) => break,
+            accepted = listener.accept() => match accepted {
+                Ok(v) => v,
+                Err(e) => {
+                    log::warn!("metrics accept failed: {e}");
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    continue;
+                }
+            },
+        };
+        let metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            let svc = hyper::service::service_fn(move |req| {
+                let response = respond(&metrics, &req);
+                async move { Ok::<_, Infallible>(response) }
+            });
+            if let Err(e) = hyper::server::conn::http1::Builder::new()
+                .serve_connection(hyper_util::rt::TokioIo::new(stream), svc)
+                .await
+            {
+                log::debug!("metrics connection from {peer} failed: {e}");
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn methods_are_split_and_unknown_paths_collapse() {
+        assert_eq!(
+            split_method("/asherah.apps.server.AppEncryption/Encrypt"),
+            ("asherah.apps.server.AppEncryption", "Encrypt")
+        );
+        assert_eq!(
+            split_method("/grpc.health.v1.Health/Check"),
+            ("grpc.health.v1.Health", "Check")
+        );
+        assert_eq!(
+            split_method("/asherah.apps.server.AppEncryption/Nope"),
+            ("unknown", "unknown")
+        );
+    }
+
+    #[test]
+    fn encoding_includes_core_counters_and_eof() {
+        let metrics = Metrics::new();
+        metrics.record_cache("meta", "miss");
+        metrics.encrypt.observe(0.002);
+        let text = metrics.encode();
+        assert!(
+            text.contains("asherah_cache_events_total{cache=\"meta\",event=\"miss\"} 1"),
+            "{text}"
+        );
+        assert!(
+            text.contains("asherah_encrypt_duration_seconds_count 1"),
+            "{text}"
+        );
+        assert!(
+            text.contains("# TYPE asherah_metrics_dropped counter"),
+            "{text}"
+        );
+        assert!(
+            text.contains("asherah_decrypt_recovery_total{outcome=\"recovered\"}"),
+            "{text}"
+        );
+        assert!(text.ends_with("# EOF\n"), "{text}");
+    }
+}
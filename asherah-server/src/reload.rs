@@ -0,0 +1,157 @@
+//! Hot configuration reload. New settings replace the factory that new
+//! sessions come from while open `Session` streams drain on the old one,
+//! and limits and verbosity switch in place. Changes the config drift guard
+//! protects (service and product, region suffix, metastore and KMS
+//! identity) are refused, since applying them needs a restart, as are
+//! factory changes over the in-memory metastore, whose keys a new factory
+//! wouldn't see.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use asherah::builders::MetastoreConfig;
+use asherah_config::ConfigOptions;
+
+use crate::limits::{LimitOptions, Limiter};
+use crate::service::AppEncryptionService;
+
+/// Everything a reload can change.
+#[derive(Clone, Debug)]
+pub struct Settings {
+    /// Factory configuration, including `Verbose`.
+    pub options: ConfigOptions,
+    pub limits: LimitOptions,
+}
+
+impl Settings {
+    fn verbose(&self) -> bool {
+        self.options.verbose.unwrap_or(false)
+    }
+}
+
+/// What [`Reloader::apply`] changed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Reloaded {
+    /// New sessions come from a new factory.
+    pub factory: bool,
+    pub limits: bool,
+    pub verbose: bool,
+}
+
+impl fmt::Display for Reloaded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let changed: Vec<&str> = [
+            (self.factory, "factory settings"),
+            (self.limits, "limits"),
+            (self.verbose, "verbosity"),
+        ]
+        .into_iter()
+        .filter_map(|(changed, name)| changed.then_some(name))
+        .collect();
+        if changed.is_empty() {
+            f.write_str("nothing changed")
+        } else {
+            f.write_str(&changed.join(", "))
+        }
+    }
+}
+
+/// Applies reloaded [`Settings`] to a running server.
+pub struct Reloader {
+    running: Settings,
+    service: Arc<AppEncryptionService>,
+    limiter: Arc<Limiter>,
+    on_verbose: Option<Box<dyn Fn(bool) + Send + Sync>>,
+}
+
+impl fmt::Debug for Reloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Reloader")
+            .field("running", &self.running)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Reloader {
+    /// `running` must be the settings `service` and `limiter` were built
+    /// with.
+    pub fn new(
+        running: Settings,
+        service: Arc<AppEncryptionService>,
+        limiter: Arc<Limiter>,
+    ) -> Self {
+        Self {
+            running,
+            service,
+            limiter,
+            on_verbose: None,
+        }
+    }
+
+    /// Call `f` with the new value whenever a reload changes `Verbose`.
+    pub fn on_verbose(mut self, f: impl Fn(bool) + Send + Sync + 'static) -> Self {
+        self.on_verbose = Some(Box::new(f));
+        self
+    }
+
+    /// Switch to `next`, or change nothing and return why not. The new
+    /// factory is built (and its drift guard checked against the metastore)
+    /// before anything is swapped, so a failure leaves the running
+    /// configuration in force.
+    pub async fn apply(&mut self, next: Settings) -> Result<Reloaded> {
+        let (running, _) = self.running.options.resolve()?;
+        let (candidate, _) = next
+            .options
+            .resolve()
+            .context("reloaded configuration is invalid")?;
+        let drift = asherah::config_drift_guard::config_drift_between(&running, &candidate)?;
+        if !drift.is_empty() {
+            anyhow::bail!(
+                "reloaded configuration changes the {}, which the config drift guard \
+                 protects; restart the server to apply it",
+                drift.join(", ")
+            );
+        }
+
+        let reloaded = Reloaded {
+            factory: factory_settings(&self.running.options)? != factory_settings(&next.options)?,
+            limits: self.running.limits != next.limits,
+            verbose: self.running.verbose() != next.verbose(),
+        };
+        if reloaded.factory && matches!(running.metastore, MetastoreConfig::Memory) {
+            anyhow::bail!(
+                "reloaded configuration changes factory settings, but a new factory would \
+                 start with an empty in-memory metastore; restart the server to apply it"
+            );
+        }
+        if reloaded.factory {
+            let options = next.options.clone();
+            let (factory, _) =
+                tokio::task::spawn_blocking(move || crate::service::factory_from_config(&options))
+                    .await
+                    .context("factory init task panicked")?
+                    .context("failed to build a factory from the reloaded configuration")?;
+            self.service.replace_factory(factory);
+        }
+        if reloaded.limits {
+            self.limiter.reconfigure(&next.limits);
+        }
+        if reloaded.verbose {
+            if let Some(f) = &self.on_verbose {
+                f(next.verbose());
+            }
+        }
+        self.running = next;
+        Ok(reloaded)
+    }
+}
+
+/// `options` as JSON without `Verbose`, which only affects logging.
+fn factory_settings(options: &ConfigOptions) -> Result<serde_json::Value> {
+    let mut value = serde_json::to_value(options).context("serialize config")?;
+    if let Some(map) = value.as_object_mut() {
+        map.remove("Verbose");
+    }
+    Ok(value)
+}
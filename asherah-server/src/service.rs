@@ -1,27 +1,49 @@
+use std::net::SocketAddr;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
 
 use tokio::sync::{mpsc, watch, Mutex};
 use tokio::task::JoinSet;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status, Streaming};
 
+use crate::audit::{AuditLog, Event};
+use crate::authz::{Access, Authorizer, Caller, Denied, Operation};
 use crate::convert::{drr_to_proto, proto_to_drr};
+use crate::limits::{Exhausted, Limiter, Reason};
+use crate::listener::ConnInfo;
 use crate::proto;
 use crate::proto::app_encryption_server::AppEncryption;
 
-pub type Factory = asherah::SessionFactory<
+/// The same `PublicFactory` the language bindings use, so the server gets
+/// decrypt recovery (`RecoveryRegionSuffixes`), self-heal, the factory
+/// session cache, metrics and the config drift guard on identical terms.
+/// Not the generic `asherah::session::SessionFactory`, which has none of
+/// those.
+pub type Factory = asherah::session::PublicFactory<
     asherah::aead::AES256GCM,
     asherah::builders::DynKms,
     asherah::builders::DynMetastore,
 >;
 
-type Session = asherah::Session<
+type Session = asherah::session::PublicSession<
     asherah::aead::AES256GCM,
     asherah::builders::DynKms,
     asherah::builders::DynMetastore,
 >;
 
+/// Build the server's factory from resolved options, exactly as the
+/// bindings do: `asherah_config::factory_from_config` (drift guard and the
+/// `RECOVERY_REGION_SUFFIXES` / `SELF_HEAL_RECOVERED_KEYS` env overrides
+/// included), opted in to metrics observation.
+pub fn factory_from_config(
+    config: &asherah_config::ConfigOptions,
+) -> anyhow::Result<(Factory, asherah_config::AppliedConfig)> {
+    let (factory, applied) = asherah_config::factory_from_config(config)?;
+    Ok((factory.with_metrics(true), applied))
+}
+
 type SessionStream =
     Pin<Box<dyn tokio_stream::Stream<Item = Result<proto::SessionResponse, Status>> + Send>>;
 
@@ -31,9 +53,18 @@ type SessionStream =
 pub type SessionTasks = Arc<Mutex<JoinSet<()>>>;
 
 pub struct AppEncryptionService {
-    factory: Arc<Factory>,
+    /// Replaced by [`replace_factory`](Self::replace_factory) on a config
+    /// reload. Streams and calls hold the `Arc` they started with.
+    factory: RwLock<Arc<Factory>>,
     shutdown_rx: watch::Receiver<bool>,
     tasks: SessionTasks,
+    /// Policy checked at GetSession and before every operation; `None`
+    /// allows every caller everything.
+    authorizer: Option<Arc<Authorizer>>,
+    /// Rate limits and the stream cap; unlimited unless configured.
+    limiter: Arc<Limiter>,
+    /// Where every operation is recorded; `None` records nothing.
+    audit: Option<Arc<AuditLog>>,
     /// Held only when the service was constructed via `new()` (tests).
     /// `watch::Receiver::changed()` returns `Err` when every sender has
     /// been dropped, which would make the per-session task's `select!`
@@ -57,9 +88,12 @@ impl AppEncryptionService {
     pub fn new(factory: Factory) -> Self {
         let (tx, rx) = watch::channel(false);
         Self {
-            factory: Arc::new(factory),
+            factory: RwLock::new(Arc::new(factory)),
             shutdown_rx: rx,
             tasks: Arc::new(Mutex::new(JoinSet::new())),
+            authorizer: None,
+            limiter: Arc::new(Limiter::default()),
+            audit: None,
             _shutdown_keepalive: Some(tx),
         }
     }
@@ -75,12 +109,102 @@ impl AppEncryptionService {
         tasks: SessionTasks,
     ) -> Self {
         Self {
-            factory: Arc::new(factory),
+            factory: RwLock::new(Arc::new(factory)),
             shutdown_rx,
             tasks,
+            authorizer: None,
+            limiter: Arc::new(Limiter::default()),
+            audit: None,
             _shutdown_keepalive: None,
         }
     }
+
+    /// The factory new sessions are drawn from, for callers that need to
+    /// reach its metastore or KMS directly (the health probe).
+    pub fn factory(&self) -> Arc<Factory> {
+        Arc::clone(&self.factory.read().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Draw new sessions from `factory` from now on. Open `Session` streams
+    /// and in-flight calls finish on the factory they started with, which
+    /// is dropped (with its caches) once the last of them ends.
+    pub fn replace_factory(&self, factory: Factory) {
+        *self.factory.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(factory);
+    }
+
+    /// Enforce `authorizer` on every request.
+    pub fn with_authorizer(mut self, authorizer: Arc<Authorizer>) -> Self {
+        self.authorizer = Some(authorizer);
+        self
+    }
+
+    /// Enforce `limiter`'s rate limits and stream cap on every request.
+    pub fn with_limiter(mut self, limiter: Arc<Limiter>) -> Self {
+        self.limiter = limiter;
+        self
+    }
+
+    /// Record every encrypt, decrypt and GetSession in `audit`.
+    pub fn with_audit_log(mut self, audit: Arc<AuditLog>) -> Self {
+        self.audit = Some(audit);
+        self
+    }
+
+    /// The limits this service enforces, shared with the HTTP gateway.
+    pub fn limiter(&self) -> &Arc<Limiter> {
+        &self.limiter
+    }
+
+    /// The checks every operation in `request` must pass, bound to the
+    /// connection it arrived on (see [`ConnInfo`]); `rpc` names the call
+    /// in audit records.
+    fn gate<T>(&self, request: &Request<T>, rpc: &'static str) -> Gate {
+        let conn = request.extensions().get::<ConnInfo>();
+        let caller = match conn {
+            Some(conn) if self.authorizer.is_some() || self.audit.is_some() => {
+                Caller::from_conn(conn)
+            }
+            _ => Caller::default(),
+        };
+        Gate {
+            access: Access::new(self.authorizer.clone(), caller),
+            limiter: Arc::clone(&self.limiter),
+            connection: conn.map(|c| c.id),
+            audit: self.audit.clone(),
+            rpc,
+            remote_addr: conn.and_then(|c| c.remote_addr),
+        }
+    }
+}
+
+/// Authorization, admission and auditing for one request's operations.
+#[derive(Clone, Debug)]
+struct Gate {
+    access: Access,
+    limiter: Arc<Limiter>,
+    connection: Option<u64>,
+    audit: Option<Arc<AuditLog>>,
+    rpc: &'static str,
+    remote_addr: Option<SocketAddr>,
+}
+
+impl Gate {
+    /// Authorize `op` on `partition_id`; encrypts and decrypts then take a
+    /// rate-limit token.
+    fn check(&self, partition_id: &str, op: Operation) -> Result<(), OpError> {
+        self.access.check(partition_id, op)?;
+        if op != Operation::GetSession {
+            self.limiter.admit(self.connection, partition_id)?;
+        }
+        Ok(())
+    }
+
+    /// Record `event` if an audit log is configured.
+    fn audit(&self, event: &Event<'_>) {
+        if let Some(audit) = &self.audit {
+            audit.record(self.rpc, self.access.caller(), self.remote_addr, event);
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -91,7 +215,26 @@ impl AppEncryption for AppEncryptionService {
         &self,
         request: Request<Streaming<proto::SessionRequest>>,
     ) -> Result<Response<Self::SessionStream>, Status> {
-        let factory = self.factory.clone();
+        // Reap finished session tasks so the set holds only live streams,
+        // then apply the stream cap. The lock is held until the new task
+        // is spawned so concurrent opens can't overshoot it.
+        let mut tasks = self.tasks.lock().await;
+        while let Some(finished) = tasks.try_join_next() {
+            if let Err(join_err) = finished {
+                if !join_err.is_cancelled() {
+                    log::debug!("session task ended: {join_err}");
+                }
+            }
+        }
+        if let Some(max) = self.limiter.max_streams() {
+            if tasks.len() >= max {
+                let err = self.limiter.reject(Reason::Streams);
+                return Err(Status::resource_exhausted(format!("{err} (limit {max})")));
+            }
+        }
+
+        let factory = self.factory();
+        let gate = self.gate(&request, "Session");
         let mut shutdown_rx = self.shutdown_rx.clone();
         let mut inbound = request.into_inner();
         let (tx, rx) = mpsc::channel(16);
@@ -132,11 +275,18 @@ impl AppEncryption for AppEncryptionService {
                             Ok(None) => break,
                             Err(e) => {
                                 log::debug!("stream error: {e}");
+                                // An oversized message (see `limits`) ends
+                                // the stream with that status so the client
+                                // sees why.
+                                if e.code() == tonic::Code::ResourceExhausted {
+                                    drop(tx.send(Err(e)).await);
+                                }
                                 break;
                             }
                         };
                         let response =
-                            process_request(&factory, &mut session, &mut partition_id, req).await;
+                            process_request(&factory, &gate, &mut session, &mut partition_id, req)
+                                .await;
                         if tx.send(Ok(response)).await.is_err() {
                             break;
                         }
@@ -159,20 +309,7 @@ impl AppEncryption for AppEncryptionService {
                 if let Some(pid) = partition_id.as_deref() {
                     log::debug!("closing session for {pid}");
                 }
-                // PublicSession::close walks IK and session caches, frees
-                // memguard-locked pages (munlock syscalls), and acquires
-                // parking_lot locks under the hood. Running it directly on
-                // a Tokio worker would block the executor for the duration
-                // of those syscalls — move it onto the blocking pool. T8 in
-                // docs/review-2026-05-05-findings.md.
-                let close_result = tokio::task::spawn_blocking(move || s.close())
-                    .await
-                    .unwrap_or_else(|join_err| {
-                        Err(anyhow::anyhow!("close task panicked: {join_err}"))
-                    });
-                if let Err(e) = close_result {
-                    log::warn!("session close error: {e}");
-                }
+                close_session(s).await;
             }
         };
 
@@ -180,9 +317,348 @@ impl AppEncryption for AppEncryptionService {
         // drain. `JoinSet::spawn` requires `&mut self`, so we serialize
         // through the Mutex; contention is bounded by stream-creation
         // rate (not per-message), so this is not on a hot path.
-        self.tasks.lock().await.spawn(task);
+        tasks.spawn(task);
         Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
     }
+
+    async fn encrypt(
+        &self,
+        request: Request<proto::EncryptRequest>,
+    ) -> Result<Response<proto::EncryptResponse>, Status> {
+        let started = Instant::now();
+        let gate = self.gate(&request, "Encrypt");
+        let req = request.into_inner();
+        validate_partition_id(&req.partition_id).map_err(Status::invalid_argument)?;
+        let result = match gate.check(&req.partition_id, Operation::Encrypt) {
+            Ok(()) => {
+                log::debug!("handling encrypt for {}", req.partition_id);
+                let session = self.factory().get_session(&req.partition_id);
+                let result = encrypt_with(&session, &req.data).await;
+                close_session(session).await;
+                result
+            }
+            Err(e) => Err(e),
+        };
+        gate.audit(&encrypt_event(
+            &req.partition_id,
+            req.data.len(),
+            started,
+            &result,
+        ));
+        Ok(Response::new(proto::EncryptResponse {
+            data_row_record: Some(result.map_err(OpError::into_status)?),
+        }))
+    }
+
+    async fn decrypt(
+        &self,
+        request: Request<proto::DecryptRequest>,
+    ) -> Result<Response<proto::DecryptResponse>, Status> {
+        let started = Instant::now();
+        let gate = self.gate(&request, "Decrypt");
+        let req = request.into_inner();
+        validate_partition_id(&req.partition_id).map_err(Status::invalid_argument)?;
+        let key = req.data_row_record.as_ref().and_then(ik_meta).cloned();
+        let result = match gate.check(&req.partition_id, Operation::Decrypt) {
+            Ok(()) => {
+                log::debug!("handling decrypt for {}", req.partition_id);
+                let session = self.factory().get_session(&req.partition_id);
+                let result = decrypt_with(&session, req.data_row_record).await;
+                close_session(session).await;
+                result
+            }
+            Err(e) => Err(e),
+        };
+        gate.audit(&decrypt_event(
+            &req.partition_id,
+            key.as_ref(),
+            started,
+            &result,
+        ));
+        Ok(Response::new(proto::DecryptResponse {
+            data: result.map_err(OpError::into_status)?,
+        }))
+    }
+
+    async fn encrypt_batch(
+        &self,
+        request: Request<proto::EncryptBatchRequest>,
+    ) -> Result<Response<proto::EncryptBatchResponse>, Status> {
+        use proto::encrypt_result::Result as Item;
+        let gate = self.gate(&request, "EncryptBatch");
+        let items = request.into_inner().requests;
+        check_batch_len(items.len())?;
+        let factory = self.factory();
+        let mut sessions = BatchSessions::default();
+        let mut results = Vec::with_capacity(items.len());
+        for item in items {
+            let started = Instant::now();
+            let result = match validate_partition_id(&item.partition_id) {
+                Ok(()) => {
+                    let session =
+                        sessions.get(&factory, &gate, Operation::Encrypt, &item.partition_id);
+                    let result = match session {
+                        Ok(s) => encrypt_with(s, &item.data).await,
+                        Err(e) => Err(e),
+                    };
+                    gate.audit(&encrypt_event(
+                        &item.partition_id,
+                        item.data.len(),
+                        started,
+                        &result,
+                    ));
+                    result
+                }
+                Err(reason) => Err(OpError::Invalid(reason.to_string())),
+            };
+            results.push(proto::EncryptResult {
+                result: Some(match result {
+                    Ok(drr) => Item::EncryptResponse(proto::EncryptResponse {
+                        data_row_record: Some(drr),
+                    }),
+                    Err(e) => Item::ErrorResponse(e.into_error_response()),
+                }),
+            });
+        }
+        sessions.close().await;
+        Ok(Response::new(proto::EncryptBatchResponse { results }))
+    }
+
+    async fn decrypt_batch(
+        &self,
+        request: Request<proto::DecryptBatchRequest>,
+    ) -> Result<Response<proto::DecryptBatchResponse>, Status> {
+        use proto::decrypt_result::Result as Item;
+        let gate = self.gate(&request, "DecryptBatch");
+        let items = request.into_inner().requests;
+        check_batch_len(items.len())?;
+        let factory = self.factory();
+        let mut sessions = BatchSessions::default();
+        let mut results = Vec::with_capacity(items.len());
+        for item in items {
+            let started = Instant::now();
+            let result = match validate_partition_id(&item.partition_id) {
+                Ok(()) => {
+                    let key = item.data_row_record.as_ref().and_then(ik_meta).cloned();
+                    let session =
+                        sessions.get(&factory, &gate, Operation::Decrypt, &item.partition_id);
+                    let result = match session {
+                        Ok(s) => decrypt_with(s, item.data_row_record).await,
+                        Err(e) => Err(e),
+                    };
+                    gate.audit(&decrypt_event(
+                        &item.partition_id,
+                        key.as_ref(),
+                        started,
+                        &result,
+                    ));
+                    result
+                }
+                Err(reason) => Err(OpError::Invalid(reason.to_string())),
+            };
+            results.push(proto::DecryptResult {
+                result: Some(match result {
+                    Ok(data) => Item::DecryptResponse(proto::DecryptResponse { data }),
+                    Err(e) => Item::ErrorResponse(e.into_error_response()),
+                }),
+            });
+        }
+        sessions.close().await;
+        Ok(Response::new(proto::DecryptBatchResponse { results }))
+    }
+}
+
+/// Maximum number of items in one `EncryptBatch` / `DecryptBatch` call.
+/// The gRPC message size cap already bounds the bytes; this bounds the
+/// work (and the distinct partitions) one call can queue up.
+pub const MAX_BATCH_LEN: usize = 1000;
+
+fn check_batch_len(len: usize) -> Result<(), Status> {
+    if len > MAX_BATCH_LEN {
+        return Err(Status::invalid_argument(format!(
+            "batch of {len} items exceeds the limit of {MAX_BATCH_LEN}"
+        )));
+    }
+    Ok(())
+}
+
+/// Sessions opened during one batch call, one per distinct partition, so
+/// items for the same partition share a session and every session is
+/// closed once at the end. Partition IDs are validated by the caller.
+#[derive(Default)]
+struct BatchSessions {
+    open: std::collections::HashMap<String, Session>,
+}
+
+impl BatchSessions {
+    fn get(
+        &mut self,
+        factory: &Factory,
+        gate: &Gate,
+        op: Operation,
+        partition_id: &str,
+    ) -> Result<&Session, OpError> {
+        gate.check(partition_id, op)?;
+        Ok(self
+            .open
+            .entry(partition_id.to_string())
+            .or_insert_with(|| {
+                log::debug!("handling batch for {partition_id}");
+                factory.get_session(partition_id)
+            }))
+    }
+
+    async fn close(self) {
+        for session in self.open.into_values() {
+            close_session(session).await;
+        }
+    }
+}
+
+/// Close a session on the blocking pool, logging (not returning) failures.
+///
+/// PublicSession::close walks IK and session caches, frees memguard-locked
+/// pages (munlock syscalls), and acquires parking_lot locks under the hood.
+/// Running it directly on a Tokio worker would block the executor for the
+/// duration of those syscalls — move it onto the blocking pool. T8 in
+/// docs/review-2026-05-05-findings.md.
+async fn close_session(session: Session) {
+    let close_result = tokio::task::spawn_blocking(move || session.close())
+        .await
+        .unwrap_or_else(|join_err| Err(anyhow::anyhow!("close task panicked: {join_err}")));
+    if let Err(e) = close_result {
+        log::warn!("session close error: {e}");
+    }
+}
+
+/// Why one encrypt or decrypt produced no result. The Session stream and
+/// batch results report every kind as an `ErrorResponse`; the unary RPCs
+/// map them to INVALID_ARGUMENT, PERMISSION_DENIED, RESOURCE_EXHAUSTED and
+/// INTERNAL.
+#[derive(Debug)]
+enum OpError {
+    /// The request itself is unacceptable (size limits, missing record).
+    Invalid(String),
+    /// The authorization policy refused the caller.
+    Denied(String),
+    /// A rate limit refused the operation.
+    Exhausted(String),
+    /// The operation ran and failed; the message is already sanitized.
+    Failed(String),
+}
+
+impl OpError {
+    fn into_message(self) -> String {
+        match self {
+            Self::Invalid(msg) | Self::Denied(msg) | Self::Exhausted(msg) | Self::Failed(msg) => {
+                msg
+            }
+        }
+    }
+
+    fn into_error_response(self) -> proto::ErrorResponse {
+        proto::ErrorResponse {
+            message: self.into_message(),
+        }
+    }
+
+    fn code(&self) -> tonic::Code {
+        match self {
+            Self::Invalid(_) => tonic::Code::InvalidArgument,
+            Self::Denied(_) => tonic::Code::PermissionDenied,
+            Self::Exhausted(_) => tonic::Code::ResourceExhausted,
+            Self::Failed(_) => tonic::Code::Internal,
+        }
+    }
+
+    fn into_status(self) -> Status {
+        let code = self.code();
+        Status::new(code, self.into_message())
+    }
+}
+
+impl From<Denied> for OpError {
+    fn from(denied: Denied) -> Self {
+        Self::Denied(denied.to_string())
+    }
+}
+
+impl From<Exhausted> for OpError {
+    fn from(exhausted: Exhausted) -> Self {
+        Self::Exhausted(exhausted.to_string())
+    }
+}
+
+fn result_code<T>(result: &Result<T, OpError>) -> tonic::Code {
+    result
+        .as_ref()
+        .map_or_else(OpError::code, |_| tonic::Code::Ok)
+}
+
+/// The intermediate key a data row key was wrapped with.
+fn ik_meta(drr: &proto::DataRowRecord) -> Option<&proto::KeyMeta> {
+    drr.key.as_ref()?.parent_key_meta.as_ref()
+}
+
+/// The audit record of encrypting `len` bytes under `partition_id`.
+fn encrypt_event<'req>(
+    partition_id: &'req str,
+    len: usize,
+    started: Instant,
+    result: &'req Result<proto::DataRowRecord, OpError>,
+) -> Event<'req> {
+    Event {
+        operation: Operation::Encrypt,
+        partition_id,
+        key: result.as_ref().ok().and_then(ik_meta),
+        bytes: Some(len),
+        latency: started.elapsed(),
+        code: result_code(result),
+    }
+}
+
+/// The audit record of decrypting a record wrapped under `key`.
+fn decrypt_event<'req>(
+    partition_id: &'req str,
+    key: Option<&'req proto::KeyMeta>,
+    started: Instant,
+    result: &Result<Vec<u8>, OpError>,
+) -> Event<'req> {
+    Event {
+        operation: Operation::Decrypt,
+        partition_id,
+        key,
+        bytes: result.as_ref().ok().map(Vec::len),
+        latency: started.elapsed(),
+        code: result_code(result),
+    }
+}
+
+async fn encrypt_with(session: &Session, data: &[u8]) -> Result<proto::DataRowRecord, OpError> {
+    asherah::limits::check_plaintext_len(data.len())
+        .map_err(|err| OpError::Invalid(err.to_string()))?;
+    match session.encrypt_async(data).await {
+        Ok(drr) => Ok(drr_to_proto(drr)),
+        Err(e) => Err(OpError::Failed(sanitize_error("encrypt", &e))),
+    }
+}
+
+async fn decrypt_with(
+    session: &Session,
+    drr: Option<proto::DataRowRecord>,
+) -> Result<Vec<u8>, OpError> {
+    let Some(proto_drr) = drr else {
+        return Err(OpError::Invalid(
+            "decrypt request missing data_row_record".to_string(),
+        ));
+    };
+    let drr = proto_to_drr(proto_drr);
+    asherah::limits::check_data_row_record(&drr)
+        .map_err(|err| OpError::Invalid(err.to_string()))?;
+    session
+        .decrypt_async(drr)
+        .await
+        .map_err(|e| OpError::Failed(sanitize_error("decrypt", &e)))
 }
 
 /// Maximum partition_id length accepted from clients. Partition IDs flow
@@ -217,6 +693,7 @@ fn sanitize_error(op: &str, err: &anyhow::Error) -> String {
 
 async fn process_request(
     factory: &Factory,
+    gate: &Gate,
     session: &mut Option<Session>,
     partition_id: &mut Option<String>,
     req: proto::SessionRequest,
@@ -238,6 +715,19 @@ async fn process_request(
             if let Err(reason) = validate_partition_id(&get.partition_id) {
                 return error_response(reason);
             }
+            let started = Instant::now();
+            let checked = gate.check(&get.partition_id, Operation::GetSession);
+            gate.audit(&Event {
+                operation: Operation::GetSession,
+                partition_id: &get.partition_id,
+                key: None,
+                bytes: None,
+                latency: started.elapsed(),
+                code: result_code(&checked),
+            });
+            if let Err(e) = checked {
+                return error_response(&e.into_message());
+            }
             log::debug!("handling get-session for {}", get.partition_id);
             *partition_id = Some(get.partition_id.clone());
             *session = Some(factory.get_session(&get.partition_id));
@@ -248,48 +738,51 @@ async fn process_request(
             let Some(s) = session.as_ref() else {
                 return error_response("session not yet initialized");
             };
-            if let Err(err) = asherah::limits::check_plaintext_len(enc.data.len()) {
-                return error_response(&err.to_string());
-            }
             // partition_id is set in lockstep with `session` at GetSession,
-            // so this branch is only reachable when both are populated.
-            if let Some(pid) = partition_id.as_deref() {
-                log::debug!("handling encrypt for {pid}");
-            }
-            match s.encrypt_async(&enc.data).await {
+            // so it is always populated here.
+            let pid = partition_id.as_deref().unwrap_or_default();
+            let started = Instant::now();
+            let result = match gate.check(pid, Operation::Encrypt) {
+                Ok(()) => {
+                    log::debug!("handling encrypt for {pid}");
+                    encrypt_with(s, &enc.data).await
+                }
+                Err(e) => Err(e),
+            };
+            gate.audit(&encrypt_event(pid, enc.data.len(), started, &result));
+            match result {
                 Ok(drr) => proto::SessionResponse {
                     response: Some(proto::session_response::Response::EncryptResponse(
                         proto::EncryptResponse {
-                            data_row_record: Some(drr_to_proto(drr)),
+                            data_row_record: Some(drr),
                         },
                     )),
                 },
-                Err(e) => error_response(&sanitize_error("encrypt", &e)),
+                Err(e) => error_response(&e.into_message()),
             }
         }
         Some(proto::session_request::Request::Decrypt(dec)) => {
             let Some(s) = session.as_ref() else {
                 return error_response("session not yet initialized");
             };
-            if let Some(pid) = partition_id.as_deref() {
-                log::debug!("handling decrypt for {pid}");
-            }
-            match dec.data_row_record {
-                Some(proto_drr) => {
-                    let drr = proto_to_drr(proto_drr);
-                    if let Err(err) = asherah::limits::check_data_row_record(&drr) {
-                        return error_response(&err.to_string());
-                    }
-                    match s.decrypt_async(drr).await {
-                        Ok(data) => proto::SessionResponse {
-                            response: Some(proto::session_response::Response::DecryptResponse(
-                                proto::DecryptResponse { data },
-                            )),
-                        },
-                        Err(e) => error_response(&sanitize_error("decrypt", &e)),
-                    }
+            let pid = partition_id.as_deref().unwrap_or_default();
+            let started = Instant::now();
+            let key = dec.data_row_record.as_ref().and_then(ik_meta).cloned();
+            let result = match gate.check(pid, Operation::Decrypt) {
+                Ok(()) => {
+                    log::debug!("handling decrypt for {pid}");
+                    decrypt_with(s, dec.data_row_record).await
                 }
-                None => error_response("decrypt request missing data_row_record"),
+                Err(e) => Err(e),
+            };
+            gate.audit(&decrypt_event(pid, key.as_ref(), started, &result));
+            match result {
+                Ok(data) => proto::SessionResponse {
+                    response: Some(proto::session_response::Response::DecryptResponse(
+                        proto::DecryptResponse { data },
+                    )),
+                },
+                Err(e) => error_response(&e.into_message()),
             }
         }
         None => error_response("empty request"),
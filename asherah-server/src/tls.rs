@@ -0,0 +1,269 @@
+//! rustls server configuration for the optional TCP listener.
+//!
+//! Server authentication is always on. Client certificates are verified
+//! when a client CA bundle is configured; an allowed-SAN list then narrows
+//! which of that CA's certificates may connect. The SAN check runs inside
+//! the handshake, so a disallowed client never reaches the gRPC service.
+
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use rustls::client::danger::HandshakeSignatureValid;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{CertificateError, DigitallySignedStruct, DistinguishedName, RootCertStore};
+use rustls::{ServerConfig, SignatureScheme};
+
+/// TLS settings for the TCP listener, as given on the command line.
+#[derive(Clone, Debug, Default)]
+pub struct TlsOptions {
+    /// PEM certificate chain presented to clients, leaf first.
+    pub cert_file: PathBuf,
+    /// PEM private key for `cert_file` (PKCS#8, SEC1 or PKCS#1).
+    pub key_file: PathBuf,
+    /// PEM CA bundle for client certificates. When set, clients must
+    /// present a certificate chaining to one of these roots (mTLS).
+    pub client_ca_file: Option<PathBuf>,
+    /// Subject alternative names a client certificate must carry one of.
+    /// DNS names and IP addresses are matched like server names (DNS
+    /// wildcards in the certificate apply); `scheme://` entries such as
+    /// SPIFFE IDs are matched exactly against URI SANs. Empty means any
+    /// certificate from `client_ca_file` is accepted.
+    pub allowed_client_sans: Vec<String>,
+}
+
+/// One allowed-SAN entry, parsed once at startup.
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum AllowedSan {
+    Name(ServerName<'static>),
+    Uri(String),
+}
+
+impl AllowedSan {
+    fn parse(raw: &str) -> Result<Self> {
+        let raw = raw.trim();
+        if raw.contains("://") {
+            return Ok(Self::Uri(raw.to_string()));
+        }
+        if let Ok(ip) = raw.parse::<IpAddr>() {
+            return Ok(Self::Name(ServerName::IpAddress(ip.into())));
+        }
+        let name = ServerName::try_from(raw.to_string())
+            .with_context(|| format!("invalid allowed client SAN '{raw}'"))?;
+        Ok(Self::Name(name))
+    }
+
+    fn matches(&self, cert: &webpki::EndEntityCert<'_>) -> bool {
+        match self {
+            Self::Name(name) => cert.verify_is_valid_for_subject_name(name).is_ok(),
+            Self::Uri(uri) => cert.valid_uri_names().any(|u| u == uri),
+        }
+    }
+}
+
+/// WebPKI client verification followed by the allowed-SAN check.
+#[derive(Debug)]
+struct SanAllowListVerifier {
+    inner: Arc<dyn ClientCertVerifier>,
+    allowed: Vec<AllowedSan>,
+}
+
+impl ClientCertVerifier for SanAllowListVerifier {
+    fn offer_client_auth(&self) -> bool {
+        self.inner.offer_client_auth()
+    }
+
+    fn client_auth_mandatory(&self) -> bool {
+        self.inner.client_auth_mandatory()
+    }
+
+    fn root_hint_subjects(&self) -> &[DistinguishedName] {
+        self.inner.root_hint_subjects()
+    }
+
+    fn verify_client_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        now: UnixTime,
+    ) -> Result<ClientCertVerified, rustls::Error> {
+        let verified = self
+            .inner
+            .verify_client_cert(end_entity, intermediates, now)?;
+        let cert = webpki::EndEntityCert::try_from(end_entity)
+            .map_err(|_| rustls::Error::InvalidCertificate(CertificateError::BadEncoding))?;
+        if self.allowed.iter().any(|san| san.matches(&cert)) {
+            Ok(verified)
+        } else {
+            log::warn!("rejected client certificate: no subject alternative name is allowed");
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+fn load_certs(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let pem = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    let certs = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("parsing certificates in {}", path.display()))?;
+    if certs.is_empty() {
+        anyhow::bail!("no certificates found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+fn load_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    let pem = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    PrivateKeyDer::from_pem_slice(&pem)
+        .with_context(|| format!("parsing private key in {}", path.display()))
+}
+
+/// Build the rustls server config for `opts`, advertising HTTP/2 via ALPN.
+///
+/// Uses the ring provider explicitly rather than the process default, since
+/// the AWS SDK dependency tree can enable more than one rustls provider.
+pub fn server_config(opts: &TlsOptions) -> Result<Arc<ServerConfig>> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let certs = load_certs(&opts.cert_file)?;
+    let key = load_key(&opts.key_file)?;
+
+    let builder = ServerConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .context("configuring TLS protocol versions")?;
+    let builder = match &opts.client_ca_file {
+        Some(ca_file) => builder.with_client_cert_verifier(client_verifier(
+            ca_file,
+            &opts.allowed_client_sans,
+            provider,
+        )?),
+        None => {
+            if !opts.allowed_client_sans.is_empty() {
+                anyhow::bail!("allowed client SANs require a client CA file");
+            }
+            builder.with_no_client_auth()
+        }
+    };
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .context("TLS certificate and private key do not match")?;
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    Ok(Arc::new(config))
+}
+
+fn client_verifier(
+    ca_file: &Path,
+    allowed_sans: &[String],
+    provider: Arc<CryptoProvider>,
+) -> Result<Arc<dyn ClientCertVerifier>> {
+    let mut roots = RootCertStore::empty();
+    for cert in load_certs(ca_file)? {
+        roots
+            .add(cert)
+            .with_context(|| format!("adding client CA from {}", ca_file.display()))?;
+    }
+    let inner = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider)
+        .build()
+        .context("building client certificate verifier")?;
+    if allowed_sans.is_empty() {
+        return Ok(inner);
+    }
+    let allowed = allowed_sans
+        .iter()
+        .map(|s| AllowedSan::parse(s))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(Arc::new(SanAllowListVerifier { inner, allowed }))
+}
+
+#[cfg(test)]
+#[allow(clippy::panic, clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn fixture(name: &str) -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/tls")
+            .join(name)
+    }
+
+    fn server_opts() -> TlsOptions {
+        TlsOptions {
+            cert_file: fixture("server.pem"),
+            key_file: fixture("server.key"),
+            ..TlsOptions::default()
+        }
+    }
+
+    #[test]
+    fn allowed_sans_parse_by_kind() {
+        assert_eq!(
+            AllowedSan::parse("spiffe://example.test/billing").unwrap(),
+            AllowedSan::Uri("spiffe://example.test/billing".to_string())
+        );
+        assert!(matches!(
+            AllowedSan::parse("10.0.0.7").unwrap(),
+            AllowedSan::Name(ServerName::IpAddress(_))
+        ));
+        assert!(matches!(
+            AllowedSan::parse(" billing.internal ").unwrap(),
+            AllowedSan::Name(ServerName::DnsName(_))
+        ));
+        assert!(AllowedSan::parse("not a name").is_err());
+    }
+
+    #[test]
+    fn allow_list_without_client_ca_is_rejected() {
+        let opts = TlsOptions {
+            allowed_client_sans: vec!["billing.internal".to_string()],
+            ..server_opts()
+        };
+        let err = server_config(&opts).unwrap_err();
+        assert!(
+            format!("{err:#}").contains("require a client CA"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn mismatched_key_is_rejected() {
+        let opts = TlsOptions {
+            key_file: fixture("client-other.key"),
+            ..server_opts()
+        };
+        assert!(server_config(&opts).is_err());
+    }
+
+    #[test]
+    fn config_advertises_h2() {
+        let config = server_config(&server_opts()).unwrap();
+        assert_eq!(config.alpn_protocols, vec![b"h2".to_vec()]);
+    }
+}
@@ -0,0 +1,240 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! `--audit-log`: one JSON line per encrypt, decrypt and GetSession from
+//! every RPC, naming the caller, partition and intermediate key, never the
+//! data.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use asherah_server::audit::{AuditLog, PartitionIds, Target};
+use asherah_server::authz::Authorizer;
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::{Encrypt, GetSession, SessionRequest};
+use serde_json::Value;
+use tokio::net::UnixStream;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+
+const SECRET: &[u8] = b"do not log me";
+
+struct Server {
+    sock: PathBuf,
+    audit: Arc<AuditLog>,
+    audit_path: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+impl Server {
+    /// Everything recorded so far.
+    fn records(&self) -> Vec<Value> {
+        self.audit.close();
+        std::fs::read_to_string(&self.audit_path)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect()
+    }
+}
+
+async fn start_server(partition_ids: PartitionIds) -> Server {
+    let dir = tempfile::tempdir().unwrap();
+    let audit_path = dir.path().join("audit.jsonl");
+    let audit = Arc::new(AuditLog::open(&Target::File(audit_path.clone()), partition_ids).unwrap());
+    let uid = rustix::process::getuid().as_raw();
+    let policy = dir.path().join("policy.json");
+    std::fs::write(
+        &policy,
+        format!(r#"{{"rules": [{{"callers": ["uid:{uid}"], "partitions": ["tenant-*"]}}]}}"#),
+    )
+    .unwrap();
+
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("audit-service".to_string()),
+        product_id: Some("audit-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _) = asherah_server::service::factory_from_config(&config).unwrap();
+    let svc = asherah_server::service::AppEncryptionService::new(factory)
+        .with_authorizer(Arc::new(Authorizer::load(&policy, None).unwrap()))
+        .with_audit_log(Arc::clone(&audit));
+
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(proto::app_encryption_server::AppEncryptionServer::new(svc))
+            .serve_with_incoming(asherah_server::listener::unix_incoming(listener))
+            .await
+            .unwrap();
+    });
+    Server {
+        sock,
+        audit,
+        audit_path,
+        _dir: dir,
+    }
+}
+
+async fn connect(server: &Server) -> AppEncryptionClient<Channel> {
+    let sock = server.sock.clone();
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    AppEncryptionClient::new(channel)
+}
+
+fn sha256_hex(value: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, value.as_bytes());
+    digest.as_ref().iter().map(|b| format!("{b:02x}")).collect()
+}
+
+#[tokio::test]
+async fn unary_calls_record_caller_key_and_result() {
+    let server = start_server(PartitionIds::Hashed).await;
+    let mut client = connect(&server).await;
+    let drr = client
+        .encrypt(proto::EncryptRequest {
+            partition_id: "tenant-1".to_string(),
+            data: SECRET.to_vec(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .data_row_record
+        .unwrap();
+    client
+        .decrypt(proto::DecryptRequest {
+            partition_id: "tenant-1".to_string(),
+            data_row_record: Some(drr.clone()),
+        })
+        .await
+        .unwrap();
+    let denied = client
+        .encrypt(proto::EncryptRequest {
+            partition_id: "other".to_string(),
+            data: SECRET.to_vec(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(denied.code(), tonic::Code::PermissionDenied);
+    // Invalid partition IDs are refused before they are audited.
+    client
+        .encrypt(proto::EncryptRequest {
+            partition_id: String::new(),
+            data: SECRET.to_vec(),
+        })
+        .await
+        .unwrap_err();
+
+    let records = server.records();
+    assert_eq!(records.len(), 3, "{records:?}");
+    let ik = drr.key.unwrap().parent_key_meta.unwrap();
+    let uid = rustix::process::getuid().as_raw();
+
+    let encrypt = &records[0];
+    assert_eq!(encrypt["rpc"], "Encrypt");
+    assert_eq!(encrypt["operation"], "encrypt");
+    assert_eq!(encrypt["caller"]["uid"], uid);
+    assert_eq!(encrypt["partition_sha256"], sha256_hex("tenant-1"));
+    assert_eq!(encrypt["key"]["id_sha256"], sha256_hex(&ik.key_id));
+    assert_eq!(encrypt["key"]["created"], ik.created);
+    assert_eq!(encrypt["bytes"], SECRET.len());
+    assert_eq!(encrypt["code"], "OK");
+    assert!(encrypt["latency_us"].is_u64());
+
+    let decrypt = &records[1];
+    assert_eq!(decrypt["operation"], "decrypt");
+    assert_eq!(decrypt["key"], encrypt["key"]);
+    assert_eq!(decrypt["bytes"], SECRET.len());
+
+    let refused = &records[2];
+    assert_eq!(refused["code"], "PERMISSION_DENIED");
+    assert!(refused.get("key").is_none());
+
+    let raw = std::fs::read_to_string(&server.audit_path).unwrap();
+    assert!(!raw.contains("tenant-1"));
+    assert!(!raw.contains("do not log me"));
+}
+
+#[tokio::test]
+async fn streams_and_batches_record_every_operation() {
+    let server = start_server(PartitionIds::Raw).await;
+    let mut client = connect(&server).await;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut responses = client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    for request in [
+        Request::GetSession(GetSession {
+            partition_id: "tenant-2".to_string(),
+        }),
+        Request::Encrypt(Encrypt {
+            data: SECRET.to_vec(),
+        }),
+    ] {
+        tx.send(SessionRequest {
+            request: Some(request),
+        })
+        .await
+        .unwrap();
+        responses.next().await.unwrap().unwrap();
+    }
+    drop(tx);
+
+    client
+        .encrypt_batch(proto::EncryptBatchRequest {
+            requests: ["tenant-3", "nope", ""]
+                .into_iter()
+                .map(|partition_id| proto::EncryptRequest {
+                    partition_id: partition_id.to_string(),
+                    data: SECRET.to_vec(),
+                })
+                .collect(),
+        })
+        .await
+        .unwrap();
+
+    let records = server.records();
+    let summary: Vec<(String, String, String, String)> = records
+        .iter()
+        .map(|r| {
+            (
+                r["rpc"].as_str().unwrap().to_string(),
+                r["operation"].as_str().unwrap().to_string(),
+                r["partition"].as_str().unwrap().to_string(),
+                r["code"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    let expected = [
+        ("Session", "get_session", "tenant-2", "OK"),
+        ("Session", "encrypt", "tenant-2", "OK"),
+        ("EncryptBatch", "encrypt", "tenant-3", "OK"),
+        ("EncryptBatch", "encrypt", "nope", "PERMISSION_DENIED"),
+    ]
+    .map(|(a, b, c, d)| (a.to_string(), b.to_string(), c.to_string(), d.to_string()));
+    assert_eq!(summary, expected);
+    assert!(records[1]["key"]["id"]
+        .as_str()
+        .unwrap()
+        .contains("tenant-2"));
+    assert!(records[0].get("bytes").is_none());
+}
@@ -0,0 +1,291 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! `--authz-policy-file`: callers identified by `SO_PEERCRED` on the Unix
+//! socket, checked at GetSession, per operation and per batch item, with
+//! reloads taking effect on open streams.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use asherah_server::authz::Authorizer;
+use asherah_server::metrics::Metrics;
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::session_response::Response;
+use asherah_server::proto::{Encrypt, GetSession, SessionRequest, SessionResponse};
+use tokio::net::UnixStream;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tonic::Streaming;
+use tower::service_fn;
+
+struct Server {
+    client: AppEncryptionClient<Channel>,
+    authorizer: Arc<Authorizer>,
+    metrics: Arc<Metrics>,
+    policy: std::path::PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+fn write_policy(path: &Path, rules: &str) {
+    std::fs::write(path, format!(r#"{{"rules": [{rules}]}}"#)).unwrap();
+}
+
+fn uid() -> u32 {
+    rustix::process::getuid().as_raw()
+}
+
+async fn start_server(rules: &str) -> Server {
+    let dir = tempfile::tempdir().unwrap();
+    let policy = dir.path().join("policy.json");
+    write_policy(&policy, rules);
+    let metrics = Arc::new(Metrics::new());
+    let authorizer = Arc::new(Authorizer::load(&policy, Some(Arc::clone(&metrics))).unwrap());
+
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("authz-service".to_string()),
+        product_id: Some("authz-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _) = asherah_server::service::factory_from_config(&config).unwrap();
+    let svc = asherah_server::service::AppEncryptionService::new(factory)
+        .with_authorizer(Arc::clone(&authorizer));
+
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(proto::app_encryption_server::AppEncryptionServer::new(svc))
+            .serve_with_incoming(asherah_server::listener::unix_incoming(listener))
+            .await
+            .unwrap();
+    });
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    Server {
+        client: AppEncryptionClient::new(channel),
+        authorizer,
+        metrics,
+        policy,
+        _dir: dir,
+    }
+}
+
+struct Stream {
+    tx: mpsc::Sender<SessionRequest>,
+    responses: Streaming<SessionResponse>,
+}
+
+impl Stream {
+    async fn open(client: &mut AppEncryptionClient<Channel>) -> Self {
+        let (tx, rx) = mpsc::channel(4);
+        let responses = client
+            .session(ReceiverStream::new(rx))
+            .await
+            .unwrap()
+            .into_inner();
+        Self { tx, responses }
+    }
+
+    async fn send(&mut self, request: Request) -> Option<Response> {
+        self.tx
+            .send(SessionRequest {
+                request: Some(request),
+            })
+            .await
+            .unwrap();
+        self.responses.next().await.unwrap().unwrap().response
+    }
+
+    async fn get_session(&mut self, partition_id: &str) -> Option<Response> {
+        self.send(Request::GetSession(GetSession {
+            partition_id: partition_id.to_string(),
+        }))
+        .await
+    }
+
+    async fn encrypt(&mut self, data: &[u8]) -> Option<Response> {
+        self.send(Request::Encrypt(Encrypt {
+            data: data.to_vec(),
+        }))
+        .await
+    }
+}
+
+fn assert_denied(response: Option<Response>) {
+    match response {
+        Some(Response::ErrorResponse(e)) => {
+            assert!(e.message.starts_with("permission denied"), "{}", e.message);
+        }
+        other => panic!("expected a denial, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn session_is_checked_at_get_session_and_per_operation() {
+    let mut server = start_server(&format!(
+        r#"{{"callers": ["uid:{uid}"], "partitions": ["team-a-*"]}},
+           {{"callers": ["uid:{uid}"], "partitions": ["shared"], "operations": ["decrypt"]}}"#,
+        uid = uid()
+    ))
+    .await;
+
+    let mut allowed = Stream::open(&mut server.client).await;
+    assert!(allowed.get_session("team-a-1").await.is_none());
+    assert!(matches!(
+        allowed.encrypt(b"ok").await,
+        Some(Response::EncryptResponse(_))
+    ));
+
+    let mut other_team = Stream::open(&mut server.client).await;
+    assert_denied(other_team.get_session("team-b-1").await);
+    // A denied GetSession leaves the stream without a session.
+    assert!(other_team.get_session("team-a-2").await.is_none());
+
+    // Decrypt-only partitions open, but refuse encrypts.
+    let mut shared = Stream::open(&mut server.client).await;
+    assert!(shared.get_session("shared").await.is_none());
+    assert_denied(shared.encrypt(b"no").await);
+
+    let err = server
+        .client
+        .encrypt(proto::EncryptRequest {
+            partition_id: "shared".to_string(),
+            data: b"no".to_vec(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::PermissionDenied);
+
+    let scrape = server.metrics.encode();
+    assert!(
+        scrape.contains("asherah_authz_denied_total{operation=\"get_session\"} 1"),
+        "{scrape}"
+    );
+    assert!(
+        scrape.contains("asherah_authz_denied_total{operation=\"encrypt\"} 2"),
+        "{scrape}"
+    );
+}
+
+#[tokio::test]
+async fn batch_items_are_checked_individually() {
+    let mut server = start_server(&format!(
+        r#"{{"callers": ["uid:{}"], "partitions": ["team-a-*"]}}"#,
+        uid()
+    ))
+    .await;
+    let results = server
+        .client
+        .encrypt_batch(proto::EncryptBatchRequest {
+            requests: ["team-a-1", "team-b-1"]
+                .into_iter()
+                .map(|p| proto::EncryptRequest {
+                    partition_id: p.to_string(),
+                    data: b"batch".to_vec(),
+                })
+                .collect(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .results;
+    use proto::encrypt_result::Result as Item;
+    assert!(matches!(results[0].result, Some(Item::EncryptResponse(_))));
+    match &results[1].result {
+        Some(Item::ErrorResponse(e)) => assert!(e.message.starts_with("permission denied")),
+        other => panic!("expected a denial, got {other:?}"),
+    }
+}
+
+#[tokio::test]
+async fn reload_applies_to_open_streams_and_keeps_policy_on_error() {
+    let mut server = start_server(&format!(
+        r#"{{"callers": ["uid:{}"], "partitions": ["team-a-*"]}}"#,
+        uid()
+    ))
+    .await;
+    let mut stream = Stream::open(&mut server.client).await;
+    assert!(stream.get_session("team-a-1").await.is_none());
+
+    // A broken file is rejected and the loaded policy stays in force.
+    std::fs::write(&server.policy, "{").unwrap();
+    assert!(server.authorizer.reload().is_err());
+    assert!(matches!(
+        stream.encrypt(b"still allowed").await,
+        Some(Response::EncryptResponse(_))
+    ));
+
+    write_policy(
+        &server.policy,
+        r#"{"callers": ["uid:4294967294"], "partitions": ["team-a-*"]}"#,
+    );
+    server.authorizer.reload().unwrap();
+    assert_denied(stream.encrypt(b"revoked").await);
+}
+
+#[tokio::test]
+async fn connections_without_peer_credentials_match_only_wildcard_rules() {
+    // No `ConnInfo` on this connection (tonic's stock Unix listener), so
+    // the caller is unidentified.
+    let dir = tempfile::tempdir().unwrap();
+    let policy = dir.path().join("policy.json");
+    write_policy(
+        &policy,
+        &format!(
+            r#"{{"callers": ["uid:{}"], "partitions": ["team-a-*"]}},
+               {{"callers": ["*"], "partitions": ["public"]}}"#,
+            uid()
+        ),
+    );
+    let authorizer = Arc::new(Authorizer::load(&policy, None).unwrap());
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("authz-service".to_string()),
+        product_id: Some("authz-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _) = asherah_server::service::factory_from_config(&config).unwrap();
+    let svc =
+        asherah_server::service::AppEncryptionService::new(factory).with_authorizer(authorizer);
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(proto::app_encryption_server::AppEncryptionServer::new(svc))
+            .serve_with_incoming(tokio_stream::wrappers::UnixListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    let mut client = AppEncryptionClient::new(channel);
+
+    let mut stream = Stream::open(&mut client).await;
+    assert_denied(stream.get_session("team-a-1").await);
+    assert!(stream.get_session("public").await.is_none());
+}
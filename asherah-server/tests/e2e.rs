@@ -111,7 +111,11 @@ fn stop_server(child: &mut std::process::Child, timeout: Duration) -> std::proce
 }
 
 async fn e2e_connect(sock: PathBuf) -> AppEncryptionClient<tonic::transport::Channel> {
-    let channel = tokio::time::timeout(Duration::from_secs(5), async {
+    AppEncryptionClient::new(e2e_channel(sock).await)
+}
+
+async fn e2e_channel(sock: PathBuf) -> tonic::transport::Channel {
+    tokio::time::timeout(Duration::from_secs(5), async {
         Endpoint::try_from("http://[::]:50051")
             .expect("endpoint")
             .connect_with_connector(service_fn(move |_: Uri| {
@@ -125,9 +129,7 @@ async fn e2e_connect(sock: PathBuf) -> AppEncryptionClient<tonic::transport::Cha
             .expect("connect failed")
     })
     .await
-    .expect("tonic connect timed out after 5s");
-
-    AppEncryptionClient::new(channel)
+    .expect("tonic connect timed out after 5s")
 }
 
 fn make_request(r: Request) -> SessionRequest {
@@ -641,3 +643,68 @@ async fn test_binary_rust_log_warn_silences_info_when_not_verbose() {
     drop(std::fs::remove_file(&sock));
     drop(std::fs::remove_file(&stderr_log));
 }
+
+#[tokio::test]
+#[serial]
+async fn test_binary_health_goes_not_serving_on_sigterm() {
+    use tonic_health::pb::health_check_response::ServingStatus;
+    use tonic_health::pb::health_client::HealthClient;
+    use tonic_health::pb::HealthCheckRequest;
+
+    // A long drain timeout: an open Watch stream must not hold the drain
+    // open, so the server should still exit well inside it.
+    let sock = e2e_socket_path();
+    let stderr_log = e2e_stderr_path();
+    let mut child = spawn_server_with_extra_env(
+        &[
+            ("ASHERAH_SOCKET_FILE", sock.to_str().unwrap()),
+            ("ASHERAH_SHUTDOWN_DRAIN_TIMEOUT", "30s"),
+        ],
+        &stderr_log,
+    );
+    wait_for_server(&sock, 5000).await;
+
+    let mut health = HealthClient::new(e2e_channel(sock.clone()).await);
+    let mut updates = health
+        .watch(HealthCheckRequest {
+            service: "asherah.apps.server.AppEncryption".to_string(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    while updates.next().await.unwrap().unwrap().status() != ServingStatus::Serving {
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "never became healthy"
+        );
+    }
+
+    send_sigterm(&child);
+    let last = tokio::time::timeout(Duration::from_secs(10), async {
+        let mut last = None;
+        while let Some(Ok(update)) = updates.next().await {
+            last = Some(update.status());
+        }
+        last
+    })
+    .await
+    .expect("watch stream should end once the drain starts");
+    assert_eq!(last, Some(ServingStatus::NotServing));
+
+    // Wait without blocking the runtime: the client connection still has
+    // to answer the server's graceful-shutdown GOAWAY ping.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+    let status = loop {
+        if let Some(status) = child.try_wait().unwrap() {
+            break status;
+        }
+        if tokio::time::Instant::now() >= deadline {
+            send_signal(&child, "-KILL");
+            panic!("server still draining 10s after SIGTERM");
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    };
+    assert!(status.success(), "server should exit 0 on SIGTERM");
+    drop(std::fs::remove_file(&stderr_log));
+}
@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+# Regenerates the TLS fixtures used by tests/tls.rs. Long-lived on purpose;
+# none of these keys protect anything.
+set -euo pipefail
+cd "$(dirname "$0")"
+DAYS=36500
+
+ca() { # name
+  openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out "$1.key"
+  openssl req -x509 -new -key "$1.key" -sha256 -days "$DAYS" -subj "/CN=$1" \
+    -addext "basicConstraints=critical,CA:TRUE" \
+    -addext "keyUsage=critical,keyCertSign,cRLSign" -out "$1.pem"
+}
+
+leaf() { # name ca eku san
+  openssl genpkey -algorithm EC -pkeyopt ec_paramgen_curve:P-256 -out "$1.key"
+  openssl req -new -key "$1.key" -subj "/CN=$1" -out "$1.csr"
+  printf 'basicConstraints=critical,CA:FALSE\nkeyUsage=critical,digitalSignature\nextendedKeyUsage=%s\nsubjectAltName=%s\n' "$3" "$4" > "$1.ext"
+  openssl x509 -req -in "$1.csr" -CA "$2.pem" -CAkey "$2.key" -CAcreateserial \
+    -sha256 -days "$DAYS" -extfile "$1.ext" -out "$1.pem"
+  rm -f "$1.csr" "$1.ext"
+}
+
+ca ca
+ca rogue-ca
+leaf server ca serverAuth "DNS:localhost,IP:127.0.0.1"
+leaf client-billing ca clientAuth "DNS:billing.internal,URI:spiffe://example.test/billing"
+leaf client-other ca clientAuth "DNS:other.internal"
+leaf client-rogue rogue-ca clientAuth "DNS:billing.internal"
+rm -f ./*.srl
@@ -0,0 +1,219 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! `--http-listen`: the HTTP/1.1 gateway over a Unix socket, sharing the
+//! service's factory, authorization policy and limits, with gRPC codes
+//! mapped to HTTP statuses.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use asherah_server::authz::Authorizer;
+use asherah_server::limits::{LimitOptions, Limiter, RateLimit};
+use asherah_server::metrics::Metrics;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::UnixStream;
+use tokio::sync::watch;
+
+struct Server {
+    sock: PathBuf,
+    metrics: Arc<Metrics>,
+    _shutdown: watch::Sender<bool>,
+    _dir: tempfile::TempDir,
+}
+
+async fn start_server(rules: Option<&str>, opts: LimitOptions) -> Server {
+    let dir = tempfile::tempdir().unwrap();
+    let metrics = Arc::new(Metrics::new());
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("gateway-service".to_string()),
+        product_id: Some("gateway-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _) = asherah_server::service::factory_from_config(&config).unwrap();
+    let mut svc = asherah_server::service::AppEncryptionService::new(factory)
+        .with_limiter(Arc::new(Limiter::new(&opts, Some(Arc::clone(&metrics)))));
+    if let Some(rules) = rules {
+        let policy = dir.path().join("policy.json");
+        std::fs::write(&policy, format!(r#"{{"rules": [{rules}]}}"#)).unwrap();
+        let authorizer = Authorizer::load(&policy, Some(Arc::clone(&metrics))).unwrap();
+        svc = svc.with_authorizer(Arc::new(authorizer));
+    }
+
+    let sock = dir.path().join("http.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
+    tokio::spawn(asherah_server::gateway::serve(
+        asherah_server::listener::unix_incoming(listener),
+        Arc::new(svc),
+        shutdown_rx,
+    ));
+    Server {
+        sock,
+        metrics,
+        _shutdown: shutdown_tx,
+        _dir: dir,
+    }
+}
+
+struct Reply {
+    status: u16,
+    content_type: String,
+    body: Vec<u8>,
+}
+
+impl Reply {
+    fn json(&self) -> serde_json::Value {
+        serde_json::from_slice(&self.body).unwrap()
+    }
+
+    fn error_code(&self) -> String {
+        self.json()["code"].as_str().unwrap().to_string()
+    }
+}
+
+/// One request on its own connection.
+async fn call(sock: &Path, method: &str, path: &str, body: &[u8]) -> Reply {
+    let mut stream = UnixStream::connect(sock).await.unwrap();
+    let head = format!(
+        "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(head.as_bytes()).await.unwrap();
+    stream.write_all(body).await.unwrap();
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).await.unwrap();
+
+    let split = raw.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = std::str::from_utf8(&raw[..split]).unwrap();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    let content_type = head
+        .lines()
+        .find_map(|l| {
+            l.to_ascii_lowercase()
+                .strip_prefix("content-type: ")
+                .map(str::to_string)
+        })
+        .unwrap_or_default();
+    Reply {
+        status,
+        content_type,
+        body: raw[split + 4..].to_vec(),
+    }
+}
+
+async fn encrypt(server: &Server, partition_id: &str, data: &[u8]) -> Reply {
+    let path = format!("/v1/partitions/{partition_id}/encrypt");
+    call(&server.sock, "POST", &path, data).await
+}
+
+async fn decrypt(server: &Server, partition_id: &str, json: &[u8]) -> Reply {
+    let path = format!("/v1/partitions/{partition_id}/decrypt");
+    call(&server.sock, "POST", &path, json).await
+}
+
+#[tokio::test]
+async fn encrypt_returns_go_json_that_decrypt_accepts() {
+    let server = start_server(None, LimitOptions::default()).await;
+    let encrypted = encrypt(&server, "user%2042", b"secret payload").await;
+    assert_eq!(encrypted.status, 200);
+    assert_eq!(encrypted.content_type, "application/json");
+    let drr = encrypted.json();
+    assert!(drr["Data"].is_string(), "{drr}");
+    assert!(drr["Key"]["ParentKeyMeta"]["KeyId"]
+        .as_str()
+        .unwrap()
+        .contains("user 42"));
+    // The same encoding the bindings' encrypt_to_json produce.
+    let parsed: asherah::DataRowRecord = serde_json::from_slice(&encrypted.body).unwrap();
+    assert_eq!(parsed.to_json_fast().as_bytes(), encrypted.body.as_slice());
+
+    let decrypted = decrypt(&server, "user%2042", &encrypted.body).await;
+    assert_eq!(decrypted.status, 200);
+    assert_eq!(decrypted.content_type, "application/octet-stream");
+    assert_eq!(decrypted.body, b"secret payload");
+
+    // A record from another partition fails like the gRPC call does.
+    let wrong = decrypt(&server, "someone-else", &encrypted.body).await;
+    assert_eq!(wrong.status, 500);
+    assert_eq!(wrong.error_code(), "INTERNAL");
+}
+
+#[tokio::test]
+async fn bad_requests_map_to_http_statuses() {
+    let server = start_server(None, LimitOptions::default()).await;
+    let not_found = call(&server.sock, "POST", "/v1/partitions/p/rotate", b"").await;
+    assert_eq!(not_found.status, 404);
+    assert_eq!(not_found.error_code(), "NOT_FOUND");
+
+    let get = call(&server.sock, "GET", "/v1/partitions/p/encrypt", b"").await;
+    assert_eq!(get.status, 405);
+
+    let garbage = decrypt(&server, "p", b"{not json").await;
+    assert_eq!(garbage.status, 400);
+    assert_eq!(garbage.error_code(), "INVALID_ARGUMENT");
+
+    let bad_base64 = decrypt(&server, "p", br#"{"Data": "not base64!"}"#).await;
+    assert_eq!(bad_base64.status, 400);
+
+    let long_id = "p".repeat(300);
+    let too_long = encrypt(&server, &long_id, b"x").await;
+    assert_eq!(too_long.status, 400);
+}
+
+#[tokio::test]
+async fn policy_denials_are_forbidden() {
+    let uid = rustix::process::getuid().as_raw();
+    let rules = format!(
+        r#"{{"callers": ["uid:{uid}"], "partitions": ["team-a-*"]}},
+           {{"callers": ["uid:{uid}"], "partitions": ["shared"], "operations": ["decrypt"]}}"#
+    );
+    let server = start_server(Some(&rules), LimitOptions::default()).await;
+    assert_eq!(encrypt(&server, "team-a-1", b"ok").await.status, 200);
+
+    let other = encrypt(&server, "team-b-1", b"no").await;
+    assert_eq!(other.status, 403);
+    assert_eq!(other.error_code(), "PERMISSION_DENIED");
+    assert_eq!(encrypt(&server, "shared", b"no").await.status, 403);
+
+    let scrape = server.metrics.encode();
+    assert!(
+        scrape.contains("asherah_authz_denied_total{operation=\"encrypt\"} 2"),
+        "{scrape}"
+    );
+}
+
+#[tokio::test]
+async fn limits_are_shared_with_grpc() {
+    let server = start_server(
+        None,
+        LimitOptions {
+            partition: Some(RateLimit {
+                per_second: 0.001,
+                burst: 1,
+            }),
+            max_request_bytes: 1024,
+            ..LimitOptions::default()
+        },
+    )
+    .await;
+    assert_eq!(encrypt(&server, "hot", b"1").await.status, 200);
+    let limited = encrypt(&server, "hot", b"2").await;
+    assert_eq!(limited.status, 429);
+    assert_eq!(limited.error_code(), "RESOURCE_EXHAUSTED");
+
+    let oversized = encrypt(&server, "cold", &[7; 2048]).await;
+    assert_eq!(oversized.status, 413);
+    assert_eq!(oversized.error_code(), "RESOURCE_EXHAUSTED");
+
+    let scrape = server.metrics.encode();
+    assert!(
+        scrape.contains("asherah_requests_rejected_total{reason=\"partition_rate\"} 1"),
+        "{scrape}"
+    );
+    assert!(
+        scrape.contains("asherah_requests_rejected_total{reason=\"request_size\"} 1"),
+        "{scrape}"
+    );
+}
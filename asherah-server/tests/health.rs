@@ -0,0 +1,236 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! `grpc.health.v1.Health` status transitions driven by the probe loop, and
+//! server reflection listing the registered services.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use asherah_server::health::{ProbeSchedule, SERVICES};
+use asherah_server::proto;
+use tokio::net::UnixStream;
+use tokio::sync::watch;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+use tower::service_fn;
+
+static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+const FAST: ProbeSchedule = ProbeSchedule {
+    interval: Duration::from_millis(20),
+    timeout: Duration::from_millis(500),
+};
+
+struct Server {
+    channel: Channel,
+    shutdown: watch::Sender<bool>,
+    _sock: tempfile::TempDir,
+}
+
+/// Serve AppEncryption, Health (driven by `probe`) and reflection on a temp
+/// Unix socket, the way `main` registers them.
+async fn start_server<F>(probe: F) -> Server
+where
+    F: Fn() -> anyhow::Result<()> + Send + Sync + 'static,
+{
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("health-service".to_string()),
+        product_id: Some("health-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _applied) = asherah_config::factory_from_config(&config).unwrap();
+    let svc = asherah_server::service::AppEncryptionService::new(factory);
+    let (shutdown, shutdown_rx) = watch::channel(false);
+    let (health, _task) = asherah_server::health::spawn_checker(probe, FAST, shutdown_rx);
+    let (reflection_v1, reflection_v1alpha) = asherah_server::reflection_services().unwrap();
+
+    let dir = tempfile::tempdir().unwrap();
+    let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+    let sock = dir.path().join(format!("health-{n}.sock"));
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(proto::app_encryption_server::AppEncryptionServer::new(svc))
+            .add_service(health)
+            .add_service(reflection_v1)
+            .add_service(reflection_v1alpha)
+            .serve_with_incoming(tokio_stream::wrappers::UnixListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    Server {
+        channel: connect(sock).await,
+        shutdown,
+        _sock: dir,
+    }
+}
+
+async fn connect(sock: PathBuf) -> Channel {
+    Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap()
+}
+
+async fn check(channel: &Channel, service: &str) -> Result<ServingStatus, tonic::Status> {
+    let resp = HealthClient::new(channel.clone())
+        .check(HealthCheckRequest {
+            service: service.to_string(),
+        })
+        .await?;
+    Ok(resp.into_inner().status())
+}
+
+/// Poll `Check` until every service reports `want`.
+async fn wait_for(channel: &Channel, want: ServingStatus) {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        let mut all = true;
+        for service in SERVICES {
+            all &= check(channel, service).await.unwrap() == want;
+        }
+        if all {
+            return;
+        }
+        assert!(
+            tokio::time::Instant::now() < deadline,
+            "health never reached {want:?}"
+        );
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn factory_probe_reports_serving() {
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("probe-service".to_string()),
+        product_id: Some("probe-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _applied) = asherah_config::factory_from_config(&config).unwrap();
+    let server = start_server(move || asherah_server::health::probe_factory(&factory)).await;
+    wait_for(&server.channel, ServingStatus::Serving).await;
+    assert_eq!(
+        check(&server.channel, "asherah.apps.server.AppEncryption")
+            .await
+            .unwrap(),
+        ServingStatus::Serving
+    );
+    let unknown = check(&server.channel, "no.such.Service").await.unwrap_err();
+    assert_eq!(unknown.code(), tonic::Code::NotFound);
+}
+
+#[tokio::test]
+async fn status_follows_probe_failures_and_recovery() {
+    let healthy = Arc::new(AtomicBool::new(false));
+    let probe_state = Arc::clone(&healthy);
+    let server = start_server(move || {
+        if probe_state.load(Ordering::SeqCst) {
+            Ok(())
+        } else {
+            anyhow::bail!("metastore unreachable")
+        }
+    })
+    .await;
+
+    wait_for(&server.channel, ServingStatus::NotServing).await;
+    healthy.store(true, Ordering::SeqCst);
+    wait_for(&server.channel, ServingStatus::Serving).await;
+    healthy.store(false, Ordering::SeqCst);
+    wait_for(&server.channel, ServingStatus::NotServing).await;
+}
+
+#[tokio::test]
+async fn slow_probe_reports_not_serving() {
+    let slow = Arc::new(AtomicBool::new(false));
+    let probe_state = Arc::clone(&slow);
+    let server = start_server(move || {
+        if probe_state.load(Ordering::SeqCst) {
+            std::thread::sleep(Duration::from_secs(1));
+        }
+        Ok(())
+    })
+    .await;
+    wait_for(&server.channel, ServingStatus::Serving).await;
+    slow.store(true, Ordering::SeqCst);
+    wait_for(&server.channel, ServingStatus::NotServing).await;
+}
+
+#[tokio::test]
+async fn shutdown_flips_to_not_serving_and_ends_watch_streams() {
+    let server = start_server(|| Ok(())).await;
+    wait_for(&server.channel, ServingStatus::Serving).await;
+
+    let mut updates = HealthClient::new(server.channel.clone())
+        .watch(HealthCheckRequest {
+            service: String::new(),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    let first = updates.next().await.unwrap().unwrap();
+    assert_eq!(first.status(), ServingStatus::Serving);
+
+    server.shutdown.send(true).unwrap();
+    let last = tokio::time::timeout(Duration::from_secs(5), async {
+        let mut last = None;
+        while let Some(update) = updates.next().await {
+            last = Some(update.unwrap().status());
+        }
+        last
+    })
+    .await
+    .expect("watch stream should end once shutdown starts");
+    assert_eq!(last, Some(ServingStatus::NotServing));
+    wait_for(&server.channel, ServingStatus::NotServing).await;
+}
+
+#[tokio::test]
+async fn reflection_lists_registered_services() {
+    use tonic_reflection::pb::v1::server_reflection_client::ServerReflectionClient;
+    use tonic_reflection::pb::v1::server_reflection_request::MessageRequest;
+    use tonic_reflection::pb::v1::server_reflection_response::MessageResponse;
+    use tonic_reflection::pb::v1::ServerReflectionRequest;
+
+    let server = start_server(|| Ok(())).await;
+    let request = ServerReflectionRequest {
+        host: String::new(),
+        message_request: Some(MessageRequest::ListServices(String::new())),
+    };
+    let mut responses = ServerReflectionClient::new(server.channel.clone())
+        .server_reflection_info(tokio_stream::iter([request]))
+        .await
+        .unwrap()
+        .into_inner();
+    let response = responses.next().await.unwrap().unwrap();
+    let Some(MessageResponse::ListServicesResponse(list)) = response.message_response else {
+        panic!("unexpected reflection response: {response:?}");
+    };
+    let names: Vec<_> = list.service.into_iter().map(|s| s.name).collect();
+    for expected in [
+        "asherah.apps.server.AppEncryption",
+        "grpc.health.v1.Health",
+        "grpc.reflection.v1.ServerReflection",
+    ] {
+        assert!(
+            names.iter().any(|n| n == expected),
+            "{expected} missing from {names:?}"
+        );
+    }
+}
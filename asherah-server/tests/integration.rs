@@ -753,3 +753,214 @@ async fn test_concurrent_shared_partition_async() {
     }
     drop(std::fs::remove_file(&sock));
 }
+
+// ============================================================
+// Unary and batch RPCs
+// ============================================================
+
+fn encrypt_req(partition: &str, data: &[u8]) -> proto::EncryptRequest {
+    proto::EncryptRequest {
+        partition_id: partition.to_string(),
+        data: data.to_vec(),
+    }
+}
+
+fn decrypt_req(partition: &str, drr: proto::DataRowRecord) -> proto::DecryptRequest {
+    proto::DecryptRequest {
+        partition_id: partition.to_string(),
+        data_row_record: Some(drr),
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unary_roundtrip() {
+    let sock = socket_path();
+    let _server = start_server(&sock).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut client = connect(sock.clone()).await;
+
+    let drr = client
+        .encrypt(encrypt_req("unary-partition", b"no handshake"))
+        .await
+        .unwrap()
+        .into_inner()
+        .data_row_record
+        .expect("missing DRR");
+    let plaintext = client
+        .decrypt(decrypt_req("unary-partition", drr))
+        .await
+        .unwrap()
+        .into_inner()
+        .data;
+    assert_eq!(plaintext, b"no handshake");
+
+    drop(std::fs::remove_file(&sock));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unary_and_session_records_interoperate() {
+    let sock = socket_path();
+    let _server = start_server(&sock).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut client = connect(sock.clone()).await;
+
+    let (tx, mut resp) = open_session(&mut client).await;
+    do_get_session(&tx, &mut resp, "shared-partition").await;
+    let from_session = do_encrypt(&tx, &mut resp, b"via session").await;
+
+    let unary = client
+        .decrypt(decrypt_req("shared-partition", from_session))
+        .await
+        .unwrap();
+    assert_eq!(unary.into_inner().data, b"via session");
+
+    let from_unary = client
+        .encrypt(encrypt_req("shared-partition", b"via unary"))
+        .await
+        .unwrap()
+        .into_inner()
+        .data_row_record
+        .unwrap();
+    assert_eq!(do_decrypt(&tx, &mut resp, from_unary).await, b"via unary");
+
+    drop(tx);
+    drop(std::fs::remove_file(&sock));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_unary_status_codes() {
+    let sock = socket_path();
+    let _server = start_server(&sock).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut client = connect(sock.clone()).await;
+
+    let empty = client.encrypt(encrypt_req("", b"x")).await.unwrap_err();
+    assert_eq!(empty.code(), tonic::Code::InvalidArgument);
+    assert_eq!(empty.message(), "partition_id is empty");
+
+    let missing = client
+        .decrypt(proto::DecryptRequest {
+            partition_id: "p".to_string(),
+            data_row_record: None,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), tonic::Code::InvalidArgument);
+    assert_eq!(missing.message(), "decrypt request missing data_row_record");
+
+    // A record is bound to its partition: decrypting it elsewhere fails.
+    let drr = client
+        .encrypt(encrypt_req("owner", b"tenant data"))
+        .await
+        .unwrap()
+        .into_inner()
+        .data_row_record
+        .unwrap();
+    let wrong = client
+        .decrypt(decrypt_req("intruder", drr))
+        .await
+        .unwrap_err();
+    assert_eq!(wrong.code(), tonic::Code::Internal);
+    assert!(wrong.message().starts_with("decrypt failed"), "{wrong:?}");
+
+    drop(std::fs::remove_file(&sock));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_roundtrip_with_per_item_errors() {
+    use proto::decrypt_result::Result as DecryptItem;
+    use proto::encrypt_result::Result as EncryptItem;
+
+    let sock = socket_path();
+    let _server = start_server(&sock).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut client = connect(sock.clone()).await;
+
+    let encrypted = client
+        .encrypt_batch(proto::EncryptBatchRequest {
+            requests: vec![
+                encrypt_req("tenant-a", b"first"),
+                encrypt_req("bad\npartition", b"rejected"),
+                encrypt_req("tenant-b", b"second"),
+                encrypt_req("tenant-a", b"third"),
+            ],
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .results;
+    assert_eq!(encrypted.len(), 4);
+    match &encrypted[1].result {
+        Some(EncryptItem::ErrorResponse(e)) => {
+            assert_eq!(e.message, "partition_id contains a control character");
+        }
+        other => panic!("expected ErrorResponse, got {other:?}"),
+    }
+
+    let mut requests = Vec::new();
+    for (i, partition) in [(0, "tenant-a"), (2, "tenant-b"), (3, "tenant-a")] {
+        let Some(EncryptItem::EncryptResponse(r)) = encrypted[i].result.clone() else {
+            panic!("item {i} failed: {:?}", encrypted[i]);
+        };
+        requests.push(decrypt_req(partition, r.data_row_record.unwrap()));
+    }
+    requests.push(proto::DecryptRequest {
+        partition_id: "tenant-a".to_string(),
+        data_row_record: None,
+    });
+    let decrypted = client
+        .decrypt_batch(proto::DecryptBatchRequest { requests })
+        .await
+        .unwrap()
+        .into_inner()
+        .results;
+    let outcomes: Vec<_> = decrypted
+        .into_iter()
+        .map(|r| match r.result {
+            Some(DecryptItem::DecryptResponse(d)) => Ok(d.data),
+            Some(DecryptItem::ErrorResponse(e)) => Err(e.message),
+            None => panic!("empty batch result"),
+        })
+        .collect();
+    assert_eq!(
+        outcomes,
+        vec![
+            Ok(b"first".to_vec()),
+            Ok(b"second".to_vec()),
+            Ok(b"third".to_vec()),
+            Err("decrypt request missing data_row_record".to_string()),
+        ]
+    );
+
+    drop(std::fs::remove_file(&sock));
+}
+
+#[tokio::test]
+#[serial]
+async fn test_batch_size_limit() {
+    let sock = socket_path();
+    let _server = start_server(&sock).await;
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let mut client = connect(sock.clone()).await;
+
+    let empty = client
+        .encrypt_batch(proto::EncryptBatchRequest { requests: vec![] })
+        .await
+        .unwrap();
+    assert!(empty.into_inner().results.is_empty());
+
+    let requests = (0..=asherah_server::service::MAX_BATCH_LEN)
+        .map(|_| encrypt_req("p", b"x"))
+        .collect();
+    let err = client
+        .encrypt_batch(proto::EncryptBatchRequest { requests })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    drop(std::fs::remove_file(&sock));
+}
@@ -0,0 +1,286 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! Admission limits: per-partition and per-connection rate limits, the
+//! concurrent stream cap and the request size cap, all refusing with
+//! RESOURCE_EXHAUSTED and counted in metrics.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use asherah_server::limits::{LimitOptions, Limiter, RateLimit, RequestSizeLayer};
+use asherah_server::metrics::Metrics;
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::session_response::Response;
+use asherah_server::proto::{Encrypt, GetSession, SessionRequest};
+use tokio::net::UnixStream;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+
+/// Practically no refill within a test.
+fn burst_only(burst: u32) -> Option<RateLimit> {
+    Some(RateLimit {
+        per_second: 0.001,
+        burst,
+    })
+}
+
+struct Server {
+    sock: PathBuf,
+    metrics: Arc<Metrics>,
+    _dir: tempfile::TempDir,
+}
+
+async fn start_server(opts: LimitOptions) -> Server {
+    let metrics = Arc::new(Metrics::new());
+    let limiter = Arc::new(Limiter::new(&opts, Some(Arc::clone(&metrics))));
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("limits-service".to_string()),
+        product_id: Some("limits-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _) = asherah_server::service::factory_from_config(&config).unwrap();
+    let svc = asherah_server::service::AppEncryptionService::new(factory)
+        .with_limiter(Arc::clone(&limiter));
+
+    let dir = tempfile::tempdir().unwrap();
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .layer(RequestSizeLayer::new(limiter))
+            .add_service(proto::app_encryption_server::AppEncryptionServer::new(svc))
+            .serve_with_incoming(asherah_server::listener::unix_incoming(listener))
+            .await
+            .unwrap();
+    });
+    Server {
+        sock,
+        metrics,
+        _dir: dir,
+    }
+}
+
+/// A new client connection.
+async fn connect(server: &Server) -> AppEncryptionClient<Channel> {
+    let sock = server.sock.clone();
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    AppEncryptionClient::new(channel)
+}
+
+async fn encrypt(
+    client: &mut AppEncryptionClient<Channel>,
+    partition_id: &str,
+    data: &[u8],
+) -> Result<(), tonic::Status> {
+    client
+        .encrypt(proto::EncryptRequest {
+            partition_id: partition_id.to_string(),
+            data: data.to_vec(),
+        })
+        .await
+        .map(drop)
+}
+
+fn session_request(request: Request) -> SessionRequest {
+    SessionRequest {
+        request: Some(request),
+    }
+}
+
+fn assert_exhausted(result: Result<(), tonic::Status>) {
+    let err = result.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted, "{err:?}");
+}
+
+#[tokio::test]
+async fn partition_rate_limit_applies_across_rpcs_and_streams() {
+    let server = start_server(LimitOptions {
+        partition: burst_only(2),
+        ..LimitOptions::default()
+    })
+    .await;
+    let mut client = connect(&server).await;
+    encrypt(&mut client, "hot", b"1").await.unwrap();
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut responses = client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    for request in [
+        Request::GetSession(GetSession {
+            partition_id: "hot".to_string(),
+        }),
+        Request::Encrypt(Encrypt {
+            data: b"2".to_vec(),
+        }),
+    ] {
+        tx.send(session_request(request)).await.unwrap();
+        let response = responses.next().await.unwrap().unwrap().response;
+        assert!(!matches!(response, Some(Response::ErrorResponse(_))));
+    }
+    tx.send(session_request(Request::Encrypt(Encrypt {
+        data: b"3".to_vec(),
+    })))
+    .await
+    .unwrap();
+    match responses.next().await.unwrap().unwrap().response {
+        Some(Response::ErrorResponse(e)) => {
+            assert!(e.message.starts_with("resource exhausted"), "{}", e.message);
+        }
+        other => panic!("expected a rejection, got {other:?}"),
+    }
+
+    // Even from a fresh connection: the limit is per partition.
+    let mut other = connect(&server).await;
+    assert_exhausted(encrypt(&mut other, "hot", b"4").await);
+    encrypt(&mut other, "cold", b"5").await.unwrap();
+
+    let scrape = server.metrics.encode();
+    assert!(
+        scrape.contains("asherah_requests_rejected_total{reason=\"partition_rate\"} 2"),
+        "{scrape}"
+    );
+}
+
+#[tokio::test]
+async fn connection_rate_limit_is_per_connection() {
+    let server = start_server(LimitOptions {
+        connection: burst_only(2),
+        ..LimitOptions::default()
+    })
+    .await;
+    let mut noisy = connect(&server).await;
+    encrypt(&mut noisy, "a", b"1").await.unwrap();
+    encrypt(&mut noisy, "b", b"2").await.unwrap();
+    assert_exhausted(encrypt(&mut noisy, "c", b"3").await);
+
+    let results = noisy
+        .encrypt_batch(proto::EncryptBatchRequest {
+            requests: vec![proto::EncryptRequest {
+                partition_id: "d".to_string(),
+                data: b"4".to_vec(),
+            }],
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .results;
+    assert!(matches!(
+        results[0].result,
+        Some(proto::encrypt_result::Result::ErrorResponse(_))
+    ));
+
+    let mut quiet = connect(&server).await;
+    encrypt(&mut quiet, "a", b"5").await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_cap_refuses_extra_streams_until_one_ends() {
+    let server = start_server(LimitOptions {
+        max_streams: Some(1),
+        ..LimitOptions::default()
+    })
+    .await;
+    let mut client = connect(&server).await;
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut first = client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx.send(session_request(Request::GetSession(GetSession {
+        partition_id: "p".to_string(),
+    })))
+    .await
+    .unwrap();
+    first.next().await.unwrap().unwrap();
+
+    let (_tx2, rx2) = tokio::sync::mpsc::channel::<SessionRequest>(1);
+    let err = client.session(ReceiverStream::new(rx2)).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+    // Unary calls don't count against the cap.
+    encrypt(&mut client, "p", b"unary").await.unwrap();
+
+    drop(tx);
+    assert!(first.next().await.is_none());
+    // The finished task is reaped on the next open, which may race its
+    // close path.
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        let (_tx3, rx3) = tokio::sync::mpsc::channel::<SessionRequest>(1);
+        match client.session(ReceiverStream::new(rx3)).await {
+            Ok(_) => break,
+            Err(e) if tokio::time::Instant::now() < deadline => {
+                assert_eq!(e.code(), tonic::Code::ResourceExhausted);
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+            Err(e) => panic!("stream slot never freed: {e:?}"),
+        }
+    }
+
+    let scrape = server.metrics.encode();
+    assert!(
+        scrape.contains("asherah_requests_rejected_total{reason=\"streams\"}"),
+        "{scrape}"
+    );
+}
+
+#[tokio::test]
+async fn oversized_requests_are_refused() {
+    let server = start_server(LimitOptions {
+        max_request_bytes: 1024,
+        ..LimitOptions::default()
+    })
+    .await;
+    let mut client = connect(&server).await;
+    encrypt(&mut client, "p", &[7; 512]).await.unwrap();
+    assert_exhausted(encrypt(&mut client, "p", &[7; 2048]).await);
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut responses = client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx.send(session_request(Request::GetSession(GetSession {
+        partition_id: "p".to_string(),
+    })))
+    .await
+    .unwrap();
+    responses.next().await.unwrap().unwrap();
+    tx.send(session_request(Request::Encrypt(Encrypt {
+        data: vec![7; 2048],
+    })))
+    .await
+    .unwrap();
+    let err = responses.next().await.unwrap().unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+    // The connection itself stays usable.
+    encrypt(&mut client, "p", b"small").await.unwrap();
+    let scrape = server.metrics.encode();
+    assert!(
+        scrape.contains("asherah_requests_rejected_total{reason=\"request_size\"} 2"),
+        "{scrape}"
+    );
+}
@@ -0,0 +1,213 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! `--metrics-listen`: core timings and cache events from the installed sink
+//! plus per-RPC status codes from the gRPC layer, scraped over HTTP.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use asherah_server::metrics::{Metrics, RpcMetricsLayer, OPENMETRICS_CONTENT_TYPE};
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::{Encrypt, GetSession, SessionRequest};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, UnixStream};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+
+struct Server {
+    client: AppEncryptionClient<Channel>,
+    metrics_addr: SocketAddr,
+    _shutdown: tokio::sync::watch::Sender<bool>,
+    _dir: tempfile::TempDir,
+}
+
+async fn start_server() -> Server {
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("metrics-service".to_string()),
+        product_id: Some("metrics-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _) = asherah_server::service::factory_from_config(&config).unwrap();
+    let metrics = Arc::new(Metrics::new());
+    metrics.install_sink();
+
+    let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let http = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let metrics_addr = http.local_addr().unwrap();
+    tokio::spawn(asherah_server::metrics::serve(
+        http,
+        Arc::clone(&metrics),
+        shutdown_rx,
+    ));
+
+    let dir = tempfile::tempdir().unwrap();
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    let grpc_svc = proto::app_encryption_server::AppEncryptionServer::new(
+        asherah_server::service::AppEncryptionService::new(factory),
+    );
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .layer(RpcMetricsLayer::new(Some(metrics)))
+            .add_service(grpc_svc)
+            .serve_with_incoming(tokio_stream::wrappers::UnixListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    Server {
+        client: AppEncryptionClient::new(channel),
+        metrics_addr,
+        _shutdown: shutdown_tx,
+        _dir: dir,
+    }
+}
+
+/// One HTTP/1.1 request; returns the raw response (head and body).
+async fn http_get(addr: SocketAddr, method: &str, path: &str) -> String {
+    let mut stream = TcpStream::connect(addr).await.unwrap();
+    let request = format!("{method} {path} HTTP/1.1\r\nHost: metrics\r\nConnection: close\r\n\r\n");
+    stream.write_all(request.as_bytes()).await.unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).await.unwrap();
+    response
+}
+
+/// Scrape until every line in `expected` shows up (the core's sink is
+/// drained on a background thread), or fail with the last scrape.
+async fn scrape_until(addr: SocketAddr, expected: &[&str]) -> String {
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+    loop {
+        let scrape = http_get(addr, "GET", "/metrics").await;
+        if expected.iter().all(|line| scrape.contains(line)) {
+            return scrape;
+        }
+        if tokio::time::Instant::now() > deadline {
+            panic!("missing {expected:?} in scrape:\n{scrape}");
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+#[tokio::test]
+async fn scrape_reports_core_metrics_and_rpc_codes() {
+    let mut server = start_server().await;
+
+    let drr = server
+        .client
+        .encrypt(proto::EncryptRequest {
+            partition_id: "p".to_string(),
+            data: b"counted".to_vec(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .data_row_record;
+    server
+        .client
+        .decrypt(proto::DecryptRequest {
+            partition_id: "p".to_string(),
+            data_row_record: drr,
+        })
+        .await
+        .unwrap();
+    let err = server
+        .client
+        .decrypt(proto::DecryptRequest {
+            partition_id: String::new(),
+            data_row_record: None,
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::InvalidArgument);
+
+    // A Session stream is counted once, when it ends.
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut responses = server
+        .client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    for request in [
+        Request::GetSession(GetSession {
+            partition_id: "p".to_string(),
+        }),
+        Request::Encrypt(Encrypt {
+            data: b"streamed".to_vec(),
+        }),
+    ] {
+        tx.send(SessionRequest {
+            request: Some(request),
+        })
+        .await
+        .unwrap();
+        responses.next().await.unwrap().unwrap();
+    }
+    drop(tx);
+    assert!(responses.next().await.is_none());
+
+    let scrape = scrape_until(
+        server.metrics_addr,
+        &[
+            "grpc_server_handled_total{grpc_service=\"asherah.apps.server.AppEncryption\",grpc_method=\"Encrypt\",grpc_code=\"OK\"} 1",
+            "grpc_server_handled_total{grpc_service=\"asherah.apps.server.AppEncryption\",grpc_method=\"Decrypt\",grpc_code=\"OK\"} 1",
+            "grpc_server_handled_total{grpc_service=\"asherah.apps.server.AppEncryption\",grpc_method=\"Decrypt\",grpc_code=\"INVALID_ARGUMENT\"} 1",
+            "grpc_server_handled_total{grpc_service=\"asherah.apps.server.AppEncryption\",grpc_method=\"Session\",grpc_code=\"OK\"} 1",
+            "asherah_encrypt_duration_seconds_count 2",
+            "asherah_decrypt_duration_seconds_count 1",
+            "asherah_metastore_store_duration_seconds_count",
+            "asherah_cache_events_total{cache=",
+        ],
+    )
+    .await;
+    assert!(scrape.starts_with("HTTP/1.1 200"), "{scrape}");
+    assert!(
+        scrape
+            .to_ascii_lowercase()
+            .contains(&format!("content-type: {OPENMETRICS_CONTENT_TYPE}")),
+        "{scrape}"
+    );
+    assert!(
+        scrape.contains("asherah_metrics_dropped_total 0"),
+        "{scrape}"
+    );
+    assert!(
+        scrape.contains("asherah_decrypt_recovery_total{outcome=\"unrecoverable\"}"),
+        "{scrape}"
+    );
+    assert!(scrape.ends_with("# EOF\n"), "{scrape}");
+}
+
+#[tokio::test]
+async fn only_get_metrics_is_served() {
+    let (_shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+    let http = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = http.local_addr().unwrap();
+    tokio::spawn(asherah_server::metrics::serve(
+        http,
+        Arc::new(Metrics::new()),
+        shutdown_rx,
+    ));
+    let other = http_get(addr, "GET", "/").await;
+    assert!(other.starts_with("HTTP/1.1 404"), "{other}");
+    let post = http_get(addr, "POST", "/metrics").await;
+    assert!(post.starts_with("HTTP/1.1 405"), "{post}");
+}
@@ -0,0 +1,345 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! The server runs on the bindings' `PublicFactory`, so decrypt recovery,
+//! self-heal, cross-RPC key caching, metrics and the config drift guard
+//! behave over gRPC exactly as they do through FFI, Node or Python.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use asherah::builders::{DynKms, DynMetastore};
+use asherah::traits::Metastore;
+use asherah::types::EnvelopeKeyRecord;
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::session_response::Response;
+use asherah_server::proto::{Decrypt, Encrypt, GetSession, SessionRequest};
+use asherah_server::service::{AppEncryptionService, Factory};
+use serial_test::serial;
+use tokio::net::UnixStream;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+
+/// In-memory metastore that counts reads, so tests can tell whether an RPC
+/// was served from the factory's caches.
+#[derive(Default)]
+struct CountingStore {
+    inner: asherah::metastore::InMemoryMetastore,
+    reads: AtomicU64,
+}
+
+impl Metastore for CountingStore {
+    fn load(&self, id: &str, created: i64) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.load(id, created)
+    }
+
+    fn load_latest(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        self.inner.load_latest(id)
+    }
+
+    fn store(&self, id: &str, created: i64, ekr: &EnvelopeKeyRecord) -> anyhow::Result<bool> {
+        self.inner.store(id, created, ekr)
+    }
+}
+
+/// One metastore and KMS shared by several factories, standing in for
+/// servers in different regions that share a DynamoDB global table.
+struct Shared {
+    crypto: Arc<asherah::aead::AES256GCM>,
+    kms: Arc<DynKms>,
+    store: Arc<CountingStore>,
+}
+
+impl Shared {
+    fn new() -> Self {
+        let crypto = Arc::new(asherah::aead::AES256GCM::new());
+        let kms = asherah::kms::StaticKMS::new(crypto.clone(), vec![9_u8; 32]).unwrap();
+        Self {
+            crypto,
+            kms: Arc::new(DynKms(Arc::new(kms))),
+            store: Arc::new(CountingStore::default()),
+        }
+    }
+
+    fn factory(&self, region_suffix: Option<&str>, recovery: &[&str], self_heal: bool) -> Factory {
+        let mut cfg = asherah::Config::new("svc", "prod")
+            .with_self_heal_recovered_keys(self_heal)
+            .with_recovery_region_suffixes(recovery.iter().map(|s| (*s).to_string()).collect());
+        if let Some(suffix) = region_suffix {
+            cfg = cfg.with_region_suffix(suffix);
+        }
+        let store: Arc<dyn Metastore> = self.store.clone();
+        asherah::api::new_session_factory(
+            cfg,
+            Arc::new(DynMetastore(store)),
+            self.kms.clone(),
+            self.crypto.clone(),
+        )
+    }
+
+    fn key_exists(&self, id: &str, created: i64) -> bool {
+        self.store.inner.load(id, created).unwrap().is_some()
+    }
+}
+
+struct Server {
+    client: AppEncryptionClient<Channel>,
+    _dir: tempfile::TempDir,
+}
+
+async fn serve(factory: Factory) -> Server {
+    let dir = tempfile::tempdir().unwrap();
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    let grpc_svc =
+        proto::app_encryption_server::AppEncryptionServer::new(AppEncryptionService::new(factory));
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(grpc_svc)
+            .serve_with_incoming(tokio_stream::wrappers::UnixListenerStream::new(listener))
+            .await
+            .unwrap();
+    });
+    let path: PathBuf = sock;
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = path.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    Server {
+        client: AppEncryptionClient::new(channel),
+        _dir: dir,
+    }
+}
+
+async fn encrypt(server: &mut Server, partition: &str, data: &[u8]) -> proto::DataRowRecord {
+    server
+        .client
+        .encrypt(proto::EncryptRequest {
+            partition_id: partition.to_string(),
+            data: data.to_vec(),
+        })
+        .await
+        .unwrap()
+        .into_inner()
+        .data_row_record
+        .unwrap()
+}
+
+async fn decrypt(
+    server: &mut Server,
+    partition: &str,
+    drr: proto::DataRowRecord,
+) -> Result<Vec<u8>, tonic::Status> {
+    let resp = server
+        .client
+        .decrypt(proto::DecryptRequest {
+            partition_id: partition.to_string(),
+            data_row_record: Some(drr),
+        })
+        .await?;
+    Ok(resp.into_inner().data)
+}
+
+fn parent_meta(drr: &proto::DataRowRecord) -> proto::KeyMeta {
+    drr.key.as_ref().unwrap().parent_key_meta.clone().unwrap()
+}
+
+/// A row written in us-east-1 but labelled with the us-west-2 IK id (the
+/// upstream cross-region defect shape).
+async fn mislabeled_row(shared: &Shared, data: &[u8]) -> proto::DataRowRecord {
+    let mut east = serve(shared.factory(Some("us-east-1"), &[], true)).await;
+    let mut drr = encrypt(&mut east, "p", data).await;
+    assert_eq!(parent_meta(&drr).key_id, "_IK_p_svc_prod_us-east-1");
+    drr.key
+        .as_mut()
+        .unwrap()
+        .parent_key_meta
+        .as_mut()
+        .unwrap()
+        .key_id = "_IK_p_svc_prod_us-west-2".to_string();
+    drr
+}
+
+#[tokio::test]
+async fn suffixed_server_recovers_unsuffixed_rows() {
+    let shared = Shared::new();
+    let mut writer = serve(shared.factory(None, &[], true)).await;
+    let drr = encrypt(&mut writer, "p", b"written before suffixing").await;
+    assert_eq!(parent_meta(&drr).key_id, "_IK_p_svc_prod");
+
+    let mut reader = serve(shared.factory(Some("us-west-2"), &[], true)).await;
+    let pt = decrypt(&mut reader, "p", drr).await.unwrap();
+    assert_eq!(pt, b"written before suffixing");
+}
+
+#[tokio::test]
+async fn recovery_region_suffixes_reach_cross_region_rows_and_self_heal() {
+    let shared = Shared::new();
+    let drr = mislabeled_row(&shared, b"cross region").await;
+    let created = parent_meta(&drr).created;
+
+    let mut west = serve(shared.factory(Some("us-west-2"), &[], true)).await;
+    let err = decrypt(&mut west, "p", drr.clone()).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::Internal);
+    assert!(!shared.key_exists("_IK_p_svc_prod_us-west-2", created));
+
+    let mut recovering = serve(shared.factory(Some("us-west-2"), &["us-east-1"], true)).await;
+    assert_eq!(
+        decrypt(&mut recovering, "p", drr).await.unwrap(),
+        b"cross region"
+    );
+    assert!(shared.key_exists("_IK_p_svc_prod_us-west-2", created));
+}
+
+#[tokio::test]
+async fn self_heal_can_be_turned_off() {
+    let shared = Shared::new();
+    let drr = mislabeled_row(&shared, b"read-only decryptor").await;
+    let created = parent_meta(&drr).created;
+
+    let mut reader = serve(shared.factory(Some("us-west-2"), &["us-east-1"], false)).await;
+    assert_eq!(
+        decrypt(&mut reader, "p", drr).await.unwrap(),
+        b"read-only decryptor"
+    );
+    assert!(!shared.key_exists("_IK_p_svc_prod_us-west-2", created));
+}
+
+#[tokio::test]
+async fn keys_stay_cached_across_rpcs_and_session_streams() {
+    let shared = Shared::new();
+    let mut server = serve(shared.factory(None, &[], true)).await;
+    let drr = encrypt(&mut server, "p", b"warm up").await;
+    decrypt(&mut server, "p", drr).await.unwrap();
+    let warm = shared.store.reads.load(Ordering::SeqCst);
+
+    for i in 0..10_u8 {
+        let drr = encrypt(&mut server, "p", &[i]).await;
+        assert_eq!(decrypt(&mut server, "p", drr).await.unwrap(), [i]);
+    }
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut responses = server
+        .client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx.send(SessionRequest {
+        request: Some(Request::GetSession(GetSession {
+            partition_id: "p".to_string(),
+        })),
+    })
+    .await
+    .unwrap();
+    responses.next().await.unwrap().unwrap();
+    tx.send(SessionRequest {
+        request: Some(Request::Encrypt(Encrypt {
+            data: b"streamed".to_vec(),
+        })),
+    })
+    .await
+    .unwrap();
+    let Some(Response::EncryptResponse(enc)) = responses.next().await.unwrap().unwrap().response
+    else {
+        panic!("expected an encrypt response");
+    };
+    tx.send(SessionRequest {
+        request: Some(Request::Decrypt(Decrypt {
+            data_row_record: enc.data_row_record,
+        })),
+    })
+    .await
+    .unwrap();
+    let Some(Response::DecryptResponse(dec)) = responses.next().await.unwrap().unwrap().response
+    else {
+        panic!("expected a decrypt response");
+    };
+    assert_eq!(dec.data, b"streamed");
+
+    assert_eq!(shared.store.reads.load(Ordering::SeqCst), warm);
+}
+
+#[derive(Clone, Default)]
+struct CountingSink {
+    encrypts: Arc<AtomicU64>,
+    decrypts: Arc<AtomicU64>,
+}
+
+impl asherah::metrics::MetricsSink for CountingSink {
+    fn encrypt(&self, _dur: std::time::Duration) {
+        self.encrypts.fetch_add(1, Ordering::SeqCst);
+    }
+
+    fn decrypt(&self, _dur: std::time::Duration) {
+        self.decrypts.fetch_add(1, Ordering::SeqCst);
+    }
+}
+
+fn memory_config() -> asherah_config::ConfigOptions {
+    asherah_config::ConfigOptions {
+        service_name: Some("parity-service".to_string()),
+        product_id: Some("parity-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    }
+}
+
+#[tokio::test]
+#[serial]
+async fn server_factory_reports_metrics() {
+    let sink = CountingSink::default();
+    asherah::metrics::set_sink(sink.clone());
+    asherah::metrics::set_enabled(true);
+
+    let (factory, _) = asherah_server::service::factory_from_config(&memory_config()).unwrap();
+    let mut server = serve(factory).await;
+    let drr = encrypt(&mut server, "p", b"observed").await;
+    decrypt(&mut server, "p", drr).await.unwrap();
+
+    asherah::metrics::set_enabled(false);
+    asherah::metrics::clear_sink();
+    assert_eq!(sink.encrypts.load(Ordering::SeqCst), 1);
+    assert_eq!(sink.decrypts.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+#[serial]
+fn server_factory_enforces_the_config_drift_guard() {
+    let (factory, _) = asherah_server::service::factory_from_config(&memory_config()).unwrap();
+
+    // Another process pointing a different KMS at the same metastore must
+    // be refused by the guard the server's factory recorded at startup.
+    let drifted = asherah_config::ConfigOptions {
+        kms: Some("aws".to_string()),
+        region_map: Some([("us-west-2".to_string(), "arn:aws:kms:x".to_string())].into()),
+        preferred_region: Some("us-west-2".to_string()),
+        ..memory_config()
+    };
+    let (resolved, _) = drifted.resolve().unwrap();
+    let err = asherah::config_drift_guard::enforce_config_drift_guard(
+        factory.metastore().as_ref(),
+        &resolved,
+        drifted.config_drift_guard_options(),
+        None,
+    )
+    .unwrap_err();
+    assert!(
+        format!("{err:#}").contains("drift guard mismatch"),
+        "{err:#}"
+    );
+}
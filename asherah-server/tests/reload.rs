@@ -0,0 +1,264 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used)]
+//! Hot reload: new settings swap the factory new sessions come from while
+//! open streams finish on the old one, limits switch in place, and changes
+//! the config drift guard protects are refused without touching anything.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use asherah_server::limits::{LimitOptions, Limiter, RateLimit};
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::session_response::Response;
+use asherah_server::proto::{Decrypt, Encrypt, GetSession, SessionRequest};
+use asherah_server::reload::{Reloaded, Reloader, Settings};
+use asherah_server::service::AppEncryptionService;
+use tokio::net::UnixStream;
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+
+struct Server {
+    sock: PathBuf,
+    svc: Arc<AppEncryptionService>,
+    limiter: Arc<Limiter>,
+    settings: Settings,
+    dir: tempfile::TempDir,
+}
+
+fn options(dir: &tempfile::TempDir) -> asherah_config::ConfigOptions {
+    asherah_config::ConfigOptions {
+        service_name: Some("reload-service".to_string()),
+        product_id: Some("reload-product".to_string()),
+        metastore: Some("sqlite".to_string()),
+        connection_string: Some(dir.path().join("keys.db").display().to_string()),
+        kms: Some("test-debug-static".to_string()),
+        session_cache_max_size: Some(100),
+        ..Default::default()
+    }
+}
+
+async fn start_server() -> Server {
+    let dir = tempfile::tempdir().unwrap();
+    let settings = Settings {
+        options: options(&dir),
+        limits: LimitOptions::default(),
+    };
+    let limiter = Arc::new(Limiter::new(&settings.limits, None));
+    let (factory, _) = asherah_server::service::factory_from_config(&settings.options).unwrap();
+    let svc = Arc::new(AppEncryptionService::new(factory).with_limiter(Arc::clone(&limiter)));
+
+    let sock = dir.path().join("asherah.sock");
+    let listener = tokio::net::UnixListener::bind(&sock).unwrap();
+    let grpc = proto::app_encryption_server::AppEncryptionServer::from_arc(Arc::clone(&svc));
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(grpc)
+            .serve_with_incoming(asherah_server::listener::unix_incoming(listener))
+            .await
+            .unwrap();
+    });
+    Server {
+        sock,
+        svc,
+        limiter,
+        settings,
+        dir,
+    }
+}
+
+impl Server {
+    fn reloader(&self) -> Reloader {
+        Reloader::new(
+            self.settings.clone(),
+            Arc::clone(&self.svc),
+            Arc::clone(&self.limiter),
+        )
+    }
+}
+
+async fn connect(server: &Server) -> AppEncryptionClient<Channel> {
+    let sock = server.sock.clone();
+    let channel = Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap();
+    AppEncryptionClient::new(channel)
+}
+
+fn session_request(request: Request) -> SessionRequest {
+    SessionRequest {
+        request: Some(request),
+    }
+}
+
+async fn encrypt(
+    client: &mut AppEncryptionClient<Channel>,
+    partition_id: &str,
+) -> Result<proto::DataRowRecord, tonic::Status> {
+    let response = client
+        .encrypt(proto::EncryptRequest {
+            partition_id: partition_id.to_string(),
+            data: b"payload".to_vec(),
+        })
+        .await?;
+    Ok(response.into_inner().data_row_record.unwrap())
+}
+
+#[tokio::test]
+async fn open_streams_finish_on_the_old_factory() {
+    let server = start_server().await;
+    let mut client = connect(&server).await;
+    let before = server.svc.factory();
+
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    let mut responses = client
+        .session(ReceiverStream::new(rx))
+        .await
+        .unwrap()
+        .into_inner();
+    tx.send(session_request(Request::GetSession(GetSession {
+        partition_id: "p".to_string(),
+    })))
+    .await
+    .unwrap();
+    responses.next().await.unwrap().unwrap();
+    tx.send(session_request(Request::Encrypt(Encrypt {
+        data: b"before reload".to_vec(),
+    })))
+    .await
+    .unwrap();
+    let drr = match responses.next().await.unwrap().unwrap().response {
+        Some(Response::EncryptResponse(r)) => r.data_row_record.unwrap(),
+        other => panic!("expected an encrypt response, got {other:?}"),
+    };
+
+    let mut next = server.settings.clone();
+    next.options.session_cache_max_size = Some(7);
+    let reloaded = server.reloader().apply(next).await.unwrap();
+    assert_eq!(
+        reloaded,
+        Reloaded {
+            factory: true,
+            ..Reloaded::default()
+        }
+    );
+    assert!(!Arc::ptr_eq(&before, &server.svc.factory()));
+
+    // The open stream still works, on the factory it started with.
+    tx.send(session_request(Request::Decrypt(Decrypt {
+        data_row_record: Some(drr.clone()),
+    })))
+    .await
+    .unwrap();
+    match responses.next().await.unwrap().unwrap().response {
+        Some(Response::DecryptResponse(r)) => assert_eq!(r.data, b"before reload"),
+        other => panic!("expected a decrypt response, got {other:?}"),
+    }
+
+    // New calls use the new factory, over the same metastore.
+    let decrypted = client
+        .decrypt(proto::DecryptRequest {
+            partition_id: "p".to_string(),
+            data_row_record: Some(drr),
+        })
+        .await
+        .unwrap()
+        .into_inner();
+    assert_eq!(decrypted.data, b"before reload");
+}
+
+#[tokio::test]
+async fn identity_changes_are_refused() {
+    let server = start_server().await;
+    let before = server.svc.factory();
+    let mut reloader = server.reloader();
+
+    let mut moved = server.settings.clone();
+    moved.options.service_name = Some("other-service".to_string());
+    moved.options.connection_string =
+        Some(server.dir.path().join("other.db").display().to_string());
+    moved.options.session_cache_max_size = Some(7);
+    let err = reloader.apply(moved).await.unwrap_err().to_string();
+    assert!(err.contains("service name, metastore"), "{err}");
+    assert!(err.contains("restart the server"), "{err}");
+    assert!(Arc::ptr_eq(&before, &server.svc.factory()));
+
+    // Refused settings aren't half-applied: the next reload still compares
+    // against what's running.
+    let mut tuned = server.settings.clone();
+    tuned.options.session_cache_max_size = Some(7);
+    assert!(reloader.apply(tuned).await.unwrap().factory);
+}
+
+#[tokio::test]
+async fn memory_metastore_keeps_its_factory() {
+    let server = start_server().await;
+    let mut running = server.settings.clone();
+    running.options.metastore = Some("memory".to_string());
+    running.options.connection_string = None;
+    let mut reloader = Reloader::new(
+        running.clone(),
+        Arc::clone(&server.svc),
+        Arc::clone(&server.limiter),
+    );
+    let before = server.svc.factory();
+
+    let mut next = running;
+    next.options.session_cache_max_size = Some(7);
+    let err = reloader.apply(next.clone()).await.unwrap_err().to_string();
+    assert!(err.contains("in-memory metastore"), "{err}");
+    assert!(Arc::ptr_eq(&before, &server.svc.factory()));
+
+    // Limits don't need a new factory.
+    let mut limited = next;
+    limited.options.session_cache_max_size = Some(100);
+    limited.limits.max_streams = Some(4);
+    assert!(reloader.apply(limited).await.unwrap().limits);
+}
+
+#[tokio::test]
+async fn limits_and_verbosity_apply_in_place() {
+    let server = start_server().await;
+    let mut client = connect(&server).await;
+    let before = server.svc.factory();
+    let verbose = Arc::new(AtomicBool::new(false));
+    let hook = Arc::clone(&verbose);
+    let mut reloader = server
+        .reloader()
+        .on_verbose(move |on| hook.store(on, Ordering::Relaxed));
+
+    encrypt(&mut client, "hot").await.unwrap();
+    encrypt(&mut client, "hot").await.unwrap();
+
+    let mut next = server.settings.clone();
+    next.options.verbose = Some(true);
+    next.limits.partition = Some(RateLimit {
+        per_second: 0.001,
+        burst: 1,
+    });
+    let reloaded = server.reloader().apply(next.clone()).await.unwrap();
+    assert_eq!(reloaded.to_string(), "limits, verbosity");
+    assert!(!reloaded.factory);
+    assert!(Arc::ptr_eq(&before, &server.svc.factory()));
+
+    encrypt(&mut client, "hot").await.unwrap();
+    let err = encrypt(&mut client, "hot").await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::ResourceExhausted);
+
+    assert!(reloader.apply(next.clone()).await.unwrap().verbose);
+    assert!(verbose.load(Ordering::Relaxed));
+    let unchanged = reloader.apply(next).await.unwrap();
+    assert_eq!(unchanged.to_string(), "nothing changed");
+}
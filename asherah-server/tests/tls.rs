@@ -0,0 +1,255 @@
+#![cfg(unix)]
+#![allow(clippy::panic, clippy::unwrap_used, clippy::expect_used)]
+//! TCP listener with TLS and mutual TLS, served alongside the Unix socket.
+//! Certificates come from `tests/fixtures/tls` (see `generate.sh` there).
+
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use asherah_server::proto;
+use asherah_server::proto::app_encryption_client::AppEncryptionClient;
+use asherah_server::proto::session_request::Request;
+use asherah_server::proto::session_response::Response;
+use asherah_server::proto::{Encrypt, GetSession, SessionRequest};
+use asherah_server::tls::TlsOptions;
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName};
+use tokio::net::{TcpStream, UnixStream};
+use tokio_stream::wrappers::ReceiverStream;
+use tokio_stream::StreamExt;
+use tonic::transport::{Channel, Endpoint, Uri};
+use tower::service_fn;
+
+fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures/tls")
+        .join(name)
+}
+
+fn certs(name: &str) -> Vec<CertificateDer<'static>> {
+    CertificateDer::pem_file_iter(fixture(name))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap()
+}
+
+struct Server {
+    tcp: SocketAddr,
+    sock: PathBuf,
+    _dir: tempfile::TempDir,
+}
+
+/// Start a server on a temp Unix socket plus a TLS listener on an ephemeral
+/// port, the way `main` wires them.
+async fn start_server(client_ca: bool, allowed_sans: &[&str]) -> Server {
+    start_server_with_policy(client_ca, allowed_sans, None).await
+}
+
+async fn start_server_with_policy(
+    client_ca: bool,
+    allowed_sans: &[&str],
+    policy: Option<&str>,
+) -> Server {
+    let config = asherah_config::ConfigOptions {
+        service_name: Some("tls-service".to_string()),
+        product_id: Some("tls-product".to_string()),
+        metastore: Some("memory".to_string()),
+        kms: Some("test-debug-static".to_string()),
+        ..Default::default()
+    };
+    let (factory, _applied) = asherah_config::factory_from_config(&config).unwrap();
+    let mut svc = asherah_server::service::AppEncryptionService::new(factory);
+    let dir = tempfile::tempdir().unwrap();
+    if let Some(policy) = policy {
+        let path = dir.path().join("policy.json");
+        std::fs::write(&path, policy).unwrap();
+        let authorizer = asherah_server::authz::Authorizer::load(path, None).unwrap();
+        svc = svc.with_authorizer(Arc::new(authorizer));
+    }
+    let grpc_svc = proto::app_encryption_server::AppEncryptionServer::new(svc);
+
+    let tls = asherah_server::tls::server_config(&TlsOptions {
+        cert_file: fixture("server.pem"),
+        key_file: fixture("server.key"),
+        client_ca_file: client_ca.then(|| fixture("ca.pem")),
+        allowed_client_sans: allowed_sans.iter().map(|s| (*s).to_string()).collect(),
+    })
+    .unwrap();
+
+    let sock = dir.path().join("asherah.sock");
+    let unix = tokio::net::UnixListener::bind(&sock).unwrap();
+    let tcp_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let tcp = tcp_listener.local_addr().unwrap();
+    let incoming = asherah_server::listener::incoming(
+        unix,
+        Some((tcp_listener, tokio_rustls::TlsAcceptor::from(tls))),
+    );
+    tokio::spawn(async move {
+        tonic::transport::Server::builder()
+            .add_service(grpc_svc)
+            .serve_with_incoming(incoming)
+            .await
+            .unwrap();
+    });
+    Server {
+        tcp,
+        sock,
+        _dir: dir,
+    }
+}
+
+/// Dial the TLS listener, optionally presenting `client` (`<name>.pem` and
+/// `<name>.key` fixtures).
+async fn connect_tls(addr: SocketAddr, client: Option<&str>) -> Result<Channel, String> {
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in certs("ca.pem") {
+        roots.add(cert).unwrap();
+    }
+    let builder = rustls::ClientConfig::builder_with_provider(Arc::new(
+        rustls::crypto::ring::default_provider(),
+    ))
+    .with_safe_default_protocol_versions()
+    .unwrap()
+    .with_root_certificates(roots);
+    let mut config = match client {
+        Some(name) => builder
+            .with_client_auth_cert(
+                certs(&format!("{name}.pem")),
+                PrivateKeyDer::from_pem_file(fixture(&format!("{name}.key"))).unwrap(),
+            )
+            .unwrap(),
+        None => builder.with_no_client_auth(),
+    };
+    config.alpn_protocols = vec![b"h2".to_vec()];
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+
+    Endpoint::try_from("http://localhost")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let connector = connector.clone();
+            async move {
+                let tcp = TcpStream::connect(addr).await?;
+                let tls = connector
+                    .connect(ServerName::try_from("localhost").unwrap(), tcp)
+                    .await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(tls))
+            }
+        }))
+        .await
+        .map_err(|e| format!("{e:?}"))
+}
+
+async fn connect_unix(sock: &Path) -> Channel {
+    let sock = sock.to_path_buf();
+    Endpoint::try_from("http://[::]:50051")
+        .unwrap()
+        .connect_with_connector(service_fn(move |_: Uri| {
+            let path = sock.clone();
+            async move {
+                let stream = UnixStream::connect(path).await?;
+                Ok::<_, std::io::Error>(hyper_util::rt::TokioIo::new(stream))
+            }
+        }))
+        .await
+        .unwrap()
+}
+
+/// GetSession + Encrypt over one Session stream; any failure is an `Err`.
+async fn encrypt_once(channel: Channel, partition: &str) -> Result<Vec<u8>, String> {
+    let mut client = AppEncryptionClient::new(channel);
+    let (tx, rx) = tokio::sync::mpsc::channel(4);
+    tx.send(SessionRequest {
+        request: Some(Request::GetSession(GetSession {
+            partition_id: partition.to_string(),
+        })),
+    })
+    .await
+    .unwrap();
+    tx.send(SessionRequest {
+        request: Some(Request::Encrypt(Encrypt {
+            data: b"over the network".to_vec(),
+        })),
+    })
+    .await
+    .unwrap();
+    let mut responses = client
+        .session(ReceiverStream::new(rx))
+        .await
+        .map_err(|e| e.to_string())?
+        .into_inner();
+    let _session = responses.next().await.ok_or("stream ended")?;
+    match responses.next().await.ok_or("stream ended")? {
+        Ok(proto::SessionResponse {
+            response: Some(Response::EncryptResponse(r)),
+        }) => Ok(r.data_row_record.unwrap().data),
+        other => Err(format!("{other:?}")),
+    }
+}
+
+async fn tls_encrypt(server: &Server, client: Option<&str>) -> Result<Vec<u8>, String> {
+    let channel = connect_tls(server.tcp, client).await?;
+    encrypt_once(channel, "tls-partition").await
+}
+
+#[tokio::test]
+async fn tls_without_client_auth_serves_requests() {
+    let server = start_server(false, &[]).await;
+    assert!(!tls_encrypt(&server, None).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn unix_socket_keeps_working_next_to_tcp() {
+    let server = start_server(true, &[]).await;
+    let unix = encrypt_once(connect_unix(&server.sock).await, "unix-partition").await;
+    assert!(!unix.unwrap().is_empty());
+    assert!(tls_encrypt(&server, Some("client-other")).await.is_ok());
+}
+
+#[tokio::test]
+async fn mtls_requires_a_client_certificate() {
+    let server = start_server(true, &[]).await;
+    assert!(tls_encrypt(&server, None).await.is_err());
+    assert!(tls_encrypt(&server, Some("client-other")).await.is_ok());
+}
+
+#[tokio::test]
+async fn mtls_rejects_certificates_from_other_cas() {
+    let server = start_server(true, &[]).await;
+    assert!(tls_encrypt(&server, Some("client-rogue")).await.is_err());
+}
+
+#[tokio::test]
+async fn allowed_san_list_admits_only_listed_clients() {
+    let server = start_server(true, &["billing.internal"]).await;
+    assert!(tls_encrypt(&server, Some("client-billing")).await.is_ok());
+    assert!(tls_encrypt(&server, Some("client-other")).await.is_err());
+    // Same SAN as the allowed client, but not issued by the client CA.
+    assert!(tls_encrypt(&server, Some("client-rogue")).await.is_err());
+}
+
+#[tokio::test]
+async fn allowed_san_list_matches_uri_sans() {
+    let server = start_server(true, &["spiffe://example.test/billing"]).await;
+    assert!(tls_encrypt(&server, Some("client-billing")).await.is_ok());
+    assert!(tls_encrypt(&server, Some("client-other")).await.is_err());
+}
+
+#[tokio::test]
+async fn authz_policy_identifies_mtls_clients_by_san() {
+    let policy = r#"{"rules": [
+        {"callers": ["san:spiffe://example.test/billing"], "partitions": ["tls-*"]}
+    ]}"#;
+    let server = start_server_with_policy(true, &[], Some(policy)).await;
+    assert!(tls_encrypt(&server, Some("client-billing")).await.is_ok());
+    // Admitted by the handshake, but the policy grants it nothing.
+    let channel = connect_tls(server.tcp, Some("client-other")).await.unwrap();
+    let err = AppEncryptionClient::new(channel)
+        .encrypt(proto::EncryptRequest {
+            partition_id: "tls-partition".to_string(),
+            data: b"denied".to_vec(),
+        })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), tonic::Code::PermissionDenied);
+}
@@ -1,3 +1,4 @@
+use crate::error::ErrorCode;
 use crate::traits::AEAD as AeadTrait;
 use rand::RngCore;
 use rand_chacha::ChaCha20Rng;
@@ -143,6 +144,7 @@ pub fn fast_random_bytes(buf: &mut [u8]) -> anyhow::Result<()> {
 #[cfg(feature = "hardware-crypto")]
 mod backend {
     use super::GCM_NONCE_SIZE;
+    use crate::error::ErrorCode;
     use hardware_rust_crypto::aes_gcm::HardwareAes256GcmKeyState;
     use std::sync::Mutex;
 
@@ -184,11 +186,14 @@ mod backend {
         /// Decrypt `ciphertext || tag || nonce`.
         pub fn decrypt(&self, aad: &[u8], data: &[u8]) -> anyhow::Result<Vec<u8>> {
             if data.len() < GCM_NONCE_SIZE + super::AES256GCM::TAG_SIZE {
-                return Err(anyhow::anyhow!("ciphertext too short"));
+                return Err(ErrorCode::DecryptionFailed.error("ciphertext too short"));
             }
             let state = self.inner.lock().unwrap_or_else(|e| e.into_inner());
             state.decrypt(aad, data).map_err(|e| {
-                anyhow::anyhow!("AES-256-GCM decrypt failed (len={}): {e}", data.len())
+                ErrorCode::DecryptionFailed.error(format!(
+                    "AES-256-GCM decrypt failed (len={}): {e}",
+                    data.len()
+                ))
             })
         }
     }
@@ -211,6 +216,7 @@ mod backend {
 #[cfg(all(feature = "ring-crypto", not(feature = "hardware-crypto")))]
 mod backend {
     use super::{fast_random_bytes, GCM_NONCE_SIZE};
+    use crate::error::ErrorCode;
     use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM};
 
     /// A pre-expanded AES-256-GCM key backed by ring's `LessSafeKey`.
@@ -252,25 +258,25 @@ mod backend {
         /// Decrypt `ciphertext || tag || nonce`.
         pub fn decrypt(&self, aad: &[u8], data: &[u8]) -> anyhow::Result<Vec<u8>> {
             if data.len() < GCM_NONCE_SIZE + super::AES256GCM::TAG_SIZE {
-                return Err(anyhow::anyhow!("ciphertext too short"));
+                return Err(ErrorCode::DecryptionFailed.error("ciphertext too short"));
             }
             let nonce_pos = data.len() - GCM_NONCE_SIZE;
             let (ct_with_tag, nonce_bytes) = data.split_at(nonce_pos);
             let nonce = Nonce::try_assume_unique_for_key(nonce_bytes).map_err(|_| {
-                anyhow::anyhow!(
+                ErrorCode::DecryptionFailed.error(format!(
                     "AES-256-GCM decrypt: invalid nonce (len={})",
                     nonce_bytes.len()
-                )
+                ))
             })?;
             let mut in_out = ct_with_tag.to_vec();
             let pt = self
                 .inner
                 .open_in_place(nonce, Aad::from(aad), &mut in_out)
                 .map_err(|_| {
-                    anyhow::anyhow!(
+                    ErrorCode::DecryptionFailed.error(format!(
                         "AES-256-GCM decrypt: authentication failed (len={})",
                         data.len()
-                    )
+                    ))
                 })?;
             let n = pt.len();
             in_out.truncate(n);
@@ -305,7 +311,7 @@ impl AeadTrait for AES256GCM {
             return Err(anyhow::anyhow!("invalid key size"));
         }
         if data.len() < Self::NONCE_SIZE + Self::TAG_SIZE {
-            return Err(anyhow::anyhow!("ciphertext too short"));
+            return Err(ErrorCode::DecryptionFailed.error("ciphertext too short"));
         }
         Aes256GcmKey::new(key)?.decrypt(&[], data)
     }
@@ -433,6 +433,17 @@ impl Metastore for DynMetastore {
     ) -> Result<(), anyhow::Error> {
         self.0.upsert_config_drift_guard(id, created, ekr)
     }
+    fn load_all(&self, id: &str) -> Result<Vec<crate::types::EnvelopeKeyRecord>, anyhow::Error> {
+        self.0.load_all(id)
+    }
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &crate::types::EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        self.0.replace_key_record(id, created, ekr)
+    }
     fn region_suffix(&self) -> Option<String> {
         self.0.region_suffix()
     }
@@ -504,6 +515,11 @@ pub enum MetastoreConfig {
         endpoint: Option<String>,
         region_suffix: bool,
     },
+    /// Implemented by the embedding application and passed in through
+    /// [`HostBackends`]; `name` identifies it to the config drift guard.
+    Host {
+        name: String,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -525,7 +541,80 @@ pub enum KmsConfig {
         addr: String,
         transit_key: String,
         transit_mount: Option<String>,
+        options: VaultOptions,
+    },
+    /// k-of-n split of every system key across heterogeneous backends; see
+    /// [`crate::kms_quorum`].
+    Quorum {
+        threshold: usize,
+        members: Vec<QuorumMember>,
     },
+    /// Implemented by the embedding application and passed in through
+    /// [`HostBackends`]; `name` identifies it to the config drift guard.
+    Host {
+        name: String,
+    },
+}
+
+/// One backend of a [`KmsConfig::Quorum`]. `name` labels its share in
+/// wrapped system keys, so it must stay stable once keys exist.
+#[derive(Clone, Debug)]
+pub struct QuorumMember {
+    pub name: String,
+    pub kms: KmsConfig,
+}
+
+/// Vault Transit authentication and key-version settings. Every unset field
+/// falls back to the matching `VAULT_*` environment variable, so configs that
+/// only set `VaultAddr`/`VaultTransitKey` keep their env-driven behavior.
+#[derive(Clone, Default)]
+pub struct VaultOptions {
+    /// Pre-acquired client token (`VAULT_TOKEN`). Wins over `auth_method`.
+    pub token: Option<String>,
+    /// `kubernetes`, `approle` or `cert` (`VAULT_AUTH_METHOD`).
+    pub auth_method: Option<String>,
+    /// Role for Kubernetes auth (`VAULT_AUTH_ROLE`).
+    pub auth_role: Option<String>,
+    /// Auth backend mount; defaults to the method name (`VAULT_AUTH_MOUNT`).
+    pub auth_mount: Option<String>,
+    pub approle_role_id: Option<String>,
+    pub approle_secret_id: Option<String>,
+    /// File holding the AppRole secret-id (`VAULT_APPROLE_SECRET_ID_FILE`).
+    /// Re-read on every login, so a rotated secret-id is picked up without a
+    /// restart. Takes precedence over `approle_secret_id`.
+    pub approle_secret_id_file: Option<String>,
+    /// PEM client certificate/key paths (`VAULT_CLIENT_CERT`/`VAULT_CLIENT_KEY`).
+    pub client_cert: Option<String>,
+    pub client_key: Option<String>,
+    /// Service-account JWT path for Kubernetes auth (`VAULT_K8S_TOKEN_PATH`).
+    pub k8s_token_path: Option<String>,
+    /// Encrypt with this Transit key version instead of the latest
+    /// (`VAULT_TRANSIT_KEY_VERSION`). Decrypt is unaffected.
+    pub key_version: Option<u32>,
+}
+
+impl std::fmt::Debug for VaultOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn redact(v: Option<&String>) -> Option<&'static str> {
+            v.map(|_| "<redacted>")
+        }
+        f.debug_struct("VaultOptions")
+            .field("token", &redact(self.token.as_ref()))
+            .field("auth_method", &self.auth_method)
+            .field("auth_role", &self.auth_role)
+            .field("auth_mount", &self.auth_mount)
+            .field("approle_role_id", &self.approle_role_id)
+            .field(
+                "approle_secret_id",
+                &redact(self.approle_secret_id.as_ref()),
+            )
+            .field("approle_secret_id_file", &self.approle_secret_id_file)
+            .field("client_cert", &self.client_cert)
+            .field("client_key", &self.client_key)
+            .field("k8s_token_path", &self.k8s_token_path)
+            .field("key_version", &self.key_version)
+            .finish()
+    }
 }
 
 #[derive(Clone, Debug, Default)]
@@ -558,9 +647,176 @@ pub struct ResolvedConfig {
     pub aws_profile_name: Option<String>,
     pub metastore: MetastoreConfig,
     pub kms: KmsConfig,
+    /// The master key being rotated away from. When set, SKs wrapped under
+    /// either `kms` or `previous_kms` decrypt, new SKs are wrapped with `kms`,
+    /// and the config drift guard also accepts a snapshot recording
+    /// `previous_kms`. See [`crate::master_key_rotation`].
+    pub previous_kms: Option<KmsConfig>,
     pub policy: PolicyConfig,
 }
 
+/// A metastore or KMS implemented by the embedding application rather than
+/// built from config. `name` stands in for the backend's settings in the
+/// config drift guard, so it must stay stable once keys exist.
+pub struct HostBackend<T: ?Sized> {
+    pub name: String,
+    pub backend: Arc<T>,
+}
+
+impl<T: ?Sized> Clone for HostBackend<T> {
+    fn clone(&self) -> Self {
+        Self {
+            name: self.name.clone(),
+            backend: Arc::clone(&self.backend),
+        }
+    }
+}
+
+impl<T: ?Sized> std::fmt::Debug for HostBackend<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("HostBackend")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Host-implemented backends for [`factory_from_resolved_with_backends`].
+/// Each one that is set replaces the config's `metastore`/`kms` with
+/// [`MetastoreConfig::Host`]/[`KmsConfig::Host`].
+#[derive(Clone, Debug, Default)]
+pub struct HostBackends {
+    metastore: Option<HostBackend<dyn Metastore>>,
+    kms: Option<HostBackend<dyn crate::traits::KeyManagementService>>,
+}
+
+impl HostBackends {
+    pub fn with_metastore(mut self, name: impl Into<String>, store: Arc<dyn Metastore>) -> Self {
+        self.metastore = Some(HostBackend {
+            name: name.into(),
+            backend: store,
+        });
+        self
+    }
+
+    pub fn with_kms(
+        mut self,
+        name: impl Into<String>,
+        kms: Arc<dyn crate::traits::KeyManagementService>,
+    ) -> Self {
+        self.kms = Some(HostBackend {
+            name: name.into(),
+            backend: kms,
+        });
+        self
+    }
+
+    pub fn metastore(&self) -> Option<&HostBackend<dyn Metastore>> {
+        self.metastore.as_ref()
+    }
+
+    pub fn kms(&self) -> Option<&HostBackend<dyn crate::traits::KeyManagementService>> {
+        self.kms.as_ref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.metastore.is_none() && self.kms.is_none()
+    }
+
+    /// `config` with the host backends substituted in.
+    fn apply<'cfg>(&self, config: &'cfg ResolvedConfig) -> std::borrow::Cow<'cfg, ResolvedConfig> {
+        if self.is_empty() {
+            return std::borrow::Cow::Borrowed(config);
+        }
+        let mut config = config.clone();
+        if let Some(host) = &self.metastore {
+            config.metastore = MetastoreConfig::Host {
+                name: host.name.clone(),
+            };
+        }
+        if let Some(host) = &self.kms {
+            config.kms = KmsConfig::Host {
+                name: host.name.clone(),
+            };
+            config.previous_kms = None;
+        }
+        std::borrow::Cow::Owned(config)
+    }
+}
+
+fn host_backend_missing(what: &str, name: &str) -> anyhow::Error {
+    anyhow::anyhow!(
+        "{what} '{name}' is implemented by the application; build the factory with \
+         factory_from_resolved_with_backends"
+    )
+}
+
+/// Derive the `previous_kms` rotation window from the current KMS config and
+/// the caller's "previous" overrides. The previous key uses the same KMS kind
+/// as `current`; only the key material fields differ. Returns `None` when no
+/// override is set.
+pub fn previous_kms_config(
+    current: &KmsConfig,
+    key_id: Option<String>,
+    region_map: Option<std::collections::HashMap<String, String>>,
+    static_key_hex: Option<String>,
+) -> anyhow::Result<Option<KmsConfig>> {
+    let key_id = key_id.filter(|v| !v.is_empty());
+    let region_map = region_map.filter(|m| !m.is_empty());
+    let static_key_hex = static_key_hex.filter(|v| !v.is_empty());
+    match current {
+        KmsConfig::Static { .. } => {
+            if key_id.is_some() || region_map.is_some() {
+                anyhow::bail!("previous KMS key id/region map cannot be used with KMS=static");
+            }
+            Ok(static_key_hex.map(|key_hex| KmsConfig::Static { key_hex }))
+        }
+        KmsConfig::Aws {
+            preferred_region,
+            region,
+            ..
+        } => {
+            if static_key_hex.is_some() {
+                anyhow::bail!("previous static master key cannot be used with KMS=aws");
+            }
+            if key_id.is_none() && region_map.is_none() {
+                return Ok(None);
+            }
+            Ok(Some(KmsConfig::Aws {
+                preferred_region: region_map.as_ref().and(preferred_region.clone()),
+                region_map,
+                key_id,
+                region: region.clone(),
+            }))
+        }
+        KmsConfig::SecretsManager { .. }
+        | KmsConfig::Vault { .. }
+        | KmsConfig::Quorum { .. }
+        | KmsConfig::Host { .. } => {
+            if key_id.is_some() || region_map.is_some() || static_key_hex.is_some() {
+                anyhow::bail!(
+                    "master-key rotation window is only supported for KMS=aws and KMS=static"
+                );
+            }
+            Ok(None)
+        }
+    }
+}
+
+/// Wrap `kms` in a decrypt window that also accepts SKs wrapped by
+/// `previous`. Encrypt always uses `kms`.
+fn with_previous_kms(
+    kms: Arc<dyn crate::traits::KeyManagementService>,
+    previous: Option<Arc<dyn crate::traits::KeyManagementService>>,
+) -> anyhow::Result<Arc<dyn crate::traits::KeyManagementService>> {
+    match previous {
+        Some(previous) => Ok(Arc::new(crate::kms_multi::MultiKms::new(
+            0,
+            vec![kms, previous],
+        )?)),
+        None => Ok(kms),
+    }
+}
+
 fn build_config_from_policy(
     service: &str,
     product: &str,
@@ -688,6 +944,7 @@ fn build_metastore(
             #[cfg(not(feature = "dynamodb"))]
             anyhow::bail!("Enable feature 'dynamodb' to use DynamoDB metastore")
         }
+        MetastoreConfig::Host { name } => Err(host_backend_missing("metastore", name)),
     }
 }
 
@@ -788,6 +1045,7 @@ async fn build_metastore_async(
             #[cfg(not(feature = "dynamodb"))]
             anyhow::bail!("Enable feature 'dynamodb' to use DynamoDB metastore")
         }
+        MetastoreConfig::Host { name } => Err(host_backend_missing("metastore", name)),
     }
 }
 
@@ -949,19 +1207,33 @@ fn build_kms(
             addr,
             transit_key,
             transit_mount,
+            options,
         } => {
             #[cfg(feature = "vault")]
             {
-                let kms = crate::kms_vault_transit::VaultTransitKms::new(
+                let kms = crate::kms_vault_transit::VaultTransitKms::with_options(
                     addr.clone(),
                     transit_key,
                     transit_mount.as_deref(),
+                    options,
                 )?;
                 Ok(Arc::new(kms))
             }
             #[cfg(not(feature = "vault"))]
             anyhow::bail!("Enable feature 'vault' to use Vault Transit KMS")
         }
+        KmsConfig::Quorum { threshold, members } => {
+            let built = members
+                .iter()
+                .map(|m| Ok((m.name.clone(), build_kms(&m.kms, crypto, aws_profile_name)?)))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            Ok(Arc::new(crate::kms_quorum::QuorumKms::new(
+                crypto.clone(),
+                *threshold,
+                built,
+            )?))
+        }
+        KmsConfig::Host { name } => Err(host_backend_missing("KMS", name)),
     }
 }
 
@@ -1022,13 +1294,15 @@ async fn build_kms_async(
             addr,
             transit_key,
             transit_mount,
+            options,
         } => {
             #[cfg(feature = "vault")]
             {
-                let kms = crate::kms_vault_transit::VaultTransitKms::new_async(
+                let kms = crate::kms_vault_transit::VaultTransitKms::with_options_async(
                     addr.clone(),
                     transit_key,
                     transit_mount.as_deref(),
+                    options,
                 )
                 .await?;
                 Ok(Arc::new(kms))
@@ -1036,6 +1310,19 @@ async fn build_kms_async(
             #[cfg(not(feature = "vault"))]
             anyhow::bail!("Enable feature 'vault' to use Vault Transit KMS")
         }
+        KmsConfig::Quorum { threshold, members } => {
+            let mut built = Vec::with_capacity(members.len());
+            for m in members {
+                let kms = Box::pin(build_kms_async(&m.kms, crypto, aws_profile_name)).await?;
+                built.push((m.name.clone(), kms));
+            }
+            Ok(Arc::new(crate::kms_quorum::QuorumKms::new(
+                crypto.clone(),
+                *threshold,
+                built,
+            )?))
+        }
+        KmsConfig::Host { name } => Err(host_backend_missing("KMS", name)),
     }
 }
 
@@ -1051,9 +1338,21 @@ pub fn factory_from_resolved(
 pub fn factory_from_resolved_with_config_drift_guard(
     config: &ResolvedConfig,
     config_drift_guard: ConfigDriftGuardOptions,
+) -> anyhow::Result<crate::session::PublicFactory<crate::aead::AES256GCM, DynKms, DynMetastore>> {
+    factory_from_resolved_with_backends(config, config_drift_guard, &HostBackends::default())
+}
+
+/// Build a factory whose metastore and/or KMS are implemented by the
+/// embedding application; the rest comes from `config` as usual.
+pub fn factory_from_resolved_with_backends(
+    config: &ResolvedConfig,
+    config_drift_guard: ConfigDriftGuardOptions,
+    backends: &HostBackends,
 ) -> anyhow::Result<crate::session::PublicFactory<crate::aead::AES256GCM, DynKms, DynMetastore>> {
     crate::process_hardening::ensure_process_hardened()
         .context("failed to initialize process hardening")?;
+    let config = backends.apply(config);
+    let config = config.as_ref();
     let aws_profile_name = config.aws_profile_name.as_deref();
     let cfg = build_config_from_policy(
         &config.service_name,
@@ -1063,7 +1362,10 @@ pub fn factory_from_resolved_with_config_drift_guard(
         config.self_heal_recovered_keys,
         &config.policy,
     );
-    let store_dyn = build_metastore(&config.metastore, aws_profile_name)?;
+    let store_dyn = match backends.metastore() {
+        Some(host) => Arc::clone(&host.backend),
+        None => build_metastore(&config.metastore, aws_profile_name)?,
+    };
     let effective_region_suffix = store_dyn
         .region_suffix()
         .filter(|suffix| !suffix.is_empty())
@@ -1074,7 +1376,16 @@ pub fn factory_from_resolved_with_config_drift_guard(
                 .filter(|suffix| !suffix.is_empty())
         });
     let crypto = Arc::new(crate::aead::AES256GCM::new());
-    let kms_dyn = build_kms(&config.kms, &crypto, aws_profile_name)?;
+    let kms_dyn = match backends.kms() {
+        Some(host) => Arc::clone(&host.backend),
+        None => build_kms(&config.kms, &crypto, aws_profile_name)?,
+    };
+    let previous_kms_dyn = config
+        .previous_kms
+        .as_ref()
+        .map(|previous| build_kms(previous, &crypto, aws_profile_name))
+        .transpose()?;
+    let kms_dyn = with_previous_kms(kms_dyn, previous_kms_dyn)?;
     crate::config_drift_guard::enforce_config_drift_guard(
         store_dyn.as_ref(),
         config,
@@ -1098,9 +1409,21 @@ pub async fn factory_from_resolved_async(
 pub async fn factory_from_resolved_with_config_drift_guard_async(
     config: &ResolvedConfig,
     config_drift_guard: ConfigDriftGuardOptions,
+) -> anyhow::Result<crate::session::PublicFactory<crate::aead::AES256GCM, DynKms, DynMetastore>> {
+    factory_from_resolved_with_backends_async(config, config_drift_guard, &HostBackends::default())
+        .await
+}
+
+/// Async variant of factory_from_resolved_with_backends.
+pub async fn factory_from_resolved_with_backends_async(
+    config: &ResolvedConfig,
+    config_drift_guard: ConfigDriftGuardOptions,
+    backends: &HostBackends,
 ) -> anyhow::Result<crate::session::PublicFactory<crate::aead::AES256GCM, DynKms, DynMetastore>> {
     crate::process_hardening::ensure_process_hardened()
         .context("failed to initialize process hardening")?;
+    let config = backends.apply(config);
+    let config = config.as_ref();
     let aws_profile_name = config.aws_profile_name.as_deref();
     let cfg = build_config_from_policy(
         &config.service_name,
@@ -1110,7 +1433,10 @@ pub async fn factory_from_resolved_with_config_drift_guard_async(
         config.self_heal_recovered_keys,
         &config.policy,
     );
-    let store_dyn = build_metastore_async(&config.metastore, aws_profile_name).await?;
+    let store_dyn = match backends.metastore() {
+        Some(host) => Arc::clone(&host.backend),
+        None => build_metastore_async(&config.metastore, aws_profile_name).await?,
+    };
     let effective_region_suffix = store_dyn
         .region_suffix()
         .filter(|suffix| !suffix.is_empty())
@@ -1121,7 +1447,15 @@ pub async fn factory_from_resolved_with_config_drift_guard_async(
                 .filter(|suffix| !suffix.is_empty())
         });
     let crypto = Arc::new(crate::aead::AES256GCM::new());
-    let kms_dyn = build_kms_async(&config.kms, &crypto, aws_profile_name).await?;
+    let kms_dyn = match backends.kms() {
+        Some(host) => Arc::clone(&host.backend),
+        None => build_kms_async(&config.kms, &crypto, aws_profile_name).await?,
+    };
+    let previous_kms_dyn = match &config.previous_kms {
+        Some(previous) => Some(build_kms_async(previous, &crypto, aws_profile_name).await?),
+        None => None,
+    };
+    let kms_dyn = with_previous_kms(kms_dyn, previous_kms_dyn)?;
     crate::config_drift_guard::enforce_config_drift_guard_async(
         store_dyn.as_ref(),
         config,
@@ -1134,6 +1468,67 @@ pub async fn factory_from_resolved_with_config_drift_guard_async(
     Ok(crate::api::new_session_factory(cfg, metastore, kms, crypto))
 }
 
+/// Re-wrap every system key of `config`'s service/product from
+/// `config.previous_kms` to `config.kms`, then switch the config drift guard
+/// to the new master key.
+///
+/// Run this once while services are deployed with the rotation window
+/// (`previous_kms` set). It is safe to repeat: already re-wrapped SKs are
+/// skipped. The guard is only updated after every SK has been re-wrapped, so
+/// a failed run leaves it recording the old key.
+pub fn rotate_master_key_from_resolved(
+    config: &ResolvedConfig,
+) -> anyhow::Result<crate::master_key_rotation::RewrapReport> {
+    let previous = config.previous_kms.as_ref().ok_or_else(|| {
+        anyhow::anyhow!("master-key rotation requires the previous KMS configuration")
+    })?;
+    crate::process_hardening::ensure_process_hardened()
+        .context("failed to initialize process hardening")?;
+    let aws_profile_name = config.aws_profile_name.as_deref();
+    let store_dyn = build_metastore(&config.metastore, aws_profile_name)?;
+    let effective_region_suffix = store_dyn
+        .region_suffix()
+        .filter(|suffix| !suffix.is_empty())
+        .or_else(|| {
+            config
+                .region_suffix
+                .clone()
+                .filter(|suffix| !suffix.is_empty())
+        });
+    let crypto = Arc::new(crate::aead::AES256GCM::new());
+    let new_kms = build_kms(&config.kms, &crypto, aws_profile_name)?;
+    let old_kms = build_kms(previous, &crypto, aws_profile_name)?;
+    // Refuse to touch keys that belong to a different configuration: the
+    // guard must record either the old or the new master key.
+    crate::config_drift_guard::enforce_config_drift_guard(
+        store_dyn.as_ref(),
+        config,
+        ConfigDriftGuardOptions::default(),
+        effective_region_suffix.as_deref(),
+    )?;
+    let suffixes = effective_region_suffix
+        .iter()
+        .chain(config.recovery_region_suffixes.iter())
+        .map(String::as_str);
+    let ids = crate::master_key_rotation::system_key_ids(
+        &config.service_name,
+        &config.product_id,
+        suffixes,
+    );
+    let report = crate::master_key_rotation::rewrap_system_keys(
+        store_dyn.as_ref(),
+        old_kms.as_ref(),
+        new_kms.as_ref(),
+        &ids,
+    )?;
+    crate::config_drift_guard::commit_master_key_rotation(
+        store_dyn.as_ref(),
+        config,
+        effective_region_suffix.as_deref(),
+    )?;
+    Ok(report)
+}
+
 /// Parse environment variables into a `ResolvedConfig`.
 #[allow(unused_variables)]
 pub fn resolve_from_env() -> anyhow::Result<ResolvedConfig> {
@@ -1272,7 +1667,45 @@ pub fn resolve_from_env() -> anyhow::Result<ResolvedConfig> {
             )
         })?
         .to_lowercase();
-    let kms = match kms_kind.as_str() {
+    let kms = kms_config_from_env(&kms_kind)?;
+
+    let previous_kms = previous_kms_config(
+        &kms,
+        std::env::var("PREVIOUS_KMS_KEY_ID").ok(),
+        std::env::var("PREVIOUS_REGION_MAP")
+            .ok()
+            .map(|j| serde_json::from_str(&j))
+            .transpose()?,
+        std::env::var("PREVIOUS_STATIC_MASTER_KEY_HEX").ok(),
+    )?;
+
+    let policy = PolicyConfig {
+        expire_key_after_s: get_i64("EXPIRE_AFTER_SECS"),
+        create_date_precision_s: get_i64("CREATE_DATE_PRECISION_SECS"),
+        revoke_check_interval_s: get_i64("REVOKE_CHECK_INTERVAL_SECS"),
+        session_cache_max_size: get_usize("SESSION_CACHE_MAX_SIZE"),
+        session_cache_ttl_s: get_i64("SESSION_CACHE_DURATION_SECS"),
+        shared_intermediate_key_cache: get_bool("SHARED_INTERMEDIATE_KEY_CACHE"),
+        intermediate_key_cache_max_size: get_usize("INTERMEDIATE_KEY_CACHE_MAX_SIZE"),
+    };
+
+    Ok(ResolvedConfig {
+        service_name,
+        product_id,
+        region_suffix,
+        recovery_region_suffixes: recovery_region_suffixes_from_env(),
+        self_heal_recovered_keys: self_heal_recovered_keys_from_env(),
+        aws_profile_name: None,
+        metastore,
+        kms,
+        previous_kms,
+        policy,
+    })
+}
+
+/// Resolve the `KMS=<kind>` settings from environment variables.
+fn kms_config_from_env(kind: &str) -> anyhow::Result<KmsConfig> {
+    Ok(match kind {
         "static" => {
             let key_hex = std::env::var("STATIC_MASTER_KEY_HEX")
                 .map_err(|_| anyhow::anyhow!("STATIC_MASTER_KEY_HEX required for KMS=static"))?;
@@ -1323,38 +1756,47 @@ pub fn resolve_from_env() -> anyhow::Result<ResolvedConfig> {
                 addr,
                 transit_key,
                 transit_mount: std::env::var("VAULT_TRANSIT_MOUNT").ok(),
+                // Auth settings are read from `VAULT_*` by the KMS itself.
+                options: VaultOptions::default(),
             }
         }
         #[cfg(not(feature = "vault"))]
         "vault" | "vault-transit" => {
             anyhow::bail!("Enable feature 'vault' to use Vault Transit KMS");
         }
+        "quorum" => quorum_kms_config_from_env()?,
         other => {
-            anyhow::bail!("Unknown KMS type '{other}'. Valid values: 'aws', 'static', 'secrets-manager', 'vault'");
+            anyhow::bail!("Unknown KMS type '{other}'. Valid values: 'aws', 'static', 'secrets-manager', 'vault', 'quorum'");
         }
-    };
+    })
+}
 
-    let policy = PolicyConfig {
-        expire_key_after_s: get_i64("EXPIRE_AFTER_SECS"),
-        create_date_precision_s: get_i64("CREATE_DATE_PRECISION_SECS"),
-        revoke_check_interval_s: get_i64("REVOKE_CHECK_INTERVAL_SECS"),
-        session_cache_max_size: get_usize("SESSION_CACHE_MAX_SIZE"),
-        session_cache_ttl_s: get_i64("SESSION_CACHE_DURATION_SECS"),
-        shared_intermediate_key_cache: get_bool("SHARED_INTERMEDIATE_KEY_CACHE"),
-        intermediate_key_cache_max_size: get_usize("INTERMEDIATE_KEY_CACHE_MAX_SIZE"),
+/// `KMS=quorum`: `QUORUM_KMS_MEMBERS` lists member kinds (comma-separated,
+/// e.g. `aws,vault`), each configured through its usual variables, and
+/// `QUORUM_KMS_THRESHOLD` says how many must cooperate (default: all).
+fn quorum_kms_config_from_env() -> anyhow::Result<KmsConfig> {
+    let members = std::env::var("QUORUM_KMS_MEMBERS")
+        .map_err(|_| anyhow::anyhow!("QUORUM_KMS_MEMBERS required for KMS=quorum"))?
+        .split(',')
+        .map(|kind| kind.trim().to_lowercase())
+        .filter(|kind| !kind.is_empty())
+        .map(|kind| {
+            if kind == "quorum" {
+                anyhow::bail!("quorum KMS members cannot themselves be KMS=quorum");
+            }
+            Ok(QuorumMember {
+                kms: kms_config_from_env(&kind)?,
+                name: kind,
+            })
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let threshold = match std::env::var("QUORUM_KMS_THRESHOLD") {
+        Ok(v) => v
+            .parse()
+            .map_err(|_| anyhow::anyhow!("QUORUM_KMS_THRESHOLD must be a positive integer"))?,
+        Err(_) => members.len(),
     };
-
-    Ok(ResolvedConfig {
-        service_name,
-        product_id,
-        region_suffix,
-        recovery_region_suffixes: recovery_region_suffixes_from_env(),
-        self_heal_recovered_keys: self_heal_recovered_keys_from_env(),
-        aws_profile_name: None,
-        metastore,
-        kms,
-        policy,
-    })
+    Ok(KmsConfig::Quorum { threshold, members })
 }
 
 /// Build a full PublicFactory from environment variables.
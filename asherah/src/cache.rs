@@ -119,6 +119,9 @@ pub trait KeyCacher: Send + Sync {
     fn entry_count(&self) -> usize {
         0
     }
+
+    /// Drop every entry, so each key is loaded again on next use.
+    fn clear(&self) {}
 }
 
 #[derive(Debug)]
@@ -725,4 +728,9 @@ impl KeyCacher for SimpleKeyCache {
     fn entry_count(&self) -> usize {
         self.by_meta.len()
     }
+
+    fn clear(&self) {
+        self.latest.clear_sync();
+        self.by_meta.clear_sync();
+    }
 }
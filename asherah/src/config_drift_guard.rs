@@ -12,6 +12,7 @@ use blake2::{Blake2b512, Digest as _};
 use serde::{Deserialize, Serialize};
 
 use crate::builders::{ConfigDriftGuardOptions, KmsConfig, MetastoreConfig, ResolvedConfig};
+use crate::error::{ErrorCode, WithErrorCode};
 use crate::traits::Metastore;
 use crate::types::EnvelopeKeyRecord;
 
@@ -47,6 +48,9 @@ enum MetastoreIdentity {
         region: Option<String>,
         region_suffix_requested: bool,
     },
+    Host {
+        name: String,
+    },
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -70,6 +74,15 @@ enum KmsIdentity {
         transit_mount: String,
         transit_key: String,
     },
+    /// Keyed by member name (the label recorded next to each share), so
+    /// reordering members is not drift but renaming or swapping one is.
+    Quorum {
+        threshold: usize,
+        members: BTreeMap<String, KmsIdentity>,
+    },
+    Host {
+        name: String,
+    },
 }
 
 impl ConfigDriftGuardSnapshot {
@@ -94,6 +107,21 @@ impl ConfigDriftGuardSnapshot {
         })
     }
 
+    /// Snapshot of the same configuration as it was before a master-key
+    /// rotation, i.e. with `previous_kms` as the KMS identity. `None` when no
+    /// rotation window is configured.
+    fn previous_from_resolved(
+        config: &ResolvedConfig,
+        effective_region_suffix: Option<&str>,
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(previous_kms) = &config.previous_kms else {
+            return Ok(None);
+        };
+        let mut snapshot = Self::from_resolved(config, effective_region_suffix)?;
+        snapshot.kms_identity = KmsIdentity::from_config(previous_kms)?;
+        Ok(Some(snapshot))
+    }
+
     fn to_canonical_json_bytes(&self) -> anyhow::Result<Vec<u8>> {
         let bytes = serde_json::to_vec(self).context("serialize config drift guard snapshot")?;
         if bytes.len() > MAX_CONFIG_DRIFT_GUARD_JSON_BYTES {
@@ -124,6 +152,7 @@ impl MetastoreIdentity {
                 region: region.clone(),
                 region_suffix_requested: *region_suffix,
             },
+            MetastoreConfig::Host { name } => Self::Host { name: name.clone() },
         }
     }
 }
@@ -175,10 +204,13 @@ impl KmsIdentity {
                 secret_id: secret_id.clone(),
                 region: region.clone(),
             }),
+            // `options` (auth, pinned encrypt version) does not change which
+            // ciphertexts decrypt, so it is not part of the identity.
             KmsConfig::Vault {
                 addr,
                 transit_key,
                 transit_mount,
+                ..
             } => Ok(Self::VaultTransit {
                 addr: addr.clone(),
                 transit_mount: transit_mount
@@ -186,6 +218,14 @@ impl KmsIdentity {
                     .unwrap_or_else(|| "transit".to_string()),
                 transit_key: transit_key.clone(),
             }),
+            KmsConfig::Quorum { threshold, members } => Ok(Self::Quorum {
+                threshold: *threshold,
+                members: members
+                    .iter()
+                    .map(|m| Ok((m.name.clone(), Self::from_config(&m.kms)?)))
+                    .collect::<anyhow::Result<_>>()?,
+            }),
+            KmsConfig::Host { name } => Ok(Self::Host { name: name.clone() }),
         }
     }
 }
@@ -245,11 +285,19 @@ fn log_load_error_force_run_override() {
 fn handle_existing(
     stored: &ConfigDriftGuardSnapshot,
     current: &ConfigDriftGuardSnapshot,
+    previous: Option<&ConfigDriftGuardSnapshot>,
     options: ConfigDriftGuardOptions,
 ) -> anyhow::Result<bool> {
     if stored == current {
         return Ok(true);
     }
+    if previous.is_some_and(|previous| stored == previous) {
+        log::info!(
+            "config drift guard still records the previous KMS identity; \
+             master-key rotation has not been committed yet"
+        );
+        return Ok(true);
+    }
     if options.force_update {
         return Ok(false);
     }
@@ -257,12 +305,12 @@ fn handle_existing(
         log_mismatch_force_run_override();
         return Ok(true);
     }
-    anyhow::bail!(
+    Err(ErrorCode::InvalidConfig.error(
         "config drift guard mismatch detected; startup refused before key writes. \
          Set ASHERAH_CONFIG_DRIFT_FORCE_RUN=true \
          to run without changing the guard, or ASHERAH_CONFIG_DRIFT_FORCE_UPDATE=true \
-         to replace the guard after validating this configuration is correct."
-    );
+         to replace the guard after validating this configuration is correct.",
+    ))
 }
 
 fn handle_load_error(err: anyhow::Error, options: ConfigDriftGuardOptions) -> anyhow::Result<bool> {
@@ -284,6 +332,8 @@ pub fn enforce_config_drift_guard(
     effective_region_suffix: Option<&str>,
 ) -> anyhow::Result<()> {
     let current = ConfigDriftGuardSnapshot::from_resolved(config, effective_region_suffix)?;
+    let previous =
+        ConfigDriftGuardSnapshot::previous_from_resolved(config, effective_region_suffix)?;
     let current_bytes = current.to_canonical_json_bytes()?;
     let guard_id = config_drift_guard_id(&current.service_name, &current.product_id);
     let current_envelope = envelope_for(&guard_id, current_bytes);
@@ -296,55 +346,57 @@ pub fn enforce_config_drift_guard(
                     if handle_load_error(err, options)? {
                         return Ok(());
                     }
-                    metastore.upsert_config_drift_guard(
-                        &guard_id,
-                        CONFIG_DRIFT_GUARD_CREATED,
-                        &current_envelope,
-                    )?;
+                    metastore
+                        .upsert_config_drift_guard(
+                            &guard_id,
+                            CONFIG_DRIFT_GUARD_CREATED,
+                            &current_envelope,
+                        )
+                        .code(ErrorCode::MetastoreUnavailable)?;
                     log::error!("config drift guard replaced by force-update override");
                     return Ok(());
                 }
             };
-            if handle_existing(&stored, &current, options)? {
+            if handle_existing(&stored, &current, previous.as_ref(), options)? {
                 return Ok(());
             }
-            metastore.upsert_config_drift_guard(
-                &guard_id,
-                CONFIG_DRIFT_GUARD_CREATED,
-                &current_envelope,
-            )?;
+            metastore
+                .upsert_config_drift_guard(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
+                .code(ErrorCode::MetastoreUnavailable)?;
             log::error!("config drift guard replaced by force-update override");
             Ok(())
         }
         Ok(None) => {
-            if metastore.store(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)? {
+            if metastore
+                .store(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
+                .code(ErrorCode::MetastoreUnavailable)?
+            {
                 log::info!("config drift guard initialized");
                 return Ok(());
             }
-            let Some(raced) = metastore.load(&guard_id, CONFIG_DRIFT_GUARD_CREATED)? else {
+            let Some(raced) = metastore
+                .load(&guard_id, CONFIG_DRIFT_GUARD_CREATED)
+                .code(ErrorCode::MetastoreUnavailable)?
+            else {
                 anyhow::bail!("config drift guard TOFU insert raced but reload missed the record");
             };
             let stored = snapshot_from_envelope(&raced)?;
-            if handle_existing(&stored, &current, options)? {
+            if handle_existing(&stored, &current, previous.as_ref(), options)? {
                 return Ok(());
             }
-            metastore.upsert_config_drift_guard(
-                &guard_id,
-                CONFIG_DRIFT_GUARD_CREATED,
-                &current_envelope,
-            )?;
+            metastore
+                .upsert_config_drift_guard(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
+                .code(ErrorCode::MetastoreUnavailable)?;
             log::error!("config drift guard replaced by force-update override");
             Ok(())
         }
         Err(err) => {
-            if handle_load_error(err, options)? {
+            if handle_load_error(ErrorCode::MetastoreUnavailable.tag(err), options)? {
                 return Ok(());
             }
-            metastore.upsert_config_drift_guard(
-                &guard_id,
-                CONFIG_DRIFT_GUARD_CREATED,
-                &current_envelope,
-            )?;
+            metastore
+                .upsert_config_drift_guard(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
+                .code(ErrorCode::MetastoreUnavailable)?;
             log::error!("config drift guard replaced by force-update override");
             Ok(())
         }
@@ -358,6 +410,8 @@ pub async fn enforce_config_drift_guard_async(
     effective_region_suffix: Option<&str>,
 ) -> anyhow::Result<()> {
     let current = ConfigDriftGuardSnapshot::from_resolved(config, effective_region_suffix)?;
+    let previous =
+        ConfigDriftGuardSnapshot::previous_from_resolved(config, effective_region_suffix)?;
     let current_bytes = current.to_canonical_json_bytes()?;
     let guard_id = config_drift_guard_id(&current.service_name, &current.product_id);
     let current_envelope = envelope_for(&guard_id, current_bytes);
@@ -379,12 +433,13 @@ pub async fn enforce_config_drift_guard_async(
                             CONFIG_DRIFT_GUARD_CREATED,
                             &current_envelope,
                         )
-                        .await?;
+                        .await
+                        .code(ErrorCode::MetastoreUnavailable)?;
                     log::error!("config drift guard replaced by force-update override");
                     return Ok(());
                 }
             };
-            if handle_existing(&stored, &current, options)? {
+            if handle_existing(&stored, &current, previous.as_ref(), options)? {
                 return Ok(());
             }
             metastore
@@ -393,26 +448,29 @@ pub async fn enforce_config_drift_guard_async(
                     CONFIG_DRIFT_GUARD_CREATED,
                     &current_envelope,
                 )
-                .await?;
+                .await
+                .code(ErrorCode::MetastoreUnavailable)?;
             log::error!("config drift guard replaced by force-update override");
             Ok(())
         }
         Ok(None) => {
             if metastore
                 .store_async(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
-                .await?
+                .await
+                .code(ErrorCode::MetastoreUnavailable)?
             {
                 log::info!("config drift guard initialized");
                 return Ok(());
             }
             let Some(raced) = metastore
                 .load_async(&guard_id, CONFIG_DRIFT_GUARD_CREATED)
-                .await?
+                .await
+                .code(ErrorCode::MetastoreUnavailable)?
             else {
                 anyhow::bail!("config drift guard TOFU insert raced but reload missed the record");
             };
             let stored = snapshot_from_envelope(&raced)?;
-            if handle_existing(&stored, &current, options)? {
+            if handle_existing(&stored, &current, previous.as_ref(), options)? {
                 return Ok(());
             }
             metastore
@@ -421,12 +479,13 @@ pub async fn enforce_config_drift_guard_async(
                     CONFIG_DRIFT_GUARD_CREATED,
                     &current_envelope,
                 )
-                .await?;
+                .await
+                .code(ErrorCode::MetastoreUnavailable)?;
             log::error!("config drift guard replaced by force-update override");
             Ok(())
         }
         Err(err) => {
-            if handle_load_error(err, options)? {
+            if handle_load_error(ErrorCode::MetastoreUnavailable.tag(err), options)? {
                 return Ok(());
             }
             metastore
@@ -435,13 +494,99 @@ pub async fn enforce_config_drift_guard_async(
                     CONFIG_DRIFT_GUARD_CREATED,
                     &current_envelope,
                 )
-                .await?;
+                .await
+                .code(ErrorCode::MetastoreUnavailable)?;
             log::error!("config drift guard replaced by force-update override");
             Ok(())
         }
     }
 }
 
+/// Record a completed master-key rotation by replacing the guard with the
+/// snapshot of `config` (whose `kms` is the new master key).
+///
+/// The guard must currently match either `config` or its `previous_kms`
+/// snapshot; anything else is a genuine drift and is refused. The replacement
+/// is a single record upsert, so concurrent startups observe either the old or
+/// the new identity, never a partial one.
+pub fn commit_master_key_rotation(
+    metastore: &dyn Metastore,
+    config: &ResolvedConfig,
+    effective_region_suffix: Option<&str>,
+) -> anyhow::Result<()> {
+    let current = ConfigDriftGuardSnapshot::from_resolved(config, effective_region_suffix)?;
+    let previous =
+        ConfigDriftGuardSnapshot::previous_from_resolved(config, effective_region_suffix)?;
+    let guard_id = config_drift_guard_id(&current.service_name, &current.product_id);
+    let current_envelope = envelope_for(&guard_id, current.to_canonical_json_bytes()?);
+
+    match metastore
+        .load(&guard_id, CONFIG_DRIFT_GUARD_CREATED)
+        .context("load config drift guard")
+        .code(ErrorCode::MetastoreUnavailable)?
+    {
+        Some(existing) => {
+            let stored = snapshot_from_envelope(&existing)?;
+            if stored == current {
+                return Ok(());
+            }
+            if previous.as_ref() != Some(&stored) {
+                anyhow::bail!(
+                    "config drift guard does not match the previous or new master key; \
+                     refusing to commit master-key rotation"
+                );
+            }
+            metastore
+                .upsert_config_drift_guard(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
+                .code(ErrorCode::MetastoreUnavailable)?;
+        }
+        None => {
+            if !metastore
+                .store(&guard_id, CONFIG_DRIFT_GUARD_CREATED, &current_envelope)
+                .code(ErrorCode::MetastoreUnavailable)?
+            {
+                anyhow::bail!("config drift guard appeared while committing master-key rotation");
+            }
+        }
+    }
+    log::info!("config drift guard updated for master-key rotation");
+    Ok(())
+}
+
+/// Which parts of the guarded identity differ between a running config and a
+/// candidate replacement: `"service name"`, `"product ID"`, `"region
+/// suffix"`, `"metastore"` and `"KMS"`. Empty when the candidate would pass
+/// the guard the running config passed, so a long-lived process can apply it
+/// to a new factory without a restart.
+///
+/// Only the configs are compared, not a stored guard, so the force-run and
+/// force-update overrides don't apply. A configured master-key rotation
+/// window (`previous_kms`) is ignored; the current KMS must match.
+pub fn config_drift_between(
+    running: &ResolvedConfig,
+    candidate: &ResolvedConfig,
+) -> anyhow::Result<Vec<&'static str>> {
+    let a = ConfigDriftGuardSnapshot::from_resolved(running, running.region_suffix.as_deref())?;
+    let b = ConfigDriftGuardSnapshot::from_resolved(candidate, candidate.region_suffix.as_deref())?;
+    let mut changed = Vec::new();
+    if a.service_name != b.service_name {
+        changed.push("service name");
+    }
+    if a.product_id != b.product_id {
+        changed.push("product ID");
+    }
+    if a.effective_region_suffix != b.effective_region_suffix {
+        changed.push("region suffix");
+    }
+    if a.metastore_identity != b.metastore_identity {
+        changed.push("metastore");
+    }
+    if a.kms_identity != b.kms_identity {
+        changed.push("KMS");
+    }
+    Ok(changed)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -466,10 +611,23 @@ mod tests {
                 key_id: Some("arn:aws:kms:us-east-1:123:key/abc".to_string()),
                 region: Some("us-east-1".to_string()),
             },
+            previous_kms: None,
             policy: Default::default(),
         }
     }
 
+    fn rotated_config() -> ResolvedConfig {
+        let mut cfg = base_config();
+        cfg.previous_kms = Some(cfg.kms.clone());
+        cfg.kms = KmsConfig::Aws {
+            region_map: None,
+            preferred_region: None,
+            key_id: Some("arn:aws:kms:us-east-1:123:key/new".to_string()),
+            region: Some("us-east-1".to_string()),
+        };
+        cfg
+    }
+
     #[test]
     fn tofu_initializes_guard_record() {
         let store = InMemoryMetastore::new();
@@ -520,6 +678,72 @@ mod tests {
         );
     }
 
+    #[test]
+    fn quorum_identity_ignores_member_order_but_not_threshold() {
+        use crate::builders::QuorumMember;
+
+        let member = |name: &str, key_hex: &str| QuorumMember {
+            name: name.to_string(),
+            kms: KmsConfig::Static {
+                key_hex: key_hex.to_string(),
+            },
+        };
+        let store = InMemoryMetastore::new();
+        let mut cfg = base_config();
+        cfg.kms = KmsConfig::Quorum {
+            threshold: 2,
+            members: vec![member("a", "aa"), member("b", "bb"), member("c", "cc")],
+        };
+        enforce_config_drift_guard(&store, &cfg, ConfigDriftGuardOptions::default(), None).unwrap();
+
+        let mut reordered = cfg.clone();
+        reordered.kms = KmsConfig::Quorum {
+            threshold: 2,
+            members: vec![member("c", "cc"), member("a", "aa"), member("b", "bb")],
+        };
+        enforce_config_drift_guard(&store, &reordered, ConfigDriftGuardOptions::default(), None)
+            .unwrap();
+
+        let mut weakened = cfg.clone();
+        weakened.kms = KmsConfig::Quorum {
+            threshold: 1,
+            members: vec![member("a", "aa"), member("b", "bb"), member("c", "cc")],
+        };
+        let err =
+            enforce_config_drift_guard(&store, &weakened, ConfigDriftGuardOptions::default(), None)
+                .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("config drift guard mismatch"),
+            "{err:#}"
+        );
+    }
+
+    #[test]
+    fn host_backends_are_identified_by_name() {
+        let store = InMemoryMetastore::new();
+        let mut cfg = base_config();
+        cfg.metastore = MetastoreConfig::Host {
+            name: "orm-table".to_string(),
+        };
+        cfg.kms = KmsConfig::Host {
+            name: "corp-hsm".to_string(),
+        };
+        enforce_config_drift_guard(&store, &cfg, ConfigDriftGuardOptions::default(), None).unwrap();
+        enforce_config_drift_guard(&store, &cfg, ConfigDriftGuardOptions::default(), None).unwrap();
+
+        let mut renamed = cfg.clone();
+        renamed.kms = KmsConfig::Host {
+            name: "other-hsm".to_string(),
+        };
+        let err =
+            enforce_config_drift_guard(&store, &renamed, ConfigDriftGuardOptions::default(), None)
+                .unwrap_err();
+        assert!(
+            format!("{err:#}").contains("config drift guard mismatch"),
+            "{err:#}"
+        );
+    }
+
     #[test]
     fn force_run_allows_mismatch_without_rewriting() {
         let store = InMemoryMetastore::new();
@@ -613,6 +837,95 @@ mod tests {
         enforce_config_drift_guard(&store, &cfg, ConfigDriftGuardOptions::default(), None).unwrap();
     }
 
+    #[test]
+    fn rotation_window_accepts_previous_kms_identity() {
+        let store = InMemoryMetastore::new();
+        enforce_config_drift_guard(
+            &store,
+            &base_config(),
+            ConfigDriftGuardOptions::default(),
+            None,
+        )
+        .unwrap();
+
+        let rotated = rotated_config();
+        enforce_config_drift_guard(&store, &rotated, ConfigDriftGuardOptions::default(), None)
+            .unwrap();
+
+        let mut new_only = rotated.clone();
+        new_only.previous_kms = None;
+        let err =
+            enforce_config_drift_guard(&store, &new_only, ConfigDriftGuardOptions::default(), None)
+                .unwrap_err();
+        assert!(format!("{err:#}").contains("config drift guard mismatch"));
+    }
+
+    #[test]
+    fn commit_master_key_rotation_switches_guard_to_new_kms() {
+        let store = InMemoryMetastore::new();
+        let original = base_config();
+        enforce_config_drift_guard(&store, &original, ConfigDriftGuardOptions::default(), None)
+            .unwrap();
+
+        let rotated = rotated_config();
+        commit_master_key_rotation(&store, &rotated, None).unwrap();
+
+        let mut new_only = rotated.clone();
+        new_only.previous_kms = None;
+        enforce_config_drift_guard(&store, &new_only, ConfigDriftGuardOptions::default(), None)
+            .unwrap();
+        let err =
+            enforce_config_drift_guard(&store, &original, ConfigDriftGuardOptions::default(), None)
+                .unwrap_err();
+        assert!(format!("{err:#}").contains("config drift guard mismatch"));
+    }
+
+    #[test]
+    fn commit_master_key_rotation_refuses_unrelated_guard() {
+        let store = InMemoryMetastore::new();
+        let mut other = base_config();
+        other.kms = KmsConfig::Static {
+            key_hex: "00".repeat(32),
+        };
+        enforce_config_drift_guard(&store, &other, ConfigDriftGuardOptions::default(), None)
+            .unwrap();
+
+        let err = commit_master_key_rotation(&store, &rotated_config(), None).unwrap_err();
+        assert!(format!("{err:#}").contains("refusing to commit master-key rotation"));
+    }
+
+    #[test]
+    fn drift_between_names_identity_changes_only() {
+        let running = base_config();
+        let mut tuned = base_config();
+        tuned.policy.session_cache_max_size = Some(7);
+        tuned.recovery_region_suffixes = vec!["us-west-2".to_string()];
+        assert!(config_drift_between(&running, &tuned).unwrap().is_empty());
+        // The rotation window alone isn't drift; the new current KMS is.
+        assert!(config_drift_between(&running, &{
+            let mut cfg = base_config();
+            cfg.previous_kms = Some(cfg.kms.clone());
+            cfg
+        })
+        .unwrap()
+        .is_empty());
+        assert_eq!(
+            config_drift_between(&running, &rotated_config()).unwrap(),
+            vec!["KMS"]
+        );
+
+        let mut moved = base_config();
+        moved.service_name = "other".to_string();
+        moved.region_suffix = Some("us-west-2".to_string());
+        moved.metastore = MetastoreConfig::Sqlite {
+            path: "/tmp/other.db".to_string(),
+        };
+        assert_eq!(
+            config_drift_between(&running, &moved).unwrap(),
+            vec!["service name", "region suffix", "metastore"]
+        );
+    }
+
     #[test]
     fn reserved_id_fits_mysql_schema_bound() {
         let id = config_drift_guard_id("svc", "prod");
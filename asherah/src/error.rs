@@ -0,0 +1,334 @@
+//! Stable error codes for callers on the other side of an FFI boundary.
+//!
+//! Asherah's APIs return [`anyhow::Error`]. The failures a caller can act on
+//! carry an [`ErrorCode`] somewhere in that chain, attached where the
+//! failure is first understood: a malformed `DataRowRecord`, a key missing
+//! from the metastore, a KMS that refused access. [`ErrorCode::of`] recovers
+//! it, preferring the innermost (most specific) code; errors nobody tagged
+//! report [`ErrorCode::Unknown`].
+//!
+//! Tagging never changes an error's message: a coded error displays exactly
+//! the text it was created or wrapped with.
+//!
+//! The numeric values are part of the C ABI, cobhan, and every language
+//! binding. Never renumber or reuse one; add new codes instead.
+//!
+//! There is deliberately no "revoked key" code. Revocation makes the next
+//! encrypt rotate to a fresh key, but data written under a revoked key still
+//! decrypts, so no operation fails because of it.
+
+use std::fmt;
+
+/// What kind of thing went wrong, for callers that only want to branch on
+/// the broad class of failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCategory {
+    /// The caller passed something unusable; retrying the same input fails
+    /// the same way.
+    Input,
+    /// The factory configuration is invalid.
+    Config,
+    /// The call came at the wrong time, e.g. before setup or after close.
+    State,
+    /// A key the operation needs does not exist.
+    Key,
+    /// The metastore could not be reached or failed the request.
+    Metastore,
+    /// The KMS could not be reached or refused the request.
+    Kms,
+    /// Authenticated decryption failed: the data or a wrapped key was
+    /// tampered with, truncated, or encrypted under a different key.
+    Crypto,
+    /// A bug or an unexpected condition inside Asherah.
+    Internal,
+}
+
+impl ErrorCategory {
+    /// Upper-snake-case name, e.g. `"METASTORE"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Input => "INPUT",
+            Self::Config => "CONFIG",
+            Self::State => "STATE",
+            Self::Key => "KEY",
+            Self::Metastore => "METASTORE",
+            Self::Kms => "KMS",
+            Self::Crypto => "CRYPTO",
+            Self::Internal => "INTERNAL",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// A stable, numbered reason for a failure. The hundreds digit groups codes
+/// by [`ErrorCategory`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+#[repr(i32)]
+pub enum ErrorCode {
+    /// Nothing more specific is known.
+    Unknown = 1,
+    /// A panic or another failure that indicates a bug.
+    Internal = 2,
+    /// An argument is missing, empty, or malformed.
+    InvalidArgument = 100,
+    /// A plaintext or envelope exceeds the size limits in [`crate::limits`].
+    PayloadTooLarge = 101,
+    /// The `DataRowRecord` is not valid JSON or is missing required fields.
+    InvalidDataRowRecord = 102,
+    /// The `DataRowRecord` was written for a different partition.
+    PartitionMismatch = 103,
+    /// The configuration is invalid.
+    InvalidConfig = 200,
+    /// The operation needs setup that has not happened.
+    NotInitialized = 300,
+    /// Setup was called twice.
+    AlreadyInitialized = 301,
+    /// The factory or session has been closed.
+    Closed = 302,
+    /// The caller cancelled the operation before it finished.
+    Cancelled = 303,
+    /// The operation did not finish within the caller's timeout.
+    DeadlineExceeded = 304,
+    /// The intermediate or system key a record refers to is not in the
+    /// metastore.
+    KeyNotFound = 400,
+    /// The metastore failed the request, e.g. it is unreachable.
+    MetastoreUnavailable = 500,
+    /// The KMS failed the request, e.g. it is unreachable or throttling.
+    KmsUnavailable = 600,
+    /// The KMS refused the request: access denied, or the master key is
+    /// disabled or pending deletion.
+    KmsAccessDenied = 601,
+    /// Authenticated decryption of the data or of a wrapped key failed.
+    DecryptionFailed = 700,
+}
+
+impl ErrorCode {
+    /// Every code, in numeric order.
+    pub const ALL: [Self; 17] = [
+        Self::Unknown,
+        Self::Internal,
+        Self::InvalidArgument,
+        Self::PayloadTooLarge,
+        Self::InvalidDataRowRecord,
+        Self::PartitionMismatch,
+        Self::InvalidConfig,
+        Self::NotInitialized,
+        Self::AlreadyInitialized,
+        Self::Closed,
+        Self::Cancelled,
+        Self::DeadlineExceeded,
+        Self::KeyNotFound,
+        Self::MetastoreUnavailable,
+        Self::KmsUnavailable,
+        Self::KmsAccessDenied,
+        Self::DecryptionFailed,
+    ];
+
+    /// The code carried by `err`, or [`ErrorCode::Unknown`].
+    pub fn of(err: &anyhow::Error) -> Self {
+        err.chain()
+            .filter_map(|cause| cause.downcast_ref::<CodedError>())
+            .last()
+            .map_or(Self::Unknown, |coded| coded.code)
+    }
+
+    /// The numeric value used across the FFI surfaces.
+    pub const fn as_i32(self) -> i32 {
+        self as i32
+    }
+
+    pub fn from_i32(value: i32) -> Option<Self> {
+        Self::ALL.into_iter().find(|code| code.as_i32() == value)
+    }
+
+    /// Upper-snake-case name, e.g. `"KMS_ACCESS_DENIED"`.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Unknown => "UNKNOWN",
+            Self::Internal => "INTERNAL",
+            Self::InvalidArgument => "INVALID_ARGUMENT",
+            Self::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            Self::InvalidDataRowRecord => "INVALID_DATA_ROW_RECORD",
+            Self::PartitionMismatch => "PARTITION_MISMATCH",
+            Self::InvalidConfig => "INVALID_CONFIG",
+            Self::NotInitialized => "NOT_INITIALIZED",
+            Self::AlreadyInitialized => "ALREADY_INITIALIZED",
+            Self::Closed => "CLOSED",
+            Self::Cancelled => "CANCELLED",
+            Self::DeadlineExceeded => "DEADLINE_EXCEEDED",
+            Self::KeyNotFound => "KEY_NOT_FOUND",
+            Self::MetastoreUnavailable => "METASTORE_UNAVAILABLE",
+            Self::KmsUnavailable => "KMS_UNAVAILABLE",
+            Self::KmsAccessDenied => "KMS_ACCESS_DENIED",
+            Self::DecryptionFailed => "DECRYPTION_FAILED",
+        }
+    }
+
+    pub const fn category(self) -> ErrorCategory {
+        match self {
+            Self::Unknown | Self::Internal => ErrorCategory::Internal,
+            Self::InvalidArgument
+            | Self::PayloadTooLarge
+            | Self::InvalidDataRowRecord
+            | Self::PartitionMismatch => ErrorCategory::Input,
+            Self::InvalidConfig => ErrorCategory::Config,
+            Self::NotInitialized
+            | Self::AlreadyInitialized
+            | Self::Closed
+            | Self::Cancelled
+            | Self::DeadlineExceeded => ErrorCategory::State,
+            Self::KeyNotFound => ErrorCategory::Key,
+            Self::MetastoreUnavailable => ErrorCategory::Metastore,
+            Self::KmsUnavailable | Self::KmsAccessDenied => ErrorCategory::Kms,
+            Self::DecryptionFailed => ErrorCategory::Crypto,
+        }
+    }
+
+    /// Whether the same call may succeed if retried later. Only transient
+    /// backend failures, and timeouts that are usually caused by one,
+    /// qualify; everything else fails again unchanged.
+    pub const fn is_retryable(self) -> bool {
+        matches!(
+            self,
+            Self::MetastoreUnavailable | Self::KmsUnavailable | Self::DeadlineExceeded
+        )
+    }
+
+    /// A new error carrying this code.
+    pub fn error(self, message: impl fmt::Display) -> anyhow::Error {
+        anyhow::Error::new(CodedError {
+            code: self,
+            inner: Inner::Message(message.to_string()),
+        })
+    }
+
+    /// Tag `err` with this code, unless it already carries one (which is
+    /// more specific). The message and chain are unchanged.
+    pub fn tag(self, err: anyhow::Error) -> anyhow::Error {
+        if Self::of(&err) != Self::Unknown {
+            return err;
+        }
+        anyhow::Error::new(CodedError {
+            code: self,
+            inner: Inner::Wrapped(err),
+        })
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// An error tagged with an [`ErrorCode`]. Displays exactly as the message
+/// or error it was made from.
+#[derive(Debug)]
+pub struct CodedError {
+    code: ErrorCode,
+    inner: Inner,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Message(String),
+    /// Stands in for the wrapped error's outermost layer: displays as that
+    /// layer and continues the chain with its source.
+    Wrapped(anyhow::Error),
+}
+
+impl CodedError {
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+}
+
+impl fmt::Display for CodedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.inner {
+            Inner::Message(message) => f.write_str(message),
+            Inner::Wrapped(err) => {
+                let outermost: &(dyn std::error::Error + Send + Sync) = err.as_ref();
+                fmt::Display::fmt(outermost, f)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CodedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.inner {
+            Inner::Message(_) => None,
+            Inner::Wrapped(err) => {
+                let outermost: &(dyn std::error::Error + Send + Sync + 'static) = err.as_ref();
+                outermost.source()
+            }
+        }
+    }
+}
+
+/// [`ErrorCode::tag`] for results, so a backend call can be tagged in place:
+/// `metastore.load(id, created).code(ErrorCode::MetastoreUnavailable)`.
+pub trait WithErrorCode<T> {
+    fn code(self, code: ErrorCode) -> anyhow::Result<T>;
+}
+
+impl<T, E> WithErrorCode<T> for Result<T, E>
+where
+    E: Into<anyhow::Error>,
+{
+    fn code(self, code: ErrorCode) -> anyhow::Result<T> {
+        self.map_err(|err| code.tag(err.into()))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn codes_round_trip_and_are_unique() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_i32(code.as_i32()), Some(code));
+            assert_eq!(
+                ErrorCode::ALL
+                    .iter()
+                    .filter(|other| other.name() == code.name())
+                    .count(),
+                1
+            );
+        }
+        assert_eq!(ErrorCode::from_i32(0), None);
+    }
+
+    #[test]
+    fn innermost_code_wins_and_messages_are_unchanged() {
+        use anyhow::Context;
+
+        let denied: anyhow::Result<()> = Err(ErrorCode::KmsAccessDenied.error("denied"));
+        let err = denied
+            .code(ErrorCode::KmsUnavailable)
+            .context("failed to decrypt system key")
+            .unwrap_err();
+        assert_eq!(ErrorCode::of(&err), ErrorCode::KmsAccessDenied);
+        assert_eq!(err.to_string(), "failed to decrypt system key");
+        assert_eq!(format!("{err:#}"), "failed to decrypt system key: denied");
+
+        let plain: anyhow::Result<()> = Err(anyhow::anyhow!("io").context("outer"));
+        let tagged = plain.code(ErrorCode::MetastoreUnavailable).unwrap_err();
+        assert_eq!(ErrorCode::of(&tagged), ErrorCode::MetastoreUnavailable);
+        assert!(ErrorCode::of(&tagged).is_retryable());
+        assert_eq!(tagged.to_string(), "outer");
+        assert_eq!(format!("{tagged:#}"), "outer: io");
+        assert_eq!(tagged.chain().count(), 2);
+    }
+}
@@ -3,8 +3,10 @@ use std::sync::OnceLock;
 
 use async_trait::async_trait;
 use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_kms::error::ProvideErrorMetadata as _;
 use aws_sdk_kms::{config::Region, primitives::Blob, Client};
 
+use crate::error::ErrorCode;
 use crate::traits::{KeyManagementService, AEAD};
 
 /// Redact the account-number segment of an AWS ARN for logging.
@@ -31,11 +33,31 @@ pub(crate) fn redact_arn(arn: &str) -> String {
     }
 }
 
+/// The [`ErrorCode`] for a failed KMS call, from the AWS error code.
+/// Refusals that a retry cannot fix are `KmsAccessDenied`; a ciphertext KMS
+/// rejects is `DecryptionFailed`; network failures, throttling and KMS-side
+/// errors are `KmsUnavailable`.
+pub(crate) fn kms_error_code(aws_code: Option<&str>) -> ErrorCode {
+    match aws_code {
+        Some(
+            "AccessDeniedException"
+            | "DisabledException"
+            | "IncorrectKeyException"
+            | "InvalidKeyUsageException"
+            | "KMSInvalidStateException"
+            | "NotFoundException"
+            | "UnrecognizedClientException",
+        ) => ErrorCode::KmsAccessDenied,
+        Some("InvalidCiphertextException") => ErrorCode::DecryptionFailed,
+        _ => ErrorCode::KmsUnavailable,
+    }
+}
+
 /// Process-wide fallback runtime. Built lazily the first time a sync KMS
 /// call lands without an existing Tokio Handle and without a per-instance
 /// runtime — replacing the per-call `tokio::runtime::Runtime::new().expect(...)`
 /// the review flagged in T4 (`docs/review-2026-05-05-findings.md`).
-fn fallback_runtime() -> Result<&'static tokio::runtime::Runtime, std::io::Error> {
+pub(crate) fn fallback_runtime() -> Result<&'static tokio::runtime::Runtime, std::io::Error> {
     static FALLBACK: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
     if let Some(rt) = FALLBACK.get() {
         return Ok(rt);
@@ -191,10 +213,10 @@ impl<A: AEAD + Send + Sync + 'static> AwsKms<A> {
                     "AwsKms encrypt_key failed: key_id={}, error={e:#}",
                     redact_arn(&self.key_id)
                 );
-                anyhow::anyhow!(
+                kms_error_code(e.code()).error(format!(
                     "KMS Encrypt call failed for key {}: {e}",
                     redact_arn(&self.key_id)
-                )
+                ))
             })?;
         let ct = resp.ciphertext_blob().ok_or_else(|| {
             anyhow::anyhow!(
@@ -223,10 +245,10 @@ impl<A: AEAD + Send + Sync + 'static> AwsKms<A> {
                     "AwsKms decrypt_key failed: key_id={}, error={e:#}",
                     redact_arn(&self.key_id)
                 );
-                anyhow::anyhow!(
+                kms_error_code(e.code()).error(format!(
                     "KMS Decrypt call failed for key {}: {e}",
                     redact_arn(&self.key_id)
-                )
+                ))
             })?;
         let pt = resp.plaintext().ok_or_else(|| {
             anyhow::anyhow!(
@@ -322,4 +344,25 @@ mod tests {
         // Drops here, inside the `#[tokio::test]` runtime — must not panic.
         drop(kms);
     }
+
+    #[test]
+    fn kms_refusals_are_not_retryable() {
+        assert_eq!(
+            kms_error_code(Some("AccessDeniedException")),
+            ErrorCode::KmsAccessDenied
+        );
+        assert_eq!(
+            kms_error_code(Some("KMSInvalidStateException")),
+            ErrorCode::KmsAccessDenied
+        );
+        assert_eq!(
+            kms_error_code(Some("InvalidCiphertextException")),
+            ErrorCode::DecryptionFailed
+        );
+        assert_eq!(
+            kms_error_code(Some("ThrottlingException")),
+            ErrorCode::KmsUnavailable
+        );
+        assert!(kms_error_code(None).is_retryable());
+    }
 }
@@ -501,6 +501,22 @@ fn new_kms_client_with_rt(
     Ok((client, resolved_region, rt_local))
 }
 
+impl<A: AEAD + Send + Sync + 'static> Drop for AwsKmsEnvelope<A> {
+    /// Same deferred shutdown as `kms_aws::AwsKms`: the `*_async`
+    /// constructors store an owned runtime, and dropping it inside another
+    /// runtime would panic, so the last holder hands it to
+    /// `shutdown_background()` there.
+    fn drop(&mut self) {
+        if let Some(rt) = self.rt.take() {
+            if let Ok(rt) = Arc::try_unwrap(rt) {
+                if tokio::runtime::Handle::try_current().is_ok() {
+                    rt.shutdown_background();
+                }
+            }
+        }
+    }
+}
+
 #[async_trait]
 impl<A: AEAD + Send + Sync + 'static> KeyManagementService for AwsKmsEnvelope<A> {
     fn encrypt_key(&self, _ctx: &(), key_bytes: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
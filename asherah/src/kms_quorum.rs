@@ -0,0 +1,528 @@
+//! Quorum (k-of-n) KMS: system keys are protected by several KMS backends at
+//! once, so compromising any `threshold - 1` of them reveals nothing.
+//!
+//! Each `encrypt_key` draws a fresh 256-bit data-encryption key (DEK), seals
+//! the system key under it with the AEAD, and splits the DEK into `n` Shamir
+//! shares over GF(2^8) with threshold `k`. Every member KMS wraps exactly one
+//! share. Decrypt unwraps shares until `k` of them reconstruct a DEK that
+//! opens the sealed key; the AEAD tag rejects a DEK rebuilt from a forged
+//! share, in which case other share combinations are tried.
+//!
+//! Unlike [`crate::kms_multi::MultiKms`] (failover: any one backend suffices)
+//! this is defense-in-depth: `k` backends must cooperate.
+//!
+//! Blob layout (all lengths big-endian):
+//!
+//! ```text
+//! "AQK1"                       magic / format version
+//! threshold        u8
+//! share_count      u8
+//! share_count × {
+//!     name_len     u8
+//!     name         name_len bytes (member name, UTF-8)
+//!     x            u8  (Shamir x-coordinate, 1..=255)
+//!     wrapped_len  u32
+//!     wrapped      wrapped_len bytes (share as wrapped by that member)
+//! }
+//! sealed           rest (AEAD(system key, DEK))
+//! ```
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use zeroize::Zeroizing;
+
+use crate::traits::{KeyManagementService, AEAD};
+
+const MAGIC: &[u8; 4] = b"AQK1";
+const DEK_LEN: usize = 32;
+
+/// A member backend and the name its share is recorded under.
+#[derive(Clone)]
+struct Member {
+    name: String,
+    kms: Arc<dyn KeyManagementService>,
+}
+
+#[allow(missing_debug_implementations)]
+pub struct QuorumKms<A: AEAD + Send + Sync + 'static> {
+    aead: Arc<A>,
+    threshold: usize,
+    members: Vec<Member>,
+}
+
+impl<A: AEAD + Send + Sync + 'static> Clone for QuorumKms<A> {
+    fn clone(&self) -> Self {
+        Self {
+            aead: Arc::clone(&self.aead),
+            threshold: self.threshold,
+            members: self.members.clone(),
+        }
+    }
+}
+
+struct WrappedShare<'blob> {
+    name: &'blob str,
+    x: u8,
+    wrapped: &'blob [u8],
+}
+
+struct ParsedBlob<'blob> {
+    threshold: usize,
+    shares: Vec<WrappedShare<'blob>>,
+    sealed: &'blob [u8],
+}
+
+/// An unwrapped share: `(x, y-bytes)`.
+type Share = (u8, Zeroizing<Vec<u8>>);
+
+/// The sealed system key plus one DEK share per member.
+type Sealed = (Vec<u8>, Vec<Zeroizing<Vec<u8>>>);
+
+impl<A: AEAD + Send + Sync + 'static> QuorumKms<A> {
+    /// `members` are `(name, kms)` pairs. Names identify shares in the blob,
+    /// so they must be unique and stay stable across config changes; member
+    /// order does not matter.
+    pub fn new(
+        aead: Arc<A>,
+        threshold: usize,
+        members: Vec<(String, Arc<dyn KeyManagementService>)>,
+    ) -> anyhow::Result<Self> {
+        if members.is_empty() {
+            anyhow::bail!("quorum KMS needs at least one member");
+        }
+        if members.len() > usize::from(u8::MAX) {
+            anyhow::bail!("quorum KMS supports at most 255 members");
+        }
+        if threshold == 0 || threshold > members.len() {
+            anyhow::bail!(
+                "quorum KMS threshold must be between 1 and the member count ({}), got {threshold}",
+                members.len()
+            );
+        }
+        let mut seen = std::collections::HashSet::new();
+        for (name, _) in &members {
+            if name.is_empty() || name.len() > usize::from(u8::MAX) {
+                anyhow::bail!("quorum KMS member names must be 1-255 bytes, got '{name}'");
+            }
+            if !seen.insert(name.as_str()) {
+                anyhow::bail!("duplicate quorum KMS member '{name}'");
+            }
+        }
+        if threshold == 1 {
+            log::warn!(
+                "Quorum KMS threshold is 1: any single member can unwrap system keys. \
+                 This is failover, not defense-in-depth."
+            );
+        }
+        Ok(Self {
+            aead,
+            threshold,
+            members: members
+                .into_iter()
+                .map(|(name, kms)| Member { name, kms })
+                .collect(),
+        })
+    }
+
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    pub fn member_names(&self) -> Vec<&str> {
+        self.members.iter().map(|m| m.name.as_str()).collect()
+    }
+
+    /// Draw a DEK, seal `key_bytes` under it and split it into one share per
+    /// member (member `i` gets x-coordinate `i + 1`).
+    fn seal(&self, key_bytes: &[u8]) -> anyhow::Result<Sealed> {
+        let mut dek = Zeroizing::new(vec![0_u8; DEK_LEN]);
+        crate::aead::fast_random_bytes(&mut dek)?;
+        let sealed = self.aead.encrypt(key_bytes, &dek)?;
+        let shares = shamir::split(&dek, self.threshold, self.members.len())?;
+        Ok((sealed, shares))
+    }
+
+    fn assemble_blob(&self, wrapped: &[Vec<u8>], sealed: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let mut out = Vec::with_capacity(
+            MAGIC.len() + 2 + wrapped.iter().map(|w| w.len() + 64).sum::<usize>() + sealed.len(),
+        );
+        out.extend_from_slice(MAGIC);
+        out.push(u8::try_from(self.threshold)?);
+        out.push(u8::try_from(self.members.len())?);
+        for (i, (member, w)) in self.members.iter().zip(wrapped).enumerate() {
+            out.push(u8::try_from(member.name.len())?);
+            out.extend_from_slice(member.name.as_bytes());
+            out.push(u8::try_from(i + 1)?);
+            out.extend_from_slice(&u32::try_from(w.len())?.to_be_bytes());
+            out.extend_from_slice(w);
+        }
+        out.extend_from_slice(sealed);
+        Ok(out)
+    }
+
+    fn member(&self, name: &str) -> Option<&Member> {
+        self.members.iter().find(|m| m.name == name)
+    }
+
+    /// Try every `threshold`-sized combination of `got` that includes the
+    /// newest share. Earlier combinations were already tried.
+    fn try_recover(&self, got: &[Share], threshold: usize, sealed: &[u8]) -> Option<Vec<u8>> {
+        let (newest, earlier) = got.split_last()?;
+        if got.len() < threshold {
+            return None;
+        }
+        for combo in combinations(earlier.len(), threshold - 1) {
+            let picked: Vec<&Share> = combo
+                .iter()
+                .map(|&i| &earlier[i])
+                .chain(std::iter::once(newest))
+                .collect();
+            let Ok(dek) = shamir::combine(&picked) else {
+                continue;
+            };
+            if let Ok(pt) = self.aead.decrypt(sealed, &dek) {
+                return Some(pt);
+            }
+        }
+        None
+    }
+
+    fn unwrap_failed(
+        &self,
+        parsed: &ParsedBlob<'_>,
+        got: usize,
+        errors: &[String],
+    ) -> anyhow::Error {
+        let detail = errors.join("; ");
+        log::error!(
+            "QuorumKms decrypt_key: recovered {got} share(s), need {}: {detail}",
+            parsed.threshold
+        );
+        anyhow::anyhow!(
+            "quorum KMS could not unwrap the system key: {got} share(s) recovered, {} required ({detail})",
+            parsed.threshold
+        )
+    }
+}
+
+fn parse_blob(blob: &[u8]) -> anyhow::Result<ParsedBlob<'_>> {
+    fn take<'blob>(rest: &mut &'blob [u8], n: usize) -> anyhow::Result<&'blob [u8]> {
+        if rest.len() < n {
+            anyhow::bail!("quorum KMS blob is truncated");
+        }
+        let (head, tail) = rest.split_at(n);
+        *rest = tail;
+        Ok(head)
+    }
+    fn take_u8(rest: &mut &[u8]) -> anyhow::Result<u8> {
+        Ok(take(rest, 1)?[0])
+    }
+
+    let mut rest = blob
+        .strip_prefix(&MAGIC[..])
+        .ok_or_else(|| anyhow::anyhow!("not a quorum KMS blob (bad magic)"))?;
+    let threshold = usize::from(take_u8(&mut rest)?);
+    let count = usize::from(take_u8(&mut rest)?);
+    if threshold == 0 || threshold > count {
+        anyhow::bail!("quorum KMS blob has invalid threshold {threshold} for {count} shares");
+    }
+    let mut shares = Vec::with_capacity(count);
+    for _ in 0..count {
+        let name_len = usize::from(take_u8(&mut rest)?);
+        let name = std::str::from_utf8(take(&mut rest, name_len)?)
+            .map_err(|_| anyhow::anyhow!("quorum KMS blob has a non-UTF-8 member name"))?;
+        let x = take_u8(&mut rest)?;
+        let len_bytes: [u8; 4] = take(&mut rest, 4)?.try_into()?;
+        let wrapped = take(&mut rest, usize::try_from(u32::from_be_bytes(len_bytes))?)?;
+        shares.push(WrappedShare { name, x, wrapped });
+    }
+    if rest.is_empty() {
+        anyhow::bail!("quorum KMS blob has no sealed key");
+    }
+    Ok(ParsedBlob {
+        threshold,
+        shares,
+        sealed: rest,
+    })
+}
+
+/// Index sets of size `k` drawn from `0..n`, in lexicographic order.
+fn combinations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k > n {
+        return Vec::new();
+    }
+    let mut out = Vec::new();
+    let mut idx: Vec<usize> = (0..k).collect();
+    loop {
+        out.push(idx.clone());
+        // Find the rightmost index that can still move right.
+        let Some(pos) = (0..k).rev().find(|&i| idx[i] < n - k + i) else {
+            return out;
+        };
+        idx[pos] += 1;
+        for j in pos + 1..k {
+            idx[j] = idx[j - 1] + 1;
+        }
+    }
+}
+
+#[async_trait]
+impl<A: AEAD + Send + Sync + 'static> KeyManagementService for QuorumKms<A> {
+    fn encrypt_key(&self, ctx: &(), key_bytes: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let (sealed, shares) = self.seal(key_bytes)?;
+        let mut wrapped = Vec::with_capacity(shares.len());
+        for (member, share) in self.members.iter().zip(&shares) {
+            wrapped.push(member.kms.encrypt_key(ctx, share).map_err(|e| {
+                log::error!(
+                    "QuorumKms encrypt_key failed on member '{}': {e:#}",
+                    member.name
+                );
+                e.context(format!(
+                    "quorum KMS member '{}' failed to wrap its share",
+                    member.name
+                ))
+            })?);
+        }
+        self.assemble_blob(&wrapped, &sealed)
+    }
+
+    fn decrypt_key(&self, ctx: &(), blob: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let parsed = parse_blob(blob)?;
+        let mut got: Vec<Share> = Vec::new();
+        let mut errors: Vec<String> = Vec::new();
+        for share in &parsed.shares {
+            let Some(member) = self.member(share.name) else {
+                errors.push(format!("{}: not a configured member", share.name));
+                continue;
+            };
+            match member.kms.decrypt_key(ctx, share.wrapped) {
+                Ok(y) => got.push((share.x, Zeroizing::new(y))),
+                Err(e) => {
+                    log::warn!(
+                        "QuorumKms decrypt_key: member '{}' failed: {e:#}",
+                        member.name
+                    );
+                    errors.push(format!("{}: {e}", member.name));
+                    continue;
+                }
+            }
+            if let Some(pt) = self.try_recover(&got, parsed.threshold, parsed.sealed) {
+                return Ok(pt);
+            }
+        }
+        Err(self.unwrap_failed(&parsed, got.len(), &errors))
+    }
+
+    /// Async encrypt awaiting each member's native async path.
+    async fn encrypt_key_async(
+        &self,
+        ctx: &(),
+        key_bytes: &[u8],
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let (sealed, shares) = self.seal(key_bytes)?;
+        let mut wrapped = Vec::with_capacity(shares.len());
+        for (member, share) in self.members.iter().zip(&shares) {
+            wrapped.push(
+                member
+                    .kms
+                    .encrypt_key_async(ctx, share)
+                    .await
+                    .map_err(|e| {
+                        log::error!(
+                            "QuorumKms encrypt_key_async failed on member '{}': {e:#}",
+                            member.name
+                        );
+                        e.context(format!(
+                            "quorum KMS member '{}' failed to wrap its share",
+                            member.name
+                        ))
+                    })?,
+            );
+        }
+        self.assemble_blob(&wrapped, &sealed)
+    }
+
+    /// Async decrypt mirroring `decrypt_key` on each member's async path.
+    async fn decrypt_key_async(&self, ctx: &(), blob: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let parsed = parse_blob(blob)?;
+        let mut got: Vec<Share> = Vec::new();
+        let mut errors: Vec<String> = Vec::new();
+        for share in &parsed.shares {
+            let Some(member) = self.member(share.name) else {
+                errors.push(format!("{}: not a configured member", share.name));
+                continue;
+            };
+            match member.kms.decrypt_key_async(ctx, share.wrapped).await {
+                Ok(y) => got.push((share.x, Zeroizing::new(y))),
+                Err(e) => {
+                    log::warn!(
+                        "QuorumKms decrypt_key_async: member '{}' failed: {e:#}",
+                        member.name
+                    );
+                    errors.push(format!("{}: {e}", member.name));
+                    continue;
+                }
+            }
+            if let Some(pt) = self.try_recover(&got, parsed.threshold, parsed.sealed) {
+                return Ok(pt);
+            }
+        }
+        Err(self.unwrap_failed(&parsed, got.len(), &errors))
+    }
+}
+
+/// Shamir secret sharing over GF(2^8) (AES polynomial), applied bytewise.
+/// Field arithmetic is branch-free so share values don't leak via timing.
+mod shamir {
+    use zeroize::Zeroizing;
+
+    fn mul(mut a: u8, mut b: u8) -> u8 {
+        let mut p = 0_u8;
+        for _ in 0..8 {
+            p ^= a & 0_u8.wrapping_sub(b & 1);
+            let carry = 0_u8.wrapping_sub(a >> 7);
+            a = (a << 1) ^ (0x1b & carry);
+            b >>= 1;
+        }
+        p
+    }
+
+    /// Multiplicative inverse as a^254; `inv(0)` is 0.
+    fn inv(a: u8) -> u8 {
+        let mut result = 1_u8;
+        let mut base = a;
+        let mut e = 254_u8;
+        while e > 0 {
+            let take = 0_u8.wrapping_sub(e & 1);
+            result = (mul(result, base) & take) | (result & !take);
+            base = mul(base, base);
+            e >>= 1;
+        }
+        result
+    }
+
+    /// Split `secret` into `n` shares; share `i` is the polynomial evaluated
+    /// at `x = i + 1`.
+    pub(super) fn split(
+        secret: &[u8],
+        threshold: usize,
+        n: usize,
+    ) -> anyhow::Result<Vec<Zeroizing<Vec<u8>>>> {
+        let mut coeffs = Zeroizing::new(vec![0_u8; secret.len() * (threshold - 1)]);
+        crate::aead::fast_random_bytes(&mut coeffs)?;
+        let mut shares = Vec::with_capacity(n);
+        for i in 0..n {
+            let x = u8::try_from(i + 1)?;
+            let mut share = Zeroizing::new(vec![0_u8; secret.len()]);
+            for (j, (&s, out)) in secret.iter().zip(share.iter_mut()).enumerate() {
+                // Horner: ((c_{k-1} x + c_{k-2}) x + ... + c_1) x + s
+                let mut y = 0_u8;
+                for c in coeffs[j * (threshold - 1)..(j + 1) * (threshold - 1)]
+                    .iter()
+                    .rev()
+                {
+                    y = mul(y, x) ^ c;
+                }
+                *out = mul(y, x) ^ s;
+            }
+            shares.push(share);
+        }
+        Ok(shares)
+    }
+
+    /// Lagrange interpolation at `x = 0`.
+    pub(super) fn combine(
+        shares: &[&(u8, Zeroizing<Vec<u8>>)],
+    ) -> anyhow::Result<Zeroizing<Vec<u8>>> {
+        let Some(first) = shares.first() else {
+            anyhow::bail!("no shares to combine");
+        };
+        let len = first.1.len();
+        for (i, (x, y)) in shares.iter().map(|s| (s.0, &s.1)).enumerate() {
+            if x == 0 || y.len() != len {
+                anyhow::bail!("malformed share");
+            }
+            if shares[..i].iter().any(|s| s.0 == x) {
+                anyhow::bail!("duplicate share x-coordinate {x}");
+            }
+        }
+        let mut secret = Zeroizing::new(vec![0_u8; len]);
+        for (i, share) in shares.iter().enumerate() {
+            // l_i(0) = prod_{j != i} x_j / (x_j - x_i); subtraction is XOR.
+            let mut num = 1_u8;
+            let mut den = 1_u8;
+            for (j, other) in shares.iter().enumerate() {
+                if i != j {
+                    num = mul(num, other.0);
+                    den = mul(den, other.0 ^ share.0);
+                }
+            }
+            let coeff = mul(num, inv(den));
+            for (out, &y) in secret.iter_mut().zip(share.1.iter()) {
+                *out ^= mul(coeff, y);
+            }
+        }
+        Ok(secret)
+    }
+
+    #[cfg(test)]
+    #[allow(clippy::unwrap_used)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn field_inverse() {
+            for a in 1..=255_u8 {
+                assert_eq!(mul(a, inv(a)), 1, "a={a}");
+            }
+        }
+
+        #[test]
+        fn any_threshold_subset_recovers() {
+            let secret = b"0123456789abcdef0123456789abcdef";
+            let shares: Vec<(u8, Zeroizing<Vec<u8>>)> = split(secret, 3, 5)
+                .unwrap()
+                .into_iter()
+                .enumerate()
+                .map(|(i, s)| (u8::try_from(i + 1).unwrap(), s))
+                .collect();
+            for combo in super::super::combinations(5, 3) {
+                let picked: Vec<_> = combo.iter().map(|&i| &shares[i]).collect();
+                assert_eq!(combine(&picked).unwrap().as_slice(), secret);
+            }
+            let two: Vec<_> = shares.iter().take(2).collect();
+            assert_ne!(combine(&two).unwrap().as_slice(), secret);
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn combinations_enumerates_subsets() {
+        assert_eq!(
+            combinations(4, 2),
+            vec![
+                vec![0, 1],
+                vec![0, 2],
+                vec![0, 3],
+                vec![1, 2],
+                vec![1, 3],
+                vec![2, 3]
+            ]
+        );
+        assert_eq!(combinations(3, 0), vec![Vec::<usize>::new()]);
+        assert!(combinations(2, 3).is_empty());
+    }
+
+    #[test]
+    fn parse_rejects_truncated_blobs() {
+        assert!(parse_blob(b"nope").is_err());
+        assert!(parse_blob(b"AQK1\x01\x01\x01a\x01\x00\x00\x00\x09").is_err());
+        assert!(parse_blob(b"AQK1\x02\x01").is_err());
+    }
+}
@@ -1,107 +1,145 @@
-// AWS Secrets Manager KMS — fetches a static master key from Secrets Manager
-// at construction time and uses it for the lifetime of the process.
-// This is a security posture improvement over KMS=static (key not in env vars)
-// but NOT a key management improvement (no rotation without re-encryption).
+// AWS Secrets Manager KMS — uses a master key stored as a Secrets Manager
+// secret. This is a security posture improvement over KMS=static (key not in
+// env vars) but NOT a key management improvement: the key material is still
+// held in process memory.
+//
+// Secret rotation is survivable: every wrapped system key is prefixed with the
+// Secrets Manager `VersionId` that wrapped it, the previous version
+// (AWSPREVIOUS) is kept in a small bounded cache, and a blob naming a version
+// that isn't cached triggers a fetch of that exact version.
 
+use std::collections::VecDeque;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_secretsmanager::{config::Region, Client};
+use parking_lot::RwLock;
 use zeroize::Zeroizing;
 
 use crate::traits::{KeyManagementService, AEAD};
 
-#[allow(missing_debug_implementations)]
-pub struct SecretsManagerKMS<A: AEAD + Send + Sync + 'static> {
-    aead: Arc<A>,
-    /// Master key fetched from Secrets Manager. `Zeroizing` volatile-wipes
-    /// the buffer when the last `Arc` clone is dropped, so the key bytes
-    /// don't linger in the freed allocator slab. T-finding "master_key
-    /// plaintext, never wiped" in `docs/review-2026-05-05-findings.md`.
-    master_key: Arc<Zeroizing<Vec<u8>>>,
+/// Header that marks a wrapped key as carrying a Secrets Manager version id.
+///
+/// Layout: `magic (4) || version_id_len (1) || version_id || aead_blob`.
+/// Blobs without the header were written before version tracking existed and
+/// are opened by trying each cached key in turn.
+pub const VERSIONED_BLOB_MAGIC: [u8; 4] = *b"ASM1";
+
+/// Default number of non-current secret versions kept in memory.
+pub const DEFAULT_MAX_PREVIOUS_VERSIONS: usize = 4;
+
+/// How long an encrypt may use the cached AWSCURRENT before re-checking it.
+/// Encrypts only happen when a system key is created, so the re-check is rare.
+const CURRENT_REFRESH_INTERVAL: Duration = Duration::from_secs(300);
+
+/// Which version of the secret to fetch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretVersion {
+    /// The `AWSCURRENT` staging label.
+    Current,
+    /// The `AWSPREVIOUS` staging label.
+    Previous,
+    /// An exact `VersionId`.
+    Id(String),
 }
 
-impl<A: AEAD + Send + Sync + 'static> Clone for SecretsManagerKMS<A> {
-    fn clone(&self) -> Self {
-        Self {
-            aead: Arc::clone(&self.aead),
-            master_key: Arc::clone(&self.master_key),
-        }
+/// One version of the master key secret.
+pub struct SecretValue {
+    pub version_id: String,
+    pub key: Zeroizing<Vec<u8>>,
+}
+
+impl std::fmt::Debug for SecretValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretValue")
+            .field("version_id", &self.version_id)
+            .field("key", &"<redacted>")
+            .finish()
     }
 }
 
-impl<A: AEAD + Send + Sync + 'static> SecretsManagerKMS<A> {
-    /// Sync constructor — fetches the secret from Secrets Manager, blocking on a
-    /// tokio runtime. The secret must be either:
-    /// - A hex-encoded 32-byte key (64 hex characters) stored as SecretString, or
-    /// - A raw 32-byte value stored as SecretBinary.
-    ///
-    /// `aws_profile_name` selects an aws-config named profile (typically
-    /// from `~/.aws/credentials`); pass `None` for the default credential
-    /// chain.
-    pub fn new(
-        aead: Arc<A>,
+/// Where `SecretsManagerKMS` reads secret versions from.
+///
+/// The production implementation is [`AwsSecretSource`]; tests inject their
+/// own to simulate rotation without AWS.
+#[async_trait]
+pub trait SecretVersionSource: Send + Sync {
+    /// Fetch one version. `Ok(None)` means the version does not exist (for
+    /// example no `AWSPREVIOUS` before the first rotation, or a `VersionId`
+    /// that has been deprecated and deleted).
+    async fn fetch(&self, version: &SecretVersion) -> anyhow::Result<Option<SecretValue>>;
+}
+
+/// [`SecretVersionSource`] backed by the AWS Secrets Manager API.
+#[allow(missing_debug_implementations)]
+pub struct AwsSecretSource {
+    client: Client,
+    secret_id: String,
+}
+
+impl AwsSecretSource {
+    /// Build a Secrets Manager client. `AWS_ENDPOINT_URL` overrides the
+    /// endpoint (LocalStack).
+    pub async fn new(
         secret_id: impl Into<String>,
         region: Option<String>,
         aws_profile_name: Option<&str>,
-    ) -> anyhow::Result<Self> {
-        let secret_id = secret_id.into();
-        let handle = tokio::runtime::Handle::try_current().ok();
-        let rt = if handle.is_some() {
-            None
+    ) -> Self {
+        let region_provider = if let Some(r) = region {
+            RegionProviderChain::first_try(Region::new(r))
         } else {
-            Some(tokio::runtime::Runtime::new()?)
+            RegionProviderChain::default_provider()
         };
-        let fetch_fut = fetch_secret(&secret_id, region, aws_profile_name);
-        let master_key = match (&rt, handle) {
-            (Some(rt), _) => {
-                if tokio::runtime::Handle::try_current().is_ok() {
-                    tokio::task::block_in_place(|| rt.block_on(fetch_fut))
-                } else {
-                    rt.block_on(fetch_fut)
-                }
-            }
-            (None, Some(h)) => tokio::task::block_in_place(|| h.block_on(fetch_fut)),
-            (None, None) => unreachable!("tokio runtime unavailable"),
-        }?;
-        log::warn!(
-            "Using static master key from Secrets Manager (secret_id={secret_id}). \
-             This is better than an environment variable but the key is still static — \
-             there is no automatic rotation of the master key."
-        );
-        Ok(Self {
-            aead,
-            // `master_key` is already `Zeroizing<Vec<u8>>` from
-            // `fetch_secret`; wrap in Arc for sharing across clones.
-            master_key: Arc::new(master_key),
-        })
+        let shared_config =
+            crate::aws_sdk_load::load_sdk_config(region_provider, aws_profile_name).await;
+        let mut b = aws_sdk_secretsmanager::config::Builder::from(&shared_config);
+        if let Ok(url) = std::env::var("AWS_ENDPOINT_URL") {
+            b = b.endpoint_url(url);
+        }
+        Self {
+            client: Client::from_conf(b.build()),
+            secret_id: secret_id.into(),
+        }
     }
+}
 
-    /// Async constructor — fetches the secret on the caller's runtime.
-    pub async fn new_async(
-        aead: Arc<A>,
-        secret_id: impl Into<String>,
-        region: Option<String>,
-        aws_profile_name: Option<&str>,
-    ) -> anyhow::Result<Self> {
-        let secret_id = secret_id.into();
-        let master_key = fetch_secret(&secret_id, region, aws_profile_name).await?;
-        log::warn!(
-            "Using static master key from Secrets Manager (secret_id={secret_id}). \
-             This is better than an environment variable but the key is still static — \
-             there is no automatic rotation of the master key."
-        );
-        Ok(Self {
-            aead,
-            // `master_key` is already `Zeroizing<Vec<u8>>` from
-            // `fetch_secret`; wrap in Arc for sharing across clones.
-            master_key: Arc::new(master_key),
-        })
+#[async_trait]
+impl SecretVersionSource for AwsSecretSource {
+    async fn fetch(&self, version: &SecretVersion) -> anyhow::Result<Option<SecretValue>> {
+        let req = self.client.get_secret_value().secret_id(&self.secret_id);
+        let req = match version {
+            SecretVersion::Current => req.version_stage("AWSCURRENT"),
+            SecretVersion::Previous => req.version_stage("AWSPREVIOUS"),
+            SecretVersion::Id(id) => req.version_id(id),
+        };
+        let resp = match req.send().await {
+            Ok(resp) => resp,
+            // A missing AWSCURRENT means the secret itself is missing, which
+            // stays a hard error; a missing AWSPREVIOUS or VersionId is
+            // reported as absent.
+            Err(e)
+                if *version != SecretVersion::Current
+                    && e.as_service_error()
+                        .is_some_and(|se| se.is_resource_not_found_exception()) =>
+            {
+                return Ok(None);
+            }
+            Err(e) => anyhow::bail!("Secrets Manager GetSecretValue failed: {e}"),
+        };
+        let version_id = resp.version_id().map(str::to_string).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Secrets Manager secret '{}' returned no VersionId",
+                self.secret_id
+            )
+        })?;
+        let key = decode_secret(&self.secret_id, resp.secret_string(), resp.secret_binary())?;
+        Ok(Some(SecretValue { version_id, key }))
     }
 }
 
-/// Fetch a 32-byte master key from AWS Secrets Manager.
+/// Decode a 32-byte master key from a GetSecretValue response.
 ///
 /// Tries SecretString first (hex-encoded), then SecretBinary (raw 32
 /// bytes). Returns the key wrapped in `Zeroizing<Vec<u8>>` so the
@@ -109,33 +147,13 @@ impl<A: AEAD + Send + Sync + 'static> SecretsManagerKMS<A> {
 /// drop coverage — the previous return shape was an unwrapped `Vec`
 /// that was wrapped at the call site, leaving a microsecond window
 /// where the bytes lived in a non-zeroizing container.
-async fn fetch_secret(
+fn decode_secret(
     secret_id: &str,
-    region: Option<String>,
-    aws_profile_name: Option<&str>,
+    secret_string: Option<&str>,
+    secret_binary: Option<&aws_sdk_secretsmanager::primitives::Blob>,
 ) -> anyhow::Result<Zeroizing<Vec<u8>>> {
-    let region_provider = if let Some(r) = region {
-        RegionProviderChain::first_try(Region::new(r))
-    } else {
-        RegionProviderChain::default_provider()
-    };
-    let shared_config =
-        crate::aws_sdk_load::load_sdk_config(region_provider, aws_profile_name).await;
-    let mut b = aws_sdk_secretsmanager::config::Builder::from(&shared_config);
-    if let Ok(url) = std::env::var("AWS_ENDPOINT_URL") {
-        b = b.endpoint_url(url);
-    }
-    let client = Client::from_conf(b.build());
-
-    let resp = client
-        .get_secret_value()
-        .secret_id(secret_id)
-        .send()
-        .await
-        .map_err(|e| anyhow::anyhow!("Secrets Manager GetSecretValue failed: {e}"))?;
-
     // Prefer SecretString (hex-encoded) over SecretBinary
-    if let Some(hex) = resp.secret_string() {
+    if let Some(hex) = secret_string {
         // Tolerate whitespace anywhere (some operators paste keys with
         // CR/LF) and an optional `0x` prefix. The error path zeroizes the
         // intermediate buffer so a half-decoded key doesn't linger in
@@ -171,13 +189,10 @@ async fn fetch_secret(
                 key.len()
             );
         }
-        // Return the wrapper directly — the caller stores it inside
-        // its own `Arc<Zeroizing<Vec<u8>>>` so wipe-on-drop coverage
-        // is unbroken across the handoff.
         return Ok(key);
     }
 
-    if let Some(blob) = resp.secret_binary() {
+    if let Some(blob) = secret_binary {
         let bytes = blob.as_ref();
         if bytes.len() != 32 {
             anyhow::bail!(
@@ -191,25 +206,526 @@ async fn fetch_secret(
     anyhow::bail!("Secrets Manager secret '{secret_id}' has neither SecretString nor SecretBinary")
 }
 
-#[async_trait]
-impl<A: AEAD + Send + Sync + 'static> KeyManagementService for SecretsManagerKMS<A> {
-    fn encrypt_key(&self, _ctx: &(), key_bytes: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
-        self.aead
-            .encrypt(key_bytes, self.master_key.as_slice())
+/// Prefix an AEAD blob with the version id that wrapped it.
+fn wrap_versioned(version_id: &str, inner: &[u8]) -> anyhow::Result<Vec<u8>> {
+    let id_len = u8::try_from(version_id.len()).map_err(|_| {
+        anyhow::anyhow!(
+            "Secrets Manager VersionId is {} bytes; at most 255 fit in the blob header",
+            version_id.len()
+        )
+    })?;
+    let mut out =
+        Vec::with_capacity(VERSIONED_BLOB_MAGIC.len() + 1 + version_id.len() + inner.len());
+    out.extend_from_slice(&VERSIONED_BLOB_MAGIC);
+    out.push(id_len);
+    out.extend_from_slice(version_id.as_bytes());
+    out.extend_from_slice(inner);
+    Ok(out)
+}
+
+/// Split a versioned blob into `(version_id, aead_blob)`.
+///
+/// Returns `None` for blobs without the header — those predate version
+/// tracking (or were written by `StaticKMS` with the same key).
+pub fn split_versioned_blob(blob: &[u8]) -> Option<(&str, &[u8])> {
+    let rest = blob.strip_prefix(&VERSIONED_BLOB_MAGIC[..])?;
+    let (&id_len, rest) = rest.split_first()?;
+    let id_len = usize::from(id_len);
+    if id_len == 0 || rest.len() <= id_len {
+        return None;
+    }
+    let (id, inner) = rest.split_at(id_len);
+    Some((std::str::from_utf8(id).ok()?, inner))
+}
+
+#[derive(Clone)]
+struct VersionedKey {
+    version_id: String,
+    /// `Zeroizing` volatile-wipes the buffer when the last `Arc` clone is
+    /// dropped, so key bytes don't linger in the freed allocator slab.
+    /// T-finding "master_key plaintext, never wiped" in
+    /// `docs/review-2026-05-05-findings.md`.
+    key: Arc<Zeroizing<Vec<u8>>>,
+}
+
+impl From<SecretValue> for VersionedKey {
+    fn from(v: SecretValue) -> Self {
+        Self {
+            version_id: v.version_id,
+            key: Arc::new(v.key),
+        }
+    }
+}
+
+/// The current key plus a bounded, most-recent-first cache of older ones.
+struct KeyRing {
+    current: VersionedKey,
+    previous: VecDeque<VersionedKey>,
+    max_previous: usize,
+    /// When AWSCURRENT was last checked (successfully or not).
+    checked_at: Instant,
+}
+
+impl KeyRing {
+    fn get(&self, version_id: &str) -> Option<&VersionedKey> {
+        std::iter::once(&self.current)
+            .chain(self.previous.iter())
+            .find(|k| k.version_id == version_id)
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &VersionedKey> {
+        std::iter::once(&self.current).chain(self.previous.iter())
+    }
+
+    /// Cache a non-current version, evicting the oldest beyond the bound.
+    fn remember(&mut self, key: VersionedKey) {
+        if self.get(&key.version_id).is_some() {
+            return;
+        }
+        self.previous.push_front(key);
+        self.previous.truncate(self.max_previous);
+    }
+
+    /// Make `key` current, demoting the old current into the cache.
+    fn promote(&mut self, key: VersionedKey) {
+        if key.version_id == self.current.version_id {
+            return;
+        }
+        self.previous.retain(|k| k.version_id != key.version_id);
+        let old = std::mem::replace(&mut self.current, key);
+        log::info!(
+            "SecretsManagerKMS: master key version changed {} -> {}",
+            old.version_id,
+            self.current.version_id
+        );
+        self.remember(old);
+    }
+}
+
+enum Attempt {
+    Done(anyhow::Result<Vec<u8>>),
+    /// Versioned blob whose version isn't cached.
+    UnknownVersion(String),
+    /// Unversioned blob that no cached key opens.
+    Legacy,
+}
+
+#[allow(missing_debug_implementations)]
+pub struct SecretsManagerKMS<A: AEAD + Send + Sync + 'static> {
+    aead: Arc<A>,
+    source: Arc<dyn SecretVersionSource>,
+    ring: Arc<RwLock<KeyRing>>,
+    rt: Option<Arc<tokio::runtime::Runtime>>, // present when we created one
+}
+
+impl<A: AEAD + Send + Sync + 'static> Clone for SecretsManagerKMS<A> {
+    fn clone(&self) -> Self {
+        Self {
+            aead: Arc::clone(&self.aead),
+            source: Arc::clone(&self.source),
+            ring: Arc::clone(&self.ring),
+            rt: self.rt.clone(),
+        }
+    }
+}
+
+impl<A: AEAD + Send + Sync + 'static> SecretsManagerKMS<A> {
+    /// Sync constructor — fetches the secret from Secrets Manager, blocking on a
+    /// tokio runtime. The secret must be either:
+    /// - A hex-encoded 32-byte key (64 hex characters) stored as SecretString, or
+    /// - A raw 32-byte value stored as SecretBinary.
+    ///
+    /// `AWSCURRENT` is required; `AWSPREVIOUS` is loaded too when it exists.
+    ///
+    /// `aws_profile_name` selects an aws-config named profile (typically
+    /// from `~/.aws/credentials`); pass `None` for the default credential
+    /// chain.
+    pub fn new(
+        aead: Arc<A>,
+        secret_id: impl Into<String>,
+        region: Option<String>,
+        aws_profile_name: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let secret_id = secret_id.into();
+        let rt = Self::runtime_for_sync()?;
+        let source = block_on_result(rt.as_deref(), async {
+            Ok(AwsSecretSource::new(secret_id.clone(), region, aws_profile_name).await)
+        })?;
+        let source: Arc<dyn SecretVersionSource> = Arc::new(source);
+        let ring = block_on_result(rt.as_deref(), load_ring(source.as_ref()))?;
+        Ok(Self::assemble(aead, source, ring, rt, &secret_id))
+    }
+
+    /// Async constructor — fetches the secret on the caller's runtime.
+    pub async fn new_async(
+        aead: Arc<A>,
+        secret_id: impl Into<String>,
+        region: Option<String>,
+        aws_profile_name: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let secret_id = secret_id.into();
+        let source: Arc<dyn SecretVersionSource> =
+            Arc::new(AwsSecretSource::new(secret_id.clone(), region, aws_profile_name).await);
+        Self::with_source_async(aead, source, &secret_id).await
+    }
+
+    /// Sync constructor over an arbitrary [`SecretVersionSource`].
+    /// `label` only appears in log lines.
+    pub fn with_source(
+        aead: Arc<A>,
+        source: Arc<dyn SecretVersionSource>,
+        label: &str,
+    ) -> anyhow::Result<Self> {
+        let rt = Self::runtime_for_sync()?;
+        let ring = block_on_result(rt.as_deref(), load_ring(source.as_ref()))?;
+        Ok(Self::assemble(aead, source, ring, rt, label))
+    }
+
+    /// Async constructor over an arbitrary [`SecretVersionSource`].
+    pub async fn with_source_async(
+        aead: Arc<A>,
+        source: Arc<dyn SecretVersionSource>,
+        label: &str,
+    ) -> anyhow::Result<Self> {
+        let ring = load_ring(source.as_ref()).await?;
+        // Keep a runtime for sync callers (encrypt_key/decrypt_key)
+        let rt = Some(Arc::new(tokio::runtime::Runtime::new()?));
+        Ok(Self::assemble(aead, source, ring, rt, label))
+    }
+
+    /// Bound the number of non-current versions kept in memory (default
+    /// [`DEFAULT_MAX_PREVIOUS_VERSIONS`]). Evicted versions are re-fetched on
+    /// demand when a blob names them.
+    #[must_use]
+    pub fn with_max_previous_versions(self, max: usize) -> Self {
+        {
+            let mut ring = self.ring.write();
+            ring.max_previous = max;
+            ring.previous.truncate(max);
+        }
+        self
+    }
+
+    /// `VersionId` of the key new blobs are wrapped under.
+    pub fn current_version_id(&self) -> String {
+        self.ring.read().current.version_id.clone()
+    }
+
+    /// `VersionId`s held in memory, current first.
+    pub fn cached_version_ids(&self) -> Vec<String> {
+        self.ring
+            .read()
+            .keys()
+            .map(|k| k.version_id.clone())
+            .collect()
+    }
+
+    /// Re-read `AWSCURRENT` (and `AWSPREVIOUS`) now instead of waiting for
+    /// the next scheduled check.
+    pub fn refresh(&self) -> anyhow::Result<()> {
+        self.block_on_result(self.refresh_async())
+    }
+
+    /// Async variant of [`Self::refresh`].
+    pub async fn refresh_async(&self) -> anyhow::Result<()> {
+        self.ring.write().checked_at = Instant::now();
+        let current = self
+            .source
+            .fetch(&SecretVersion::Current)
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("Secrets Manager secret has no AWSCURRENT version"))?;
+        self.ring.write().promote(current.into());
+        match self.source.fetch(&SecretVersion::Previous).await {
+            Ok(Some(previous)) => self.ring.write().remember(previous.into()),
+            Ok(None) => {}
+            Err(e) => log::warn!("SecretsManagerKMS: AWSPREVIOUS refresh failed: {e:#}"),
+        }
+        Ok(())
+    }
+
+    fn runtime_for_sync() -> anyhow::Result<Option<Arc<tokio::runtime::Runtime>>> {
+        if tokio::runtime::Handle::try_current().is_ok() {
+            Ok(None)
+        } else {
+            Ok(Some(Arc::new(tokio::runtime::Runtime::new()?)))
+        }
+    }
+
+    fn assemble(
+        aead: Arc<A>,
+        source: Arc<dyn SecretVersionSource>,
+        ring: KeyRing,
+        rt: Option<Arc<tokio::runtime::Runtime>>,
+        label: &str,
+    ) -> Self {
+        log::warn!(
+            "Using master key from Secrets Manager (secret_id={label}, version={}). \
+             This is better than an environment variable but the key material is still \
+             held in process memory.",
+            ring.current.version_id
+        );
+        Self {
+            aead,
+            source,
+            ring: Arc::new(RwLock::new(ring)),
+            rt,
+        }
+    }
+
+    fn block_on_result<T, F>(&self, f: F) -> anyhow::Result<T>
+    where
+        F: std::future::Future<Output = anyhow::Result<T>>,
+    {
+        block_on_result(self.rt.as_deref(), f)
+    }
+
+    /// The current key when it was checked recently, otherwise `None`.
+    fn fresh_current(&self) -> Option<VersionedKey> {
+        let ring = self.ring.read();
+        (ring.checked_at.elapsed() < CURRENT_REFRESH_INTERVAL).then(|| ring.current.clone())
+    }
+
+    fn encrypt_with(&self, key: &VersionedKey, key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let inner = self
+            .aead
+            .encrypt(key_bytes, key.key.as_slice())
             .map_err(|e| {
                 log::error!("SecretsManagerKMS encrypt_key failed: {e:#}");
                 e
-            })
+            })?;
+        wrap_versioned(&key.version_id, &inner)
+    }
+
+    fn log_refresh_failure(e: &anyhow::Error) {
+        log::warn!(
+            "SecretsManagerKMS: AWSCURRENT refresh failed, encrypting under the cached version: {e:#}"
+        );
+    }
+
+    /// Try every cached key against an unversioned blob.
+    fn open_legacy(&self, ring: &KeyRing, blob: &[u8]) -> Option<Vec<u8>> {
+        ring.keys()
+            .find_map(|k| self.aead.decrypt(blob, k.key.as_slice()).ok())
+    }
+
+    fn try_cached(&self, blob: &[u8]) -> Attempt {
+        let ring = self.ring.read();
+        let Some((version_id, inner)) = split_versioned_blob(blob) else {
+            return match self.open_legacy(&ring, blob) {
+                Some(pt) => Attempt::Done(Ok(pt)),
+                None => Attempt::Legacy,
+            };
+        };
+        let Some(key) = ring.get(version_id) else {
+            return Attempt::UnknownVersion(version_id.to_string());
+        };
+        match self.aead.decrypt(inner, key.key.as_slice()) {
+            Ok(pt) => Attempt::Done(Ok(pt)),
+            // An unversioned blob can start with the magic bytes by chance;
+            // give the legacy path a go before reporting the failure.
+            Err(e) => match self.open_legacy(&ring, blob) {
+                Some(pt) => Attempt::Done(Ok(pt)),
+                None => Attempt::Done(Err(e.context(format!(
+                    "decrypt under Secrets Manager version {version_id} failed"
+                )))),
+            },
+        }
+    }
+
+    /// Make `version_id` available: refresh the staging labels first (the
+    /// usual reason for an unknown version is a rotation), then fetch the
+    /// exact version if it is still missing.
+    async fn load_version_async(&self, version_id: &str) -> anyhow::Result<()> {
+        if let Err(e) = self.refresh_async().await {
+            log::warn!("SecretsManagerKMS: AWSCURRENT refresh failed: {e:#}");
+        }
+        if self.ring.read().get(version_id).is_some() {
+            return Ok(());
+        }
+        let value = self
+            .source
+            .fetch(&SecretVersion::Id(version_id.to_string()))
+            .await?
+            .ok_or_else(|| {
+                anyhow::anyhow!("Secrets Manager has no version {version_id} of the master key")
+            })?;
+        self.ring.write().remember(value.into());
+        Ok(())
+    }
+
+    fn finish(&self, attempt: Attempt, blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let result = match attempt {
+            Attempt::Done(r) => r,
+            Attempt::UnknownVersion(version_id) => Err(anyhow::anyhow!(
+                "Secrets Manager version {version_id} could not be loaded"
+            )),
+            Attempt::Legacy => Err(anyhow::anyhow!(
+                "no cached Secrets Manager master-key version decrypts this blob"
+            )),
+        };
+        result.map_err(|e| {
+            log::error!(
+                "SecretsManagerKMS decrypt_key failed (blob_len={}): {e:#}",
+                blob.len()
+            );
+            e
+        })
+    }
+}
+
+/// Run a fallible future to completion from a sync caller.
+///
+/// Same preference order as `AwsKms`: the per-instance runtime, then the
+/// caller's current Tokio Handle, then the process-wide fallback runtime.
+fn block_on_result<T, F>(rt: Option<&tokio::runtime::Runtime>, f: F) -> anyhow::Result<T>
+where
+    F: std::future::Future<Output = anyhow::Result<T>>,
+{
+    if let Some(rt) = rt {
+        return if tokio::runtime::Handle::try_current().is_ok() {
+            tokio::task::block_in_place(|| rt.block_on(f))
+        } else {
+            rt.block_on(f)
+        };
+    }
+    if let Ok(h) = tokio::runtime::Handle::try_current() {
+        return tokio::task::block_in_place(|| h.block_on(f));
+    }
+    let rt = crate::kms_aws::fallback_runtime().map_err(|e| {
+        anyhow::anyhow!("SecretsManagerKMS: failed to build fallback tokio runtime: {e}")
+    })?;
+    rt.block_on(f)
+}
+
+/// Load AWSCURRENT (required) and AWSPREVIOUS (best effort).
+async fn load_ring(source: &dyn SecretVersionSource) -> anyhow::Result<KeyRing> {
+    let current = source
+        .fetch(&SecretVersion::Current)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Secrets Manager secret has no AWSCURRENT version"))?;
+    let mut ring = KeyRing {
+        current: current.into(),
+        previous: VecDeque::new(),
+        max_previous: DEFAULT_MAX_PREVIOUS_VERSIONS,
+        checked_at: Instant::now(),
+    };
+    match source.fetch(&SecretVersion::Previous).await {
+        Ok(Some(previous)) => ring.remember(previous.into()),
+        Ok(None) => {}
+        Err(e) => log::warn!("SecretsManagerKMS: could not load AWSPREVIOUS: {e:#}"),
+    }
+    Ok(ring)
+}
+
+impl<A: AEAD + Send + Sync + 'static> Drop for SecretsManagerKMS<A> {
+    /// Shut the per-instance runtime down without blocking the current
+    /// thread; see `AwsKms`'s Drop for why a blocking drop inside another
+    /// runtime panics.
+    fn drop(&mut self) {
+        if let Some(rt) = self.rt.take() {
+            if let Ok(rt) = Arc::try_unwrap(rt) {
+                if tokio::runtime::Handle::try_current().is_ok() {
+                    rt.shutdown_background();
+                }
+            }
+        }
     }
+}
+
+#[async_trait]
+impl<A: AEAD + Send + Sync + 'static> KeyManagementService for SecretsManagerKMS<A> {
+    fn encrypt_key(&self, _ctx: &(), key_bytes: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let key = match self.fresh_current() {
+            Some(key) => key,
+            None => {
+                if let Err(e) = self.refresh() {
+                    Self::log_refresh_failure(&e);
+                }
+                self.ring.read().current.clone()
+            }
+        };
+        self.encrypt_with(&key, key_bytes)
+    }
+
     fn decrypt_key(&self, _ctx: &(), blob: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
-        self.aead
-            .decrypt(blob, self.master_key.as_slice())
-            .map_err(|e| {
-                log::error!(
-                    "SecretsManagerKMS decrypt_key failed (blob_len={}): {e:#}",
-                    blob.len()
-                );
-                e
-            })
+        let attempt = match self.try_cached(blob) {
+            Attempt::UnknownVersion(version_id) => {
+                match self.block_on_result(self.load_version_async(&version_id)) {
+                    Ok(()) => self.try_cached(blob),
+                    Err(e) => Attempt::Done(Err(e)),
+                }
+            }
+            Attempt::Legacy => {
+                if let Err(e) = self.refresh() {
+                    log::warn!("SecretsManagerKMS: AWSCURRENT refresh failed: {e:#}");
+                }
+                self.try_cached(blob)
+            }
+            done @ Attempt::Done(_) => done,
+        };
+        self.finish(attempt, blob)
+    }
+
+    async fn encrypt_key_async(
+        &self,
+        _ctx: &(),
+        key_bytes: &[u8],
+    ) -> Result<Vec<u8>, anyhow::Error> {
+        let key = match self.fresh_current() {
+            Some(key) => key,
+            None => {
+                if let Err(e) = self.refresh_async().await {
+                    Self::log_refresh_failure(&e);
+                }
+                self.ring.read().current.clone()
+            }
+        };
+        self.encrypt_with(&key, key_bytes)
+    }
+
+    async fn decrypt_key_async(&self, _ctx: &(), blob: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+        let attempt = match self.try_cached(blob) {
+            Attempt::UnknownVersion(version_id) => {
+                match self.load_version_async(&version_id).await {
+                    Ok(()) => self.try_cached(blob),
+                    Err(e) => Attempt::Done(Err(e)),
+                }
+            }
+            Attempt::Legacy => {
+                if let Err(e) = self.refresh_async().await {
+                    log::warn!("SecretsManagerKMS: AWSCURRENT refresh failed: {e:#}");
+                }
+                self.try_cached(blob)
+            }
+            done @ Attempt::Done(_) => done,
+        };
+        self.finish(attempt, blob)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn versioned_blob_round_trips_header() {
+        let blob = wrap_versioned("v-1234", b"ciphertext").unwrap_or_default();
+        assert_eq!(
+            split_versioned_blob(&blob),
+            Some(("v-1234", &b"ciphertext"[..]))
+        );
+    }
+
+    #[test]
+    fn unversioned_blobs_are_not_split() {
+        assert_eq!(split_versioned_blob(b"plain aead output"), None);
+        // Magic with an empty id or no payload is not a versioned blob.
+        assert_eq!(split_versioned_blob(b"ASM1\x00abc"), None);
+        assert_eq!(split_versioned_blob(b"ASM1\x03abc"), None);
+    }
+
+    #[test]
+    fn oversized_version_id_is_rejected() {
+        assert!(wrap_versioned(&"x".repeat(256), b"ct").is_err());
     }
 }
@@ -1,9 +1,13 @@
 use async_trait::async_trait;
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
-use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use parking_lot::{Condvar, Mutex, RwLock};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 use zeroize::Zeroizing;
 
+use crate::builders::VaultOptions;
 use crate::traits::KeyManagementService;
 
 /// Vault Transit KMS — uses HashiCorp Vault's Transit secrets engine as an
@@ -11,27 +15,49 @@ use crate::traits::KeyManagementService;
 ///
 /// # Authentication
 ///
-/// Supports multiple auth methods via environment variables:
+/// Supports multiple auth methods, configured through [`VaultOptions`] with
+/// each unset field falling back to its environment variable:
 ///
 /// - **Token** (simplest, for dev): `VAULT_TOKEN`
 /// - **Kubernetes** (pods): `VAULT_AUTH_METHOD=kubernetes` + `VAULT_AUTH_ROLE`
 ///   (uses the pod's service account JWT at `/var/run/secrets/kubernetes.io/serviceaccount/token`)
-/// - **AppRole** (CI/automation): `VAULT_AUTH_METHOD=approle` + `VAULT_APPROLE_ROLE_ID` + `VAULT_APPROLE_SECRET_ID`
+/// - **AppRole** (CI/automation): `VAULT_AUTH_METHOD=approle` + `VAULT_APPROLE_ROLE_ID` +
+///   `VAULT_APPROLE_SECRET_ID` or `VAULT_APPROLE_SECRET_ID_FILE`
 /// - **TLS Certificate** (machine identity): `VAULT_AUTH_METHOD=cert` + `VAULT_CLIENT_CERT` + `VAULT_CLIENT_KEY`
+///
+/// # Token lifetime
+///
+/// A background thread renews the client token at two thirds of its TTL via
+/// `auth/token/renew-self`. When the token is not renewable, renewal fails,
+/// or the granted TTL starts shrinking (the token is approaching its max
+/// TTL), the thread logs in again with the configured auth method — re-reading
+/// the Kubernetes JWT and the AppRole secret-id file each time. A plain
+/// `VAULT_TOKEN` cannot log in again, so it is renewed for as long as Vault
+/// allows and then left to expire. The thread exits when the last clone of
+/// the KMS is dropped.
+///
+/// # Key versions
+///
+/// Encrypt uses the Transit key's latest version unless
+/// [`VaultOptions::key_version`] pins one; the pin is validated against the
+/// key's `min_encryption_version`/`latest_version` at construction when the
+/// token may read the key. Decrypt accepts any version Vault still allows. A
+/// ciphertext Vault rejects as too old is reported against the key's
+/// `min_decryption_version` so a trimmed key version is distinguishable from
+/// a corrupt blob.
 #[allow(missing_debug_implementations)]
 pub struct VaultTransitKms {
     sync_client: reqwest::blocking::Client,
     async_client: reqwest::Client,
     encrypt_url: String,
     decrypt_url: String,
-    /// Vault client token. Wrapped in `Zeroizing` so the bytes are
-    /// volatile-wiped when the struct (and any clone) is dropped. T-finding
-    /// "token: String cached for process life with no zeroize" in
-    /// `docs/review-2026-05-05-findings.md`. The renewal/TTL gap is left
-    /// as a documented follow-up — Vault tokens with finite TTL still
-    /// fail at expiry; refresh logic requires plumbing through the auth
-    /// method (Kubernetes JWT path / AppRole secret_id / explicit token).
-    token: Arc<Zeroizing<String>>,
+    key_url: String,
+    key_version: Option<u32>,
+    /// Key version Vault reported for the most recent encrypt; 0 until the
+    /// first one. Used to log when a Transit key rotation takes effect.
+    last_encrypt_version: Arc<AtomicU32>,
+    auth: Arc<VaultAuth>,
+    renewer: Option<Arc<TokenRenewer>>,
 }
 
 impl Clone for VaultTransitKms {
@@ -41,14 +67,31 @@ impl Clone for VaultTransitKms {
             async_client: self.async_client.clone(),
             encrypt_url: self.encrypt_url.clone(),
             decrypt_url: self.decrypt_url.clone(),
-            token: Arc::clone(&self.token),
+            key_url: self.key_url.clone(),
+            key_version: self.key_version,
+            last_encrypt_version: Arc::clone(&self.last_encrypt_version),
+            auth: Arc::clone(&self.auth),
+            renewer: self.renewer.clone(),
         }
     }
 }
 
+/// Metadata of the Transit key, from `GET /v1/{mount}/keys/{name}`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct VaultKeyInfo {
+    pub latest_version: u32,
+    /// Ciphertexts below this version are refused by Vault.
+    pub min_decryption_version: u32,
+    /// Lowest version encrypt may use; 0 means "latest only" is not enforced.
+    #[serde(default)]
+    pub min_encryption_version: u32,
+}
+
 #[derive(Serialize)]
 struct EncryptRequest<'req> {
     plaintext: &'req str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_version: Option<u32>,
 }
 
 #[derive(Serialize)]
@@ -56,6 +99,23 @@ struct DecryptRequest<'req> {
     ciphertext: &'req str,
 }
 
+#[derive(Serialize)]
+struct PlaintextItem<'req> {
+    plaintext: &'req str,
+}
+
+#[derive(Serialize)]
+struct BatchEncryptRequest<'req> {
+    batch_input: Vec<PlaintextItem<'req>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key_version: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct BatchDecryptRequest<'req> {
+    batch_input: Vec<DecryptRequest<'req>>,
+}
+
 #[derive(Deserialize)]
 struct VaultResponse<T> {
     data: Option<T>,
@@ -65,6 +125,8 @@ struct VaultResponse<T> {
 #[derive(Deserialize)]
 struct EncryptData {
     ciphertext: String,
+    #[serde(default)]
+    key_version: Option<u32>,
 }
 
 #[derive(Deserialize)]
@@ -72,7 +134,26 @@ struct DecryptData {
     plaintext: String,
 }
 
-/// Vault auth response (shared across auth methods).
+#[derive(Deserialize)]
+struct BatchData<T> {
+    batch_results: Vec<T>,
+}
+
+#[derive(Deserialize)]
+struct BatchEncryptItem {
+    ciphertext: Option<String>,
+    #[serde(default)]
+    key_version: Option<u32>,
+    error: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct BatchDecryptItem {
+    plaintext: Option<String>,
+    error: Option<String>,
+}
+
+/// Vault auth response (shared across auth methods and `renew-self`).
 #[derive(Deserialize)]
 struct AuthResponse {
     auth: Option<AuthData>,
@@ -82,6 +163,19 @@ struct AuthResponse {
 #[derive(Deserialize)]
 struct AuthData {
     client_token: String,
+    #[serde(default)]
+    lease_duration: u64,
+    #[serde(default)]
+    renewable: bool,
+}
+
+/// `auth/token/lookup-self` payload — only the lease fields are read.
+#[derive(Deserialize)]
+struct TokenLookupData {
+    #[serde(default)]
+    ttl: u64,
+    #[serde(default)]
+    renewable: bool,
 }
 
 /// Truncate a body snippet for inclusion in error logs. Vault server
@@ -102,24 +196,470 @@ fn truncate_for_log(s: &str, max: usize) -> String {
     }
 }
 
+/// Key version of a `vault:v<n>:<payload>` ciphertext.
+pub fn ciphertext_key_version(ciphertext: &str) -> Option<u32> {
+    let rest = ciphertext.strip_prefix("vault:v")?;
+    let (version, _) = rest.split_once(':')?;
+    version.parse().ok()
+}
+
+fn option_or_env(value: Option<&String>, var: &str) -> Option<String> {
+    value
+        .cloned()
+        .or_else(|| std::env::var(var).ok())
+        .filter(|v| !v.is_empty())
+}
+
+// ── authentication ──────────────────────────────────────────────────
+
+/// How the client token is obtained. Resolved once at construction; login
+/// methods re-read their credential files on every login.
+enum AuthMethod {
+    Token,
+    Kubernetes {
+        role: String,
+        jwt_path: String,
+        mount: String,
+    },
+    AppRole {
+        role_id: String,
+        secret_id: Option<Zeroizing<String>>,
+        secret_id_file: Option<String>,
+        mount: String,
+    },
+    Cert {
+        mount: String,
+    },
+}
+
+impl AuthMethod {
+    /// Resolve the auth method. Checks in order: an explicit token
+    /// (`VaultOptions::token`, then `VAULT_TOKEN`), then the auth method
+    /// (kubernetes, approle, cert).
+    fn resolve(options: &VaultOptions) -> anyhow::Result<(Self, Option<Zeroizing<String>>)> {
+        if let Some(token) = option_or_env(options.token.as_ref(), "VAULT_TOKEN") {
+            return Ok((Self::Token, Some(Zeroizing::new(token))));
+        }
+        let auth_method = option_or_env(options.auth_method.as_ref(), "VAULT_AUTH_METHOD")
+            .unwrap_or_default()
+            .to_lowercase();
+        let mount = |default: &str| {
+            option_or_env(options.auth_mount.as_ref(), "VAULT_AUTH_MOUNT")
+                .unwrap_or_else(|| default.to_string())
+        };
+        let method = match auth_method.as_str() {
+            "kubernetes" | "k8s" => Self::Kubernetes {
+                role: option_or_env(options.auth_role.as_ref(), "VAULT_AUTH_ROLE").ok_or_else(
+                    || anyhow::anyhow!("VAULT_AUTH_ROLE required for Vault Kubernetes auth"),
+                )?,
+                jwt_path: option_or_env(options.k8s_token_path.as_ref(), "VAULT_K8S_TOKEN_PATH")
+                    .unwrap_or_else(|| {
+                        "/var/run/secrets/kubernetes.io/serviceaccount/token".to_string()
+                    }),
+                mount: mount("kubernetes"),
+            },
+            "approle" => Self::AppRole {
+                role_id: option_or_env(options.approle_role_id.as_ref(), "VAULT_APPROLE_ROLE_ID")
+                    .ok_or_else(|| {
+                    anyhow::anyhow!("VAULT_APPROLE_ROLE_ID required for Vault AppRole auth")
+                })?,
+                secret_id: option_or_env(
+                    options.approle_secret_id.as_ref(),
+                    "VAULT_APPROLE_SECRET_ID",
+                )
+                .map(Zeroizing::new),
+                secret_id_file: option_or_env(
+                    options.approle_secret_id_file.as_ref(),
+                    "VAULT_APPROLE_SECRET_ID_FILE",
+                ),
+                mount: mount("approle"),
+            },
+            // TLS cert auth uses the client certificate configured on the HTTP client
+            "cert" | "tls" => Self::Cert {
+                mount: mount("cert"),
+            },
+            "" => anyhow::bail!(
+                "Vault authentication required: set VAULT_TOKEN, or VAULT_AUTH_METHOD \
+                 (kubernetes, approle, cert) with the appropriate credentials"
+            ),
+            other => anyhow::bail!(
+                "unsupported VAULT_AUTH_METHOD '{other}': expected kubernetes, approle, or cert"
+            ),
+        };
+        Ok((method, None))
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Self::Token => "token",
+            Self::Kubernetes { .. } => "kubernetes",
+            Self::AppRole { .. } => "approle",
+            Self::Cert { .. } => "cert",
+        }
+    }
+
+    fn can_login(&self) -> bool {
+        !matches!(self, Self::Token)
+    }
+
+    /// Login URL and body for this method. Credentials are read from disk
+    /// here, not at construction, so rotated files take effect on re-login.
+    fn login_request(&self, base: &str) -> anyhow::Result<(String, Option<serde_json::Value>)> {
+        match self {
+            Self::Token => anyhow::bail!("Vault token auth cannot log in again"),
+            Self::Kubernetes {
+                role,
+                jwt_path,
+                mount,
+            } => {
+                let jwt = Zeroizing::new(std::fs::read_to_string(jwt_path).map_err(|e| {
+                    anyhow::anyhow!(
+                        "failed to read Kubernetes service account token at {jwt_path}: {e}"
+                    )
+                })?);
+                Ok((
+                    format!("{base}/v1/auth/{mount}/login"),
+                    Some(serde_json::json!({"role": role, "jwt": jwt.trim()})),
+                ))
+            }
+            Self::AppRole {
+                role_id,
+                secret_id,
+                secret_id_file,
+                mount,
+            } => {
+                let secret_id = match secret_id_file {
+                    Some(path) => Some(Zeroizing::new(
+                        std::fs::read_to_string(path)
+                            .map_err(|e| {
+                                anyhow::anyhow!(
+                                    "failed to read VAULT_APPROLE_SECRET_ID_FILE at {path}: {e}"
+                                )
+                            })?
+                            .trim()
+                            .to_string(),
+                    )),
+                    None => secret_id.clone(),
+                };
+                let mut body = serde_json::json!({"role_id": role_id});
+                if let Some(secret_id) = secret_id.filter(|s| !s.is_empty()) {
+                    body["secret_id"] = serde_json::Value::String(secret_id.to_string());
+                }
+                Ok((format!("{base}/v1/auth/{mount}/login"), Some(body)))
+            }
+            Self::Cert { mount } => Ok((format!("{base}/v1/auth/{mount}/login"), None)),
+        }
+    }
+}
+
+/// Lease of the current client token. A zero `ttl` means the token does not
+/// expire (or its TTL is unknown) and is never renewed.
+#[derive(Clone, Copy, Debug)]
+struct TokenLease {
+    ttl: Duration,
+    renewable: bool,
+    obtained: Instant,
+}
+
+impl TokenLease {
+    fn new(ttl_s: u64, renewable: bool) -> Self {
+        Self {
+            ttl: Duration::from_secs(ttl_s),
+            renewable,
+            obtained: Instant::now(),
+        }
+    }
+}
+
+/// Earliest a renewal is scheduled, so a tiny TTL cannot spin the thread.
+const MIN_RENEWAL_DELAY: Duration = Duration::from_secs(1);
+/// Delay before retrying after a failed renewal/login.
+const RENEWAL_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// How long to wait before refreshing `lease`: two thirds of the TTL after
+/// it was obtained, leaving a third of the TTL for retries.
+fn renewal_delay(lease: &TokenLease, now: Instant) -> Option<Duration> {
+    if lease.ttl.is_zero() {
+        return None;
+    }
+    let due = lease.obtained + lease.ttl * 2 / 3;
+    Some(due.saturating_duration_since(now).max(MIN_RENEWAL_DELAY))
+}
+
+/// Shared token state. `token` is swapped wholesale on renewal/login so a
+/// request in flight keeps the token it started with.
+struct VaultAuth {
+    base: String,
+    method: AuthMethod,
+    client: reqwest::blocking::Client,
+    /// Vault client token. Wrapped in `Zeroizing` so the bytes are
+    /// volatile-wiped once the last request using them drops its `Arc`.
+    /// T-finding "token: String cached for process life with no zeroize"
+    /// in `docs/review-2026-05-05-findings.md`.
+    token: RwLock<Arc<Zeroizing<String>>>,
+    lease: Mutex<TokenLease>,
+}
+
+impl VaultAuth {
+    fn token(&self) -> Arc<Zeroizing<String>> {
+        Arc::clone(&self.token.read())
+    }
+
+    fn set_token(&self, token: String, lease: TokenLease) {
+        *self.token.write() = Arc::new(Zeroizing::new(token));
+        *self.lease.lock() = lease;
+    }
+
+    fn extract_token(resp: AuthResponse, method: &str) -> anyhow::Result<(String, TokenLease)> {
+        if let Some(errs) = resp.errors {
+            if !errs.is_empty() {
+                return Err(anyhow::anyhow!(
+                    "Vault {method} auth failed: {}",
+                    errs.join("; ")
+                ));
+            }
+        }
+        resp.auth
+            .map(|a| {
+                let lease = TokenLease::new(a.lease_duration, a.renewable);
+                (a.client_token, lease)
+            })
+            .ok_or_else(|| anyhow::anyhow!("Vault {method} auth returned no token"))
+    }
+
+    fn lookup_lease(resp: VaultResponse<TokenLookupData>) -> TokenLease {
+        match resp.data {
+            Some(d) => TokenLease::new(d.ttl, d.renewable),
+            None => TokenLease::new(0, false),
+        }
+    }
+
+    fn login_sync(&self) -> anyhow::Result<(String, TokenLease)> {
+        let method = self.method.name();
+        let (url, body) = self.method.login_request(&self.base)?;
+        let mut req = self.client.post(&url);
+        if let Some(body) = &body {
+            req = req.json(body);
+        }
+        let resp: AuthResponse = req
+            .send()
+            .map_err(|e| anyhow::anyhow!("Vault {method} auth failed: {e}"))?
+            .json()
+            .map_err(|e| anyhow::anyhow!("Vault {method} auth: invalid response: {e}"))?;
+        Self::extract_token(resp, method)
+    }
+
+    async fn login_async(&self, client: &reqwest::Client) -> anyhow::Result<(String, TokenLease)> {
+        let method = self.method.name();
+        let (url, body) = self.method.login_request(&self.base)?;
+        let mut req = client.post(&url);
+        if let Some(body) = &body {
+            req = req.json(body);
+        }
+        let resp: AuthResponse = req
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!("Vault {method} auth failed: {e}"))?
+            .json()
+            .await
+            .map_err(|e| anyhow::anyhow!("Vault {method} auth: invalid response: {e}"))?;
+        Self::extract_token(resp, method)
+    }
+
+    /// Lease of an explicitly supplied token. A token without permission to
+    /// look itself up is treated as non-expiring and never renewed.
+    fn lookup_self_sync(&self, token: &str) -> TokenLease {
+        let url = format!("{}/v1/auth/token/lookup-self", self.base);
+        let resp = self
+            .client
+            .get(&url)
+            .header("X-Vault-Token", token)
+            .send()
+            .and_then(reqwest::blocking::Response::error_for_status)
+            .and_then(reqwest::blocking::Response::json);
+        match resp {
+            Ok(resp) => Self::lookup_lease(resp),
+            Err(e) => {
+                log::debug!("VaultTransitKms: token lookup-self failed, not renewing: {e}");
+                TokenLease::new(0, false)
+            }
+        }
+    }
+
+    async fn lookup_self_async(&self, client: &reqwest::Client, token: &str) -> TokenLease {
+        let url = format!("{}/v1/auth/token/lookup-self", self.base);
+        let resp = match client
+            .get(&url)
+            .header("X-Vault-Token", token)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+        {
+            Ok(resp) => resp.json().await,
+            Err(e) => Err(e),
+        };
+        match resp {
+            Ok(resp) => Self::lookup_lease(resp),
+            Err(e) => {
+                log::debug!("VaultTransitKms: token lookup-self failed, not renewing: {e}");
+                TokenLease::new(0, false)
+            }
+        }
+    }
+
+    fn renew_self_sync(&self) -> anyhow::Result<(String, TokenLease)> {
+        let url = format!("{}/v1/auth/token/renew-self", self.base);
+        let token = self.token();
+        let resp = self
+            .client
+            .post(&url)
+            .header("X-Vault-Token", token.as_str())
+            .json(&serde_json::json!({}))
+            .send()
+            .map_err(|e| anyhow::anyhow!("Vault token renewal failed: {e}"))?;
+        let status = resp.status();
+        if !status.is_success() {
+            let snippet = resp.text().unwrap_or_default();
+            let snippet = truncate_for_log(&snippet, 256);
+            anyhow::bail!("Vault token renewal: HTTP {status} (body: {snippet})");
+        }
+        let resp: AuthResponse = resp
+            .json()
+            .map_err(|e| anyhow::anyhow!("Vault token renewal: invalid response: {e}"))?;
+        Self::extract_token(resp, "token renewal")
+    }
+
+    /// Renew the token, or log in again when renewal is impossible or the
+    /// token is running into its max TTL.
+    fn refresh_sync(&self) -> anyhow::Result<()> {
+        let lease = *self.lease.lock();
+        if lease.renewable {
+            match self.renew_self_sync() {
+                Ok((token, renewed)) => {
+                    // Vault caps the renewed TTL at the token's remaining max
+                    // TTL; once it shrinks, a fresh login is the only way to
+                    // keep a full-length token.
+                    if renewed.ttl >= lease.ttl || !self.method.can_login() {
+                        log::debug!(
+                            "VaultTransitKms: renewed token, ttl={}s",
+                            renewed.ttl.as_secs()
+                        );
+                        self.set_token(token, renewed);
+                        return Ok(());
+                    }
+                    log::info!(
+                        "VaultTransitKms: token nearing max TTL (ttl={}s), logging in again",
+                        renewed.ttl.as_secs()
+                    );
+                }
+                Err(e) if !self.method.can_login() => return Err(e),
+                Err(e) => log::warn!("VaultTransitKms: {e:#}; logging in again"),
+            }
+        } else if !self.method.can_login() {
+            anyhow::bail!("Vault token is not renewable and token auth cannot log in again");
+        }
+        let (token, lease) = self.login_sync()?;
+        log::debug!(
+            "VaultTransitKms: {} login refreshed token, ttl={}s",
+            self.method.name(),
+            lease.ttl.as_secs()
+        );
+        self.set_token(token, lease);
+        Ok(())
+    }
+}
+
+/// Stops the renewal thread when the last KMS clone drops it.
+struct TokenRenewer {
+    stop: Arc<(Mutex<bool>, Condvar)>,
+}
+
+impl Drop for TokenRenewer {
+    fn drop(&mut self) {
+        let (stopped, cv) = &*self.stop;
+        *stopped.lock() = true;
+        cv.notify_all();
+    }
+}
+
+impl TokenRenewer {
+    /// Spawn the renewal thread, or return `None` for a token that never
+    /// expires. The thread holds only a `Weak` to the auth state.
+    fn spawn(auth: &Arc<VaultAuth>) -> Option<Arc<Self>> {
+        let lease = *auth.lease.lock();
+        let mut delay = renewal_delay(&lease, Instant::now())?;
+        if !lease.renewable && !auth.method.can_login() {
+            log::debug!("VaultTransitKms: token is not renewable; it expires in {lease:?}");
+            return None;
+        }
+        let stop = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread_stop = Arc::clone(&stop);
+        let weak: Weak<VaultAuth> = Arc::downgrade(auth);
+        // Use std::thread since construction may be sync and we don't want
+        // to require a tokio runtime to outlive the KMS.
+        let spawned = std::thread::Builder::new()
+            .name("vault-token-renewer".into())
+            .spawn(move || loop {
+                {
+                    let (stopped, cv) = &*thread_stop;
+                    let mut guard = stopped.lock();
+                    if !*guard {
+                        cv.wait_for(&mut guard, delay);
+                    }
+                    if *guard {
+                        break;
+                    }
+                }
+                let Some(auth) = weak.upgrade() else { break };
+                delay = match auth.refresh_sync() {
+                    Ok(()) => match renewal_delay(&auth.lease.lock(), Instant::now()) {
+                        Some(d) => d,
+                        None => break,
+                    },
+                    Err(e) => {
+                        log::warn!("VaultTransitKms: token refresh failed: {e:#}");
+                        RENEWAL_RETRY_DELAY
+                    }
+                };
+            });
+        match spawned {
+            Ok(_) => Some(Arc::new(Self { stop })),
+            Err(e) => {
+                // Without the thread the token simply expires, as before.
+                log::warn!("VaultTransitKms: failed to spawn token renewer: {e}");
+                None
+            }
+        }
+    }
+}
+
+// ── Transit client ──────────────────────────────────────────────────
+
 impl VaultTransitKms {
     /// Build Transit API URLs from components.
-    fn build_urls(vault_addr: &str, transit_mount: &str, key_name: &str) -> (String, String) {
+    fn build_urls(
+        vault_addr: &str,
+        transit_mount: &str,
+        key_name: &str,
+    ) -> (String, String, String) {
         let base = vault_addr.trim_end_matches('/');
         let mount = transit_mount.trim_matches('/');
         let encrypt_url = format!("{base}/v1/{mount}/encrypt/{key_name}");
         let decrypt_url = format!("{base}/v1/{mount}/decrypt/{key_name}");
-        (encrypt_url, decrypt_url)
+        let key_url = format!("{base}/v1/{mount}/keys/{key_name}");
+        (encrypt_url, decrypt_url, key_url)
     }
 
-    fn build_clients() -> anyhow::Result<(reqwest::blocking::Client, reqwest::Client)> {
+    fn build_clients(
+        options: &VaultOptions,
+    ) -> anyhow::Result<(reqwest::blocking::Client, reqwest::Client)> {
         let mut sync_builder = reqwest::blocking::Client::builder().use_rustls_tls();
         let mut async_builder = reqwest::Client::builder().use_rustls_tls();
 
         // TLS client certificate auth: configure the HTTP client with the cert
-        if let (Ok(cert_path), Ok(key_path)) = (
-            std::env::var("VAULT_CLIENT_CERT"),
-            std::env::var("VAULT_CLIENT_KEY"),
+        if let (Some(cert_path), Some(key_path)) = (
+            option_or_env(options.client_cert.as_ref(), "VAULT_CLIENT_CERT"),
+            option_or_env(options.client_key.as_ref(), "VAULT_CLIENT_KEY"),
         ) {
             let cert_pem = std::fs::read(&cert_path).map_err(|e| {
                 anyhow::anyhow!("failed to read VAULT_CLIENT_CERT at {cert_path}: {e}")
@@ -148,195 +688,62 @@ impl VaultTransitKms {
         Ok((sync_client, async_client))
     }
 
-    /// Resolve a Vault token from environment configuration.
-    /// Checks in order: VAULT_TOKEN, then VAULT_AUTH_METHOD (kubernetes, approle, cert).
-    fn resolve_token_sync(
+    /// Auth state for `addr` with no token yet; the constructors log in or
+    /// look the explicit token up before calling [`Self::assemble`].
+    fn unauthenticated(
+        addr: &str,
+        options: &VaultOptions,
         client: &reqwest::blocking::Client,
-        vault_addr: &str,
-    ) -> anyhow::Result<String> {
-        // 1. Explicit token
-        if let Ok(token) = std::env::var("VAULT_TOKEN") {
-            if !token.is_empty() {
-                return Ok(token);
-            }
-        }
-
-        let auth_method = std::env::var("VAULT_AUTH_METHOD")
-            .unwrap_or_default()
-            .to_lowercase();
-        let base = vault_addr.trim_end_matches('/');
-
-        match auth_method.as_str() {
-            "kubernetes" | "k8s" => {
-                let role = std::env::var("VAULT_AUTH_ROLE").map_err(|_| {
-                    anyhow::anyhow!("VAULT_AUTH_ROLE required for Vault Kubernetes auth")
-                })?;
-                let jwt_path = std::env::var("VAULT_K8S_TOKEN_PATH").unwrap_or_else(|_| {
-                    "/var/run/secrets/kubernetes.io/serviceaccount/token".to_string()
-                });
-                let jwt = std::fs::read_to_string(&jwt_path).map_err(|e| {
-                    anyhow::anyhow!(
-                        "failed to read Kubernetes service account token at {jwt_path}: {e}"
-                    )
-                })?;
-                let mount =
-                    std::env::var("VAULT_AUTH_MOUNT").unwrap_or_else(|_| "kubernetes".to_string());
-                let url = format!("{base}/v1/auth/{mount}/login");
-                let resp: AuthResponse = client
-                    .post(&url)
-                    .json(&serde_json::json!({"role": role, "jwt": jwt.trim()}))
-                    .send()
-                    .map_err(|e| anyhow::anyhow!("Vault Kubernetes auth failed: {e}"))?
-                    .json()
-                    .map_err(|e| anyhow::anyhow!("Vault Kubernetes auth: invalid response: {e}"))?;
-                Self::extract_token(resp, "kubernetes")
-            }
-            "approle" => {
-                let role_id = std::env::var("VAULT_APPROLE_ROLE_ID").map_err(|_| {
-                    anyhow::anyhow!("VAULT_APPROLE_ROLE_ID required for Vault AppRole auth")
-                })?;
-                let secret_id = std::env::var("VAULT_APPROLE_SECRET_ID").unwrap_or_default();
-                let mount =
-                    std::env::var("VAULT_AUTH_MOUNT").unwrap_or_else(|_| "approle".to_string());
-                let url = format!("{base}/v1/auth/{mount}/login");
-                let mut body = serde_json::json!({"role_id": role_id});
-                if !secret_id.is_empty() {
-                    body["secret_id"] = serde_json::Value::String(secret_id);
-                }
-                let resp: AuthResponse = client
-                    .post(&url)
-                    .json(&body)
-                    .send()
-                    .map_err(|e| anyhow::anyhow!("Vault AppRole auth failed: {e}"))?
-                    .json()
-                    .map_err(|e| anyhow::anyhow!("Vault AppRole auth: invalid response: {e}"))?;
-                Self::extract_token(resp, "approle")
-            }
-            "cert" | "tls" => {
-                // TLS cert auth uses the client certificate configured on the HTTP client
-                let mount =
-                    std::env::var("VAULT_AUTH_MOUNT").unwrap_or_else(|_| "cert".to_string());
-                let url = format!("{base}/v1/auth/{mount}/login");
-                let resp: AuthResponse = client
-                    .post(&url)
-                    .send()
-                    .map_err(|e| anyhow::anyhow!("Vault TLS cert auth failed: {e}"))?
-                    .json()
-                    .map_err(|e| anyhow::anyhow!("Vault TLS cert auth: invalid response: {e}"))?;
-                Self::extract_token(resp, "cert")
-            }
-            "" => Err(anyhow::anyhow!(
-                "Vault authentication required: set VAULT_TOKEN, or VAULT_AUTH_METHOD \
-                 (kubernetes, approle, cert) with the appropriate credentials"
-            )),
-            other => Err(anyhow::anyhow!(
-                "unsupported VAULT_AUTH_METHOD '{other}': expected kubernetes, approle, or cert"
-            )),
-        }
-    }
-
-    /// Async version of token resolution.
-    async fn resolve_token_async(
-        client: &reqwest::Client,
-        vault_addr: &str,
-    ) -> anyhow::Result<String> {
-        if let Ok(token) = std::env::var("VAULT_TOKEN") {
-            if !token.is_empty() {
-                return Ok(token);
-            }
-        }
-
-        let auth_method = std::env::var("VAULT_AUTH_METHOD")
-            .unwrap_or_default()
-            .to_lowercase();
-        let base = vault_addr.trim_end_matches('/');
-
-        match auth_method.as_str() {
-            "kubernetes" | "k8s" => {
-                let role = std::env::var("VAULT_AUTH_ROLE").map_err(|_| {
-                    anyhow::anyhow!("VAULT_AUTH_ROLE required for Vault Kubernetes auth")
-                })?;
-                let jwt_path = std::env::var("VAULT_K8S_TOKEN_PATH").unwrap_or_else(|_| {
-                    "/var/run/secrets/kubernetes.io/serviceaccount/token".to_string()
-                });
-                let jwt = std::fs::read_to_string(&jwt_path).map_err(|e| {
-                    anyhow::anyhow!(
-                        "failed to read Kubernetes service account token at {jwt_path}: {e}"
-                    )
-                })?;
-                let mount =
-                    std::env::var("VAULT_AUTH_MOUNT").unwrap_or_else(|_| "kubernetes".to_string());
-                let url = format!("{base}/v1/auth/{mount}/login");
-                let resp: AuthResponse = client
-                    .post(&url)
-                    .json(&serde_json::json!({"role": role, "jwt": jwt.trim()}))
-                    .send()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Vault Kubernetes auth failed: {e}"))?
-                    .json()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Vault Kubernetes auth: invalid response: {e}"))?;
-                Self::extract_token(resp, "kubernetes")
-            }
-            "approle" => {
-                let role_id = std::env::var("VAULT_APPROLE_ROLE_ID").map_err(|_| {
-                    anyhow::anyhow!("VAULT_APPROLE_ROLE_ID required for Vault AppRole auth")
-                })?;
-                let secret_id = std::env::var("VAULT_APPROLE_SECRET_ID").unwrap_or_default();
-                let mount =
-                    std::env::var("VAULT_AUTH_MOUNT").unwrap_or_else(|_| "approle".to_string());
-                let url = format!("{base}/v1/auth/{mount}/login");
-                let mut body = serde_json::json!({"role_id": role_id});
-                if !secret_id.is_empty() {
-                    body["secret_id"] = serde_json::Value::String(secret_id);
-                }
-                let resp: AuthResponse = client
-                    .post(&url)
-                    .json(&body)
-                    .send()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Vault AppRole auth failed: {e}"))?
-                    .json()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Vault AppRole auth: invalid response: {e}"))?;
-                Self::extract_token(resp, "approle")
-            }
-            "cert" | "tls" => {
-                let mount =
-                    std::env::var("VAULT_AUTH_MOUNT").unwrap_or_else(|_| "cert".to_string());
-                let url = format!("{base}/v1/auth/{mount}/login");
-                let resp: AuthResponse = client
-                    .post(&url)
-                    .send()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Vault TLS cert auth failed: {e}"))?
-                    .json()
-                    .await
-                    .map_err(|e| anyhow::anyhow!("Vault TLS cert auth: invalid response: {e}"))?;
-                Self::extract_token(resp, "cert")
-            }
-            "" => Err(anyhow::anyhow!(
-                "Vault authentication required: set VAULT_TOKEN, or VAULT_AUTH_METHOD \
-                 (kubernetes, approle, cert) with the appropriate credentials"
-            )),
-            other => Err(anyhow::anyhow!(
-                "unsupported VAULT_AUTH_METHOD '{other}': expected kubernetes, approle, or cert"
-            )),
-        }
+    ) -> anyhow::Result<(VaultAuth, Option<Zeroizing<String>>)> {
+        let (method, explicit) = AuthMethod::resolve(options)?;
+        let auth = VaultAuth {
+            base: addr.trim_end_matches('/').to_string(),
+            method,
+            client: client.clone(),
+            token: RwLock::new(Arc::new(Zeroizing::new(String::new()))),
+            lease: Mutex::new(TokenLease::new(0, false)),
+        };
+        Ok((auth, explicit))
     }
 
-    fn extract_token(resp: AuthResponse, method: &str) -> anyhow::Result<String> {
-        if let Some(errs) = resp.errors {
-            if !errs.is_empty() {
-                return Err(anyhow::anyhow!(
-                    "Vault {method} auth failed: {}",
-                    errs.join("; ")
-                ));
-            }
+    fn assemble(
+        key_name: &str,
+        transit_mount: Option<&str>,
+        options: &VaultOptions,
+        clients: (reqwest::blocking::Client, reqwest::Client),
+        auth: VaultAuth,
+    ) -> anyhow::Result<Self> {
+        let mount = transit_mount.unwrap_or("transit");
+        let (encrypt_url, decrypt_url, key_url) = Self::build_urls(&auth.base, mount, key_name);
+        let (sync_client, async_client) = clients;
+        let key_version = match options.key_version {
+            Some(v) => Some(v),
+            None => std::env::var("VAULT_TRANSIT_KEY_VERSION")
+                .ok()
+                .filter(|v| !v.is_empty())
+                .map(|v| {
+                    v.parse().map_err(|e| {
+                        anyhow::anyhow!("invalid VAULT_TRANSIT_KEY_VERSION '{v}': {e}")
+                    })
+                })
+                .transpose()?,
+        };
+        if key_version == Some(0) {
+            anyhow::bail!("Vault Transit key_version must be 1 or greater");
         }
-        resp.auth
-            .map(|a| a.client_token)
-            .ok_or_else(|| anyhow::anyhow!("Vault {method} auth returned no token"))
+        let auth = Arc::new(auth);
+        let renewer = TokenRenewer::spawn(&auth);
+        Ok(Self {
+            sync_client,
+            async_client,
+            encrypt_url,
+            decrypt_url,
+            key_url,
+            key_version,
+            last_encrypt_version: Arc::new(AtomicU32::new(0)),
+            auth,
+            renewer,
+        })
     }
 
     /// Sync constructor. Authenticates with Vault and creates HTTP clients.
@@ -348,18 +755,12 @@ impl VaultTransitKms {
         key_name: impl AsRef<str>,
         transit_mount: Option<&str>,
     ) -> anyhow::Result<Self> {
-        let addr = vault_addr.into();
-        let mount = transit_mount.unwrap_or("transit");
-        let (encrypt_url, decrypt_url) = Self::build_urls(&addr, mount, key_name.as_ref());
-        let (sync_client, async_client) = Self::build_clients()?;
-        let token = Self::resolve_token_sync(&sync_client, &addr)?;
-        Ok(Self {
-            sync_client,
-            async_client,
-            encrypt_url,
-            decrypt_url,
-            token: Arc::new(Zeroizing::new(token)),
-        })
+        Self::with_options(
+            vault_addr,
+            key_name,
+            transit_mount,
+            &VaultOptions::default(),
+        )
     }
 
     /// Async constructor. Authenticates with Vault and creates HTTP clients.
@@ -368,18 +769,68 @@ impl VaultTransitKms {
         key_name: impl AsRef<str>,
         transit_mount: Option<&str>,
     ) -> anyhow::Result<Self> {
-        let addr = vault_addr.into();
-        let mount = transit_mount.unwrap_or("transit");
-        let (encrypt_url, decrypt_url) = Self::build_urls(&addr, mount, key_name.as_ref());
-        let (sync_client, async_client) = Self::build_clients()?;
-        let token = Self::resolve_token_async(&async_client, &addr).await?;
-        Ok(Self {
-            sync_client,
-            async_client,
-            encrypt_url,
-            decrypt_url,
-            token: Arc::new(Zeroizing::new(token)),
-        })
+        Self::with_options_async(
+            vault_addr,
+            key_name,
+            transit_mount,
+            &VaultOptions::default(),
+        )
+        .await
+    }
+
+    /// Sync constructor with explicit auth/key-version settings. Unset
+    /// fields of `options` fall back to the `VAULT_*` environment variables.
+    pub fn with_options(
+        vault_addr: impl Into<String>,
+        key_name: impl AsRef<str>,
+        transit_mount: Option<&str>,
+        options: &VaultOptions,
+    ) -> anyhow::Result<Self> {
+        let clients = Self::build_clients(options)?;
+        let (auth, explicit) = Self::unauthenticated(&vault_addr.into(), options, &clients.0)?;
+        let (token, lease) = match explicit {
+            Some(token) => {
+                let lease = auth.lookup_self_sync(&token);
+                (token.to_string(), lease)
+            }
+            None => auth.login_sync()?,
+        };
+        auth.set_token(token, lease);
+        let kms = Self::assemble(key_name.as_ref(), transit_mount, options, clients, auth)?;
+        if let Some(pin) = kms.key_version {
+            match kms.key_info() {
+                Ok(info) => check_key_version_pin(pin, &info)?,
+                Err(e) => log::warn!("VaultTransitKms: cannot validate key_version {pin}: {e:#}"),
+            }
+        }
+        Ok(kms)
+    }
+
+    /// Async variant of [`Self::with_options`].
+    pub async fn with_options_async(
+        vault_addr: impl Into<String>,
+        key_name: impl AsRef<str>,
+        transit_mount: Option<&str>,
+        options: &VaultOptions,
+    ) -> anyhow::Result<Self> {
+        let clients = Self::build_clients(options)?;
+        let (auth, explicit) = Self::unauthenticated(&vault_addr.into(), options, &clients.0)?;
+        let (token, lease) = match explicit {
+            Some(token) => {
+                let lease = auth.lookup_self_async(&clients.1, &token).await;
+                (token.to_string(), lease)
+            }
+            None => auth.login_async(&clients.1).await?,
+        };
+        auth.set_token(token, lease);
+        let kms = Self::assemble(key_name.as_ref(), transit_mount, options, clients, auth)?;
+        if let Some(pin) = kms.key_version {
+            match kms.key_info_async().await {
+                Ok(info) => check_key_version_pin(pin, &info)?,
+                Err(e) => log::warn!("VaultTransitKms: cannot validate key_version {pin}: {e:#}"),
+            }
+        }
+        Ok(kms)
     }
 
     /// Check a Vault response for errors and return a descriptive message.
@@ -395,6 +846,12 @@ impl VaultTransitKms {
         Ok(())
     }
 
+    fn unwrap_data<T>(resp: VaultResponse<T>, operation: &str) -> anyhow::Result<T> {
+        Self::check_vault_errors(resp.errors, operation)?;
+        resp.data
+            .ok_or_else(|| anyhow::anyhow!("Vault Transit {operation} returned no data"))
+    }
+
     fn encode_plaintext_for_vault(key_bytes: &[u8]) -> Zeroizing<String> {
         Zeroizing::new(BASE64.encode(key_bytes))
     }
@@ -406,18 +863,126 @@ impl VaultTransitKms {
             .map_err(|e| anyhow::anyhow!("Vault Transit decrypt: invalid base64 in plaintext: {e}"))
     }
 
+    fn ciphertext_str(blob: &[u8]) -> anyhow::Result<&str> {
+        let ciphertext = std::str::from_utf8(blob)
+            .map_err(|e| anyhow::anyhow!("Vault Transit decrypt: blob is not valid UTF-8: {e}"))?;
+        // Vault transit ciphertexts are versioned with a `vault:v<n>:` prefix.
+        // Reject anything else early — a corrupted or truncated metastore
+        // value otherwise round-trips into Vault and produces a confusing
+        // server-side error. T-finding "no validation of `vault:v` prefix"
+        // in `docs/review-2026-05-05-findings.md`.
+        if !ciphertext.starts_with("vault:v") {
+            anyhow::bail!(
+                "Vault Transit decrypt: ciphertext does not start with the expected \
+                 `vault:v<n>:` version prefix (got {} bytes)",
+                ciphertext.len()
+            );
+        }
+        Ok(ciphertext)
+    }
+
+    /// Log once per observed change of the key version Vault encrypts with,
+    /// so a monthly Transit rotation is visible in the application log.
+    fn note_encrypt_version(&self, version: Option<u32>) {
+        let Some(version) = version else { return };
+        let previous = self.last_encrypt_version.swap(version, Ordering::Relaxed);
+        if previous != 0 && previous != version {
+            log::info!(
+                "VaultTransitKms: now encrypting with key version v{version} (was v{previous})"
+            );
+        }
+    }
+
+    fn encrypt_body<'req>(&self, encoded: &'req str) -> EncryptRequest<'req> {
+        EncryptRequest {
+            plaintext: encoded,
+            key_version: self.key_version,
+        }
+    }
+
+    fn batch_encrypt_body<'req>(
+        &self,
+        encoded: &'req [Zeroizing<String>],
+    ) -> BatchEncryptRequest<'req> {
+        BatchEncryptRequest {
+            batch_input: encoded
+                .iter()
+                .map(|p| PlaintextItem { plaintext: p })
+                .collect(),
+            key_version: self.key_version,
+        }
+    }
+
+    fn batch_encrypt_results(
+        &self,
+        data: BatchData<BatchEncryptItem>,
+        expected: usize,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        check_batch_len(data.batch_results.len(), expected, "encrypt")?;
+        let mut out = Vec::with_capacity(expected);
+        for (i, item) in data.batch_results.into_iter().enumerate() {
+            batch_item_error(item.error, i, "encrypt")?;
+            let ciphertext = item.ciphertext.ok_or_else(|| {
+                anyhow::anyhow!("Vault Transit batch encrypt: item {i} returned no ciphertext")
+            })?;
+            self.note_encrypt_version(item.key_version);
+            out.push(ciphertext.into_bytes());
+        }
+        Ok(out)
+    }
+
+    fn batch_decrypt_results(
+        data: BatchData<BatchDecryptItem>,
+        expected: usize,
+    ) -> anyhow::Result<Vec<Vec<u8>>> {
+        check_batch_len(data.batch_results.len(), expected, "decrypt")?;
+        let mut out = Vec::with_capacity(expected);
+        for (i, item) in data.batch_results.into_iter().enumerate() {
+            batch_item_error(item.error, i, "decrypt")?;
+            let plaintext = item.plaintext.ok_or_else(|| {
+                anyhow::anyhow!("Vault Transit batch decrypt: item {i} returned no plaintext")
+            })?;
+            out.push(Self::decode_plaintext_from_vault(plaintext)?);
+        }
+        Ok(out)
+    }
+
+    /// Turn a decrypt rejected with HTTP 400 into a version-aware error when
+    /// the ciphertext's key version is below `min_decryption_version`.
+    fn explain_decrypt_failure(
+        err: anyhow::Error,
+        ciphertext: &str,
+        info: anyhow::Result<VaultKeyInfo>,
+    ) -> anyhow::Error {
+        match (ciphertext_key_version(ciphertext), info) {
+            (Some(v), Ok(info)) if v < info.min_decryption_version => err.context(format!(
+                "ciphertext uses Transit key version v{v}, below the key's \
+                 min_decryption_version {}; re-wrap it before trimming older versions",
+                info.min_decryption_version
+            )),
+            (Some(v), Ok(info)) if v > info.latest_version => err.context(format!(
+                "ciphertext uses Transit key version v{v}, newer than the key's \
+                 latest_version {} (was the key re-created?)",
+                info.latest_version
+            )),
+            _ => err,
+        }
+    }
+
     // --- sync helpers ---
 
-    fn encrypt_key_sync(&self, key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
-        let encoded = Self::encode_plaintext_for_vault(key_bytes);
-        let body = EncryptRequest {
-            plaintext: &encoded,
-        };
+    fn post_sync<B: Serialize, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &B,
+        operation: &str,
+    ) -> anyhow::Result<VaultResponse<T>> {
+        let token = self.auth.token();
         let resp = self
             .sync_client
-            .post(&self.encrypt_url)
-            .header("X-Vault-Token", self.token.as_str())
-            .json(&body)
+            .post(url)
+            .header("X-Vault-Token", token.as_str())
+            .json(body)
             .send()
             .map_err(|e| {
                 // `warn!` rather than `error!` because the error chain is
@@ -427,154 +992,268 @@ impl VaultTransitKms {
                 // a chance to handle/retry. T-finding "Vault transit logs
                 // full reqwest chain at error!" in
                 // `docs/review-2026-05-05-findings.md`.
-                log::warn!("VaultTransitKms encrypt HTTP error: {e:#}");
-                anyhow::anyhow!("Vault Transit encrypt request failed: {e}")
+                log::warn!("VaultTransitKms {operation} HTTP error: {e:#}");
+                anyhow::anyhow!("Vault Transit {operation} request failed: {e}")
             })?;
+        // Check the HTTP status *before* parsing JSON. A 5xx with an HTML
+        // body (or a 401/403 reverse-proxy challenge page) would otherwise
+        // surface as an opaque "JSON parse failed" error and mask the real
+        // status. T-finding "never inspects resp.status() before .json()"
+        // in `docs/review-2026-05-05-findings.md`.
         let status = resp.status();
         if !status.is_success() {
             let snippet = resp.text().unwrap_or_default();
             let snippet = truncate_for_log(&snippet, 256);
-            anyhow::bail!("Vault Transit encrypt: HTTP {status} (body: {snippet})");
+            anyhow::bail!("Vault Transit {operation}: HTTP {status} (body: {snippet})");
         }
-        let vault_resp: VaultResponse<EncryptData> = resp.json().map_err(|e| {
+        resp.json().map_err(|e| {
             anyhow::anyhow!(
-                "Vault Transit encrypt: failed to parse response (status {status}): {e}"
+                "Vault Transit {operation}: failed to parse response (status {status}): {e}"
             )
-        })?;
-        Self::check_vault_errors(vault_resp.errors, "encrypt")?;
-        let data = vault_resp
-            .data
-            .ok_or_else(|| anyhow::anyhow!("Vault Transit encrypt returned no data"))?;
+        })
+    }
+
+    fn encrypt_key_sync(&self, key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let encoded = Self::encode_plaintext_for_vault(key_bytes);
+        let resp = self.post_sync(&self.encrypt_url, &self.encrypt_body(&encoded), "encrypt")?;
+        let data: EncryptData = Self::unwrap_data(resp, "encrypt")?;
+        self.note_encrypt_version(data.key_version);
         Ok(data.ciphertext.into_bytes())
     }
 
     fn decrypt_key_sync(&self, blob: &[u8]) -> anyhow::Result<Vec<u8>> {
-        let ciphertext = std::str::from_utf8(blob)
-            .map_err(|e| anyhow::anyhow!("Vault Transit decrypt: blob is not valid UTF-8: {e}"))?;
-        // Vault transit ciphertexts are versioned with a `vault:v<n>:` prefix.
-        // Reject anything else early — a corrupted or truncated metastore
-        // value otherwise round-trips into Vault and produces a confusing
-        // server-side error. T-finding "no validation of `vault:v` prefix"
-        // in `docs/review-2026-05-05-findings.md`.
-        if !ciphertext.starts_with("vault:v") {
-            anyhow::bail!(
-                "Vault Transit decrypt: ciphertext does not start with the expected \
-                 `vault:v<n>:` version prefix (got {} bytes)",
-                ciphertext.len()
-            );
-        }
+        let ciphertext = Self::ciphertext_str(blob)?;
         let body = DecryptRequest { ciphertext };
+        let data: DecryptData = match self.post_sync(&self.decrypt_url, &body, "decrypt") {
+            Ok(resp) => Self::unwrap_data(resp, "decrypt")?,
+            Err(e) if is_bad_request(&e) => {
+                return Err(Self::explain_decrypt_failure(
+                    e,
+                    ciphertext,
+                    self.key_info(),
+                ))
+            }
+            Err(e) => return Err(e),
+        };
+        Self::decode_plaintext_from_vault(data.plaintext)
+    }
+
+    /// Encrypt several keys in one `batch_input` request. Results are in
+    /// input order; any per-item error fails the whole call.
+    pub fn encrypt_keys_batch(&self, keys: &[&[u8]]) -> anyhow::Result<Vec<Vec<u8>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encoded: Vec<_> = keys
+            .iter()
+            .map(|k| Self::encode_plaintext_for_vault(k))
+            .collect();
+        let resp = self.post_sync(
+            &self.encrypt_url,
+            &self.batch_encrypt_body(&encoded),
+            "batch encrypt",
+        )?;
+        self.batch_encrypt_results(Self::unwrap_data(resp, "batch encrypt")?, keys.len())
+    }
+
+    /// Decrypt several blobs in one `batch_input` request. Results are in
+    /// input order; any per-item error fails the whole call.
+    pub fn decrypt_keys_batch(&self, blobs: &[&[u8]]) -> anyhow::Result<Vec<Vec<u8>>> {
+        if blobs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = BatchDecryptRequest {
+            batch_input: blobs
+                .iter()
+                .map(|b| Self::ciphertext_str(b).map(|ciphertext| DecryptRequest { ciphertext }))
+                .collect::<anyhow::Result<_>>()?,
+        };
+        let resp = self.post_sync(&self.decrypt_url, &body, "batch decrypt")?;
+        Self::batch_decrypt_results(Self::unwrap_data(resp, "batch decrypt")?, blobs.len())
+    }
+
+    /// Read the Transit key's version metadata. Requires `read` on
+    /// `{mount}/keys/{name}`.
+    pub fn key_info(&self) -> anyhow::Result<VaultKeyInfo> {
+        let token = self.auth.token();
         let resp = self
             .sync_client
-            .post(&self.decrypt_url)
-            .header("X-Vault-Token", self.token.as_str())
-            .json(&body)
+            .get(&self.key_url)
+            .header("X-Vault-Token", token.as_str())
             .send()
-            .map_err(|e| {
-                // See the matching note in encrypt — `warn!` because the
-                // error is also returned to the caller.
-                log::warn!("VaultTransitKms decrypt HTTP error: {e:#}");
-                anyhow::anyhow!("Vault Transit decrypt request failed: {e}")
-            })?;
-        // Check the HTTP status *before* parsing JSON. A 5xx with an HTML
-        // body (or a 401/403 reverse-proxy challenge page) would otherwise
-        // surface as an opaque "JSON parse failed" error and mask the real
-        // status. T-finding "never inspects resp.status() before .json()"
-        // in `docs/review-2026-05-05-findings.md`.
+            .map_err(|e| anyhow::anyhow!("Vault Transit key read request failed: {e}"))?;
         let status = resp.status();
         if !status.is_success() {
             let snippet = resp.text().unwrap_or_default();
             let snippet = truncate_for_log(&snippet, 256);
-            anyhow::bail!("Vault Transit decrypt: HTTP {status} (body: {snippet})");
+            anyhow::bail!("Vault Transit key read: HTTP {status} (body: {snippet})");
         }
-        let vault_resp: VaultResponse<DecryptData> = resp.json().map_err(|e| {
-            anyhow::anyhow!(
-                "Vault Transit decrypt: failed to parse response (status {status}): {e}"
-            )
+        let resp: VaultResponse<VaultKeyInfo> = resp.json().map_err(|e| {
+            anyhow::anyhow!("Vault Transit key read: failed to parse response: {e}")
         })?;
-        Self::check_vault_errors(vault_resp.errors, "decrypt")?;
-        let data = vault_resp
-            .data
-            .ok_or_else(|| anyhow::anyhow!("Vault Transit decrypt returned no data"))?;
-        Self::decode_plaintext_from_vault(data.plaintext)
+        Self::unwrap_data(resp, "key read")
     }
 
     // --- async helpers ---
 
-    async fn encrypt_key_impl(&self, key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
-        let encoded = Self::encode_plaintext_for_vault(key_bytes);
-        let body = EncryptRequest {
-            plaintext: &encoded,
-        };
+    async fn post_async<B: Serialize + Sync, T: DeserializeOwned>(
+        &self,
+        url: &str,
+        body: &B,
+        operation: &str,
+    ) -> anyhow::Result<VaultResponse<T>> {
+        let token = self.auth.token();
         let resp = self
             .async_client
-            .post(&self.encrypt_url)
-            .header("X-Vault-Token", self.token.as_str())
-            .json(&body)
+            .post(url)
+            .header("X-Vault-Token", token.as_str())
+            .json(body)
             .send()
             .await
             .map_err(|e| {
-                log::warn!("VaultTransitKms encrypt HTTP error: {e:#}");
-                anyhow::anyhow!("Vault Transit encrypt request failed: {e}")
+                // See the matching note in `post_sync` — `warn!` because
+                // the error is also returned to the caller.
+                log::warn!("VaultTransitKms {operation} HTTP error: {e:#}");
+                anyhow::anyhow!("Vault Transit {operation} request failed: {e}")
             })?;
         let status = resp.status();
         if !status.is_success() {
             let snippet = resp.text().await.unwrap_or_default();
             let snippet = truncate_for_log(&snippet, 256);
-            anyhow::bail!("Vault Transit encrypt: HTTP {status} (body: {snippet})");
+            anyhow::bail!("Vault Transit {operation}: HTTP {status} (body: {snippet})");
         }
-        let vault_resp: VaultResponse<EncryptData> = resp.json().await.map_err(|e| {
+        resp.json().await.map_err(|e| {
             anyhow::anyhow!(
-                "Vault Transit encrypt: failed to parse response (status {status}): {e}"
+                "Vault Transit {operation}: failed to parse response (status {status}): {e}"
             )
-        })?;
-        Self::check_vault_errors(vault_resp.errors, "encrypt")?;
-        let data = vault_resp
-            .data
-            .ok_or_else(|| anyhow::anyhow!("Vault Transit encrypt returned no data"))?;
+        })
+    }
+
+    async fn encrypt_key_impl(&self, key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        let encoded = Self::encode_plaintext_for_vault(key_bytes);
+        let resp = self
+            .post_async(&self.encrypt_url, &self.encrypt_body(&encoded), "encrypt")
+            .await?;
+        let data: EncryptData = Self::unwrap_data(resp, "encrypt")?;
+        self.note_encrypt_version(data.key_version);
         Ok(data.ciphertext.into_bytes())
     }
 
     async fn decrypt_key_impl(&self, blob: &[u8]) -> anyhow::Result<Vec<u8>> {
-        let ciphertext = std::str::from_utf8(blob)
-            .map_err(|e| anyhow::anyhow!("Vault Transit decrypt: blob is not valid UTF-8: {e}"))?;
-        if !ciphertext.starts_with("vault:v") {
-            anyhow::bail!(
-                "Vault Transit decrypt: ciphertext does not start with the expected \
-                 `vault:v<n>:` version prefix (got {} bytes)",
-                ciphertext.len()
-            );
-        }
+        let ciphertext = Self::ciphertext_str(blob)?;
         let body = DecryptRequest { ciphertext };
+        let data: DecryptData = match self.post_async(&self.decrypt_url, &body, "decrypt").await {
+            Ok(resp) => Self::unwrap_data(resp, "decrypt")?,
+            Err(e) if is_bad_request(&e) => {
+                let info = self.key_info_async().await;
+                return Err(Self::explain_decrypt_failure(e, ciphertext, info));
+            }
+            Err(e) => return Err(e),
+        };
+        Self::decode_plaintext_from_vault(data.plaintext)
+    }
+
+    /// Async variant of [`Self::encrypt_keys_batch`].
+    pub async fn encrypt_keys_batch_async(&self, keys: &[&[u8]]) -> anyhow::Result<Vec<Vec<u8>>> {
+        if keys.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encoded: Vec<_> = keys
+            .iter()
+            .map(|k| Self::encode_plaintext_for_vault(k))
+            .collect();
+        let resp = self
+            .post_async(
+                &self.encrypt_url,
+                &self.batch_encrypt_body(&encoded),
+                "batch encrypt",
+            )
+            .await?;
+        self.batch_encrypt_results(Self::unwrap_data(resp, "batch encrypt")?, keys.len())
+    }
+
+    /// Async variant of [`Self::decrypt_keys_batch`].
+    pub async fn decrypt_keys_batch_async(&self, blobs: &[&[u8]]) -> anyhow::Result<Vec<Vec<u8>>> {
+        if blobs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let body = BatchDecryptRequest {
+            batch_input: blobs
+                .iter()
+                .map(|b| Self::ciphertext_str(b).map(|ciphertext| DecryptRequest { ciphertext }))
+                .collect::<anyhow::Result<_>>()?,
+        };
+        let resp = self
+            .post_async(&self.decrypt_url, &body, "batch decrypt")
+            .await?;
+        Self::batch_decrypt_results(Self::unwrap_data(resp, "batch decrypt")?, blobs.len())
+    }
+
+    /// Async variant of [`Self::key_info`].
+    pub async fn key_info_async(&self) -> anyhow::Result<VaultKeyInfo> {
+        let token = self.auth.token();
         let resp = self
             .async_client
-            .post(&self.decrypt_url)
-            .header("X-Vault-Token", self.token.as_str())
-            .json(&body)
+            .get(&self.key_url)
+            .header("X-Vault-Token", token.as_str())
             .send()
             .await
-            .map_err(|e| {
-                // See the matching note in encrypt — `warn!` because the
-                // error is also returned to the caller.
-                log::warn!("VaultTransitKms decrypt HTTP error: {e:#}");
-                anyhow::anyhow!("Vault Transit decrypt request failed: {e}")
-            })?;
+            .map_err(|e| anyhow::anyhow!("Vault Transit key read request failed: {e}"))?;
         let status = resp.status();
         if !status.is_success() {
             let snippet = resp.text().await.unwrap_or_default();
             let snippet = truncate_for_log(&snippet, 256);
-            anyhow::bail!("Vault Transit decrypt: HTTP {status} (body: {snippet})");
+            anyhow::bail!("Vault Transit key read: HTTP {status} (body: {snippet})");
         }
-        let vault_resp: VaultResponse<DecryptData> = resp.json().await.map_err(|e| {
-            anyhow::anyhow!(
-                "Vault Transit decrypt: failed to parse response (status {status}): {e}"
-            )
+        let resp: VaultResponse<VaultKeyInfo> = resp.json().await.map_err(|e| {
+            anyhow::anyhow!("Vault Transit key read: failed to parse response: {e}")
         })?;
-        Self::check_vault_errors(vault_resp.errors, "decrypt")?;
-        let data = vault_resp
-            .data
-            .ok_or_else(|| anyhow::anyhow!("Vault Transit decrypt returned no data"))?;
-        Self::decode_plaintext_from_vault(data.plaintext)
+        Self::unwrap_data(resp, "key read")
+    }
+}
+
+/// Vault answers a decrypt of a trimmed key version with HTTP 400
+/// ("ciphertext or signature version is disallowed by policy").
+fn is_bad_request(err: &anyhow::Error) -> bool {
+    err.to_string().contains(": HTTP 400 ")
+}
+
+fn check_key_version_pin(pin: u32, info: &VaultKeyInfo) -> anyhow::Result<()> {
+    if pin > info.latest_version {
+        anyhow::bail!(
+            "Vault Transit key_version {pin} does not exist (latest_version is {})",
+            info.latest_version
+        );
+    }
+    if pin < info.min_encryption_version {
+        anyhow::bail!(
+            "Vault Transit key_version {pin} is below the key's min_encryption_version {}",
+            info.min_encryption_version
+        );
+    }
+    if pin < info.min_decryption_version {
+        anyhow::bail!(
+            "Vault Transit key_version {pin} is below the key's min_decryption_version {}; \
+             ciphertexts it produces could not be decrypted",
+            info.min_decryption_version
+        );
+    }
+    Ok(())
+}
+
+fn check_batch_len(got: usize, expected: usize, operation: &str) -> anyhow::Result<()> {
+    if got != expected {
+        anyhow::bail!(
+            "Vault Transit batch {operation} returned {got} results for {expected} inputs"
+        );
+    }
+    Ok(())
+}
+
+fn batch_item_error(error: Option<String>, index: usize, operation: &str) -> anyhow::Result<()> {
+    match error.filter(|e| !e.is_empty()) {
+        Some(e) => anyhow::bail!("Vault Transit batch {operation}: item {index} failed: {e}"),
+        None => Ok(()),
     }
 }
 
@@ -623,4 +1302,73 @@ mod tests {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn ciphertext_key_version_parses_vault_prefix() {
+        assert_eq!(ciphertext_key_version("vault:v12:abc"), Some(12));
+        assert_eq!(ciphertext_key_version("vault:v1:"), Some(1));
+        assert_eq!(ciphertext_key_version("vault:vx:abc"), None);
+        assert_eq!(ciphertext_key_version("vault:v3"), None);
+        assert_eq!(ciphertext_key_version("v3:abc"), None);
+    }
+
+    #[test]
+    fn renewal_is_scheduled_at_two_thirds_of_ttl() {
+        let now = Instant::now();
+        let lease = TokenLease {
+            ttl: Duration::from_secs(3600),
+            renewable: true,
+            obtained: now,
+        };
+        assert_eq!(renewal_delay(&lease, now), Some(Duration::from_secs(2400)));
+        let non_expiring = TokenLease {
+            ttl: Duration::ZERO,
+            ..lease
+        };
+        assert_eq!(renewal_delay(&non_expiring, now), None);
+        let overdue = renewal_delay(&lease, now + Duration::from_secs(3000));
+        assert_eq!(overdue, Some(MIN_RENEWAL_DELAY));
+    }
+
+    #[test]
+    fn key_version_pin_must_be_usable() {
+        let info = VaultKeyInfo {
+            latest_version: 5,
+            min_decryption_version: 3,
+            min_encryption_version: 0,
+        };
+        assert!(check_key_version_pin(4, &info).is_ok());
+        assert!(check_key_version_pin(5, &info).is_ok());
+        assert!(check_key_version_pin(6, &info).is_err());
+        assert!(check_key_version_pin(2, &info).is_err());
+        let info = VaultKeyInfo {
+            min_encryption_version: 5,
+            ..info
+        };
+        assert!(check_key_version_pin(4, &info).is_err());
+    }
+
+    #[test]
+    fn decrypt_failure_names_min_decryption_version() {
+        let info = VaultKeyInfo {
+            latest_version: 6,
+            min_decryption_version: 4,
+            min_encryption_version: 0,
+        };
+        let err = VaultTransitKms::explain_decrypt_failure(
+            anyhow::anyhow!("Vault Transit decrypt: HTTP 400 Bad Request (body: ...)"),
+            "vault:v2:abc",
+            Ok(info),
+        );
+        let msg = format!("{err:#}");
+        assert!(msg.contains("min_decryption_version 4"), "{msg}");
+        assert!(msg.contains("HTTP 400"), "{msg}");
+
+        let err = VaultTransitKms::explain_decrypt_failure(
+            anyhow::anyhow!("HTTP 400"),
+            "vault:v5:abc",
+            Ok(info),
+        );
+        assert_eq!(format!("{err:#}"), "HTTP 400");
+    }
 }
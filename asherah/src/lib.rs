@@ -22,18 +22,21 @@ pub mod builders;
 pub mod cache;
 pub mod config;
 pub mod config_drift_guard;
+pub mod error;
 pub mod internal;
 pub mod kms;
 pub mod kms_aws;
 pub mod kms_aws_envelope;
 pub mod kms_builders;
 pub mod kms_multi;
+pub mod kms_quorum;
 #[cfg(feature = "secrets-manager")]
 pub mod kms_secrets_manager;
 #[cfg(feature = "vault")]
 pub mod kms_vault_transit;
 pub mod limits;
 pub mod logging;
+pub mod master_key_rotation;
 pub mod metastore;
 #[cfg(feature = "dynamodb")]
 pub mod metastore_dynamodb;
@@ -69,6 +72,7 @@ pub mod session_cache;
 /// for a metastore-shaped equivalent.
 #[doc(hidden)]
 pub mod store;
+pub mod stream;
 pub mod traits;
 pub mod types;
 // Crate-private helpers (not re-exported)
@@ -1,3 +1,5 @@
+use crate::error::ErrorCode;
+
 /// Default maximum plaintext bytes accepted by public encrypt APIs.
 ///
 /// Asherah is intended for application data row encryption, not bulk object
@@ -20,27 +22,28 @@ pub const MAX_DATA_ROW_KEY_ID_BYTES: usize = 4 * 1024;
 
 pub fn check_plaintext_len(len: usize) -> anyhow::Result<()> {
     if len > MAX_PAYLOAD_BYTES {
-        anyhow::bail!(
+        return Err(ErrorCode::PayloadTooLarge.error(format!(
             "plaintext length {len} exceeds maximum {} bytes",
             MAX_PAYLOAD_BYTES
-        );
+        )));
     }
     Ok(())
 }
 
 pub fn check_ciphertext_len(len: usize) -> anyhow::Result<()> {
     if len > MAX_ENVELOPE_BYTES {
-        anyhow::bail!(
+        return Err(ErrorCode::PayloadTooLarge.error(format!(
             "ciphertext/envelope length {len} exceeds maximum {} bytes",
             MAX_ENVELOPE_BYTES
-        );
+        )));
     }
     Ok(())
 }
 
 fn check_nested_len(label: &str, len: usize, max: usize) -> anyhow::Result<()> {
     if len > max {
-        anyhow::bail!("{label} length {len} exceeds maximum {max} bytes");
+        return Err(ErrorCode::InvalidDataRowRecord
+            .error(format!("{label} length {len} exceeds maximum {max} bytes")));
     }
     Ok(())
 }
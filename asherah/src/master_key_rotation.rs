@@ -0,0 +1,144 @@
+//! Master-key rotation: re-wrap stored system keys under a new KMS.
+//!
+//! Changing the AWS KMS key ARN or the static master key leaves every
+//! existing system key (SK) wrapped under the old master key. The functions
+//! here visit every stored version of each SK id, unwrap it with the old
+//! `KeyManagementService`, wrap the same key bytes with the new one and
+//! replace the record in place under the same id/created. Intermediate keys
+//! are encrypted under the SK plaintext — which does not change — and
+//! reference their parent by id/created, so they need no rewrite.
+//!
+//! Re-wrapping is idempotent: records that already decrypt under the new KMS
+//! are skipped, so an interrupted run can be repeated. While it runs, services
+//! should be configured with the new KMS and `previous_kms` set to the old one
+//! (see [`crate::builders::ResolvedConfig::previous_kms`]); that decrypt window
+//! accepts SKs wrapped under either key and wraps new SKs with the new key.
+
+use anyhow::Context as _;
+use subtle::ConstantTimeEq as _;
+use zeroize::Zeroizing;
+
+use crate::partition::DefaultPartition;
+use crate::traits::{KeyManagementService, Metastore, Partition as _};
+use crate::types::EnvelopeKeyRecord;
+
+/// Outcome of a [`rewrap_system_keys`] run.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RewrapReport {
+    /// System-key records visited.
+    pub scanned: usize,
+    /// Records re-wrapped under the new KMS and written back.
+    pub rewrapped: usize,
+    /// Records that already decrypted under the new KMS and were left alone.
+    pub already_current: usize,
+}
+
+/// Re-wrap every stored version of each id in `system_key_ids` from
+/// `old_kms` to `new_kms`.
+///
+/// Each re-wrapped blob is decrypted again with `new_kms` and compared to the
+/// original key bytes before it replaces the stored record, so a
+/// misconfigured new KMS fails the run instead of orphaning the SK. The
+/// `revoked` flag and the record id/created are preserved.
+pub fn rewrap_system_keys(
+    metastore: &dyn Metastore,
+    old_kms: &dyn KeyManagementService,
+    new_kms: &dyn KeyManagementService,
+    system_key_ids: &[String],
+) -> anyhow::Result<RewrapReport> {
+    let mut report = RewrapReport::default();
+    for id in system_key_ids {
+        let records = metastore
+            .load_all(id)
+            .with_context(|| format!("failed to enumerate system key versions for id={id}"))?;
+        for ekr in records {
+            report.scanned += 1;
+            if rewrap_record(metastore, old_kms, new_kms, id, &ekr)? {
+                report.rewrapped += 1;
+            } else {
+                report.already_current += 1;
+            }
+        }
+    }
+    log::info!(
+        "master-key rotation: scanned={} rewrapped={} already_current={}",
+        report.scanned,
+        report.rewrapped,
+        report.already_current
+    );
+    Ok(report)
+}
+
+/// SK ids a rotation must visit for `service`/`product`: the unsuffixed id
+/// plus one per region suffix in use (the effective suffix and any recovery
+/// suffixes). Duplicates and empty suffixes are dropped.
+pub fn system_key_ids(
+    service: &str,
+    product: &str,
+    region_suffixes: impl IntoIterator<Item = impl AsRef<str>>,
+) -> Vec<String> {
+    let base = DefaultPartition::new(String::new(), service.to_string(), product.to_string());
+    let mut ids = vec![base.system_key_id()];
+    for suffix in region_suffixes {
+        let suffix = suffix.as_ref();
+        if suffix.is_empty() {
+            continue;
+        }
+        let id = DefaultPartition::new_suffixed(
+            String::new(),
+            service.to_string(),
+            product.to_string(),
+            suffix.to_string(),
+        )
+        .system_key_id();
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+fn rewrap_record(
+    metastore: &dyn Metastore,
+    old_kms: &dyn KeyManagementService,
+    new_kms: &dyn KeyManagementService,
+    id: &str,
+    ekr: &EnvelopeKeyRecord,
+) -> anyhow::Result<bool> {
+    let created = ekr.created;
+    if ekr.parent_key_meta.is_some() {
+        anyhow::bail!("record id={id} created={created} has a parent key and is not a system key");
+    }
+    if let Ok(current) = new_kms.decrypt_key(&(), &ekr.encrypted_key) {
+        drop(Zeroizing::new(current));
+        log::debug!("master-key rotation: id={id} created={created} already wrapped by new KMS");
+        return Ok(false);
+    }
+    let key = Zeroizing::new(
+        old_kms
+            .decrypt_key(&(), &ekr.encrypted_key)
+            .with_context(|| {
+                format!("old KMS failed to decrypt system key id={id} created={created}")
+            })?,
+    );
+    let wrapped = new_kms.encrypt_key(&(), &key).with_context(|| {
+        format!("new KMS failed to encrypt system key id={id} created={created}")
+    })?;
+    let check = Zeroizing::new(new_kms.decrypt_key(&(), &wrapped).with_context(|| {
+        format!("new KMS failed to decrypt re-wrapped system key id={id} created={created}")
+    })?);
+    if !bool::from(check.as_slice().ct_eq(key.as_slice())) {
+        anyhow::bail!("re-wrapped system key id={id} created={created} failed verification");
+    }
+    let updated = EnvelopeKeyRecord {
+        encrypted_key: wrapped,
+        ..ekr.clone()
+    };
+    metastore
+        .replace_key_record(id, created, &updated)
+        .with_context(|| {
+            format!("failed to write re-wrapped system key id={id} created={created}")
+        })?;
+    log::debug!("master-key rotation: re-wrapped id={id} created={created}");
+    Ok(true)
+}
@@ -136,6 +136,34 @@ impl Metastore for InMemoryMetastore {
         Ok(())
     }
 
+    fn load_all(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        let mut out = Vec::new();
+        self.by_key.iter_sync(|(key_id, _), rec| {
+            if key_id.as_ref() == id {
+                out.push(rec.clone());
+            }
+            true
+        });
+        out.sort_by_key(|rec| rec.created);
+        Ok(out)
+    }
+
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        let key: Arc<str> = Arc::from(id);
+        let replaced = self.by_key.update_sync(&(key, created), |_, rec| {
+            *rec = ekr.clone();
+        });
+        if replaced.is_none() {
+            anyhow::bail!("key record not found: id={id} created={created}");
+        }
+        Ok(())
+    }
+
     fn region_suffix(&self) -> Option<String> {
         None
     }
@@ -267,6 +267,76 @@ impl DynamoDbMetastore {
         Self::do_upsert_config_drift_guard(&self.sync_client, &self.table, id, created, ekr).await
     }
 
+    async fn load_all_impl_sync(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        log::debug!("dynamodb load_all: table={} id={id}", self.table);
+        let mut out = Vec::new();
+        let mut start_key = None;
+        loop {
+            let page = self
+                .sync_client
+                .query()
+                .table_name(&self.table)
+                .key_condition_expression("Id = :id")
+                .expression_attribute_values(":id", AttributeValue::S(id.to_string()))
+                .scan_index_forward(true)
+                .consistent_read(true)
+                .set_exclusive_start_key(start_key)
+                .send()
+                .await
+                .with_context(|| {
+                    format!("DynamoDB Query failed for table={} id={id}", self.table)
+                })?;
+            for item in page.items() {
+                if let Some(ekr) = Self::parse_item(Some(item), id)? {
+                    out.push(ekr);
+                }
+            }
+            match page.last_evaluated_key() {
+                Some(key) if !key.is_empty() => start_key = Some(key.clone()),
+                _ => break,
+            }
+        }
+        Ok(out)
+    }
+
+    async fn replace_key_record_impl_sync(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        let key_record = Self::key_record_attribute_map(ekr);
+        log::debug!(
+            "dynamodb replace: table={} id={id} created={created}",
+            self.table
+        );
+        let out = self
+            .sync_client
+            .put_item()
+            .table_name(&self.table)
+            .item("Id", AttributeValue::S(id.to_string()))
+            .item("Created", AttributeValue::N(created.to_string()))
+            .item("KeyRecord", AttributeValue::M(key_record))
+            .condition_expression("attribute_exists(Id)")
+            .send()
+            .await;
+        match out {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                let is_missing = e
+                    .as_service_error()
+                    .is_some_and(|svc| svc.is_conditional_check_failed_exception());
+                if is_missing {
+                    anyhow::bail!("key record not found: id={id} created={created}");
+                }
+                Err(anyhow::anyhow!(
+                    "DynamoDB replace PutItem failed for table={} id={id}: {e}",
+                    self.table
+                ))
+            }
+        }
+    }
+
     // ── Async implementations (use async_client on caller's runtime) ──
 
     async fn load_impl_async(
@@ -573,6 +643,22 @@ impl Metastore for DynamoDbMetastore {
         )
     }
 
+    fn load_all(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        Self::block_on_maybe(self.rt.runtime(), self.load_all_impl_sync(id))
+    }
+
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        Self::block_on_maybe(
+            self.rt.runtime(),
+            self.replace_key_record_impl_sync(id, created, ekr),
+        )
+    }
+
     fn region_suffix(&self) -> Option<String> {
         if self.region_suffix_enabled {
             self.region_suffix.clone()
@@ -174,6 +174,50 @@ impl Metastore for MySqlMetastore {
         Ok(())
     }
 
+    fn load_all(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        log::debug!("mysql load_all: id={id}");
+        let mut conn = self.conn()?;
+        let rows: Vec<(String,)> = conn
+            .exec(
+                "SELECT key_record FROM encryption_key WHERE id=? ORDER BY created ASC",
+                (id,),
+            )
+            .with_context(|| format!("MySQL load_all query failed for id={id}"))?;
+        drop(conn);
+        rows.into_iter()
+            .map(|(json_str,)| {
+                EnvelopeKeyRecord::from_json_fast(&json_str).with_context(|| {
+                    format!("MySQL load_all: failed to parse key_record JSON for id={id}")
+                })
+            })
+            .collect()
+    }
+
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        log::debug!("mysql replace: id={id} created={created}");
+        let rec = ekr.to_json_fast();
+        let mut conn = self.conn()?;
+        let ts = epoch_to_utc_datetime(created);
+        conn.exec_drop(
+            "UPDATE encryption_key SET key_record=? WHERE id=? AND created=?",
+            (rec, id, &ts),
+        )
+        .with_context(|| format!("MySQL replace failed for id={id} created={created}"))?;
+        // MySQL reports 0 affected rows when the new value equals the old
+        // one, so a miss is confirmed with a load rather than inferred.
+        let replaced = conn.affected_rows() > 0;
+        drop(conn);
+        if !replaced && self.load(id, created)?.is_none() {
+            anyhow::bail!("key record not found: id={id} created={created}");
+        }
+        Ok(())
+    }
+
     // Async methods use spawn_blocking (reuses thread pool) instead of
     // std::thread::spawn (creates new OS thread per call). The mysql crate
     // doesn't call block_on internally, so spawn_blocking is safe here.
@@ -487,6 +487,51 @@ impl Metastore for PostgresMetastore {
         Ok(())
     }
 
+    fn load_all(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        log::debug!("postgres load_all: id={id}");
+        let mut c = self.client()?;
+        let rows = c
+            .query(
+                "SELECT key_record::text FROM encryption_key WHERE id=$1 ORDER BY created ASC",
+                &[&id],
+            )
+            .with_context(|| format!("Postgres load_all query failed for id={id}"))?;
+        rows.iter()
+            .map(|row| {
+                let txt: String = row.get(0);
+                EnvelopeKeyRecord::from_json_fast(&txt).with_context(|| {
+                    format!("Postgres load_all: failed to parse key_record JSON for id={id}")
+                })
+            })
+            .collect()
+    }
+
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        log::debug!("postgres replace: id={id} created={created}");
+        let mut c = self.client()?;
+        let v = ekr.to_json_fast();
+        let v_json: serde_json::Value = serde_json::from_str(&v).with_context(|| {
+            format!("Postgres replace: failed to re-parse key_record JSON for id={id}")
+        })?;
+        let created_f = created as f64;
+        let res = c
+            .execute(
+                "UPDATE encryption_key SET key_record = $3 \
+             WHERE id=$1 AND created=to_timestamp($2)",
+                &[&id, &created_f, &v_json],
+            )
+            .with_context(|| format!("Postgres replace failed for id={id} created={created}"))?;
+        if res == 0 {
+            anyhow::bail!("key record not found: id={id} created={created}");
+        }
+        Ok(())
+    }
+
     // The sync postgres crate does blocking I/O with internal block_on for
     // connection management. spawn_blocking is safe here because blocking pool
     // threads don't have the runtime "entered" (only Handle is available).
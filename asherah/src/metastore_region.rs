@@ -42,6 +42,17 @@ impl<M: Metastore + ?Sized> Metastore for RegionSuffixMetastore<M> {
     ) -> Result<(), anyhow::Error> {
         self.inner.upsert_config_drift_guard(id, created, ekr)
     }
+    fn load_all(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        self.inner.load_all(id)
+    }
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        self.inner.replace_key_record(id, created, ekr)
+    }
     fn region_suffix(&self) -> Option<String> {
         Some(self.suffix.clone())
     }
@@ -120,6 +120,48 @@ impl Metastore for SqliteMetastore {
         })?;
         Ok(())
     }
+    fn load_all(&self, id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        log::debug!("sqlite load_all: id={id}");
+        let conn = self.conn.lock();
+        let mut stmt = conn
+            .prepare("SELECT key_record FROM encryption_key WHERE id=?1 ORDER BY created ASC")
+            .with_context(|| format!("SQLite load_all prepare failed for id={id}"))?;
+        let mut rows = stmt
+            .query(params![id])
+            .with_context(|| format!("SQLite load_all query failed for id={id}"))?;
+        let mut out = Vec::new();
+        while let Some(row) = rows.next()? {
+            let txt: String = row.get(0)?;
+            let ekr: EnvelopeKeyRecord = serde_json::from_str(&txt).with_context(|| {
+                format!("SQLite load_all: failed to parse key_record JSON for id={id}")
+            })?;
+            out.push(ekr);
+        }
+        Ok(out)
+    }
+    fn replace_key_record(
+        &self,
+        id: &str,
+        created: i64,
+        ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        log::debug!("sqlite replace: id={id} created={created}");
+        let rec = serde_json::to_string(ekr).with_context(|| {
+            format!("SQLite replace: failed to serialize key_record for id={id}")
+        })?;
+        let conn = self.conn.lock();
+        let res = conn
+            .execute(
+                "UPDATE encryption_key SET key_record=?3 \
+                 WHERE id=?1 AND created = datetime(?2, 'unixepoch')",
+                params![id, created, rec],
+            )
+            .with_context(|| format!("SQLite replace failed for id={id} created={created}"))?;
+        if res == 0 {
+            anyhow::bail!("key record not found: id={id} created={created}");
+        }
+        Ok(())
+    }
     fn region_suffix(&self) -> Option<String> {
         None
     }
@@ -1,5 +1,6 @@
 use crate::cache::{CacheCheck, CachePolicy, KeyCacher, NeverCache, SimpleKeyCache};
 use crate::config::Config;
+use crate::error::{ErrorCode, WithErrorCode};
 use crate::internal::crypto_key::{
     generate_key, generate_key_with_key_schedule_cache, is_key_expired,
 };
@@ -118,6 +119,7 @@ impl<
             .f
             .metastore
             .load(&meta.id, meta.created)
+            .code(ErrorCode::MetastoreUnavailable)
             .context(format!(
                 "failed to load system key id={} created={}",
                 meta.id, meta.created
@@ -128,11 +130,10 @@ impl<
                     meta.id,
                     meta.created
                 );
-                anyhow::anyhow!(
+                ErrorCode::KeyNotFound.error(format!(
                     "system key not found: id={} created={}",
-                    meta.id,
-                    meta.created
-                )
+                    meta.id, meta.created
+                ))
             })?;
         self.system_key_from_ekr(&ekr)
             .context(format!("failed to decrypt system key id={}", meta.id))
@@ -143,6 +144,7 @@ impl<
             .f
             .kms
             .decrypt_key(&(), &ekr.encrypted_key)
+            .code(ErrorCode::KmsUnavailable)
             .context(format!(
                 "KMS failed to decrypt system key id={} created={}",
                 ekr.id, ekr.created
@@ -198,7 +200,12 @@ impl<
     }
 
     fn load_latest_or_create_system_key(&self) -> anyhow::Result<CryptoKey> {
-        if let Some(ekr) = self.f.metastore.load_latest(&self.system_key_id())? {
+        if let Some(ekr) = self
+            .f
+            .metastore
+            .load_latest(&self.system_key_id())
+            .code(ErrorCode::MetastoreUnavailable)?
+        {
             if !self.is_envelope_invalid(&ekr) {
                 return self.system_key_from_ekr(&ekr);
             }
@@ -218,7 +225,12 @@ impl<
     }
 
     fn try_store_system_key(&self, sk: &CryptoKey) -> (bool, Option<anyhow::Error>) {
-        let enc = match sk.with_key_func(|k| self.f.kms.encrypt_key(&(), k)) {
+        let enc = match sk.with_key_func(|k| {
+            self.f
+                .kms
+                .encrypt_key(&(), k)
+                .code(ErrorCode::KmsUnavailable)
+        }) {
             Ok(Ok(v)) => v,
             Ok(Err(e)) => {
                 log::error!("try_store_system_key: KMS encrypt_key failed: {e:#}");
@@ -271,10 +283,11 @@ impl<
             .f
             .metastore
             .load_latest(id)
+            .code(ErrorCode::MetastoreUnavailable)
             .context(format!("failed to load latest key for id={id}"))?
             .ok_or_else(|| {
                 log::error!("latest key not found for id={id}");
-                anyhow::anyhow!("latest key not found for id={id}")
+                ErrorCode::KeyNotFound.error(format!("latest key not found for id={id}"))
             })?;
         Ok(ekr)
     }
@@ -296,6 +309,7 @@ impl<
             .f
             .metastore
             .load_latest(&ik_id)
+            .code(ErrorCode::MetastoreUnavailable)
             .context(format!("encrypt: failed to load latest IK id={ik_id}"))?;
         let ik = match ik_ekr {
             Some(ekr) if !self.is_envelope_invalid(&ekr) => {
@@ -334,14 +348,15 @@ impl<
                 // T-finding "Legacy Session::encrypt doesn't reload
                 // load_latest on race-loss" in
                 // `docs/review-2026-05-05-findings.md`.
-                let stored =
-                    self.f
-                        .metastore
-                        .store(&ekr.id, ekr.created, &ekr)
-                        .context(format!(
-                            "encrypt: failed to store intermediate key id={}",
-                            ekr.id
-                        ))?;
+                let stored = self
+                    .f
+                    .metastore
+                    .store(&ekr.id, ekr.created, &ekr)
+                    .code(ErrorCode::MetastoreUnavailable)
+                    .context(format!(
+                        "encrypt: failed to store intermediate key id={}",
+                        ekr.id
+                    ))?;
                 if stored {
                     ik
                 } else {
@@ -353,6 +368,7 @@ impl<
                         .f
                         .metastore
                         .load_latest(&ekr.id)
+                        .code(ErrorCode::MetastoreUnavailable)
                         .context("encrypt: race-loss fallback load_latest failed")?
                         .ok_or_else(|| {
                             anyhow::anyhow!(
@@ -395,18 +411,18 @@ impl<
 
     pub fn decrypt(&self, drr: crate::types::DataRowRecord) -> anyhow::Result<Vec<u8>> {
         crate::limits::check_data_row_record(&drr)?;
-        let key = drr
-            .key
-            .ok_or_else(|| anyhow::anyhow!("decrypt: DRR missing key envelope"))?;
-        let pmeta = key
-            .parent_key_meta
-            .ok_or_else(|| anyhow::anyhow!("decrypt: DRR key missing parent_key_meta"))?;
+        let key = drr.key.ok_or_else(|| {
+            ErrorCode::InvalidDataRowRecord.error("decrypt: DRR missing key envelope")
+        })?;
+        let pmeta = key.parent_key_meta.ok_or_else(|| {
+            ErrorCode::InvalidDataRowRecord.error("decrypt: DRR key missing parent_key_meta")
+        })?;
         if !self.f.partition.is_valid_intermediate_key_id(&pmeta.id) {
-            return Err(anyhow::anyhow!(
+            return Err(ErrorCode::PartitionMismatch.error(format!(
                 "decrypt: invalid IK id={} for partition (expected {})",
                 pmeta.id,
                 self.f.partition.intermediate_key_id()
-            ));
+            )));
         }
         log::debug!(
             "decrypt: loading IK id={} created={}",
@@ -418,6 +434,7 @@ impl<
             .f
             .metastore
             .load(&pmeta.id, pmeta.created)
+            .code(ErrorCode::MetastoreUnavailable)
             .context(format!(
                 "decrypt: failed to load IK id={} created={}",
                 pmeta.id, pmeta.created
@@ -428,11 +445,10 @@ impl<
                     pmeta.id,
                     pmeta.created
                 );
-                anyhow::anyhow!(
+                ErrorCode::KeyNotFound.error(format!(
                     "decrypt: intermediate key not found id={} created={}",
-                    pmeta.id,
-                    pmeta.created
-                )
+                    pmeta.id, pmeta.created
+                ))
             })?;
         let sk = self.load_system_key(KeyMeta {
             id: self.f.partition.system_key_id(),
@@ -529,6 +545,24 @@ pub(crate) fn now_s() -> i64 {
     }
 }
 
+/// A snapshot of a factory's caches, from [`PublicFactory::cache_stats`].
+/// Values are racy under concurrent use: fit for monitoring, not for
+/// decisions.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Sessions in the session cache. `0` with session caching disabled.
+    pub sessions: usize,
+    /// `get_session` calls served from the session cache.
+    pub session_cache_hits: u64,
+    /// `get_session` calls that built a session despite session caching.
+    pub session_cache_misses: u64,
+    /// Entries in the shared intermediate key cache; see
+    /// [`PublicFactory::ik_cache_entry_count`].
+    pub intermediate_keys: usize,
+    /// Entries in the system key cache.
+    pub system_keys: usize,
+}
+
 // Public factory and session that mirror Go API surface (non-generic entrypoints)
 #[allow(missing_debug_implementations)]
 pub struct PublicFactory<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone> {
@@ -626,12 +660,69 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
             .map(|c| c.entry_count())
             .unwrap_or(0)
     }
-    pub fn get_session(&self, id: &str) -> PublicSession<A, K, M> {
+
+    /// Sizes and hit counts of this factory's caches.
+    pub fn cache_stats(&self) -> CacheStats {
+        let (sessions, session_cache_hits, session_cache_misses) = self
+            .session_cache
+            .as_ref()
+            .map_or((0, 0, 0), |c| (c.len(), c.hits(), c.misses()));
+        CacheStats {
+            sessions,
+            session_cache_hits,
+            session_cache_misses,
+            intermediate_keys: self.ik_cache_entry_count(),
+            system_keys: self.shared_sk_cache.entry_count(),
+        }
+    }
+
+    /// Drop `partition_id`'s cached session, so the next `get_session` for
+    /// it builds a fresh one. Sessions already handed out are unaffected.
+    /// Returns whether a session was cached.
+    pub fn evict_session(&self, partition_id: &str) -> bool {
+        self.session_cache
+            .as_ref()
+            .is_some_and(|c| c.remove(partition_id))
+    }
+
+    /// Empty the session cache and the shared system and intermediate key
+    /// caches, so every key is loaded from the metastore again — after a
+    /// key revocation, or to release memory. Sessions already handed out
+    /// keep a per-session intermediate key cache, when the IK cache isn't
+    /// shared, until they are closed.
+    pub fn flush_caches(&self) {
+        if let Some(c) = &self.session_cache {
+            c.clear();
+        }
+        if let Some(c) = &self.shared_ik_cache {
+            c.clear();
+        }
+        self.shared_sk_cache.clear();
+    }
+
+    /// Metastore shared by every session this factory hands out.
+    pub fn metastore(&self) -> &Arc<M> {
+        &self.metastore
+    }
+
+    /// KMS shared by every session this factory hands out.
+    pub fn kms(&self) -> &Arc<K> {
+        &self.kms
+    }
+
+    /// Metastore id of this factory's system key, including any region
+    /// suffix. Health probes use it to exercise the same `load_latest`
+    /// lookup the first encrypt on a fresh partition performs.
+    pub fn system_key_id(&self) -> String {
+        self.partition("").system_key_id()
+    }
+
+    fn partition(&self, id: &str) -> DefaultPartition {
         let mut suffix = self.metastore.region_suffix();
         if suffix.as_deref().unwrap_or("").is_empty() {
             suffix = self.cfg.region_suffix.clone();
         }
-        let part = match suffix {
+        match suffix {
             Some(s) if !s.is_empty() => DefaultPartition::new_suffixed(
                 id.to_string(),
                 self.cfg.service.clone(),
@@ -643,7 +734,11 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
                 self.cfg.service.clone(),
                 self.cfg.product.clone(),
             ),
-        };
+        }
+    }
+
+    pub fn get_session(&self, id: &str) -> PublicSession<A, K, M> {
+        let part = self.partition(id);
         let invalid_partition = id.is_empty();
         let construct = || {
             let inner = SessionFactory::new(
@@ -779,7 +874,7 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
     #[inline(always)]
     fn ensure_valid_partition(&self) -> anyhow::Result<()> {
         if self.invalid_partition {
-            return Err(anyhow::anyhow!("partition id cannot be empty"));
+            return Err(ErrorCode::InvalidArgument.error("partition id cannot be empty"));
         }
         Ok(())
     }
@@ -871,9 +966,14 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
                 "create_intermediate_key: store returned false, loading latest for id={ik_id}"
             );
             // Fallback: assume duplicate/newer IK exists; load latest and return that one.
-            if let Some(latest) = self.metastore.load_latest(&ik_id).context(format!(
-                "create_intermediate_key: fallback load_latest failed for id={ik_id}"
-            ))? {
+            if let Some(latest) = self
+                .metastore
+                .load_latest(&ik_id)
+                .code(ErrorCode::MetastoreUnavailable)
+                .context(format!(
+                    "create_intermediate_key: fallback load_latest failed for id={ik_id}"
+                ))?
+            {
                 if !self.inner.is_envelope_invalid(&latest) {
                     let sk_meta = latest.parent_key_meta.clone().unwrap_or(KeyMeta {
                         id: self.inner.f.partition.system_key_id(),
@@ -900,7 +1000,8 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
     fn load_latest_or_create_intermediate_key(&self) -> anyhow::Result<Arc<CryptoKey>> {
         if let Some(ekr) = self
             .metastore
-            .load_latest(&self.inner.f.partition.intermediate_key_id())?
+            .load_latest(&self.inner.f.partition.intermediate_key_id())
+            .code(ErrorCode::MetastoreUnavailable)?
         {
             if !self.inner.is_envelope_invalid(&ekr) {
                 // decrypt under SK
@@ -925,6 +1026,7 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
         let ekr = self
             .metastore
             .load(&meta.id, meta.created)
+            .code(ErrorCode::MetastoreUnavailable)
             .context(format!(
                 "failed to load intermediate key id={} created={}",
                 meta.id, meta.created
@@ -935,11 +1037,10 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
                     meta.id,
                     meta.created
                 );
-                anyhow::anyhow!(
+                ErrorCode::KeyNotFound.error(format!(
                     "intermediate key not found: id={} created={}",
-                    meta.id,
-                    meta.created
-                )
+                    meta.id, meta.created
+                ))
             })?;
         let sk_meta = ekr.parent_key_meta.clone().unwrap_or(KeyMeta {
             id: self.inner.f.partition.system_key_id(),
@@ -950,6 +1051,12 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
         Ok(Arc::new(ik))
     }
 
+    /// Id of the intermediate key this session's records are encrypted
+    /// under, as stored in each record's parent key metadata.
+    pub fn intermediate_key_id(&self) -> &str {
+        &self.cached_ik_id
+    }
+
     pub fn encrypt(&self, data: &[u8]) -> anyhow::Result<crate::types::DataRowRecord> {
         crate::limits::check_plaintext_len(data.len())?;
         self.ensure_valid_partition()?;
@@ -1461,23 +1568,22 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone>
         } else {
             None
         };
-        let key = drr
-            .key
-            .ok_or_else(|| anyhow::anyhow!("decrypt: DRR missing key envelope"))?;
-        let pmeta = key
-            .parent_key_meta
-            .ok_or_else(|| anyhow::anyhow!("decrypt: DRR key missing parent_key_meta"))?;
+        let key = drr.key.ok_or_else(|| {
+            ErrorCode::InvalidDataRowRecord.error("decrypt: DRR missing key envelope")
+        })?;
+        let pmeta = key.parent_key_meta.ok_or_else(|| {
+            ErrorCode::InvalidDataRowRecord.error("decrypt: DRR key missing parent_key_meta")
+        })?;
         // Fast path: the row's IK id matches this session's partition. Kept
         // inline and unchanged so the hot path pays no extra cost; any failure
         // here (validation reject, IK-not-found, or AEAD tag failure) drops to
         // the best-effort recovery path below.
         let fast: anyhow::Result<Vec<u8>> = (|| {
             if !self.ik_id_accepted_by_gate(&pmeta.id) {
-                return Err(anyhow::anyhow!(
+                return Err(ErrorCode::PartitionMismatch.error(format!(
                     "decrypt: invalid IK id={} for partition (session partition expected {})",
-                    pmeta.id,
-                    self.cached_ik_id
-                ));
+                    pmeta.id, self.cached_ik_id
+                )));
             }
             log::debug!(
                 "PublicSession::decrypt: loading IK id={} created={}",
@@ -1673,16 +1779,16 @@ impl<
             .metastore
             .load_async(&meta.id, meta.created)
             .await
+            .code(ErrorCode::MetastoreUnavailable)
             .context(format!(
                 "failed to load intermediate key id={} created={}",
                 meta.id, meta.created
             ))?
             .ok_or_else(|| {
-                anyhow::anyhow!(
+                ErrorCode::KeyNotFound.error(format!(
                     "intermediate key not found: id={} created={}",
-                    meta.id,
-                    meta.created
-                )
+                    meta.id, meta.created
+                ))
             })?;
         let sk_meta = ekr.parent_key_meta.clone().unwrap_or(KeyMeta {
             id: self.inner.f.partition.system_key_id(),
@@ -1697,7 +1803,8 @@ impl<
         if let Some(ekr) = self
             .metastore
             .load_latest_async(&self.inner.f.partition.intermediate_key_id())
-            .await?
+            .await
+            .code(ErrorCode::MetastoreUnavailable)?
         {
             if !self.inner.is_envelope_invalid(&ekr) {
                 let sk_meta = ekr.parent_key_meta.clone().unwrap_or(KeyMeta {
@@ -1756,13 +1863,14 @@ impl<
             log::debug!(
                 "create_intermediate_key_async: store returned false, loading latest for id={ik_id}"
             );
-            if let Some(latest) =
-                self.metastore
-                    .load_latest_async(&ik_id)
-                    .await
-                    .context(format!(
-                        "create_intermediate_key_async: fallback load_latest failed for id={ik_id}"
-                    ))?
+            if let Some(latest) = self
+                .metastore
+                .load_latest_async(&ik_id)
+                .await
+                .code(ErrorCode::MetastoreUnavailable)
+                .context(format!(
+                    "create_intermediate_key_async: fallback load_latest failed for id={ik_id}"
+                ))?
             {
                 if !self.inner.is_envelope_invalid(&latest) {
                     let sk_meta = latest.parent_key_meta.clone().unwrap_or(KeyMeta {
@@ -1880,21 +1988,20 @@ impl<
         } else {
             None
         };
-        let key = drr
-            .key
-            .ok_or_else(|| anyhow::anyhow!("decrypt_async: DRR missing key envelope"))?;
-        let pmeta = key
-            .parent_key_meta
-            .ok_or_else(|| anyhow::anyhow!("decrypt_async: DRR key missing parent_key_meta"))?;
+        let key = drr.key.ok_or_else(|| {
+            ErrorCode::InvalidDataRowRecord.error("decrypt_async: DRR missing key envelope")
+        })?;
+        let pmeta = key.parent_key_meta.ok_or_else(|| {
+            ErrorCode::InvalidDataRowRecord.error("decrypt_async: DRR key missing parent_key_meta")
+        })?;
         // Fast path (see sync `decrypt` for rationale). Any failure drops to the
         // best-effort recovery path below.
         let fast: anyhow::Result<Vec<u8>> = async {
             if !self.ik_id_accepted_by_gate(&pmeta.id) {
-                return Err(anyhow::anyhow!(
+                return Err(ErrorCode::PartitionMismatch.error(format!(
                     "decrypt_async: invalid IK id={} for partition (session partition expected {})",
-                    pmeta.id,
-                    self.cached_ik_id
-                ));
+                    pmeta.id, self.cached_ik_id
+                )));
             }
             log::debug!(
                 "PublicSession::decrypt_async: loading IK id={} created={}",
@@ -25,6 +25,8 @@ pub struct SessionCache<A: AEAD + Clone, K: KeyManagementService + Clone, M: Met
     policy: CachePolicy,
     access_ctr: AtomicU64,
     decay_ctr: AtomicU64,
+    hits: AtomicU64,
+    misses: AtomicU64,
 }
 
 impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone> SessionCache<A, K, M> {
@@ -40,6 +42,8 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone> Ses
             policy,
             access_ctr: AtomicU64::new(0),
             decay_ctr: AtomicU64::new(0),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
         }
     }
 
@@ -65,6 +69,7 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone> Ses
                 None
             }
         }) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
             if promote {
                 self.slru_rebalance();
             }
@@ -72,6 +77,7 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone> Ses
         }
 
         // Cache miss or expired — create new session
+        self.misses.fetch_add(1, Ordering::Relaxed);
         let s = Arc::new(create());
         let entry = SessionEntry {
             sess: s.clone(),
@@ -95,6 +101,35 @@ impl<A: AEAD + Clone, K: KeyManagementService + Clone, M: Metastore + Clone> Ses
         self.map.retain_sync(|_, _| false);
     }
 
+    /// Number of cached sessions, expired ones included until replaced.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Lookups served from the cache since it was created.
+    pub fn hits(&self) -> u64 {
+        self.hits.load(Ordering::Relaxed)
+    }
+
+    /// Lookups that built a new session: absent or expired.
+    pub fn misses(&self) -> u64 {
+        self.misses.load(Ordering::Relaxed)
+    }
+
+    /// Drop `id`'s session. Returns whether one was cached.
+    pub fn remove(&self, id: &str) -> bool {
+        self.map.remove_sync(id).is_some()
+    }
+
+    /// Drop every session. The hit and miss counts are kept.
+    pub fn clear(&self) {
+        self.map.clear_sync();
+    }
+
     fn next_access(&self) -> u64 {
         self.access_ctr.fetch_add(1, Ordering::Relaxed) + 1
     }
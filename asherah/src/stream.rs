@@ -0,0 +1,306 @@
+//! Chunked encryption for payloads too large to hold in memory at once.
+//!
+//! Each stream is sealed with its own random AES-256 key. That key is
+//! encrypted like any other payload, as a DataRowRecord under the session's
+//! intermediate key, and stored at the front of the stream:
+//!
+//! ```text
+//! "ASHS" | version (1 byte) | header length (u32 BE) | header (DataRowRecord JSON)
+//! frame*: ciphertext length (u32 BE) | last (1 byte) | ciphertext || tag || nonce
+//! ```
+//!
+//! Every frame holds one chunk of plaintext. Its associated data is the
+//! frame's index (u64 BE) and the `last` flag, so frames can't be reordered
+//! or dropped, and a stream cut off after any frame other than the last one
+//! fails to decrypt. The last frame may be empty.
+//!
+//! Neither side does any I/O: `update` and `finish` append whatever output
+//! is ready to a caller's buffer.
+
+use std::fmt;
+
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::aead::{fast_random_bytes, Aes256GcmKey, AES256GCM};
+use crate::error::ErrorCode;
+use crate::session::PublicSession;
+use crate::traits::{KeyManagementService, Metastore, AEAD};
+use crate::types::DataRowRecord;
+
+/// Plaintext bytes per frame unless the caller picks another size.
+pub const DEFAULT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Largest chunk a stream may use.
+pub const MAX_CHUNK_SIZE: usize = 16 * 1024 * 1024;
+
+const MAGIC: &[u8; 4] = b"ASHS";
+const VERSION: u8 = 1;
+/// Magic, version and header length.
+const PREAMBLE_LEN: usize = MAGIC.len() + 1 + 4;
+const MAX_HEADER_LEN: usize = 64 * 1024;
+/// Ciphertext length and `last` flag.
+const FRAME_PREFIX_LEN: usize = 4 + 1;
+const FRAME_OVERHEAD: usize = AES256GCM::NONCE_SIZE + AES256GCM::TAG_SIZE;
+const KEY_LEN: usize = 32;
+
+fn frame_aad(index: u64, last: bool) -> [u8; 9] {
+    let mut aad = [0_u8; 9];
+    aad[..8].copy_from_slice(&index.to_be_bytes());
+    aad[8] = u8::from(last);
+    aad
+}
+
+fn malformed(message: impl fmt::Display) -> anyhow::Error {
+    ErrorCode::InvalidDataRowRecord.error(format_args!("malformed stream: {message}"))
+}
+
+fn read_u32(bytes: &[u8]) -> usize {
+    let mut be = [0_u8; 4];
+    be.copy_from_slice(&bytes[..4]);
+    u32::from_be_bytes(be) as usize
+}
+
+/// Encrypts a stream chunk by chunk.
+pub struct StreamEncryptor {
+    key: Aes256GcmKey,
+    header: Option<Vec<u8>>,
+    chunk: Zeroizing<Vec<u8>>,
+    chunk_size: usize,
+    index: u64,
+    finished: bool,
+}
+
+impl fmt::Debug for StreamEncryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamEncryptor")
+            .field("chunk_size", &self.chunk_size)
+            .field("index", &self.index)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamEncryptor {
+    /// Start a stream with a new key, encrypted through `session`.
+    /// `chunk_size` is the plaintext in each frame, up to `MAX_CHUNK_SIZE`.
+    pub fn new<A, K, M>(session: &PublicSession<A, K, M>, chunk_size: usize) -> anyhow::Result<Self>
+    where
+        A: AEAD + Clone,
+        K: KeyManagementService + Clone,
+        M: Metastore + Clone,
+    {
+        if chunk_size == 0 || chunk_size > MAX_CHUNK_SIZE {
+            return Err(ErrorCode::InvalidArgument.error(format_args!(
+                "chunk size {chunk_size} must be between 1 and {MAX_CHUNK_SIZE}"
+            )));
+        }
+        let mut key = Zeroizing::new([0_u8; KEY_LEN]);
+        fast_random_bytes(key.as_mut())?;
+        let record = session.encrypt(key.as_ref())?.to_json_fast();
+
+        let mut header = Vec::with_capacity(PREAMBLE_LEN + record.len());
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.extend_from_slice(&(record.len() as u32).to_be_bytes());
+        header.extend_from_slice(record.as_bytes());
+        Ok(Self {
+            key: Aes256GcmKey::new(key.as_ref())?,
+            header: Some(header),
+            chunk: Zeroizing::new(Vec::with_capacity(chunk_size)),
+            chunk_size,
+            index: 0,
+            finished: false,
+        })
+    }
+
+    /// Encrypt `data`, appending any complete frames to `out`.
+    pub fn update(&mut self, mut data: &[u8], out: &mut Vec<u8>) -> anyhow::Result<()> {
+        self.start(out)?;
+        while !data.is_empty() {
+            // Whole chunks are sealed straight from `data`.
+            if self.chunk.is_empty() && data.len() >= self.chunk_size {
+                let (chunk, rest) = data.split_at(self.chunk_size);
+                self.seal(chunk, false, out)?;
+                data = rest;
+                continue;
+            }
+            let take = (self.chunk_size - self.chunk.len()).min(data.len());
+            self.chunk.extend_from_slice(&data[..take]);
+            data = &data[take..];
+            if self.chunk.len() == self.chunk_size {
+                self.seal_buffered(false, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Seal the last frame and append it to `out`. A stream that is never
+    /// finished fails to decrypt.
+    pub fn finish(&mut self, out: &mut Vec<u8>) -> anyhow::Result<()> {
+        self.start(out)?;
+        self.seal_buffered(true, out)?;
+        self.finished = true;
+        Ok(())
+    }
+
+    fn start(&mut self, out: &mut Vec<u8>) -> anyhow::Result<()> {
+        if self.finished {
+            return Err(ErrorCode::InvalidArgument.error("stream already finished"));
+        }
+        if let Some(header) = self.header.take() {
+            out.extend_from_slice(&header);
+        }
+        Ok(())
+    }
+
+    fn seal_buffered(&mut self, last: bool, out: &mut Vec<u8>) -> anyhow::Result<()> {
+        let chunk = std::mem::take(&mut *self.chunk);
+        let result = self.seal(&chunk, last, out);
+        *self.chunk = chunk;
+        self.chunk.zeroize();
+        result
+    }
+
+    fn seal(&mut self, chunk: &[u8], last: bool, out: &mut Vec<u8>) -> anyhow::Result<()> {
+        let sealed = self.key.encrypt(&frame_aad(self.index, last), chunk)?;
+        out.reserve(FRAME_PREFIX_LEN + sealed.len());
+        out.extend_from_slice(&(sealed.len() as u32).to_be_bytes());
+        out.push(u8::from(last));
+        out.extend_from_slice(&sealed);
+        self.index += 1;
+        Ok(())
+    }
+}
+
+/// Decrypts a stream written by [`StreamEncryptor`], in pieces of any size.
+#[derive(Default)]
+pub struct StreamDecryptor {
+    pending: Vec<u8>,
+    key: Option<Aes256GcmKey>,
+    index: u64,
+    finished: bool,
+}
+
+impl fmt::Debug for StreamDecryptor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("StreamDecryptor")
+            .field("pending", &self.pending.len())
+            .field("index", &self.index)
+            .field("finished", &self.finished)
+            .finish_non_exhaustive()
+    }
+}
+
+impl StreamDecryptor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Decrypt the next `data` bytes of the stream, appending any plaintext
+    /// to `out`. `session` decrypts the stream key once the header arrives.
+    pub fn update<A, K, M>(
+        &mut self,
+        session: &PublicSession<A, K, M>,
+        data: &[u8],
+        out: &mut Vec<u8>,
+    ) -> anyhow::Result<()>
+    where
+        A: AEAD + Clone,
+        K: KeyManagementService + Clone,
+        M: Metastore + Clone,
+    {
+        if self.finished {
+            if data.is_empty() {
+                return Ok(());
+            }
+            return Err(malformed("data after the last frame"));
+        }
+        self.pending.extend_from_slice(data);
+        let mut consumed = 0;
+        let result = self.drain(session, &mut consumed, out);
+        self.pending.drain(..consumed);
+        result
+    }
+
+    /// Check that the whole stream arrived.
+    pub fn finish(&self) -> anyhow::Result<()> {
+        if self.finished {
+            Ok(())
+        } else {
+            Err(malformed("truncated"))
+        }
+    }
+
+    fn drain<A, K, M>(
+        &mut self,
+        session: &PublicSession<A, K, M>,
+        consumed: &mut usize,
+        out: &mut Vec<u8>,
+    ) -> anyhow::Result<()>
+    where
+        A: AEAD + Clone,
+        K: KeyManagementService + Clone,
+        M: Metastore + Clone,
+    {
+        loop {
+            let rest = &self.pending[*consumed..];
+            let Some(key) = &self.key else {
+                if rest.len() < PREAMBLE_LEN {
+                    return Ok(());
+                }
+                if &rest[..MAGIC.len()] != MAGIC {
+                    return Err(malformed("not an Asherah stream"));
+                }
+                if rest[MAGIC.len()] != VERSION {
+                    return Err(malformed(format_args!(
+                        "unsupported version {}",
+                        rest[MAGIC.len()]
+                    )));
+                }
+                let len = read_u32(&rest[MAGIC.len() + 1..]);
+                if len > MAX_HEADER_LEN {
+                    return Err(malformed(format_args!("header length {len}")));
+                }
+                let Some(header) = rest.get(PREAMBLE_LEN..PREAMBLE_LEN + len) else {
+                    return Ok(());
+                };
+                let record: DataRowRecord = serde_json::from_slice(header)
+                    .map_err(|e| malformed(format_args!("invalid header: {e}")))?;
+                let stream_key = Zeroizing::new(session.decrypt(record)?);
+                if stream_key.len() != KEY_LEN {
+                    return Err(malformed("invalid stream key"));
+                }
+                self.key = Some(Aes256GcmKey::new(&stream_key)?);
+                *consumed += PREAMBLE_LEN + len;
+                continue;
+            };
+
+            if self.finished {
+                if rest.is_empty() {
+                    return Ok(());
+                }
+                return Err(malformed("data after the last frame"));
+            }
+            if rest.len() < FRAME_PREFIX_LEN {
+                return Ok(());
+            }
+            let len = read_u32(rest);
+            if !(FRAME_OVERHEAD..=MAX_CHUNK_SIZE + FRAME_OVERHEAD).contains(&len) {
+                return Err(malformed(format_args!("frame length {len}")));
+            }
+            let last = match rest[4] {
+                0 => false,
+                1 => true,
+                flag => return Err(malformed(format_args!("frame flag {flag}"))),
+            };
+            let Some(sealed) = rest.get(FRAME_PREFIX_LEN..FRAME_PREFIX_LEN + len) else {
+                return Ok(());
+            };
+            let chunk = Zeroizing::new(key.decrypt(&frame_aad(self.index, last), sealed)?);
+            out.extend_from_slice(&chunk);
+            self.index += 1;
+            self.finished = last;
+            *consumed += FRAME_PREFIX_LEN + len;
+        }
+    }
+}
@@ -51,6 +51,29 @@ pub trait Metastore: Send + Sync {
         None
     }
 
+    /// Load every stored version of `id`, oldest first.
+    ///
+    /// Only administrative operations (master-key rotation) use this; it is
+    /// never called on the encrypt/decrypt path. Backends that cannot
+    /// enumerate versions keep the default, which refuses.
+    fn load_all(&self, _id: &str) -> Result<Vec<EnvelopeKeyRecord>, anyhow::Error> {
+        anyhow::bail!("metastore does not support enumerating key records")
+    }
+
+    /// Overwrite an existing key record in place under the same id/created.
+    ///
+    /// Normal key records remain insert-if-absent; this hook exists only for
+    /// re-wrapping system keys under a new master key, where the plaintext key
+    /// (and therefore every IK that references it) is unchanged.
+    fn replace_key_record(
+        &self,
+        _id: &str,
+        _created: i64,
+        _ekr: &EnvelopeKeyRecord,
+    ) -> Result<(), anyhow::Error> {
+        anyhow::bail!("metastore does not support replacing key records")
+    }
+
     /// Async variant — defaults to calling the sync method.
     /// DynamoDB overrides this with native `.await`.
     async fn load_async(
@@ -328,7 +328,43 @@ pub(crate) mod serde_base64 {
     }
 }
 
+/// AES-256-GCM nonce and tag around every ciphertext.
+const AEAD_OVERHEAD: usize = crate::aead::AES256GCM::NONCE_SIZE + crate::aead::AES256GCM::TAG_SIZE;
+/// A data key is an AES-256 key, encrypted under the intermediate key.
+const ENCRYPTED_KEY_LEN: usize = 32 + AEAD_OVERHEAD;
+/// Longest decimal `i64`.
+const MAX_I64_DIGITS: usize = 20;
+/// Everything in a record's JSON except its variable parts.
+const JSON_FRAME: &str =
+    r#"{"Key":{"Created":,"Key":"","ParentKeyMeta":{"KeyId":"","Created":}},"Data":""}"#;
+
+/// Length of `id` once JSON-escaped the way `to_json_fast` escapes it.
+fn escaped_len(id: &str) -> usize {
+    id.bytes()
+        .map(|b| match b {
+            b'"' | b'\\' | b'\n' | b'\r' | b'\t' => 2,
+            0..=0x1f => 6,
+            _ => 1,
+        })
+        .sum()
+}
+
 impl DataRowRecord {
+    /// Upper bound on the `to_json_fast` length of a record for
+    /// `plaintext_len` bytes, encrypted under the intermediate key `ik_id`.
+    pub fn json_len_bound(ik_id: &str, plaintext_len: usize) -> usize {
+        JSON_FRAME.len()
+            + 2 * MAX_I64_DIGITS
+            + ENCRYPTED_KEY_LEN.div_ceil(3) * 4
+            + escaped_len(ik_id)
+            + (plaintext_len + AEAD_OVERHEAD).div_ceil(3) * 4
+    }
+
+    /// Size of the plaintext in this record, known before decrypting it.
+    pub fn plaintext_len(&self) -> usize {
+        self.data.len().saturating_sub(AEAD_OVERHEAD)
+    }
+
     /// Hand-written JSON serializer — avoids serde overhead and intermediate allocations.
     #[inline]
     pub fn to_json_fast(&self) -> String {
@@ -0,0 +1,190 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! Session failures carry the `ErrorCode` an FFI caller branches on.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use asherah as ael;
+use asherah::error::{ErrorCategory, ErrorCode};
+use asherah::metastore::InMemoryMetastore;
+use asherah::traits::{KeyManagementService, Metastore};
+use asherah::types::{DataRowRecord, EnvelopeKeyRecord};
+
+#[derive(Clone, Default)]
+struct Switchable {
+    down: Arc<AtomicBool>,
+}
+
+impl Switchable {
+    fn check(&self, what: &str) -> anyhow::Result<()> {
+        if self.down.load(Ordering::SeqCst) {
+            anyhow::bail!("{what} unreachable");
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone)]
+struct FlakyMetastore {
+    inner: InMemoryMetastore,
+    switch: Switchable,
+}
+
+impl Metastore for FlakyMetastore {
+    fn load(&self, id: &str, created: i64) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        self.switch.check("metastore")?;
+        self.inner.load(id, created)
+    }
+
+    fn load_latest(&self, id: &str) -> anyhow::Result<Option<EnvelopeKeyRecord>> {
+        self.switch.check("metastore")?;
+        self.inner.load_latest(id)
+    }
+
+    fn store(&self, id: &str, created: i64, ekr: &EnvelopeKeyRecord) -> anyhow::Result<bool> {
+        self.switch.check("metastore")?;
+        self.inner.store(id, created, ekr)
+    }
+}
+
+#[derive(Clone)]
+struct FlakyKms {
+    inner: Arc<ael::kms::StaticKMS<ael::aead::AES256GCM>>,
+    switch: Switchable,
+}
+
+impl KeyManagementService for FlakyKms {
+    fn encrypt_key(&self, ctx: &(), key: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.switch.check("kms")?;
+        self.inner.encrypt_key(ctx, key)
+    }
+
+    fn decrypt_key(&self, ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.switch.check("kms")?;
+        self.inner.decrypt_key(ctx, blob)
+    }
+}
+
+struct Harness {
+    factory: ael::session::PublicFactory<ael::aead::AES256GCM, FlakyKms, FlakyMetastore>,
+    metastore: Switchable,
+    kms: Switchable,
+}
+
+fn harness() -> Harness {
+    let crypto = Arc::new(ael::aead::AES256GCM::new());
+    let metastore = Switchable::default();
+    let kms = Switchable::default();
+    let factory = ael::api::new_session_factory(
+        ael::Config::new("svc", "prod"),
+        Arc::new(FlakyMetastore {
+            inner: InMemoryMetastore::new(),
+            switch: metastore.clone(),
+        }),
+        Arc::new(FlakyKms {
+            inner: Arc::new(ael::kms::StaticKMS::new(crypto.clone(), vec![7_u8; 32]).unwrap()),
+            switch: kms.clone(),
+        }),
+        crypto,
+    );
+    Harness {
+        factory,
+        metastore,
+        kms,
+    }
+}
+
+fn code<T: std::fmt::Debug>(result: anyhow::Result<T>) -> ErrorCode {
+    ErrorCode::of(&result.unwrap_err())
+}
+
+#[test]
+fn input_errors() {
+    let h = harness();
+    let session = h.factory.get_session("p1");
+    let drr = session.encrypt(b"payload").unwrap();
+
+    assert_eq!(
+        code(h.factory.get_session("").encrypt(b"x")),
+        ErrorCode::InvalidArgument
+    );
+    assert_eq!(
+        code(session.decrypt(DataRowRecord {
+            key: None,
+            data: drr.data.clone(),
+        })),
+        ErrorCode::InvalidDataRowRecord
+    );
+    assert_eq!(
+        code(h.factory.get_session("p2").decrypt(drr.clone())),
+        ErrorCode::PartitionMismatch
+    );
+    assert_eq!(
+        code(session.encrypt(&vec![0_u8; ael::limits::MAX_PAYLOAD_BYTES + 1])),
+        ErrorCode::PayloadTooLarge
+    );
+
+    let mut tampered = drr;
+    let last = tampered.data.len() - 1;
+    tampered.data[last] ^= 1;
+    let err = session.decrypt(tampered).unwrap_err();
+    assert_eq!(ErrorCode::of(&err), ErrorCode::DecryptionFailed);
+    assert_eq!(ErrorCode::of(&err).category(), ErrorCategory::Crypto);
+    assert!(!ErrorCode::of(&err).is_retryable());
+}
+
+#[test]
+fn missing_keys_are_key_not_found() {
+    let writer = harness();
+    let drr = writer
+        .factory
+        .get_session("p1")
+        .encrypt(b"payload")
+        .unwrap();
+
+    // Same master key, empty metastore: the row's IK is nowhere to be found.
+    let reader = harness();
+    let err = reader.factory.get_session("p1").decrypt(drr).unwrap_err();
+    assert_eq!(ErrorCode::of(&err), ErrorCode::KeyNotFound);
+}
+
+#[test]
+fn backend_outages_are_retryable() {
+    let h = harness();
+    h.metastore.down.store(true, Ordering::SeqCst);
+    let err = h.factory.get_session("p1").encrypt(b"x").unwrap_err();
+    assert_eq!(ErrorCode::of(&err), ErrorCode::MetastoreUnavailable);
+    assert!(ErrorCode::of(&err).is_retryable());
+    // The message is what it always was.
+    assert!(!err.to_string().contains("METASTORE"), "{err}");
+    h.metastore.down.store(false, Ordering::SeqCst);
+
+    h.kms.down.store(true, Ordering::SeqCst);
+    let err = h.factory.get_session("p2").encrypt(b"x").unwrap_err();
+    assert_eq!(ErrorCode::of(&err), ErrorCode::KmsUnavailable);
+    assert_eq!(ErrorCode::of(&err).category(), ErrorCategory::Kms);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn async_paths_carry_the_same_codes() {
+    let h = harness();
+    let session = h.factory.get_session("p1");
+    let drr = session.encrypt_async(b"payload").await.unwrap();
+
+    let mut tampered = drr.clone();
+    tampered.data[0] ^= 1;
+    assert_eq!(
+        code(session.decrypt_async(tampered).await),
+        ErrorCode::DecryptionFailed
+    );
+    assert_eq!(
+        code(h.factory.get_session("p2").decrypt_async(drr).await),
+        ErrorCode::PartitionMismatch
+    );
+
+    h.metastore.down.store(true, Ordering::SeqCst);
+    assert_eq!(
+        code(h.factory.get_session("p3").encrypt_async(b"x").await),
+        ErrorCode::MetastoreUnavailable
+    );
+}
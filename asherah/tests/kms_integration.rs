@@ -705,6 +705,57 @@ mod secrets_manager_tests {
         .unwrap();
     }
 
+    // ── Secret rotation ──
+
+    #[tokio::test]
+    async fn test_secrets_manager_survives_rotation() {
+        let endpoint = match shared_localstack().await {
+            Some(v) => v,
+            None => return,
+        };
+
+        let (secret_id, _key) = create_hex_secret(&endpoint, "test/rotating-key").await;
+
+        let endpoint_clone = endpoint.clone();
+        let sid = secret_id.clone();
+        let before = tokio::task::spawn_blocking(move || {
+            let kms = make_sm_kms(&endpoint_clone, &sid);
+            let blob = kms.encrypt_key(&(), b"wrapped before rotation").unwrap();
+            (kms, blob)
+        })
+        .await
+        .unwrap();
+
+        let rotated: String = (0..32_u8).map(|b| format!("{:02x}", b ^ 0x5A)).collect();
+        sm_client(&endpoint)
+            .await
+            .put_secret_value()
+            .secret_id(&secret_id)
+            .secret_string(rotated)
+            .send()
+            .await
+            .unwrap();
+
+        let endpoint_clone = endpoint.clone();
+        tokio::task::spawn_blocking(move || {
+            let (old_kms, old_blob) = before;
+            let new_kms = make_sm_kms(&endpoint_clone, &secret_id);
+            assert_ne!(new_kms.current_version_id(), old_kms.current_version_id());
+
+            // New process: AWSPREVIOUS opens the pre-rotation blob.
+            let decrypted = new_kms.decrypt_key(&(), &old_blob).unwrap();
+            assert_eq!(decrypted, b"wrapped before rotation");
+
+            // Old process: the unknown version in the new blob triggers a
+            // re-fetch.
+            let new_blob = new_kms.encrypt_key(&(), b"wrapped after rotation").unwrap();
+            let decrypted = old_kms.decrypt_key(&(), &new_blob).unwrap();
+            assert_eq!(decrypted, b"wrapped after rotation");
+        })
+        .await
+        .unwrap();
+    }
+
     // ── Wire compatibility with StaticKMS ──
 
     #[tokio::test]
@@ -741,9 +792,15 @@ mod secrets_manager_tests {
             let decrypted = sm_kms.decrypt_key(&(), &encrypted).unwrap();
             assert_eq!(decrypted, original);
 
-            // And the reverse: encrypt with SM, decrypt with Static
+            // And the reverse: SM prefixes its blobs with the secret's
+            // VersionId; the AEAD payload behind the header is exactly what
+            // StaticKMS produces.
             let encrypted2 = sm_kms.encrypt_key(&(), original).unwrap();
-            let decrypted2 = static_kms.decrypt_key(&(), &encrypted2).unwrap();
+            let (version_id, inner) =
+                asherah::kms_secrets_manager::split_versioned_blob(&encrypted2)
+                    .expect("SM blobs carry a version header");
+            assert_eq!(version_id, sm_kms.current_version_id());
+            let decrypted2 = static_kms.decrypt_key(&(), inner).unwrap();
             assert_eq!(decrypted2, original);
         })
         .await
@@ -0,0 +1,152 @@
+//! Re-wrapping stored system keys under a new master key.
+//!
+//! `master_key_rotation::rewrap_system_keys` replaces each SK record in
+//! place with the same key bytes wrapped by the new KMS. Rows written before
+//! the rotation must then decrypt through a factory that only knows the new
+//! master key, and a repeated run must be a no-op.
+
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+
+use std::sync::Arc;
+
+use asherah as ael;
+use asherah::aead::AES256GCM;
+use asherah::builders::DynKms;
+use asherah::kms::StaticKMS;
+use asherah::kms_multi::MultiKms;
+use asherah::master_key_rotation::{rewrap_system_keys, system_key_ids, RewrapReport};
+use asherah::metastore::InMemoryMetastore;
+use asherah::traits::{KeyManagementService, Metastore};
+
+const SERVICE: &str = "mkr-svc";
+const PRODUCT: &str = "mkr-prod";
+
+fn static_kms(byte: u8) -> Arc<StaticKMS<AES256GCM>> {
+    Arc::new(StaticKMS::new(Arc::new(AES256GCM::new()), vec![byte; 32]).unwrap())
+}
+
+fn make_factory<K: KeyManagementService + Clone>(
+    store: Arc<InMemoryMetastore>,
+    kms: Arc<K>,
+) -> ael::SessionFactory<AES256GCM, K, InMemoryMetastore> {
+    let mut cfg = ael::Config::new(SERVICE, PRODUCT);
+    cfg.policy.create_date_precision_s = 1;
+    cfg.policy.cache_sessions = false;
+    ael::api::new_session_factory(cfg, store, kms, Arc::new(AES256GCM::new()))
+}
+
+#[test]
+fn rewrapped_system_keys_decrypt_under_new_master_key_only() {
+    let store = Arc::new(InMemoryMetastore::new());
+    let old_kms = static_kms(0x11);
+    let new_kms = static_kms(0x22);
+
+    let drr = {
+        let factory = make_factory(store.clone(), old_kms.clone());
+        factory
+            .get_session("p1")
+            .encrypt(b"before rotation")
+            .unwrap()
+    };
+
+    let ids = system_key_ids(SERVICE, PRODUCT, std::iter::empty::<&str>());
+    let report = rewrap_system_keys(store.as_ref(), old_kms.as_ref(), new_kms.as_ref(), &ids)
+        .expect("rewrap must succeed");
+    assert_eq!(
+        report,
+        RewrapReport {
+            scanned: 1,
+            rewrapped: 1,
+            already_current: 0,
+        }
+    );
+
+    let factory = make_factory(store.clone(), new_kms.clone());
+    let pt = factory.get_session("p1").decrypt(drr.clone()).unwrap();
+    assert_eq!(pt, b"before rotation");
+
+    let stale = make_factory(store, old_kms);
+    assert!(
+        stale.get_session("p1").decrypt(drr).is_err(),
+        "old master key must no longer unwrap the re-wrapped SK"
+    );
+}
+
+#[test]
+fn rewrap_is_idempotent_and_preserves_record_identity() {
+    let store = Arc::new(InMemoryMetastore::new());
+    let old_kms = static_kms(0x33);
+    let new_kms = static_kms(0x44);
+    make_factory(store.clone(), old_kms.clone())
+        .get_session("p1")
+        .encrypt(b"x")
+        .unwrap();
+
+    let ids = system_key_ids(SERVICE, PRODUCT, std::iter::empty::<&str>());
+    let before = store.load_all(&ids[0]).unwrap();
+    rewrap_system_keys(store.as_ref(), old_kms.as_ref(), new_kms.as_ref(), &ids).unwrap();
+    let after = store.load_all(&ids[0]).unwrap();
+    assert_eq!(before.len(), after.len());
+    assert_eq!(before[0].created, after[0].created);
+    assert_eq!(before[0].revoked, after[0].revoked);
+    assert_ne!(before[0].encrypted_key, after[0].encrypted_key);
+
+    let again =
+        rewrap_system_keys(store.as_ref(), old_kms.as_ref(), new_kms.as_ref(), &ids).unwrap();
+    assert_eq!(again.rewrapped, 0);
+    assert_eq!(again.already_current, 1);
+}
+
+#[test]
+fn rewrap_fails_when_old_master_key_is_wrong() {
+    let store = Arc::new(InMemoryMetastore::new());
+    make_factory(store.clone(), static_kms(0x55))
+        .get_session("p1")
+        .encrypt(b"x")
+        .unwrap();
+
+    let ids = system_key_ids(SERVICE, PRODUCT, std::iter::empty::<&str>());
+    let err = rewrap_system_keys(
+        store.as_ref(),
+        static_kms(0x66).as_ref(),
+        static_kms(0x77).as_ref(),
+        &ids,
+    )
+    .unwrap_err();
+    assert!(format!("{err:#}").contains("old KMS failed to decrypt system key"));
+}
+
+#[test]
+fn dual_kms_window_decrypts_both_generations() {
+    let store = Arc::new(InMemoryMetastore::new());
+    let old_kms = static_kms(0x88);
+    let new_kms = static_kms(0x99);
+
+    let drr_old = make_factory(store.clone(), old_kms.clone())
+        .get_session("p-old")
+        .encrypt(b"old")
+        .unwrap();
+
+    let backends: Vec<Arc<dyn KeyManagementService>> = vec![new_kms.clone(), old_kms.clone()];
+    let window: Arc<dyn KeyManagementService> = Arc::new(MultiKms::new(0, backends).unwrap());
+    let factory = make_factory(store.clone(), Arc::new(DynKms(window)));
+    let session = factory.get_session("p-old");
+    assert_eq!(session.decrypt(drr_old).unwrap(), b"old");
+}
+
+#[test]
+fn system_key_ids_cover_region_suffixes_once() {
+    let ids = system_key_ids(
+        SERVICE,
+        PRODUCT,
+        ["us-west-2", "", "us-west-2", "eu-west-1"],
+    );
+    assert_eq!(
+        ids,
+        vec![
+            format!("_SK_{SERVICE}_{PRODUCT}"),
+            format!("_SK_{SERVICE}_{PRODUCT}_us-west-2"),
+            format!("_SK_{SERVICE}_{PRODUCT}_eu-west-1"),
+        ]
+    );
+}
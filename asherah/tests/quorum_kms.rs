@@ -0,0 +1,173 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+//! k-of-n quorum KMS: shares split across member backends, recovery with a
+//! member down or lying, and the factory path through `KmsConfig::Quorum`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use asherah::aead::AES256GCM;
+use asherah::builders::{
+    factory_from_resolved, KmsConfig, MetastoreConfig, PolicyConfig, QuorumMember, ResolvedConfig,
+};
+use asherah::kms::StaticKMS;
+use asherah::kms_quorum::QuorumKms;
+use asherah::traits::KeyManagementService;
+use async_trait::async_trait;
+
+/// Wraps a real KMS; can be switched to fail or to return a forged share.
+struct Switchable {
+    inner: StaticKMS<AES256GCM>,
+    down: AtomicBool,
+    lying: AtomicBool,
+}
+
+impl Switchable {
+    fn new(fill: u8) -> Arc<Self> {
+        Arc::new(Self {
+            inner: StaticKMS::new(Arc::new(AES256GCM::new()), vec![fill; 32]).unwrap(),
+            down: AtomicBool::new(false),
+            lying: AtomicBool::new(false),
+        })
+    }
+}
+
+#[async_trait]
+impl KeyManagementService for Switchable {
+    fn encrypt_key(&self, ctx: &(), key_bytes: &[u8]) -> anyhow::Result<Vec<u8>> {
+        self.inner.encrypt_key(ctx, key_bytes)
+    }
+
+    fn decrypt_key(&self, ctx: &(), blob: &[u8]) -> anyhow::Result<Vec<u8>> {
+        if self.down.load(Ordering::SeqCst) {
+            anyhow::bail!("ServiceUnavailable");
+        }
+        let mut share = self.inner.decrypt_key(ctx, blob)?;
+        if self.lying.load(Ordering::SeqCst) {
+            share[0] ^= 0xff;
+        }
+        Ok(share)
+    }
+}
+
+fn quorum(threshold: usize, members: &[(&str, &Arc<Switchable>)]) -> QuorumKms<AES256GCM> {
+    QuorumKms::new(
+        Arc::new(AES256GCM::new()),
+        threshold,
+        members
+            .iter()
+            .map(|(name, kms)| {
+                let kms: Arc<dyn KeyManagementService> = Arc::<Switchable>::clone(kms);
+                ((*name).to_string(), kms)
+            })
+            .collect(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn two_of_three_survives_one_member_down() {
+    let (aws, vault, sm) = (Switchable::new(1), Switchable::new(2), Switchable::new(3));
+    let kms = quorum(2, &[("aws", &aws), ("vault", &vault), ("sm", &sm)]);
+    let blob = kms.encrypt_key(&(), b"system key material").unwrap();
+
+    aws.down.store(true, Ordering::SeqCst);
+    assert_eq!(kms.decrypt_key(&(), &blob).unwrap(), b"system key material");
+
+    vault.down.store(true, Ordering::SeqCst);
+    let err = kms.decrypt_key(&(), &blob).unwrap_err();
+    assert!(
+        format!("{err:#}").contains("1 share(s) recovered, 2 required"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn a_single_member_cannot_unwrap() {
+    let (a, b) = (Switchable::new(1), Switchable::new(2));
+    let blob = quorum(2, &[("a", &a), ("b", &b)])
+        .encrypt_key(&(), b"needs both")
+        .unwrap();
+
+    // A deployment holding only one member's KMS learns nothing.
+    let only_a = quorum(1, &[("a", &a)]);
+    assert!(only_a.decrypt_key(&(), &blob).is_err());
+}
+
+#[test]
+fn forged_share_is_detected_and_bypassed() {
+    let (a, b, c) = (Switchable::new(1), Switchable::new(2), Switchable::new(3));
+    let kms = quorum(2, &[("a", &a), ("b", &b), ("c", &c)]);
+    let blob = kms.encrypt_key(&(), b"tamper evident").unwrap();
+
+    a.lying.store(true, Ordering::SeqCst);
+    assert_eq!(kms.decrypt_key(&(), &blob).unwrap(), b"tamper evident");
+}
+
+#[test]
+fn shares_are_matched_by_member_name_not_position() {
+    let (a, b, c) = (Switchable::new(1), Switchable::new(2), Switchable::new(3));
+    let blob = quorum(3, &[("a", &a), ("b", &b), ("c", &c)])
+        .encrypt_key(&(), b"order independent")
+        .unwrap();
+
+    let reordered = quorum(3, &[("c", &c), ("a", &a), ("b", &b)]);
+    assert_eq!(
+        reordered.decrypt_key(&(), &blob).unwrap(),
+        b"order independent"
+    );
+}
+
+#[test]
+fn invalid_policies_are_rejected() {
+    let a = Switchable::new(1);
+    let aead = Arc::new(AES256GCM::new());
+    let member = |name: &str| {
+        let kms: Arc<dyn KeyManagementService> = Arc::<Switchable>::clone(&a);
+        (name.to_string(), kms)
+    };
+    assert!(QuorumKms::new(aead.clone(), 0, vec![member("a")]).is_err());
+    assert!(QuorumKms::new(aead.clone(), 2, vec![member("a")]).is_err());
+    assert!(QuorumKms::new(aead, 1, vec![member("a"), member("a")]).is_err());
+}
+
+#[tokio::test]
+async fn async_paths_round_trip() {
+    let (a, b, c) = (Switchable::new(1), Switchable::new(2), Switchable::new(3));
+    let kms = quorum(2, &[("a", &a), ("b", &b), ("c", &c)]);
+    let blob = kms.encrypt_key_async(&(), b"async").await.unwrap();
+    c.down.store(true, Ordering::SeqCst);
+    assert_eq!(kms.decrypt_key_async(&(), &blob).await.unwrap(), b"async");
+}
+
+#[test]
+fn factory_round_trip_through_quorum_config() {
+    let member = |name: &str, fill: &str| QuorumMember {
+        name: name.to_string(),
+        kms: KmsConfig::Static {
+            key_hex: fill.repeat(32),
+        },
+    };
+    let cfg = ResolvedConfig {
+        service_name: "svc".into(),
+        product_id: "prod".into(),
+        region_suffix: None,
+        recovery_region_suffixes: Vec::new(),
+        self_heal_recovered_keys: true,
+        aws_profile_name: None,
+        metastore: MetastoreConfig::Memory,
+        kms: KmsConfig::Quorum {
+            threshold: 2,
+            members: vec![
+                member("one", "11"),
+                member("two", "22"),
+                member("three", "33"),
+            ],
+        },
+        previous_kms: None,
+        policy: PolicyConfig::default(),
+    };
+    let factory = factory_from_resolved(&cfg).unwrap();
+    let session = factory.get_session("quorum-partition");
+    let drr = session.encrypt(b"payload").unwrap();
+    assert_eq!(session.decrypt(drr).unwrap(), b"payload");
+}
@@ -9,7 +9,7 @@ use asherah::aead::AES256GCM;
 use asherah::cache::CachePolicy;
 use asherah::kms::StaticKMS;
 use asherah::metastore::InMemoryMetastore;
-use asherah::session::PublicFactory;
+use asherah::session::{CacheStats, PublicFactory};
 use asherah::session_cache::SessionCache;
 use asherah::Config;
 
@@ -286,6 +286,37 @@ fn close_clears_cache() {
     assert!(!Arc::ptr_eq(&s1, &s2));
 }
 
+#[test]
+fn factory_cache_stats_evict_and_flush() {
+    let factory = make_factory();
+    assert_eq!(factory.cache_stats(), CacheStats::default());
+
+    let drr = factory.get_session("p1").encrypt(b"secret").unwrap();
+    let _ = factory.get_session("p1");
+    let _ = factory.get_session("p2");
+    let stats = factory.cache_stats();
+    assert_eq!(stats.sessions, 2);
+    assert_eq!(stats.session_cache_hits, 1);
+    assert_eq!(stats.session_cache_misses, 2);
+    assert_eq!(stats.intermediate_keys, 1);
+    assert_eq!(stats.system_keys, 1);
+
+    assert!(factory.evict_session("p1"));
+    assert!(!factory.evict_session("p1"));
+    assert_eq!(factory.cache_stats().sessions, 1);
+
+    factory.flush_caches();
+    let stats = factory.cache_stats();
+    assert_eq!(stats.sessions, 0);
+    assert_eq!(stats.intermediate_keys, 0);
+    assert_eq!(stats.system_keys, 0);
+    assert_eq!(stats.session_cache_hits, 1);
+
+    // Keys come back from the metastore.
+    assert_eq!(factory.get_session("p1").decrypt(drr).unwrap(), b"secret");
+    assert_eq!(factory.cache_stats().intermediate_keys, 1);
+}
+
 #[test]
 fn close_multiple_times_is_safe() {
     let factory = make_factory();
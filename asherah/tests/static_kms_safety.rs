@@ -18,6 +18,7 @@ fn resolved_with_kms(kms: KmsConfig) -> ResolvedConfig {
         aws_profile_name: None,
         metastore: MetastoreConfig::Memory,
         kms,
+        previous_kms: None,
         policy: PolicyConfig::default(),
     }
 }
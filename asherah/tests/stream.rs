@@ -0,0 +1,102 @@
+#![allow(clippy::unwrap_used, clippy::expect_used)]
+use std::sync::Arc;
+
+use ael::error::ErrorCode;
+use ael::stream::{StreamDecryptor, StreamEncryptor, DEFAULT_CHUNK_SIZE};
+use asherah as ael;
+
+type Kms = ael::kms::StaticKMS<ael::aead::AES256GCM>;
+type Session = ael::Session<ael::aead::AES256GCM, Kms, ael::metastore::InMemoryMetastore>;
+
+fn factory() -> ael::SessionFactory<ael::aead::AES256GCM, Kms, ael::metastore::InMemoryMetastore> {
+    let crypto = Arc::new(ael::aead::AES256GCM::new());
+    let kms = Arc::new(ael::kms::StaticKMS::new(crypto.clone(), vec![7_u8; 32]).unwrap());
+    let metastore = Arc::new(ael::metastore::InMemoryMetastore::new());
+    ael::api::new_session_factory(ael::Config::new("svc", "prod"), metastore, kms, crypto)
+}
+
+fn encrypt(session: &Session, plaintext: &[u8], chunk_size: usize, piece: usize) -> Vec<u8> {
+    let mut encryptor = StreamEncryptor::new(session, chunk_size).unwrap();
+    let mut out = Vec::new();
+    for part in plaintext.chunks(piece.max(1)) {
+        encryptor.update(part, &mut out).unwrap();
+    }
+    encryptor.finish(&mut out).unwrap();
+    out
+}
+
+fn decrypt(session: &Session, stream: &[u8], piece: usize) -> anyhow::Result<Vec<u8>> {
+    let mut decryptor = StreamDecryptor::new();
+    let mut out = Vec::new();
+    for part in stream.chunks(piece) {
+        decryptor.update(session, part, &mut out)?;
+    }
+    decryptor.finish()?;
+    Ok(out)
+}
+
+#[test]
+fn round_trips_across_chunk_and_piece_sizes() {
+    let factory = factory();
+    let session = factory.get_session("p1");
+    let plaintext: Vec<u8> = (0..100_000_u32).map(|i| (i % 251) as u8).collect();
+    for (chunk_size, piece) in [
+        (DEFAULT_CHUNK_SIZE, 100_000),
+        (1000, 333),
+        (4096, 4096),
+        (7, 1),
+    ] {
+        let stream = encrypt(&session, &plaintext, chunk_size, piece);
+        for read_piece in [1, 17, 4096, stream.len()] {
+            assert_eq!(decrypt(&session, &stream, read_piece).unwrap(), plaintext);
+        }
+    }
+    let empty = encrypt(&session, &[], 16, 1);
+    assert!(decrypt(&session, &empty, 5).unwrap().is_empty());
+}
+
+#[test]
+fn tampered_and_truncated_streams_fail() {
+    let factory = factory();
+    let session = factory.get_session("p1");
+    let plaintext = vec![9_u8; 10_000];
+    let stream = encrypt(&session, &plaintext, 1000, 10_000);
+
+    // Cut off at a frame boundary: every frame authenticates, but the last
+    // one never arrives.
+    let frame = 4 + 1 + 1000 + 28;
+    let cut = &stream[..stream.len() - (4 + 1 + 28) - frame];
+    let err = decrypt(&session, cut, 64).unwrap_err();
+    assert_eq!(ErrorCode::of(&err), ErrorCode::InvalidDataRowRecord);
+
+    let mut flipped = stream.clone();
+    let last = flipped.len() - 40;
+    flipped[last] ^= 1;
+    let err = decrypt(&session, &flipped, 64).unwrap_err();
+    assert_eq!(ErrorCode::of(&err), ErrorCode::DecryptionFailed);
+
+    let mut trailing = stream.clone();
+    trailing.push(0);
+    assert!(decrypt(&session, &trailing, 64).is_err());
+
+    assert!(decrypt(&session, b"not a stream at all", 64).is_err());
+
+    // The stream key is bound to the partition it was written under.
+    let other = factory.get_session("p2");
+    assert!(decrypt(&other, &stream, 64).is_err());
+}
+
+#[test]
+fn rejects_bad_chunk_sizes_and_use_after_finish() {
+    let factory = factory();
+    let session = factory.get_session("p1");
+    for size in [0, ael::stream::MAX_CHUNK_SIZE + 1] {
+        let err = StreamEncryptor::new(&session, size).unwrap_err();
+        assert_eq!(ErrorCode::of(&err), ErrorCode::InvalidArgument);
+    }
+    let mut encryptor = StreamEncryptor::new(&session, 16).unwrap();
+    let mut out = Vec::new();
+    encryptor.finish(&mut out).unwrap();
+    assert!(encryptor.update(b"late", &mut out).is_err());
+    assert!(encryptor.finish(&mut out).is_err());
+}
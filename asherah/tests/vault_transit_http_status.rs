@@ -28,8 +28,15 @@ use asherah::traits::KeyManagementService;
 /// Serialize tests because they mutate the `VAULT_TOKEN` env var.
 static ENV_MUTEX: Mutex<()> = Mutex::new(());
 
+const LOOKUP_DENIED: &str = "HTTP/1.1 403 Forbidden\r\n\
+     Content-Length: 0\r\n\
+     Connection: close\r\n\
+     \r\n";
+
 /// Spawn a one-shot HTTP responder on a random localhost port.
-/// Reads (and discards) one request, writes one response, exits.
+/// Reads (and discards) one request, writes one response, exits. The
+/// constructor's token `lookup-self` probe is refused first (the KMS then
+/// treats the token as non-expiring) and does not count as the one shot.
 fn spawn_one_shot_responder(
     status_line: &'static str,
     content_type: &'static str,
@@ -48,13 +55,18 @@ fn spawn_one_shot_responder(
         len = body.len(),
     );
     let h = thread::spawn(move || {
-        if let Ok((mut stream, _)) = listener.accept() {
-            // Drain the request — we don't care about its content.
+        while let Ok((mut stream, _)) = listener.accept() {
+            // Drain the request — we only look at the request line.
             drop(stream.set_read_timeout(Some(std::time::Duration::from_secs(2))));
             let mut buf = [0_u8; 4096];
-            drop(stream.read(&mut buf));
+            let n = stream.read(&mut buf).unwrap_or(0);
+            if String::from_utf8_lossy(&buf[..n]).contains("/auth/token/lookup-self") {
+                drop(stream.write_all(LOOKUP_DENIED.as_bytes()));
+                continue;
+            }
             drop(stream.write_all(response.as_bytes()));
             drop(stream.flush());
+            break;
         }
     });
     (url, h)
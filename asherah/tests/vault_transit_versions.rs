@@ -0,0 +1,357 @@
+//! Vault Transit key versioning, batch endpoints and token lifecycle against
+//! a scripted in-process Vault. Each test routes requests by method + path
+//! and records what the KMS sent, so request bodies (`batch_input`,
+//! `key_version`, `secret_id`) and background renew/login traffic can be
+//! asserted without a real Vault.
+//!
+//! Gated behind the `vault` feature because `kms_vault_transit` is an
+//! optional module. Run with:
+//!   cargo test -p asherah --features vault --test vault_transit_versions
+
+#![cfg(feature = "vault")]
+#![allow(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use asherah::builders::VaultOptions;
+use asherah::kms_vault_transit::VaultTransitKms;
+use asherah::traits::KeyManagementService;
+use serde_json::{json, Value};
+
+/// One recorded request: `"POST /v1/transit/encrypt/k"`, lower-cased
+/// headers, and the body.
+#[derive(Clone, Debug)]
+struct Seen {
+    line: String,
+    headers: String,
+    body: String,
+}
+
+type Route = dyn Fn(&str, &Value) -> (u16, Value) + Send + Sync;
+
+struct MockVault {
+    url: String,
+    seen: Arc<Mutex<Vec<Seen>>>,
+}
+
+impl MockVault {
+    fn spawn(route: impl Fn(&str, &Value) -> (u16, Value) + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("bind localhost");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let route: Arc<Route> = Arc::new(route);
+        let log = Arc::clone(&seen);
+        drop(thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                let (route, log) = (Arc::clone(&route), Arc::clone(&log));
+                drop(thread::spawn(move || serve(stream, &*route, &log)));
+            }
+        }));
+        Self { url, seen }
+    }
+
+    fn requests(&self, line_contains: &str) -> Vec<Seen> {
+        self.seen
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.line.contains(line_contains))
+            .cloned()
+            .collect()
+    }
+
+    fn wait_for(&self, what: &str, pred: impl Fn(&[Seen]) -> bool) {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            if pred(&self.seen.lock().unwrap()) {
+                return;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+        panic!(
+            "timed out waiting for {what}: {:?}",
+            self.seen.lock().unwrap()
+        );
+    }
+}
+
+fn serve(stream: std::net::TcpStream, route: &Route, log: &Mutex<Vec<Seen>>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut line = String::new();
+    if reader.read_line(&mut line).unwrap_or(0) == 0 {
+        return;
+    }
+    let line = line
+        .trim_end()
+        .rsplit_once(' ')
+        .map_or(String::new(), |(l, _)| l.to_string());
+    let mut headers = String::new();
+    let mut len = 0_usize;
+    loop {
+        let mut h = String::new();
+        if reader.read_line(&mut h).unwrap_or(0) == 0 || h == "\r\n" {
+            break;
+        }
+        let h = h.to_ascii_lowercase();
+        if let Some(v) = h.strip_prefix("content-length:") {
+            len = v.trim().parse().unwrap_or(0);
+        }
+        headers.push_str(&h);
+    }
+    let mut body = vec![0_u8; len];
+    drop(reader.read_exact(&mut body));
+    let body = String::from_utf8_lossy(&body).into_owned();
+    let parsed = serde_json::from_str(&body).unwrap_or(Value::Null);
+    log.lock().unwrap().push(Seen {
+        line: line.clone(),
+        headers,
+        body,
+    });
+    let (status, payload) = route(&line, &parsed);
+    let payload = payload.to_string();
+    let mut stream = stream;
+    drop(write!(
+        stream,
+        "HTTP/1.1 {status} X\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{payload}",
+        payload.len()
+    ));
+    drop(stream.flush());
+}
+
+fn key_read(latest: u32, min_decrypt: u32) -> (u16, Value) {
+    (
+        200,
+        json!({"data": {
+            "latest_version": latest,
+            "min_decryption_version": min_decrypt,
+            "min_encryption_version": 0,
+        }}),
+    )
+}
+
+fn denied() -> (u16, Value) {
+    (403, json!({"errors": ["permission denied"]}))
+}
+
+fn token_options() -> VaultOptions {
+    VaultOptions {
+        token: Some("static-token".into()),
+        ..VaultOptions::default()
+    }
+}
+
+/// Transit stand-in: "ciphertext" is `vault:v<version>:<base64 plaintext>`.
+fn transit(line: &str, body: &Value, version: u32) -> Option<(u16, Value)> {
+    let seal = |p: &Value| {
+        json!({
+            "ciphertext": format!("vault:v{version}:{}", p.as_str().unwrap()),
+            "key_version": version,
+        })
+    };
+    let open = |c: &Value| {
+        let c = c.as_str().unwrap();
+        json!({"plaintext": c.splitn(3, ':').nth(2).unwrap()})
+    };
+    let (item, field): (&dyn Fn(&Value) -> Value, &str) =
+        if line.starts_with("POST /v1/transit/encrypt/") {
+            (&seal, "plaintext")
+        } else if line.starts_with("POST /v1/transit/decrypt/") {
+            (&open, "ciphertext")
+        } else {
+            return None;
+        };
+    let data = match body.get("batch_input").and_then(Value::as_array) {
+        Some(items) => {
+            let results: Vec<Value> = items.iter().map(|i| item(&i[field])).collect();
+            json!({"batch_results": results})
+        }
+        None => item(&body[field]),
+    };
+    Some((200, json!({"data": data})))
+}
+
+#[test]
+fn batch_round_trip_sends_batch_input_with_pinned_key_version() {
+    let vault = MockVault::spawn(|line, body| {
+        if line == "GET /v1/transit/keys/k" {
+            return key_read(3, 1);
+        }
+        transit(line, body, 2).unwrap_or_else(denied)
+    });
+    let options = VaultOptions {
+        key_version: Some(2),
+        ..token_options()
+    };
+    let kms = VaultTransitKms::with_options(&vault.url, "k", None, &options).unwrap();
+
+    let keys: [&[u8]; 3] = [&[1; 32], &[2; 32], &[3; 32]];
+    let wrapped = kms.encrypt_keys_batch(&keys).unwrap();
+    assert_eq!(wrapped.len(), 3);
+    assert!(wrapped.iter().all(|w| w.starts_with(b"vault:v2:")));
+    let blobs: Vec<&[u8]> = wrapped.iter().map(Vec::as_slice).collect();
+    let unwrapped = kms.decrypt_keys_batch(&blobs).unwrap();
+    assert_eq!(
+        unwrapped,
+        keys.iter().map(|k| k.to_vec()).collect::<Vec<_>>()
+    );
+
+    let encrypts = vault.requests("/encrypt/k");
+    assert_eq!(encrypts.len(), 1, "one request for the whole batch");
+    let sent: Value = serde_json::from_str(&encrypts[0].body).unwrap();
+    assert_eq!(sent["key_version"], 2);
+    assert_eq!(sent["batch_input"].as_array().unwrap().len(), 3);
+    assert_eq!(vault.requests("/decrypt/k").len(), 1);
+
+    // Single-item encrypt carries the pin too.
+    kms.encrypt_key(&(), &[9; 32]).unwrap();
+    let single: Value = serde_json::from_str(&vault.requests("/encrypt/k")[1].body).unwrap();
+    assert_eq!(single["key_version"], 2);
+}
+
+#[test]
+fn batch_item_error_fails_the_call_with_its_index() {
+    let vault = MockVault::spawn(|line, _| {
+        if line.starts_with("POST /v1/transit/decrypt/") {
+            return (
+                200,
+                json!({"data": {"batch_results": [
+                    {"plaintext": "AAAA"},
+                    {"error": "cipher: message authentication failed"},
+                ]}}),
+            );
+        }
+        denied()
+    });
+    let kms = VaultTransitKms::with_options(&vault.url, "k", None, &token_options()).unwrap();
+    let err = kms
+        .decrypt_keys_batch(&[b"vault:v1:a", b"vault:v1:b"])
+        .unwrap_err();
+    assert!(format!("{err:#}").contains("item 1 failed"), "{err:#}");
+}
+
+#[test]
+fn pinned_key_version_beyond_latest_fails_construction() {
+    let vault = MockVault::spawn(|line, _| {
+        if line == "GET /v1/transit/keys/k" {
+            return key_read(3, 1);
+        }
+        denied()
+    });
+    let options = VaultOptions {
+        key_version: Some(5),
+        ..token_options()
+    };
+    let err = VaultTransitKms::with_options(&vault.url, "k", None, &options)
+        .err()
+        .expect("pin beyond latest_version must be rejected");
+    assert!(format!("{err:#}").contains("does not exist"), "{err:#}");
+}
+
+#[test]
+fn decrypt_of_trimmed_version_names_min_decryption_version() {
+    let vault = MockVault::spawn(|line, _| match line {
+        "GET /v1/transit/keys/k" => key_read(6, 3),
+        l if l.starts_with("POST /v1/transit/decrypt/") => (
+            400,
+            json!({"errors": ["ciphertext or signature version is disallowed by policy (too old)"]}),
+        ),
+        _ => denied(),
+    });
+    let kms = VaultTransitKms::with_options(&vault.url, "k", None, &token_options()).unwrap();
+    let err = kms.decrypt_key(&(), b"vault:v1:abc").unwrap_err();
+    let msg = format!("{err:#}");
+    assert!(msg.contains("min_decryption_version 3"), "{msg}");
+    assert!(msg.contains("HTTP 400"), "{msg}");
+}
+
+#[test]
+fn renewable_token_is_renewed_before_expiry() {
+    let renewals = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&renewals);
+    let vault = MockVault::spawn(move |line, body| match line {
+        "GET /v1/auth/token/lookup-self" => (200, json!({"data": {"ttl": 1, "renewable": true}})),
+        "POST /v1/auth/token/renew-self" => {
+            counter.fetch_add(1, Ordering::SeqCst);
+            (
+                200,
+                json!({"auth": {
+                    "client_token": "static-token",
+                    "lease_duration": 30,
+                    "renewable": true,
+                }}),
+            )
+        }
+        _ => transit(line, body, 1).unwrap_or_else(denied),
+    });
+    let kms = VaultTransitKms::with_options(&vault.url, "k", None, &token_options()).unwrap();
+    vault.wait_for("renew-self", |_| renewals.load(Ordering::SeqCst) > 0);
+    kms.encrypt_key(&(), &[7; 32]).unwrap();
+    assert_eq!(
+        renewals.load(Ordering::SeqCst),
+        1,
+        "next renewal is 20s out"
+    );
+}
+
+#[test]
+fn approle_relogin_rereads_rotated_secret_id_file() {
+    let path = std::env::temp_dir().join(format!(
+        "asherah-vault-secret-id-{}-{}",
+        std::process::id(),
+        line!()
+    ));
+    std::fs::write(&path, "secret-one\n").unwrap();
+
+    let logins = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&logins);
+    let vault = MockVault::spawn(move |line, body| match line {
+        "POST /v1/auth/approle/login" => {
+            let n = counter.fetch_add(1, Ordering::SeqCst) + 1;
+            assert_eq!(body["role_id"], "role");
+            // Short, non-renewable leases force a fresh login each cycle.
+            (
+                200,
+                json!({"auth": {
+                    "client_token": format!("token-{n}"),
+                    "lease_duration": 1,
+                    "renewable": false,
+                }}),
+            )
+        }
+        _ => transit(line, body, 1).unwrap_or_else(denied),
+    });
+    let options = VaultOptions {
+        auth_method: Some("approle".into()),
+        approle_role_id: Some("role".into()),
+        approle_secret_id_file: Some(path.display().to_string()),
+        ..VaultOptions::default()
+    };
+    let kms = VaultTransitKms::with_options(&vault.url, "k", None, &options).unwrap();
+    std::fs::write(&path, "secret-two\n").unwrap();
+
+    vault.wait_for("re-login with rotated secret-id", |seen| {
+        seen.iter()
+            .any(|s| s.line.ends_with("/approle/login") && s.body.contains("secret-two"))
+    });
+    let first = &vault.requests("/approle/login")[0];
+    assert!(
+        first.body.contains("\"secret_id\":\"secret-one\""),
+        "{first:?}"
+    );
+
+    kms.encrypt_key(&(), &[5; 32]).unwrap();
+    let last = vault.requests("/encrypt/k").pop().unwrap();
+    assert!(
+        !last.headers.contains("x-vault-token: token-1\r\n"),
+        "encrypt must use the refreshed token: {last:?}"
+    );
+    drop(kms);
+    drop(std::fs::remove_file(&path));
+}